@@ -0,0 +1,77 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use wasm_ast::{parse_binary, parse_binary_parallel};
+
+/// Generates a module with the given number of functions, each with a small body.
+fn module(functions: usize) -> Vec<u8> {
+    let mut text = String::from("(module");
+
+    for index in 0..functions {
+        text.push_str(&format!(
+            "(func (export \"f{index}\") (param i32) (result i32) (local i64)
+                (i32.add (local.get 0) (i32.const {index})))"
+        ));
+    }
+
+    text.push(')');
+
+    wat::parse_str(text).unwrap()
+}
+
+/// Generates a module with the given number of functions, each with a loop over a mix of
+/// control, variable, memory and numeric instructions, as found in compiled code.
+fn instructions(functions: usize) -> Vec<u8> {
+    let mut text = String::from("(module (memory 1)");
+
+    for index in 0..functions {
+        text.push_str(&format!(
+            "(func (export \"f{index}\") (param i32) (result i32) (local i32 f64)
+                (loop $continue
+                    (local.set 1 (i32.add (local.get 1) (i32.load offset=4 (local.get 0))))
+                    (i32.store (local.get 0) (i32.mul (local.get 1) (i32.const {index})))
+                    (local.set 2 (f64.add (local.get 2) (f64.convert_i32_s (local.get 1))))
+                    (br_if $continue (i32.lt_u (local.tee 0 (i32.sub (local.get 0) (i32.const 1))) (i32.const 8))))
+                (select (local.get 1) (i32.trunc_sat_f64_s (local.get 2)) (i32.eqz (local.get 0))))"
+        ));
+    }
+
+    text.push(')');
+
+    wat::parse_str(text).unwrap()
+}
+
+/// Measures the sequential parser on function bodies with a mix of instructions.
+fn sequential(c: &mut Criterion) {
+    let mut group = c.benchmark_group("instructions");
+    let bytes = instructions(10_000);
+
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("parse_binary", |b| b.iter(|| parse_binary(&bytes).unwrap()));
+    group.finish();
+}
+
+/// Compares the sequential and parallel parsers on code sections of increasing size,
+/// from one below the size at which the parallel parser spawns threads to one that spans many threads.
+fn parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("code section");
+
+    for functions in [100, 2_000, 50_000] {
+        let bytes = module(functions);
+
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("parse_binary", functions),
+            &bytes,
+            |b, bytes| b.iter(|| parse_binary(bytes).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_binary_parallel", functions),
+            &bytes,
+            |b, bytes| b.iter(|| parse_binary_parallel(bytes).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sequential, parallel);
+criterion_main!(benches);
@@ -41,7 +41,11 @@ fn main() {
 
     let module = builder.build();
 
-    assert_eq!(module.function_types(), Some(function_types.as_slice()));
+    assert_eq!(
+        module.types().map(|types| types.len()),
+        Some(function_types.len())
+    );
+    assert_eq!(module.function_type(1), Some(&function_types[1]));
     assert_eq!(module.functions(), Some(functions.as_slice()));
     assert_eq!(module.tables(), None);
     assert_eq!(module.memories(), Some(memories.as_slice()));
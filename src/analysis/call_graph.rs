@@ -0,0 +1,762 @@
+use crate::model::{
+    ControlInstruction, ElementMode, ExportDescription, FunctionIndex, HeapType, ImportDescription,
+    Module, Name, ReferenceInstruction, TableIndex, TableInstruction, TypeIndex,
+};
+use crate::validation::Types;
+use crate::visit::{
+    walk_control_instruction, walk_reference_instruction, walk_table_instruction, Visit,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// The ways in which a function can refer to another function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CallKind {
+    /// A `call` or `return_call` instruction.
+    Direct,
+    /// A `call_indirect`, `return_call_indirect`, `call_ref` or `return_call_ref` instruction
+    /// that may call the function.
+    Indirect,
+    /// A `ref.func` instruction that takes a reference to the function.
+    Reference,
+}
+
+impl CallKind {
+    /// The name of the kind in the serialized forms of a call graph.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CallKind::Direct => "direct",
+            CallKind::Indirect => "indirect",
+            CallKind::Reference => "reference",
+        }
+    }
+}
+
+/// An edge from a calling function to a function it may call or refer to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CallEdge {
+    caller: FunctionIndex,
+    callee: FunctionIndex,
+    kind: CallKind,
+}
+
+impl CallEdge {
+    /// Creates a new edge from the caller to the callee.
+    pub fn new(caller: FunctionIndex, callee: FunctionIndex, kind: CallKind) -> Self {
+        CallEdge {
+            caller,
+            callee,
+            kind,
+        }
+    }
+
+    /// The index of the calling function.
+    pub fn caller(&self) -> FunctionIndex {
+        self.caller
+    }
+
+    /// The index of the called (or referenced) function.
+    pub fn callee(&self) -> FunctionIndex {
+        self.callee
+    }
+
+    /// The kind of reference from the caller to the callee.
+    pub fn kind(&self) -> CallKind {
+        self.kind
+    }
+}
+
+/// The call graph of a module, with a node for every function (including imports)
+/// and an edge for every function that a function body may call or refer to.
+///
+/// Indirect calls are resolved conservatively:
+/// a `call_indirect` may call any function placed in its table by its initializer, an active element segment,
+/// or by a passive element segment, whose function type matches (i.e., is a subtype of) the type of the call.
+/// Tables that are imported, exported or written by `table.set`, `table.fill`, `table.grow` or `table.copy`
+/// instructions may hold any referenced function instead.
+/// A `call_ref` may call any function with a matching type that is referenced
+/// by an element segment, a `ref.func` instruction or an export of the module.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::analysis::{CallEdge, CallGraph, CallKind};
+/// use wasm_ast::{ControlInstruction, Expression, Function, FunctionType, Module, ResultType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder.add_function_type(FunctionType::runnable()).unwrap();
+///
+/// builder
+///     .add_function(Function::new(kind, ResultType::empty(), Expression::from(vec![ControlInstruction::Call(1).into()])))
+///     .unwrap();
+/// builder
+///     .add_function(Function::new(kind, ResultType::empty(), Expression::from(vec![ControlInstruction::Call(1).into()])))
+///     .unwrap();
+///
+/// let graph = CallGraph::new(&builder.build());
+///
+/// assert_eq!(graph.functions(), 2);
+/// assert_eq!(
+///     graph.edges().collect::<Vec<_>>(),
+///     vec![&CallEdge::new(0, 1, CallKind::Direct), &CallEdge::new(1, 1, CallKind::Direct)]
+/// );
+/// assert!(!graph.is_recursive(0));
+/// assert!(graph.is_recursive(1));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallGraph {
+    imports: u32,
+    functions: u32,
+    names: BTreeMap<FunctionIndex, Name>,
+    edges: BTreeSet<CallEdge>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of the given module.
+    ///
+    /// # Panics
+    /// Panics if a function was parsed lazily and its code is not a valid expression in the binary format,
+    /// as `Function::body` does. Use `validate` or `Function::decode_body` to check the code of such functions first.
+    pub fn new(module: &Module) -> Self {
+        let types: Vec<TypeIndex> = module
+            .imports()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|import| match import.description() {
+                ImportDescription::Function(kind) => Some(*kind),
+                _ => None,
+            })
+            .chain(
+                module
+                    .functions()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|function| function.kind()),
+            )
+            .collect();
+        let imports = types.len() - module.functions().map(<[_]>::len).unwrap_or_default();
+        let mut targets = Targets {
+            tables: BTreeMap::new(),
+            referenced: BTreeSet::new(),
+            written: BTreeSet::new(),
+            table: None,
+        };
+        let mut imported_tables = 0;
+
+        for import in module.imports().unwrap_or_default() {
+            if let ImportDescription::Table(_) = import.description() {
+                targets.written.insert(imported_tables);
+                imported_tables += 1;
+            }
+        }
+
+        for export in module.exports().unwrap_or_default() {
+            if let ExportDescription::Table(table) = export.description() {
+                targets.written.insert(*table);
+            }
+
+            targets.visit_export(export);
+        }
+
+        for element in module.elements().unwrap_or_default() {
+            targets.table = match element.mode() {
+                ElementMode::Active(table, _) => Some(Some(*table)),
+                ElementMode::Passive => Some(None),
+                ElementMode::Declarative => None,
+            };
+            targets.visit_element(element);
+        }
+
+        for (index, table) in module.tables().unwrap_or_default().iter().enumerate() {
+            targets.table = Some(Some(imported_tables + index as TableIndex));
+            targets.visit_table(table);
+        }
+
+        targets.table = None;
+
+        for global in module.globals().unwrap_or_default() {
+            targets.visit_global(global);
+        }
+
+        for function in module.functions().unwrap_or_default() {
+            targets.visit_function(function);
+        }
+
+        let sub_types = Types::new(module.types().unwrap_or_default());
+        let mut edges = BTreeSet::new();
+
+        for (index, function) in module.functions().unwrap_or_default().iter().enumerate() {
+            let mut calls = Calls {
+                sub_types: &sub_types,
+                types: &types,
+                targets: &targets,
+                caller: (imports + index) as FunctionIndex,
+                edges: BTreeSet::new(),
+            };
+
+            calls.visit_function(function);
+            edges.append(&mut calls.edges);
+        }
+
+        CallGraph {
+            imports: imports as u32,
+            functions: types.len() as u32,
+            names: module
+                .names()
+                .map(|names| names.functions().clone())
+                .unwrap_or_default(),
+            edges,
+        }
+    }
+
+    /// The number of functions in the graph, including imported functions.
+    pub fn functions(&self) -> u32 {
+        self.functions
+    }
+
+    /// Returns true if the function with the given index is imported, false otherwise.
+    pub fn is_imported(&self, function: FunctionIndex) -> bool {
+        function < self.imports
+    }
+
+    /// The name of the function with the given index in the name section of the module, if any.
+    pub fn name(&self, function: FunctionIndex) -> Option<&Name> {
+        self.names.get(&function)
+    }
+
+    /// The edges of the graph, ordered by caller, callee and kind.
+    pub fn edges(&self) -> impl Iterator<Item = &CallEdge> {
+        self.edges.iter()
+    }
+
+    /// The functions that the given function may call or refer to.
+    pub fn callees(&self, caller: FunctionIndex) -> impl Iterator<Item = &CallEdge> {
+        self.edges
+            .range(CallEdge::new(caller, 0, CallKind::Direct)..)
+            .take_while(move |edge| edge.caller == caller)
+    }
+
+    /// The functions that may call or refer to the given function.
+    pub fn callers(&self, callee: FunctionIndex) -> impl Iterator<Item = &CallEdge> {
+        self.edges.iter().filter(move |edge| edge.callee == callee)
+    }
+
+    /// Returns true if the function may call itself, either directly or through other functions.
+    /// Only calls are followed; references taken by `ref.func` instructions are not.
+    pub fn is_recursive(&self, function: FunctionIndex) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![function];
+
+        while let Some(caller) = pending.pop() {
+            for edge in self.callees(caller) {
+                if edge.kind == CallKind::Reference {
+                    continue;
+                }
+
+                if edge.callee == function {
+                    return true;
+                }
+
+                if visited.insert(edge.callee) {
+                    pending.push(edge.callee);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Writes the graph in the Graphviz DOT language.
+    /// Nodes are labeled with the name of the function if it has one, or its index otherwise.
+    /// Imported functions are drawn as boxes, and indirect calls and references as dashed and dotted edges.
+    ///
+    /// See <https://graphviz.org/doc/info/lang.html>
+    pub fn write_dot<O: Write>(&self, output: &mut O) -> std::io::Result<()> {
+        writeln!(output, "digraph calls {{")?;
+
+        for function in 0..self.functions {
+            let shape = if self.is_imported(function) {
+                "box"
+            } else {
+                "ellipse"
+            };
+
+            write!(output, "  {} [label=", function)?;
+            write_quoted(&self.label(function), '"', output)?;
+            writeln!(output, ", shape={}];", shape)?;
+        }
+
+        for edge in &self.edges {
+            let style = match edge.kind {
+                CallKind::Direct => "solid",
+                CallKind::Indirect => "dashed",
+                CallKind::Reference => "dotted",
+            };
+
+            writeln!(
+                output,
+                "  {} -> {} [style={}];",
+                edge.caller, edge.callee, style
+            )?;
+        }
+
+        writeln!(output, "}}")
+    }
+
+    /// Writes the graph as a JSON object with a list of functions and a list of edges.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wasm_ast::analysis::CallGraph;
+    /// use wasm_ast::{ControlInstruction, Expression, Function, FunctionType, Import, Module, ResultType};
+    ///
+    /// let mut builder = Module::builder();
+    /// let kind = builder.add_function_type(FunctionType::runnable()).unwrap();
+    ///
+    /// builder.add_import(Import::function("env".into(), "log".into(), kind)).unwrap();
+    /// builder
+    ///     .add_function(Function::new(kind, ResultType::empty(), Expression::from(vec![ControlInstruction::Call(0).into()])))
+    ///     .unwrap();
+    ///
+    /// let mut json = Vec::new();
+    ///
+    /// CallGraph::new(&builder.build()).write_json(&mut json).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(json).unwrap(),
+    ///     r#"{"functions":[{"index":0,"name":null,"imported":true},{"index":1,"name":null,"imported":false}],"edges":[{"caller":1,"callee":0,"kind":"direct"}]}"#
+    /// );
+    /// ```
+    pub fn write_json<O: Write>(&self, output: &mut O) -> std::io::Result<()> {
+        write!(output, "{{\"functions\":[")?;
+
+        for function in 0..self.functions {
+            if function > 0 {
+                write!(output, ",")?;
+            }
+
+            write!(output, "{{\"index\":{},\"name\":", function)?;
+
+            match self.name(function) {
+                Some(name) => write_quoted(name.as_str(), '"', output)?,
+                None => write!(output, "null")?,
+            }
+
+            write!(output, ",\"imported\":{}}}", self.is_imported(function))?;
+        }
+
+        write!(output, "],\"edges\":[")?;
+
+        for (index, edge) in self.edges.iter().enumerate() {
+            if index > 0 {
+                write!(output, ",")?;
+            }
+
+            write!(
+                output,
+                "{{\"caller\":{},\"callee\":{},\"kind\":\"{}\"}}",
+                edge.caller,
+                edge.callee,
+                edge.kind.name()
+            )?;
+        }
+
+        write!(output, "]}}")
+    }
+
+    /// The label of a function in the DOT language.
+    fn label(&self, function: FunctionIndex) -> String {
+        match self.name(function) {
+            Some(name) => format!("${}", name.as_str()),
+            None => function.to_string(),
+        }
+    }
+}
+
+/// Writes a string surrounded by the given quote, escaping quotes, backslashes and control characters.
+fn write_quoted<O: Write>(value: &str, quote: char, output: &mut O) -> std::io::Result<()> {
+    write!(output, "{}", quote)?;
+
+    for character in value.chars() {
+        match character {
+            '\\' => write!(output, "\\\\")?,
+            '\n' => write!(output, "\\n")?,
+            '\r' => write!(output, "\\r")?,
+            '\t' => write!(output, "\\t")?,
+            character if character == quote => write!(output, "\\{}", character)?,
+            character if character.is_control() => write!(output, "\\u{:04x}", character as u32)?,
+            character => write!(output, "{}", character)?,
+        }
+    }
+
+    write!(output, "{}", quote)
+}
+
+/// The functions that may be the target of an indirect call.
+struct Targets {
+    /// The functions placed in each table by table initializers and active element segments,
+    /// with functions of passive element segments under `None`.
+    tables: BTreeMap<Option<TableIndex>, BTreeSet<FunctionIndex>>,
+    /// The functions referenced by element segments, `ref.func` instructions and exports.
+    referenced: BTreeSet<FunctionIndex>,
+    /// The tables whose contents are not known from the module alone,
+    /// because they are imported, exported or written by instructions.
+    written: BTreeSet<TableIndex>,
+    /// The table of the table initializer or element segment being visited, if any.
+    table: Option<Option<TableIndex>>,
+}
+
+impl<'ast> Visit<'ast> for Targets {
+    fn visit_function_index(&mut self, function: FunctionIndex) {
+        self.referenced.insert(function);
+
+        if let Some(table) = self.table {
+            self.tables.entry(table).or_default().insert(function);
+        }
+    }
+
+    fn visit_control_instruction(&mut self, node: &'ast ControlInstruction) {
+        if let ControlInstruction::Call(_) | ControlInstruction::ReturnCall(_) = node {
+            return;
+        }
+
+        walk_control_instruction(self, node);
+    }
+
+    fn visit_table_instruction(&mut self, node: &'ast TableInstruction) {
+        match node {
+            TableInstruction::Set(table)
+            | TableInstruction::Grow(table)
+            | TableInstruction::Fill(table)
+            | TableInstruction::Copy(table, _) => {
+                self.written.insert(*table);
+            }
+            _ => {}
+        }
+
+        walk_table_instruction(self, node);
+    }
+}
+
+/// Collects the edges from the body of a function.
+struct Calls<'module> {
+    sub_types: &'module Types<'module>,
+    types: &'module [TypeIndex],
+    targets: &'module Targets,
+    caller: FunctionIndex,
+    edges: BTreeSet<CallEdge>,
+}
+
+impl Calls<'_> {
+    fn add(&mut self, callee: FunctionIndex, kind: CallKind) {
+        self.edges.insert(CallEdge::new(self.caller, callee, kind));
+    }
+
+    /// Adds an indirect edge to each of the candidates whose function type matches the given function type.
+    fn add_indirect<'a>(
+        &mut self,
+        kind: TypeIndex,
+        candidates: impl IntoIterator<Item = &'a FunctionIndex>,
+    ) {
+        if self.sub_types.function(kind).is_none() {
+            return;
+        }
+
+        for candidate in candidates {
+            let matches = self
+                .types
+                .get(*candidate as usize)
+                .is_some_and(|candidate| {
+                    self.sub_types
+                        .heap_subtype(HeapType::Index(*candidate), HeapType::Index(kind))
+                });
+
+            if matches {
+                self.add(*candidate, CallKind::Indirect);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Calls<'_> {
+    fn visit_control_instruction(&mut self, node: &'ast ControlInstruction) {
+        let targets = self.targets;
+
+        match node {
+            ControlInstruction::Call(function) | ControlInstruction::ReturnCall(function) => {
+                self.add(*function, CallKind::Direct)
+            }
+            ControlInstruction::CallIndirect(kind, table)
+            | ControlInstruction::ReturnCallIndirect(kind, table)
+                if targets.written.contains(table) =>
+            {
+                self.add_indirect(*kind, &targets.referenced)
+            }
+            ControlInstruction::CallIndirect(kind, table)
+            | ControlInstruction::ReturnCallIndirect(kind, table) => {
+                let tables = [Some(*table), None];
+                let candidates = tables
+                    .iter()
+                    .filter_map(|table| targets.tables.get(table))
+                    .flatten();
+
+                self.add_indirect(*kind, candidates);
+            }
+            ControlInstruction::CallRef(kind) | ControlInstruction::ReturnCallRef(kind) => {
+                self.add_indirect(*kind, &targets.referenced)
+            }
+            _ => {}
+        }
+
+        walk_control_instruction(self, node);
+    }
+
+    fn visit_reference_instruction(&mut self, node: &'ast ReferenceInstruction) {
+        if let ReferenceInstruction::Function(function) = node {
+            self.add(*function, CallKind::Reference);
+        }
+
+        walk_reference_instruction(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+
+    fn module() -> Module {
+        let mut builder = Module::builder();
+        let mut names = NameSection::new();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_function_type(FunctionType::side_effect(ResultType::from(vec![
+                ValueType::I32,
+            ])))
+            .unwrap();
+        builder
+            .add_import(Import::function("env".into(), "log".into(), 1))
+            .unwrap();
+        builder
+            .add_table(TableType::new(ReferenceType::Function, Limit::unbounded(2)).into())
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    0i32.into(),
+                    ControlInstruction::CallIndirect(0, 0).into(),
+                    ReferenceInstruction::Function(3).into(),
+                    ParametricInstruction::Drop.into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                1,
+                ResultType::empty(),
+                Expression::from(vec![
+                    VariableInstruction::LocalGet(0).into(),
+                    ControlInstruction::If(
+                        BlockType::None,
+                        Expression::from(vec![ControlInstruction::Call(0).into()]),
+                        None,
+                    )
+                    .into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    42i32.into(),
+                    ControlInstruction::Call(2).into(),
+                    ReferenceInstruction::Function(1).into(),
+                    ControlInstruction::CallRef(0).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::active(
+                0,
+                Expression::from(vec![0i32.into()]),
+                ReferenceType::Function,
+                vec![1u32, 2].to_initializers(),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::declarative(
+                ReferenceType::Function,
+                vec![3u32].to_initializers(),
+            ))
+            .unwrap();
+
+        names.functions_mut().insert(0, "log".into());
+        names.functions_mut().insert(1, "say \"hi\"".into());
+        builder.set_names(Some(names));
+
+        builder.build()
+    }
+
+    #[test]
+    fn build_call_graph() {
+        let graph = CallGraph::new(&module());
+
+        assert_eq!(graph.functions(), 4);
+        assert!(graph.is_imported(0));
+        assert!(!graph.is_imported(1));
+        assert_eq!(
+            graph.edges().copied().collect::<Vec<_>>(),
+            vec![
+                CallEdge::new(1, 1, CallKind::Indirect),
+                CallEdge::new(1, 3, CallKind::Reference),
+                CallEdge::new(2, 0, CallKind::Direct),
+                CallEdge::new(3, 1, CallKind::Indirect),
+                CallEdge::new(3, 1, CallKind::Reference),
+                CallEdge::new(3, 2, CallKind::Direct),
+                CallEdge::new(3, 3, CallKind::Indirect),
+            ]
+        );
+        assert_eq!(
+            graph.callers(3).map(CallEdge::caller).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(graph.is_recursive(1));
+        assert!(!graph.is_recursive(2));
+        assert!(graph.is_recursive(3));
+    }
+
+    #[test]
+    fn open_tables() {
+        let indirect = CallEdge::new(1, 3, CallKind::Indirect);
+
+        assert!(!CallGraph::new(&module())
+            .edges()
+            .any(|edge| *edge == indirect));
+
+        let mut builder = module().into_builder();
+
+        builder.add_export(Export::table("table".into(), 0));
+
+        assert!(CallGraph::new(&builder.build())
+            .edges()
+            .any(|edge| *edge == indirect));
+
+        let mut builder = module().into_builder();
+
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    0i32.into(),
+                    ReferenceInstruction::Null(HeapType::Function).into(),
+                    TableInstruction::Set(0).into(),
+                ]),
+            ))
+            .unwrap();
+
+        assert!(CallGraph::new(&builder.build())
+            .edges()
+            .any(|edge| *edge == indirect));
+    }
+
+    #[test]
+    fn subtype_targets() {
+        let mut builder = Module::builder();
+
+        builder
+            .add_type(RecursiveType::from(vec![SubType::new(
+                false,
+                vec![],
+                FunctionType::runnable().into(),
+            )]))
+            .unwrap();
+        builder
+            .add_type(RecursiveType::from(vec![SubType::new(
+                true,
+                vec![0],
+                FunctionType::runnable().into(),
+            )]))
+            .unwrap();
+        builder
+            .add_table(TableType::new(ReferenceType::Function, Limit::unbounded(1)).into())
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                1,
+                ResultType::empty(),
+                Expression::from(vec![
+                    0i32.into(),
+                    ControlInstruction::CallIndirect(0, 0).into(),
+                    0i32.into(),
+                    ControlInstruction::CallIndirect(1, 0).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(0, ResultType::empty(), Expression::empty()))
+            .unwrap();
+        builder
+            .add_element(Element::active(
+                0,
+                Expression::from(vec![0i32.into()]),
+                ReferenceType::Function,
+                vec![0u32, 1].to_initializers(),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            CallGraph::new(&builder.build())
+                .edges()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![
+                CallEdge::new(0, 0, CallKind::Indirect),
+                CallEdge::new(0, 1, CallKind::Indirect),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_dot() {
+        let mut dot = Vec::new();
+
+        CallGraph::new(&module()).write_dot(&mut dot).unwrap();
+
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            r#"digraph calls {
+  0 [label="$log", shape=box];
+  1 [label="$say \"hi\"", shape=ellipse];
+  2 [label="2", shape=ellipse];
+  3 [label="3", shape=ellipse];
+  1 -> 1 [style=dashed];
+  1 -> 3 [style=dotted];
+  2 -> 0 [style=solid];
+  3 -> 1 [style=dashed];
+  3 -> 1 [style=dotted];
+  3 -> 2 [style=solid];
+  3 -> 3 [style=dashed];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn write_json() {
+        let mut json = Vec::new();
+
+        CallGraph::new(&module()).write_json(&mut json).unwrap();
+
+        let json = String::from_utf8(json).unwrap();
+
+        assert!(json.starts_with(
+            r#"{"functions":[{"index":0,"name":"log","imported":true},{"index":1,"name":"say \"hi\"","imported":false},"#
+        ));
+        assert!(json.ends_with(r#"{"caller":3,"callee":3,"kind":"indirect"}]}"#));
+    }
+}
@@ -0,0 +1,792 @@
+use crate::model::{
+    CatchClause, ControlInstruction, Expression, Function, Instruction, LabelIndex,
+};
+use std::collections::HashSet;
+
+/// The index of a basic block in a control flow graph.
+pub type BlockIndex = usize;
+
+/// The ways in which control flows from one basic block to another.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum EdgeKind {
+    /// Control continues with the next instruction in sequence
+    /// (e.g., at the end of a block, into the then-branch of an `if`, or when a `br_if` is not taken).
+    Fallthrough,
+    /// Control branches to the target of a label (e.g., `br`, `br_table`, `return`, or the else-branch of an `if`).
+    Branch,
+    /// Execution stops abruptly, due to an `unreachable` instruction or an uncaught exception.
+    Trap,
+    /// An exception is caught by a catch clause of an enclosing `try_table`.
+    Exception,
+}
+
+/// An edge between two basic blocks of a control flow graph.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ControlFlowEdge {
+    source: BlockIndex,
+    target: BlockIndex,
+    kind: EdgeKind,
+}
+
+impl ControlFlowEdge {
+    /// The block that control flows from.
+    pub fn source(&self) -> BlockIndex {
+        self.source
+    }
+
+    /// The block that control flows to.
+    pub fn target(&self) -> BlockIndex {
+        self.target
+    }
+
+    /// The kind of control flow.
+    pub fn kind(&self) -> EdgeKind {
+        self.kind
+    }
+}
+
+/// A straight-line sequence of instructions with a single entry and a single exit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BasicBlock<'function> {
+    instructions: Vec<&'function Instruction>,
+}
+
+impl<'function> BasicBlock<'function> {
+    /// The instructions of the block, in execution order.
+    pub fn instructions(&self) -> &[&'function Instruction] {
+        &self.instructions
+    }
+
+    /// Returns true if the block has no instructions, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+/// The control flow graph of a function body, lowered from its structured instructions into basic blocks.
+///
+/// The graph has three distinguished blocks: the entry of the function,
+/// the exit reached by returning from the function, and the trap reached when execution stops abruptly.
+/// The exit and trap blocks are always empty.
+///
+/// Basic blocks contain every instruction except for the structured `block`, `loop` and `try_table` instructions,
+/// whose bodies are lowered into blocks of their own.
+/// An `if` instruction is the last instruction of the block that evaluates its condition.
+/// Branch, return, `unreachable` and throw instructions end their block,
+/// as do calls inside a `try_table`, since a call may throw an exception caught by the `try_table`.
+/// Like a throw, a call inside `try_table` instructions without a catch-all clause also has an edge to the trap block.
+/// Other instructions that may trap at runtime (e.g., division or loads) do not end their block.
+///
+/// Instructions following an unconditional branch are placed in blocks without predecessors.
+/// Branches to undefined labels are ignored; modules should be validated before building their control flow graphs.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::analysis::{ControlFlowGraph, EdgeKind};
+/// use wasm_ast::{BlockType, ControlInstruction, Expression, Function, ResultType, VariableInstruction};
+///
+/// let function = Function::new(
+///     0,
+///     ResultType::empty(),
+///     Expression::from(vec![
+///         ControlInstruction::Loop(
+///             BlockType::None,
+///             Expression::from(vec![
+///                 VariableInstruction::LocalGet(0).into(),
+///                 ControlInstruction::BranchIf(0).into(),
+///             ]),
+///         )
+///         .into(),
+///     ]),
+/// );
+/// let graph = ControlFlowGraph::new(&function);
+/// let header = graph.successors(graph.entry()).next().unwrap().target();
+///
+/// assert_eq!(graph.block(header).unwrap().instructions().len(), 2);
+/// assert!(graph
+///     .successors(header)
+///     .any(|edge| edge.target() == header && edge.kind() == EdgeKind::Branch));
+/// assert!(graph.dominators().dominates(header, graph.exit()));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlFlowGraph<'function> {
+    blocks: Vec<BasicBlock<'function>>,
+    edges: Vec<ControlFlowEdge>,
+}
+
+impl<'function> ControlFlowGraph<'function> {
+    const ENTRY: BlockIndex = 0;
+    const EXIT: BlockIndex = 1;
+    const TRAP: BlockIndex = 2;
+
+    /// Builds the control flow graph of the body of a function.
+    pub fn new(function: &'function Function) -> Self {
+        Self::from_expression(function.body())
+    }
+
+    /// Builds the control flow graph of an expression,
+    /// where branches to the outermost label exit the expression.
+    pub fn from_expression(expression: &'function Expression) -> Self {
+        let mut builder = Builder {
+            graph: ControlFlowGraph {
+                blocks: vec![BasicBlock::default(); 3],
+                edges: Vec::new(),
+            },
+            edges: HashSet::new(),
+            labels: vec![Label::new(Self::EXIT)],
+            current: Some(Self::ENTRY),
+        };
+
+        builder.lower(expression);
+        builder.fallthrough(Self::EXIT);
+        builder.graph
+    }
+
+    /// The block where execution of the function begins.
+    pub fn entry(&self) -> BlockIndex {
+        Self::ENTRY
+    }
+
+    /// The block reached by returning from the function.
+    pub fn exit(&self) -> BlockIndex {
+        Self::EXIT
+    }
+
+    /// The block reached when execution stops abruptly.
+    pub fn trap(&self) -> BlockIndex {
+        Self::TRAP
+    }
+
+    /// The basic blocks of the graph.
+    pub fn blocks(&self) -> &[BasicBlock<'function>] {
+        &self.blocks
+    }
+
+    /// The basic block with the given index, if any.
+    pub fn block(&self, block: BlockIndex) -> Option<&BasicBlock<'function>> {
+        self.blocks.get(block)
+    }
+
+    /// The edges of the graph, in the order they were created.
+    pub fn edges(&self) -> &[ControlFlowEdge] {
+        &self.edges
+    }
+
+    /// The edges from the given block to its successors.
+    pub fn successors(&self, block: BlockIndex) -> impl Iterator<Item = &ControlFlowEdge> {
+        self.edges.iter().filter(move |edge| edge.source == block)
+    }
+
+    /// The edges from the predecessors of the given block to the block.
+    pub fn predecessors(&self, block: BlockIndex) -> impl Iterator<Item = &ControlFlowEdge> {
+        self.edges.iter().filter(move |edge| edge.target == block)
+    }
+
+    /// Computes the dominator tree of the graph, rooted at the entry block.
+    ///
+    /// See <https://www.cs.rice.edu/~keith/EMBED/dom.pdf>
+    pub fn dominators(&self) -> DominatorTree {
+        let successors = self.adjacency(|edge| (edge.source, edge.target));
+        let predecessors = self.adjacency(|edge| (edge.target, edge.source));
+        let order = postorder(Self::ENTRY, &successors);
+        let mut numbers = vec![None; self.blocks.len()];
+
+        for (number, block) in order.iter().enumerate() {
+            numbers[*block] = Some(number);
+        }
+
+        let mut dominators = vec![None; self.blocks.len()];
+        let mut changed = true;
+
+        dominators[Self::ENTRY] = Some(Self::ENTRY);
+
+        while changed {
+            changed = false;
+
+            for block in order.iter().rev().skip(1) {
+                let mut processed = predecessors[*block]
+                    .iter()
+                    .filter(|predecessor| dominators[**predecessor].is_some());
+                let first = match processed.next() {
+                    Some(first) => *first,
+                    None => continue,
+                };
+                let dominator = processed.fold(first, |dominator, predecessor| {
+                    intersect(*predecessor, dominator, &dominators, &numbers)
+                });
+
+                if dominators[*block] != Some(dominator) {
+                    dominators[*block] = Some(dominator);
+                    changed = true;
+                }
+            }
+        }
+
+        dominators[Self::ENTRY] = None;
+
+        DominatorTree {
+            entry: Self::ENTRY,
+            dominators,
+        }
+    }
+
+    /// Lists the neighbors of each block, using the given function to orient the edges.
+    fn adjacency(
+        &self,
+        orient: impl Fn(&ControlFlowEdge) -> (BlockIndex, BlockIndex),
+    ) -> Vec<Vec<BlockIndex>> {
+        let mut adjacency = vec![Vec::new(); self.blocks.len()];
+
+        for edge in &self.edges {
+            let (from, to) = orient(edge);
+
+            adjacency[from].push(to);
+        }
+
+        adjacency
+    }
+}
+
+/// Lists the blocks reachable from the entry in postorder.
+fn postorder(entry: BlockIndex, successors: &[Vec<BlockIndex>]) -> Vec<BlockIndex> {
+    let mut visited = vec![false; successors.len()];
+    let mut order = Vec::with_capacity(successors.len());
+    let mut stack = vec![(entry, 0)];
+
+    visited[entry] = true;
+
+    while let Some((block, next)) = stack.pop() {
+        match successors[block].get(next) {
+            Some(successor) => {
+                stack.push((block, next + 1));
+
+                if !visited[*successor] {
+                    visited[*successor] = true;
+                    stack.push((*successor, 0));
+                }
+            }
+            None => order.push(block),
+        }
+    }
+
+    order
+}
+
+/// Finds the closest common dominator of two blocks by walking up the dominator tree.
+fn intersect(
+    mut left: BlockIndex,
+    mut right: BlockIndex,
+    dominators: &[Option<BlockIndex>],
+    numbers: &[Option<usize>],
+) -> BlockIndex {
+    while left != right {
+        while numbers[left] < numbers[right] {
+            left = dominators[left].unwrap_or(right);
+        }
+
+        while numbers[right] < numbers[left] {
+            right = dominators[right].unwrap_or(left);
+        }
+    }
+
+    left
+}
+
+/// The dominator tree of a control flow graph.
+/// A block dominates another block if every path from the entry to the other block passes through it.
+/// Blocks that are unreachable from the entry are not part of the tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DominatorTree {
+    entry: BlockIndex,
+    dominators: Vec<Option<BlockIndex>>,
+}
+
+impl DominatorTree {
+    /// The immediate dominator of the given block,
+    /// or `None` for the entry block and blocks that are unreachable from the entry.
+    pub fn immediate_dominator(&self, block: BlockIndex) -> Option<BlockIndex> {
+        self.dominators.get(block).copied().flatten()
+    }
+
+    /// Returns true if the given block is reachable from the entry, false otherwise.
+    pub fn is_reachable(&self, block: BlockIndex) -> bool {
+        block == self.entry || self.immediate_dominator(block).is_some()
+    }
+
+    /// Returns true if the dominator dominates the given block, false otherwise.
+    /// Every reachable block dominates itself.
+    pub fn dominates(&self, dominator: BlockIndex, block: BlockIndex) -> bool {
+        if !self.is_reachable(block) {
+            return false;
+        }
+
+        let mut current = Some(block);
+
+        while let Some(block) = current {
+            if block == dominator {
+                return true;
+            }
+
+            current = self.immediate_dominator(block);
+        }
+
+        false
+    }
+
+    /// The blocks immediately dominated by the given block.
+    pub fn children(&self, block: BlockIndex) -> impl Iterator<Item = BlockIndex> + '_ {
+        self.dominators
+            .iter()
+            .enumerate()
+            .filter(move |(_, dominator)| **dominator == Some(block))
+            .map(|(child, _)| child)
+    }
+}
+
+/// The target of a label in the lowering of a structured instruction,
+/// along with the targets of its catch clauses for a `try_table` instruction.
+struct Label {
+    target: BlockIndex,
+    catches: Vec<(BlockIndex, bool)>,
+}
+
+impl Label {
+    fn new(target: BlockIndex) -> Self {
+        Label {
+            target,
+            catches: Vec::new(),
+        }
+    }
+}
+
+/// Lowers structured instructions into basic blocks.
+struct Builder<'function> {
+    graph: ControlFlowGraph<'function>,
+    /// The edges of the graph, to avoid adding an edge more than once.
+    edges: HashSet<ControlFlowEdge>,
+    labels: Vec<Label>,
+    current: Option<BlockIndex>,
+}
+
+impl<'function> Builder<'function> {
+    fn lower(&mut self, expression: &'function Expression) {
+        for instruction in expression.instructions() {
+            match instruction {
+                Instruction::Control(control) => self.lower_control(instruction, control),
+                _ => self.append(instruction),
+            }
+        }
+    }
+
+    fn lower_control(
+        &mut self,
+        instruction: &'function Instruction,
+        control: &'function ControlInstruction,
+    ) {
+        match control {
+            ControlInstruction::Block(_, body) => {
+                let after = self.new_block();
+
+                self.nested(Label::new(after), body, after);
+                self.current = Some(after);
+            }
+            ControlInstruction::Loop(_, body) => {
+                let header = self.new_block();
+                let after = self.new_block();
+
+                self.fallthrough(header);
+                self.current = Some(header);
+                self.nested(Label::new(header), body, after);
+                self.current = Some(after);
+            }
+            ControlInstruction::If(_, consequent, alternative) => {
+                self.append(instruction);
+
+                let condition = self.current;
+                let then = self.new_block();
+                let otherwise = alternative.as_ref().map(|_| self.new_block());
+                let after = self.new_block();
+
+                self.fallthrough(then);
+                self.current = condition;
+                self.branch(otherwise.unwrap_or(after), EdgeKind::Branch);
+                self.current = Some(then);
+                self.nested(Label::new(after), consequent, after);
+
+                if let (Some(otherwise), Some(alternative)) = (otherwise, alternative) {
+                    self.current = Some(otherwise);
+                    self.nested(Label::new(after), alternative, after);
+                }
+
+                self.current = Some(after);
+            }
+            ControlInstruction::TryTable(_, clauses, body) => {
+                let catches = clauses
+                    .iter()
+                    .filter_map(|clause| {
+                        let all = matches!(
+                            clause,
+                            CatchClause::CatchAll(_) | CatchClause::CatchAllReference(_)
+                        );
+
+                        Some((self.target(clause.label())?, all))
+                    })
+                    .collect();
+                let after = self.new_block();
+
+                self.nested(
+                    Label {
+                        target: after,
+                        catches,
+                    },
+                    body,
+                    after,
+                );
+                self.current = Some(after);
+            }
+            ControlInstruction::Branch(label) => {
+                self.append(instruction);
+                self.branch_to_label(*label);
+                self.current = None;
+            }
+            ControlInstruction::BranchIf(label)
+            | ControlInstruction::BranchOnNull(label)
+            | ControlInstruction::BranchOnNonNull(label)
+            | ControlInstruction::BranchOnCast(label, _, _)
+            | ControlInstruction::BranchOnCastFail(label, _, _) => {
+                self.append(instruction);
+                self.branch_to_label(*label);
+                self.next();
+            }
+            ControlInstruction::BranchTable(labels, default) => {
+                self.append(instruction);
+
+                for label in labels.iter().chain(Some(default)) {
+                    self.branch_to_label(*label);
+                }
+
+                self.current = None;
+            }
+            ControlInstruction::Return
+            | ControlInstruction::ReturnCall(_)
+            | ControlInstruction::ReturnCallIndirect(_, _)
+            | ControlInstruction::ReturnCallRef(_) => {
+                self.append(instruction);
+                self.branch(ControlFlowGraph::EXIT, EdgeKind::Branch);
+                self.current = None;
+            }
+            ControlInstruction::Unreachable => {
+                self.append(instruction);
+                self.branch(ControlFlowGraph::TRAP, EdgeKind::Trap);
+                self.current = None;
+            }
+            ControlInstruction::Throw(_) | ControlInstruction::ThrowRef => {
+                self.append(instruction);
+
+                if !self.catch() {
+                    self.branch(ControlFlowGraph::TRAP, EdgeKind::Trap);
+                }
+
+                self.current = None;
+            }
+            ControlInstruction::Call(_)
+            | ControlInstruction::CallIndirect(_, _)
+            | ControlInstruction::CallRef(_) => {
+                self.append(instruction);
+
+                if self.labels.iter().any(|label| !label.catches.is_empty()) {
+                    if !self.catch() {
+                        self.branch(ControlFlowGraph::TRAP, EdgeKind::Trap);
+                    }
+
+                    self.next();
+                }
+            }
+            ControlInstruction::Nop => self.append(instruction),
+        }
+    }
+
+    /// Lowers the body of a structured instruction with the given label,
+    /// falling through to the block after the instruction at the end of the body.
+    fn nested(&mut self, label: Label, body: &'function Expression, after: BlockIndex) {
+        self.labels.push(label);
+        self.lower(body);
+        self.labels.pop();
+        self.fallthrough(after);
+    }
+
+    /// Adds edges from the current block to the catch clauses of the enclosing `try_table` instructions,
+    /// from the innermost outwards, until a catch-all clause is found.
+    /// Returns true if a catch-all clause is found, false otherwise.
+    fn catch(&mut self) -> bool {
+        let mut targets = Vec::new();
+        let mut caught = false;
+
+        for label in self.labels.iter().rev() {
+            for (target, all) in &label.catches {
+                targets.push(*target);
+                caught |= *all;
+            }
+
+            if caught {
+                break;
+            }
+        }
+
+        for target in targets {
+            self.branch(target, EdgeKind::Exception);
+        }
+
+        caught
+    }
+
+    /// The block targeted by the given label, if the label is defined.
+    fn target(&self, label: LabelIndex) -> Option<BlockIndex> {
+        let index = self.labels.len().checked_sub(label as usize + 1)?;
+
+        Some(self.labels[index].target)
+    }
+
+    fn branch_to_label(&mut self, label: LabelIndex) {
+        if let Some(target) = self.target(label) {
+            self.branch(target, EdgeKind::Branch);
+        }
+    }
+
+    /// Ends the current block, falling through to a new block.
+    fn next(&mut self) {
+        let next = self.new_block();
+
+        self.fallthrough(next);
+        self.current = Some(next);
+    }
+
+    fn fallthrough(&mut self, target: BlockIndex) {
+        self.branch(target, EdgeKind::Fallthrough);
+    }
+
+    /// Adds an edge from the current block to the target, unless the current block is unreachable.
+    fn branch(&mut self, target: BlockIndex, kind: EdgeKind) {
+        if let Some(source) = self.current {
+            let edge = ControlFlowEdge {
+                source,
+                target,
+                kind,
+            };
+
+            if self.edges.insert(edge) {
+                self.graph.edges.push(edge);
+            }
+        }
+    }
+
+    /// Appends an instruction to the current block,
+    /// starting a new block without predecessors if the current block is unreachable.
+    fn append(&mut self, instruction: &'function Instruction) {
+        let current = match self.current {
+            Some(current) => current,
+            None => {
+                let block = self.new_block();
+
+                self.current = Some(block);
+                block
+            }
+        };
+
+        self.graph.blocks[current].instructions.push(instruction);
+    }
+
+    fn new_block(&mut self) -> BlockIndex {
+        self.graph.blocks.push(BasicBlock::default());
+        self.graph.blocks.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+
+    fn edges(graph: &ControlFlowGraph) -> Vec<(BlockIndex, BlockIndex, EdgeKind)> {
+        graph
+            .edges()
+            .iter()
+            .map(|edge| (edge.source(), edge.target(), edge.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn if_else() {
+        let function = Function::new(
+            0,
+            ResultType::empty(),
+            Expression::from(vec![
+                VariableInstruction::LocalGet(0).into(),
+                ControlInstruction::If(
+                    BlockType::None,
+                    Expression::from(vec![1i32.into(), ParametricInstruction::Drop.into()]),
+                    Some(Expression::from(vec![ControlInstruction::Nop.into()])),
+                )
+                .into(),
+                ControlInstruction::Nop.into(),
+            ]),
+        );
+        let graph = ControlFlowGraph::new(&function);
+        let dominators = graph.dominators();
+
+        assert_eq!(graph.blocks().len(), 6);
+        assert_eq!(graph.block(0).unwrap().instructions().len(), 2);
+        assert_eq!(graph.block(3).unwrap().instructions().len(), 2);
+        assert_eq!(graph.block(4).unwrap().instructions().len(), 1);
+        assert_eq!(graph.block(5).unwrap().instructions().len(), 1);
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (0, 3, EdgeKind::Fallthrough),
+                (0, 4, EdgeKind::Branch),
+                (3, 5, EdgeKind::Fallthrough),
+                (4, 5, EdgeKind::Fallthrough),
+                (5, 1, EdgeKind::Fallthrough),
+            ]
+        );
+        assert_eq!(dominators.immediate_dominator(0), None);
+        assert_eq!(dominators.immediate_dominator(3), Some(0));
+        assert_eq!(dominators.immediate_dominator(4), Some(0));
+        assert_eq!(dominators.immediate_dominator(5), Some(0));
+        assert_eq!(dominators.immediate_dominator(1), Some(5));
+        assert_eq!(dominators.children(0).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert!(!dominators.dominates(3, 5));
+        assert!(!dominators.is_reachable(graph.trap()));
+    }
+
+    #[test]
+    fn loop_with_trap() {
+        let function = Function::new(
+            0,
+            ResultType::empty(),
+            Expression::from(vec![
+                ControlInstruction::Loop(
+                    BlockType::None,
+                    Expression::from(vec![
+                        VariableInstruction::LocalGet(0).into(),
+                        ControlInstruction::BranchIf(0).into(),
+                    ]),
+                )
+                .into(),
+                ControlInstruction::Unreachable.into(),
+            ]),
+        );
+        let graph = ControlFlowGraph::new(&function);
+        let dominators = graph.dominators();
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (0, 3, EdgeKind::Fallthrough),
+                (3, 3, EdgeKind::Branch),
+                (3, 5, EdgeKind::Fallthrough),
+                (5, 4, EdgeKind::Fallthrough),
+                (4, 2, EdgeKind::Trap),
+            ]
+        );
+        assert_eq!(dominators.immediate_dominator(3), Some(0));
+        assert_eq!(dominators.immediate_dominator(5), Some(3));
+        assert_eq!(dominators.immediate_dominator(4), Some(5));
+        assert_eq!(dominators.immediate_dominator(2), Some(4));
+        assert!(dominators.dominates(3, graph.trap()));
+        assert!(!dominators.is_reachable(graph.exit()));
+    }
+
+    #[test]
+    fn exceptions_and_dead_code() {
+        let function = Function::new(
+            0,
+            ResultType::empty(),
+            Expression::from(vec![
+                ControlInstruction::Block(
+                    BlockType::None,
+                    Expression::from(vec![ControlInstruction::TryTable(
+                        BlockType::None,
+                        vec![CatchClause::CatchAll(0)],
+                        Expression::from(vec![
+                            ControlInstruction::Call(0).into(),
+                            ControlInstruction::Throw(0).into(),
+                        ]),
+                    )
+                    .into()]),
+                )
+                .into(),
+                ControlInstruction::Branch(0).into(),
+                ControlInstruction::Nop.into(),
+            ]),
+        );
+        let graph = ControlFlowGraph::new(&function);
+        let dominators = graph.dominators();
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (0, 3, EdgeKind::Exception),
+                (0, 5, EdgeKind::Fallthrough),
+                (5, 3, EdgeKind::Exception),
+                (4, 3, EdgeKind::Fallthrough),
+                (3, 1, EdgeKind::Branch),
+                (6, 1, EdgeKind::Fallthrough),
+            ]
+        );
+        assert_eq!(graph.block(6).unwrap().instructions().len(), 1);
+        assert_eq!(dominators.immediate_dominator(3), Some(0));
+        assert_eq!(dominators.immediate_dominator(1), Some(3));
+        assert!(!dominators.is_reachable(4));
+        assert!(!dominators.is_reachable(6));
+    }
+
+    #[test]
+    fn uncaught_call() {
+        let function = Function::new(
+            0,
+            ResultType::empty(),
+            Expression::from(vec![ControlInstruction::Block(
+                BlockType::None,
+                Expression::from(vec![ControlInstruction::TryTable(
+                    BlockType::None,
+                    vec![CatchClause::Catch(0, 0)],
+                    Expression::from(vec![
+                        ControlInstruction::Call(0).into(),
+                        ControlInstruction::Nop.into(),
+                    ]),
+                )
+                .into()]),
+            )
+            .into()]),
+        );
+        let graph = ControlFlowGraph::new(&function);
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (0, 3, EdgeKind::Exception),
+                (0, 2, EdgeKind::Trap),
+                (0, 5, EdgeKind::Fallthrough),
+                (5, 4, EdgeKind::Fallthrough),
+                (4, 3, EdgeKind::Fallthrough),
+                (3, 1, EdgeKind::Fallthrough),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_branch_targets() {
+        let function = Function::new(
+            0,
+            ResultType::empty(),
+            Expression::from(vec![
+                VariableInstruction::LocalGet(0).into(),
+                ControlInstruction::BranchTable(vec![0, 0, 0], 0).into(),
+            ]),
+        );
+        let graph = ControlFlowGraph::new(&function);
+
+        assert_eq!(edges(&graph), vec![(0, 1, EdgeKind::Branch)]);
+    }
+}
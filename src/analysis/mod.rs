@@ -0,0 +1,12 @@
+//! Analyses of WebAssembly modules.
+//!
+//! Analyses summarize the structure of a module (e.g., which functions call each other)
+//! without modifying it.
+
+mod call_graph;
+mod control_flow;
+
+pub use call_graph::{CallEdge, CallGraph, CallKind};
+pub use control_flow::{
+    BasicBlock, BlockIndex, ControlFlowEdge, ControlFlowGraph, DominatorTree, EdgeKind,
+};
@@ -0,0 +1,153 @@
+//! Classification of instructions and expressions as constant.
+
+use crate::model::{
+    AggregateInstruction, Expression, Instruction, NumberType, NumericInstruction,
+    ReferenceInstruction, VariableInstruction, VectorInstruction,
+};
+
+/// Determines whether the instruction is allowed in a constant expression.
+/// In addition to the constant instructions of the core specification
+/// (i.e., 𝗍.𝖼𝗈𝗇𝗌𝗍, 𝗋𝖾𝖿.𝗇𝗎𝗅𝗅, 𝗋𝖾𝖿.𝖿𝗎𝗇𝖼 and 𝗀𝗅𝗈𝖻𝖺𝗅.𝗀𝖾𝗍),
+/// the extended constant expressions proposal allows 𝗂𝟥𝟤 and 𝗂𝟨𝟦 𝖺𝖽𝖽, 𝗌𝗎𝖻 and 𝗆𝗎𝗅,
+/// while the garbage collection proposal allows allocating structures, arrays and 𝗂𝟥𝟣 references,
+/// as well as converting between the internal and external type hierarchies.
+///
+/// **Note:** The instruction 𝗀𝗅𝗈𝖻𝖺𝗅.𝗀𝖾𝗍 is only constant when it refers to an immutable global,
+/// which cannot be determined from the instruction alone.
+///
+/// See <https://webassembly.github.io/spec/core/valid/instructions.html#constant-expressions>
+///
+/// See <https://webassembly.github.io/extended-const/core/valid/instructions.html#constant-expressions>
+///
+/// See <https://webassembly.github.io/gc/core/valid/instructions.html#constant-expressions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{is_constant_instruction, NumberType, NumericInstruction, VariableInstruction};
+///
+/// assert!(is_constant_instruction(&42i32.into()));
+/// assert!(is_constant_instruction(&VariableInstruction::GlobalGet(0).into()));
+/// assert!(is_constant_instruction(&NumericInstruction::Add(NumberType::I64).into()));
+/// assert!(!is_constant_instruction(&NumericInstruction::Add(NumberType::F32).into()));
+/// assert!(!is_constant_instruction(&VariableInstruction::LocalGet(0).into()));
+/// ```
+pub fn is_constant_instruction(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Numeric(
+            NumericInstruction::I32Constant(_)
+            | NumericInstruction::I64Constant(_)
+            | NumericInstruction::F32Constant(_)
+            | NumericInstruction::F64Constant(_),
+        ) => true,
+        Instruction::Numeric(
+            NumericInstruction::Add(kind)
+            | NumericInstruction::Subtract(kind)
+            | NumericInstruction::Multiply(kind),
+        ) => matches!(kind, NumberType::I32 | NumberType::I64),
+        Instruction::Vector(VectorInstruction::Constant(_)) => true,
+        Instruction::Reference(
+            ReferenceInstruction::Null(_) | ReferenceInstruction::Function(_),
+        ) => true,
+        Instruction::Variable(VariableInstruction::GlobalGet(_)) => true,
+        Instruction::Aggregate(
+            AggregateInstruction::StructNew(_)
+            | AggregateInstruction::StructNewDefault(_)
+            | AggregateInstruction::ArrayNew(_)
+            | AggregateInstruction::ArrayNewDefault(_)
+            | AggregateInstruction::ArrayNewFixed(_, _)
+            | AggregateInstruction::ReferenceI31
+            | AggregateInstruction::AnyConvertExternal
+            | AggregateInstruction::ExternalConvertAny,
+        ) => true,
+        _ => false,
+    }
+}
+
+/// Determines whether every instruction in the expression is allowed in a constant expression.
+///
+/// See <https://webassembly.github.io/extended-const/core/valid/instructions.html#constant-expressions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{is_constant, Expression, NumberType, NumericInstruction, VariableInstruction};
+///
+/// let offset = Expression::new(vec![
+///     VariableInstruction::GlobalGet(0).into(),
+///     1024i32.into(),
+///     NumericInstruction::Add(NumberType::I32).into(),
+/// ]);
+///
+/// assert!(is_constant(&offset));
+/// assert!(is_constant(&Expression::empty()));
+/// assert!(!is_constant(&vec![VariableInstruction::LocalGet(0).into()].into()));
+/// ```
+pub fn is_constant(expression: &Expression) -> bool {
+    expression
+        .instructions()
+        .iter()
+        .all(is_constant_instruction)
+}
+
+/// The instructions of the expression that are not allowed in a constant expression,
+/// along with their position in the expression.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{non_constant_instructions, Expression, NumberType, NumericInstruction};
+///
+/// let expression = Expression::new(vec![
+///     1i32.into(),
+///     2i32.into(),
+///     NumericInstruction::DivideFloat(wasm_ast::FloatType::F32).into(),
+/// ]);
+/// let violations: Vec<_> = non_constant_instructions(&expression).collect();
+///
+/// assert_eq!(violations, vec![(2, &NumericInstruction::DivideFloat(wasm_ast::FloatType::F32).into())]);
+/// ```
+pub fn non_constant_instructions(
+    expression: &Expression,
+) -> impl Iterator<Item = (usize, &Instruction)> {
+    expression
+        .instructions()
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| !is_constant_instruction(instruction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ControlInstruction, HeapType, IntegerType, SignExtension};
+
+    #[test]
+    fn classify_extended_constants() {
+        let expression = Expression::new(vec![
+            VariableInstruction::GlobalGet(0).into(),
+            2i64.into(),
+            NumericInstruction::Multiply(NumberType::I64).into(),
+            3i64.into(),
+            NumericInstruction::Subtract(NumberType::I64).into(),
+            ReferenceInstruction::Null(HeapType::Function).into(),
+            AggregateInstruction::ArrayNewFixed(0, 1).into(),
+        ]);
+
+        assert!(is_constant(&expression));
+        assert_eq!(non_constant_instructions(&expression).count(), 0);
+    }
+
+    #[test]
+    fn classify_non_constants() {
+        let expression = Expression::new(vec![
+            1i32.into(),
+            NumericInstruction::DivideInteger(IntegerType::I32, SignExtension::Signed).into(),
+            ControlInstruction::Nop.into(),
+            VariableInstruction::GlobalSet(0).into(),
+        ]);
+        let violations: Vec<usize> = non_constant_instructions(&expression)
+            .map(|(index, _)| index)
+            .collect();
+
+        assert!(!is_constant(&expression));
+        assert_eq!(violations, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,26 @@
+use crate::constant::ConstantValue;
+use crate::model::{DataIndex, ElementIndex, GlobalIndex, Instruction};
+use thiserror::Error;
+
+/// An error in classifying or evaluating a WebAssembly constant expression.
+#[derive(Error, Debug)]
+pub enum ConstantError {
+    #[error("The instruction is not allowed in a constant expression: {0:?}")]
+    NonConstant(Instruction),
+    #[error(
+        "The instruction is constant, but cannot be evaluated without allocating in a store: {0:?}"
+    )]
+    Unsupported(Instruction),
+    #[error("The value of the global with index {0} is unknown.")]
+    UnknownGlobal(GlobalIndex),
+    #[error("The operands of the instruction are missing or have the wrong type: {0:?}")]
+    TypeMismatch(Instruction),
+    #[error("A constant expression must produce exactly one value, but produced {0}.")]
+    InvalidArity(usize),
+    #[error("The constant expression produced a value of an unexpected type: {0:?}")]
+    UnexpectedValue(ConstantValue),
+    #[error("The module does not define a data segment with index {0}.")]
+    UnknownData(DataIndex),
+    #[error("The module does not define an element segment with index {0}.")]
+    UnknownElement(ElementIndex),
+}
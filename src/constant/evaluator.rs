@@ -0,0 +1,496 @@
+//! Evaluation of constant expressions without instantiating a module.
+
+use crate::constant::{is_constant_instruction, ConstantError};
+use crate::model::{
+    DataIndex, DataMode, ElementIndex, ElementMode, Expression, FunctionIndex, GlobalIndex,
+    HeapType, ImportDescription, Instruction, Module, NumberType, NumericInstruction,
+    ReferenceInstruction, VariableInstruction, VectorInstruction,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// The value produced by evaluating a constant expression.
+///
+/// See <https://webassembly.github.io/spec/core/exec/runtime.html#values>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{ConstantValue, HeapType};
+///
+/// assert_eq!(ConstantValue::from(42i32), ConstantValue::I32(42));
+/// assert_eq!(ConstantValue::from(42i64), ConstantValue::I64(42));
+/// assert_eq!(ConstantValue::I32(-1).as_offset(), Some(u32::MAX as u64));
+/// assert_eq!(ConstantValue::I64(8).as_offset(), Some(8));
+/// assert_eq!(ConstantValue::Null(HeapType::Function).as_offset(), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConstantValue {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    V128(i128),
+    /// A null reference of the given heap type.
+    Null(HeapType),
+    /// A reference to the function with the given index.
+    Function(FunctionIndex),
+}
+
+impl ConstantValue {
+    /// Interprets this value as an unsigned offset into a memory or table.
+    /// Only integer values can be interpreted as offsets.
+    pub fn as_offset(&self) -> Option<u64> {
+        match self {
+            ConstantValue::I32(value) => Some(*value as u32 as u64),
+            ConstantValue::I64(value) => Some(*value as u64),
+            _ => None,
+        }
+    }
+}
+
+impl From<i32> for ConstantValue {
+    fn from(value: i32) -> Self {
+        ConstantValue::I32(value)
+    }
+}
+
+impl From<i64> for ConstantValue {
+    fn from(value: i64) -> Self {
+        ConstantValue::I64(value)
+    }
+}
+
+impl From<f32> for ConstantValue {
+    fn from(value: f32) -> Self {
+        ConstantValue::F32(value)
+    }
+}
+
+impl From<f64> for ConstantValue {
+    fn from(value: f64) -> Self {
+        ConstantValue::F64(value)
+    }
+}
+
+/// Evaluates a constant expression to the single value it produces.
+/// The values of globals referenced by 𝗀𝗅𝗈𝖻𝖺𝗅.𝗀𝖾𝗍 are looked up using the given function.
+/// Integer arithmetic wraps around on overflow, as it does at runtime.
+///
+/// Instructions that allocate in the store (e.g., 𝗌𝗍𝗋𝗎𝖼𝗍.𝗇𝖾𝗐) are constant,
+/// but cannot be evaluated without a store and result in an error.
+///
+/// See <https://webassembly.github.io/extended-const/core/exec/instructions.html>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{evaluate, ConstantValue, Expression, NumberType, NumericInstruction, VariableInstruction};
+///
+/// let offset = Expression::new(vec![
+///     VariableInstruction::GlobalGet(0).into(),
+///     16i32.into(),
+///     NumericInstruction::Add(NumberType::I32).into(),
+/// ]);
+///
+/// assert_eq!(
+///     evaluate(&offset, |index| (index == 0).then(|| ConstantValue::I32(1024))).unwrap(),
+///     ConstantValue::I32(1040)
+/// );
+/// assert!(evaluate(&offset, |_| None).is_err());
+/// ```
+pub fn evaluate<G>(expression: &Expression, mut globals: G) -> Result<ConstantValue, ConstantError>
+where
+    G: FnMut(GlobalIndex) -> Option<ConstantValue>,
+{
+    evaluate_with(expression, |index| {
+        globals(index).ok_or(ConstantError::UnknownGlobal(index))
+    })
+}
+
+/// Evaluates a constant expression using a fallible lookup of global values.
+fn evaluate_with<G>(expression: &Expression, mut globals: G) -> Result<ConstantValue, ConstantError>
+where
+    G: FnMut(GlobalIndex) -> Result<ConstantValue, ConstantError>,
+{
+    let mut stack = Vec::new();
+
+    for instruction in expression.instructions() {
+        let value = match instruction {
+            Instruction::Numeric(NumericInstruction::I32Constant(value)) => {
+                ConstantValue::I32(*value)
+            }
+            Instruction::Numeric(NumericInstruction::I64Constant(value)) => {
+                ConstantValue::I64(*value)
+            }
+            Instruction::Numeric(NumericInstruction::F32Constant(value)) => {
+                ConstantValue::F32(*value)
+            }
+            Instruction::Numeric(NumericInstruction::F64Constant(value)) => {
+                ConstantValue::F64(*value)
+            }
+            Instruction::Vector(VectorInstruction::Constant(value)) => ConstantValue::V128(*value),
+            Instruction::Reference(ReferenceInstruction::Null(kind)) => ConstantValue::Null(*kind),
+            Instruction::Reference(ReferenceInstruction::Function(index)) => {
+                ConstantValue::Function(*index)
+            }
+            Instruction::Variable(VariableInstruction::GlobalGet(index)) => globals(*index)?,
+            Instruction::Numeric(numeric) if is_constant_instruction(instruction) => {
+                let right = stack.pop();
+                let left = stack.pop();
+
+                evaluate_arithmetic(numeric, left, right)
+                    .ok_or_else(|| ConstantError::TypeMismatch(instruction.clone()))?
+            }
+            instruction if is_constant_instruction(instruction) => {
+                return Err(ConstantError::Unsupported(instruction.clone()))
+            }
+            instruction => return Err(ConstantError::NonConstant(instruction.clone())),
+        };
+
+        stack.push(value);
+    }
+
+    match stack.as_slice() {
+        [value] => Ok(*value),
+        values => Err(ConstantError::InvalidArity(values.len())),
+    }
+}
+
+/// Evaluates an extended constant arithmetic instruction on the given operands.
+/// Returns `None` if an operand is missing or has a type that does not match the instruction.
+fn evaluate_arithmetic(
+    instruction: &NumericInstruction,
+    left: Option<ConstantValue>,
+    right: Option<ConstantValue>,
+) -> Option<ConstantValue> {
+    let value = match (instruction, left?, right?) {
+        (
+            NumericInstruction::Add(NumberType::I32),
+            ConstantValue::I32(left),
+            ConstantValue::I32(right),
+        ) => ConstantValue::I32(left.wrapping_add(right)),
+        (
+            NumericInstruction::Subtract(NumberType::I32),
+            ConstantValue::I32(left),
+            ConstantValue::I32(right),
+        ) => ConstantValue::I32(left.wrapping_sub(right)),
+        (
+            NumericInstruction::Multiply(NumberType::I32),
+            ConstantValue::I32(left),
+            ConstantValue::I32(right),
+        ) => ConstantValue::I32(left.wrapping_mul(right)),
+        (
+            NumericInstruction::Add(NumberType::I64),
+            ConstantValue::I64(left),
+            ConstantValue::I64(right),
+        ) => ConstantValue::I64(left.wrapping_add(right)),
+        (
+            NumericInstruction::Subtract(NumberType::I64),
+            ConstantValue::I64(left),
+            ConstantValue::I64(right),
+        ) => ConstantValue::I64(left.wrapping_sub(right)),
+        (
+            NumericInstruction::Multiply(NumberType::I64),
+            ConstantValue::I64(left),
+            ConstantValue::I64(right),
+        ) => ConstantValue::I64(left.wrapping_mul(right)),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Evaluates the constant expressions of a module (e.g., data segment offsets),
+/// such as a linker needs to without instantiating the module.
+///
+/// The values of imported globals are unknown to the module and must be provided to the evaluator.
+/// Globals defined by the module are evaluated from their initializer,
+/// which may only refer to globals that precede them in the global index space.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{ConstantEvaluator, ConstantValue, Data, Expression, GlobalType, Import, Module, NumberType, NumericInstruction, ValueType, VariableInstruction};
+///
+/// let mut builder = Module::builder();
+/// builder.add_import(Import::global("env".into(), "__memory_base".into(), GlobalType::immutable(ValueType::I32))).unwrap();
+/// builder.add_data(Data::active(0, Expression::new(vec![
+///     VariableInstruction::GlobalGet(0).into(),
+///     16i32.into(),
+///     NumericInstruction::Add(NumberType::I32).into(),
+/// ]), b"hello".to_vec())).unwrap();
+/// builder.add_data(Data::passive(b"world".to_vec())).unwrap();
+///
+/// let module = builder.build();
+/// let mut evaluator = ConstantEvaluator::new(&module);
+///
+/// assert!(evaluator.data_offset(0).is_err());
+///
+/// evaluator.set_global(0, ConstantValue::I32(1024));
+///
+/// assert_eq!(evaluator.global(0).unwrap(), ConstantValue::I32(1024));
+/// assert_eq!(evaluator.data_offset(0).unwrap(), Some(1040));
+/// assert_eq!(evaluator.data_offset(1).unwrap(), None);
+/// assert!(evaluator.data_offset(2).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConstantEvaluator<'module> {
+    module: &'module Module,
+    imports: usize,
+    globals: HashMap<GlobalIndex, ConstantValue>,
+    evaluated: RefCell<HashMap<GlobalIndex, ConstantValue>>,
+}
+
+impl<'module> ConstantEvaluator<'module> {
+    /// Creates a new evaluator of constant expressions in the context of the given module.
+    pub fn new(module: &'module Module) -> Self {
+        let imports = module
+            .imports()
+            .unwrap_or_default()
+            .iter()
+            .filter(|import| matches!(import.description(), ImportDescription::Global(_)))
+            .count();
+
+        ConstantEvaluator {
+            module,
+            imports,
+            globals: HashMap::new(),
+            evaluated: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the value of the global at the given index (e.g., the value supplied for an import).
+    /// Values set on the evaluator take precedence over the initializers of defined globals.
+    pub fn set_global(&mut self, index: GlobalIndex, value: ConstantValue) {
+        self.globals.insert(index, value);
+        self.evaluated.get_mut().clear();
+    }
+
+    /// The value of the global at the given index.
+    /// The value of each defined global is evaluated once and reused by later calls.
+    pub fn global(&self, index: GlobalIndex) -> Result<ConstantValue, ConstantError> {
+        if let Some(value) = self.globals.get(&index) {
+            return Ok(*value);
+        }
+
+        if let Some(value) = self.evaluated.borrow().get(&index) {
+            return Ok(*value);
+        }
+
+        let global = (index as usize)
+            .checked_sub(self.imports)
+            .and_then(|defined| self.module.globals()?.get(defined))
+            .ok_or(ConstantError::UnknownGlobal(index))?;
+        let value = evaluate_with(global.initializer(), |referenced| {
+            if referenced < index {
+                self.global(referenced)
+            } else {
+                Err(ConstantError::UnknownGlobal(referenced))
+            }
+        })?;
+
+        self.evaluated.borrow_mut().insert(index, value);
+
+        Ok(value)
+    }
+
+    /// Evaluates the constant expression in the context of the module.
+    pub fn evaluate(&self, expression: &Expression) -> Result<ConstantValue, ConstantError> {
+        evaluate_with(expression, |index| self.global(index))
+    }
+
+    /// The offset into memory of the data segment at the given index.
+    /// Returns `None` for passive data segments.
+    pub fn data_offset(&self, index: DataIndex) -> Result<Option<u64>, ConstantError> {
+        let data = self
+            .module
+            .data()
+            .and_then(|data| data.get(index as usize))
+            .ok_or(ConstantError::UnknownData(index))?;
+
+        match data.mode() {
+            DataMode::Passive => Ok(None),
+            DataMode::Active(_, offset) => self.offset(offset).map(Some),
+        }
+    }
+
+    /// The offset into a table of the element segment at the given index.
+    /// Returns `None` for passive and declarative element segments.
+    pub fn element_offset(&self, index: ElementIndex) -> Result<Option<u64>, ConstantError> {
+        let element = self
+            .module
+            .elements()
+            .and_then(|elements| elements.get(index as usize))
+            .ok_or(ConstantError::UnknownElement(index))?;
+
+        match element.mode() {
+            ElementMode::Passive | ElementMode::Declarative => Ok(None),
+            ElementMode::Active(_, offset) => self.offset(offset).map(Some),
+        }
+    }
+
+    /// Evaluates the constant expression as an offset into a memory or table.
+    fn offset(&self, expression: &Expression) -> Result<u64, ConstantError> {
+        let value = self.evaluate(expression)?;
+
+        value
+            .as_offset()
+            .ok_or(ConstantError::UnexpectedValue(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AggregateInstruction, Global, GlobalType, Import, ValueType};
+
+    #[test]
+    fn evaluate_wrapping_arithmetic() {
+        let expression = Expression::new(vec![
+            i64::MAX.into(),
+            2i64.into(),
+            NumericInstruction::Multiply(NumberType::I64).into(),
+            3i64.into(),
+            NumericInstruction::Subtract(NumberType::I64).into(),
+        ]);
+
+        assert_eq!(
+            evaluate(&expression, |_| None).unwrap(),
+            ConstantValue::I64(i64::MAX.wrapping_mul(2).wrapping_sub(3))
+        );
+    }
+
+    #[test]
+    fn evaluate_type_mismatch() {
+        let expression = Expression::new(vec![
+            1i32.into(),
+            2i64.into(),
+            NumericInstruction::Add(NumberType::I32).into(),
+        ]);
+
+        assert!(matches!(
+            evaluate(&expression, |_| None),
+            Err(ConstantError::TypeMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn evaluate_stack_underflow() {
+        let expression = Expression::new(vec![
+            1i32.into(),
+            NumericInstruction::Add(NumberType::I32).into(),
+        ]);
+
+        assert!(matches!(
+            evaluate(&expression, |_| None),
+            Err(ConstantError::TypeMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn evaluate_invalid_arity() {
+        let expression = Expression::new(vec![1i32.into(), 2i32.into()]);
+
+        assert!(matches!(
+            evaluate(&expression, |_| None),
+            Err(ConstantError::InvalidArity(2))
+        ));
+        assert!(matches!(
+            evaluate(&Expression::empty(), |_| None),
+            Err(ConstantError::InvalidArity(0))
+        ));
+    }
+
+    #[test]
+    fn evaluate_non_constant() {
+        let expression = Expression::new(vec![VariableInstruction::LocalGet(0).into()]);
+
+        assert!(matches!(
+            evaluate(&expression, |_| None),
+            Err(ConstantError::NonConstant(_))
+        ));
+    }
+
+    #[test]
+    fn evaluate_unsupported() {
+        let expression = Expression::new(vec![AggregateInstruction::StructNewDefault(0).into()]);
+
+        assert!(matches!(
+            evaluate(&expression, |_| None),
+            Err(ConstantError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn evaluate_defined_globals() {
+        let mut builder = Module::builder();
+        builder
+            .add_import(Import::global(
+                "env".into(),
+                "base".into(),
+                GlobalType::immutable(ValueType::I64),
+            ))
+            .unwrap();
+        builder
+            .add_global(Global::immutable(
+                ValueType::I64,
+                Expression::new(vec![
+                    VariableInstruction::GlobalGet(0).into(),
+                    8i64.into(),
+                    NumericInstruction::Add(NumberType::I64).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_global(Global::immutable(
+                ValueType::I64,
+                Expression::new(vec![VariableInstruction::GlobalGet(2).into()]),
+            ))
+            .unwrap();
+
+        let module = builder.build();
+        let mut evaluator = ConstantEvaluator::new(&module);
+
+        evaluator.set_global(0, ConstantValue::I64(4096));
+
+        assert_eq!(evaluator.global(1).unwrap(), ConstantValue::I64(4104));
+        assert!(matches!(
+            evaluator.global(2),
+            Err(ConstantError::UnknownGlobal(2))
+        ));
+        assert!(matches!(
+            evaluator.global(3),
+            Err(ConstantError::UnknownGlobal(3))
+        ));
+
+        evaluator.set_global(0, ConstantValue::I64(0));
+
+        assert_eq!(evaluator.global(1).unwrap(), ConstantValue::I64(8));
+    }
+
+    #[test]
+    fn evaluate_chained_globals() {
+        let mut builder = Module::builder();
+
+        builder
+            .add_global(Global::immutable(ValueType::I64, vec![1i64.into()].into()))
+            .unwrap();
+
+        for index in 0..64 {
+            builder
+                .add_global(Global::immutable(
+                    ValueType::I64,
+                    Expression::new(vec![
+                        VariableInstruction::GlobalGet(index).into(),
+                        VariableInstruction::GlobalGet(index).into(),
+                        NumericInstruction::Add(NumberType::I64).into(),
+                    ]),
+                ))
+                .unwrap();
+        }
+
+        let module = builder.build();
+        let evaluator = ConstantEvaluator::new(&module);
+
+        assert_eq!(evaluator.global(62).unwrap(), ConstantValue::I64(1 << 62));
+        assert_eq!(evaluator.global(64).unwrap(), ConstantValue::I64(0));
+    }
+}
@@ -0,0 +1,18 @@
+//! Classify and evaluate WebAssembly constant expressions.
+//!
+//! Constant expressions initialize globals, element segments and the offsets of active data and
+//! element segments.
+//! The evaluator supports the extended constant expressions, which allow integer arithmetic on
+//! the values of imported globals (e.g., to relocate data segments in a dynamically linked module).
+//!
+//! See <https://webassembly.github.io/spec/core/valid/instructions.html#constant-expressions>
+//!
+//! See <https://webassembly.github.io/extended-const/core/valid/instructions.html#constant-expressions>
+
+mod classifier;
+mod errors;
+mod evaluator;
+
+pub use classifier::{is_constant, is_constant_instruction, non_constant_instructions};
+pub use errors::ConstantError;
+pub use evaluator::{evaluate, ConstantEvaluator, ConstantValue};
@@ -1,3 +1,4 @@
+use crate::model::Instruction;
 use thiserror::Error;
 
 /// An error in emitting a WebAssembly module in the binary format.
@@ -9,4 +10,9 @@ pub enum EmitError {
     IO(#[from] std::io::Error),
     #[error("An error occurred encoding a number into LEB-128.")]
     Encode(#[from] crate::leb128::LEB128Error),
+    #[error("The instruction has no encoding in the binary format: {0:?}")]
+    UnsupportedInstruction(Instruction),
+    #[cfg(feature = "parser")]
+    #[error("The code of a function parsed lazily is not a valid binary format: {0}.")]
+    InvalidBinary(crate::parser::BinaryError),
 }
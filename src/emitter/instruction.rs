@@ -1,12 +1,15 @@
 use crate::emitter::errors::EmitError;
-use crate::emitter::types::{emit_reference_type, emit_value_type};
+use crate::emitter::types::{emit_heap_type, emit_value_type};
 use crate::emitter::values::{
-    emit_byte, emit_f32, emit_f64, emit_i32, emit_i64, emit_repeated, emit_u32, emit_vector,
+    emit_byte, emit_bytes, emit_f32, emit_f64, emit_i32, emit_i64, emit_repeated, emit_u32,
+    emit_u64, emit_vector,
 };
 use crate::model::{
-    BlockType, ControlInstruction, Expression, FloatType, Instruction, IntegerType, MemoryArgument,
-    MemoryInstruction, NumberType, NumericInstruction, ParametricInstruction, ReferenceInstruction,
-    SignExtension, TableInstruction, VariableInstruction,
+    AggregateInstruction, AtomicInstruction, AtomicOperation, BlockType, CatchClause,
+    ControlInstruction, Expression, FloatType, FloatVectorShape, Instruction, IntegerType,
+    IntegerVectorShape, LabelIndex, MemoryArgument, MemoryInstruction, NumberType,
+    NumericInstruction, ParametricInstruction, ReferenceInstruction, ReferenceType, SignExtension,
+    TableInstruction, VariableInstruction, VectorHalf, VectorInstruction, VectorShape,
 };
 use std::io::Write;
 
@@ -18,7 +21,6 @@ pub fn emit_expression<O: Write + ?Sized>(
     output: &mut O,
 ) -> Result<usize, EmitError> {
     let mut bytes = 0;
-    let expression = expression;
 
     for instruction in expression.instructions() {
         bytes += emit_instruction(instruction, output)?;
@@ -38,11 +40,14 @@ pub fn emit_instruction<O: Write + ?Sized>(
 ) -> Result<usize, EmitError> {
     match instruction {
         Instruction::Numeric(instruction) => emit_numeric_instruction(instruction, output),
+        Instruction::Vector(instruction) => emit_vector_instruction(instruction, output),
         Instruction::Reference(instruction) => emit_reference_instruction(instruction, output),
         Instruction::Parametric(instruction) => emit_parametric_instruction(instruction, output),
         Instruction::Variable(instruction) => emit_variable_instruction(instruction, output),
         Instruction::Table(instruction) => emit_table_instruction(instruction, output),
         Instruction::Memory(instruction) => emit_memory_instruction(instruction, output),
+        Instruction::Atomic(instruction) => emit_atomic_instruction(instruction, output),
+        Instruction::Aggregate(instruction) => emit_aggregate_instruction(instruction, output),
         Instruction::Control(instruction) => emit_control_instruction(instruction, output),
     }
 }
@@ -586,6 +591,522 @@ fn emit_numeric_instruction<O: Write + ?Sized>(
     Ok(bytes)
 }
 
+/// Emit a vector instruction to the output.
+/// Returns an error for instructions whose shape is not supported in the binary format.
+///
+/// See https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions
+pub fn emit_vector_instruction<O: Write + ?Sized>(
+    instruction: &VectorInstruction,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let opcode = vector_opcode(instruction)
+        .ok_or(EmitError::UnsupportedInstruction((*instruction).into()))?;
+    let mut bytes = 0;
+
+    bytes += emit_byte(0xFDu8, output)?;
+    bytes += emit_u32(opcode, output)?;
+
+    match instruction {
+        VectorInstruction::Constant(value) => {
+            bytes += emit_bytes(&value.to_le_bytes(), output, false)?;
+        }
+        VectorInstruction::Shuffle(lanes) => {
+            bytes += emit_bytes(lanes, output, false)?;
+        }
+        VectorInstruction::ExtractLane(_, _, lane) | VectorInstruction::ReplaceLane(_, lane) => {
+            bytes += emit_byte(lane, output)?;
+        }
+        VectorInstruction::Load(memory_argument)
+        | VectorInstruction::Store(memory_argument)
+        | VectorInstruction::Load8x8(_, memory_argument)
+        | VectorInstruction::Load16x4(_, memory_argument)
+        | VectorInstruction::Load32x2(_, memory_argument)
+        | VectorInstruction::Load8Splat(memory_argument)
+        | VectorInstruction::Load16Splat(memory_argument)
+        | VectorInstruction::Load32Splat(memory_argument)
+        | VectorInstruction::Load64Splat(memory_argument)
+        | VectorInstruction::Load32Zero(memory_argument)
+        | VectorInstruction::Load64Zero(memory_argument) => {
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        VectorInstruction::Load8Lane(memory_argument, lane)
+        | VectorInstruction::Load16Lane(memory_argument, lane)
+        | VectorInstruction::Load32Lane(memory_argument, lane)
+        | VectorInstruction::Load64Lane(memory_argument, lane)
+        | VectorInstruction::Store8Lane(memory_argument, lane)
+        | VectorInstruction::Store16Lane(memory_argument, lane)
+        | VectorInstruction::Store32Lane(memory_argument, lane)
+        | VectorInstruction::Store64Lane(memory_argument, lane) => {
+            bytes += emit_memory_argument(memory_argument, output)?;
+            bytes += emit_byte(lane, output)?;
+        }
+        _ => {}
+    }
+
+    Ok(bytes)
+}
+
+/// The opcode (following the 0xFD prefix) of a vector instruction.
+/// Returns `None` for instructions whose shape is not supported in the binary format.
+fn vector_opcode(instruction: &VectorInstruction) -> Option<u32> {
+    let opcode = match instruction {
+        VectorInstruction::Load(_) => 0x00,
+        VectorInstruction::Load8x8(SignExtension::Signed, _) => 0x01,
+        VectorInstruction::Load8x8(SignExtension::Unsigned, _) => 0x02,
+        VectorInstruction::Load16x4(SignExtension::Signed, _) => 0x03,
+        VectorInstruction::Load16x4(SignExtension::Unsigned, _) => 0x04,
+        VectorInstruction::Load32x2(SignExtension::Signed, _) => 0x05,
+        VectorInstruction::Load32x2(SignExtension::Unsigned, _) => 0x06,
+        VectorInstruction::Load8Splat(_) => 0x07,
+        VectorInstruction::Load16Splat(_) => 0x08,
+        VectorInstruction::Load32Splat(_) => 0x09,
+        VectorInstruction::Load64Splat(_) => 0x0A,
+        VectorInstruction::Store(_) => 0x0B,
+        VectorInstruction::Constant(_) => 0x0C,
+        VectorInstruction::Shuffle(_) => 0x0D,
+        VectorInstruction::Swizzle => 0x0E,
+        VectorInstruction::Splat(VectorShape::I8x16) => 0x0F,
+        VectorInstruction::Splat(VectorShape::I16x8) => 0x10,
+        VectorInstruction::Splat(VectorShape::I32x4) => 0x11,
+        VectorInstruction::Splat(VectorShape::I64x2) => 0x12,
+        VectorInstruction::Splat(VectorShape::F32x4) => 0x13,
+        VectorInstruction::Splat(VectorShape::F64x2) => 0x14,
+        VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Signed), _) => 0x15,
+        VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Unsigned), _) => {
+            0x16
+        }
+        VectorInstruction::ReplaceLane(VectorShape::I8x16, _) => 0x17,
+        VectorInstruction::ExtractLane(VectorShape::I16x8, Some(SignExtension::Signed), _) => 0x18,
+        VectorInstruction::ExtractLane(VectorShape::I16x8, Some(SignExtension::Unsigned), _) => {
+            0x19
+        }
+        VectorInstruction::ReplaceLane(VectorShape::I16x8, _) => 0x1A,
+        VectorInstruction::ExtractLane(VectorShape::I32x4, None, _) => 0x1B,
+        VectorInstruction::ReplaceLane(VectorShape::I32x4, _) => 0x1C,
+        VectorInstruction::ExtractLane(VectorShape::I64x2, None, _) => 0x1D,
+        VectorInstruction::ReplaceLane(VectorShape::I64x2, _) => 0x1E,
+        VectorInstruction::ExtractLane(VectorShape::F32x4, None, _) => 0x1F,
+        VectorInstruction::ReplaceLane(VectorShape::F32x4, _) => 0x20,
+        VectorInstruction::ExtractLane(VectorShape::F64x2, None, _) => 0x21,
+        VectorInstruction::ReplaceLane(VectorShape::F64x2, _) => 0x22,
+        VectorInstruction::Equal(VectorShape::I8x16) => 0x23,
+        VectorInstruction::NotEqual(VectorShape::I8x16) => 0x24,
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I8x16, SignExtension::Signed) => {
+            0x25
+        }
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned) => {
+            0x26
+        }
+        VectorInstruction::GreaterThanInteger(IntegerVectorShape::I8x16, SignExtension::Signed) => {
+            0x27
+        }
+        VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ) => 0x28,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Signed,
+        ) => 0x29,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ) => 0x2A,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Signed,
+        ) => 0x2B,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ) => 0x2C,
+        VectorInstruction::Equal(VectorShape::I16x8) => 0x2D,
+        VectorInstruction::NotEqual(VectorShape::I16x8) => 0x2E,
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I16x8, SignExtension::Signed) => {
+            0x2F
+        }
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned) => {
+            0x30
+        }
+        VectorInstruction::GreaterThanInteger(IntegerVectorShape::I16x8, SignExtension::Signed) => {
+            0x31
+        }
+        VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ) => 0x32,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Signed,
+        ) => 0x33,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ) => 0x34,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Signed,
+        ) => 0x35,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ) => 0x36,
+        VectorInstruction::Equal(VectorShape::I32x4) => 0x37,
+        VectorInstruction::NotEqual(VectorShape::I32x4) => 0x38,
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I32x4, SignExtension::Signed) => {
+            0x39
+        }
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned) => {
+            0x3A
+        }
+        VectorInstruction::GreaterThanInteger(IntegerVectorShape::I32x4, SignExtension::Signed) => {
+            0x3B
+        }
+        VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ) => 0x3C,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Signed,
+        ) => 0x3D,
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ) => 0x3E,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Signed,
+        ) => 0x3F,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ) => 0x40,
+        VectorInstruction::Equal(VectorShape::F32x4) => 0x41,
+        VectorInstruction::NotEqual(VectorShape::F32x4) => 0x42,
+        VectorInstruction::LessThanFloat(FloatVectorShape::F32x4) => 0x43,
+        VectorInstruction::GreaterThanFloat(FloatVectorShape::F32x4) => 0x44,
+        VectorInstruction::LessThanOrEqualToFloat(FloatVectorShape::F32x4) => 0x45,
+        VectorInstruction::GreaterThanOrEqualToFloat(FloatVectorShape::F32x4) => 0x46,
+        VectorInstruction::Equal(VectorShape::F64x2) => 0x47,
+        VectorInstruction::NotEqual(VectorShape::F64x2) => 0x48,
+        VectorInstruction::LessThanFloat(FloatVectorShape::F64x2) => 0x49,
+        VectorInstruction::GreaterThanFloat(FloatVectorShape::F64x2) => 0x4A,
+        VectorInstruction::LessThanOrEqualToFloat(FloatVectorShape::F64x2) => 0x4B,
+        VectorInstruction::GreaterThanOrEqualToFloat(FloatVectorShape::F64x2) => 0x4C,
+        VectorInstruction::Not => 0x4D,
+        VectorInstruction::And => 0x4E,
+        VectorInstruction::AndNot => 0x4F,
+        VectorInstruction::Or => 0x50,
+        VectorInstruction::Xor => 0x51,
+        VectorInstruction::BitSelect => 0x52,
+        VectorInstruction::AnyTrue => 0x53,
+        VectorInstruction::Load8Lane(_, _) => 0x54,
+        VectorInstruction::Load16Lane(_, _) => 0x55,
+        VectorInstruction::Load32Lane(_, _) => 0x56,
+        VectorInstruction::Load64Lane(_, _) => 0x57,
+        VectorInstruction::Store8Lane(_, _) => 0x58,
+        VectorInstruction::Store16Lane(_, _) => 0x59,
+        VectorInstruction::Store32Lane(_, _) => 0x5A,
+        VectorInstruction::Store64Lane(_, _) => 0x5B,
+        VectorInstruction::Load32Zero(_) => 0x5C,
+        VectorInstruction::Load64Zero(_) => 0x5D,
+        VectorInstruction::Demote => 0x5E,
+        VectorInstruction::Promote => 0x5F,
+        VectorInstruction::AbsoluteValue(VectorShape::I8x16) => 0x60,
+        VectorInstruction::Negate(VectorShape::I8x16) => 0x61,
+        VectorInstruction::CountOnes => 0x62,
+        VectorInstruction::AllTrue(IntegerVectorShape::I8x16) => 0x63,
+        VectorInstruction::Bitmask(IntegerVectorShape::I8x16) => 0x64,
+        VectorInstruction::Narrow(IntegerVectorShape::I8x16, SignExtension::Signed) => 0x65,
+        VectorInstruction::Narrow(IntegerVectorShape::I8x16, SignExtension::Unsigned) => 0x66,
+        VectorInstruction::Ceiling(FloatVectorShape::F32x4) => 0x67,
+        VectorInstruction::Floor(FloatVectorShape::F32x4) => 0x68,
+        VectorInstruction::Truncate(FloatVectorShape::F32x4) => 0x69,
+        VectorInstruction::Nearest(FloatVectorShape::F32x4) => 0x6A,
+        VectorInstruction::ShiftLeft(IntegerVectorShape::I8x16) => 0x6B,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I8x16, SignExtension::Signed) => 0x6C,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I8x16, SignExtension::Unsigned) => 0x6D,
+        VectorInstruction::Add(VectorShape::I8x16) => 0x6E,
+        VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Signed) => 0x6F,
+        VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned) => 0x70,
+        VectorInstruction::Subtract(VectorShape::I8x16) => 0x71,
+        VectorInstruction::SubtractSaturate(IntegerVectorShape::I8x16, SignExtension::Signed) => {
+            0x72
+        }
+        VectorInstruction::SubtractSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned) => {
+            0x73
+        }
+        VectorInstruction::Ceiling(FloatVectorShape::F64x2) => 0x74,
+        VectorInstruction::Floor(FloatVectorShape::F64x2) => 0x75,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I8x16, SignExtension::Signed) => 0x76,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned) => {
+            0x77
+        }
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I8x16, SignExtension::Signed) => 0x78,
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned) => {
+            0x79
+        }
+        VectorInstruction::Truncate(FloatVectorShape::F64x2) => 0x7A,
+        VectorInstruction::AverageRounded(IntegerVectorShape::I8x16) => 0x7B,
+        VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I16x8, SignExtension::Signed) => {
+            0x7C
+        }
+        VectorInstruction::ExtendAddPairwise(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ) => 0x7D,
+        VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I32x4, SignExtension::Signed) => {
+            0x7E
+        }
+        VectorInstruction::ExtendAddPairwise(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ) => 0x7F,
+        VectorInstruction::AbsoluteValue(VectorShape::I16x8) => 0x80,
+        VectorInstruction::Negate(VectorShape::I16x8) => 0x81,
+        VectorInstruction::Q15MultiplyRoundSaturate => 0x82,
+        VectorInstruction::AllTrue(IntegerVectorShape::I16x8) => 0x83,
+        VectorInstruction::Bitmask(IntegerVectorShape::I16x8) => 0x84,
+        VectorInstruction::Narrow(IntegerVectorShape::I16x8, SignExtension::Signed) => 0x85,
+        VectorInstruction::Narrow(IntegerVectorShape::I16x8, SignExtension::Unsigned) => 0x86,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0x87,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0x88,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0x89,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0x8A,
+        VectorInstruction::ShiftLeft(IntegerVectorShape::I16x8) => 0x8B,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I16x8, SignExtension::Signed) => 0x8C,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I16x8, SignExtension::Unsigned) => 0x8D,
+        VectorInstruction::Add(VectorShape::I16x8) => 0x8E,
+        VectorInstruction::AddSaturate(IntegerVectorShape::I16x8, SignExtension::Signed) => 0x8F,
+        VectorInstruction::AddSaturate(IntegerVectorShape::I16x8, SignExtension::Unsigned) => 0x90,
+        VectorInstruction::Subtract(VectorShape::I16x8) => 0x91,
+        VectorInstruction::SubtractSaturate(IntegerVectorShape::I16x8, SignExtension::Signed) => {
+            0x92
+        }
+        VectorInstruction::SubtractSaturate(IntegerVectorShape::I16x8, SignExtension::Unsigned) => {
+            0x93
+        }
+        VectorInstruction::Nearest(FloatVectorShape::F64x2) => 0x94,
+        VectorInstruction::Multiply(VectorShape::I16x8) => 0x95,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I16x8, SignExtension::Signed) => 0x96,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned) => {
+            0x97
+        }
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I16x8, SignExtension::Signed) => 0x98,
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned) => {
+            0x99
+        }
+        VectorInstruction::AverageRounded(IntegerVectorShape::I16x8) => 0x9B,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0x9C,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0x9D,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0x9E,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0x9F,
+        VectorInstruction::AbsoluteValue(VectorShape::I32x4) => 0xA0,
+        VectorInstruction::Negate(VectorShape::I32x4) => 0xA1,
+        VectorInstruction::AllTrue(IntegerVectorShape::I32x4) => 0xA3,
+        VectorInstruction::Bitmask(IntegerVectorShape::I32x4) => 0xA4,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0xA7,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0xA8,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0xA9,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0xAA,
+        VectorInstruction::ShiftLeft(IntegerVectorShape::I32x4) => 0xAB,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I32x4, SignExtension::Signed) => 0xAC,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I32x4, SignExtension::Unsigned) => 0xAD,
+        VectorInstruction::Add(VectorShape::I32x4) => 0xAE,
+        VectorInstruction::Subtract(VectorShape::I32x4) => 0xB1,
+        VectorInstruction::Multiply(VectorShape::I32x4) => 0xB5,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I32x4, SignExtension::Signed) => 0xB6,
+        VectorInstruction::MinimumInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned) => {
+            0xB7
+        }
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I32x4, SignExtension::Signed) => 0xB8,
+        VectorInstruction::MaximumInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned) => {
+            0xB9
+        }
+        VectorInstruction::DotProduct => 0xBA,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0xBC,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0xBD,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0xBE,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0xBF,
+        VectorInstruction::AbsoluteValue(VectorShape::I64x2) => 0xC0,
+        VectorInstruction::Negate(VectorShape::I64x2) => 0xC1,
+        VectorInstruction::AllTrue(IntegerVectorShape::I64x2) => 0xC3,
+        VectorInstruction::Bitmask(IntegerVectorShape::I64x2) => 0xC4,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0xC7,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0xC8,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0xC9,
+        VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0xCA,
+        VectorInstruction::ShiftLeft(IntegerVectorShape::I64x2) => 0xCB,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I64x2, SignExtension::Signed) => 0xCC,
+        VectorInstruction::ShiftRight(IntegerVectorShape::I64x2, SignExtension::Unsigned) => 0xCD,
+        VectorInstruction::Add(VectorShape::I64x2) => 0xCE,
+        VectorInstruction::Subtract(VectorShape::I64x2) => 0xD1,
+        VectorInstruction::Multiply(VectorShape::I64x2) => 0xD5,
+        VectorInstruction::Equal(VectorShape::I64x2) => 0xD6,
+        VectorInstruction::NotEqual(VectorShape::I64x2) => 0xD7,
+        VectorInstruction::LessThanInteger(IntegerVectorShape::I64x2, SignExtension::Signed) => {
+            0xD8
+        }
+        VectorInstruction::GreaterThanInteger(IntegerVectorShape::I64x2, SignExtension::Signed) => {
+            0xD9
+        }
+        VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I64x2,
+            SignExtension::Signed,
+        ) => 0xDA,
+        VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I64x2,
+            SignExtension::Signed,
+        ) => 0xDB,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ) => 0xDC,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ) => 0xDD,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ) => 0xDE,
+        VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ) => 0xDF,
+        VectorInstruction::AbsoluteValue(VectorShape::F32x4) => 0xE0,
+        VectorInstruction::Negate(VectorShape::F32x4) => 0xE1,
+        VectorInstruction::SquareRoot(FloatVectorShape::F32x4) => 0xE3,
+        VectorInstruction::Add(VectorShape::F32x4) => 0xE4,
+        VectorInstruction::Subtract(VectorShape::F32x4) => 0xE5,
+        VectorInstruction::Multiply(VectorShape::F32x4) => 0xE6,
+        VectorInstruction::DivideFloat(FloatVectorShape::F32x4) => 0xE7,
+        VectorInstruction::MinimumFloat(FloatVectorShape::F32x4) => 0xE8,
+        VectorInstruction::MaximumFloat(FloatVectorShape::F32x4) => 0xE9,
+        VectorInstruction::PseudoMinimum(FloatVectorShape::F32x4) => 0xEA,
+        VectorInstruction::PseudoMaximum(FloatVectorShape::F32x4) => 0xEB,
+        VectorInstruction::AbsoluteValue(VectorShape::F64x2) => 0xEC,
+        VectorInstruction::Negate(VectorShape::F64x2) => 0xED,
+        VectorInstruction::SquareRoot(FloatVectorShape::F64x2) => 0xEF,
+        VectorInstruction::Add(VectorShape::F64x2) => 0xF0,
+        VectorInstruction::Subtract(VectorShape::F64x2) => 0xF1,
+        VectorInstruction::Multiply(VectorShape::F64x2) => 0xF2,
+        VectorInstruction::DivideFloat(FloatVectorShape::F64x2) => 0xF3,
+        VectorInstruction::MinimumFloat(FloatVectorShape::F64x2) => 0xF4,
+        VectorInstruction::MaximumFloat(FloatVectorShape::F64x2) => 0xF5,
+        VectorInstruction::PseudoMinimum(FloatVectorShape::F64x2) => 0xF6,
+        VectorInstruction::PseudoMaximum(FloatVectorShape::F64x2) => 0xF7,
+        VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Signed,
+        ) => 0xF8,
+        VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Unsigned,
+        ) => 0xF9,
+        VectorInstruction::Convert(FloatVectorShape::F32x4, SignExtension::Signed) => 0xFA,
+        VectorInstruction::Convert(FloatVectorShape::F32x4, SignExtension::Unsigned) => 0xFB,
+        VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Signed,
+        ) => 0xFC,
+        VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Unsigned,
+        ) => 0xFD,
+        VectorInstruction::Convert(FloatVectorShape::F64x2, SignExtension::Signed) => 0xFE,
+        VectorInstruction::Convert(FloatVectorShape::F64x2, SignExtension::Unsigned) => 0xFF,
+        _ => return None,
+    };
+
+    Some(opcode)
+}
+
 /// Emit a reference instruction to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/instructions.html#reference-instructions
@@ -598,7 +1119,7 @@ pub fn emit_reference_instruction<O: Write + ?Sized>(
     match instruction {
         ReferenceInstruction::Null(kind) => {
             bytes += emit_byte(0xD0u8, output)?;
-            bytes += emit_reference_type(*kind, output)?;
+            bytes += emit_heap_type(*kind, output)?;
         }
         ReferenceInstruction::IsNull => {
             bytes += emit_byte(0xD1u8, output)?;
@@ -607,6 +1128,26 @@ pub fn emit_reference_instruction<O: Write + ?Sized>(
             bytes += emit_byte(0xD2u8, output)?;
             bytes += emit_u32(index, output)?;
         }
+        ReferenceInstruction::AsNonNull => {
+            bytes += emit_byte(0xD4u8, output)?;
+        }
+        ReferenceInstruction::Equal => {
+            bytes += emit_byte(0xD3u8, output)?;
+        }
+        ReferenceInstruction::Test(kind) => {
+            let opcode: u32 = if kind.is_nullable() { 21 } else { 20 };
+
+            bytes += emit_byte(0xFBu8, output)?;
+            bytes += emit_u32(opcode, output)?;
+            bytes += emit_heap_type(kind.heap_type(), output)?;
+        }
+        ReferenceInstruction::Cast(kind) => {
+            let opcode: u32 = if kind.is_nullable() { 23 } else { 22 };
+
+            bytes += emit_byte(0xFBu8, output)?;
+            bytes += emit_u32(opcode, output)?;
+            bytes += emit_heap_type(kind.heap_type(), output)?;
+        }
     }
 
     Ok(bytes)
@@ -829,41 +1370,290 @@ pub fn emit_memory_instruction<O: Write + ?Sized>(
             bytes += emit_byte(0x3Eu8, output)?;
             bytes += emit_memory_argument(memory_argument, output)?;
         }
-        MemoryInstruction::Size => {
+        MemoryInstruction::Size(memory) => {
             bytes += emit_byte(0x3Fu8, output)?;
-            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_u32(memory, output)?;
         }
-        MemoryInstruction::Grow => {
+        MemoryInstruction::Grow(memory) => {
             bytes += emit_byte(0x40u8, output)?;
-            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_u32(memory, output)?;
         }
-        MemoryInstruction::Init(index) => {
+        MemoryInstruction::Init(index, memory) => {
             bytes += emit_byte(0xFCu8, output)?;
             bytes += emit_u32(8u32, output)?;
             bytes += emit_u32(index, output)?;
-            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_u32(memory, output)?;
         }
         MemoryInstruction::DataDrop(index) => {
             bytes += emit_byte(0xFCu8, output)?;
             bytes += emit_u32(9u32, output)?;
             bytes += emit_u32(index, output)?;
         }
-        MemoryInstruction::Copy => {
+        MemoryInstruction::Copy(destination, source) => {
             bytes += emit_byte(0xFCu8, output)?;
             bytes += emit_u32(10u32, output)?;
-            bytes += emit_byte(0x00u8, output)?;
-            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_u32(destination, output)?;
+            bytes += emit_u32(source, output)?;
         }
-        MemoryInstruction::Fill => {
+        MemoryInstruction::Fill(memory) => {
             bytes += emit_byte(0xFCu8, output)?;
             bytes += emit_u32(11u32, output)?;
+            bytes += emit_u32(memory, output)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Emit an aggregate instruction to the output.
+///
+/// See https://webassembly.github.io/gc/core/binary/instructions.html#aggregate-instructions
+pub fn emit_aggregate_instruction<O: Write + ?Sized>(
+    instruction: &AggregateInstruction,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_byte(0xFBu8, output)?;
+
+    match instruction {
+        AggregateInstruction::StructNew(kind) => {
+            bytes += emit_u32(0u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::StructNewDefault(kind) => {
+            bytes += emit_u32(1u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::StructGet(extension, kind, field) => {
+            let opcode: u32 = match extension {
+                None => 2,
+                Some(SignExtension::Signed) => 3,
+                Some(SignExtension::Unsigned) => 4,
+            };
+
+            bytes += emit_u32(opcode, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(field, output)?;
+        }
+        AggregateInstruction::StructSet(kind, field) => {
+            bytes += emit_u32(5u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(field, output)?;
+        }
+        AggregateInstruction::ArrayNew(kind) => {
+            bytes += emit_u32(6u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::ArrayNewDefault(kind) => {
+            bytes += emit_u32(7u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::ArrayNewFixed(kind, size) => {
+            bytes += emit_u32(8u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(size, output)?;
+        }
+        AggregateInstruction::ArrayNewData(kind, data) => {
+            bytes += emit_u32(9u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(data, output)?;
+        }
+        AggregateInstruction::ArrayNewElement(kind, element) => {
+            bytes += emit_u32(10u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(element, output)?;
+        }
+        AggregateInstruction::ArrayGet(extension, kind) => {
+            let opcode: u32 = match extension {
+                None => 11,
+                Some(SignExtension::Signed) => 12,
+                Some(SignExtension::Unsigned) => 13,
+            };
+
+            bytes += emit_u32(opcode, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::ArraySet(kind) => {
+            bytes += emit_u32(14u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::ArrayLength => {
+            bytes += emit_u32(15u32, output)?;
+        }
+        AggregateInstruction::ArrayFill(kind) => {
+            bytes += emit_u32(16u32, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        AggregateInstruction::ArrayCopy(destination, source) => {
+            bytes += emit_u32(17u32, output)?;
+            bytes += emit_u32(destination, output)?;
+            bytes += emit_u32(source, output)?;
+        }
+        AggregateInstruction::ArrayInitData(kind, data) => {
+            bytes += emit_u32(18u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(data, output)?;
+        }
+        AggregateInstruction::ArrayInitElement(kind, element) => {
+            bytes += emit_u32(19u32, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(element, output)?;
+        }
+        AggregateInstruction::AnyConvertExternal => {
+            bytes += emit_u32(26u32, output)?;
+        }
+        AggregateInstruction::ExternalConvertAny => {
+            bytes += emit_u32(27u32, output)?;
+        }
+        AggregateInstruction::ReferenceI31 => {
+            bytes += emit_u32(28u32, output)?;
+        }
+        AggregateInstruction::I31Get(SignExtension::Signed) => {
+            bytes += emit_u32(29u32, output)?;
+        }
+        AggregateInstruction::I31Get(SignExtension::Unsigned) => {
+            bytes += emit_u32(30u32, output)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Emit an atomic memory instruction to the output.
+///
+/// See https://webassembly.github.io/threads/core/binary/instructions.html#atomic-memory-instructions
+pub fn emit_atomic_instruction<O: Write + ?Sized>(
+    instruction: &AtomicInstruction,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_byte(0xFEu8, output)?;
+
+    match instruction {
+        AtomicInstruction::Notify(memory_argument) => {
+            bytes += emit_u32(0x00u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Wait(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x01u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Wait(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x02u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Fence => {
+            bytes += emit_u32(0x03u32, output)?;
             bytes += emit_byte(0x00u8, output)?;
         }
+        AtomicInstruction::Load(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x10u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x11u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load8(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x12u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load16(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x13u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load8(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x14u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load16(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x15u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Load32(memory_argument) => {
+            bytes += emit_u32(0x16u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x17u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x18u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store8(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x19u32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store16(IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(0x1Au32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store8(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x1Bu32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store16(IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(0x1Cu32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::Store32(memory_argument) => {
+            bytes += emit_u32(0x1Du32, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite(operation, IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation), output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite(operation, IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 1, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite8(operation, IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 2, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite16(operation, IntegerType::I32, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 3, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite8(operation, IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 4, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite16(operation, IntegerType::I64, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 5, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
+        AtomicInstruction::ReadModifyWrite32(operation, memory_argument) => {
+            bytes += emit_u32(atomic_operation_opcode(operation) + 6, output)?;
+            bytes += emit_memory_argument(memory_argument, output)?;
+        }
     }
 
     Ok(bytes)
 }
 
+/// The opcode of the first (i.e., 32-bit) read-modify-write instruction for the given operation.
+/// The read-modify-write instructions are grouped by operation,
+/// with 7 consecutive opcodes per operation.
+fn atomic_operation_opcode(operation: &AtomicOperation) -> u32 {
+    let index = match operation {
+        AtomicOperation::Add => 0,
+        AtomicOperation::Subtract => 1,
+        AtomicOperation::And => 2,
+        AtomicOperation::Or => 3,
+        AtomicOperation::Xor => 4,
+        AtomicOperation::Exchange => 5,
+        AtomicOperation::CompareExchange => 6,
+    };
+
+    0x1E + 7 * index
+}
+
 /// Emit a control instruction to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/instructions.html#control-instructions
@@ -922,16 +1712,126 @@ fn emit_control_instruction<O: Write + ?Sized>(
             bytes += emit_byte(0x10u8, output)?;
             bytes += emit_u32(index, output)?;
         }
-        ControlInstruction::CallIndirect(table, kind) => {
+        ControlInstruction::CallIndirect(kind, table) => {
             bytes += emit_byte(0x11u8, output)?;
             bytes += emit_u32(kind, output)?;
             bytes += emit_u32(table, output)?;
         }
+        ControlInstruction::ReturnCall(index) => {
+            bytes += emit_byte(0x12u8, output)?;
+            bytes += emit_u32(index, output)?;
+        }
+        ControlInstruction::ReturnCallIndirect(kind, table) => {
+            bytes += emit_byte(0x13u8, output)?;
+            bytes += emit_u32(kind, output)?;
+            bytes += emit_u32(table, output)?;
+        }
+        ControlInstruction::CallRef(kind) => {
+            bytes += emit_byte(0x14u8, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        ControlInstruction::ReturnCallRef(kind) => {
+            bytes += emit_byte(0x15u8, output)?;
+            bytes += emit_u32(kind, output)?;
+        }
+        ControlInstruction::BranchOnNull(index) => {
+            bytes += emit_byte(0xD5u8, output)?;
+            bytes += emit_u32(index, output)?;
+        }
+        ControlInstruction::BranchOnNonNull(index) => {
+            bytes += emit_byte(0xD6u8, output)?;
+            bytes += emit_u32(index, output)?;
+        }
+        ControlInstruction::BranchOnCast(index, from, to) => {
+            bytes += emit_byte(0xFBu8, output)?;
+            bytes += emit_u32(24u32, output)?;
+            bytes += emit_cast(*index, *from, *to, output)?;
+        }
+        ControlInstruction::BranchOnCastFail(index, from, to) => {
+            bytes += emit_byte(0xFBu8, output)?;
+            bytes += emit_u32(25u32, output)?;
+            bytes += emit_cast(*index, *from, *to, output)?;
+        }
+        ControlInstruction::Throw(index) => {
+            bytes += emit_byte(0x08u8, output)?;
+            bytes += emit_u32(index, output)?;
+        }
+        ControlInstruction::ThrowRef => {
+            bytes += emit_byte(0x0Au8, output)?;
+        }
+        ControlInstruction::TryTable(kind, catches, expression) => {
+            bytes += emit_byte(0x1Fu8, output)?;
+            bytes += emit_block_type(kind, output)?;
+            bytes += emit_vector(catches, output, emit_catch_clause)?;
+            bytes += emit_expression(expression, output)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Emit a catch clause of a try table instruction to the output.
+///
+/// See https://webassembly.github.io/exception-handling/core/binary/instructions.html#control-instructions
+pub fn emit_catch_clause<O: Write + ?Sized>(
+    clause: &CatchClause,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    match clause {
+        CatchClause::Catch(tag, label) => {
+            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_u32(tag, output)?;
+            bytes += emit_u32(label, output)?;
+        }
+        CatchClause::CatchReference(tag, label) => {
+            bytes += emit_byte(0x01u8, output)?;
+            bytes += emit_u32(tag, output)?;
+            bytes += emit_u32(label, output)?;
+        }
+        CatchClause::CatchAll(label) => {
+            bytes += emit_byte(0x02u8, output)?;
+            bytes += emit_u32(label, output)?;
+        }
+        CatchClause::CatchAllReference(label) => {
+            bytes += emit_byte(0x03u8, output)?;
+            bytes += emit_u32(label, output)?;
+        }
     }
 
     Ok(bytes)
 }
 
+/// Emit the immediates of a branch on cast instruction to the output.
+/// The nullability of both reference types is encoded in a leading flags byte.
+///
+/// See https://webassembly.github.io/gc/core/binary/instructions.html#control-instructions
+fn emit_cast<O: Write + ?Sized>(
+    index: LabelIndex,
+    from: ReferenceType,
+    to: ReferenceType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+    let mut flags = 0x00u8;
+
+    if from.is_nullable() {
+        flags |= 0x01;
+    }
+
+    if to.is_nullable() {
+        flags |= 0x02;
+    }
+
+    bytes += emit_byte(flags, output)?;
+    bytes += emit_u32(index, output)?;
+    bytes += emit_heap_type(from.heap_type(), output)?;
+    bytes += emit_heap_type(to.heap_type(), output)?;
+
+    Ok(bytes)
+}
+
 /// Emit a block type to the output.
 ///
 /// See  https://webassembly.github.io/spec/core/binary/instructions.html#control-instructions
@@ -947,16 +1847,26 @@ pub fn emit_block_type<O: Write + ?Sized>(
 }
 
 /// Emit a memory argument to the output.
+/// Memory arguments for memories other than memory 0 set bit 6 of the alignment,
+/// followed by the memory index.
 ///
 /// See https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions
+///
+/// See https://webassembly.github.io/multi-memory/core/binary/instructions.html#memory-instructions
 pub fn emit_memory_argument<O: Write + ?Sized>(
     argument: &MemoryArgument,
     output: &mut O,
 ) -> Result<usize, EmitError> {
     let mut bytes = 0;
 
-    bytes += emit_u32(argument.align(), output)?;
-    bytes += emit_u32(argument.offset(), output)?;
+    if argument.memory() == 0 {
+        bytes += emit_u32(argument.align(), output)?;
+    } else {
+        bytes += emit_u32(argument.align() | (1 << 6), output)?;
+        bytes += emit_u32(argument.memory(), output)?;
+    }
+
+    bytes += emit_u64(argument.offset(), output)?;
 
     Ok(bytes)
 }
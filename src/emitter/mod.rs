@@ -1,14 +1,19 @@
-//! Emit WebAssembly binary format.
+//! Emit WebAssembly binary and text formats.
 
 mod errors;
 mod instruction;
 mod module;
+mod names;
 mod sections;
+mod text;
 mod types;
 mod values;
 
-use crate::model::Module;
-use sections::emit_module;
+pub use errors::EmitError;
+pub use text::{emit_text, TextOptions, TextStyle};
+
+use crate::model::{BorrowedModule, Module};
+use sections::{emit_borrowed_module, emit_module};
 use std::fmt::Debug;
 use std::io::Write;
 
@@ -27,10 +32,52 @@ use std::io::Write;
 ///
 /// assert_eq!(buffer, vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]);
 /// ```
-pub fn emit_binary<O: Write>(module: &Module, output: &mut O) -> Result<usize, errors::EmitError> {
+pub fn emit_binary<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
     emit_module(module, output)
 }
 
+/// Emits a binary representation of a borrowed WebAssembly module to a `Write` output,
+/// writing its import and export names, data segments and custom sections directly from the borrowed input.
+/// The output is identical to that of `emit_binary` for the owned form of the module.
+///
+/// See <https://webassembly.github.io/spec/core/binary/index.html>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{
+///     emit_binary, emit_binary_borrowed, BorrowedCustom, BorrowedData, BorrowedExport, BorrowedImport,
+///     BorrowedModule, ExportDescription, ImportDescription, Module, ModuleSection,
+/// };
+///
+/// let mut builder = Module::builder();
+///
+/// builder.set_data_count(Some(1));
+///
+/// let initializer = [42];
+/// let contents = [0, 1];
+/// let module = BorrowedModule::new(
+///     builder.build(),
+///     Some(vec![BorrowedImport::new("env", "log", ImportDescription::Function(0))]),
+///     Some(vec![BorrowedExport::new("log", ExportDescription::Function(0))]),
+///     Some(vec![BorrowedData::passive(&initializer)]),
+///     vec![(ModuleSection::Data, BorrowedCustom::new("version", &contents))],
+/// );
+///
+/// let mut borrowed = Vec::new();
+/// let mut owned = Vec::new();
+///
+/// emit_binary_borrowed(&module, &mut borrowed).unwrap();
+/// emit_binary(&module.into_owned(), &mut owned).unwrap();
+///
+/// assert_eq!(borrowed, owned);
+/// ```
+pub fn emit_binary_borrowed<O: Write>(
+    module: &BorrowedModule,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    emit_borrowed_module(module, output)
+}
+
 /// Counts the number of bytes written, but does else nothing with the bytes.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 struct CountingWrite {
@@ -76,12 +123,13 @@ impl Write for CountingWrite {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::emitter::errors::EmitError;
     use crate::model::{
-        ControlInstruction, Custom, Data, DataMode, Element, ElementInitializer, ElementMode,
-        Export, ExportDescription, Expression, Function, FunctionType, Global, GlobalType, Import,
-        ImportDescription, Instruction, Limit, Memory, MemoryType, Module, ModuleSection, Name,
-        NumericInstruction, ReferenceType, ResultType, Start, Table, TableType, ValueType,
+        BlockType, ControlInstruction, Custom, Data, DataMode, Element, ElementInitializer,
+        ElementMode, Export, ExportDescription, Expression, Function, FunctionType, Global,
+        GlobalType, Import, ImportDescription, IndexType, Instruction, Limit, Memory, MemoryType,
+        Module, ModuleSection, Name, NameSection, NumericInstruction, ReferenceInstruction,
+        ReferenceType, ResultType, Start, Table, TableType, ValueType, VectorInstruction,
+        VectorShape,
     };
     use crate::parser::parse_binary;
     use wasmtime::{Engine, Extern, Func, Instance, Store};
@@ -89,7 +137,7 @@ mod tests {
     fn validate(target: &Module) -> Result<(), EmitError> {
         let mut bytes = Vec::new();
 
-        emit_binary(&target, &mut bytes)?;
+        emit_binary(target, &mut bytes)?;
 
         let parsed = parse_binary(bytes.as_slice())
             .map_err(|_| EmitError::IO(std::io::Error::from(std::io::ErrorKind::NotFound)))?;
@@ -328,6 +376,29 @@ mod tests {
         validate(&module.build()).unwrap();
     }
 
+    #[test]
+    fn element_kind() {
+        let binary = wat::parse_str(
+            "(module
+                (table 1 funcref)
+                (table 1 funcref)
+                (func $f)
+                (elem (table 1) (i32.const 0) func $f)
+                (elem declare func $f))",
+        )
+        .unwrap();
+        let module = parse_binary(&binary).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert!(bytes
+            .windows(15)
+            .any(|section| section
+                == b"\x09\x0D\x02\x02\x01\x41\x00\x0B\x00\x01\x00\x03\x00\x01\x00"));
+    }
+
     #[test]
     fn valid_module_table_only() {
         let mut module = Module::builder();
@@ -397,6 +468,892 @@ mod tests {
         validate(&module.build()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "text")]
+    fn vector_instructions() {
+        let text = r#"
+        (module
+            (memory 1)
+            (func
+                v128.load offset=16
+                v128.load8x8_s offset=16
+                v128.load8x8_u offset=16
+                v128.load16x4_s offset=16
+                v128.load16x4_u offset=16
+                v128.load32x2_s offset=16
+                v128.load32x2_u offset=16
+                v128.load8_splat offset=16
+                v128.load16_splat offset=16
+                v128.load32_splat offset=16
+                v128.load64_splat offset=16
+                v128.store offset=16
+                v128.const i64x2 42 -1
+                i8x16.shuffle 15 14 13 12 11 10 9 8 7 6 5 4 3 2 1 0
+                i8x16.swizzle
+                i8x16.splat
+                i16x8.splat
+                i32x4.splat
+                i64x2.splat
+                f32x4.splat
+                f64x2.splat
+                i8x16.extract_lane_s 1
+                i8x16.extract_lane_u 1
+                i8x16.replace_lane 1
+                i16x8.extract_lane_s 1
+                i16x8.extract_lane_u 1
+                i16x8.replace_lane 1
+                i32x4.extract_lane 1
+                i32x4.replace_lane 1
+                i64x2.extract_lane 1
+                i64x2.replace_lane 1
+                f32x4.extract_lane 1
+                f32x4.replace_lane 1
+                f64x2.extract_lane 1
+                f64x2.replace_lane 1
+                i8x16.eq
+                i8x16.ne
+                i8x16.lt_s
+                i8x16.lt_u
+                i8x16.gt_s
+                i8x16.gt_u
+                i8x16.le_s
+                i8x16.le_u
+                i8x16.ge_s
+                i8x16.ge_u
+                i16x8.eq
+                i16x8.ne
+                i16x8.lt_s
+                i16x8.lt_u
+                i16x8.gt_s
+                i16x8.gt_u
+                i16x8.le_s
+                i16x8.le_u
+                i16x8.ge_s
+                i16x8.ge_u
+                i32x4.eq
+                i32x4.ne
+                i32x4.lt_s
+                i32x4.lt_u
+                i32x4.gt_s
+                i32x4.gt_u
+                i32x4.le_s
+                i32x4.le_u
+                i32x4.ge_s
+                i32x4.ge_u
+                f32x4.eq
+                f32x4.ne
+                f32x4.lt
+                f32x4.gt
+                f32x4.le
+                f32x4.ge
+                f64x2.eq
+                f64x2.ne
+                f64x2.lt
+                f64x2.gt
+                f64x2.le
+                f64x2.ge
+                v128.not
+                v128.and
+                v128.andnot
+                v128.or
+                v128.xor
+                v128.bitselect
+                v128.any_true
+                v128.load8_lane offset=8 1
+                v128.load16_lane offset=8 1
+                v128.load32_lane offset=8 1
+                v128.load64_lane offset=8 1
+                v128.store8_lane offset=8 1
+                v128.store16_lane offset=8 1
+                v128.store32_lane offset=8 1
+                v128.store64_lane offset=8 1
+                v128.load32_zero offset=16
+                v128.load64_zero offset=16
+                f32x4.demote_f64x2_zero
+                f64x2.promote_low_f32x4
+                i8x16.abs
+                i8x16.neg
+                i8x16.popcnt
+                i8x16.all_true
+                i8x16.bitmask
+                i8x16.narrow_i16x8_s
+                i8x16.narrow_i16x8_u
+                f32x4.ceil
+                f32x4.floor
+                f32x4.trunc
+                f32x4.nearest
+                i8x16.shl
+                i8x16.shr_s
+                i8x16.shr_u
+                i8x16.add
+                i8x16.add_sat_s
+                i8x16.add_sat_u
+                i8x16.sub
+                i8x16.sub_sat_s
+                i8x16.sub_sat_u
+                f64x2.ceil
+                f64x2.floor
+                i8x16.min_s
+                i8x16.min_u
+                i8x16.max_s
+                i8x16.max_u
+                f64x2.trunc
+                i8x16.avgr_u
+                i16x8.extadd_pairwise_i8x16_s
+                i16x8.extadd_pairwise_i8x16_u
+                i32x4.extadd_pairwise_i16x8_s
+                i32x4.extadd_pairwise_i16x8_u
+                i16x8.abs
+                i16x8.neg
+                i16x8.q15mulr_sat_s
+                i16x8.all_true
+                i16x8.bitmask
+                i16x8.narrow_i32x4_s
+                i16x8.narrow_i32x4_u
+                i16x8.extend_low_i8x16_s
+                i16x8.extend_high_i8x16_s
+                i16x8.extend_low_i8x16_u
+                i16x8.extend_high_i8x16_u
+                i16x8.shl
+                i16x8.shr_s
+                i16x8.shr_u
+                i16x8.add
+                i16x8.add_sat_s
+                i16x8.add_sat_u
+                i16x8.sub
+                i16x8.sub_sat_s
+                i16x8.sub_sat_u
+                f64x2.nearest
+                i16x8.mul
+                i16x8.min_s
+                i16x8.min_u
+                i16x8.max_s
+                i16x8.max_u
+                i16x8.avgr_u
+                i16x8.extmul_low_i8x16_s
+                i16x8.extmul_high_i8x16_s
+                i16x8.extmul_low_i8x16_u
+                i16x8.extmul_high_i8x16_u
+                i32x4.abs
+                i32x4.neg
+                i32x4.all_true
+                i32x4.bitmask
+                i32x4.extend_low_i16x8_s
+                i32x4.extend_high_i16x8_s
+                i32x4.extend_low_i16x8_u
+                i32x4.extend_high_i16x8_u
+                i32x4.shl
+                i32x4.shr_s
+                i32x4.shr_u
+                i32x4.add
+                i32x4.sub
+                i32x4.mul
+                i32x4.min_s
+                i32x4.min_u
+                i32x4.max_s
+                i32x4.max_u
+                i32x4.dot_i16x8_s
+                i32x4.extmul_low_i16x8_s
+                i32x4.extmul_high_i16x8_s
+                i32x4.extmul_low_i16x8_u
+                i32x4.extmul_high_i16x8_u
+                i64x2.abs
+                i64x2.neg
+                i64x2.all_true
+                i64x2.bitmask
+                i64x2.extend_low_i32x4_s
+                i64x2.extend_high_i32x4_s
+                i64x2.extend_low_i32x4_u
+                i64x2.extend_high_i32x4_u
+                i64x2.shl
+                i64x2.shr_s
+                i64x2.shr_u
+                i64x2.add
+                i64x2.sub
+                i64x2.mul
+                i64x2.eq
+                i64x2.ne
+                i64x2.lt_s
+                i64x2.gt_s
+                i64x2.le_s
+                i64x2.ge_s
+                i64x2.extmul_low_i32x4_s
+                i64x2.extmul_high_i32x4_s
+                i64x2.extmul_low_i32x4_u
+                i64x2.extmul_high_i32x4_u
+                f32x4.abs
+                f32x4.neg
+                f32x4.sqrt
+                f32x4.add
+                f32x4.sub
+                f32x4.mul
+                f32x4.div
+                f32x4.min
+                f32x4.max
+                f32x4.pmin
+                f32x4.pmax
+                f64x2.abs
+                f64x2.neg
+                f64x2.sqrt
+                f64x2.add
+                f64x2.sub
+                f64x2.mul
+                f64x2.div
+                f64x2.min
+                f64x2.max
+                f64x2.pmin
+                f64x2.pmax
+                i32x4.trunc_sat_f32x4_s
+                i32x4.trunc_sat_f32x4_u
+                f32x4.convert_i32x4_s
+                f32x4.convert_i32x4_u
+                i32x4.trunc_sat_f64x2_s_zero
+                i32x4.trunc_sat_f64x2_u_zero
+                f64x2.convert_low_i32x4_s
+                f64x2.convert_low_i32x4_u
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn atomic_instructions() {
+        let text = r#"
+        (module
+            (memory 1 2 shared)
+            (func
+                memory.atomic.notify offset=4
+                memory.atomic.wait32 offset=4
+                memory.atomic.wait64 offset=4
+                atomic.fence
+                i32.atomic.load offset=4
+                i64.atomic.load offset=4
+                i32.atomic.load8_u offset=4
+                i32.atomic.load16_u offset=4
+                i64.atomic.load8_u offset=4
+                i64.atomic.load16_u offset=4
+                i64.atomic.load32_u offset=4
+                i32.atomic.store offset=4
+                i64.atomic.store offset=4
+                i32.atomic.store8 offset=4
+                i32.atomic.store16 offset=4
+                i64.atomic.store8 offset=4
+                i64.atomic.store16 offset=4
+                i64.atomic.store32 offset=4
+                i32.atomic.rmw.add offset=4
+                i64.atomic.rmw.add offset=4
+                i32.atomic.rmw8.add_u offset=4
+                i32.atomic.rmw16.add_u offset=4
+                i64.atomic.rmw8.add_u offset=4
+                i64.atomic.rmw16.add_u offset=4
+                i64.atomic.rmw32.add_u offset=4
+                i32.atomic.rmw.sub offset=4
+                i64.atomic.rmw.sub offset=4
+                i32.atomic.rmw8.sub_u offset=4
+                i32.atomic.rmw16.sub_u offset=4
+                i64.atomic.rmw8.sub_u offset=4
+                i64.atomic.rmw16.sub_u offset=4
+                i64.atomic.rmw32.sub_u offset=4
+                i32.atomic.rmw.and offset=4
+                i64.atomic.rmw.and offset=4
+                i32.atomic.rmw8.and_u offset=4
+                i32.atomic.rmw16.and_u offset=4
+                i64.atomic.rmw8.and_u offset=4
+                i64.atomic.rmw16.and_u offset=4
+                i64.atomic.rmw32.and_u offset=4
+                i32.atomic.rmw.or offset=4
+                i64.atomic.rmw.or offset=4
+                i32.atomic.rmw8.or_u offset=4
+                i32.atomic.rmw16.or_u offset=4
+                i64.atomic.rmw8.or_u offset=4
+                i64.atomic.rmw16.or_u offset=4
+                i64.atomic.rmw32.or_u offset=4
+                i32.atomic.rmw.xor offset=4
+                i64.atomic.rmw.xor offset=4
+                i32.atomic.rmw8.xor_u offset=4
+                i32.atomic.rmw16.xor_u offset=4
+                i64.atomic.rmw8.xor_u offset=4
+                i64.atomic.rmw16.xor_u offset=4
+                i64.atomic.rmw32.xor_u offset=4
+                i32.atomic.rmw.xchg offset=4
+                i64.atomic.rmw.xchg offset=4
+                i32.atomic.rmw8.xchg_u offset=4
+                i32.atomic.rmw16.xchg_u offset=4
+                i64.atomic.rmw8.xchg_u offset=4
+                i64.atomic.rmw16.xchg_u offset=4
+                i64.atomic.rmw32.xchg_u offset=4
+                i32.atomic.rmw.cmpxchg offset=4
+                i64.atomic.rmw.cmpxchg offset=4
+                i32.atomic.rmw8.cmpxchg_u offset=4
+                i32.atomic.rmw16.cmpxchg_u offset=4
+                i64.atomic.rmw8.cmpxchg_u offset=4
+                i64.atomic.rmw16.cmpxchg_u offset=4
+                i64.atomic.rmw32.cmpxchg_u offset=4
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert!(module.memories().unwrap()[0].kind().is_shared());
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn multiple_memories() {
+        let text = r#"
+        (module
+            (memory $a 1)
+            (memory $b 1 2)
+            (data $d "hello")
+            (func
+                i32.const 0
+                i32.load $b offset=4
+                i64.load8_u $a offset=8
+                drop
+                drop
+                memory.size $b
+                memory.grow $b
+                drop
+                i32.const 0
+                i32.const 0
+                i32.const 5
+                memory.copy $a $b
+                i32.const 0
+                i32.const 0
+                i32.const 5
+                memory.init $b $d
+                i32.const 0
+                i32.const 0
+                i32.const 5
+                memory.fill $b
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.memories().unwrap().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn memory64() {
+        let text = r#"
+        (module
+            (memory $a i64 1)
+            (memory $b i64 1 65536 shared)
+            (table i64 1 funcref)
+            (func
+                i64.const 0
+                i32.load $a offset=4294967296
+                drop
+                i64.const 0
+                i64.load8_u $b offset=8
+                drop
+                memory.size $b
+                drop
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(
+            module.memories().unwrap()[1].kind().index_type(),
+            IndexType::I64
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn exception_handling() {
+        use crate::model::{Tag, TagType};
+
+        let text = r#"
+        (module
+            (import "env" "error" (tag $error (param i32)))
+            (tag $other)
+            (func (param exnref) (result i32)
+                block $outer (result i32 exnref)
+                    block $any
+                        block $any_ref (result exnref)
+                            try_table (catch $error 2) (catch_ref $other 1) (catch_all 2) (catch_all_ref 1)
+                                i32.const 42
+                                throw $error
+                            end
+                            local.get 0
+                            throw_ref
+                        end
+                        throw_ref
+                    end
+                    unreachable
+                end
+                drop
+            )
+            (export "other" (tag $other))
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.tags(), Some(&[Tag::new(TagType::new(1))][..]));
+    }
+
+    #[test]
+    fn call_indirect_operand_order() {
+        let binary = wat::parse_str(
+            "(module
+                (type (func))
+                (type $nullary (func (result i32)))
+                (table 1 funcref)
+                (func (type $nullary)
+                    (call_indirect (type $nullary) (i32.const 0))))",
+        )
+        .unwrap();
+        let module = parse_binary(&binary).unwrap();
+        let mut bytes = Vec::new();
+
+        assert_eq!(
+            module.functions().unwrap()[0].body().instructions()[1],
+            ControlInstruction::CallIndirect(1, 0).into()
+        );
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert!(bytes.windows(3).any(|call| call == b"\x11\x01\x00"));
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn tail_calls() {
+        let text = r#"
+        (module
+            (type $unary (func (param i32) (result i32)))
+            (type $nullary (func (result i32)))
+            (table $first 1 funcref)
+            (table $second 1 funcref)
+            (func $identity (type $unary)
+                local.get 0
+            )
+            (func (type $nullary)
+                i32.const 7
+                i32.const 0
+                call_indirect $second (type $unary)
+                i32.const 0
+                return_call_indirect $second (type $unary)
+            )
+            (func (type $nullary)
+                i32.const 7
+                return_call $identity
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn typed_function_references() {
+        let text = r#"
+        (module
+            (type $unary (func (param i32) (result i32)))
+            (table $functions 1 (ref null $unary))
+            (global $null (ref null $unary) (ref.null $unary))
+            (global $extern externref (ref.null extern))
+            (func $identity (type $unary)
+                local.get 0
+            )
+            (func (param (ref null $unary)) (result i32)
+                (local $callee (ref $unary))
+                block $null
+                    local.get 0
+                    br_on_null $null
+                    local.set $callee
+                    i32.const 1
+                    local.get $callee
+                    call_ref $unary
+                    return
+                end
+                block $non_null (result (ref $unary))
+                    global.get $null
+                    br_on_non_null $non_null
+                    ref.func $identity
+                    ref.as_non_null
+                end
+                drop
+                i32.const 2
+                ref.func $identity
+                return_call_ref $unary
+            )
+            (elem declare func $identity)
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn garbage_collection() {
+        let text = r#"
+        (module
+            (rec
+                (type $node (sub (struct (field $value i32) (field $next (mut (ref null $node))))))
+                (type $leaf (sub final $node (struct (field i32) (field (mut (ref null $node))) (field i8))))
+            )
+            (type $bytes (array (mut i8)))
+            (type $objects (array (mut anyref)))
+            (type $callback (func (param (ref null $node)) (result i32)))
+            (data $text "hello")
+            (elem $refs func $visit)
+            (global $empty (ref null none) (ref.null none))
+            (func $visit (type $callback)
+                local.get 0
+                struct.get $node $value
+            )
+            (func (param $any anyref) (param $extern externref) (result i32)
+                (local $node (ref null $node))
+                (local $bytes (ref null $bytes))
+                i32.const 1
+                ref.null $node
+                struct.new $node
+                local.tee $node
+                i32.const 2
+                struct.set $node $value
+                struct.new_default $node
+                drop
+                i32.const 3
+                i32.const 4
+                ref.null $node
+                i32.const 5
+                struct.new $leaf
+                struct.get_s $leaf 2
+                drop
+                drop
+                i32.const 0
+                i32.const 4
+                array.new $bytes
+                local.tee $bytes
+                i32.const 0
+                array.get_u $bytes
+                drop
+                local.get $bytes
+                i32.const 0
+                i32.const 7
+                array.set $bytes
+                local.get $bytes
+                array.len
+                drop
+                local.get $bytes
+                i32.const 0
+                i32.const 1
+                i32.const 2
+                array.fill $bytes
+                local.get $bytes
+                i32.const 0
+                local.get $bytes
+                i32.const 1
+                i32.const 2
+                array.copy $bytes $bytes
+                local.get $bytes
+                i32.const 0
+                i32.const 0
+                i32.const 1
+                array.init_data $bytes $text
+                i32.const 2
+                array.new_default $objects
+                drop
+                i32.const 1
+                ref.i31
+                i32.const 2
+                ref.i31
+                array.new_fixed $objects 2
+                drop
+                i32.const 0
+                i32.const 1
+                array.new_data $bytes $text
+                drop
+                i32.const 0
+                i32.const 1
+                array.new_elem $objects $refs
+                array.get $objects
+                drop
+                global.get $empty
+                local.get $node
+                ref.eq
+                drop
+                local.get $any
+                ref.test (ref i31)
+                drop
+                local.get $any
+                ref.test (ref null $node)
+                drop
+                local.get $any
+                ref.cast (ref null $node)
+                drop
+                block $cast (result (ref i31))
+                    local.get $any
+                    br_on_cast $cast anyref (ref i31)
+                    drop
+                    block $fail (result anyref)
+                        local.get $any
+                        br_on_cast_fail $fail anyref (ref $node)
+                        drop
+                        local.get $extern
+                        any.convert_extern
+                        extern.convert_any
+                        drop
+                        i32.const 1
+                        ref.i31
+                    end
+                    ref.cast (ref i31)
+                end
+                i31.get_s
+                i32.const 1
+                ref.i31
+                i31.get_u
+                i32.add
+            )
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.types().unwrap().len(), 5);
+        assert!(!module.sub_type(0).unwrap().is_final());
+        assert_eq!(module.sub_type(1).unwrap().supertypes(), &[0]);
+        assert!(module.function_type(1).is_none());
+        assert!(module.function_type(4).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn name_section() {
+        let text = r#"
+        (module $app
+            (type $point (struct (field $x i32) (field $y i32)))
+            (import "env" "log" (func $log (param i32)))
+            (table $table 1 funcref)
+            (memory $memory 1)
+            (global $counter (mut i32) (i32.const 0))
+            (tag $oops)
+            (func $main (param $n i32) (local $i i32)
+                (block $done
+                    (loop $next
+                        (br_if $done (local.get $n))
+                        (br $next))))
+            (elem $functions (i32.const 0) func $main)
+            (data $greeting (i32.const 0) "hello")
+        )
+        "#;
+        let binary = wat::parse_str(text).unwrap();
+        let module = parse_binary(binary.as_slice()).unwrap();
+        let names = module.names().unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.custom_sections_at(ModuleSection::Data), None);
+        assert_eq!(names.module(), Some(&Name::from("app")));
+        assert_eq!(names.function(0), Some(&Name::from("log")));
+        assert_eq!(names.function(1), Some(&Name::from("main")));
+        assert_eq!(names.local(1, 0), Some(&Name::from("n")));
+        assert_eq!(names.local(1, 1), Some(&Name::from("i")));
+        assert_eq!(names.types().get(&0), Some(&Name::from("point")));
+        assert_eq!(names.tables().get(&0), Some(&Name::from("table")));
+        assert_eq!(names.memories().get(&0), Some(&Name::from("memory")));
+        assert_eq!(names.globals().get(&0), Some(&Name::from("counter")));
+        assert_eq!(names.elements().get(&0), Some(&Name::from("functions")));
+        assert_eq!(names.data().get(&0), Some(&Name::from("greeting")));
+        assert_eq!(names.field(0, 1), Some(&Name::from("y")));
+        assert_eq!(names.tags().get(&0), Some(&Name::from("oops")));
+    }
+
+    #[test]
+    fn emit_names() {
+        let mut names = NameSection::new();
+
+        names.set_module(Some(Name::from("app")));
+        names.functions_mut().insert(0, Name::from("main"));
+        names
+            .labels_mut()
+            .entry(0)
+            .or_default()
+            .insert(0, Name::from("exit"));
+
+        let mut builder = Module::builder();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![ControlInstruction::Block(
+                    BlockType::None,
+                    Expression::empty(),
+                )
+                .into()]),
+            ))
+            .unwrap();
+        builder.set_names(Some(names.clone()));
+
+        let module = builder.build();
+        let mut bytes = Vec::new();
+
+        assert!(validate(&module).is_ok());
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        let parsed = parse_binary(&bytes).unwrap();
+
+        assert_eq!(parsed.names(), Some(&names));
+        assert_eq!(parsed.names_placement(), None);
+        assert_eq!(parsed, module);
+    }
+
+    #[test]
+    fn names_after_custom_sections() {
+        let binary = wat::parse_str(
+            r#"(module $app
+                (func $main)
+                (data "hello")
+                (@custom ".debug_info" (after data) "x"))"#,
+        )
+        .unwrap();
+        let module = parse_binary(&binary).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.names_placement(), Some((ModuleSection::Data, 1)));
+    }
+
+    #[test]
+    fn names_before_custom_sections() {
+        let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x00\x0B\x04name\x00\x04\x03app\
+            \x00\x02\x01x";
+        let module = parse_binary(binary).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(module.names_placement(), Some((ModuleSection::Custom, 0)));
+        assert_eq!(
+            module.names().and_then(NameSection::module),
+            Some(&Name::from("app"))
+        );
+    }
+
+    #[test]
+    fn table_initializer() {
+        let binary = wat::parse_str(
+            "(module (type $t (func)) (func $f (type $t)) (table 1 (ref $t) (ref.func $f)))",
+        )
+        .unwrap();
+        let module = parse_binary(&binary).unwrap();
+        let mut bytes = Vec::new();
+
+        emit_binary(&module, &mut bytes).unwrap();
+
+        assert_eq!(bytes, binary);
+        assert_eq!(
+            module.tables().unwrap()[0].initializer(),
+            Some(&Expression::new(vec![
+                ReferenceInstruction::Function(0).into()
+            ]))
+        );
+    }
+
+    #[test]
+    fn limit_out_of_range() {
+        let mut module = Module::builder();
+        let limit = Limit::unbounded(u32::MAX as u64 + 1);
+
+        module
+            .add_memory(Memory::new(MemoryType::new(limit)))
+            .unwrap();
+
+        let result = emit_binary(&module.build(), &mut Vec::new());
+
+        assert!(matches!(result, Err(EmitError::Conversion(_))));
+
+        let mut module = Module::builder();
+        let limit = Limit::with_index_type(IndexType::I64, u32::MAX as u64 + 1, None);
+
+        module
+            .add_memory(Memory::new(MemoryType::new(limit)))
+            .unwrap();
+
+        assert!(emit_binary(&module.build(), &mut Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn unsupported_vector_instruction() {
+        let mut module = Module::builder();
+        module.add_function_type(FunctionType::runnable()).unwrap();
+        module
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::new(vec![VectorInstruction::Multiply(VectorShape::I8x16).into()]),
+            ))
+            .unwrap();
+
+        let result = emit_binary(&module.build(), &mut Vec::new());
+
+        assert!(matches!(
+            result,
+            Err(EmitError::UnsupportedInstruction(Instruction::Vector(
+                VectorInstruction::Multiply(VectorShape::I8x16)
+            )))
+        ));
+    }
+
     #[test]
     fn invalid_module() {
         let mut module = Module::builder();
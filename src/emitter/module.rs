@@ -1,14 +1,15 @@
 use crate::emitter::errors::EmitError;
 use crate::emitter::instruction::emit_expression;
 use crate::emitter::types::{
-    emit_global_type, emit_memory_type, emit_reference_type, emit_table_type, emit_value_type,
+    emit_global_type, emit_memory_type, emit_reference_type, emit_table_type, emit_tag_type,
+    emit_value_type,
 };
 use crate::emitter::values::{emit_byte, emit_bytes, emit_name, emit_u32, emit_usize, emit_vector};
 use crate::emitter::CountingWrite;
 use crate::model::{
-    Custom, Data, DataMode, Element, ElementMode, Export, ExportDescription, Expression, Function,
-    Global, Import, ImportDescription, Instruction, Memory, ReferenceInstruction, ReferenceType,
-    Start, Table,
+    BorrowedCustom, BorrowedData, BorrowedExport, BorrowedImport, Custom, Data, DataMode, Element,
+    ElementMode, Export, ExportDescription, Expression, Function, Global, Import,
+    ImportDescription, Instruction, Memory, ReferenceInstruction, ReferenceType, Start, Table, Tag,
 };
 use std::io::Write;
 
@@ -42,6 +43,11 @@ fn emit_function_code<O: Write + ?Sized>(
         emit_value_type(local, output)?;
     }
 
+    #[cfg(feature = "parser")]
+    if let Some(bytes) = function.body.encoded() {
+        return emit_bytes(bytes, output, false);
+    }
+
     emit_expression(function.body(), output)
 }
 
@@ -58,6 +64,22 @@ pub fn emit_import<O: Write + ?Sized>(import: &Import, output: &mut O) -> Result
     Ok(bytes)
 }
 
+/// Emit a borrowed import to the output.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html#import-section
+pub fn emit_borrowed_import<O: Write + ?Sized>(
+    import: &BorrowedImport,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_bytes(import.module().as_bytes(), output, true)?;
+    bytes += emit_bytes(import.name().as_bytes(), output, true)?;
+    bytes += emit_import_description(import.description(), output)?;
+
+    Ok(bytes)
+}
+
 /// Emit an import description to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#import-section
@@ -84,6 +106,10 @@ pub fn emit_import_description<O: Write + ?Sized>(
             bytes += emit_byte(0x03u8, output)?;
             bytes += emit_global_type(global_type, output)?;
         }
+        ImportDescription::Tag(tag_type) => {
+            bytes += emit_byte(0x04u8, output)?;
+            bytes += emit_tag_type(tag_type, output)?;
+        }
     };
 
     Ok(bytes)
@@ -91,9 +117,25 @@ pub fn emit_import_description<O: Write + ?Sized>(
 
 /// Emit a table to the output.
 ///
-/// See https://webassembly.github.io/spec/core/binary/modules.html#table-section
+/// Tables with an initializer are prefixed by the bytes `0x40 0x00`.
+///
+/// See https://webassembly.github.io/function-references/core/binary/modules.html#table-section
 pub fn emit_table<O: Write + ?Sized>(table: &Table, output: &mut O) -> Result<usize, EmitError> {
-    emit_table_type(table.kind(), output)
+    let mut bytes = 0;
+
+    match table.initializer() {
+        Some(initializer) => {
+            bytes += emit_byte(0x40u8, output)?;
+            bytes += emit_byte(0x00u8, output)?;
+            bytes += emit_table_type(table.kind(), output)?;
+            bytes += emit_expression(initializer, output)?;
+        }
+        None => {
+            bytes += emit_table_type(table.kind(), output)?;
+        }
+    }
+
+    Ok(bytes)
 }
 
 /// Emit a memory to the output.
@@ -103,6 +145,13 @@ pub fn emit_memory<O: Write + ?Sized>(memory: &Memory, output: &mut O) -> Result
     emit_memory_type(memory.kind(), output)
 }
 
+/// Emit a tag to the output.
+///
+/// See https://webassembly.github.io/exception-handling/core/binary/modules.html#tag-section
+pub fn emit_tag<O: Write + ?Sized>(tag: &Tag, output: &mut O) -> Result<usize, EmitError> {
+    emit_tag_type(tag.kind(), output)
+}
+
 /// Emit a global to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#global-section
@@ -127,6 +176,21 @@ pub fn emit_export<O: Write + ?Sized>(export: &Export, output: &mut O) -> Result
     Ok(bytes)
 }
 
+/// Emit a borrowed export to the output.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html#export-section
+pub fn emit_borrowed_export<O: Write + ?Sized>(
+    export: &BorrowedExport,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_bytes(export.name().as_bytes(), output, true)?;
+    bytes += emit_export_description(export.description(), output)?;
+
+    Ok(bytes)
+}
+
 /// Emit an export description to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#export-section
@@ -139,6 +203,7 @@ pub fn emit_export_description<O: Write + ?Sized>(
         ExportDescription::Table(index) => (0x01, index),
         ExportDescription::Memory(index) => (0x02, index),
         ExportDescription::Global(index) => (0x03, index),
+        ExportDescription::Tag(index) => (0x04, index),
     };
     let mut bytes = 0;
 
@@ -207,22 +272,24 @@ pub fn emit_element<O: Write + ?Sized>(
                 emit_u32,
             )?;
         }
-        (expressions, ElementMode::Active(table, offset), kind)
+        (expressions, ElementMode::Active(table, offset), ReferenceType::Function)
             if is_function_indices(expressions) =>
         {
             bytes += emit_byte(0x02u8, output)?;
             bytes += emit_u32(table, output)?;
             bytes += emit_expression(offset, output)?;
-            bytes += emit_reference_type(kind, output)?;
+            bytes += emit_byte(0x00u8, output)?;
             bytes += emit_vector(
                 expressions.iter().filter_map(extract_index),
                 output,
                 emit_u32,
             )?;
         }
-        (expressions, ElementMode::Declarative, kind) if is_function_indices(expressions) => {
+        (expressions, ElementMode::Declarative, ReferenceType::Function)
+            if is_function_indices(expressions) =>
+        {
             bytes += emit_byte(0x03u8, output)?;
-            bytes += emit_reference_type(kind, output)?;
+            bytes += emit_byte(0x00u8, output)?;
             bytes += emit_vector(
                 expressions.iter().filter_map(extract_index),
                 output,
@@ -260,9 +327,28 @@ pub fn emit_element<O: Write + ?Sized>(
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#data-section
 pub fn emit_data<O: Write + ?Sized>(data: &Data, output: &mut O) -> Result<usize, EmitError> {
+    emit_data_segment(data.mode(), data.initializer(), output)
+}
+
+/// Emit a borrowed data segment to the output.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html#data-section
+pub fn emit_borrowed_data<O: Write + ?Sized>(
+    data: &BorrowedData,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    emit_data_segment(data.mode(), data.initializer(), output)
+}
+
+/// Emits the mode and initializer of a data segment.
+fn emit_data_segment<O: Write + ?Sized>(
+    mode: &DataMode,
+    initializer: &[u8],
+    output: &mut O,
+) -> Result<usize, EmitError> {
     let mut bytes = 0;
 
-    match data.mode() {
+    match mode {
         DataMode::Active(0, offset) => {
             bytes += emit_byte(0x00u8, output)?;
             bytes += emit_expression(offset, output)?;
@@ -277,7 +363,7 @@ pub fn emit_data<O: Write + ?Sized>(data: &Data, output: &mut O) -> Result<usize
         }
     };
 
-    bytes += emit_bytes(data.initializer(), output, true)?;
+    bytes += emit_bytes(initializer, output, true)?;
 
     Ok(bytes)
 }
@@ -296,3 +382,18 @@ pub fn emit_custom_content<O: Write + ?Sized>(
 
     Ok(bytes)
 }
+
+/// Emit borrowed named custom content to the module.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html#custom-section
+pub fn emit_borrowed_custom_content<O: Write + ?Sized>(
+    custom: &BorrowedCustom,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_bytes(custom.name().as_bytes(), output, true)?;
+    bytes += emit_bytes(custom.bytes(), output, false)?;
+
+    Ok(bytes)
+}
@@ -0,0 +1,117 @@
+use crate::emitter::errors::EmitError;
+use crate::emitter::values::{emit_byte, emit_bytes, emit_name, emit_u32, emit_vector};
+use crate::model::{IndirectNameMap, Name, NameMap, NameSection, NameSubsection};
+use std::io::Write;
+
+/// Emit the contents of a name section to the output.
+/// Subsections are emitted in order of increasing id; empty subsections are omitted.
+///
+/// See https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+pub fn emit_name_section_content<O: Write + ?Sized>(
+    names: &NameSection,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    if let Some(module) = names.module() {
+        bytes += emit_subsection(NameSubsection::Module, output, |o| emit_name(module, o))?;
+    }
+
+    bytes += emit_name_subsection(NameSubsection::Function, names.functions(), output)?;
+    bytes += emit_indirect_name_subsection(NameSubsection::Local, names.locals(), output)?;
+    bytes += emit_indirect_name_subsection(NameSubsection::Label, names.labels(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Type, names.types(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Table, names.tables(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Memory, names.memories(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Global, names.globals(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Element, names.elements(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Data, names.data(), output)?;
+    bytes += emit_indirect_name_subsection(NameSubsection::Field, names.fields(), output)?;
+    bytes += emit_name_subsection(NameSubsection::Tag, names.tags(), output)?;
+
+    Ok(bytes)
+}
+
+/// Emit a subsection consisting of a name map, unless the map is empty.
+fn emit_name_subsection<O: Write + ?Sized>(
+    subsection: NameSubsection,
+    map: &NameMap,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    if map.is_empty() {
+        return Ok(0);
+    }
+
+    emit_subsection(subsection, output, |o| emit_name_map(map, o))
+}
+
+/// Emit a subsection consisting of an indirect name map, unless the map is empty.
+fn emit_indirect_name_subsection<O: Write + ?Sized>(
+    subsection: NameSubsection,
+    map: &IndirectNameMap,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    if map.is_empty() {
+        return Ok(0);
+    }
+
+    emit_subsection(subsection, output, |o| emit_indirect_name_map(map, o))
+}
+
+/// Emit a subsection with the given id to the output, prefixed by the size of its contents.
+///
+/// See https://webassembly.github.io/spec/core/appendix/custom.html#subsections
+fn emit_subsection<E, O>(
+    subsection: NameSubsection,
+    output: &mut O,
+    emit: E,
+) -> Result<usize, EmitError>
+where
+    O: Write + ?Sized,
+    E: Fn(&mut Vec<u8>) -> Result<usize, EmitError>,
+{
+    let mut contents = Vec::new();
+
+    emit(&mut contents)?;
+
+    let mut bytes = emit_byte(subsection as u8, output)?;
+
+    bytes += emit_bytes(&contents, output, true)?;
+
+    Ok(bytes)
+}
+
+/// Emit a name map to the output.
+///
+/// See https://webassembly.github.io/spec/core/appendix/custom.html#name-maps
+pub fn emit_name_map<O: Write + ?Sized>(map: &NameMap, output: &mut O) -> Result<usize, EmitError> {
+    emit_vector(map, output, emit_name_association)
+}
+
+/// Emit an indirect name map to the output.
+///
+/// See https://webassembly.github.io/spec/core/appendix/custom.html#name-maps
+pub fn emit_indirect_name_map<O: Write + ?Sized>(
+    map: &IndirectNameMap,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    emit_vector(map, output, |(index, map), o| {
+        let mut bytes = emit_u32(index, o)?;
+
+        bytes += emit_name_map(map, o)?;
+
+        Ok(bytes)
+    })
+}
+
+/// Emit an index and its associated name to the output.
+fn emit_name_association<O: Write + ?Sized>(
+    (index, name): (&u32, &Name),
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = emit_u32(index, output)?;
+
+    bytes += emit_name(name, output)?;
+
+    Ok(bytes)
+}
@@ -1,14 +1,16 @@
 use crate::emitter::errors::EmitError;
 use crate::emitter::module::{
+    emit_borrowed_custom_content, emit_borrowed_data, emit_borrowed_export, emit_borrowed_import,
     emit_custom_content, emit_data, emit_element, emit_export, emit_function, emit_global,
-    emit_import, emit_memory, emit_start, emit_table,
-};
-use crate::emitter::types::emit_function_type;
-use crate::emitter::values::{
-    emit_byte, emit_bytes, emit_repeated, emit_u32, emit_usize, emit_vector,
+    emit_import, emit_memory, emit_start, emit_table, emit_tag,
 };
+use crate::emitter::names::emit_name_section_content;
+use crate::emitter::types::emit_recursive_type;
+use crate::emitter::values::{emit_byte, emit_bytes, emit_name, emit_u32, emit_usize, emit_vector};
 use crate::emitter::CountingWrite;
-use crate::model::{Custom, Function, Module, ModuleSection, TypeIndex};
+use crate::model::{
+    BorrowedCustom, BorrowedModule, Custom, Function, Module, ModuleSection, Name, TypeIndex,
+};
 use std::io::Write;
 
 /// A magic constant used to quickly identify WebAssembly binary file contents.
@@ -17,55 +19,160 @@ const PREAMBLE: [u8; 4] = [0x00u8, 0x61u8, 0x73u8, 0x6Du8];
 /// The version of the binary WebAssembly format emitted.
 const VERSION: [u8; 4] = [0x01u8, 0x00u8, 0x00u8, 0x00u8];
 
+/// The name of the custom section that holds the names of a module.
+const NAME_SECTION: &str = "name";
+
 /// Emit a module to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html
 pub fn emit_module<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
+    emit_sections(
+        module,
+        module.data_count(),
+        output,
+        |o| emit_import_section(module.imports(), o, |import, o| emit_import(import, o)),
+        |o| emit_export_section(module.exports(), o, |export, o| emit_export(export, o)),
+        |insertion_point, o| {
+            let sections = module
+                .custom_sections_at(insertion_point)
+                .unwrap_or_default();
+
+            emit_custom_sections(module, sections, insertion_point, o, emit_custom_section)
+        },
+        |o| emit_data_section(module.data(), o, |data, o| emit_data(data, o)),
+    )
+}
+
+/// Emit a borrowed module to the output.
+/// The imports, exports, data segments and custom sections are emitted from the borrowed input.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html
+pub fn emit_borrowed_module<O: Write>(
+    module: &BorrowedModule,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    emit_sections(
+        module.module(),
+        module.data_count(),
+        output,
+        |o| {
+            emit_import_section(module.imports(), o, |import, o| {
+                emit_borrowed_import(import, o)
+            })
+        },
+        |o| {
+            emit_export_section(module.exports(), o, |export, o| {
+                emit_borrowed_export(export, o)
+            })
+        },
+        |insertion_point, o| {
+            let sections = module
+                .custom_sections_at(insertion_point)
+                .unwrap_or_default();
+
+            emit_custom_sections(
+                module.module(),
+                sections,
+                insertion_point,
+                o,
+                emit_borrowed_custom_section,
+            )
+        },
+        |o| emit_data_section(module.data(), o, |data, o| emit_borrowed_data(data, o)),
+    )
+}
+
+/// Emits the sections of a module in order, using the given functions to emit the import and export sections,
+/// the custom sections at each insertion point and the data section.
+fn emit_sections<O, I, X, C, D>(
+    module: &Module,
+    data_count: Option<u32>,
+    output: &mut O,
+    imports: I,
+    exports: X,
+    custom: C,
+    data: D,
+) -> Result<usize, EmitError>
+where
+    O: Write,
+    I: Fn(&mut O) -> Result<usize, EmitError>,
+    X: Fn(&mut O) -> Result<usize, EmitError>,
+    C: Fn(ModuleSection, &mut O) -> Result<usize, EmitError>,
+    D: Fn(&mut O) -> Result<usize, EmitError>,
+{
     let mut bytes = 0;
 
     bytes += emit_bytes(&PREAMBLE, output, false)?;
     bytes += emit_bytes(&VERSION, output, false)?;
-    bytes += emit_custom_sections(module, ModuleSection::Custom, output)?;
+    bytes += custom(ModuleSection::Custom, output)?;
     bytes += emit_type_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Type, output)?;
-    bytes += emit_import_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Import, output)?;
+    bytes += custom(ModuleSection::Type, output)?;
+    bytes += imports(output)?;
+    bytes += custom(ModuleSection::Import, output)?;
     bytes += emit_function_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Function, output)?;
+    bytes += custom(ModuleSection::Function, output)?;
     bytes += emit_table_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Table, output)?;
+    bytes += custom(ModuleSection::Table, output)?;
     bytes += emit_memory_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Memory, output)?;
+    bytes += custom(ModuleSection::Memory, output)?;
+    bytes += emit_tag_section(module, output)?;
+    bytes += custom(ModuleSection::Tag, output)?;
     bytes += emit_global_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Global, output)?;
-    bytes += emit_export_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Export, output)?;
+    bytes += custom(ModuleSection::Global, output)?;
+    bytes += exports(output)?;
+    bytes += custom(ModuleSection::Export, output)?;
     bytes += emit_start_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Start, output)?;
+    bytes += custom(ModuleSection::Start, output)?;
     bytes += emit_element_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Element, output)?;
-    bytes += emit_data_count_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::DataCount, output)?;
+    bytes += custom(ModuleSection::Element, output)?;
+    bytes += emit_data_count_section(data_count, output)?;
+    bytes += custom(ModuleSection::DataCount, output)?;
     bytes += emit_code_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Code, output)?;
-    bytes += emit_data_section(module, output)?;
-    bytes += emit_custom_sections(module, ModuleSection::Data, output)?;
+    bytes += custom(ModuleSection::Code, output)?;
+    bytes += data(output)?;
+    bytes += custom(ModuleSection::Data, output)?;
 
     Ok(bytes)
 }
 
-/// Emits the custom section to the output.
+/// Emits the given custom sections at the given insertion point to the output,
+/// including the name section of the module when it is placed there.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#custom-section
-pub fn emit_custom_sections<O: Write>(
+pub fn emit_custom_sections<C, E, O>(
     module: &Module,
+    sections: &[C],
     insertion_point: ModuleSection,
     output: &mut O,
-) -> Result<usize, EmitError> {
-    match module.custom_sections_at(insertion_point) {
-        None => Ok(0),
-        Some(sections) => emit_repeated(sections, output, emit_custom_section),
+    emit: E,
+) -> Result<usize, EmitError>
+where
+    O: Write,
+    E: Fn(&C, &mut O) -> Result<usize, EmitError>,
+{
+    let names = match module.names_placement() {
+        Some((placement, position)) if placement == insertion_point => {
+            Some(position.min(sections.len()))
+        }
+        Some(_) => None,
+        None if insertion_point == ModuleSection::Data => Some(0),
+        None => None,
+    };
+    let mut bytes = 0;
+
+    for (position, custom) in sections.iter().enumerate() {
+        if names == Some(position) {
+            bytes += emit_name_section(module, output)?;
+        }
+
+        bytes += emit(custom, output)?;
+    }
+
+    if names == Some(sections.len()) {
+        bytes += emit_name_section(module, output)?;
     }
+
+    Ok(bytes)
 }
 
 /// Emits the custom section to the output.
@@ -77,14 +184,43 @@ pub fn emit_custom_section<O: Write>(custom: &Custom, output: &mut O) -> Result<
     })
 }
 
+/// Emits the borrowed custom section to the output.
+///
+/// See https://webassembly.github.io/spec/core/binary/modules.html#custom-section
+pub fn emit_borrowed_custom_section<O: Write>(
+    custom: &BorrowedCustom,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    emit_section(ModuleSection::Custom, output, |o| {
+        emit_borrowed_custom_content(custom, o)
+    })
+}
+
+/// Emits the names of a module as a custom section to the output.
+/// The name section is expected to appear once, at the placement of the names in the module.
+///
+/// See https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+pub fn emit_name_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
+    match module.names() {
+        None => Ok(0),
+        Some(names) => emit_section(ModuleSection::Custom, output, |o| {
+            let mut bytes = emit_name(&Name::from(NAME_SECTION), o)?;
+
+            bytes += emit_name_section_content(names, o)?;
+
+            Ok(bytes)
+        }),
+    }
+}
+
 /// Emits the type section to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#type-section
 pub fn emit_type_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
-    match module.function_types() {
+    match module.types() {
         None => Ok(0),
         Some(types) => emit_section(ModuleSection::Type, output, |o| {
-            emit_vector(types, o, emit_function_type)
+            emit_vector(types, o, emit_recursive_type)
         }),
     }
 }
@@ -92,11 +228,19 @@ pub fn emit_type_section<O: Write>(module: &Module, output: &mut O) -> Result<us
 /// Emits the import section to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#import-section
-pub fn emit_import_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
-    match module.imports() {
+pub fn emit_import_section<I, E, O>(
+    imports: Option<&[I]>,
+    output: &mut O,
+    emit: E,
+) -> Result<usize, EmitError>
+where
+    O: Write,
+    E: Fn(&I, &mut dyn Write) -> Result<usize, EmitError>,
+{
+    match imports {
         None => Ok(0),
         Some(imports) => emit_section(ModuleSection::Import, output, |o| {
-            emit_vector(imports, o, emit_import)
+            emit_vector(imports, o, |import, o| emit(import, o))
         }),
     }
 }
@@ -144,6 +288,18 @@ pub fn emit_memory_section<O: Write>(module: &Module, output: &mut O) -> Result<
     }
 }
 
+/// Emits the tag section to the output.
+///
+/// See https://webassembly.github.io/exception-handling/core/binary/modules.html#tag-section
+pub fn emit_tag_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
+    match module.tags() {
+        Some(tags) => emit_section(ModuleSection::Tag, output, |o| {
+            emit_vector(tags, o, emit_tag)
+        }),
+        None => Ok(0),
+    }
+}
+
 /// Emits the global section to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#global-section
@@ -159,10 +315,18 @@ pub fn emit_global_section<O: Write>(module: &Module, output: &mut O) -> Result<
 /// Emits the export section to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#export-section
-pub fn emit_export_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
-    match module.exports() {
+pub fn emit_export_section<X, E, O>(
+    exports: Option<&[X]>,
+    output: &mut O,
+    emit: E,
+) -> Result<usize, EmitError>
+where
+    O: Write,
+    E: Fn(&X, &mut dyn Write) -> Result<usize, EmitError>,
+{
+    match exports {
         Some(exports) => emit_section(ModuleSection::Export, output, |o| {
-            emit_vector(exports, o, emit_export)
+            emit_vector(exports, o, |export, o| emit(export, o))
         }),
         None => Ok(0),
     }
@@ -192,10 +356,10 @@ pub fn emit_element_section<O: Write>(module: &Module, output: &mut O) -> Result
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#data-count-section
 pub fn emit_data_count_section<O: Write>(
-    module: &Module,
+    data_count: Option<u32>,
     output: &mut O,
 ) -> Result<usize, EmitError> {
-    match module.data_count() {
+    match data_count {
         None => Ok(0),
         Some(count) => emit_section(ModuleSection::DataCount, output, |o| emit_u32(count, o)),
     }
@@ -216,11 +380,19 @@ pub fn emit_code_section<O: Write>(module: &Module, output: &mut O) -> Result<us
 /// Emits the data section to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/modules.html#data-section
-pub fn emit_data_section<O: Write>(module: &Module, output: &mut O) -> Result<usize, EmitError> {
-    match module.data() {
+pub fn emit_data_section<D, E, O>(
+    data: Option<&[D]>,
+    output: &mut O,
+    emit: E,
+) -> Result<usize, EmitError>
+where
+    O: Write,
+    E: Fn(&D, &mut dyn Write) -> Result<usize, EmitError>,
+{
+    match data {
         None => Ok(0),
         Some(data) => emit_section(ModuleSection::Data, output, |o| {
-            emit_vector(data, o, emit_data)
+            emit_vector(data, o, |data, o| emit(data, o))
         }),
     }
 }
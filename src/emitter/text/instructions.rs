@@ -0,0 +1,1319 @@
+//! Emit WebAssembly instructions in the text format.
+//!
+//! See https://webassembly.github.io/spec/core/text/instructions.html
+
+use crate::emitter::text::Printer;
+use crate::model::{
+    AggregateInstruction, AtomicInstruction, AtomicOperation, BlockType, CatchClause,
+    CompositeType, ControlInstruction, Expression, FloatType, FloatVectorShape, HeapType,
+    Instruction, IntegerType, IntegerVectorShape, MemoryArgument, MemoryInstruction, NumberType,
+    NumericInstruction, ParametricInstruction, ReferenceInstruction, ReferenceType, SignExtension,
+    TableInstruction, ValueType, VariableInstruction, VectorHalf, VectorInstruction, VectorShape,
+};
+
+/// An instruction in the folded style, along with the folded instructions that compute its operands.
+///
+/// See https://webassembly.github.io/spec/core/text/instructions.html#folded-instructions
+pub struct Node<'i> {
+    instruction: &'i Instruction,
+    operands: Vec<Node<'i>>,
+    results: Option<usize>,
+}
+
+impl<'m> Printer<'m> {
+    /// Prints an instruction in the flat style.
+    pub(super) fn flat(&mut self, instruction: &Instruction) {
+        self.line(&instruction_text(instruction));
+
+        if let Instruction::Control(control) = instruction {
+            match control {
+                ControlInstruction::Block(kind, body)
+                | ControlInstruction::Loop(kind, body)
+                | ControlInstruction::TryTable(kind, _, body) => {
+                    self.flat_body(self.label_arity(control, *kind), body);
+                    self.line("end");
+                }
+                ControlInstruction::If(kind, consequent, alternative) => {
+                    let arity = self.label_arity(control, *kind);
+
+                    self.flat_body(arity, consequent);
+
+                    if let Some(alternative) = alternative {
+                        self.line("else");
+                        self.flat_body(arity, alternative);
+                    }
+
+                    self.line("end");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Prints the body of a structured instruction in the flat style.
+    fn flat_body(&mut self, arity: usize, body: &Expression) {
+        self.depth += 1;
+        self.labels.push(arity);
+        self.instructions(body.instructions());
+        self.labels.pop();
+        self.depth -= 1;
+    }
+
+    /// Prints an instruction in the folded style, preceded by its operands.
+    pub(super) fn folded(&mut self, node: &Node) {
+        let text = instruction_text(node.instruction);
+
+        match node.instruction {
+            Instruction::Control(
+                control @ (ControlInstruction::Block(kind, body)
+                | ControlInstruction::Loop(kind, body)
+                | ControlInstruction::TryTable(kind, _, body)),
+            ) => {
+                let arity = self.label_arity(control, *kind);
+
+                self.open(&text);
+                self.folded_operands(node);
+                self.labels.push(arity);
+                self.instructions(body.instructions());
+                self.labels.pop();
+                self.close();
+            }
+            Instruction::Control(
+                control @ ControlInstruction::If(kind, consequent, alternative),
+            ) => {
+                let arity = self.label_arity(control, *kind);
+
+                self.open(&text);
+                self.folded_operands(node);
+                self.labels.push(arity);
+                self.open("then");
+                self.instructions(consequent.instructions());
+                self.close();
+
+                if let Some(alternative) = alternative {
+                    self.open("else");
+                    self.instructions(alternative.instructions());
+                    self.close();
+                }
+
+                self.labels.pop();
+                self.close();
+            }
+            _ if node.operands.is_empty() => self.line(&format!("({})", text)),
+            _ => {
+                self.open(&text);
+                self.folded_operands(node);
+                self.close();
+            }
+        }
+    }
+
+    /// Prints the operands of a folded instruction.
+    fn folded_operands(&mut self, node: &Node) {
+        for operand in node.operands.iter() {
+            self.folded(operand);
+        }
+    }
+
+    /// Groups a sequence of instructions into folded instructions.
+    /// An instruction encloses the immediately preceding instructions as its operands only when
+    /// each of them produces a single value that the instruction consumes.
+    /// Otherwise, the preceding instructions are left as siblings, which is equivalent since
+    /// folded instructions are unfolded in order.
+    pub(super) fn fold<'i>(&self, instructions: &'i [Instruction]) -> Vec<Node<'i>> {
+        let mut nodes: Vec<Node> = Vec::new();
+
+        for instruction in instructions {
+            let (operands, results) = match self.arity(instruction) {
+                Some((operands, results)) => (operands, Some(results)),
+                None => (0, None),
+            };
+            let foldable = operands > 0
+                && nodes.len() >= operands
+                && nodes[nodes.len() - operands..]
+                    .iter()
+                    .all(|node| node.results == Some(1));
+            let operands = if foldable {
+                nodes.split_off(nodes.len() - operands)
+            } else {
+                Vec::new()
+            };
+
+            nodes.push(Node {
+                instruction,
+                operands,
+                results,
+            });
+        }
+
+        nodes
+    }
+
+    /// Renders an instruction in the flat style on a single line,
+    /// including the bodies of structured instructions.
+    pub(super) fn inline_flat(&self, instruction: &Instruction) -> String {
+        let mut text = instruction_text(instruction);
+        let body = |text: &mut String, body: &Expression| {
+            for instruction in body.instructions() {
+                text.push(' ');
+                text.push_str(&self.inline_flat(instruction));
+            }
+        };
+
+        match instruction {
+            Instruction::Control(
+                ControlInstruction::Block(_, expression)
+                | ControlInstruction::Loop(_, expression)
+                | ControlInstruction::TryTable(_, _, expression),
+            ) => {
+                body(&mut text, expression);
+                text.push_str(" end");
+            }
+            Instruction::Control(ControlInstruction::If(_, consequent, alternative)) => {
+                body(&mut text, consequent);
+
+                if let Some(alternative) = alternative {
+                    text.push_str(" else");
+                    body(&mut text, alternative);
+                }
+
+                text.push_str(" end");
+            }
+            _ => {}
+        }
+
+        text
+    }
+
+    /// Renders a folded instruction on a single line.
+    pub(super) fn inline_folded(&self, node: &Node) -> String {
+        let mut text = format!("({}", instruction_text(node.instruction));
+        let body = |text: &mut String, body: &Expression| {
+            for node in self.fold(body.instructions()) {
+                text.push(' ');
+                text.push_str(&self.inline_folded(&node));
+            }
+        };
+
+        for operand in node.operands.iter() {
+            text.push(' ');
+            text.push_str(&self.inline_folded(operand));
+        }
+
+        match node.instruction {
+            Instruction::Control(
+                ControlInstruction::Block(_, expression)
+                | ControlInstruction::Loop(_, expression)
+                | ControlInstruction::TryTable(_, _, expression),
+            ) => body(&mut text, expression),
+            Instruction::Control(ControlInstruction::If(_, consequent, alternative)) => {
+                text.push_str(" (then");
+                body(&mut text, consequent);
+                text.push(')');
+
+                if let Some(alternative) = alternative {
+                    text.push_str(" (else");
+                    body(&mut text, alternative);
+                    text.push(')');
+                }
+            }
+            _ => {}
+        }
+
+        text.push(')');
+        text
+    }
+
+    /// The number of parameters and results of the given block type, if known.
+    fn block_arity(&self, kind: BlockType) -> Option<(usize, usize)> {
+        match kind {
+            BlockType::None => Some((0, 0)),
+            BlockType::ValueType(_) => Some((0, 1)),
+            BlockType::Index(index) => self
+                .function_type(index)
+                .map(|kind| (kind.parameters().len(), kind.results().len())),
+        }
+    }
+
+    /// The number of values a branch to the label of the given structured instruction carries.
+    /// Branches to a loop carry its parameters, while all others carry its results.
+    fn label_arity(&self, control: &ControlInstruction, kind: BlockType) -> usize {
+        let (parameters, results) = self.block_arity(kind).unwrap_or_default();
+
+        match control {
+            ControlInstruction::Loop(..) => parameters,
+            _ => results,
+        }
+    }
+
+    /// The number of values carried by a branch to the given label.
+    fn branch_arity(&self, label: u32) -> Option<usize> {
+        let depth = self.labels.len().checked_sub(label as usize + 1)?;
+
+        self.labels.get(depth).copied()
+    }
+
+    /// The number of parameters and results of the function type at the given index, if known.
+    fn function_arity(&self, kind: u32) -> Option<(usize, usize)> {
+        self.function_type(kind)
+            .map(|kind| (kind.parameters().len(), kind.results().len()))
+    }
+
+    /// The number of operands an instruction consumes and the number of values it produces,
+    /// for the purpose of folding.
+    /// Structured instructions other than 𝗂𝖿 consume no operands when folded,
+    /// as their folded form only encloses their body.
+    /// Returns `None` when the arity depends on information that is not available.
+    fn arity(&self, instruction: &Instruction) -> Option<(usize, usize)> {
+        let arity = match instruction {
+            Instruction::Numeric(instruction) => numeric_arity(instruction),
+            Instruction::Vector(instruction) => vector_arity(instruction),
+            Instruction::Reference(instruction) => match instruction {
+                ReferenceInstruction::Null(_) | ReferenceInstruction::Function(_) => (0, 1),
+                ReferenceInstruction::Equal => (2, 1),
+                _ => (1, 1),
+            },
+            Instruction::Parametric(ParametricInstruction::Drop) => (1, 0),
+            Instruction::Parametric(ParametricInstruction::Select(_)) => (3, 1),
+            Instruction::Variable(instruction) => match instruction {
+                VariableInstruction::LocalGet(_) | VariableInstruction::GlobalGet(_) => (0, 1),
+                VariableInstruction::LocalSet(_) | VariableInstruction::GlobalSet(_) => (1, 0),
+                VariableInstruction::LocalTee(_) => (1, 1),
+            },
+            Instruction::Table(instruction) => match instruction {
+                TableInstruction::Get(_) => (1, 1),
+                TableInstruction::Set(_) => (2, 0),
+                TableInstruction::Size(_) => (0, 1),
+                TableInstruction::Grow(_) => (2, 1),
+                TableInstruction::Fill(_)
+                | TableInstruction::Copy(_, _)
+                | TableInstruction::Init(_, _) => (3, 0),
+                TableInstruction::ElementDrop(_) => (0, 0),
+            },
+            Instruction::Memory(instruction) => match instruction {
+                MemoryInstruction::Load(..)
+                | MemoryInstruction::Load8(..)
+                | MemoryInstruction::Load16(..)
+                | MemoryInstruction::Load32(..)
+                | MemoryInstruction::Grow(_) => (1, 1),
+                MemoryInstruction::Store(..)
+                | MemoryInstruction::Store8(..)
+                | MemoryInstruction::Store16(..)
+                | MemoryInstruction::Store32(_) => (2, 0),
+                MemoryInstruction::Size(_) => (0, 1),
+                MemoryInstruction::Fill(_)
+                | MemoryInstruction::Copy(_, _)
+                | MemoryInstruction::Init(_, _) => (3, 0),
+                MemoryInstruction::DataDrop(_) => (0, 0),
+            },
+            Instruction::Atomic(instruction) => match instruction {
+                AtomicInstruction::Notify(_) => (2, 1),
+                AtomicInstruction::Wait(..) => (3, 1),
+                AtomicInstruction::Fence => (0, 0),
+                AtomicInstruction::Load(..)
+                | AtomicInstruction::Load8(..)
+                | AtomicInstruction::Load16(..)
+                | AtomicInstruction::Load32(_) => (1, 1),
+                AtomicInstruction::Store(..)
+                | AtomicInstruction::Store8(..)
+                | AtomicInstruction::Store16(..)
+                | AtomicInstruction::Store32(_) => (2, 0),
+                AtomicInstruction::ReadModifyWrite(AtomicOperation::CompareExchange, ..)
+                | AtomicInstruction::ReadModifyWrite8(AtomicOperation::CompareExchange, ..)
+                | AtomicInstruction::ReadModifyWrite16(AtomicOperation::CompareExchange, ..)
+                | AtomicInstruction::ReadModifyWrite32(AtomicOperation::CompareExchange, _) => {
+                    (3, 1)
+                }
+                _ => (2, 1),
+            },
+            Instruction::Aggregate(instruction) => match instruction {
+                AggregateInstruction::StructNew(kind) => {
+                    match self.types.get(*kind as usize).map(|kind| kind.kind()) {
+                        Some(CompositeType::Struct(kind)) => (kind.fields().len(), 1),
+                        _ => return None,
+                    }
+                }
+                AggregateInstruction::StructNewDefault(_) => (0, 1),
+                AggregateInstruction::StructSet(..) => (2, 0),
+                AggregateInstruction::ArrayNew(_)
+                | AggregateInstruction::ArrayNewData(..)
+                | AggregateInstruction::ArrayNewElement(..)
+                | AggregateInstruction::ArrayGet(..) => (2, 1),
+                AggregateInstruction::ArrayNewFixed(_, length) => (*length as usize, 1),
+                AggregateInstruction::ArraySet(_) => (3, 0),
+                AggregateInstruction::ArrayFill(_)
+                | AggregateInstruction::ArrayInitData(..)
+                | AggregateInstruction::ArrayInitElement(..) => (4, 0),
+                AggregateInstruction::ArrayCopy(..) => (5, 0),
+                _ => (1, 1),
+            },
+            Instruction::Control(instruction) => match instruction {
+                ControlInstruction::Nop | ControlInstruction::Unreachable => (0, 0),
+                ControlInstruction::Block(kind, _)
+                | ControlInstruction::Loop(kind, _)
+                | ControlInstruction::TryTable(kind, _, _) => (0, self.block_arity(*kind)?.1),
+                ControlInstruction::If(kind, _, _) => {
+                    let (parameters, results) = self.block_arity(*kind)?;
+
+                    (parameters + 1, results)
+                }
+                ControlInstruction::Branch(label) => (self.branch_arity(*label)?, 0),
+                ControlInstruction::BranchIf(label) => {
+                    let arity = self.branch_arity(*label)?;
+
+                    (arity + 1, arity)
+                }
+                ControlInstruction::BranchTable(_, default) => {
+                    (self.branch_arity(*default)? + 1, 0)
+                }
+                ControlInstruction::Return => (self.results, 0),
+                ControlInstruction::Call(function) => {
+                    self.function_arity(*self.functions.get(*function as usize)?)?
+                }
+                ControlInstruction::ReturnCall(function) => (
+                    self.function_arity(*self.functions.get(*function as usize)?)?
+                        .0,
+                    0,
+                ),
+                ControlInstruction::CallIndirect(kind, _) | ControlInstruction::CallRef(kind) => {
+                    let (parameters, results) = self.function_arity(*kind)?;
+
+                    (parameters + 1, results)
+                }
+                ControlInstruction::ReturnCallIndirect(kind, _)
+                | ControlInstruction::ReturnCallRef(kind) => (self.function_arity(*kind)?.0 + 1, 0),
+                ControlInstruction::Throw(tag) => {
+                    (self.function_arity(*self.tags.get(*tag as usize)?)?.0, 0)
+                }
+                ControlInstruction::ThrowRef => (1, 0),
+                ControlInstruction::BranchOnNull(_)
+                | ControlInstruction::BranchOnNonNull(_)
+                | ControlInstruction::BranchOnCast(..)
+                | ControlInstruction::BranchOnCastFail(..) => return None,
+            },
+        };
+
+        Some(arity)
+    }
+}
+
+/// The number of operands consumed and values produced by a numeric instruction.
+fn numeric_arity(instruction: &NumericInstruction) -> (usize, usize) {
+    match instruction {
+        NumericInstruction::I32Constant(_)
+        | NumericInstruction::I64Constant(_)
+        | NumericInstruction::F32Constant(_)
+        | NumericInstruction::F64Constant(_) => (0, 1),
+        NumericInstruction::CountLeadingZeros(_)
+        | NumericInstruction::CountTrailingZeros(_)
+        | NumericInstruction::CountOnes(_)
+        | NumericInstruction::AbsoluteValue(_)
+        | NumericInstruction::Negate(_)
+        | NumericInstruction::SquareRoot(_)
+        | NumericInstruction::Ceiling(_)
+        | NumericInstruction::Floor(_)
+        | NumericInstruction::Truncate(_)
+        | NumericInstruction::Nearest(_)
+        | NumericInstruction::EqualToZero(_)
+        | NumericInstruction::ExtendSigned8(_)
+        | NumericInstruction::ExtendSigned16(_)
+        | NumericInstruction::ExtendSigned32
+        | NumericInstruction::Wrap
+        | NumericInstruction::ExtendWithSignExtension(_)
+        | NumericInstruction::ConvertAndTruncate(..)
+        | NumericInstruction::ConvertAndTruncateWithSaturation(..)
+        | NumericInstruction::Demote
+        | NumericInstruction::Promote
+        | NumericInstruction::Convert(..)
+        | NumericInstruction::ReinterpretFloat(_)
+        | NumericInstruction::ReinterpretInteger(_) => (1, 1),
+        _ => (2, 1),
+    }
+}
+
+/// The number of operands consumed and values produced by a vector instruction.
+fn vector_arity(instruction: &VectorInstruction) -> (usize, usize) {
+    match instruction {
+        VectorInstruction::Constant(_) => (0, 1),
+        VectorInstruction::Not
+        | VectorInstruction::AnyTrue
+        | VectorInstruction::Splat(_)
+        | VectorInstruction::ExtractLane(..)
+        | VectorInstruction::AbsoluteValue(_)
+        | VectorInstruction::Negate(_)
+        | VectorInstruction::CountOnes
+        | VectorInstruction::SquareRoot(_)
+        | VectorInstruction::Ceiling(_)
+        | VectorInstruction::Floor(_)
+        | VectorInstruction::Truncate(_)
+        | VectorInstruction::Nearest(_)
+        | VectorInstruction::AllTrue(_)
+        | VectorInstruction::Bitmask(_)
+        | VectorInstruction::Extend(..)
+        | VectorInstruction::ExtendAddPairwise(..)
+        | VectorInstruction::Demote
+        | VectorInstruction::Promote
+        | VectorInstruction::ConvertAndTruncateWithSaturation(..)
+        | VectorInstruction::Convert(..)
+        | VectorInstruction::Load(_)
+        | VectorInstruction::Load8x8(..)
+        | VectorInstruction::Load16x4(..)
+        | VectorInstruction::Load32x2(..)
+        | VectorInstruction::Load8Splat(_)
+        | VectorInstruction::Load16Splat(_)
+        | VectorInstruction::Load32Splat(_)
+        | VectorInstruction::Load64Splat(_)
+        | VectorInstruction::Load32Zero(_)
+        | VectorInstruction::Load64Zero(_) => (1, 1),
+        VectorInstruction::BitSelect => (3, 1),
+        VectorInstruction::Store(_)
+        | VectorInstruction::Store8Lane(..)
+        | VectorInstruction::Store16Lane(..)
+        | VectorInstruction::Store32Lane(..)
+        | VectorInstruction::Store64Lane(..) => (2, 0),
+        _ => (2, 1),
+    }
+}
+
+/// Renders an instruction as its keyword followed by its immediate arguments.
+/// Structured instructions are rendered without their bodies.
+pub fn instruction_text(instruction: &Instruction) -> String {
+    match instruction {
+        Instruction::Numeric(instruction) => numeric_text(instruction),
+        Instruction::Vector(instruction) => vector_text(instruction),
+        Instruction::Reference(instruction) => match instruction {
+            ReferenceInstruction::Null(kind) => format!("ref.null {}", heap_type(*kind)),
+            ReferenceInstruction::IsNull => "ref.is_null".to_string(),
+            ReferenceInstruction::Function(function) => format!("ref.func {}", function),
+            ReferenceInstruction::AsNonNull => "ref.as_non_null".to_string(),
+            ReferenceInstruction::Equal => "ref.eq".to_string(),
+            ReferenceInstruction::Test(kind) => format!("ref.test {}", reference_type(*kind)),
+            ReferenceInstruction::Cast(kind) => format!("ref.cast {}", reference_type(*kind)),
+        },
+        Instruction::Parametric(ParametricInstruction::Drop) => "drop".to_string(),
+        Instruction::Parametric(ParametricInstruction::Select(None)) => "select".to_string(),
+        Instruction::Parametric(ParametricInstruction::Select(Some(kinds))) => {
+            let kinds: Vec<String> = kinds.iter().map(|kind| value_type(*kind)).collect();
+
+            format!("select (result {})", kinds.join(" "))
+        }
+        Instruction::Variable(instruction) => match instruction {
+            VariableInstruction::LocalGet(index) => format!("local.get {}", index),
+            VariableInstruction::LocalSet(index) => format!("local.set {}", index),
+            VariableInstruction::LocalTee(index) => format!("local.tee {}", index),
+            VariableInstruction::GlobalGet(index) => format!("global.get {}", index),
+            VariableInstruction::GlobalSet(index) => format!("global.set {}", index),
+        },
+        Instruction::Table(instruction) => match instruction {
+            TableInstruction::Get(table) => format!("table.get {}", table),
+            TableInstruction::Set(table) => format!("table.set {}", table),
+            TableInstruction::Size(table) => format!("table.size {}", table),
+            TableInstruction::Grow(table) => format!("table.grow {}", table),
+            TableInstruction::Fill(table) => format!("table.fill {}", table),
+            TableInstruction::Copy(target, source) => format!("table.copy {} {}", target, source),
+            TableInstruction::Init(element, table) => format!("table.init {} {}", table, element),
+            TableInstruction::ElementDrop(element) => format!("elem.drop {}", element),
+        },
+        Instruction::Memory(instruction) => memory_text(instruction),
+        Instruction::Atomic(instruction) => atomic_text(instruction),
+        Instruction::Aggregate(instruction) => aggregate_text(instruction),
+        Instruction::Control(instruction) => control_text(instruction),
+    }
+}
+
+/// Renders a control instruction.
+fn control_text(instruction: &ControlInstruction) -> String {
+    match instruction {
+        ControlInstruction::Nop => "nop".to_string(),
+        ControlInstruction::Unreachable => "unreachable".to_string(),
+        ControlInstruction::Block(kind, _) => format!("block{}", block_type(*kind)),
+        ControlInstruction::Loop(kind, _) => format!("loop{}", block_type(*kind)),
+        ControlInstruction::If(kind, _, _) => format!("if{}", block_type(*kind)),
+        ControlInstruction::TryTable(kind, catches, _) => {
+            let mut text = format!("try_table{}", block_type(*kind));
+
+            for catch in catches {
+                text.push_str(&match catch {
+                    CatchClause::Catch(tag, label) => format!(" (catch {} {})", tag, label),
+                    CatchClause::CatchReference(tag, label) => {
+                        format!(" (catch_ref {} {})", tag, label)
+                    }
+                    CatchClause::CatchAll(label) => format!(" (catch_all {})", label),
+                    CatchClause::CatchAllReference(label) => format!(" (catch_all_ref {})", label),
+                });
+            }
+
+            text
+        }
+        ControlInstruction::Branch(label) => format!("br {}", label),
+        ControlInstruction::BranchIf(label) => format!("br_if {}", label),
+        ControlInstruction::BranchTable(labels, default) => {
+            let mut text = String::from("br_table");
+
+            for label in labels.iter().chain(std::iter::once(default)) {
+                text.push_str(&format!(" {}", label));
+            }
+
+            text
+        }
+        ControlInstruction::Return => "return".to_string(),
+        ControlInstruction::Call(function) => format!("call {}", function),
+        ControlInstruction::CallIndirect(kind, table) => {
+            format!("call_indirect {} (type {})", table, kind)
+        }
+        ControlInstruction::ReturnCall(function) => format!("return_call {}", function),
+        ControlInstruction::ReturnCallIndirect(kind, table) => {
+            format!("return_call_indirect {} (type {})", table, kind)
+        }
+        ControlInstruction::CallRef(kind) => format!("call_ref {}", kind),
+        ControlInstruction::ReturnCallRef(kind) => format!("return_call_ref {}", kind),
+        ControlInstruction::BranchOnNull(label) => format!("br_on_null {}", label),
+        ControlInstruction::BranchOnNonNull(label) => format!("br_on_non_null {}", label),
+        ControlInstruction::BranchOnCast(label, source, target) => format!(
+            "br_on_cast {} {} {}",
+            label,
+            reference_type(*source),
+            reference_type(*target)
+        ),
+        ControlInstruction::BranchOnCastFail(label, source, target) => format!(
+            "br_on_cast_fail {} {} {}",
+            label,
+            reference_type(*source),
+            reference_type(*target)
+        ),
+        ControlInstruction::Throw(tag) => format!("throw {}", tag),
+        ControlInstruction::ThrowRef => "throw_ref".to_string(),
+    }
+}
+
+/// Renders the type of a structured instruction, preceded by a space unless it is empty.
+///
+/// See https://webassembly.github.io/spec/core/text/instructions.html#control-instructions
+fn block_type(kind: BlockType) -> String {
+    match kind {
+        BlockType::None => String::new(),
+        BlockType::Index(index) => format!(" (type {})", index),
+        BlockType::ValueType(kind) => format!(" (result {})", value_type(kind)),
+    }
+}
+
+/// Renders an aggregate instruction.
+fn aggregate_text(instruction: &AggregateInstruction) -> String {
+    match instruction {
+        AggregateInstruction::StructNew(kind) => format!("struct.new {}", kind),
+        AggregateInstruction::StructNewDefault(kind) => format!("struct.new_default {}", kind),
+        AggregateInstruction::StructGet(extension, kind, field) => {
+            format!("struct.get{} {} {}", packed_suffix(*extension), kind, field)
+        }
+        AggregateInstruction::StructSet(kind, field) => format!("struct.set {} {}", kind, field),
+        AggregateInstruction::ArrayNew(kind) => format!("array.new {}", kind),
+        AggregateInstruction::ArrayNewDefault(kind) => format!("array.new_default {}", kind),
+        AggregateInstruction::ArrayNewFixed(kind, length) => {
+            format!("array.new_fixed {} {}", kind, length)
+        }
+        AggregateInstruction::ArrayNewData(kind, data) => {
+            format!("array.new_data {} {}", kind, data)
+        }
+        AggregateInstruction::ArrayNewElement(kind, element) => {
+            format!("array.new_elem {} {}", kind, element)
+        }
+        AggregateInstruction::ArrayGet(extension, kind) => {
+            format!("array.get{} {}", packed_suffix(*extension), kind)
+        }
+        AggregateInstruction::ArraySet(kind) => format!("array.set {}", kind),
+        AggregateInstruction::ArrayLength => "array.len".to_string(),
+        AggregateInstruction::ArrayFill(kind) => format!("array.fill {}", kind),
+        AggregateInstruction::ArrayCopy(target, source) => {
+            format!("array.copy {} {}", target, source)
+        }
+        AggregateInstruction::ArrayInitData(kind, data) => {
+            format!("array.init_data {} {}", kind, data)
+        }
+        AggregateInstruction::ArrayInitElement(kind, element) => {
+            format!("array.init_elem {} {}", kind, element)
+        }
+        AggregateInstruction::ReferenceI31 => "ref.i31".to_string(),
+        AggregateInstruction::I31Get(extension) => format!("i31.get_{}", sign(*extension)),
+        AggregateInstruction::AnyConvertExternal => "any.convert_extern".to_string(),
+        AggregateInstruction::ExternalConvertAny => "extern.convert_any".to_string(),
+    }
+}
+
+/// The suffix of an access to a packed field (e.g., `_s`).
+fn packed_suffix(extension: Option<SignExtension>) -> String {
+    extension
+        .map(|extension| format!("_{}", sign(extension)))
+        .unwrap_or_default()
+}
+
+/// Renders a memory instruction.
+fn memory_text(instruction: &MemoryInstruction) -> String {
+    match instruction {
+        MemoryInstruction::Load(kind, argument) => memory_access(
+            &format!("{}.load", number(*kind)),
+            argument,
+            number_width(*kind),
+        ),
+        MemoryInstruction::Store(kind, argument) => memory_access(
+            &format!("{}.store", number(*kind)),
+            argument,
+            number_width(*kind),
+        ),
+        MemoryInstruction::Load8(kind, extension, argument) => memory_access(
+            &format!("{}.load8_{}", integer(*kind), sign(*extension)),
+            argument,
+            8,
+        ),
+        MemoryInstruction::Load16(kind, extension, argument) => memory_access(
+            &format!("{}.load16_{}", integer(*kind), sign(*extension)),
+            argument,
+            16,
+        ),
+        MemoryInstruction::Load32(extension, argument) => {
+            memory_access(&format!("i64.load32_{}", sign(*extension)), argument, 32)
+        }
+        MemoryInstruction::Store8(kind, argument) => {
+            memory_access(&format!("{}.store8", integer(*kind)), argument, 8)
+        }
+        MemoryInstruction::Store16(kind, argument) => {
+            memory_access(&format!("{}.store16", integer(*kind)), argument, 16)
+        }
+        MemoryInstruction::Store32(argument) => memory_access("i64.store32", argument, 32),
+        MemoryInstruction::Size(memory) => format!("memory.size {}", memory),
+        MemoryInstruction::Grow(memory) => format!("memory.grow {}", memory),
+        MemoryInstruction::Fill(memory) => format!("memory.fill {}", memory),
+        MemoryInstruction::Copy(target, source) => format!("memory.copy {} {}", target, source),
+        MemoryInstruction::Init(data, memory) => format!("memory.init {} {}", memory, data),
+        MemoryInstruction::DataDrop(data) => format!("data.drop {}", data),
+    }
+}
+
+/// Renders an atomic memory instruction.
+///
+/// See https://webassembly.github.io/threads/core/text/instructions.html#atomic-memory-instructions
+fn atomic_text(instruction: &AtomicInstruction) -> String {
+    let width = |kind: IntegerType| match kind {
+        IntegerType::I32 => 32,
+        IntegerType::I64 => 64,
+    };
+
+    match instruction {
+        AtomicInstruction::Notify(argument) => memory_access("memory.atomic.notify", argument, 32),
+        AtomicInstruction::Wait(kind, argument) => memory_access(
+            &format!("memory.atomic.wait{}", width(*kind)),
+            argument,
+            width(*kind),
+        ),
+        AtomicInstruction::Fence => "atomic.fence".to_string(),
+        AtomicInstruction::Load(kind, argument) => memory_access(
+            &format!("{}.atomic.load", integer(*kind)),
+            argument,
+            width(*kind),
+        ),
+        AtomicInstruction::Load8(kind, argument) => {
+            memory_access(&format!("{}.atomic.load8_u", integer(*kind)), argument, 8)
+        }
+        AtomicInstruction::Load16(kind, argument) => {
+            memory_access(&format!("{}.atomic.load16_u", integer(*kind)), argument, 16)
+        }
+        AtomicInstruction::Load32(argument) => memory_access("i64.atomic.load32_u", argument, 32),
+        AtomicInstruction::Store(kind, argument) => memory_access(
+            &format!("{}.atomic.store", integer(*kind)),
+            argument,
+            width(*kind),
+        ),
+        AtomicInstruction::Store8(kind, argument) => {
+            memory_access(&format!("{}.atomic.store8", integer(*kind)), argument, 8)
+        }
+        AtomicInstruction::Store16(kind, argument) => {
+            memory_access(&format!("{}.atomic.store16", integer(*kind)), argument, 16)
+        }
+        AtomicInstruction::Store32(argument) => memory_access("i64.atomic.store32", argument, 32),
+        AtomicInstruction::ReadModifyWrite(operation, kind, argument) => memory_access(
+            &format!(
+                "{}.atomic.rmw.{}",
+                integer(*kind),
+                atomic_operation(*operation)
+            ),
+            argument,
+            width(*kind),
+        ),
+        AtomicInstruction::ReadModifyWrite8(operation, kind, argument) => memory_access(
+            &format!(
+                "{}.atomic.rmw8.{}_u",
+                integer(*kind),
+                atomic_operation(*operation)
+            ),
+            argument,
+            8,
+        ),
+        AtomicInstruction::ReadModifyWrite16(operation, kind, argument) => memory_access(
+            &format!(
+                "{}.atomic.rmw16.{}_u",
+                integer(*kind),
+                atomic_operation(*operation)
+            ),
+            argument,
+            16,
+        ),
+        AtomicInstruction::ReadModifyWrite32(operation, argument) => memory_access(
+            &format!("i64.atomic.rmw32.{}_u", atomic_operation(*operation)),
+            argument,
+            32,
+        ),
+    }
+}
+
+/// The keyword of an atomic read-modify-write operation.
+fn atomic_operation(operation: AtomicOperation) -> &'static str {
+    match operation {
+        AtomicOperation::Add => "add",
+        AtomicOperation::Subtract => "sub",
+        AtomicOperation::And => "and",
+        AtomicOperation::Or => "or",
+        AtomicOperation::Xor => "xor",
+        AtomicOperation::Exchange => "xchg",
+        AtomicOperation::CompareExchange => "cmpxchg",
+    }
+}
+
+/// Renders an instruction that accesses memory with the given width in bits.
+/// The memory index, offset and alignment are omitted when they have their default values.
+///
+/// See https://webassembly.github.io/spec/core/text/instructions.html#memory-instructions
+fn memory_access(keyword: &str, argument: &MemoryArgument, width: u32) -> String {
+    let mut text = keyword.to_string();
+
+    if argument.memory() != 0 {
+        text.push_str(&format!(" {}", argument.memory()));
+    }
+
+    if argument.offset() != 0 {
+        text.push_str(&format!(" offset={}", argument.offset()));
+    }
+
+    if argument.align() != (width / 8).trailing_zeros() {
+        text.push_str(&format!(" align={}", 1u64 << argument.align()));
+    }
+
+    text
+}
+
+/// Renders a vector instruction that accesses a single lane of memory.
+fn lane_access(keyword: &str, argument: &MemoryArgument, width: u32, lane: u8) -> String {
+    format!("{} {}", memory_access(keyword, argument, width), lane)
+}
+
+/// Renders a numeric instruction.
+fn numeric_text(instruction: &NumericInstruction) -> String {
+    match instruction {
+        NumericInstruction::I32Constant(value) => format!("i32.const {}", value),
+        NumericInstruction::I64Constant(value) => format!("i64.const {}", value),
+        NumericInstruction::F32Constant(value) => format!("f32.const {}", f32_text(*value)),
+        NumericInstruction::F64Constant(value) => format!("f64.const {}", f64_text(*value)),
+        NumericInstruction::CountLeadingZeros(kind) => format!("{}.clz", integer(*kind)),
+        NumericInstruction::CountTrailingZeros(kind) => format!("{}.ctz", integer(*kind)),
+        NumericInstruction::CountOnes(kind) => format!("{}.popcnt", integer(*kind)),
+        NumericInstruction::AbsoluteValue(kind) => format!("{}.abs", float(*kind)),
+        NumericInstruction::Negate(kind) => format!("{}.neg", float(*kind)),
+        NumericInstruction::SquareRoot(kind) => format!("{}.sqrt", float(*kind)),
+        NumericInstruction::Ceiling(kind) => format!("{}.ceil", float(*kind)),
+        NumericInstruction::Floor(kind) => format!("{}.floor", float(*kind)),
+        NumericInstruction::Truncate(kind) => format!("{}.trunc", float(*kind)),
+        NumericInstruction::Nearest(kind) => format!("{}.nearest", float(*kind)),
+        NumericInstruction::Add(kind) => format!("{}.add", number(*kind)),
+        NumericInstruction::Subtract(kind) => format!("{}.sub", number(*kind)),
+        NumericInstruction::Multiply(kind) => format!("{}.mul", number(*kind)),
+        NumericInstruction::DivideInteger(kind, extension) => {
+            format!("{}.div_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::DivideFloat(kind) => format!("{}.div", float(*kind)),
+        NumericInstruction::Remainder(kind, extension) => {
+            format!("{}.rem_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::And(kind) => format!("{}.and", integer(*kind)),
+        NumericInstruction::Or(kind) => format!("{}.or", integer(*kind)),
+        NumericInstruction::Xor(kind) => format!("{}.xor", integer(*kind)),
+        NumericInstruction::ShiftLeft(kind) => format!("{}.shl", integer(*kind)),
+        NumericInstruction::ShiftRight(kind, extension) => {
+            format!("{}.shr_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::RotateLeft(kind) => format!("{}.rotl", integer(*kind)),
+        NumericInstruction::RotateRight(kind) => format!("{}.rotr", integer(*kind)),
+        NumericInstruction::Minimum(kind) => format!("{}.min", float(*kind)),
+        NumericInstruction::Maximum(kind) => format!("{}.max", float(*kind)),
+        NumericInstruction::CopySign(kind) => format!("{}.copysign", float(*kind)),
+        NumericInstruction::EqualToZero(kind) => format!("{}.eqz", integer(*kind)),
+        NumericInstruction::Equal(kind) => format!("{}.eq", number(*kind)),
+        NumericInstruction::NotEqual(kind) => format!("{}.ne", number(*kind)),
+        NumericInstruction::LessThanInteger(kind, extension) => {
+            format!("{}.lt_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::LessThanFloat(kind) => format!("{}.lt", float(*kind)),
+        NumericInstruction::GreaterThanInteger(kind, extension) => {
+            format!("{}.gt_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::GreaterThanFloat(kind) => format!("{}.gt", float(*kind)),
+        NumericInstruction::LessThanOrEqualToInteger(kind, extension) => {
+            format!("{}.le_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::LessThanOrEqualToFloat(kind) => format!("{}.le", float(*kind)),
+        NumericInstruction::GreaterThanOrEqualToInteger(kind, extension) => {
+            format!("{}.ge_{}", integer(*kind), sign(*extension))
+        }
+        NumericInstruction::GreaterThanOrEqualToFloat(kind) => format!("{}.ge", float(*kind)),
+        NumericInstruction::ExtendSigned8(kind) => format!("{}.extend8_s", integer(*kind)),
+        NumericInstruction::ExtendSigned16(kind) => format!("{}.extend16_s", integer(*kind)),
+        NumericInstruction::ExtendSigned32 => "i64.extend32_s".to_string(),
+        NumericInstruction::Wrap => "i32.wrap_i64".to_string(),
+        NumericInstruction::ExtendWithSignExtension(extension) => {
+            format!("i64.extend_i32_{}", sign(*extension))
+        }
+        NumericInstruction::ConvertAndTruncate(kind, source, extension) => format!(
+            "{}.trunc_{}_{}",
+            integer(*kind),
+            float(*source),
+            sign(*extension)
+        ),
+        NumericInstruction::ConvertAndTruncateWithSaturation(kind, source, extension) => format!(
+            "{}.trunc_sat_{}_{}",
+            integer(*kind),
+            float(*source),
+            sign(*extension)
+        ),
+        NumericInstruction::Demote => "f32.demote_f64".to_string(),
+        NumericInstruction::Promote => "f64.promote_f32".to_string(),
+        NumericInstruction::Convert(kind, source, extension) => format!(
+            "{}.convert_{}_{}",
+            float(*kind),
+            integer(*source),
+            sign(*extension)
+        ),
+        NumericInstruction::ReinterpretFloat(kind) => match kind {
+            IntegerType::I32 => "i32.reinterpret_f32".to_string(),
+            IntegerType::I64 => "i64.reinterpret_f64".to_string(),
+        },
+        NumericInstruction::ReinterpretInteger(kind) => match kind {
+            FloatType::F32 => "f32.reinterpret_i32".to_string(),
+            FloatType::F64 => "f64.reinterpret_i64".to_string(),
+        },
+    }
+}
+
+/// Renders a vector instruction.
+///
+/// See https://webassembly.github.io/spec/core/text/instructions.html#vector-instructions
+fn vector_text(instruction: &VectorInstruction) -> String {
+    match instruction {
+        VectorInstruction::Constant(value) => {
+            let lanes: Vec<String> = value
+                .to_le_bytes()
+                .chunks(4)
+                .map(|lane| {
+                    let lane = u32::from_le_bytes([lane[0], lane[1], lane[2], lane[3]]);
+
+                    format!("0x{:08x}", lane)
+                })
+                .collect();
+
+            format!("v128.const i32x4 {}", lanes.join(" "))
+        }
+        VectorInstruction::Not => "v128.not".to_string(),
+        VectorInstruction::And => "v128.and".to_string(),
+        VectorInstruction::AndNot => "v128.andnot".to_string(),
+        VectorInstruction::Or => "v128.or".to_string(),
+        VectorInstruction::Xor => "v128.xor".to_string(),
+        VectorInstruction::BitSelect => "v128.bitselect".to_string(),
+        VectorInstruction::AnyTrue => "v128.any_true".to_string(),
+        VectorInstruction::Shuffle(lanes) => {
+            let lanes: Vec<String> = lanes.iter().map(u8::to_string).collect();
+
+            format!("i8x16.shuffle {}", lanes.join(" "))
+        }
+        VectorInstruction::Swizzle => "i8x16.swizzle".to_string(),
+        VectorInstruction::Splat(shape) => format!("{}.splat", vector_shape(*shape)),
+        VectorInstruction::ExtractLane(shape, extension, lane) => format!(
+            "{}.extract_lane{} {}",
+            vector_shape(*shape),
+            packed_suffix(*extension),
+            lane
+        ),
+        VectorInstruction::ReplaceLane(shape, lane) => {
+            format!("{}.replace_lane {}", vector_shape(*shape), lane)
+        }
+        VectorInstruction::Equal(shape) => format!("{}.eq", vector_shape(*shape)),
+        VectorInstruction::NotEqual(shape) => format!("{}.ne", vector_shape(*shape)),
+        VectorInstruction::LessThanInteger(shape, extension) => {
+            format!("{}.lt_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::LessThanFloat(shape) => format!("{}.lt", float_shape(*shape)),
+        VectorInstruction::GreaterThanInteger(shape, extension) => {
+            format!("{}.gt_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::GreaterThanFloat(shape) => format!("{}.gt", float_shape(*shape)),
+        VectorInstruction::LessThanOrEqualToInteger(shape, extension) => {
+            format!("{}.le_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::LessThanOrEqualToFloat(shape) => format!("{}.le", float_shape(*shape)),
+        VectorInstruction::GreaterThanOrEqualToInteger(shape, extension) => {
+            format!("{}.ge_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::GreaterThanOrEqualToFloat(shape) => {
+            format!("{}.ge", float_shape(*shape))
+        }
+        VectorInstruction::AbsoluteValue(shape) => format!("{}.abs", vector_shape(*shape)),
+        VectorInstruction::Negate(shape) => format!("{}.neg", vector_shape(*shape)),
+        VectorInstruction::CountOnes => "i8x16.popcnt".to_string(),
+        VectorInstruction::SquareRoot(shape) => format!("{}.sqrt", float_shape(*shape)),
+        VectorInstruction::Ceiling(shape) => format!("{}.ceil", float_shape(*shape)),
+        VectorInstruction::Floor(shape) => format!("{}.floor", float_shape(*shape)),
+        VectorInstruction::Truncate(shape) => format!("{}.trunc", float_shape(*shape)),
+        VectorInstruction::Nearest(shape) => format!("{}.nearest", float_shape(*shape)),
+        VectorInstruction::AllTrue(shape) => format!("{}.all_true", integer_shape(*shape)),
+        VectorInstruction::Bitmask(shape) => format!("{}.bitmask", integer_shape(*shape)),
+        VectorInstruction::Narrow(shape, extension) => {
+            let source = match shape {
+                IntegerVectorShape::I8x16 => "i16x8",
+                _ => "i32x4",
+            };
+
+            format!(
+                "{}.narrow_{}_{}",
+                integer_shape(*shape),
+                source,
+                sign(*extension)
+            )
+        }
+        VectorInstruction::Extend(shape, half, extension) => format!(
+            "{}.extend_{}_{}_{}",
+            integer_shape(*shape),
+            vector_half(*half),
+            extended_shape(*shape),
+            sign(*extension)
+        ),
+        VectorInstruction::ShiftLeft(shape) => format!("{}.shl", integer_shape(*shape)),
+        VectorInstruction::ShiftRight(shape, extension) => {
+            format!("{}.shr_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::Add(shape) => format!("{}.add", vector_shape(*shape)),
+        VectorInstruction::Subtract(shape) => format!("{}.sub", vector_shape(*shape)),
+        VectorInstruction::Multiply(shape) => format!("{}.mul", vector_shape(*shape)),
+        VectorInstruction::AddSaturate(shape, extension) => {
+            format!("{}.add_sat_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::SubtractSaturate(shape, extension) => {
+            format!("{}.sub_sat_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::MinimumInteger(shape, extension) => {
+            format!("{}.min_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::MaximumInteger(shape, extension) => {
+            format!("{}.max_{}", integer_shape(*shape), sign(*extension))
+        }
+        VectorInstruction::DivideFloat(shape) => format!("{}.div", float_shape(*shape)),
+        VectorInstruction::MinimumFloat(shape) => format!("{}.min", float_shape(*shape)),
+        VectorInstruction::MaximumFloat(shape) => format!("{}.max", float_shape(*shape)),
+        VectorInstruction::PseudoMinimum(shape) => format!("{}.pmin", float_shape(*shape)),
+        VectorInstruction::PseudoMaximum(shape) => format!("{}.pmax", float_shape(*shape)),
+        VectorInstruction::AverageRounded(shape) => format!("{}.avgr_u", integer_shape(*shape)),
+        VectorInstruction::Q15MultiplyRoundSaturate => "i16x8.q15mulr_sat_s".to_string(),
+        VectorInstruction::DotProduct => "i32x4.dot_i16x8_s".to_string(),
+        VectorInstruction::ExtendMultiply(shape, half, extension) => format!(
+            "{}.extmul_{}_{}_{}",
+            integer_shape(*shape),
+            vector_half(*half),
+            extended_shape(*shape),
+            sign(*extension)
+        ),
+        VectorInstruction::ExtendAddPairwise(shape, extension) => format!(
+            "{}.extadd_pairwise_{}_{}",
+            integer_shape(*shape),
+            extended_shape(*shape),
+            sign(*extension)
+        ),
+        VectorInstruction::Demote => "f32x4.demote_f64x2_zero".to_string(),
+        VectorInstruction::Promote => "f64x2.promote_low_f32x4".to_string(),
+        VectorInstruction::ConvertAndTruncateWithSaturation(shape, extension) => match shape {
+            FloatVectorShape::F32x4 => format!("i32x4.trunc_sat_f32x4_{}", sign(*extension)),
+            FloatVectorShape::F64x2 => format!("i32x4.trunc_sat_f64x2_{}_zero", sign(*extension)),
+        },
+        VectorInstruction::Convert(shape, extension) => match shape {
+            FloatVectorShape::F32x4 => format!("f32x4.convert_i32x4_{}", sign(*extension)),
+            FloatVectorShape::F64x2 => format!("f64x2.convert_low_i32x4_{}", sign(*extension)),
+        },
+        VectorInstruction::Load(argument) => memory_access("v128.load", argument, 128),
+        VectorInstruction::Store(argument) => memory_access("v128.store", argument, 128),
+        VectorInstruction::Load8x8(extension, argument) => {
+            memory_access(&format!("v128.load8x8_{}", sign(*extension)), argument, 64)
+        }
+        VectorInstruction::Load16x4(extension, argument) => {
+            memory_access(&format!("v128.load16x4_{}", sign(*extension)), argument, 64)
+        }
+        VectorInstruction::Load32x2(extension, argument) => {
+            memory_access(&format!("v128.load32x2_{}", sign(*extension)), argument, 64)
+        }
+        VectorInstruction::Load8Splat(argument) => memory_access("v128.load8_splat", argument, 8),
+        VectorInstruction::Load16Splat(argument) => {
+            memory_access("v128.load16_splat", argument, 16)
+        }
+        VectorInstruction::Load32Splat(argument) => {
+            memory_access("v128.load32_splat", argument, 32)
+        }
+        VectorInstruction::Load64Splat(argument) => {
+            memory_access("v128.load64_splat", argument, 64)
+        }
+        VectorInstruction::Load32Zero(argument) => memory_access("v128.load32_zero", argument, 32),
+        VectorInstruction::Load64Zero(argument) => memory_access("v128.load64_zero", argument, 64),
+        VectorInstruction::Load8Lane(argument, lane) => {
+            lane_access("v128.load8_lane", argument, 8, *lane)
+        }
+        VectorInstruction::Load16Lane(argument, lane) => {
+            lane_access("v128.load16_lane", argument, 16, *lane)
+        }
+        VectorInstruction::Load32Lane(argument, lane) => {
+            lane_access("v128.load32_lane", argument, 32, *lane)
+        }
+        VectorInstruction::Load64Lane(argument, lane) => {
+            lane_access("v128.load64_lane", argument, 64, *lane)
+        }
+        VectorInstruction::Store8Lane(argument, lane) => {
+            lane_access("v128.store8_lane", argument, 8, *lane)
+        }
+        VectorInstruction::Store16Lane(argument, lane) => {
+            lane_access("v128.store16_lane", argument, 16, *lane)
+        }
+        VectorInstruction::Store32Lane(argument, lane) => {
+            lane_access("v128.store32_lane", argument, 32, *lane)
+        }
+        VectorInstruction::Store64Lane(argument, lane) => {
+            lane_access("v128.store64_lane", argument, 64, *lane)
+        }
+    }
+}
+
+/// Renders a 32-bit float so that it is parsed back to the same bits.
+/// Finite values use the shortest decimal representation that round-trips.
+///
+/// See https://webassembly.github.io/spec/core/text/values.html#floating-point
+fn f32_text(value: f32) -> String {
+    let bits = value.to_bits();
+    let sign = if bits >> 31 == 1 { "-" } else { "" };
+    let payload = bits & 0x7F_FFFF;
+
+    if value.is_nan() && payload == 0x40_0000 {
+        format!("{}nan", sign)
+    } else if value.is_nan() {
+        format!("{}nan:0x{:x}", sign, payload)
+    } else if value.is_infinite() {
+        format!("{}inf", sign)
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+/// Renders a 64-bit float so that it is parsed back to the same bits.
+/// Finite values use the shortest decimal representation that round-trips.
+///
+/// See https://webassembly.github.io/spec/core/text/values.html#floating-point
+fn f64_text(value: f64) -> String {
+    let bits = value.to_bits();
+    let sign = if bits >> 63 == 1 { "-" } else { "" };
+    let payload = bits & 0xF_FFFF_FFFF_FFFF;
+
+    if value.is_nan() && payload == 0x8_0000_0000_0000 {
+        format!("{}nan", sign)
+    } else if value.is_nan() {
+        format!("{}nan:0x{:x}", sign, payload)
+    } else if value.is_infinite() {
+        format!("{}inf", sign)
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+/// The width in bits of a number type.
+fn number_width(kind: NumberType) -> u32 {
+    match kind {
+        NumberType::I32 | NumberType::F32 => 32,
+        NumberType::I64 | NumberType::F64 => 64,
+    }
+}
+
+/// The keyword of a number type.
+fn number(kind: NumberType) -> &'static str {
+    match kind {
+        NumberType::I32 => "i32",
+        NumberType::I64 => "i64",
+        NumberType::F32 => "f32",
+        NumberType::F64 => "f64",
+    }
+}
+
+/// The keyword of an integer type.
+fn integer(kind: IntegerType) -> &'static str {
+    match kind {
+        IntegerType::I32 => "i32",
+        IntegerType::I64 => "i64",
+    }
+}
+
+/// The keyword of a float type.
+fn float(kind: FloatType) -> &'static str {
+    match kind {
+        FloatType::F32 => "f32",
+        FloatType::F64 => "f64",
+    }
+}
+
+/// The suffix of a sign extension.
+fn sign(extension: SignExtension) -> &'static str {
+    match extension {
+        SignExtension::Signed => "s",
+        SignExtension::Unsigned => "u",
+    }
+}
+
+/// The keyword of a vector shape.
+fn vector_shape(shape: VectorShape) -> &'static str {
+    match shape {
+        VectorShape::I8x16 => "i8x16",
+        VectorShape::I16x8 => "i16x8",
+        VectorShape::I32x4 => "i32x4",
+        VectorShape::I64x2 => "i64x2",
+        VectorShape::F32x4 => "f32x4",
+        VectorShape::F64x2 => "f64x2",
+    }
+}
+
+/// The keyword of an integer vector shape.
+fn integer_shape(shape: IntegerVectorShape) -> &'static str {
+    match shape {
+        IntegerVectorShape::I8x16 => "i8x16",
+        IntegerVectorShape::I16x8 => "i16x8",
+        IntegerVectorShape::I32x4 => "i32x4",
+        IntegerVectorShape::I64x2 => "i64x2",
+    }
+}
+
+/// The keyword of the shape with lanes half the width of the given shape,
+/// which is the operand shape of extending instructions.
+fn extended_shape(shape: IntegerVectorShape) -> &'static str {
+    match shape {
+        IntegerVectorShape::I8x16 | IntegerVectorShape::I16x8 => "i8x16",
+        IntegerVectorShape::I32x4 => "i16x8",
+        IntegerVectorShape::I64x2 => "i32x4",
+    }
+}
+
+/// The keyword of a float vector shape.
+fn float_shape(shape: FloatVectorShape) -> &'static str {
+    match shape {
+        FloatVectorShape::F32x4 => "f32x4",
+        FloatVectorShape::F64x2 => "f64x2",
+    }
+}
+
+/// The keyword of a half of a vector.
+fn vector_half(half: VectorHalf) -> &'static str {
+    match half {
+        VectorHalf::Low => "low",
+        VectorHalf::High => "high",
+    }
+}
+
+/// Renders a value type.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#value-types
+pub fn value_type(kind: ValueType) -> String {
+    match kind {
+        ValueType::I32 => "i32".to_string(),
+        ValueType::I64 => "i64".to_string(),
+        ValueType::F32 => "f32".to_string(),
+        ValueType::F64 => "f64".to_string(),
+        ValueType::V128 => "v128".to_string(),
+        ValueType::FunctionReference => "funcref".to_string(),
+        ValueType::ExternalReference => "externref".to_string(),
+        ValueType::ExceptionReference => "exnref".to_string(),
+        ValueType::Reference(kind) => reference_type(kind),
+    }
+}
+
+/// Renders a reference type, using the shorthand form for nullable abstract heap types.
+///
+/// See https://webassembly.github.io/gc/core/text/types.html#reference-types
+pub fn reference_type(kind: ReferenceType) -> String {
+    let shorthand = match kind {
+        ReferenceType::Function | ReferenceType::Nullable(HeapType::Function) => "funcref",
+        ReferenceType::External | ReferenceType::Nullable(HeapType::External) => "externref",
+        ReferenceType::Exception | ReferenceType::Nullable(HeapType::Exception) => "exnref",
+        ReferenceType::Nullable(HeapType::Any) => "anyref",
+        ReferenceType::Nullable(HeapType::Equality) => "eqref",
+        ReferenceType::Nullable(HeapType::I31) => "i31ref",
+        ReferenceType::Nullable(HeapType::Struct) => "structref",
+        ReferenceType::Nullable(HeapType::Array) => "arrayref",
+        ReferenceType::Nullable(HeapType::None) => "nullref",
+        ReferenceType::Nullable(HeapType::NoFunction) => "nullfuncref",
+        ReferenceType::Nullable(HeapType::NoExternal) => "nullexternref",
+        ReferenceType::Nullable(HeapType::NoException) => "nullexnref",
+        ReferenceType::Nullable(kind) => return format!("(ref null {})", heap_type(kind)),
+        ReferenceType::NonNullable(kind) => return format!("(ref {})", heap_type(kind)),
+    };
+
+    shorthand.to_string()
+}
+
+/// Renders a heap type.
+///
+/// See https://webassembly.github.io/gc/core/text/types.html#heap-types
+pub fn heap_type(kind: HeapType) -> String {
+    let keyword = match kind {
+        HeapType::Function => "func",
+        HeapType::External => "extern",
+        HeapType::Exception => "exn",
+        HeapType::Any => "any",
+        HeapType::Equality => "eq",
+        HeapType::I31 => "i31",
+        HeapType::Struct => "struct",
+        HeapType::Array => "array",
+        HeapType::None => "none",
+        HeapType::NoFunction => "nofunc",
+        HeapType::NoExternal => "noextern",
+        HeapType::NoException => "noexn",
+        HeapType::Index(index) => return index.to_string(),
+    };
+
+    keyword.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_floats() {
+        assert_eq!(f32_text(1.0), "1.0");
+        assert_eq!(f32_text(-0.0), "-0.0");
+        assert_eq!(f32_text(f32::NAN), "nan");
+        assert_eq!(f32_text(f32::from_bits(0xFF80_0001)), "-nan:0x1");
+        assert_eq!(f64_text(f64::NEG_INFINITY), "-inf");
+        assert_eq!(f64_text(1e-300), "1e-300");
+    }
+
+    #[test]
+    fn render_memory_arguments() {
+        let argument = MemoryArgument::with_memory(1, 3, 16);
+
+        assert_eq!(
+            memory_text(&MemoryInstruction::Load(NumberType::I64, argument)),
+            "i64.load 1 offset=16"
+        );
+        assert_eq!(
+            memory_text(&MemoryInstruction::Load(NumberType::I32, argument)),
+            "i32.load 1 offset=16 align=8"
+        );
+        assert_eq!(
+            vector_text(&VectorInstruction::Load8Lane(MemoryArgument::new(0, 0), 7)),
+            "v128.load8_lane 7"
+        );
+    }
+}
@@ -0,0 +1,980 @@
+//! Emit WebAssembly text format.
+//!
+//! Definitions are printed with numeric indices, annotated with index comments (e.g. `(;0;)`)
+//! to ease reading and diffing the output.
+//!
+//! See https://webassembly.github.io/spec/core/text/index.html
+
+mod instructions;
+
+use crate::emitter::EmitError;
+use crate::model::{
+    CompositeType, Data, DataMode, Element, ElementMode, Export, ExportDescription, Expression,
+    FieldType, Function, FunctionType, Global, GlobalType, Import, ImportDescription, Instruction,
+    Limit, MemoryType, Module, Mutability, Name, RecursiveType, ReferenceInstruction,
+    ReferenceType, StorageType, SubType, Table, TableType, TypeIndex,
+};
+use instructions::{reference_type, value_type};
+use std::io::Write;
+
+/// The layout of the instructions of a function body in the text format.
+///
+/// See https://webassembly.github.io/spec/core/text/instructions.html#folded-instructions
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TextStyle {
+    /// Each instruction is printed on its own line,
+    /// with structured instructions terminated by the 𝖾𝗇𝖽 keyword.
+    Flat,
+    /// Instructions are printed as S-expressions that enclose the instructions computing their operands.
+    Folded,
+}
+
+/// Options for the layout of a module emitted in the text format.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{TextOptions, TextStyle};
+///
+/// let options = TextOptions::new(TextStyle::Folded, 4);
+///
+/// assert_eq!(options.style(), TextStyle::Folded);
+/// assert_eq!(options.indentation(), 4);
+/// assert_eq!(TextOptions::default(), TextOptions::new(TextStyle::Flat, 2));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TextOptions {
+    style: TextStyle,
+    indentation: usize,
+}
+
+impl TextOptions {
+    /// Creates new options with the given style and number of spaces per level of indentation.
+    pub fn new(style: TextStyle, indentation: usize) -> Self {
+        TextOptions { style, indentation }
+    }
+
+    /// The layout of the instructions of a function body.
+    pub fn style(&self) -> TextStyle {
+        self.style
+    }
+
+    /// The number of spaces per level of indentation.
+    pub fn indentation(&self) -> usize {
+        self.indentation
+    }
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions::new(TextStyle::Flat, 2)
+    }
+}
+
+/// Emits a text representation of a WebAssembly Abstract Syntax Tree (AST) to a `Write` output.
+/// Returns the number of bytes written.
+///
+/// See https://webassembly.github.io/spec/core/text/index.html
+///
+/// # Examples
+/// ## Empty
+/// ```rust
+/// use wasm_ast::{emit_text, Module, TextOptions};
+///
+/// let mut buffer = Vec::new();
+/// let bytes = emit_text(&Module::empty(), TextOptions::default(), &mut buffer).unwrap();
+///
+/// assert_eq!(bytes, 8);
+/// assert_eq!(buffer, b"(module)");
+/// ```
+///
+/// ## Folded
+/// ```rust
+/// use wasm_ast::{
+///     emit_text, Expression, Function, FunctionType, Module, NumericInstruction, ResultType,
+///     TextOptions, TextStyle, ValueType, VariableInstruction,
+/// };
+///
+/// let mut builder = Module::builder();
+/// let kind = FunctionType::new(ResultType::empty(), vec![ValueType::I32].into());
+///
+/// builder.add_function_type(kind).unwrap();
+/// builder
+///     .add_function(Function::new(
+///         0,
+///         ResultType::empty(),
+///         Expression::new(vec![
+///             NumericInstruction::I32Constant(1).into(),
+///             NumericInstruction::I32Constant(2).into(),
+///             NumericInstruction::Add(wasm_ast::NumberType::I32).into(),
+///         ]),
+///     ))
+///     .unwrap();
+///
+/// let mut buffer = Vec::new();
+///
+/// emit_text(&builder.build(), TextOptions::new(TextStyle::Folded, 2), &mut buffer).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(buffer).unwrap(),
+///     "(module
+///   (type (;0;) (func (result i32)))
+///   (func (;0;) (type 0) (result i32)
+///     (i32.add
+///       (i32.const 1)
+///       (i32.const 2))))"
+/// );
+/// ```
+pub fn emit_text<O: Write>(
+    module: &Module,
+    options: TextOptions,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    #[cfg(feature = "parser")]
+    for function in module.functions().unwrap_or_default() {
+        function.body.decode().map_err(EmitError::InvalidBinary)?;
+    }
+
+    let mut printer = Printer::new(module, options);
+
+    printer.module();
+    output.write_all(printer.text.as_bytes())?;
+
+    Ok(printer.text.len())
+}
+
+/// Prints a module in the text format, one line at a time.
+struct Printer<'m> {
+    module: &'m Module,
+    options: TextOptions,
+    types: Vec<&'m SubType>,
+    functions: Vec<TypeIndex>,
+    tags: Vec<TypeIndex>,
+    labels: Vec<usize>,
+    results: usize,
+    depth: usize,
+    text: String,
+}
+
+impl<'m> Printer<'m> {
+    /// Creates a printer for the given module.
+    fn new(module: &'m Module, options: TextOptions) -> Self {
+        let imports = module.imports().unwrap_or_default();
+        let functions = imports
+            .iter()
+            .filter_map(|import| match import.description() {
+                ImportDescription::Function(kind) => Some(*kind),
+                _ => None,
+            })
+            .chain(
+                module
+                    .functions()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(Function::kind),
+            )
+            .collect();
+        let tags = imports
+            .iter()
+            .filter_map(|import| match import.description() {
+                ImportDescription::Tag(kind) => Some(kind.kind()),
+                _ => None,
+            })
+            .chain(
+                module
+                    .tags()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|tag| tag.kind().kind()),
+            )
+            .collect();
+
+        Printer {
+            module,
+            options,
+            types: module.sub_types().collect(),
+            functions,
+            tags,
+            labels: Vec::new(),
+            results: 0,
+            depth: 0,
+            text: String::new(),
+        }
+    }
+
+    /// Starts a new line at the current depth with the given text.
+    fn line(&mut self, text: &str) {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+
+        self.text
+            .push_str(&" ".repeat(self.depth * self.options.indentation));
+        self.text.push_str(text);
+    }
+
+    /// Starts a new line that opens a list with the given text, increasing the depth.
+    fn open(&mut self, text: &str) {
+        self.line(&format!("({}", text));
+        self.depth += 1;
+    }
+
+    /// Closes the innermost list opened by `open`.
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.text.push(')');
+    }
+
+    /// Prints the module and all of its fields.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#modules
+    fn module(&mut self) {
+        let module = self.module;
+
+        self.open("module");
+        self.types(module.types().unwrap_or_default());
+
+        let mut counts = [0u32; 5];
+
+        for import in module.imports().unwrap_or_default() {
+            self.import(import, &mut counts);
+        }
+
+        let [functions, tables, memories, globals, tags] = counts;
+
+        for (index, function) in module.functions().unwrap_or_default().iter().enumerate() {
+            self.function(functions + index as u32, function);
+        }
+
+        for (index, table) in module.tables().unwrap_or_default().iter().enumerate() {
+            self.table(tables + index as u32, table);
+        }
+
+        for (index, memory) in module.memories().unwrap_or_default().iter().enumerate() {
+            let header = format!(
+                "(memory (;{};) {})",
+                memories + index as u32,
+                memory_type(memory.kind())
+            );
+
+            self.line(&header);
+        }
+
+        for (index, tag) in module.tags().unwrap_or_default().iter().enumerate() {
+            let header = format!(
+                "(tag (;{};) (type {}))",
+                tags + index as u32,
+                tag.kind().kind()
+            );
+
+            self.line(&header);
+        }
+
+        for (index, global) in module.globals().unwrap_or_default().iter().enumerate() {
+            self.global(globals + index as u32, global);
+        }
+
+        for export in module.exports().unwrap_or_default() {
+            self.export(export);
+        }
+
+        if let Some(start) = module.start() {
+            self.line(&format!("(start {})", start.function()));
+        }
+
+        for (index, element) in module.elements().unwrap_or_default().iter().enumerate() {
+            self.element(index, element);
+        }
+
+        for (index, data) in module.data().unwrap_or_default().iter().enumerate() {
+            self.data(index, data);
+        }
+
+        self.close();
+    }
+
+    /// Prints the type definitions, grouping the members of recursion groups.
+    ///
+    /// See https://webassembly.github.io/gc/core/text/types.html#recursive-types
+    fn types(&mut self, types: &[RecursiveType]) {
+        let mut index = 0;
+
+        for group in types {
+            if group.len() == 1 {
+                self.line(&format!(
+                    "(type (;{};) {})",
+                    index,
+                    sub_type(&group.types()[0])
+                ));
+                index += 1;
+            } else {
+                self.open("rec");
+
+                for kind in group.types() {
+                    self.line(&format!("(type (;{};) {})", index, sub_type(kind)));
+                    index += 1;
+                }
+
+                self.close();
+            }
+        }
+    }
+
+    /// Prints an import, assigning it the next index of its index space.
+    /// The counts are kept in the order of functions, tables, memories, globals and tags.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#imports
+    fn import(&mut self, import: &Import, counts: &mut [u32; 5]) {
+        let (slot, description) = match import.description() {
+            ImportDescription::Function(kind) => {
+                (0, format!("func (;{};) (type {})", counts[0], kind))
+            }
+            ImportDescription::Table(kind) => {
+                (1, format!("table (;{};) {}", counts[1], table_type(kind)))
+            }
+            ImportDescription::Memory(kind) => {
+                (2, format!("memory (;{};) {}", counts[2], memory_type(kind)))
+            }
+            ImportDescription::Global(kind) => {
+                (3, format!("global (;{};) {}", counts[3], global_type(kind)))
+            }
+            ImportDescription::Tag(kind) => {
+                (4, format!("tag (;{};) (type {})", counts[4], kind.kind()))
+            }
+        };
+
+        counts[slot] += 1;
+        self.line(&format!(
+            "(import {} {} ({}))",
+            name(import.module()),
+            name(import.name()),
+            description
+        ));
+    }
+
+    /// The function type at the given type index, if the type at that index is a function type.
+    fn function_type(&self, index: TypeIndex) -> Option<&'m FunctionType> {
+        self.types.get(index as usize)?.kind().function()
+    }
+
+    /// Prints a function definition, including the signature of its type for readability.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#functions
+    fn function(&mut self, index: u32, function: &Function) {
+        let kind = self.function_type(function.kind());
+        let mut header = format!("func (;{};) (type {})", index, function.kind());
+
+        if let Some(kind) = kind {
+            header.push_str(&signature(kind));
+        }
+
+        self.open(&header);
+
+        if !function.locals().is_empty() {
+            self.line(&format!(
+                "(local {})",
+                value_types(function.locals().kinds())
+            ));
+        }
+
+        self.results = kind.map(|kind| kind.results().len()).unwrap_or_default();
+        self.labels = vec![self.results];
+        self.instructions(function.body().instructions());
+        self.labels.clear();
+        self.close();
+    }
+
+    /// Prints a table, including its initializer.
+    ///
+    /// See https://webassembly.github.io/function-references/core/text/modules.html#tables
+    fn table(&mut self, index: u32, table: &Table) {
+        let line = match table.initializer() {
+            Some(initializer) => format!(
+                "(table (;{};) {} {})",
+                index,
+                table_type(table.kind()),
+                self.inline(initializer)
+            ),
+            None => format!("(table (;{};) {})", index, table_type(table.kind())),
+        };
+
+        self.line(&line);
+    }
+
+    /// Prints a global definition with its initializer on the same line.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#globals
+    fn global(&mut self, index: u32, global: &Global) {
+        let initializer = self.inline(global.initializer());
+
+        self.line(&format!(
+            "(global (;{};) {} {})",
+            index,
+            global_type(global.kind()),
+            initializer
+        ));
+    }
+
+    /// Prints an export.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#exports
+    fn export(&mut self, export: &Export) {
+        let description = match export.description() {
+            ExportDescription::Function(index) => format!("func {}", index),
+            ExportDescription::Table(index) => format!("table {}", index),
+            ExportDescription::Memory(index) => format!("memory {}", index),
+            ExportDescription::Global(index) => format!("global {}", index),
+            ExportDescription::Tag(index) => format!("tag {}", index),
+        };
+
+        self.line(&format!(
+            "(export {} ({}))",
+            name(export.name()),
+            description
+        ));
+    }
+
+    /// Prints an element segment.
+    /// Segments of function references initialized by 𝗋𝖾𝖿.𝖿𝗎𝗇𝖼 are abbreviated to a list of
+    /// function indices.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#element-segments
+    fn element(&mut self, index: usize, element: &Element) {
+        let mut text = format!("(elem (;{};)", index);
+
+        match element.mode() {
+            ElementMode::Passive => {}
+            ElementMode::Declarative => text.push_str(" declare"),
+            ElementMode::Active(table, offset) => {
+                if *table != 0 {
+                    text.push_str(&format!(" (table {})", table));
+                }
+
+                text.push_str(&format!(" (offset {})", self.inline(offset)));
+            }
+        }
+
+        let functions: Option<Vec<String>> = element
+            .initializers()
+            .iter()
+            .map(|initializer| match initializer.instructions() {
+                [Instruction::Reference(ReferenceInstruction::Function(function))] => {
+                    Some(function.to_string())
+                }
+                _ => None,
+            })
+            .collect();
+
+        match functions {
+            Some(functions) if element.kind() == ReferenceType::Function => {
+                text.push_str(" func");
+
+                for function in functions {
+                    text.push(' ');
+                    text.push_str(&function);
+                }
+            }
+            _ => {
+                text.push(' ');
+                text.push_str(&reference_type(element.kind()));
+
+                for initializer in element.initializers() {
+                    text.push_str(&format!(" (item {})", self.inline(initializer)));
+                }
+            }
+        }
+
+        text.push(')');
+        self.line(&text);
+    }
+
+    /// Prints a data segment.
+    ///
+    /// See https://webassembly.github.io/spec/core/text/modules.html#data-segments
+    fn data(&mut self, index: usize, data: &Data) {
+        let mut text = format!("(data (;{};)", index);
+
+        if let DataMode::Active(memory, offset) = data.mode() {
+            if *memory != 0 {
+                text.push_str(&format!(" (memory {})", memory));
+            }
+
+            text.push_str(&format!(" (offset {})", self.inline(offset)));
+        }
+
+        text.push(' ');
+        text.push_str(&string(data.initializer()));
+        text.push(')');
+        self.line(&text);
+    }
+
+    /// Prints a sequence of instructions, one per line, in the configured style.
+    fn instructions(&mut self, instructions: &[Instruction]) {
+        match self.options.style {
+            TextStyle::Flat => {
+                for instruction in instructions {
+                    self.flat(instruction);
+                }
+            }
+            TextStyle::Folded => {
+                for node in self.fold(instructions) {
+                    self.folded(&node);
+                }
+            }
+        }
+    }
+
+    /// Renders a constant expression on a single line in the configured style.
+    ///
+    /// See https://webassembly.github.io/spec/core/valid/instructions.html#constant-expressions
+    fn inline(&self, expression: &Expression) -> String {
+        let parts: Vec<String> = match self.options.style {
+            TextStyle::Flat => expression
+                .instructions()
+                .iter()
+                .map(|instruction| self.inline_flat(instruction))
+                .collect(),
+            TextStyle::Folded => self
+                .fold(expression.instructions())
+                .iter()
+                .map(|node| self.inline_folded(node))
+                .collect(),
+        };
+
+        parts.join(" ")
+    }
+}
+
+/// Renders a sub type, omitting the `sub` keyword for final types without supertypes.
+///
+/// See https://webassembly.github.io/gc/core/text/types.html#recursive-types
+fn sub_type(kind: &SubType) -> String {
+    let composite = composite_type(kind.kind());
+
+    if kind.is_final() && kind.supertypes().is_empty() {
+        return composite;
+    }
+
+    let mut text = String::from("(sub");
+
+    if kind.is_final() {
+        text.push_str(" final");
+    }
+
+    for supertype in kind.supertypes() {
+        text.push_str(&format!(" {}", supertype));
+    }
+
+    format!("{} {})", text, composite)
+}
+
+/// Renders a composite type.
+///
+/// See https://webassembly.github.io/gc/core/text/types.html#composite-types
+fn composite_type(kind: &CompositeType) -> String {
+    match kind {
+        CompositeType::Function(kind) => format!("(func{})", signature(kind)),
+        CompositeType::Struct(kind) => {
+            let mut text = String::from("(struct");
+
+            for field in kind.fields() {
+                text.push_str(&format!(" (field {})", field_type(*field)));
+            }
+
+            text.push(')');
+            text
+        }
+        CompositeType::Array(kind) => format!("(array {})", field_type(kind.field())),
+    }
+}
+
+/// Renders a field type of an aggregate.
+fn field_type(kind: FieldType) -> String {
+    let storage = match kind.kind() {
+        StorageType::Value(kind) => value_type(kind),
+        StorageType::Packed(crate::model::PackedType::I8) => "i8".to_string(),
+        StorageType::Packed(crate::model::PackedType::I16) => "i16".to_string(),
+    };
+
+    match kind.mutability() {
+        Mutability::Mutable => format!("(mut {})", storage),
+        Mutability::Immutable => storage,
+    }
+}
+
+/// Renders the parameters and results of a function type, each preceded by a space.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#function-types
+fn signature(kind: &FunctionType) -> String {
+    let mut text = String::new();
+
+    if !kind.parameters().is_empty() {
+        text.push_str(&format!(
+            " (param {})",
+            value_types(kind.parameters().kinds())
+        ));
+    }
+
+    if !kind.results().is_empty() {
+        text.push_str(&format!(
+            " (result {})",
+            value_types(kind.results().kinds())
+        ));
+    }
+
+    text
+}
+
+/// Renders a space-separated sequence of value types.
+fn value_types(kinds: &[crate::model::ValueType]) -> String {
+    kinds
+        .iter()
+        .map(|kind| value_type(*kind))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Renders limits, preceded by the index type when it is not the default.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#limits
+fn limit(limit: &Limit) -> String {
+    let mut text = match limit.index_type() {
+        crate::model::IndexType::I32 => String::new(),
+        crate::model::IndexType::I64 => String::from("i64 "),
+    };
+
+    text.push_str(&limit.min().to_string());
+
+    if let Some(max) = limit.max() {
+        text.push_str(&format!(" {}", max));
+    }
+
+    text
+}
+
+/// Renders a table type.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#table-types
+fn table_type(kind: &TableType) -> String {
+    format!("{} {}", limit(kind.limits()), reference_type(kind.kind()))
+}
+
+/// Renders a memory type.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#memory-types
+fn memory_type(kind: &MemoryType) -> String {
+    if kind.is_shared() {
+        format!("{} shared", limit(kind.limits()))
+    } else {
+        limit(kind.limits())
+    }
+}
+
+/// Renders a global type.
+///
+/// See https://webassembly.github.io/spec/core/text/types.html#global-types
+fn global_type(kind: &GlobalType) -> String {
+    match kind.mutability() {
+        Mutability::Mutable => format!("(mut {})", value_type(kind.kind())),
+        Mutability::Immutable => value_type(kind.kind()),
+    }
+}
+
+/// Renders a name as a string literal.
+///
+/// See https://webassembly.github.io/spec/core/text/values.html#names
+fn name(name: &Name) -> String {
+    let mut text = String::from("\"");
+
+    for character in name.as_str().chars() {
+        match character {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            character if character.is_control() => {
+                let mut bytes = [0; 4];
+
+                for byte in character.encode_utf8(&mut bytes).bytes() {
+                    text.push_str(&format!("\\{:02x}", byte));
+                }
+            }
+            character => text.push(character),
+        }
+    }
+
+    text.push('"');
+    text
+}
+
+/// Renders arbitrary bytes as a string literal, escaping all non-printable ASCII bytes.
+///
+/// See https://webassembly.github.io/spec/core/text/values.html#strings
+fn string(bytes: &[u8]) -> String {
+    let mut text = String::from("\"");
+
+    for byte in bytes {
+        match byte {
+            b'"' => text.push_str("\\\""),
+            b'\\' => text.push_str("\\\\"),
+            0x20..=0x7E => text.push(*byte as char),
+            _ => text.push_str(&format!("\\{:02x}", byte)),
+        }
+    }
+
+    text.push('"');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        ControlInstruction, FunctionType, NumberType, NumericInstruction, ResultType, ValueType,
+    };
+
+    fn emit(module: &Module, options: TextOptions) -> String {
+        let mut buffer = Vec::new();
+        let bytes = emit_text(module, options, &mut buffer).unwrap();
+
+        assert_eq!(bytes, buffer.len());
+
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn emit_flat_blocks() {
+        let mut builder = Module::builder();
+
+        builder
+            .add_function_type(FunctionType::new(ResultType::empty(), ResultType::empty()))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                vec![ValueType::I64].into(),
+                Expression::new(vec![
+                    ControlInstruction::Block(
+                        crate::model::BlockType::ValueType(ValueType::I32),
+                        Expression::new(vec![
+                            NumericInstruction::I32Constant(-1).into(),
+                            ControlInstruction::BranchIf(0).into(),
+                        ]),
+                    )
+                    .into(),
+                    ControlInstruction::Nop.into(),
+                ]),
+            ))
+            .unwrap();
+
+        let text = emit(&builder.build(), TextOptions::new(TextStyle::Flat, 4));
+
+        assert_eq!(
+            text,
+            "(module
+    (type (;0;) (func))
+    (func (;0;) (type 0)
+        (local i64)
+        block (result i32)
+            i32.const -1
+            br_if 0
+        end
+        nop))"
+        );
+    }
+
+    #[test]
+    fn emit_strings() {
+        assert_eq!(string(b"a\"\\\x00\xff"), "\"a\\\"\\\\\\00\\ff\"");
+        assert_eq!(name(&Name::from("λ\n")), "\"λ\\0a\"");
+    }
+
+    #[test]
+    fn emit_inline_constant() {
+        let mut builder = Module::builder();
+        let initializer = Expression::new(vec![
+            NumericInstruction::I64Constant(1).into(),
+            NumericInstruction::I64Constant(2).into(),
+            NumericInstruction::Add(NumberType::I64).into(),
+        ]);
+
+        builder
+            .add_global(Global::immutable(ValueType::I64, initializer))
+            .unwrap();
+
+        let module = builder.build();
+
+        assert_eq!(
+            emit(&module, TextOptions::default()),
+            "(module\n  (global (;0;) i64 i64.const 1 i64.const 2 i64.add))"
+        );
+        assert_eq!(
+            emit(&module, TextOptions::new(TextStyle::Folded, 2)),
+            "(module\n  (global (;0;) i64 (i64.add (i64.const 1) (i64.const 2))))"
+        );
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn emit_invalid_lazy_body() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\xFF\x0B";
+        let module = crate::parse_binary_lazy(bytes).unwrap();
+        let mut buffer = Vec::new();
+
+        match emit_text(&module, TextOptions::default(), &mut buffer) {
+            Err(EmitError::InvalidBinary(error)) => assert_eq!(error.function(), Some(0)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        assert!(buffer.is_empty());
+        let mut decoded = module.clone().into_builder();
+
+        decoded.set_functions(Some(vec![Function::new(
+            0,
+            ResultType::empty(),
+            Expression::empty(),
+        )]));
+
+        assert_ne!(module, decoded.build());
+    }
+
+    #[cfg(feature = "text")]
+    mod round_trip {
+        use super::*;
+        use crate::parser::parse_text;
+
+        /// Asserts that emitting the module in either style and parsing the text yields the
+        /// same module. Modules are compared by their debug representation and emitted text,
+        /// since NaN constants are not equal to themselves.
+        fn assert_round_trip(text: &str) {
+            let module = parse_text(text).unwrap();
+
+            for style in [TextStyle::Flat, TextStyle::Folded] {
+                let emitted = emit(&module, TextOptions::new(style, 2));
+                let parsed = parse_text(&emitted).unwrap_or_else(|error| {
+                    panic!("{:?} for:\n{}", error, emitted);
+                });
+
+                assert_eq!(format!("{:?}", parsed), format!("{:?}", module));
+                assert_eq!(emit(&parsed, TextOptions::new(style, 2)), emitted);
+            }
+        }
+
+        #[test]
+        fn round_trip_module() {
+            assert_round_trip(
+                r#"(module
+                    (type $pair (func (param i32 i64) (result i64 i32)))
+                    (rec
+                        (type $node (sub (struct (field $value i32) (field $next (ref null $node)))))
+                        (type $leaf (sub final $node (struct (field i32) (field (ref null $node)))))
+                        (type $bytes (array (mut i8))))
+                    (import "env" "log" (func $log (param i32)))
+                    (import "env" "table" (table 1 funcref))
+                    (import "env" "memory" (memory i64 1 2 shared))
+                    (global $counter (import "env" "counter") (mut i32))
+                    (tag $error (import "env" "error") (param i32))
+                    (func $swap (export "swap") (type $pair) (param $a i32) (param $b i64) (result i64 i32)
+                        local.get $b
+                        local.get $a)
+                    (func $store (param i32) (local $tmp i64) (local f32 v128)
+                        (i64.store offset=8 align=4 (i64.const 0) (local.get $tmp))
+                        (memory.init $passive (i64.const 0) (i32.const 0) (i32.const 4))
+                        (data.drop $passive)
+                        (f32.store (i64.const 4) (f32.const -0x1.8p-3))
+                        (f64.store (i64.const 8) (f64.const nan:0x123))
+                        (v128.store (i64.const 16) (v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 255)))
+                    (table $functions 2 10 funcref)
+                    (table $references i64 0 (ref null $node))
+                    (global $limit i64 (i64.const -42))
+                    (global $node (ref null $node) (ref.null $node))
+                    (tag $fatal)
+                    (export "counter" (global $counter))
+                    (export "\u{3bb}\"quoted\"" (tag $fatal))
+                    (start $entry)
+                    (func $entry)
+                    (elem (table $functions) (i32.const 1) func $entry $swap)
+                    (elem declare func $store)
+                    (elem $items funcref (ref.func $log) (item ref.null func))
+                    (data (i64.const 16) "\00\01\02\03\"\\")
+                    (data $passive "hello"))"#,
+            );
+        }
+
+        #[test]
+        fn round_trip_table_initializer() {
+            assert_round_trip(
+                "(module (type $t (func)) (func $f (type $t)) (table 1 (ref $t) (ref.func $f)))",
+            );
+        }
+
+        #[test]
+        fn round_trip_instructions() {
+            assert_round_trip(
+                r#"(module
+                    (type $point (struct (field $x (mut i32)) (field $y i32)))
+                    (type $values (array (mut i32)))
+                    (tag $oops (param i32))
+                    (memory 1)
+                    (func $control (param $n i32) (result i32)
+                        (block $outer (result i32)
+                            (loop $inner (param i32) (result i32)
+                                (br_if $inner (local.get $n))
+                                (if (result i32 i32) (local.get $n)
+                                    (then (i32.const 1) (i32.const 2))
+                                    (else (i32.const 3) (i32.const 4)))
+                                drop
+                                (br_table $inner $outer 0))
+                            block $exit (param i32) (result i32)
+                                try_table (result i32) (catch $oops $exit) (catch_all_ref $outer)
+                                    (throw $oops (i32.const 9))
+                                end
+                            end $exit)
+                        (if (local.get 0) (then (return (i32.const 1))))
+                        (call $control (i32.const 2))
+                        (call_indirect (param i32) (result i32) (i32.const 7) (i32.const 0))
+                        i32.add)
+                    (table 1 funcref)
+                    (func $vectors (param v128) (result v128)
+                        local.get 0
+                        v128.const f32x4 1 -2.5 inf -nan
+                        i32x4.add
+                        i8x16.shuffle 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 31
+                        i16x8.extend_low_i8x16_s
+                        f64x2.convert_low_i32x4_u
+                        i32x4.trunc_sat_f64x2_s_zero
+                        i16x8.extract_lane_u 3
+                        i16x8.splat
+                        i32.const 0
+                        v128.load32_lane offset=4 1
+                        i64.const 16
+                        i64x2.replace_lane 1
+                        i32.const 0
+                        v128.load8x8_s align=2
+                        v128.bitselect)
+                    (func $references (param $p (ref null $point)) (result i32)
+                        (struct.set $point $x (local.get $p) (i32.const 7))
+                        (struct.get $point $y (local.get $p))
+                        (array.len (array.new_fixed $values 2 (i32.const 1) (i32.const 2)))
+                        (i31.get_u (ref.i31 (i32.const 9)))
+                        (br_on_cast 0 anyref (ref i31) (ref.null any))
+                        drop
+                        (select (result i32) (i32.const 0) (i32.const 1) (i32.const 2))
+                        i32.add
+                        i32.add)
+                    (func $atomics (param i32)
+                        (i32.atomic.rmw8.cmpxchg_u offset=4 (i32.const 0) (i32.const 1) (i32.const 2))
+                        (memory.atomic.notify (i32.const 0) (i32.const 1))
+                        i64.extend_i32_u
+                        i32.wrap_i64
+                        (i64.atomic.rmw32.xchg_u (i32.const 0) (i64.const 1))
+                        (i64.atomic.rmw.add (i32.const 0) (i64.const 1))
+                        i64.add
+                        i32.wrap_i64
+                        i32.add
+                        drop
+                        (memory.copy (i32.const 0) (i32.const 1) (i32.const 2))
+                        (table.size)
+                        drop
+                        atomic.fence))"#,
+            );
+        }
+    }
+}
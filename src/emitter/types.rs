@@ -1,17 +1,69 @@
 use crate::emitter::errors::EmitError;
-use crate::emitter::values::{emit_byte, emit_u32, emit_vector};
+use crate::emitter::values::{emit_byte, emit_i64, emit_u32, emit_u64, emit_vector};
 use crate::model::{
-    FunctionType, GlobalType, Limit, MemoryType, Mutability, ReferenceType, ResultType, TableType,
-    ValueType,
+    ArrayType, CompositeType, FieldType, FunctionType, GlobalType, HeapType, IndexType, Limit,
+    MemoryType, Mutability, PackedType, RecursiveType, ReferenceType, ResultType, StorageType,
+    StructType, SubType, TableType, TagType, ValueType,
 };
 use std::borrow::Borrow;
 use std::io::Write;
 
+pub fn emit_heap_type<O: Write + ?Sized>(
+    kind: HeapType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    match kind {
+        HeapType::Function => emit_byte(0x70u8, output),
+        HeapType::External => emit_byte(0x6Fu8, output),
+        HeapType::Exception => emit_byte(0x69u8, output),
+        HeapType::Any => emit_byte(0x6Eu8, output),
+        HeapType::Equality => emit_byte(0x6Du8, output),
+        HeapType::I31 => emit_byte(0x6Cu8, output),
+        HeapType::Struct => emit_byte(0x6Bu8, output),
+        HeapType::Array => emit_byte(0x6Au8, output),
+        HeapType::None => emit_byte(0x71u8, output),
+        HeapType::NoFunction => emit_byte(0x73u8, output),
+        HeapType::NoExternal => emit_byte(0x72u8, output),
+        HeapType::NoException => emit_byte(0x74u8, output),
+        HeapType::Index(index) => emit_i64(index as i64, output),
+    }
+}
+
 pub fn emit_reference_type<O: Write + ?Sized>(
     kind: ReferenceType,
     output: &mut O,
 ) -> Result<usize, EmitError> {
-    emit_value_type(ValueType::from(kind), output)
+    let mut bytes = 0;
+
+    match kind {
+        ReferenceType::Function => bytes += emit_heap_type(HeapType::Function, output)?,
+        ReferenceType::External => bytes += emit_heap_type(HeapType::External, output)?,
+        ReferenceType::Exception => bytes += emit_heap_type(HeapType::Exception, output)?,
+        ReferenceType::Nullable(
+            heap_type @ (HeapType::Function
+            | HeapType::External
+            | HeapType::Exception
+            | HeapType::Any
+            | HeapType::Equality
+            | HeapType::I31
+            | HeapType::Struct
+            | HeapType::Array
+            | HeapType::None
+            | HeapType::NoFunction
+            | HeapType::NoExternal
+            | HeapType::NoException),
+        ) => bytes += emit_heap_type(heap_type, output)?,
+        ReferenceType::Nullable(heap_type) => {
+            bytes += emit_byte(0x63u8, output)?;
+            bytes += emit_heap_type(heap_type, output)?;
+        }
+        ReferenceType::NonNullable(heap_type) => {
+            bytes += emit_byte(0x64u8, output)?;
+            bytes += emit_heap_type(heap_type, output)?;
+        }
+    }
+
+    Ok(bytes)
 }
 
 pub fn emit_value_type<T: Borrow<ValueType>, O: Write + ?Sized>(
@@ -23,8 +75,11 @@ pub fn emit_value_type<T: Borrow<ValueType>, O: Write + ?Sized>(
         ValueType::I64 => 0x7E,
         ValueType::F32 => 0x7D,
         ValueType::F64 => 0x7C,
+        ValueType::V128 => 0x7B,
         ValueType::FunctionReference => 0x70,
         ValueType::ExternalReference => 0x6F,
+        ValueType::ExceptionReference => 0x69,
+        ValueType::Reference(kind) => return emit_reference_type(kind, output),
     };
 
     emit_byte(value, output)
@@ -50,29 +105,171 @@ pub fn emit_function_type<O: Write + ?Sized>(
     Ok(bytes)
 }
 
-pub fn emit_limit<O: Write + ?Sized>(limits: &Limit, output: &mut O) -> Result<usize, EmitError> {
+pub fn emit_storage_type<O: Write + ?Sized>(
+    kind: StorageType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    match kind {
+        StorageType::Value(kind) => emit_value_type(kind, output),
+        StorageType::Packed(PackedType::I8) => emit_byte(0x78u8, output),
+        StorageType::Packed(PackedType::I16) => emit_byte(0x77u8, output),
+    }
+}
+
+pub fn emit_field_type<T: Borrow<FieldType>, O: Write + ?Sized>(
+    kind: T,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let kind = kind.borrow();
     let mut bytes = 0;
 
-    match limits.max() {
-        Some(max) => {
-            bytes += emit_byte(0x01u8, output)?;
-            bytes += emit_u32(limits.min(), output)?;
-            bytes += emit_u32(max, output)?;
-        }
-        None => {
-            bytes += emit_byte(0x00u8, output)?;
-            bytes += emit_u32(limits.min(), output)?;
-        }
-    };
+    bytes += emit_storage_type(kind.kind(), output)?;
+    bytes += emit_mutability(kind.mutability(), output)?;
 
     Ok(bytes)
 }
 
+pub fn emit_struct_type<O: Write + ?Sized>(
+    kind: &StructType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_byte(0x5Fu8, output)?;
+    bytes += emit_vector(kind.fields(), output, emit_field_type)?;
+
+    Ok(bytes)
+}
+
+pub fn emit_array_type<O: Write + ?Sized>(
+    kind: &ArrayType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_byte(0x5Eu8, output)?;
+    bytes += emit_field_type(kind.field(), output)?;
+
+    Ok(bytes)
+}
+
+pub fn emit_composite_type<O: Write + ?Sized>(
+    kind: &CompositeType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    match kind {
+        CompositeType::Function(kind) => emit_function_type(kind, output),
+        CompositeType::Struct(kind) => emit_struct_type(kind, output),
+        CompositeType::Array(kind) => emit_array_type(kind, output),
+    }
+}
+
+/// Emits a sub type, using the composite type shorthand for final sub types without supertypes.
+///
+/// See https://webassembly.github.io/gc/core/binary/types.html#recursive-types
+pub fn emit_sub_type<O: Write + ?Sized>(
+    kind: &SubType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    if !kind.is_final() || !kind.supertypes().is_empty() {
+        let prefix: u8 = if kind.is_final() { 0x4F } else { 0x50 };
+
+        bytes += emit_byte(prefix, output)?;
+        bytes += emit_vector(kind.supertypes(), output, emit_u32)?;
+    }
+
+    bytes += emit_composite_type(kind.kind(), output)?;
+
+    Ok(bytes)
+}
+
+/// Emits a recursive type, using the sub type shorthand for groups with a single sub type.
+///
+/// See https://webassembly.github.io/gc/core/binary/types.html#recursive-types
+pub fn emit_recursive_type<O: Write + ?Sized>(
+    kind: &RecursiveType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    match kind.types() {
+        [kind] => emit_sub_type(kind, output),
+        types => {
+            let mut bytes = 0;
+
+            bytes += emit_byte(0x4Eu8, output)?;
+            bytes += emit_vector(types, output, emit_sub_type)?;
+
+            Ok(bytes)
+        }
+    }
+}
+
+pub fn emit_limit<O: Write + ?Sized>(limits: &Limit, output: &mut O) -> Result<usize, EmitError> {
+    emit_limit_with_flags(limits, 0x00, output)
+}
+
 pub fn emit_memory_type<O: Write + ?Sized>(
     kind: &MemoryType,
     output: &mut O,
 ) -> Result<usize, EmitError> {
-    emit_limit(kind.limits(), output)
+    let flags = if kind.is_shared() { 0x02 } else { 0x00 };
+
+    emit_limit_with_flags(kind.limits(), flags, output)
+}
+
+/// Emits a limit prefixed by the given flags combined with the flags for the limit's maximum
+/// and index type.
+/// Fails if the bounds of a limit with a 32-bit index type do not fit in 32 bits.
+///
+/// See https://webassembly.github.io/memory64/core/binary/types.html#limits
+fn emit_limit_with_flags<O: Write + ?Sized>(
+    limits: &Limit,
+    mut flags: u8,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    if limits.max().is_some() {
+        flags |= 0x01;
+    }
+
+    if limits.index_type() == IndexType::I64 {
+        flags |= 0x04;
+    }
+
+    bytes += emit_byte(flags, output)?;
+    bytes += emit_bound(limits.index_type(), limits.min(), output)?;
+
+    if let Some(max) = limits.max() {
+        bytes += emit_bound(limits.index_type(), max, output)?;
+    }
+
+    Ok(bytes)
+}
+
+/// Emits a bound of a limit with the given index type.
+fn emit_bound<O: Write + ?Sized>(
+    index_type: IndexType,
+    bound: u64,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    match index_type {
+        IndexType::I32 => emit_u32(u32::try_from(bound)?, output),
+        IndexType::I64 => emit_u64(bound, output),
+    }
+}
+
+pub fn emit_tag_type<O: Write + ?Sized>(
+    kind: &TagType,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let mut bytes = 0;
+
+    bytes += emit_byte(0x00u8, output)?;
+    bytes += emit_u32(kind.kind(), output)?;
+
+    Ok(bytes)
 }
 
 pub fn emit_table_type<O: Write + ?Sized>(
@@ -94,13 +291,19 @@ pub fn emit_global_type<O: Write + ?Sized>(
     let mut bytes = 0;
 
     bytes += emit_value_type(kind.kind(), output)?;
+    bytes += emit_mutability(kind.mutability(), output)?;
+
+    Ok(bytes)
+}
 
-    let mutability: u8 = match kind.mutability() {
+fn emit_mutability<O: Write + ?Sized>(
+    mutability: Mutability,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    let value: u8 = match mutability {
         Mutability::Immutable => 0x00,
         Mutability::Mutable => 0x01,
     };
 
-    bytes += emit_byte(mutability, output)?;
-
-    Ok(bytes)
+    emit_byte(value, output)
 }
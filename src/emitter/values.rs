@@ -82,6 +82,16 @@ pub fn emit_u32<T: Borrow<u32>, O: Write + ?Sized>(
     Ok(encode_unsigned(*value.borrow(), output)?)
 }
 
+/// Emits an unsigned 64-bit integer to the output.
+///
+/// See https://webassembly.github.io/spec/core/binary/values.html#integers
+pub fn emit_u64<T: Borrow<u64>, O: Write + ?Sized>(
+    value: T,
+    output: &mut O,
+) -> Result<usize, EmitError> {
+    Ok(encode_unsigned(*value.borrow(), output)?)
+}
+
 /// Emits an unsigned platform-specific (i.e., 32-bit or 64-bit) integer to the output.
 ///
 /// See https://webassembly.github.io/spec/core/binary/values.html#integers
@@ -0,0 +1,984 @@
+//! Owned transformation of a syntax tree.
+
+use crate::model::{
+    AggregateInstruction, ArrayType, AtomicInstruction, BlockType, CatchClause, CompositeType,
+    ControlInstruction, Data, DataIndex, DataMode, Element, ElementIndex, ElementMode, Export,
+    ExportDescription, Expression, FieldIndex, FieldType, Function, FunctionIndex, FunctionType,
+    Global, GlobalIndex, GlobalType, HeapType, Import, ImportDescription, Instruction, LabelIndex,
+    Limit, LocalIndex, Memory, MemoryArgument, MemoryIndex, MemoryInstruction, MemoryType, Module,
+    NumericInstruction, ParametricInstruction, RecursiveType, ReferenceInstruction, ReferenceType,
+    ResultType, Start, StorageType, StructType, SubType, Table, TableIndex, TableInstruction,
+    TableType, Tag, TagIndex, TagType, TypeIndex, ValueType, VariableInstruction,
+    VectorInstruction,
+};
+
+/// A transformation of an owned syntax tree.
+/// Each method consumes a single kind of node and returns its replacement.
+/// By default, methods call the free function of the same name,
+/// which folds the children of the node and rebuilds it from the results.
+///
+/// The components of a module (e.g., functions and exports) are folded into an [`Option`],
+/// where `None` drops the component from the module.
+/// Instructions are folded into a sequence of instructions,
+/// which allows a single instruction to be removed or replaced with several instructions.
+///
+/// **Note:** Folding does not renumber the index spaces of a module.
+/// Transformations that drop or insert components must also rewrite the indices that refer to them.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::fold::{fold_instruction, Fold};
+/// use wasm_ast::{Expression, Instruction, NumberType, NumericInstruction, ParametricInstruction};
+///
+/// struct Rewrite;
+///
+/// impl Fold for Rewrite {
+///     fn fold_instruction(&mut self, node: Instruction) -> Vec<Instruction> {
+///         match node {
+///             Instruction::Numeric(NumericInstruction::I32Constant(value)) => vec![
+///                 NumericInstruction::I32Constant(value / 2).into(),
+///                 NumericInstruction::I32Constant(value - value / 2).into(),
+///                 NumericInstruction::Add(NumberType::I32).into(),
+///             ],
+///             Instruction::Parametric(ParametricInstruction::Drop) => vec![],
+///             node => vec![fold_instruction(self, node)],
+///         }
+///     }
+/// }
+///
+/// let expression = Expression::from(vec![
+///     NumericInstruction::I32Constant(3).into(),
+///     ParametricInstruction::Drop.into(),
+/// ]);
+///
+/// assert_eq!(
+///     Rewrite.fold_expression(expression),
+///     Expression::from(vec![
+///         NumericInstruction::I32Constant(1).into(),
+///         NumericInstruction::I32Constant(2).into(),
+///         NumericInstruction::Add(NumberType::I32).into(),
+///     ])
+/// );
+/// ```
+pub trait Fold {
+    /// Folds a module.
+    fn fold_module(&mut self, node: Module) -> Module {
+        fold_module(self, node)
+    }
+
+    /// Folds a recursive type. Returning `None` drops the type from the module.
+    fn fold_recursive_type(&mut self, node: RecursiveType) -> Option<RecursiveType> {
+        Some(fold_recursive_type(self, node))
+    }
+
+    /// Folds a sub type.
+    fn fold_sub_type(&mut self, node: SubType) -> SubType {
+        fold_sub_type(self, node)
+    }
+
+    /// Folds a composite type.
+    fn fold_composite_type(&mut self, node: CompositeType) -> CompositeType {
+        fold_composite_type(self, node)
+    }
+
+    /// Folds a function type.
+    fn fold_function_type(&mut self, node: FunctionType) -> FunctionType {
+        fold_function_type(self, node)
+    }
+
+    /// Folds a structure type.
+    fn fold_struct_type(&mut self, node: StructType) -> StructType {
+        fold_struct_type(self, node)
+    }
+
+    /// Folds an array type.
+    fn fold_array_type(&mut self, node: ArrayType) -> ArrayType {
+        fold_array_type(self, node)
+    }
+
+    /// Folds a field type.
+    fn fold_field_type(&mut self, node: FieldType) -> FieldType {
+        fold_field_type(self, node)
+    }
+
+    /// Folds a result type (i.e., the parameters, results or locals of a function).
+    fn fold_result_type(&mut self, node: ResultType) -> ResultType {
+        fold_result_type(self, node)
+    }
+
+    /// Folds a value type.
+    fn fold_value_type(&mut self, node: ValueType) -> ValueType {
+        fold_value_type(self, node)
+    }
+
+    /// Folds a reference type.
+    fn fold_reference_type(&mut self, node: ReferenceType) -> ReferenceType {
+        fold_reference_type(self, node)
+    }
+
+    /// Folds a heap type.
+    fn fold_heap_type(&mut self, node: HeapType) -> HeapType {
+        fold_heap_type(self, node)
+    }
+
+    /// Folds the limits of a table or memory type.
+    fn fold_limit(&mut self, node: Limit) -> Limit {
+        node
+    }
+
+    /// Folds a table type.
+    fn fold_table_type(&mut self, node: TableType) -> TableType {
+        fold_table_type(self, node)
+    }
+
+    /// Folds a memory type.
+    fn fold_memory_type(&mut self, node: MemoryType) -> MemoryType {
+        fold_memory_type(self, node)
+    }
+
+    /// Folds a global type.
+    fn fold_global_type(&mut self, node: GlobalType) -> GlobalType {
+        fold_global_type(self, node)
+    }
+
+    /// Folds a tag type.
+    fn fold_tag_type(&mut self, node: TagType) -> TagType {
+        fold_tag_type(self, node)
+    }
+
+    /// Folds an import. Returning `None` drops the import from the module.
+    fn fold_import(&mut self, node: Import) -> Option<Import> {
+        Some(fold_import(self, node))
+    }
+
+    /// Folds a function. Returning `None` drops the function from the module.
+    fn fold_function(&mut self, node: Function) -> Option<Function> {
+        Some(fold_function(self, node))
+    }
+
+    /// Folds a table. Returning `None` drops the table from the module.
+    fn fold_table(&mut self, node: Table) -> Option<Table> {
+        Some(fold_table(self, node))
+    }
+
+    /// Folds a memory. Returning `None` drops the memory from the module.
+    fn fold_memory(&mut self, node: Memory) -> Option<Memory> {
+        Some(fold_memory(self, node))
+    }
+
+    /// Folds a tag. Returning `None` drops the tag from the module.
+    fn fold_tag(&mut self, node: Tag) -> Option<Tag> {
+        Some(fold_tag(self, node))
+    }
+
+    /// Folds a global. Returning `None` drops the global from the module.
+    fn fold_global(&mut self, node: Global) -> Option<Global> {
+        Some(fold_global(self, node))
+    }
+
+    /// Folds an export. Returning `None` drops the export from the module.
+    fn fold_export(&mut self, node: Export) -> Option<Export> {
+        Some(fold_export(self, node))
+    }
+
+    /// Folds the start function. Returning `None` drops the start function from the module.
+    fn fold_start(&mut self, node: Start) -> Option<Start> {
+        Some(fold_start(self, node))
+    }
+
+    /// Folds an element segment. Returning `None` drops the segment from the module.
+    fn fold_element(&mut self, node: Element) -> Option<Element> {
+        Some(fold_element(self, node))
+    }
+
+    /// Folds a data segment. Returning `None` drops the segment from the module.
+    fn fold_data(&mut self, node: Data) -> Option<Data> {
+        Some(fold_data(self, node))
+    }
+
+    /// Folds an expression (e.g., a function body, an initializer or the body of a block).
+    fn fold_expression(&mut self, node: Expression) -> Expression {
+        fold_expression(self, node)
+    }
+
+    /// Folds an instruction into the sequence of instructions that replaces it.
+    fn fold_instruction(&mut self, node: Instruction) -> Vec<Instruction> {
+        vec![fold_instruction(self, node)]
+    }
+
+    /// Folds a numeric instruction.
+    fn fold_numeric_instruction(&mut self, node: NumericInstruction) -> NumericInstruction {
+        node
+    }
+
+    /// Folds a vector instruction.
+    fn fold_vector_instruction(&mut self, node: VectorInstruction) -> VectorInstruction {
+        fold_vector_instruction(self, node)
+    }
+
+    /// Folds a reference instruction.
+    fn fold_reference_instruction(&mut self, node: ReferenceInstruction) -> ReferenceInstruction {
+        fold_reference_instruction(self, node)
+    }
+
+    /// Folds a parametric instruction.
+    fn fold_parametric_instruction(
+        &mut self,
+        node: ParametricInstruction,
+    ) -> ParametricInstruction {
+        fold_parametric_instruction(self, node)
+    }
+
+    /// Folds a variable instruction.
+    fn fold_variable_instruction(&mut self, node: VariableInstruction) -> VariableInstruction {
+        fold_variable_instruction(self, node)
+    }
+
+    /// Folds a table instruction.
+    fn fold_table_instruction(&mut self, node: TableInstruction) -> TableInstruction {
+        fold_table_instruction(self, node)
+    }
+
+    /// Folds a memory instruction.
+    fn fold_memory_instruction(&mut self, node: MemoryInstruction) -> MemoryInstruction {
+        fold_memory_instruction(self, node)
+    }
+
+    /// Folds an atomic memory instruction.
+    fn fold_atomic_instruction(&mut self, node: AtomicInstruction) -> AtomicInstruction {
+        fold_atomic_instruction(self, node)
+    }
+
+    /// Folds an aggregate (i.e., structure, array or i31) instruction.
+    fn fold_aggregate_instruction(&mut self, node: AggregateInstruction) -> AggregateInstruction {
+        fold_aggregate_instruction(self, node)
+    }
+
+    /// Folds a control instruction.
+    fn fold_control_instruction(&mut self, node: ControlInstruction) -> ControlInstruction {
+        fold_control_instruction(self, node)
+    }
+
+    /// Folds the block type of a structured control instruction.
+    fn fold_block_type(&mut self, node: BlockType) -> BlockType {
+        fold_block_type(self, node)
+    }
+
+    /// Folds a catch clause of a try table instruction.
+    fn fold_catch_clause(&mut self, node: CatchClause) -> CatchClause {
+        fold_catch_clause(self, node)
+    }
+
+    /// Folds the memory argument of a load or store instruction.
+    fn fold_memory_argument(&mut self, node: MemoryArgument) -> MemoryArgument {
+        fold_memory_argument(self, node)
+    }
+
+    /// Folds a reference to a type.
+    fn fold_type_index(&mut self, index: TypeIndex) -> TypeIndex {
+        index
+    }
+
+    /// Folds a reference to a function.
+    fn fold_function_index(&mut self, index: FunctionIndex) -> FunctionIndex {
+        index
+    }
+
+    /// Folds a reference to a table.
+    fn fold_table_index(&mut self, index: TableIndex) -> TableIndex {
+        index
+    }
+
+    /// Folds a reference to a memory.
+    fn fold_memory_index(&mut self, index: MemoryIndex) -> MemoryIndex {
+        index
+    }
+
+    /// Folds a reference to a global.
+    fn fold_global_index(&mut self, index: GlobalIndex) -> GlobalIndex {
+        index
+    }
+
+    /// Folds a reference to an element segment.
+    fn fold_element_index(&mut self, index: ElementIndex) -> ElementIndex {
+        index
+    }
+
+    /// Folds a reference to a data segment.
+    fn fold_data_index(&mut self, index: DataIndex) -> DataIndex {
+        index
+    }
+
+    /// Folds a reference to a local.
+    fn fold_local_index(&mut self, index: LocalIndex) -> LocalIndex {
+        index
+    }
+
+    /// Folds a reference to a label.
+    fn fold_label_index(&mut self, index: LabelIndex) -> LabelIndex {
+        index
+    }
+
+    /// Folds a reference to a tag.
+    fn fold_tag_index(&mut self, index: TagIndex) -> TagIndex {
+        index
+    }
+
+    /// Folds a reference to a field of a structure type.
+    fn fold_field_index(&mut self, index: FieldIndex) -> FieldIndex {
+        index
+    }
+}
+
+/// Folds the components of a module in the order of their index spaces:
+/// types, imports, functions, tables, memories, tags, globals, exports,
+/// the start function, element segments and data segments.
+/// The module is rebuilt with a [`ModuleBuilder`](crate::model::ModuleBuilder),
+/// which keeps the custom sections and names of the original module.
+/// The data count is recomputed when the original module includes one.
+pub fn fold_module<F: Fold + ?Sized>(folder: &mut F, mut node: Module) -> Module {
+    let types = node.types.take();
+    let imports = node.imports.take();
+    let functions = node.functions.take();
+    let tables = node.tables.take();
+    let memories = node.memories.take();
+    let tags = node.tags.take();
+    let globals = node.globals.take();
+    let exports = node.exports.take();
+    let start = node.start.take();
+    let elements = node.elements.take();
+    let data = node.data.take();
+    let data_count = node.data_count().is_some();
+    let mut builder = node.into_builder();
+
+    builder.set_types(fold_all(types, |kind| folder.fold_recursive_type(kind)));
+    builder.set_imports(fold_all(imports, |import| folder.fold_import(import)));
+    builder.set_functions(fold_all(functions, |function| {
+        folder.fold_function(function)
+    }));
+    builder.set_tables(fold_all(tables, |table| folder.fold_table(table)));
+    builder.set_memories(fold_all(memories, |memory| folder.fold_memory(memory)));
+    builder.set_tags(fold_all(tags, |tag| folder.fold_tag(tag)));
+    builder.set_globals(fold_all(globals, |global| folder.fold_global(global)));
+    builder.set_exports(fold_all(exports, |export| folder.fold_export(export)));
+    builder.set_start(start.and_then(|start| folder.fold_start(start)));
+    builder.set_elements(fold_all(elements, |element| folder.fold_element(element)));
+    builder.set_data(fold_all(data, |datum| folder.fold_data(datum)));
+
+    if data_count {
+        builder.include_data_count();
+    }
+
+    builder.build()
+}
+
+/// Folds each component of a module section, keeping the ones that are not dropped.
+fn fold_all<T, F>(components: Option<Vec<T>>, fold: F) -> Option<Vec<T>>
+where
+    F: FnMut(T) -> Option<T>,
+{
+    components.map(|components| components.into_iter().filter_map(fold).collect())
+}
+
+/// Folds the sub types of a recursive type.
+pub fn fold_recursive_type<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: RecursiveType,
+) -> RecursiveType {
+    node.types = node
+        .types
+        .into_iter()
+        .map(|kind| folder.fold_sub_type(kind))
+        .collect();
+    node
+}
+
+/// Folds the supertypes and composite type of a sub type.
+pub fn fold_sub_type<F: Fold + ?Sized>(folder: &mut F, mut node: SubType) -> SubType {
+    node.supertypes = node
+        .supertypes
+        .into_iter()
+        .map(|supertype| folder.fold_type_index(supertype))
+        .collect();
+    node.kind = folder.fold_composite_type(node.kind);
+    node
+}
+
+/// Folds the function, structure or array type of a composite type.
+pub fn fold_composite_type<F: Fold + ?Sized>(folder: &mut F, node: CompositeType) -> CompositeType {
+    match node {
+        CompositeType::Function(kind) => CompositeType::Function(folder.fold_function_type(kind)),
+        CompositeType::Struct(kind) => CompositeType::Struct(folder.fold_struct_type(kind)),
+        CompositeType::Array(kind) => CompositeType::Array(folder.fold_array_type(kind)),
+    }
+}
+
+/// Folds the parameters and results of a function type.
+pub fn fold_function_type<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: FunctionType,
+) -> FunctionType {
+    node.parameters = folder.fold_result_type(node.parameters);
+    node.results = folder.fold_result_type(node.results);
+    node
+}
+
+/// Folds the fields of a structure type.
+pub fn fold_struct_type<F: Fold + ?Sized>(folder: &mut F, mut node: StructType) -> StructType {
+    node.fields = node
+        .fields
+        .into_iter()
+        .map(|field| folder.fold_field_type(field))
+        .collect();
+    node
+}
+
+/// Folds the field of an array type.
+pub fn fold_array_type<F: Fold + ?Sized>(folder: &mut F, mut node: ArrayType) -> ArrayType {
+    node.field = folder.fold_field_type(node.field);
+    node
+}
+
+/// Folds the value type of a field, unless the field is packed.
+pub fn fold_field_type<F: Fold + ?Sized>(folder: &mut F, mut node: FieldType) -> FieldType {
+    if let StorageType::Value(kind) = node.kind {
+        node.kind = StorageType::Value(folder.fold_value_type(kind));
+    }
+
+    node
+}
+
+/// Folds the value types of a result type.
+pub fn fold_result_type<F: Fold + ?Sized>(folder: &mut F, mut node: ResultType) -> ResultType {
+    node.kinds = node
+        .kinds
+        .into_iter()
+        .map(|kind| folder.fold_value_type(kind))
+        .collect();
+    node
+}
+
+/// Folds the reference type of a value type, if any.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn fold_value_type<F: Fold + ?Sized>(folder: &mut F, node: ValueType) -> ValueType {
+    match node {
+        ValueType::Reference(kind) => ValueType::Reference(folder.fold_reference_type(kind)),
+        node => node,
+    }
+}
+
+/// Folds the heap type of a reference type.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn fold_reference_type<F: Fold + ?Sized>(folder: &mut F, node: ReferenceType) -> ReferenceType {
+    match node {
+        ReferenceType::Nullable(kind) => ReferenceType::Nullable(folder.fold_heap_type(kind)),
+        ReferenceType::NonNullable(kind) => ReferenceType::NonNullable(folder.fold_heap_type(kind)),
+        node => node,
+    }
+}
+
+/// Folds the type index of a concrete heap type.
+pub fn fold_heap_type<F: Fold + ?Sized>(folder: &mut F, node: HeapType) -> HeapType {
+    match node {
+        HeapType::Index(index) => HeapType::Index(folder.fold_type_index(index)),
+        node => node,
+    }
+}
+
+/// Folds the limits and element type of a table type.
+pub fn fold_table_type<F: Fold + ?Sized>(folder: &mut F, mut node: TableType) -> TableType {
+    node.limits = folder.fold_limit(node.limits);
+    node.kind = folder.fold_reference_type(node.kind);
+    node
+}
+
+/// Folds the limits of a memory type.
+pub fn fold_memory_type<F: Fold + ?Sized>(folder: &mut F, mut node: MemoryType) -> MemoryType {
+    node.limits = folder.fold_limit(node.limits);
+    node
+}
+
+/// Folds the value type of a global type.
+pub fn fold_global_type<F: Fold + ?Sized>(folder: &mut F, mut node: GlobalType) -> GlobalType {
+    node.kind = folder.fold_value_type(node.kind);
+    node
+}
+
+/// Folds the function type index of a tag type.
+pub fn fold_tag_type<F: Fold + ?Sized>(folder: &mut F, mut node: TagType) -> TagType {
+    node.kind = folder.fold_type_index(node.kind);
+    node
+}
+
+/// Folds the type of the definition required by an import.
+pub fn fold_import<F: Fold + ?Sized>(folder: &mut F, mut node: Import) -> Import {
+    node.description = match node.description {
+        ImportDescription::Function(kind) => {
+            ImportDescription::Function(folder.fold_type_index(kind))
+        }
+        ImportDescription::Table(kind) => ImportDescription::Table(folder.fold_table_type(kind)),
+        ImportDescription::Memory(kind) => ImportDescription::Memory(folder.fold_memory_type(kind)),
+        ImportDescription::Global(kind) => ImportDescription::Global(folder.fold_global_type(kind)),
+        ImportDescription::Tag(kind) => ImportDescription::Tag(folder.fold_tag_type(kind)),
+    };
+    node
+}
+
+/// Folds the type, locals and body of a function.
+pub fn fold_function<F: Fold + ?Sized>(folder: &mut F, mut node: Function) -> Function {
+    node.kind = folder.fold_type_index(node.kind);
+    node.locals = folder.fold_result_type(node.locals);
+    node.body = folder.fold_expression(node.body.into_expression()).into();
+    node
+}
+
+/// Folds the table type and initializer of a table.
+pub fn fold_table<F: Fold + ?Sized>(folder: &mut F, mut node: Table) -> Table {
+    node.kind = folder.fold_table_type(node.kind);
+    node.initializer = node
+        .initializer
+        .map(|initializer| folder.fold_expression(initializer));
+    node
+}
+
+/// Folds the memory type of a memory.
+pub fn fold_memory<F: Fold + ?Sized>(folder: &mut F, mut node: Memory) -> Memory {
+    node.kind = folder.fold_memory_type(node.kind);
+    node
+}
+
+/// Folds the tag type of a tag.
+pub fn fold_tag<F: Fold + ?Sized>(folder: &mut F, mut node: Tag) -> Tag {
+    node.kind = folder.fold_tag_type(node.kind);
+    node
+}
+
+/// Folds the type and initializer of a global.
+pub fn fold_global<F: Fold + ?Sized>(folder: &mut F, mut node: Global) -> Global {
+    node.kind = folder.fold_global_type(node.kind);
+    node.initializer = folder.fold_expression(node.initializer);
+    node
+}
+
+/// Folds the index of the exported definition.
+pub fn fold_export<F: Fold + ?Sized>(folder: &mut F, mut node: Export) -> Export {
+    node.description = match node.description {
+        ExportDescription::Function(index) => {
+            ExportDescription::Function(folder.fold_function_index(index))
+        }
+        ExportDescription::Table(index) => ExportDescription::Table(folder.fold_table_index(index)),
+        ExportDescription::Memory(index) => {
+            ExportDescription::Memory(folder.fold_memory_index(index))
+        }
+        ExportDescription::Global(index) => {
+            ExportDescription::Global(folder.fold_global_index(index))
+        }
+        ExportDescription::Tag(index) => ExportDescription::Tag(folder.fold_tag_index(index)),
+    };
+    node
+}
+
+/// Folds the index of the start function.
+pub fn fold_start<F: Fold + ?Sized>(folder: &mut F, mut node: Start) -> Start {
+    node.function = folder.fold_function_index(node.function);
+    node
+}
+
+/// Folds the element type, mode and initializers of an element segment.
+pub fn fold_element<F: Fold + ?Sized>(folder: &mut F, mut node: Element) -> Element {
+    node.kind = folder.fold_reference_type(node.kind);
+    node.mode = match node.mode {
+        ElementMode::Active(table, offset) => ElementMode::Active(
+            folder.fold_table_index(table),
+            folder.fold_expression(offset),
+        ),
+        mode => mode,
+    };
+    node.initializers = node
+        .initializers
+        .into_iter()
+        .map(|initializer| folder.fold_expression(initializer))
+        .collect();
+    node
+}
+
+/// Folds the mode of a data segment.
+pub fn fold_data<F: Fold + ?Sized>(folder: &mut F, mut node: Data) -> Data {
+    node.mode = match node.mode {
+        DataMode::Active(memory, offset) => DataMode::Active(
+            folder.fold_memory_index(memory),
+            folder.fold_expression(offset),
+        ),
+        mode => mode,
+    };
+    node
+}
+
+/// Folds each instruction of an expression, splicing the resulting sequences into the expression.
+pub fn fold_expression<F: Fold + ?Sized>(folder: &mut F, mut node: Expression) -> Expression {
+    node.instructions = node
+        .instructions
+        .into_iter()
+        .flat_map(|instruction| folder.fold_instruction(instruction))
+        .collect();
+    node
+}
+
+/// Folds the instruction according to its family.
+pub fn fold_instruction<F: Fold + ?Sized>(folder: &mut F, node: Instruction) -> Instruction {
+    match node {
+        Instruction::Numeric(instruction) => folder.fold_numeric_instruction(instruction).into(),
+        Instruction::Vector(instruction) => folder.fold_vector_instruction(instruction).into(),
+        Instruction::Reference(instruction) => {
+            folder.fold_reference_instruction(instruction).into()
+        }
+        Instruction::Parametric(instruction) => {
+            folder.fold_parametric_instruction(instruction).into()
+        }
+        Instruction::Variable(instruction) => folder.fold_variable_instruction(instruction).into(),
+        Instruction::Table(instruction) => folder.fold_table_instruction(instruction).into(),
+        Instruction::Memory(instruction) => folder.fold_memory_instruction(instruction).into(),
+        Instruction::Atomic(instruction) => folder.fold_atomic_instruction(instruction).into(),
+        Instruction::Aggregate(instruction) => {
+            folder.fold_aggregate_instruction(instruction).into()
+        }
+        Instruction::Control(instruction) => folder.fold_control_instruction(instruction).into(),
+    }
+}
+
+/// Folds the memory argument of a vector load or store instruction.
+pub fn fold_vector_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: VectorInstruction,
+) -> VectorInstruction {
+    match &mut node {
+        VectorInstruction::Load(argument)
+        | VectorInstruction::Store(argument)
+        | VectorInstruction::Load8x8(_, argument)
+        | VectorInstruction::Load16x4(_, argument)
+        | VectorInstruction::Load32x2(_, argument)
+        | VectorInstruction::Load8Splat(argument)
+        | VectorInstruction::Load16Splat(argument)
+        | VectorInstruction::Load32Splat(argument)
+        | VectorInstruction::Load64Splat(argument)
+        | VectorInstruction::Load32Zero(argument)
+        | VectorInstruction::Load64Zero(argument)
+        | VectorInstruction::Load8Lane(argument, _)
+        | VectorInstruction::Load16Lane(argument, _)
+        | VectorInstruction::Load32Lane(argument, _)
+        | VectorInstruction::Load64Lane(argument, _)
+        | VectorInstruction::Store8Lane(argument, _)
+        | VectorInstruction::Store16Lane(argument, _)
+        | VectorInstruction::Store32Lane(argument, _)
+        | VectorInstruction::Store64Lane(argument, _) => {
+            *argument = folder.fold_memory_argument(*argument)
+        }
+        _ => {}
+    }
+
+    node
+}
+
+/// Folds the types and function index of a reference instruction.
+pub fn fold_reference_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: ReferenceInstruction,
+) -> ReferenceInstruction {
+    match node {
+        ReferenceInstruction::Null(kind) => ReferenceInstruction::Null(folder.fold_heap_type(kind)),
+        ReferenceInstruction::Function(index) => {
+            ReferenceInstruction::Function(folder.fold_function_index(index))
+        }
+        ReferenceInstruction::Test(kind) => {
+            ReferenceInstruction::Test(folder.fold_reference_type(kind))
+        }
+        ReferenceInstruction::Cast(kind) => {
+            ReferenceInstruction::Cast(folder.fold_reference_type(kind))
+        }
+        node => node,
+    }
+}
+
+/// Folds the value types of a typed select instruction.
+pub fn fold_parametric_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: ParametricInstruction,
+) -> ParametricInstruction {
+    match node {
+        ParametricInstruction::Select(Some(kinds)) => ParametricInstruction::Select(Some(
+            kinds
+                .into_iter()
+                .map(|kind| folder.fold_value_type(kind))
+                .collect(),
+        )),
+        node => node,
+    }
+}
+
+/// Folds the local or global index of a variable instruction.
+pub fn fold_variable_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: VariableInstruction,
+) -> VariableInstruction {
+    match &mut node {
+        VariableInstruction::LocalGet(index)
+        | VariableInstruction::LocalSet(index)
+        | VariableInstruction::LocalTee(index) => *index = folder.fold_local_index(*index),
+        VariableInstruction::GlobalGet(index) | VariableInstruction::GlobalSet(index) => {
+            *index = folder.fold_global_index(*index)
+        }
+    }
+
+    node
+}
+
+/// Folds the table and element indices of a table instruction.
+pub fn fold_table_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: TableInstruction,
+) -> TableInstruction {
+    match &mut node {
+        TableInstruction::Get(table)
+        | TableInstruction::Set(table)
+        | TableInstruction::Size(table)
+        | TableInstruction::Grow(table)
+        | TableInstruction::Fill(table) => *table = folder.fold_table_index(*table),
+        TableInstruction::Copy(destination, source) => {
+            *destination = folder.fold_table_index(*destination);
+            *source = folder.fold_table_index(*source);
+        }
+        TableInstruction::Init(element, table) => {
+            *element = folder.fold_element_index(*element);
+            *table = folder.fold_table_index(*table);
+        }
+        TableInstruction::ElementDrop(element) => *element = folder.fold_element_index(*element),
+    }
+
+    node
+}
+
+/// Folds the memory arguments, memory and data indices of a memory instruction.
+pub fn fold_memory_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: MemoryInstruction,
+) -> MemoryInstruction {
+    match &mut node {
+        MemoryInstruction::Load(_, argument)
+        | MemoryInstruction::Store(_, argument)
+        | MemoryInstruction::Load8(_, _, argument)
+        | MemoryInstruction::Load16(_, _, argument)
+        | MemoryInstruction::Load32(_, argument)
+        | MemoryInstruction::Store8(_, argument)
+        | MemoryInstruction::Store16(_, argument)
+        | MemoryInstruction::Store32(argument) => {
+            *argument = folder.fold_memory_argument(*argument)
+        }
+        MemoryInstruction::Size(memory)
+        | MemoryInstruction::Grow(memory)
+        | MemoryInstruction::Fill(memory) => *memory = folder.fold_memory_index(*memory),
+        MemoryInstruction::Copy(destination, source) => {
+            *destination = folder.fold_memory_index(*destination);
+            *source = folder.fold_memory_index(*source);
+        }
+        MemoryInstruction::Init(data, memory) => {
+            *data = folder.fold_data_index(*data);
+            *memory = folder.fold_memory_index(*memory);
+        }
+        MemoryInstruction::DataDrop(data) => *data = folder.fold_data_index(*data),
+    }
+
+    node
+}
+
+/// Folds the memory argument of an atomic memory instruction.
+pub fn fold_atomic_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: AtomicInstruction,
+) -> AtomicInstruction {
+    match &mut node {
+        AtomicInstruction::Notify(argument)
+        | AtomicInstruction::Wait(_, argument)
+        | AtomicInstruction::Load(_, argument)
+        | AtomicInstruction::Load8(_, argument)
+        | AtomicInstruction::Load16(_, argument)
+        | AtomicInstruction::Load32(argument)
+        | AtomicInstruction::Store(_, argument)
+        | AtomicInstruction::Store8(_, argument)
+        | AtomicInstruction::Store16(_, argument)
+        | AtomicInstruction::Store32(argument)
+        | AtomicInstruction::ReadModifyWrite(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite8(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite16(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite32(_, argument) => {
+            *argument = folder.fold_memory_argument(*argument)
+        }
+        AtomicInstruction::Fence => {}
+    }
+
+    node
+}
+
+/// Folds the type, field, data and element indices of an aggregate instruction.
+pub fn fold_aggregate_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: AggregateInstruction,
+) -> AggregateInstruction {
+    match &mut node {
+        AggregateInstruction::StructNew(kind)
+        | AggregateInstruction::StructNewDefault(kind)
+        | AggregateInstruction::ArrayNew(kind)
+        | AggregateInstruction::ArrayNewDefault(kind)
+        | AggregateInstruction::ArrayNewFixed(kind, _)
+        | AggregateInstruction::ArrayGet(_, kind)
+        | AggregateInstruction::ArraySet(kind)
+        | AggregateInstruction::ArrayFill(kind) => *kind = folder.fold_type_index(*kind),
+        AggregateInstruction::StructGet(_, kind, field)
+        | AggregateInstruction::StructSet(kind, field) => {
+            *kind = folder.fold_type_index(*kind);
+            *field = folder.fold_field_index(*field);
+        }
+        AggregateInstruction::ArrayNewData(kind, data)
+        | AggregateInstruction::ArrayInitData(kind, data) => {
+            *kind = folder.fold_type_index(*kind);
+            *data = folder.fold_data_index(*data);
+        }
+        AggregateInstruction::ArrayNewElement(kind, element)
+        | AggregateInstruction::ArrayInitElement(kind, element) => {
+            *kind = folder.fold_type_index(*kind);
+            *element = folder.fold_element_index(*element);
+        }
+        AggregateInstruction::ArrayCopy(destination, source) => {
+            *destination = folder.fold_type_index(*destination);
+            *source = folder.fold_type_index(*source);
+        }
+        AggregateInstruction::ArrayLength
+        | AggregateInstruction::ReferenceI31
+        | AggregateInstruction::I31Get(_)
+        | AggregateInstruction::AnyConvertExternal
+        | AggregateInstruction::ExternalConvertAny => {}
+    }
+
+    node
+}
+
+/// Folds the block types, nested expressions, labels and indices of a control instruction.
+pub fn fold_control_instruction<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: ControlInstruction,
+) -> ControlInstruction {
+    match node {
+        ControlInstruction::Block(kind, body) => {
+            ControlInstruction::Block(folder.fold_block_type(kind), folder.fold_expression(body))
+        }
+        ControlInstruction::Loop(kind, body) => {
+            ControlInstruction::Loop(folder.fold_block_type(kind), folder.fold_expression(body))
+        }
+        ControlInstruction::If(kind, consequent, alternative) => ControlInstruction::If(
+            folder.fold_block_type(kind),
+            folder.fold_expression(consequent),
+            alternative.map(|alternative| folder.fold_expression(alternative)),
+        ),
+        ControlInstruction::Branch(label) => {
+            ControlInstruction::Branch(folder.fold_label_index(label))
+        }
+        ControlInstruction::BranchIf(label) => {
+            ControlInstruction::BranchIf(folder.fold_label_index(label))
+        }
+        ControlInstruction::BranchOnNull(label) => {
+            ControlInstruction::BranchOnNull(folder.fold_label_index(label))
+        }
+        ControlInstruction::BranchOnNonNull(label) => {
+            ControlInstruction::BranchOnNonNull(folder.fold_label_index(label))
+        }
+        ControlInstruction::BranchTable(labels, default) => ControlInstruction::BranchTable(
+            labels
+                .into_iter()
+                .map(|label| folder.fold_label_index(label))
+                .collect(),
+            folder.fold_label_index(default),
+        ),
+        ControlInstruction::Call(function) => {
+            ControlInstruction::Call(folder.fold_function_index(function))
+        }
+        ControlInstruction::ReturnCall(function) => {
+            ControlInstruction::ReturnCall(folder.fold_function_index(function))
+        }
+        ControlInstruction::CallIndirect(kind, table) => ControlInstruction::CallIndirect(
+            folder.fold_type_index(kind),
+            folder.fold_table_index(table),
+        ),
+        ControlInstruction::ReturnCallIndirect(kind, table) => {
+            ControlInstruction::ReturnCallIndirect(
+                folder.fold_type_index(kind),
+                folder.fold_table_index(table),
+            )
+        }
+        ControlInstruction::CallRef(kind) => {
+            ControlInstruction::CallRef(folder.fold_type_index(kind))
+        }
+        ControlInstruction::ReturnCallRef(kind) => {
+            ControlInstruction::ReturnCallRef(folder.fold_type_index(kind))
+        }
+        ControlInstruction::BranchOnCast(label, source, target) => {
+            ControlInstruction::BranchOnCast(
+                folder.fold_label_index(label),
+                folder.fold_reference_type(source),
+                folder.fold_reference_type(target),
+            )
+        }
+        ControlInstruction::BranchOnCastFail(label, source, target) => {
+            ControlInstruction::BranchOnCastFail(
+                folder.fold_label_index(label),
+                folder.fold_reference_type(source),
+                folder.fold_reference_type(target),
+            )
+        }
+        ControlInstruction::Throw(tag) => ControlInstruction::Throw(folder.fold_tag_index(tag)),
+        ControlInstruction::TryTable(kind, clauses, body) => ControlInstruction::TryTable(
+            folder.fold_block_type(kind),
+            clauses
+                .into_iter()
+                .map(|clause| folder.fold_catch_clause(clause))
+                .collect(),
+            folder.fold_expression(body),
+        ),
+        node @ (ControlInstruction::Nop
+        | ControlInstruction::Unreachable
+        | ControlInstruction::Return
+        | ControlInstruction::ThrowRef) => node,
+    }
+}
+
+/// Folds the type index or value type of a block type.
+pub fn fold_block_type<F: Fold + ?Sized>(folder: &mut F, node: BlockType) -> BlockType {
+    match node {
+        BlockType::None => BlockType::None,
+        BlockType::Index(index) => BlockType::Index(folder.fold_type_index(index)),
+        BlockType::ValueType(kind) => BlockType::ValueType(folder.fold_value_type(kind)),
+    }
+}
+
+/// Folds the tag and label indices of a catch clause.
+pub fn fold_catch_clause<F: Fold + ?Sized>(folder: &mut F, node: CatchClause) -> CatchClause {
+    match node {
+        CatchClause::Catch(tag, label) => {
+            CatchClause::Catch(folder.fold_tag_index(tag), folder.fold_label_index(label))
+        }
+        CatchClause::CatchReference(tag, label) => {
+            CatchClause::CatchReference(folder.fold_tag_index(tag), folder.fold_label_index(label))
+        }
+        CatchClause::CatchAll(label) => CatchClause::CatchAll(folder.fold_label_index(label)),
+        CatchClause::CatchAllReference(label) => {
+            CatchClause::CatchAllReference(folder.fold_label_index(label))
+        }
+    }
+}
+
+/// Folds the memory index of a memory argument.
+pub fn fold_memory_argument<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut node: MemoryArgument,
+) -> MemoryArgument {
+    node.memory = folder.fold_memory_index(node.memory);
+    node
+}
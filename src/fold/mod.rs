@@ -0,0 +1,114 @@
+//! Transformations of an owned WebAssembly syntax tree.
+//! The [`Fold`] trait consumes a module and rebuilds it node by node,
+//! which allows a pass to replace instructions with sequences of instructions, drop components or change types.
+
+mod folder;
+
+pub use folder::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+
+    fn module() -> Module {
+        let mut builder = Module::builder();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::from(vec![ValueType::I32]),
+                Expression::from(vec![
+                    ControlInstruction::Call(1).into(),
+                    ControlInstruction::Block(
+                        BlockType::None,
+                        Expression::from(vec![ControlInstruction::Nop.into()]),
+                    )
+                    .into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(0, ResultType::empty(), Expression::empty()))
+            .unwrap();
+        builder.add_export(Export::function("run".into(), 0));
+        builder.add_data(Data::passive(vec![42])).unwrap();
+        builder.include_data_count();
+        builder.add_custom_section(ModuleSection::Data, Custom::new("version".into(), vec![1]));
+
+        builder.build()
+    }
+
+    struct Identity;
+
+    impl Fold for Identity {}
+
+    #[test]
+    fn fold_identity() {
+        assert_eq!(Identity.fold_module(module()), module());
+    }
+
+    struct Inline;
+
+    impl Fold for Inline {
+        fn fold_function(&mut self, node: Function) -> Option<Function> {
+            if node.body().is_empty() {
+                None
+            } else {
+                Some(fold_function(self, node))
+            }
+        }
+
+        fn fold_instruction(&mut self, node: Instruction) -> Vec<Instruction> {
+            match node {
+                Instruction::Control(ControlInstruction::Call(1)) => Vec::new(),
+                Instruction::Control(ControlInstruction::Nop) => vec![
+                    NumericInstruction::I32Constant(1).into(),
+                    VariableInstruction::LocalSet(0).into(),
+                ],
+                node => vec![fold_instruction(self, node)],
+            }
+        }
+
+        fn fold_value_type(&mut self, node: ValueType) -> ValueType {
+            match node {
+                ValueType::I32 => ValueType::I64,
+                node => fold_value_type(self, node),
+            }
+        }
+
+        fn fold_data(&mut self, _node: Data) -> Option<Data> {
+            None
+        }
+    }
+
+    #[test]
+    fn fold_module_components() {
+        let module = Inline.fold_module(module());
+        let expected = Function::new(
+            0,
+            ResultType::from(vec![ValueType::I64]),
+            Expression::from(vec![ControlInstruction::Block(
+                BlockType::None,
+                Expression::from(vec![
+                    NumericInstruction::I32Constant(1).into(),
+                    VariableInstruction::LocalSet(0).into(),
+                ]),
+            )
+            .into()]),
+        );
+
+        assert_eq!(module.functions(), Some(&[expected][..]));
+        assert_eq!(
+            module.exports(),
+            Some(&[Export::function("run".into(), 0)][..])
+        );
+        assert_eq!(module.data(), Some(&[][..]));
+        assert_eq!(module.data_count(), Some(0));
+        assert_eq!(
+            module.custom_sections_at(ModuleSection::Data),
+            Some(&[Custom::new("version".into(), vec![1])][..])
+        );
+    }
+}
@@ -1,7 +1,13 @@
 //! A Rust-native WebAssembly syntax model useful for generating, parsing, and emitting WebAssembly code.
 
+pub mod analysis;
+pub mod constant;
+pub mod fold;
 pub mod leb128;
 pub mod model;
+pub mod transform;
+pub mod validation;
+pub mod visit;
 
 #[cfg(feature = "emitter")]
 pub mod emitter;
@@ -9,7 +15,12 @@ pub mod emitter;
 #[cfg(feature = "parser")]
 pub mod parser;
 
+pub use constant::*;
+pub use fold::Fold;
 pub use model::*;
+pub use transform::*;
+pub use validation::*;
+pub use visit::{Visit, VisitMut};
 
 #[cfg(feature = "emitter")]
 pub use emitter::*;
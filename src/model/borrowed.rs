@@ -0,0 +1,398 @@
+//! A form of the model that borrows the bulk contents of a module from its binary input.
+
+use crate::model::module::CustomSections;
+use crate::model::{
+    Custom, Data, DataMode, Export, ExportDescription, Expression, Import, ImportDescription,
+    MemoryIndex, Module, ModuleSection, Name,
+};
+use std::collections::HashMap;
+
+/// A module whose import and export names, data segment initializers and custom section contents
+/// are borrowed from the input it was parsed from, rather than copied.
+/// Modules with megabytes of data segments or debugging information (e.g., DWARF custom sections),
+/// or with thousands of imports and exports, are parsed without duplicating those bytes.
+/// The remaining components of the module (e.g., functions, types, and the name section) are owned,
+/// so the names of the name section are copied from the input.
+///
+/// The data count is held by the borrowed module along with the data segments.
+/// Since the imports are held by the borrowed module as well, the index spaces of the owned components
+/// do not account for imported definitions;
+/// convert the borrowed module into an owned module to validate it.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{
+///     BorrowedCustom, BorrowedData, BorrowedExport, BorrowedImport, BorrowedModule, Custom, Data,
+///     Export, ExportDescription, Import, ImportDescription, Module, ModuleSection,
+/// };
+///
+/// let mut builder = Module::builder();
+///
+/// builder.set_data_count(Some(1));
+///
+/// let initializer = [42];
+/// let contents = [0, 1];
+/// let module = BorrowedModule::new(
+///     builder.build(),
+///     Some(vec![BorrowedImport::new("env", "log", ImportDescription::Function(0))]),
+///     Some(vec![BorrowedExport::new("main", ExportDescription::Function(1))]),
+///     Some(vec![BorrowedData::passive(&initializer)]),
+///     vec![(ModuleSection::Data, BorrowedCustom::new("version", &contents))],
+/// );
+///
+/// assert_eq!(module.data_count(), Some(1));
+/// assert_eq!(module.module().data_count(), None);
+/// assert_eq!(module.imports(), Some(&[BorrowedImport::new("env", "log", ImportDescription::Function(0))][..]));
+/// assert_eq!(module.module().imports(), None);
+/// assert_eq!(module.exports(), Some(&[BorrowedExport::new("main", ExportDescription::Function(1))][..]));
+/// assert_eq!(module.module().exports(), None);
+/// assert_eq!(module.data(), Some(&[BorrowedData::passive(&initializer)][..]));
+/// assert_eq!(
+///     module.custom_sections_at(ModuleSection::Data),
+///     Some(&[BorrowedCustom::new("version", &contents)][..])
+/// );
+/// assert_eq!(module.custom_sections_at(ModuleSection::Code), None);
+///
+/// let mut builder = Module::builder();
+///
+/// builder.set_imports(Some(vec![Import::function("env".into(), "log".into(), 0)]));
+/// builder.set_exports(Some(vec![Export::function("main".into(), 1)]));
+/// builder.set_data_count(Some(1));
+/// builder.set_data(Some(vec![Data::passive(vec![42])]));
+/// builder.add_custom_section(ModuleSection::Data, Custom::new("version".into(), vec![0, 1]));
+///
+/// assert_eq!(module.into_owned(), builder.build());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowedModule<'input> {
+    module: Module,
+    imports: Option<Vec<BorrowedImport<'input>>>,
+    exports: Option<Vec<BorrowedExport<'input>>>,
+    data_count: Option<u32>,
+    data: Option<Vec<BorrowedData<'input>>>,
+    custom_sections: HashMap<ModuleSection, Vec<BorrowedCustom<'input>>>,
+}
+
+impl<'input> BorrowedModule<'input> {
+    /// Creates a borrowed module from the owned components of a module, its borrowed imports, exports
+    /// and data segments, and its borrowed custom sections along with their insertion points.
+    /// The imports, exports, data segments and custom sections of the owned module are replaced by the borrowed ones,
+    /// and its data count is moved into the borrowed module.
+    pub fn new(
+        module: Module,
+        imports: Option<Vec<BorrowedImport<'input>>>,
+        exports: Option<Vec<BorrowedExport<'input>>>,
+        data: Option<Vec<BorrowedData<'input>>>,
+        custom_sections: Vec<(ModuleSection, BorrowedCustom<'input>)>,
+    ) -> Self {
+        let data_count = module.data_count;
+        let module = Module {
+            imports: None,
+            exports: None,
+            data_count: None,
+            data: None,
+            custom_sections: CustomSections::new(),
+            ..module
+        };
+        let mut sections: HashMap<ModuleSection, Vec<BorrowedCustom<'input>>> = HashMap::new();
+
+        for (insertion_point, custom_section) in custom_sections {
+            sections
+                .entry(insertion_point)
+                .or_default()
+                .push(custom_section);
+        }
+
+        BorrowedModule {
+            module,
+            imports,
+            exports,
+            data_count,
+            data,
+            custom_sections: sections,
+        }
+    }
+
+    /// The owned components of the module.
+    /// The imports, exports, data count, data segments and custom sections are held by the borrowed module instead.
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// The borrowed imports of the module.
+    pub fn imports(&self) -> Option<&[BorrowedImport<'input>]> {
+        self.imports.as_deref()
+    }
+
+    /// The borrowed exports of the module.
+    pub fn exports(&self) -> Option<&[BorrowedExport<'input>]> {
+        self.exports.as_deref()
+    }
+
+    /// The number of data segments declared by the data count section of the module.
+    pub fn data_count(&self) -> Option<u32> {
+        self.data_count
+    }
+
+    /// The borrowed data segments of the module.
+    pub fn data(&self) -> Option<&[BorrowedData<'input>]> {
+        self.data.as_deref()
+    }
+
+    /// The borrowed custom sections of the module for a given insertion point.
+    pub fn custom_sections_at(
+        &self,
+        insertion_point: ModuleSection,
+    ) -> Option<&[BorrowedCustom<'input>]> {
+        self.custom_sections
+            .get(&insertion_point)
+            .map(Vec::as_slice)
+    }
+
+    /// Copies the borrowed contents of the module into an owned module.
+    pub fn into_owned(self) -> Module {
+        let mut builder = self.module.into_builder();
+
+        builder.set_imports(
+            self.imports
+                .map(|imports| imports.into_iter().map(Import::from).collect()),
+        );
+        builder.set_exports(
+            self.exports
+                .map(|exports| exports.into_iter().map(Export::from).collect()),
+        );
+        builder.set_data_count(self.data_count);
+        builder.set_data(
+            self.data
+                .map(|data| data.into_iter().map(Data::from).collect()),
+        );
+
+        for (insertion_point, custom_sections) in self.custom_sections {
+            builder.set_custom_sections(
+                insertion_point,
+                Some(custom_sections.into_iter().map(Custom::from).collect()),
+            );
+        }
+
+        builder.build()
+    }
+}
+
+impl<'input> From<BorrowedModule<'input>> for Module {
+    fn from(module: BorrowedModule<'input>) -> Self {
+        module.into_owned()
+    }
+}
+
+/// An import whose module and name are borrowed from the input it was parsed from.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/modules.html#imports>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{BorrowedImport, Import, ImportDescription};
+///
+/// let import = BorrowedImport::new("env", "log", ImportDescription::Function(0));
+///
+/// assert_eq!(import.module(), "env");
+/// assert_eq!(import.name(), "log");
+/// assert_eq!(import.description(), &ImportDescription::Function(0));
+/// assert_eq!(Import::from(import), Import::function("env".into(), "log".into(), 0));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowedImport<'input> {
+    module: &'input str,
+    name: &'input str,
+    description: ImportDescription,
+}
+
+impl<'input> BorrowedImport<'input> {
+    /// Creates a new borrowed import.
+    pub fn new(module: &'input str, name: &'input str, description: ImportDescription) -> Self {
+        BorrowedImport {
+            module,
+            name,
+            description,
+        }
+    }
+
+    /// The name of the module the import is provided by.
+    pub fn module(&self) -> &'input str {
+        self.module
+    }
+
+    /// The name of the import within its module.
+    pub fn name(&self) -> &'input str {
+        self.name
+    }
+
+    /// The description of the import.
+    pub fn description(&self) -> &ImportDescription {
+        &self.description
+    }
+}
+
+impl<'input> From<BorrowedImport<'input>> for Import {
+    fn from(import: BorrowedImport<'input>) -> Self {
+        Import::new(
+            Name::from(import.module),
+            Name::from(import.name),
+            import.description,
+        )
+    }
+}
+
+/// An export whose name is borrowed from the input it was parsed from.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/modules.html#exports>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{BorrowedExport, Export, ExportDescription};
+///
+/// let export = BorrowedExport::new("main", ExportDescription::Function(1));
+///
+/// assert_eq!(export.name(), "main");
+/// assert_eq!(export.description(), &ExportDescription::Function(1));
+/// assert_eq!(Export::from(export), Export::function("main".into(), 1));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BorrowedExport<'input> {
+    name: &'input str,
+    description: ExportDescription,
+}
+
+impl<'input> BorrowedExport<'input> {
+    /// Creates a new borrowed export.
+    pub fn new(name: &'input str, description: ExportDescription) -> Self {
+        BorrowedExport { name, description }
+    }
+
+    /// The name of the export.
+    pub fn name(&self) -> &'input str {
+        self.name
+    }
+
+    /// The description of the export.
+    pub fn description(&self) -> &ExportDescription {
+        &self.description
+    }
+}
+
+impl<'input> From<BorrowedExport<'input>> for Export {
+    fn from(export: BorrowedExport<'input>) -> Self {
+        Export::new(Name::from(export.name), export.description)
+    }
+}
+
+/// A data segment whose initializer is borrowed from the input it was parsed from.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/modules.html#data-segments>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{BorrowedData, Data, DataMode, Expression};
+///
+/// let initializer = [42];
+/// let offset: Expression = vec![1u32.into()].into();
+/// let data = BorrowedData::active(0, offset.clone(), &initializer);
+///
+/// assert_eq!(data, BorrowedData::new(DataMode::Active(0, offset.clone()), &initializer));
+/// assert_eq!(data.mode(), &DataMode::Active(0, offset.clone()));
+/// assert_eq!(data.initializer(), &initializer);
+/// assert_eq!(data.len(), 1);
+/// assert_eq!(data.is_empty(), false);
+/// assert_eq!(Data::from(data), Data::active(0, offset, vec![42]));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowedData<'input> {
+    mode: DataMode,
+    initializer: &'input [u8],
+}
+
+impl<'input> BorrowedData<'input> {
+    /// Creates an instance of a borrowed data segment.
+    pub fn new(mode: DataMode, initializer: &'input [u8]) -> Self {
+        BorrowedData { mode, initializer }
+    }
+
+    /// Creates an instance of a passive borrowed data segment.
+    pub fn passive(initializer: &'input [u8]) -> Self {
+        BorrowedData::new(DataMode::Passive, initializer)
+    }
+
+    /// Creates an instance of an active borrowed data segment.
+    pub fn active(memory: MemoryIndex, offset: Expression, initializer: &'input [u8]) -> Self {
+        BorrowedData::new(DataMode::Active(memory, offset), initializer)
+    }
+
+    /// The mode of the data segment.
+    pub fn mode(&self) -> &DataMode {
+        &self.mode
+    }
+
+    /// The data to initialize the segment with.
+    pub fn initializer(&self) -> &'input [u8] {
+        self.initializer
+    }
+
+    /// The number of bytes in the data segment initializer.
+    pub fn len(&self) -> usize {
+        self.initializer.len()
+    }
+
+    /// True if the data segment's initializer's length is zero, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.initializer.is_empty()
+    }
+}
+
+impl<'input> From<BorrowedData<'input>> for Data {
+    fn from(data: BorrowedData<'input>) -> Self {
+        Data::new(data.mode, data.initializer.to_vec())
+    }
+}
+
+/// A custom section whose name and contents are borrowed from the input it was parsed from.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#custom-section>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{BorrowedCustom, Custom};
+///
+/// let contents = [0, 1];
+/// let custom = BorrowedCustom::new("version", &contents);
+///
+/// assert_eq!(custom.name(), "version");
+/// assert_eq!(custom.bytes(), &contents);
+/// assert_eq!(Custom::from(custom), Custom::new("version".into(), vec![0, 1]));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BorrowedCustom<'input> {
+    name: &'input str,
+    bytes: &'input [u8],
+}
+
+impl<'input> BorrowedCustom<'input> {
+    /// Creates a new instance of a borrowed custom section.
+    pub fn new(name: &'input str, bytes: &'input [u8]) -> Self {
+        BorrowedCustom { name, bytes }
+    }
+
+    /// The name of the custom section.
+    pub fn name(&self) -> &'input str {
+        self.name
+    }
+
+    /// The contents of the custom section.
+    pub fn bytes(&self) -> &'input [u8] {
+        self.bytes
+    }
+}
+
+impl<'input> From<BorrowedCustom<'input>> for Custom {
+    fn from(custom: BorrowedCustom<'input>) -> Self {
+        Custom::new(Name::from(custom.name), custom.bytes.to_vec())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::model::FunctionIndex;
 use thiserror::Error;
 
 /// An error in a WebAssembly module model.
@@ -5,4 +6,6 @@ use thiserror::Error;
 pub enum ModelError {
     #[error("The module does not have enough space to add the given component. The indices in a WebAssembly module are limited by the capacity of a u32.")]
     IndexOverflow(#[from] std::num::TryFromIntError),
+    #[error("The module does not define a function with index {0}. Imported functions cannot be replaced.")]
+    UndefinedFunction(FunctionIndex),
 }
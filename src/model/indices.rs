@@ -12,7 +12,15 @@
 //!
 //! Label indices reference structured control instructions inside an instruction sequence.
 //!
+//! Tag indices reference the tags of exceptions, including tag imports.
+//!
+//! Field indices reference the fields of a structure type and are local to that type.
+//!
 //! See <https://webassembly.github.io/spec/core/syntax/modules.html#indices>
+//!
+//! See <https://webassembly.github.io/exception-handling/core/syntax/modules.html#indices>
+//!
+//! See <https://webassembly.github.io/gc/core/syntax/modules.html#indices>
 
 pub type TypeIndex = u32;
 pub type FunctionIndex = u32;
@@ -23,3 +31,5 @@ pub type ElementIndex = u32;
 pub type DataIndex = u32;
 pub type LocalIndex = u32;
 pub type LabelIndex = u32;
+pub type TagIndex = u32;
+pub type FieldIndex = u32;
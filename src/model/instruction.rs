@@ -1,8 +1,9 @@
 //! WebAssembly instruction set.
 
 use crate::model::{
-    DataIndex, ElementIndex, FloatType, FunctionIndex, GlobalIndex, IntegerType, LabelIndex,
-    LocalIndex, NumberType, ReferenceType, TableIndex, TypeIndex, ValueType,
+    DataIndex, ElementIndex, FieldIndex, FloatType, FunctionIndex, GlobalIndex, HeapType,
+    IntegerType, LabelIndex, LocalIndex, MemoryIndex, NumberType, ReferenceType, TableIndex,
+    TagIndex, TypeIndex, ValueType,
 };
 
 /// WebAssembly code consists of sequences of instructions.
@@ -22,11 +23,14 @@ use crate::model::{
 #[derive(Clone, Debug, PartialEq)]
 pub enum Instruction {
     Numeric(NumericInstruction),
+    Vector(VectorInstruction),
     Reference(ReferenceInstruction),
     Parametric(ParametricInstruction),
     Variable(VariableInstruction),
     Table(TableInstruction),
     Memory(MemoryInstruction),
+    Atomic(AtomicInstruction),
+    Aggregate(AggregateInstruction),
     Control(ControlInstruction),
 }
 
@@ -452,18 +456,397 @@ impl From<f64> for Instruction {
     }
 }
 
+/// Vector instructions (also known as SIMD instructions, single data multiple value) provide
+/// basic operations over values of vector type.
+///
+/// Vector instructions have a naming convention involving a prefix that determines how their
+/// operands will be interpreted. This prefix describes the shape of the operand,
+/// written t𝗑N, and consisting of a packed numeric type t and the number of lanes N of that type.
+/// Operations are performed point-wise on the values of each lane.
+///
+/// Instructions prefixed with 𝗏𝟣𝟤𝟪 do not involve a specific interpretation,
+/// and treat the 𝗏𝟣𝟤𝟪 as an 𝗂𝟣𝟤𝟪 value or a vector of 128 individual bits.
+///
+/// Some instructions are not available for all shapes.
+/// Such instructions cannot be emitted in the binary format.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+///
+/// # Examples
+/// ## Constant
+/// ```rust
+/// use wasm_ast::{VectorInstruction, Instruction};
+///
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Constant(42)),
+///     42i128.into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Shuffle([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])),
+///     VectorInstruction::Shuffle([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]).into()
+/// );
+/// ```
+///
+/// ## Lanes
+/// ```rust
+/// use wasm_ast::{VectorInstruction, Instruction, VectorShape, SignExtension};
+///
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Splat(VectorShape::F32x4)),
+///     VectorInstruction::Splat(VectorShape::F32x4).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Signed), 15)),
+///     VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Signed), 15).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::ReplaceLane(VectorShape::I64x2, 1)),
+///     VectorInstruction::ReplaceLane(VectorShape::I64x2, 1).into()
+/// );
+/// ```
+///
+/// ## Arithmetic
+/// ```rust
+/// use wasm_ast::{VectorInstruction, Instruction, VectorShape, IntegerVectorShape, FloatVectorShape, SignExtension, VectorHalf};
+///
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Add(VectorShape::I32x4)),
+///     VectorInstruction::Add(VectorShape::I32x4).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned)),
+///     VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::PseudoMinimum(FloatVectorShape::F64x2)),
+///     VectorInstruction::PseudoMinimum(FloatVectorShape::F64x2).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::ExtendMultiply(IntegerVectorShape::I64x2, VectorHalf::High, SignExtension::Signed)),
+///     VectorInstruction::ExtendMultiply(IntegerVectorShape::I64x2, VectorHalf::High, SignExtension::Signed).into()
+/// );
+/// ```
+///
+/// ## Memory
+/// ```rust
+/// use wasm_ast::{VectorInstruction, Instruction, MemoryArgument, SignExtension};
+///
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Load(MemoryArgument::default_offset(16))),
+///     VectorInstruction::Load(MemoryArgument::default_offset(16)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Load8x8(SignExtension::Unsigned, MemoryArgument::default_offset(8))),
+///     VectorInstruction::Load8x8(SignExtension::Unsigned, MemoryArgument::default_offset(8)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Vector(VectorInstruction::Store32Lane(MemoryArgument::default_offset(4), 3)),
+///     VectorInstruction::Store32Lane(MemoryArgument::default_offset(4), 3).into()
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VectorInstruction {
+    /// v128.const i128
+    Constant(i128),
+    /// v128.not
+    Not,
+    /// v128.and
+    And,
+    /// v128.andnot
+    AndNot,
+    /// v128.or
+    Or,
+    /// v128.xor
+    Xor,
+    /// v128.bitselect
+    BitSelect,
+    /// v128.any_true
+    AnyTrue,
+    /// i8x16.shuffle laneidx^16
+    Shuffle([LaneIndex; 16]),
+    /// i8x16.swizzle
+    Swizzle,
+    /// shape.splat
+    Splat(VectorShape),
+    /// shape.extract_lane_sx? laneidx
+    /// The sign extension is only present for the 𝗂𝟪𝗑𝟣𝟨 and 𝗂𝟣𝟨𝗑𝟪 shapes.
+    ExtractLane(VectorShape, Option<SignExtension>, LaneIndex),
+    /// shape.replace_lane laneidx
+    ReplaceLane(VectorShape, LaneIndex),
+    /// shape.eq
+    Equal(VectorShape),
+    /// shape.ne
+    NotEqual(VectorShape),
+    /// ishape.lt_sx
+    LessThanInteger(IntegerVectorShape, SignExtension),
+    /// fshape.lt
+    LessThanFloat(FloatVectorShape),
+    /// ishape.gt_sx
+    GreaterThanInteger(IntegerVectorShape, SignExtension),
+    /// fshape.gt
+    GreaterThanFloat(FloatVectorShape),
+    /// ishape.le_sx
+    LessThanOrEqualToInteger(IntegerVectorShape, SignExtension),
+    /// fshape.le
+    LessThanOrEqualToFloat(FloatVectorShape),
+    /// ishape.ge_sx
+    GreaterThanOrEqualToInteger(IntegerVectorShape, SignExtension),
+    /// fshape.ge
+    GreaterThanOrEqualToFloat(FloatVectorShape),
+    /// shape.abs
+    AbsoluteValue(VectorShape),
+    /// shape.neg
+    Negate(VectorShape),
+    /// i8x16.popcnt
+    CountOnes,
+    /// fshape.sqrt
+    SquareRoot(FloatVectorShape),
+    /// fshape.ceil
+    Ceiling(FloatVectorShape),
+    /// fshape.floor
+    Floor(FloatVectorShape),
+    /// fshape.trunc
+    Truncate(FloatVectorShape),
+    /// fshape.nearest
+    Nearest(FloatVectorShape),
+    /// ishape.all_true
+    AllTrue(IntegerVectorShape),
+    /// ishape.bitmask
+    Bitmask(IntegerVectorShape),
+    /// ishape.narrow_ishape_sx
+    /// The shape is that of the result (i.e., 𝗂𝟪𝗑𝟣𝟨 or 𝗂𝟣𝟨𝗑𝟪).
+    Narrow(IntegerVectorShape, SignExtension),
+    /// ishape.extend_half_ishape_sx
+    /// The shape is that of the result (i.e., 𝗂𝟣𝟨𝗑𝟪, 𝗂𝟥𝟤𝗑𝟦 or 𝗂𝟨𝟦𝗑𝟤).
+    Extend(IntegerVectorShape, VectorHalf, SignExtension),
+    /// ishape.shl
+    ShiftLeft(IntegerVectorShape),
+    /// ishape.shr_sx
+    ShiftRight(IntegerVectorShape, SignExtension),
+    /// shape.add
+    Add(VectorShape),
+    /// shape.sub
+    Subtract(VectorShape),
+    /// shape.mul
+    Multiply(VectorShape),
+    /// ishape.add_sat_sx
+    AddSaturate(IntegerVectorShape, SignExtension),
+    /// ishape.sub_sat_sx
+    SubtractSaturate(IntegerVectorShape, SignExtension),
+    /// ishape.min_sx
+    MinimumInteger(IntegerVectorShape, SignExtension),
+    /// ishape.max_sx
+    MaximumInteger(IntegerVectorShape, SignExtension),
+    /// fshape.div
+    DivideFloat(FloatVectorShape),
+    /// fshape.min
+    MinimumFloat(FloatVectorShape),
+    /// fshape.max
+    MaximumFloat(FloatVectorShape),
+    /// fshape.pmin
+    PseudoMinimum(FloatVectorShape),
+    /// fshape.pmax
+    PseudoMaximum(FloatVectorShape),
+    /// ishape.avgr_u
+    AverageRounded(IntegerVectorShape),
+    /// i16x8.q15mulr_sat_s
+    Q15MultiplyRoundSaturate,
+    /// i32x4.dot_i16x8_s
+    DotProduct,
+    /// ishape.extmul_half_ishape_sx
+    /// The shape is that of the result (i.e., 𝗂𝟣𝟨𝗑𝟪, 𝗂𝟥𝟤𝗑𝟦 or 𝗂𝟨𝟦𝗑𝟤).
+    ExtendMultiply(IntegerVectorShape, VectorHalf, SignExtension),
+    /// ishape.extadd_pairwise_ishape_sx
+    /// The shape is that of the result (i.e., 𝗂𝟣𝟨𝗑𝟪 or 𝗂𝟥𝟤𝗑𝟦).
+    ExtendAddPairwise(IntegerVectorShape, SignExtension),
+    /// f32x4.demote_f64x2_zero
+    Demote,
+    /// f64x2.promote_low_f32x4
+    Promote,
+    /// i32x4.trunc_sat_f32x4_sx or i32x4.trunc_sat_f64x2_sx_zero
+    /// The shape is that of the operand.
+    ConvertAndTruncateWithSaturation(FloatVectorShape, SignExtension),
+    /// f32x4.convert_i32x4_sx or f64x2.convert_low_i32x4_sx
+    /// The shape is that of the result.
+    Convert(FloatVectorShape, SignExtension),
+    /// v128.load memarg
+    Load(MemoryArgument),
+    /// v128.store memarg
+    Store(MemoryArgument),
+    /// v128.load8x8_sx memarg
+    /// Loads 8 packed 8-bit integers, extending each to 16 bits.
+    Load8x8(SignExtension, MemoryArgument),
+    /// v128.load16x4_sx memarg
+    Load16x4(SignExtension, MemoryArgument),
+    /// v128.load32x2_sx memarg
+    Load32x2(SignExtension, MemoryArgument),
+    /// v128.load8_splat memarg
+    /// Loads a single value and repeats it in every lane of the vector.
+    Load8Splat(MemoryArgument),
+    /// v128.load16_splat memarg
+    Load16Splat(MemoryArgument),
+    /// v128.load32_splat memarg
+    Load32Splat(MemoryArgument),
+    /// v128.load64_splat memarg
+    Load64Splat(MemoryArgument),
+    /// v128.load32_zero memarg
+    /// Loads a single value into the lowest lane, setting all other bits to zero.
+    Load32Zero(MemoryArgument),
+    /// v128.load64_zero memarg
+    Load64Zero(MemoryArgument),
+    /// v128.load8_lane memarg laneidx
+    /// Loads a single value into the given lane, leaving the other lanes untouched.
+    Load8Lane(MemoryArgument, LaneIndex),
+    /// v128.load16_lane memarg laneidx
+    Load16Lane(MemoryArgument, LaneIndex),
+    /// v128.load32_lane memarg laneidx
+    Load32Lane(MemoryArgument, LaneIndex),
+    /// v128.load64_lane memarg laneidx
+    Load64Lane(MemoryArgument, LaneIndex),
+    /// v128.store8_lane memarg laneidx
+    /// Stores the value of a single lane.
+    Store8Lane(MemoryArgument, LaneIndex),
+    /// v128.store16_lane memarg laneidx
+    Store16Lane(MemoryArgument, LaneIndex),
+    /// v128.store32_lane memarg laneidx
+    Store32Lane(MemoryArgument, LaneIndex),
+    /// v128.store64_lane memarg laneidx
+    Store64Lane(MemoryArgument, LaneIndex),
+}
+
+impl From<VectorInstruction> for Instruction {
+    fn from(instruction: VectorInstruction) -> Self {
+        Instruction::Vector(instruction)
+    }
+}
+
+impl From<i128> for Instruction {
+    fn from(value: i128) -> Self {
+        Self::Vector(VectorInstruction::Constant(value))
+    }
+}
+
+/// The shape of a vector operand, consisting of a packed numeric type and the number of lanes of
+/// that type.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{VectorShape, IntegerVectorShape, FloatVectorShape};
+///
+/// assert_eq!(VectorShape::I8x16, IntegerVectorShape::I8x16.into());
+/// assert_eq!(VectorShape::I16x8, IntegerVectorShape::I16x8.into());
+/// assert_eq!(VectorShape::I32x4, IntegerVectorShape::I32x4.into());
+/// assert_eq!(VectorShape::I64x2, IntegerVectorShape::I64x2.into());
+/// assert_eq!(VectorShape::F32x4, FloatVectorShape::F32x4.into());
+/// assert_eq!(VectorShape::F64x2, FloatVectorShape::F64x2.into());
+/// assert_eq!(VectorShape::I8x16.lanes(), 16);
+/// assert_eq!(VectorShape::F64x2.lanes(), 2);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VectorShape {
+    I8x16,
+    I16x8,
+    I32x4,
+    I64x2,
+    F32x4,
+    F64x2,
+}
+
+impl VectorShape {
+    /// The number of lanes in a vector of this shape.
+    pub fn lanes(&self) -> u8 {
+        match self {
+            VectorShape::I8x16 => 16,
+            VectorShape::I16x8 => 8,
+            VectorShape::I32x4 | VectorShape::F32x4 => 4,
+            VectorShape::I64x2 | VectorShape::F64x2 => 2,
+        }
+    }
+}
+
+impl From<IntegerVectorShape> for VectorShape {
+    fn from(shape: IntegerVectorShape) -> Self {
+        match shape {
+            IntegerVectorShape::I8x16 => VectorShape::I8x16,
+            IntegerVectorShape::I16x8 => VectorShape::I16x8,
+            IntegerVectorShape::I32x4 => VectorShape::I32x4,
+            IntegerVectorShape::I64x2 => VectorShape::I64x2,
+        }
+    }
+}
+
+impl From<FloatVectorShape> for VectorShape {
+    fn from(shape: FloatVectorShape) -> Self {
+        match shape {
+            FloatVectorShape::F32x4 => VectorShape::F32x4,
+            FloatVectorShape::F64x2 => VectorShape::F64x2,
+        }
+    }
+}
+
+/// The shapes of vectors with packed integer lanes.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IntegerVectorShape {
+    I8x16,
+    I16x8,
+    I32x4,
+    I64x2,
+}
+
+/// The shapes of vectors with packed floating-point lanes.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FloatVectorShape {
+    F32x4,
+    F64x2,
+}
+
+/// Selects which half of the lanes of a vector operand is used by an extending instruction.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VectorHalf {
+    Low,
+    High,
+}
+
+/// Lane indices select a single lane of a vector operand.
+///
+/// See <https://webassembly.github.io/spec/core/syntax/instructions.html#vector-instructions>
+pub type LaneIndex = u8;
+
 /// Instructions in this group are concerned with accessing references.
-/// These instruction produce a null value, check for a null value, or produce a reference to a given function, respectively.
+/// These instruction produce a null value, check for a null value, produce a reference to a given function,
+/// or assert that a reference is not null, respectively.
+/// With garbage collection, references can also be compared for equality,
+/// tested against a reference type, or cast to a reference type.
 ///
 /// See <https://webassembly.github.io/spec/core/syntax/instructions.html#reference-instructions>
 ///
+/// See <https://webassembly.github.io/function-references/core/syntax/instructions.html#reference-instructions>
+///
+/// See <https://webassembly.github.io/gc/core/syntax/instructions.html#reference-instructions>
+///
 /// # Examples
 /// ```rust
-/// use wasm_ast::{ReferenceInstruction, Instruction, ReferenceType};
+/// use wasm_ast::{ReferenceInstruction, Instruction, HeapType, ReferenceType};
 ///
 /// assert_eq!(
-///     Instruction::Reference(ReferenceInstruction::Null(ReferenceType::External)),
-///     ReferenceInstruction::Null(ReferenceType::External).into()
+///     Instruction::Reference(ReferenceInstruction::Null(HeapType::External)),
+///     ReferenceInstruction::Null(HeapType::External).into()
+/// );
+/// assert_eq!(
+///     Instruction::Reference(ReferenceInstruction::Null(HeapType::Index(0))),
+///     ReferenceInstruction::Null(HeapType::Index(0)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Reference(ReferenceInstruction::AsNonNull),
+///     ReferenceInstruction::AsNonNull.into()
 /// );
 /// assert_eq!(
 ///     Instruction::Reference(ReferenceInstruction::IsNull),
@@ -473,18 +856,42 @@ impl From<f64> for Instruction {
 ///     Instruction::Reference(ReferenceInstruction::Function(3)),
 ///     ReferenceInstruction::Function(3).into()
 /// );
+/// assert_eq!(
+///     Instruction::Reference(ReferenceInstruction::Equal),
+///     ReferenceInstruction::Equal.into()
+/// );
+/// assert_eq!(
+///     Instruction::Reference(ReferenceInstruction::Test(ReferenceType::NonNullable(HeapType::I31))),
+///     ReferenceInstruction::Test(ReferenceType::NonNullable(HeapType::I31)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Reference(ReferenceInstruction::Cast(ReferenceType::Nullable(HeapType::Index(0)))),
+///     ReferenceInstruction::Cast(ReferenceType::Nullable(HeapType::Index(0))).into()
+/// );
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ReferenceInstruction {
-    /// ref.null
+    /// ref.null heaptype
     /// Produce a null value.
-    Null(ReferenceType),
+    Null(HeapType),
     /// ref.is_null
     /// Check for a null value.
     IsNull,
     /// ref.func funcidx
     /// Produce a reference to a given function.
     Function(FunctionIndex),
+    /// ref.as_non_null
+    /// Convert a nullable reference to a non-nullable one, trapping if it is null.
+    AsNonNull,
+    /// ref.eq
+    /// Compare two references of type 𝖾𝗊𝗋𝖾𝖿 for equality.
+    Equal,
+    /// ref.test reftype
+    /// Check whether a reference has the given reference type.
+    Test(ReferenceType),
+    /// ref.cast reftype
+    /// Cast a reference to the given reference type, trapping if the cast fails.
+    Cast(ReferenceType),
 }
 
 impl From<ReferenceInstruction> for Instruction {
@@ -673,7 +1080,7 @@ impl From<TableInstruction> for Instruction {
 /// In the case of loads, a sign extension mode sx is then required to select appropriate behavior.
 ///
 /// The static address offset is added to the dynamic address operand,
-/// yielding a 33 bit effective address that is the zero-based index at which the memory is accessed.
+/// yielding a 33 bit (or 65 bit for 64-bit memories) effective address that is the zero-based index at which the memory is accessed.
 /// All values are read and written in little endian byte order.
 /// A trap results if any of the accessed memory bytes lies outside the address range implied by
 /// the memory’s current size.
@@ -717,24 +1124,24 @@ impl From<TableInstruction> for Instruction {
 ///     MemoryInstruction::Store32(MemoryArgument::default_offset(4)).into()
 /// );
 /// assert_eq!(
-///     Instruction::Memory(MemoryInstruction::Size),
-///     MemoryInstruction::Size.into()
+///     Instruction::Memory(MemoryInstruction::Size(0)),
+///     MemoryInstruction::Size(0).into()
 /// );
 /// assert_eq!(
-///     Instruction::Memory(MemoryInstruction::Grow),
-///     MemoryInstruction::Grow.into()
+///     Instruction::Memory(MemoryInstruction::Grow(0)),
+///     MemoryInstruction::Grow(0).into()
 /// );
 /// assert_eq!(
-///     Instruction::Memory(MemoryInstruction::Fill),
-///     MemoryInstruction::Fill.into()
+///     Instruction::Memory(MemoryInstruction::Fill(1)),
+///     MemoryInstruction::Fill(1).into()
 /// );
 /// assert_eq!(
-///     Instruction::Memory(MemoryInstruction::Copy),
-///     MemoryInstruction::Copy.into()
+///     Instruction::Memory(MemoryInstruction::Copy(0, 1)),
+///     MemoryInstruction::Copy(0, 1).into()
 /// );
 /// assert_eq!(
-///     Instruction::Memory(MemoryInstruction::Init(1)),
-///     MemoryInstruction::Init(1).into()
+///     Instruction::Memory(MemoryInstruction::Init(1, 0)),
+///     MemoryInstruction::Init(1, 0).into()
 /// );
 /// assert_eq!(
 ///     Instruction::Memory(MemoryInstruction::DataDrop(0)),
@@ -767,17 +1174,17 @@ pub enum MemoryInstruction {
     Store32(MemoryArgument),
     /// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝗌𝗂𝗓𝖾 instruction returns the current size of a memory.
     /// Operates in units of page size.
-    Size,
+    Size(MemoryIndex),
     /// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝗀𝗋𝗈𝗐 instruction grows memory by a given delta and returns the previous size,
     /// or −1 if enough memory cannot be allocated.
-    Grow,
+    Grow(MemoryIndex),
     /// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝖿𝗂𝗅𝗅 instruction sets all values in a region to a given byte.
-    Fill,
+    Fill(MemoryIndex),
     /// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝖼𝗈𝗉𝗒 instruction copies data from a source memory region to
-    /// a possibly overlapping destination region.
-    Copy,
+    /// a possibly overlapping destination region; the first index denotes the destination.
+    Copy(MemoryIndex, MemoryIndex),
     /// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝗂𝗇𝗂𝗍 instruction copies data from a passive data segment into a memory.
-    Init(DataIndex),
+    Init(DataIndex, MemoryIndex),
     /// he 𝖽𝖺𝗍𝖺.𝖽𝗋𝗈𝗉 instruction prevents further use of a passive data segment.
     /// This instruction is intended to be used as an optimization hint.
     /// After a data segment is dropped its data can no longer be retrieved,
@@ -791,6 +1198,222 @@ impl From<MemoryInstruction> for Instruction {
     }
 }
 
+/// Atomic instructions access shared linear memory with well-defined behavior when accessed by
+/// multiple threads concurrently.
+/// Atomic loads, stores and read-modify-write operations require natural alignment and trap if the
+/// effective address is not aligned.
+/// Narrow read-modify-write instructions zero-extend their result, as indicated by the _u suffix.
+///
+/// The 𝗆𝖾𝗆𝗈𝗋𝗒.𝖺𝗍𝗈𝗆𝗂𝖼.𝗐𝖺𝗂𝗍 and 𝗆𝖾𝗆𝗈𝗋𝗒.𝖺𝗍𝗈𝗆𝗂𝖼.𝗇𝗈𝗍𝗂𝖿𝗒 instructions suspend and wake threads
+/// waiting on an address, while 𝖺𝗍𝗈𝗆𝗂𝖼.𝖿𝖾𝗇𝖼𝖾 orders memory accesses without accessing memory itself.
+///
+/// See <https://webassembly.github.io/threads/core/syntax/instructions.html#memory-instructions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{AtomicInstruction, AtomicOperation, Instruction, IntegerType, MemoryArgument};
+///
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::Notify(MemoryArgument::default_offset(2))),
+///     AtomicInstruction::Notify(MemoryArgument::default_offset(2)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::Wait(IntegerType::I64, MemoryArgument::default_offset(3))),
+///     AtomicInstruction::Wait(IntegerType::I64, MemoryArgument::default_offset(3)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::Fence),
+///     AtomicInstruction::Fence.into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::Load(IntegerType::I32, MemoryArgument::default_offset(2))),
+///     AtomicInstruction::Load(IntegerType::I32, MemoryArgument::default_offset(2)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::Store16(IntegerType::I64, MemoryArgument::default_offset(1))),
+///     AtomicInstruction::Store16(IntegerType::I64, MemoryArgument::default_offset(1)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::ReadModifyWrite(AtomicOperation::Add, IntegerType::I32, MemoryArgument::default_offset(2))),
+///     AtomicInstruction::ReadModifyWrite(AtomicOperation::Add, IntegerType::I32, MemoryArgument::default_offset(2)).into()
+/// );
+/// assert_eq!(
+///     Instruction::Atomic(AtomicInstruction::ReadModifyWrite32(AtomicOperation::CompareExchange, MemoryArgument::default_offset(2))),
+///     AtomicInstruction::ReadModifyWrite32(AtomicOperation::CompareExchange, MemoryArgument::default_offset(2)).into()
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AtomicInstruction {
+    /// memory.atomic.notify memarg
+    /// Wakes up a number of threads waiting on the given address.
+    Notify(MemoryArgument),
+    /// memory.atomic.waitnn memarg
+    /// Suspends the current thread until notified, as long as the value at the given address
+    /// matches the expected value.
+    Wait(IntegerType, MemoryArgument),
+    /// atomic.fence
+    Fence,
+    /// inn.atomic.load memarg
+    Load(IntegerType, MemoryArgument),
+    /// inn.atomic.load8_u memarg
+    Load8(IntegerType, MemoryArgument),
+    /// inn.atomic.load16_u memarg
+    Load16(IntegerType, MemoryArgument),
+    /// i64.atomic.load32_u memarg
+    Load32(MemoryArgument),
+    /// inn.atomic.store memarg
+    Store(IntegerType, MemoryArgument),
+    /// inn.atomic.store8 memarg
+    Store8(IntegerType, MemoryArgument),
+    /// inn.atomic.store16 memarg
+    Store16(IntegerType, MemoryArgument),
+    /// i64.atomic.store32 memarg
+    Store32(MemoryArgument),
+    /// inn.atomic.rmw.op memarg
+    ReadModifyWrite(AtomicOperation, IntegerType, MemoryArgument),
+    /// inn.atomic.rmw8.op_u memarg
+    ReadModifyWrite8(AtomicOperation, IntegerType, MemoryArgument),
+    /// inn.atomic.rmw16.op_u memarg
+    ReadModifyWrite16(AtomicOperation, IntegerType, MemoryArgument),
+    /// i64.atomic.rmw32.op_u memarg
+    ReadModifyWrite32(AtomicOperation, MemoryArgument),
+}
+
+impl From<AtomicInstruction> for Instruction {
+    fn from(instruction: AtomicInstruction) -> Self {
+        Instruction::Atomic(instruction)
+    }
+}
+
+/// The operation performed by an atomic read-modify-write instruction.
+///
+/// See <https://webassembly.github.io/threads/core/syntax/instructions.html#memory-instructions>
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AtomicOperation {
+    /// add
+    Add,
+    /// sub
+    Subtract,
+    /// and
+    And,
+    /// or
+    Or,
+    /// xor
+    Xor,
+    /// xchg
+    Exchange,
+    /// cmpxchg
+    CompareExchange,
+}
+
+/// Aggregate instructions allocate and access heap-allocated structures and arrays,
+/// as well as convert between references in the internal and external type hierarchies and
+/// box and unbox unboxed scalars (i.e., 𝗂𝟥𝟣).
+/// Instructions that read a field or element of packed type come in signed and unsigned flavors
+/// to extend the packed value to an 𝗂𝟥𝟤; for fields and elements of any other type,
+/// the sign extension is omitted.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/instructions.html#aggregate-instructions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{AggregateInstruction, Instruction, SignExtension};
+///
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::StructNew(0)),
+///     AggregateInstruction::StructNew(0).into()
+/// );
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::StructGet(Some(SignExtension::Signed), 0, 1)),
+///     AggregateInstruction::StructGet(Some(SignExtension::Signed), 0, 1).into()
+/// );
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::ArrayNewFixed(1, 3)),
+///     AggregateInstruction::ArrayNewFixed(1, 3).into()
+/// );
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::ArrayCopy(1, 2)),
+///     AggregateInstruction::ArrayCopy(1, 2).into()
+/// );
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::ArrayLength),
+///     AggregateInstruction::ArrayLength.into()
+/// );
+/// assert_eq!(
+///     Instruction::Aggregate(AggregateInstruction::I31Get(SignExtension::Unsigned)),
+///     AggregateInstruction::I31Get(SignExtension::Unsigned).into()
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AggregateInstruction {
+    /// struct.new typeidx
+    /// Allocate a structure with field values taken from the operand stack.
+    StructNew(TypeIndex),
+    /// struct.new_default typeidx
+    /// Allocate a structure with default field values.
+    StructNewDefault(TypeIndex),
+    /// struct.get_sx? typeidx fieldidx
+    /// Read a field of a structure.
+    StructGet(Option<SignExtension>, TypeIndex, FieldIndex),
+    /// struct.set typeidx fieldidx
+    /// Write a field of a structure.
+    StructSet(TypeIndex, FieldIndex),
+    /// array.new typeidx
+    /// Allocate an array with every element set to the given value.
+    ArrayNew(TypeIndex),
+    /// array.new_default typeidx
+    /// Allocate an array with every element set to its default value.
+    ArrayNewDefault(TypeIndex),
+    /// array.new_fixed typeidx u32
+    /// Allocate an array of a fixed size with elements taken from the operand stack.
+    ArrayNewFixed(TypeIndex, u32),
+    /// array.new_data typeidx dataidx
+    /// Allocate an array with elements taken from a data segment.
+    ArrayNewData(TypeIndex, DataIndex),
+    /// array.new_elem typeidx elemidx
+    /// Allocate an array with elements taken from an element segment.
+    ArrayNewElement(TypeIndex, ElementIndex),
+    /// array.get_sx? typeidx
+    /// Read an element of an array.
+    ArrayGet(Option<SignExtension>, TypeIndex),
+    /// array.set typeidx
+    /// Write an element of an array.
+    ArraySet(TypeIndex),
+    /// array.len
+    /// Produce the length of an array.
+    ArrayLength,
+    /// array.fill typeidx
+    /// Set a range of elements of an array to the given value.
+    ArrayFill(TypeIndex),
+    /// array.copy typeidx typeidx
+    /// Copy a range of elements from a source array (second immediate) to a destination array.
+    ArrayCopy(TypeIndex, TypeIndex),
+    /// array.init_data typeidx dataidx
+    /// Overwrite a range of elements of an array with elements from a data segment.
+    ArrayInitData(TypeIndex, DataIndex),
+    /// array.init_elem typeidx elemidx
+    /// Overwrite a range of elements of an array with elements from an element segment.
+    ArrayInitElement(TypeIndex, ElementIndex),
+    /// ref.i31
+    /// Box an 𝗂𝟥𝟤 into an unboxed scalar, discarding its most significant bit.
+    ReferenceI31,
+    /// i31.get_sx
+    /// Unbox an unboxed scalar into an 𝗂𝟥𝟤 using the given sign extension.
+    I31Get(SignExtension),
+    /// any.convert_extern
+    /// Convert an external reference into the internal type hierarchy.
+    AnyConvertExternal,
+    /// extern.convert_any
+    /// Convert an internal reference into the external type hierarchy.
+    ExternalConvertAny,
+}
+
+impl From<AggregateInstruction> for Instruction {
+    fn from(instruction: AggregateInstruction) -> Self {
+        Instruction::Aggregate(instruction)
+    }
+}
+
 /// Instructions in this group affect the flow of control.
 /// The 𝖻𝗅𝗈𝖼𝗄, 𝗅𝗈𝗈𝗉 and 𝗂𝖿 instructions are structured instructions.
 /// They bracket nested sequences of instructions, called blocks, terminated with, or separated by,
@@ -823,6 +1446,14 @@ impl From<MemoryInstruction> for Instruction {
 ///
 /// See <https://webassembly.github.io/spec/core/syntax/instructions.html#control-instructions>
 ///
+/// See <https://webassembly.github.io/exception-handling/core/syntax/instructions.html#control-instructions>
+///
+/// See <https://webassembly.github.io/tail-call/core/syntax/instructions.html#control-instructions>
+///
+/// See <https://webassembly.github.io/function-references/core/syntax/instructions.html#control-instructions>
+///
+/// See <https://webassembly.github.io/gc/core/syntax/instructions.html#control-instructions>
+///
 /// # Examples
 /// ## Simple
 /// ```rust
@@ -836,6 +1467,31 @@ impl From<MemoryInstruction> for Instruction {
 /// assert_eq!(Instruction::Control(ControlInstruction::Return), ControlInstruction::Return.into());
 /// assert_eq!(Instruction::Control(ControlInstruction::Call(1)), ControlInstruction::Call(1).into());
 /// assert_eq!(Instruction::Control(ControlInstruction::CallIndirect(0, 1)), ControlInstruction::CallIndirect(0, 1).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::ReturnCall(1)), ControlInstruction::ReturnCall(1).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::ReturnCallIndirect(0, 1)), ControlInstruction::ReturnCallIndirect(0, 1).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::CallRef(0)), ControlInstruction::CallRef(0).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::ReturnCallRef(0)), ControlInstruction::ReturnCallRef(0).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::BranchOnNull(0)), ControlInstruction::BranchOnNull(0).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::BranchOnNonNull(1)), ControlInstruction::BranchOnNonNull(1).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::Throw(0)), ControlInstruction::Throw(0).into());
+/// assert_eq!(Instruction::Control(ControlInstruction::ThrowRef), ControlInstruction::ThrowRef.into());
+/// ```
+///
+/// ## Cast
+/// ```rust
+/// use wasm_ast::{ControlInstruction, HeapType, Instruction, ReferenceType};
+///
+/// let any = ReferenceType::Nullable(HeapType::Any);
+/// let i31 = ReferenceType::NonNullable(HeapType::I31);
+///
+/// assert_eq!(
+///     Instruction::Control(ControlInstruction::BranchOnCast(0, any, i31)),
+///     ControlInstruction::BranchOnCast(0, any, i31).into()
+/// );
+/// assert_eq!(
+///     Instruction::Control(ControlInstruction::BranchOnCastFail(1, any, i31)),
+///     ControlInstruction::BranchOnCastFail(1, any, i31).into()
+/// );
 /// ```
 ///
 /// ## Block
@@ -908,6 +1564,39 @@ pub enum ControlInstruction {
     /// the callee is dynamically checked against the function type indexed by the instruction’s
     /// second immediate, and the call is aborted with a trap if it does not match.
     CallIndirect(TypeIndex, TableIndex),
+    /// The 𝗋𝖾𝗍𝗎𝗋𝗇_𝖼𝖺𝗅𝗅 instruction is the tail-call version of 𝖼𝖺𝗅𝗅.
+    /// It unwinds the frame of the current function before invoking the callee,
+    /// returning the callee's results to the caller of the current function.
+    ReturnCall(FunctionIndex),
+    /// The 𝗋𝖾𝗍𝗎𝗋𝗇_𝖼𝖺𝗅𝗅_𝗂𝗇𝖽𝗂𝗋𝖾𝖼𝗍 instruction is the tail-call version of 𝖼𝖺𝗅𝗅_𝗂𝗇𝖽𝗂𝗋𝖾𝖼𝗍.
+    ReturnCallIndirect(TypeIndex, TableIndex),
+    /// The 𝖼𝖺𝗅𝗅_𝗋𝖾𝖿 instruction calls a function through a typed function reference operand.
+    /// The immediate denotes the function type of the reference.
+    CallRef(TypeIndex),
+    /// The 𝗋𝖾𝗍𝗎𝗋𝗇_𝖼𝖺𝗅𝗅_𝗋𝖾𝖿 instruction is the tail-call version of 𝖼𝖺𝗅𝗅_𝗋𝖾𝖿.
+    ReturnCallRef(TypeIndex),
+    /// The 𝖻𝗋_𝗈𝗇_𝗇𝗎𝗅𝗅 instruction branches if the reference operand is null.
+    /// Otherwise, the reference is pushed back on the stack as non-nullable.
+    BranchOnNull(LabelIndex),
+    /// The 𝖻𝗋_𝗈𝗇_𝗇𝗈𝗇_𝗇𝗎𝗅𝗅 instruction branches with the reference operand if it is not null.
+    /// Otherwise, the null reference is dropped.
+    BranchOnNonNull(LabelIndex),
+    /// The 𝖻𝗋_𝗈𝗇_𝖼𝖺𝗌𝗍 instruction branches with the reference operand (of the first reference type)
+    /// if it can be cast to the second reference type.
+    BranchOnCast(LabelIndex, ReferenceType, ReferenceType),
+    /// The 𝖻𝗋_𝗈𝗇_𝖼𝖺𝗌𝗍_𝖿𝖺𝗂𝗅 instruction branches with the reference operand
+    /// (of the first reference type) if it cannot be cast to the second reference type.
+    BranchOnCastFail(LabelIndex, ReferenceType, ReferenceType),
+    /// The 𝗍𝗁𝗋𝗈𝗐 instruction creates an exception with the given tag from the operands on the stack
+    /// and throws it.
+    Throw(TagIndex),
+    /// The 𝗍𝗁𝗋𝗈𝗐_𝗋𝖾𝖿 instruction re-throws an exception given by an operand of type 𝖾𝗑𝗇𝗋𝖾𝖿.
+    ThrowRef,
+    /// The 𝗍𝗋𝗒_𝗍𝖺𝖻𝗅𝖾 instruction executes its expression as a block with the given catch clauses
+    /// installed as exception handlers.
+    /// When an exception is thrown inside the block, the first matching catch clause branches
+    /// to its label.
+    TryTable(BlockType, Vec<CatchClause>, Expression),
 }
 
 impl From<ControlInstruction> for Instruction {
@@ -916,6 +1605,57 @@ impl From<ControlInstruction> for Instruction {
     }
 }
 
+/// A catch clause of a 𝗍𝗋𝗒_𝗍𝖺𝖻𝗅𝖾 instruction, which branches to a label when an exception
+/// thrown inside the block matches the clause.
+/// The 𝖼𝖺𝗍𝖼𝗁 and 𝖼𝖺𝗍𝖼𝗁_𝗋𝖾𝖿 clauses only match exceptions with the given tag,
+/// while the 𝖼𝖺𝗍𝖼𝗁_𝖺𝗅𝗅 and 𝖼𝖺𝗍𝖼𝗁_𝖺𝗅𝗅_𝗋𝖾𝖿 clauses match any exception.
+/// The 𝗋𝖾𝖿 variants additionally pass the caught exception to the label as an 𝖾𝗑𝗇𝗋𝖾𝖿.
+///
+/// See <https://webassembly.github.io/exception-handling/core/syntax/instructions.html#control-instructions>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{BlockType, CatchClause, ControlInstruction, Expression, Instruction};
+///
+/// let catches = vec![
+///     CatchClause::Catch(0, 1),
+///     CatchClause::CatchReference(0, 1),
+///     CatchClause::CatchAll(2),
+///     CatchClause::CatchAllReference(2),
+/// ];
+/// let expression = Expression::new(vec![ControlInstruction::Throw(0).into()]);
+///
+/// assert_eq!(
+///     Instruction::Control(ControlInstruction::TryTable(BlockType::None, catches.clone(), expression.clone())),
+///     ControlInstruction::TryTable(BlockType::None, catches, expression).into()
+/// );
+/// assert_eq!(CatchClause::Catch(0, 1).label(), 1);
+/// assert_eq!(CatchClause::CatchAllReference(2).label(), 2);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CatchClause {
+    /// 𝖼𝖺𝗍𝖼𝗁 x l
+    Catch(TagIndex, LabelIndex),
+    /// 𝖼𝖺𝗍𝖼𝗁_𝗋𝖾𝖿 x l
+    CatchReference(TagIndex, LabelIndex),
+    /// 𝖼𝖺𝗍𝖼𝗁_𝖺𝗅𝗅 l
+    CatchAll(LabelIndex),
+    /// 𝖼𝖺𝗍𝖼𝗁_𝖺𝗅𝗅_𝗋𝖾𝖿 l
+    CatchAllReference(LabelIndex),
+}
+
+impl CatchClause {
+    /// The label the catch clause branches to when it matches a thrown exception.
+    pub fn label(&self) -> LabelIndex {
+        match self {
+            CatchClause::Catch(_, label)
+            | CatchClause::CatchReference(_, label)
+            | CatchClause::CatchAll(label)
+            | CatchClause::CatchAllReference(label) => *label,
+        }
+    }
+}
+
 /// A structured instruction can consume input and produce output on the operand stack according to
 /// its annotated block type.
 /// It is given either as a type index that refers to a suitable function type,
@@ -929,14 +1669,17 @@ pub enum BlockType {
     ValueType(ValueType),
 }
 
-/// Argument to load and store instructions that contains an address offset and
-/// the expected alignment (expressed as the exponent of a power of 2).
+/// Argument to load and store instructions that contains an address offset,
+/// the expected alignment (expressed as the exponent of a power of 2),
+/// and the index of the memory being accessed.
 ///
 /// The static address offset is added to the dynamic address operand,
-/// yielding a 33 bit effective address that is the zero-based index at which the memory is accessed.
+/// yielding a 33 bit (or 65 bit for 64-bit memories) effective address that is the zero-based index at which the memory is accessed.
 ///
 /// See <https://webassembly.github.io/spec/core/syntax/instructions.html#memory-instructions>
 ///
+/// See <https://webassembly.github.io/multi-memory/core/syntax/instructions.html#memory-instructions>
+///
 /// # Examples
 /// ## With Offset & Alignment
 /// ```rust
@@ -976,26 +1719,61 @@ pub enum BlockType {
 ///
 /// assert_eq!(argument.offset(), 0);
 /// assert_eq!(argument.align(), 1);
+/// assert_eq!(argument.memory(), 0);
+/// ```
+///
+/// ## With Memory
+/// ```rust
+/// use wasm_ast::MemoryArgument;
+///
+/// let argument = MemoryArgument::with_memory(2, 4, 42);
+///
+/// assert_eq!(argument.memory(), 2);
+/// assert_eq!(argument.offset(), 42);
+/// assert_eq!(argument.align(), 4);
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct MemoryArgument {
     align: u32,
-    offset: u32,
+    offset: u64,
+    pub(crate) memory: MemoryIndex,
 }
 
 impl MemoryArgument {
-    /// Creates a new memory argument with the given alignment and offset.
-    pub fn new(align: u32, offset: u32) -> Self {
-        MemoryArgument { align, offset }
+    /// Creates a new memory argument for the default memory with the given alignment and offset.
+    pub fn new(align: u32, offset: u64) -> Self {
+        MemoryArgument {
+            align,
+            offset,
+            memory: 0,
+        }
     }
 
-    /// Creates a new memory argument with the default offset and the given alignment.
+    /// Creates a new memory argument with the given memory index, alignment and offset.
+    pub fn with_memory(memory: MemoryIndex, align: u32, offset: u64) -> Self {
+        MemoryArgument {
+            align,
+            offset,
+            memory,
+        }
+    }
+
+    /// Creates a new memory argument for the default memory with the default offset and the given alignment.
     pub fn default_offset(align: u32) -> Self {
-        MemoryArgument { offset: 0, align }
+        MemoryArgument {
+            offset: 0,
+            align,
+            memory: 0,
+        }
+    }
+
+    /// The index of the memory accessed by the instruction.
+    pub fn memory(&self) -> MemoryIndex {
+        self.memory
     }
 
     /// The static address offset of the memory instruction.
-    pub fn offset(&self) -> u32 {
+    pub fn offset(&self) -> u64 {
         self.offset
     }
 
@@ -1067,7 +1845,7 @@ pub enum SignExtension {
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct Expression {
-    instructions: Vec<Instruction>,
+    pub(crate) instructions: Vec<Instruction>,
 }
 
 impl Expression {
@@ -1088,6 +1866,12 @@ impl Expression {
         &self.instructions
     }
 
+    /// The mutable instructions for this expression,
+    /// in order to insert, remove or rewrite instructions in place.
+    pub fn instructions_mut(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
     /// Returns true if this `Expression` has a length of zero, false otherwise.
     pub fn is_empty(&self) -> bool {
         self.instructions.is_empty()
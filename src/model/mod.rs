@@ -1,15 +1,19 @@
 //! The model of the WebAssembly syntax.
 
+pub mod borrowed;
 mod errors;
 pub mod indices;
 pub mod instruction;
 pub mod module;
+pub mod names;
 pub mod types;
 pub mod values;
 
+pub use borrowed::*;
 pub use errors::ModelError;
 pub use indices::*;
 pub use instruction::*;
 pub use module::*;
+pub use names::*;
 pub use types::*;
 pub use values::*;
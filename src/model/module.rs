@@ -2,11 +2,15 @@
 
 use crate::model::indices::*;
 use crate::model::types::*;
-use crate::model::{Expression, Name};
+use crate::model::{Expression, Name, NameSection};
 use crate::{ModelError, ReferenceInstruction};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::mem::discriminant;
+#[cfg(feature = "parser")]
+use std::ops::Range;
+#[cfg(feature = "parser")]
+use std::sync::{Arc, OnceLock};
 
 /// A builder pattern for `Module`s.
 /// The builder performs minimal validation when using the `add_*` family of methods.
@@ -24,23 +28,37 @@ impl ModuleBuilder {
         }
     }
 
-    /// Sets the function types segment for the WebAssembly module to be built.
+    /// Sets the types segment for the WebAssembly module to be built.
+    pub fn set_types(&mut self, types: Option<Vec<RecursiveType>>) {
+        self.module.types = types;
+    }
+
+    /// Adds the recursive type to the module's segment.
+    /// Returns the index of the first sub type of the recursive group in the module.
+    pub fn add_type(&mut self, kind: RecursiveType) -> Result<TypeIndex, ModelError> {
+        let types = self.module.types.get_or_insert_with(Vec::new);
+        let index = u32::try_from(types.iter().map(RecursiveType::len).sum::<usize>())?;
+
+        u32::try_from(index as usize + kind.len())?;
+        types.push(kind);
+
+        Ok(index)
+    }
+
+    /// Sets the types segment for the WebAssembly module to be built
+    /// to a recursive type for each of the given function types.
     pub fn set_function_types(&mut self, function_types: Option<Vec<FunctionType>>) {
-        self.module.function_types = function_types;
+        self.module.types =
+            function_types.map(|types| types.into_iter().map(RecursiveType::from).collect());
     }
 
-    /// Adds the function type to the module's segment.
+    /// Adds the function type to the module's segment as its own recursive type.
     /// Returns the index of the type in the module.
     pub fn add_function_type(
         &mut self,
         function_type: FunctionType,
     ) -> Result<TypeIndex, ModelError> {
-        let function_types = self.module.function_types.get_or_insert_with(Vec::new);
-        let index = u32::try_from(function_types.len())?;
-
-        function_types.push(function_type);
-
-        Ok(index)
+        self.add_type(function_type.into())
     }
 
     /// Sets the functions segment for the WebAssembly module to be built.
@@ -54,14 +72,8 @@ impl ModuleBuilder {
     /// **Note:** In order for the returned index to be accurate,
     /// all function imports must be defined prior to adding any functions.
     pub fn add_function(&mut self, function: Function) -> Result<FunctionIndex, ModelError> {
+        let imports = self.function_imports();
         let functions = self.module.functions.get_or_insert_with(Vec::new);
-        let imports = match &self.module.imports {
-            Some(imports) => imports
-                .iter()
-                .filter(|import| matches!(import.description(), ImportDescription::Function(_)))
-                .count(),
-            None => 0,
-        };
         let index = u32::try_from(functions.len() + imports)?;
 
         functions.push(function);
@@ -69,6 +81,44 @@ impl ModuleBuilder {
         Ok(index)
     }
 
+    /// The mutable functions defined by the module to be built, excluding imports.
+    /// Functions can be modified in place, but not added or removed.
+    pub fn functions_mut(&mut self) -> Option<&mut [Function]> {
+        self.module.functions.as_deref_mut()
+    }
+
+    /// Replaces the function at the given index in the module's segment.
+    /// The index includes function imports, which cannot be replaced.
+    /// Returns the replaced function.
+    pub fn replace_function(
+        &mut self,
+        index: FunctionIndex,
+        function: Function,
+    ) -> Result<Function, ModelError> {
+        let offset = (index as usize)
+            .checked_sub(self.function_imports())
+            .ok_or(ModelError::UndefinedFunction(index))?;
+        let target = self
+            .module
+            .functions
+            .as_mut()
+            .and_then(|functions| functions.get_mut(offset))
+            .ok_or(ModelError::UndefinedFunction(index))?;
+
+        Ok(std::mem::replace(target, function))
+    }
+
+    /// The number of functions imported by the module to be built.
+    fn function_imports(&self) -> usize {
+        match &self.module.imports {
+            Some(imports) => imports
+                .iter()
+                .filter(|import| matches!(import.description(), ImportDescription::Function(_)))
+                .count(),
+            None => 0,
+        }
+    }
+
     /// Sets the table segment for the WebAssembly module to be built.
     pub fn set_tables(&mut self, tables: Option<Vec<Table>>) {
         self.module.tables = tables;
@@ -121,6 +171,32 @@ impl ModuleBuilder {
         Ok(index)
     }
 
+    /// Sets the tags segment for the WebAssembly module to be built.
+    pub fn set_tags(&mut self, tags: Option<Vec<Tag>>) {
+        self.module.tags = tags;
+    }
+
+    /// Adds the tag to the module's segment.
+    /// Returns the index of the tag in the module.
+    ///
+    /// **Note:** In order for the returned index to be accurate,
+    /// all tag imports must be defined prior to adding any tags.
+    pub fn add_tag(&mut self, tag: Tag) -> Result<TagIndex, ModelError> {
+        let tags = self.module.tags.get_or_insert_with(Vec::new);
+        let imports = match &self.module.imports {
+            Some(imports) => imports
+                .iter()
+                .filter(|import| matches!(import.description(), ImportDescription::Tag(_)))
+                .count(),
+            None => 0,
+        };
+        let index = u32::try_from(tags.len() + imports)?;
+
+        tags.push(tag);
+
+        Ok(index)
+    }
+
     /// Sets the globals segment for the WebAssembly module to be built.
     pub fn set_globals(&mut self, globals: Option<Vec<Global>>) {
         self.module.globals = globals;
@@ -218,6 +294,17 @@ impl ModuleBuilder {
         exports.push(export);
     }
 
+    /// Removes the export with the given name from the module's segment.
+    /// Returns the removed export, if any.
+    pub fn remove_export(&mut self, name: &str) -> Option<Export> {
+        let exports = self.module.exports.as_mut()?;
+        let index = exports
+            .iter()
+            .position(|export| export.name().as_str() == name)?;
+
+        Some(exports.remove(index))
+    }
+
     /// Sets the custom section at the given insertion point for the WebAssembly module to be built.
     /// WebAssembly binary format allows custom sections to be at the start of a module, or after any other section.
     pub fn set_custom_sections(
@@ -243,14 +330,27 @@ impl ModuleBuilder {
         self.module.data_count = data_count;
     }
 
+    /// Sets the names of the module and its definitions for the WebAssembly module to be built.
+    /// The names are emitted in the name custom section, at the placement given by `set_names_placement`.
+    pub fn set_names(&mut self, names: Option<NameSection>) {
+        self.module.names = names;
+    }
+
+    /// Sets the placement of the name section for the WebAssembly module to be built,
+    /// as an insertion point and the position among the other custom sections at that insertion point.
+    /// Without a placement, the name section is emitted after the data section, before any other custom sections.
+    pub fn set_names_placement(&mut self, placement: Option<(ModuleSection, usize)>) {
+        self.module.names_placement = placement;
+    }
+
     /// Includes a data count based on the number of data segments currently in this builder.
     pub fn include_data_count(&mut self) {
         self.module.data_count = self.module.data.as_ref().map(|v| v.len()).map(|l| l as u32);
     }
 
     /// The 𝗍𝗒𝗉𝖾𝗌 component of the module to be built.
-    pub fn function_types(&self) -> Option<&[FunctionType]> {
-        self.module.function_types()
+    pub fn types(&self) -> Option<&[RecursiveType]> {
+        self.module.types()
     }
 
     /// The 𝖿𝗎𝗇𝖼𝗌 component of the module to be built.
@@ -268,6 +368,11 @@ impl ModuleBuilder {
         self.module.memories()
     }
 
+    /// The 𝗍𝖺𝗀𝗌 component of the module to be built.
+    pub fn tags(&self) -> Option<&[Tag]> {
+        self.module.tags()
+    }
+
     /// The 𝗀𝗅𝗈𝖻𝖺𝗅𝗌 component of the module to be built.
     pub fn globals(&self) -> Option<&[Global]> {
         self.module.globals()
@@ -303,6 +408,16 @@ impl ModuleBuilder {
         self.module.custom_sections_at(insertion_point)
     }
 
+    /// The names of the module to be built.
+    pub fn names(&self) -> Option<&NameSection> {
+        self.module.names()
+    }
+
+    /// The placement of the name section of the module to be built.
+    pub fn names_placement(&self) -> Option<(ModuleSection, usize)> {
+        self.module.names_placement()
+    }
+
     /// Builds the current segments into a module.
     pub fn build(self) -> Module {
         self.into()
@@ -315,6 +430,12 @@ impl From<ModuleBuilder> for Module {
     }
 }
 
+impl From<Module> for ModuleBuilder {
+    fn from(module: Module) -> Self {
+        ModuleBuilder { module }
+    }
+}
+
 impl Default for ModuleBuilder {
     fn default() -> Self {
         ModuleBuilder {
@@ -324,7 +445,7 @@ impl Default for ModuleBuilder {
 }
 
 /// WebAssembly programs are organized into modules, which are the unit of deployment, loading, and compilation.
-/// A module collects definitions for types, functions, tables, memories, and globals.
+/// A module collects definitions for types, functions, tables, memories, tags, and globals.
 /// In addition,
 /// it can declare imports and exports and provide initialization in the form of data and element segments,
 /// or a start function.
@@ -343,6 +464,7 @@ impl Default for ModuleBuilder {
 /// assert_eq!(module.functions(), None);
 /// assert_eq!(module.tables(), None);
 /// assert_eq!(module.memories(), None);
+/// assert_eq!(module.tags(), None);
 /// assert_eq!(module.globals(), None);
 /// assert_eq!(module.elements(), None);
 /// assert_eq!(module.data(), None);
@@ -350,6 +472,7 @@ impl Default for ModuleBuilder {
 /// assert_eq!(module.imports(), None);
 /// assert_eq!(module.exports(), None);
 /// assert_eq!(module.data_count(), None);
+/// assert_eq!(module.names(), None);
 /// ```
 ///
 /// ## Builder
@@ -363,6 +486,7 @@ impl Default for ModuleBuilder {
 /// assert_eq!(module.functions(), None);
 /// assert_eq!(module.tables(), None);
 /// assert_eq!(module.memories(), None);
+/// assert_eq!(module.tags(), None);
 /// assert_eq!(module.globals(), None);
 /// assert_eq!(module.elements(), None);
 /// assert_eq!(module.data(), None);
@@ -371,20 +495,48 @@ impl Default for ModuleBuilder {
 /// assert_eq!(module.exports(), None);
 /// assert_eq!(module.data_count(), None);
 /// ```
+///
+/// ## Mutation
+/// ```rust
+/// use wasm_ast::{ControlInstruction, Export, Expression, Function, FunctionType, Module, ResultType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder.add_function_type(FunctionType::runnable()).unwrap();
+/// let index = builder
+///     .add_function(Function::new(kind, ResultType::empty(), Expression::empty()))
+///     .unwrap();
+///
+/// builder.add_export(Export::function("main".into(), index));
+///
+/// let mut builder = builder.build().into_builder();
+/// let body = Expression::from(vec![ControlInstruction::Nop.into()]);
+/// let replacement = Function::new(kind, ResultType::empty(), body);
+///
+/// builder.replace_function(index, replacement.clone()).unwrap();
+/// builder.remove_export("main");
+///
+/// let module = builder.build();
+///
+/// assert_eq!(module.functions(), Some(&[replacement][..]));
+/// assert_eq!(module.exports(), Some(&[][..]));
+/// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct Module {
-    function_types: Option<Vec<FunctionType>>,
-    functions: Option<Vec<Function>>,
-    tables: Option<Vec<Table>>,
-    memories: Option<Vec<Memory>>,
-    globals: Option<Vec<Global>>,
-    elements: Option<Vec<Element>>,
-    data: Option<Vec<Data>>,
-    start: Option<Start>,
-    imports: Option<Vec<Import>>,
-    exports: Option<Vec<Export>>,
-    custom_sections: CustomSections,
-    data_count: Option<u32>,
+    pub(crate) types: Option<Vec<RecursiveType>>,
+    pub(crate) functions: Option<Vec<Function>>,
+    pub(crate) tables: Option<Vec<Table>>,
+    pub(crate) memories: Option<Vec<Memory>>,
+    pub(crate) tags: Option<Vec<Tag>>,
+    pub(crate) globals: Option<Vec<Global>>,
+    pub(crate) elements: Option<Vec<Element>>,
+    pub(crate) data: Option<Vec<Data>>,
+    pub(crate) start: Option<Start>,
+    pub(crate) imports: Option<Vec<Import>>,
+    pub(crate) exports: Option<Vec<Export>>,
+    pub(crate) custom_sections: CustomSections,
+    pub(crate) data_count: Option<u32>,
+    pub(crate) names: Option<NameSection>,
+    pub(crate) names_placement: Option<(ModuleSection, usize)>,
 }
 
 impl Module {
@@ -393,13 +545,20 @@ impl Module {
         ModuleBuilder::new()
     }
 
+    /// Creates a builder initialized with the components of this module,
+    /// in order to modify a module after it has been built or parsed.
+    pub fn into_builder(self) -> ModuleBuilder {
+        self.into()
+    }
+
     /// Creates a new empty `Module`.
     pub fn empty() -> Self {
         Module {
-            function_types: None,
+            types: None,
             functions: None,
             tables: None,
             memories: None,
+            tags: None,
             globals: None,
             elements: None,
             data: None,
@@ -408,12 +567,46 @@ impl Module {
             exports: None,
             custom_sections: CustomSections::new(),
             data_count: None,
+            names: None,
+            names_placement: None,
         }
     }
 
-    /// The 𝗍𝗒𝗉𝖾𝗌 component of a module defines a vector of function types.
-    pub fn function_types(&self) -> Option<&[FunctionType]> {
-        self.function_types.as_deref()
+    /// The 𝗍𝗒𝗉𝖾𝗌 component of a module defines a vector of recursive types,
+    /// each of which defines a group of (mutually recursive) sub types.
+    /// The types of a module are no longer a flat slice of function types,
+    /// so `function_types` is removed in favor of `sub_types` and `function_type`.
+    pub fn types(&self) -> Option<&[RecursiveType]> {
+        self.types.as_deref()
+    }
+
+    /// The sub types defined by the 𝗍𝗒𝗉𝖾𝗌 component of a module, in type index order.
+    pub fn sub_types(&self) -> impl Iterator<Item = &SubType> {
+        self.types
+            .iter()
+            .flatten()
+            .flat_map(|kind| kind.types().iter())
+    }
+
+    /// The sub type at the given type index in the 𝗍𝗒𝗉𝖾𝗌 component of a module.
+    /// Recursive types are skipped as a whole until the one that defines the index.
+    pub fn sub_type(&self, index: TypeIndex) -> Option<&SubType> {
+        let mut index = index as usize;
+
+        for kind in self.types.as_deref().unwrap_or_default() {
+            match kind.types().get(index) {
+                Some(sub_type) => return Some(sub_type),
+                None => index -= kind.len(),
+            }
+        }
+
+        None
+    }
+
+    /// The function type at the given type index in the 𝗍𝗒𝗉𝖾𝗌 component of a module,
+    /// if the type at that index is a function type.
+    pub fn function_type(&self, index: TypeIndex) -> Option<&FunctionType> {
+        self.sub_type(index)?.kind().function()
     }
 
     /// The 𝖿𝗎𝗇𝖼𝗌 component of a module defines a vector of functions.
@@ -432,6 +625,11 @@ impl Module {
         self.memories.as_deref()
     }
 
+    /// The 𝗍𝖺𝗀𝗌 component of a module defines a vector of tags described by their tag type.
+    pub fn tags(&self) -> Option<&[Tag]> {
+        self.tags.as_deref()
+    }
+
     /// The 𝗀𝗅𝗈𝖻𝖺𝗅𝗌 component of a module defines a vector of global variables (or globals for short).
     pub fn globals(&self) -> Option<&[Global]> {
         self.globals.as_deref()
@@ -474,11 +672,24 @@ impl Module {
     pub fn data_count(&self) -> Option<u32> {
         self.data_count
     }
+
+    /// The names of the module and its definitions, as recorded in the name custom section.
+    /// Names are not part of the semantics of a module.
+    pub fn names(&self) -> Option<&NameSection> {
+        self.names.as_ref()
+    }
+
+    /// The placement of the name section, as an insertion point and
+    /// the position among the other custom sections at that insertion point.
+    /// Modules without a placement emit the name section after the data section, before any other custom sections.
+    pub fn names_placement(&self) -> Option<(ModuleSection, usize)> {
+        self.names_placement
+    }
 }
 
 /// Maps insertion points to custom sections for a WebAssembly module.
 #[derive(Clone, Debug)]
-struct CustomSections {
+pub(crate) struct CustomSections {
     custom_sections: HashMap<ModuleSection, Vec<Custom>>,
 }
 
@@ -513,10 +724,7 @@ impl CustomSections {
     /// Adds the export to the module's segment.
     /// Returns the index of the export in the module.
     pub fn add_custom_section(&mut self, insertion_point: ModuleSection, custom_section: Custom) {
-        let custom_sections = self
-            .custom_sections
-            .entry(insertion_point)
-            .or_insert_with(Vec::new);
+        let custom_sections = self.custom_sections.entry(insertion_point).or_default();
 
         custom_sections.push(custom_section);
     }
@@ -600,14 +808,18 @@ impl Custom {
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct Function {
-    kind: TypeIndex,
-    locals: ResultType,
-    body: Expression,
+    pub(crate) kind: TypeIndex,
+    pub(crate) locals: ResultType,
+    pub(crate) body: Body,
 }
 
 impl Function {
     pub fn new(kind: TypeIndex, locals: ResultType, body: Expression) -> Self {
-        Function { kind, locals, body }
+        Function {
+            kind,
+            locals,
+            body: Body::Decoded(body),
+        }
     }
 
     /// The index of the type definition for this `Function`.
@@ -621,14 +833,210 @@ impl Function {
     }
 
     /// The code for this `Function`.
+    /// The code of a function parsed by `parse_binary_lazy` is decoded on first access.
+    ///
+    /// # Panics
+    /// Panics if the function was parsed lazily and its code is not a valid expression in the binary format.
+    /// Use `decode_body` to handle invalid code instead.
+    /// Visiting or folding the module and building its call graph panic in the same way,
+    /// while `validate`, `emit_text`, `remap_indices` and `eliminate_dead_code` report invalid code as an error.
     pub fn body(&self) -> &Expression {
-        &self.body
+        self.body.expression()
+    }
+
+    /// Decodes the code for this `Function`, if it was parsed lazily by `parse_binary_lazy`.
+    /// Functions that are not parsed lazily always decode successfully.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wasm_ast::{parse_binary_lazy, Expression};
+    ///
+    /// let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x04\x01\x02\x00\x0B";
+    /// let module = parse_binary_lazy(binary).unwrap();
+    /// let function = &module.functions().unwrap()[0];
+    ///
+    /// assert!(!function.is_decoded());
+    /// assert_eq!(function.decode_body().unwrap(), &Expression::empty());
+    /// assert!(function.is_decoded());
+    /// ```
+    #[cfg(feature = "parser")]
+    pub fn decode_body(&self) -> Result<&Expression, crate::parser::ParseError> {
+        self.body
+            .decode()
+            .map_err(crate::parser::ParseError::InvalidBinary)
+    }
+
+    /// Returns true if the code for this `Function` is decoded, false if it was parsed lazily and is yet to be accessed.
+    pub fn is_decoded(&self) -> bool {
+        self.body.is_decoded()
+    }
+}
+
+/// The code of a function, either as a decoded expression or,
+/// for functions parsed lazily, as the encoded expression that is decoded on first access.
+#[derive(Clone, Debug)]
+pub(crate) enum Body {
+    Decoded(Expression),
+    #[cfg(feature = "parser")]
+    Encoded(Box<EncodedBody>),
+}
+
+impl Body {
+    /// The decoded expression, decoding it first if needed.
+    pub(crate) fn expression(&self) -> &Expression {
+        match self {
+            Body::Decoded(expression) => expression,
+            #[cfg(feature = "parser")]
+            Body::Encoded(body) => body.decode().unwrap_or_else(|error| panic!("{}", error)),
+        }
+    }
+
+    /// The decoded expression for modification, discarding the encoded expression.
+    pub(crate) fn expression_mut(&mut self) -> &mut Expression {
+        #[cfg(feature = "parser")]
+        if matches!(self, Body::Encoded(_)) {
+            *self = Body::Decoded(self.expression().clone());
+        }
+
+        match self {
+            Body::Decoded(expression) => expression,
+            #[cfg(feature = "parser")]
+            Body::Encoded(_) => unreachable!("the body was decoded"),
+        }
+    }
+
+    /// Takes the decoded expression, decoding it first if needed.
+    pub(crate) fn into_expression(self) -> Expression {
+        match self {
+            Body::Decoded(expression) => expression,
+            #[cfg(feature = "parser")]
+            Body::Encoded(body) => body
+                .into_expression()
+                .unwrap_or_else(|error| panic!("{}", error)),
+        }
+    }
+
+    /// The encoded expression of a body parsed lazily, if any.
+    #[cfg(feature = "parser")]
+    pub(crate) fn encoded(&self) -> Option<&[u8]> {
+        match self {
+            Body::Decoded(_) => None,
+            Body::Encoded(body) => Some(body.bytes()),
+        }
+    }
+
+    /// The decoded expression, or the error in the encoded expression of a body parsed lazily.
+    #[cfg(feature = "parser")]
+    pub(crate) fn decode(&self) -> Result<&Expression, crate::parser::BinaryError> {
+        match self {
+            Body::Decoded(expression) => Ok(expression),
+            Body::Encoded(body) => body.decode(),
+        }
+    }
+
+    fn is_decoded(&self) -> bool {
+        match self {
+            Body::Decoded(_) => true,
+            #[cfg(feature = "parser")]
+            Body::Encoded(body) => body.is_decoded(),
+        }
+    }
+}
+
+/// The encoded expression of a function body that was parsed lazily.
+/// The bodies of a module share the bytes of its code section,
+/// and each body is decoded at most once.
+#[cfg(feature = "parser")]
+#[derive(Clone, Debug)]
+pub(crate) struct EncodedBody {
+    code: Arc<[u8]>,
+    offset: usize,
+    range: Range<usize>,
+    function: FunctionIndex,
+    expression: OnceLock<Expression>,
+}
+
+#[cfg(feature = "parser")]
+impl EncodedBody {
+    /// Creates an encoded body of the function with the given index from a range of the shared code bytes,
+    /// which start at the given offset of the module.
+    pub(crate) fn new(
+        code: Arc<[u8]>,
+        offset: usize,
+        range: Range<usize>,
+        function: FunctionIndex,
+    ) -> Self {
+        EncodedBody {
+            code,
+            offset,
+            range,
+            function,
+            expression: OnceLock::new(),
+        }
+    }
+
+    /// The encoded expression, including the end opcode.
+    pub(crate) fn bytes(&self) -> &[u8] {
+        &self.code[self.range.clone()]
+    }
+
+    /// Returns true if the expression has been decoded, false otherwise.
+    pub(crate) fn is_decoded(&self) -> bool {
+        self.expression.get().is_some()
+    }
+
+    /// Decodes the expression, or returns the expression decoded by a previous call.
+    pub(crate) fn decode(&self) -> Result<&Expression, crate::parser::BinaryError> {
+        if let Some(expression) = self.expression.get() {
+            return Ok(expression);
+        }
+
+        let expression = self.parse()?;
+
+        Ok(self.expression.get_or_init(|| expression))
+    }
+
+    /// Takes the decoded expression, decoding it if needed.
+    pub(crate) fn into_expression(self) -> Result<Expression, crate::parser::BinaryError> {
+        match self.expression.into_inner() {
+            Some(expression) => Ok(expression),
+            None => crate::parser::decode_body(&self.code, self.offset, self.range, self.function),
+        }
+    }
+
+    /// Parses the encoded expression, locating errors within the module.
+    fn parse(&self) -> Result<Expression, crate::parser::BinaryError> {
+        crate::parser::decode_body(&self.code, self.offset, self.range.clone(), self.function)
+    }
+}
+
+impl From<Expression> for Body {
+    fn from(expression: Expression) -> Self {
+        Body::Decoded(expression)
+    }
+}
+
+/// Bodies are equal if their encodings are equal, or their decoded expressions otherwise.
+impl PartialEq for Body {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "parser")]
+        if let (Some(left), Some(right)) = (self.encoded(), other.encoded()) {
+            return left == right;
+        }
+
+        #[cfg(feature = "parser")]
+        return matches!((self.decode(), other.decode()), (Ok(left), Ok(right)) if left == right);
+
+        #[cfg(not(feature = "parser"))]
+        return self.expression() == other.expression();
     }
 }
 
 /// A table is a vector of opaque values of a particular reference type.
 /// The 𝗆𝗂𝗇 size in the limits of the table type specifies the initial size of that table, while its 𝗆𝖺𝗑, if present, restricts the size to which it can grow later.
 /// Tables can be initialized through element segments.
+/// A table may also have an initializer, a constant expression whose value fills every element of the table.
+/// Tables of non-nullable references require an initializer.
 /// Tables are referenced through table indices,
 /// starting with the smallest index not referencing a table import.
 /// Most constructs implicitly reference table index 0.
@@ -645,27 +1053,57 @@ impl Function {
 ///
 /// assert_eq!(table, kind.into());
 /// assert_eq!(table.kind(), &kind);
+/// assert_eq!(table.initializer(), None);
 /// ```
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+///
+/// ## Initializer
+/// ```rust
+/// use wasm_ast::{Expression, HeapType, Limit, ReferenceInstruction, ReferenceType, Table, TableType};
+///
+/// let kind = TableType::new(ReferenceType::NonNullable(HeapType::Function), Limit::unbounded(1));
+/// let initializer: Expression = vec![ReferenceInstruction::Function(0).into()].into();
+/// let table = Table::with_initializer(kind, initializer.clone());
+///
+/// assert_eq!(table.kind(), &kind);
+/// assert_eq!(table.initializer(), Some(&initializer));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 pub struct Table {
-    kind: TableType,
+    pub(crate) kind: TableType,
+    pub(crate) initializer: Option<Expression>,
 }
 
 impl Table {
     /// Creates a new instance of a `Table`.
     pub fn new(kind: TableType) -> Self {
-        Table { kind }
+        Table {
+            kind,
+            initializer: None,
+        }
+    }
+
+    /// Creates a new instance of a `Table` whose elements are initialized by the given constant expression.
+    pub fn with_initializer(kind: TableType, initializer: Expression) -> Self {
+        Table {
+            kind,
+            initializer: Some(initializer),
+        }
     }
 
     /// The type descriptor of this `Table`.
     pub fn kind(&self) -> &TableType {
         &self.kind
     }
+
+    /// The constant expression that initializes the elements of this `Table`, if any.
+    pub fn initializer(&self) -> Option<&Expression> {
+        self.initializer.as_ref()
+    }
 }
 
 impl From<TableType> for Table {
     fn from(kind: TableType) -> Self {
-        Table { kind }
+        Table::new(kind)
     }
 }
 
@@ -693,7 +1131,7 @@ impl From<TableType> for Table {
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Memory {
-    kind: MemoryType,
+    pub(crate) kind: MemoryType,
 }
 
 impl Memory {
@@ -717,6 +1155,51 @@ where
     }
 }
 
+/// A tag declares an exception that can be thrown with the 𝗍𝗁𝗋𝗈𝗐 instruction
+/// and caught by a handler of a 𝗍𝗋𝗒_𝗍𝖺𝖻𝗅𝖾 instruction.
+/// The values carried by an exception are described by the parameters of the tag's function type.
+/// Tags are referenced through tag indices,
+/// starting with the smallest index not referencing a tag import.
+///
+/// See <https://webassembly.github.io/exception-handling/core/syntax/modules.html#tags>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{Tag, TagType};
+///
+/// let kind = TagType::new(1);
+/// let tag = Tag::new(kind);
+///
+/// assert_eq!(tag, kind.into());
+/// assert_eq!(tag, 1.into());
+/// assert_eq!(tag.kind(), &kind);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tag {
+    pub(crate) kind: TagType,
+}
+
+impl Tag {
+    /// Creates a new `Tag` of the given type.
+    pub fn new(kind: TagType) -> Self {
+        Tag { kind }
+    }
+
+    /// The type definition for this tag.
+    pub fn kind(&self) -> &TagType {
+        &self.kind
+    }
+}
+
+impl<T> From<T> for Tag
+where
+    T: Into<TagType>,
+{
+    fn from(kind: T) -> Self {
+        Tag { kind: kind.into() }
+    }
+}
+
 /// Each global stores a single value of the given global type.
 /// Its 𝗍𝗒𝗉𝖾 also specifies whether a global is immutable or mutable.
 /// Moreover, each global is initialized with an 𝗂𝗇𝗂𝗍 value given by a constant initializer expression.
@@ -753,8 +1236,8 @@ where
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct Global {
-    kind: GlobalType,
-    initializer: Expression,
+    pub(crate) kind: GlobalType,
+    pub(crate) initializer: Expression,
 }
 
 impl Global {
@@ -858,9 +1341,9 @@ impl Global {
 /// ````
 #[derive(Clone, Debug, PartialEq)]
 pub struct Element {
-    kind: ReferenceType,
-    mode: ElementMode,
-    initializers: Vec<Expression>,
+    pub(crate) kind: ReferenceType,
+    pub(crate) mode: ElementMode,
+    pub(crate) initializers: Vec<Expression>,
 }
 
 impl Element {
@@ -995,7 +1478,7 @@ pub enum ElementMode {
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct Data {
-    mode: DataMode,
+    pub(crate) mode: DataMode,
     initializer: Vec<u8>,
 }
 
@@ -1076,7 +1559,7 @@ pub enum DataMode {
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Start {
-    function: FunctionIndex,
+    pub(crate) function: FunctionIndex,
 }
 
 impl Start {
@@ -1157,10 +1640,23 @@ impl From<u32> for Start {
 /// assert_eq!(export.name(), &Name::new(String::from(name)));
 /// assert_eq!(export.description(), &description);
 /// ```
+///
+/// ## Tag
+/// ```rust
+/// use wasm_ast::{Export, ExportDescription, Name};
+///
+/// let name = "error";
+/// let description = ExportDescription::Tag(0);
+/// let export = Export::new(name.into(), description.clone());
+///
+/// assert_eq!(export, Export::tag(name.into(), 0));
+/// assert_eq!(export.name(), &Name::new(String::from(name)));
+/// assert_eq!(export.description(), &description);
+/// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Export {
     name: Name,
-    description: ExportDescription,
+    pub(crate) description: ExportDescription,
 }
 
 impl Export {
@@ -1201,6 +1697,14 @@ impl Export {
         }
     }
 
+    /// Create a new instance of an `Export` with the given name and description for a tag.
+    pub fn tag(name: Name, tag: TagIndex) -> Self {
+        Export {
+            name,
+            description: ExportDescription::Tag(tag),
+        }
+    }
+
     /// The name of the export.
     pub fn name(&self) -> &Name {
         &self.name
@@ -1212,7 +1716,7 @@ impl Export {
     }
 }
 
-/// Exportable definitions are functions, tables, memories, and globals,
+/// Exportable definitions are functions, tables, memories, globals, and tags,
 /// which are referenced through a respective descriptor.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ExportDescription {
@@ -1220,11 +1724,12 @@ pub enum ExportDescription {
     Table(TableIndex),
     Memory(MemoryIndex),
     Global(GlobalIndex),
+    Tag(TagIndex),
 }
 
 /// Each import is labeled by a two-level name space,
 /// consisting of a 𝗆𝗈𝖽𝗎𝗅𝖾 name and a 𝗇𝖺𝗆𝖾 for an entity within that module.
-/// Importable definitions are functions, tables, memories, and globals.
+/// Importable definitions are functions, tables, memories, globals, and tags.
 /// Each import is specified by a descriptor with a respective type that a definition provided
 /// during instantiation is required to match.
 /// Every import defines an index in the respective index space.
@@ -1296,11 +1801,27 @@ pub enum ExportDescription {
 /// assert_eq!(import.name(), &Name::new(String::from(name)));
 /// assert_eq!(import.description(), &description);
 /// ```
+///
+/// ## Tag
+/// ```rust
+/// use wasm_ast::{Import, ImportDescription, Name, TagType};
+///
+/// let module = "system";
+/// let name = "error";
+/// let kind = TagType::new(1);
+/// let description = ImportDescription::Tag(kind);
+/// let import = Import::new(module.into(), name.into(), description.clone());
+///
+/// assert_eq!(import, Import::tag(module.into(), name.into(), kind));
+/// assert_eq!(import.module(), &Name::new(String::from(module)));
+/// assert_eq!(import.name(), &Name::new(String::from(name)));
+/// assert_eq!(import.description(), &description);
+/// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Import {
     module: Name,
     name: Name,
-    description: ImportDescription,
+    pub(crate) description: ImportDescription,
 }
 
 impl Import {
@@ -1349,6 +1870,15 @@ impl Import {
         }
     }
 
+    /// Create a new instance of an `Import` with the given name and description for a tag.
+    pub fn tag(module: Name, name: Name, tag_kind: TagType) -> Self {
+        Import {
+            module,
+            name,
+            description: ImportDescription::Tag(tag_kind),
+        }
+    }
+
     /// The name of the module (i.e.m namespace).
     pub fn module(&self) -> &Name {
         &self.module
@@ -1373,6 +1903,7 @@ pub enum ImportDescription {
     Table(TableType),
     Memory(MemoryType),
     Global(GlobalType),
+    Tag(TagType),
 }
 
 /// The binary encoding of modules is organized into sections.
@@ -1430,6 +1961,10 @@ pub enum ModuleSection {
     /// It decodes into an optional u32 that represents the number of data segments in the data section.
     /// If this count does not match the length of the data segment vector, the module is malformed.
     DataCount,
+    /// The tag section has the id 13.
+    /// It decodes into a vector of tags that represent the 𝗍𝖺𝗀𝗌 component of a module.
+    /// The tag section is placed between the memory section and the global section.
+    Tag,
 }
 
 #[cfg(test)]
@@ -1474,4 +2009,78 @@ mod tests {
 
         assert_ne!(module, other_module);
     }
+
+    #[test]
+    fn sub_type_lookup() {
+        let mut builder = Module::builder();
+        let unary = FunctionType::side_effect(ResultType::from(vec![ValueType::I32]));
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_type(RecursiveType::new(vec![
+                SubType::from(CompositeType::Struct(StructType::new(vec![]))),
+                SubType::from(unary.clone()),
+            ]))
+            .unwrap();
+        builder.add_function_type(unary.clone()).unwrap();
+
+        let module = builder.build();
+
+        assert_eq!(module.function_type(0), Some(&FunctionType::runnable()));
+        assert_eq!(module.function_type(1), None);
+        assert_eq!(module.function_type(2), Some(&unary));
+        assert_eq!(module.function_type(3), Some(&unary));
+        assert_eq!(module.sub_type(4), None);
+        assert_eq!(
+            module
+                .sub_types()
+                .filter_map(|kind| kind.kind().function())
+                .collect::<Vec<_>>(),
+            vec![&FunctionType::runnable(), &unary, &unary]
+        );
+    }
+
+    #[test]
+    fn into_builder_round_trip() {
+        let mut builder = Module::builder();
+
+        builder
+            .add_import(Import::function("env".into(), "log".into(), 0))
+            .unwrap();
+        builder
+            .add_function(Function::new(0, ResultType::empty(), Expression::empty()))
+            .unwrap();
+        builder.add_export(Export::function("main".into(), 1));
+
+        let module = builder.build();
+        let mut builder = module.clone().into_builder();
+
+        assert_eq!(ModuleBuilder::from(module.clone()).build(), module);
+        assert!(matches!(
+            builder.replace_function(
+                0,
+                Function::new(1, ResultType::empty(), Expression::empty())
+            ),
+            Err(ModelError::UndefinedFunction(0))
+        ));
+        assert!(matches!(
+            builder.replace_function(
+                2,
+                Function::new(1, ResultType::empty(), Expression::empty())
+            ),
+            Err(ModelError::UndefinedFunction(2))
+        ));
+        assert_eq!(builder.remove_export("missing"), None);
+
+        for function in builder.functions_mut().unwrap() {
+            *function = Function::new(1, function.locals().clone(), function.body().clone());
+        }
+
+        assert_eq!(builder.functions().unwrap()[0].kind(), 1);
+        assert_eq!(
+            builder.remove_export("main"),
+            Some(Export::function("main".into(), 1))
+        );
+        assert_ne!(builder.build(), module);
+    }
 }
@@ -0,0 +1,265 @@
+//! Model for the symbolic names of a WebAssembly module.
+//!
+//! Names are not part of the semantics of a module.
+//! They are recorded in the name custom section, or as symbolic identifiers in the text format,
+//! to aid debugging and make the module readable by humans.
+//!
+//! See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+//!
+//! See <https://github.com/WebAssembly/extended-name-section/blob/main/proposals/extended-name-section/Overview.md>
+
+use crate::model::{FunctionIndex, Name, TypeIndex};
+use std::collections::BTreeMap;
+
+/// A name map assigns names to indices in a given index space.
+/// Entries are ordered by index.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-maps>
+pub type NameMap = BTreeMap<u32, Name>;
+
+/// An indirect name map assigns names to a two-dimensional index space,
+/// where secondary indices are grouped by primary indices (e.g., the locals of each function).
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-maps>
+pub type IndirectNameMap = BTreeMap<u32, NameMap>;
+
+/// The names of a module, its definitions, and the locals and labels of its functions.
+/// Each index space has its own name map; indices without a name are absent from the map.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{Name, NameSection};
+///
+/// let mut names = NameSection::new();
+///
+/// assert!(names.is_empty());
+///
+/// names.set_module(Some(Name::from("example")));
+/// names.functions_mut().insert(0, Name::from("main"));
+/// names.locals_mut().entry(0).or_default().insert(1, Name::from("counter"));
+///
+/// assert!(!names.is_empty());
+/// assert_eq!(names.module(), Some(&Name::from("example")));
+/// assert_eq!(names.function(0), Some(&Name::from("main")));
+/// assert_eq!(names.local(0, 1), Some(&Name::from("counter")));
+/// assert_eq!(names.local(0, 0), None);
+/// assert_eq!(names.label(0, 0), None);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NameSection {
+    module: Option<Name>,
+    functions: NameMap,
+    locals: IndirectNameMap,
+    labels: IndirectNameMap,
+    types: NameMap,
+    tables: NameMap,
+    memories: NameMap,
+    globals: NameMap,
+    elements: NameMap,
+    data: NameMap,
+    fields: IndirectNameMap,
+    tags: NameMap,
+}
+
+impl NameSection {
+    /// Creates a new empty name section.
+    pub fn new() -> Self {
+        NameSection::default()
+    }
+
+    /// Returns true if no names are recorded in this section, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.module.is_none()
+            && self.functions.is_empty()
+            && self.locals.values().all(NameMap::is_empty)
+            && self.labels.values().all(NameMap::is_empty)
+            && self.types.is_empty()
+            && self.tables.is_empty()
+            && self.memories.is_empty()
+            && self.globals.is_empty()
+            && self.elements.is_empty()
+            && self.data.is_empty()
+            && self.fields.values().all(NameMap::is_empty)
+            && self.tags.is_empty()
+    }
+
+    /// The name of the module.
+    pub fn module(&self) -> Option<&Name> {
+        self.module.as_ref()
+    }
+
+    /// Sets the name of the module.
+    pub fn set_module(&mut self, module: Option<Name>) {
+        self.module = module;
+    }
+
+    /// The name of the function at the given index, if any.
+    pub fn function(&self, function: FunctionIndex) -> Option<&Name> {
+        self.functions.get(&function)
+    }
+
+    /// The name of the given local of the function at the given index, if any.
+    pub fn local(&self, function: FunctionIndex, local: u32) -> Option<&Name> {
+        self.locals.get(&function)?.get(&local)
+    }
+
+    /// The name of the given label of the function at the given index, if any.
+    /// Labels are indexed by the order in which their structured instructions
+    /// appear in the function body.
+    pub fn label(&self, function: FunctionIndex, label: u32) -> Option<&Name> {
+        self.labels.get(&function)?.get(&label)
+    }
+
+    /// The name of the given field of the type at the given index, if any.
+    pub fn field(&self, kind: TypeIndex, field: u32) -> Option<&Name> {
+        self.fields.get(&kind)?.get(&field)
+    }
+
+    /// The names of the functions, including imports.
+    pub fn functions(&self) -> &NameMap {
+        &self.functions
+    }
+
+    /// The mutable names of the functions, including imports.
+    pub fn functions_mut(&mut self) -> &mut NameMap {
+        &mut self.functions
+    }
+
+    /// The names of the locals (including parameters), grouped by function.
+    pub fn locals(&self) -> &IndirectNameMap {
+        &self.locals
+    }
+
+    /// The mutable names of the locals (including parameters), grouped by function.
+    pub fn locals_mut(&mut self) -> &mut IndirectNameMap {
+        &mut self.locals
+    }
+
+    /// The names of the labels, grouped by function.
+    pub fn labels(&self) -> &IndirectNameMap {
+        &self.labels
+    }
+
+    /// The mutable names of the labels, grouped by function.
+    pub fn labels_mut(&mut self) -> &mut IndirectNameMap {
+        &mut self.labels
+    }
+
+    /// The names of the types.
+    pub fn types(&self) -> &NameMap {
+        &self.types
+    }
+
+    /// The mutable names of the types.
+    pub fn types_mut(&mut self) -> &mut NameMap {
+        &mut self.types
+    }
+
+    /// The names of the tables, including imports.
+    pub fn tables(&self) -> &NameMap {
+        &self.tables
+    }
+
+    /// The mutable names of the tables, including imports.
+    pub fn tables_mut(&mut self) -> &mut NameMap {
+        &mut self.tables
+    }
+
+    /// The names of the memories, including imports.
+    pub fn memories(&self) -> &NameMap {
+        &self.memories
+    }
+
+    /// The mutable names of the memories, including imports.
+    pub fn memories_mut(&mut self) -> &mut NameMap {
+        &mut self.memories
+    }
+
+    /// The names of the globals, including imports.
+    pub fn globals(&self) -> &NameMap {
+        &self.globals
+    }
+
+    /// The mutable names of the globals, including imports.
+    pub fn globals_mut(&mut self) -> &mut NameMap {
+        &mut self.globals
+    }
+
+    /// The names of the element segments.
+    pub fn elements(&self) -> &NameMap {
+        &self.elements
+    }
+
+    /// The mutable names of the element segments.
+    pub fn elements_mut(&mut self) -> &mut NameMap {
+        &mut self.elements
+    }
+
+    /// The names of the data segments.
+    pub fn data(&self) -> &NameMap {
+        &self.data
+    }
+
+    /// The mutable names of the data segments.
+    pub fn data_mut(&mut self) -> &mut NameMap {
+        &mut self.data
+    }
+
+    /// The names of the fields, grouped by structure type.
+    pub fn fields(&self) -> &IndirectNameMap {
+        &self.fields
+    }
+
+    /// The mutable names of the fields, grouped by structure type.
+    pub fn fields_mut(&mut self) -> &mut IndirectNameMap {
+        &mut self.fields
+    }
+
+    /// The names of the tags, including imports.
+    pub fn tags(&self) -> &NameMap {
+        &self.tags
+    }
+
+    /// The mutable names of the tags, including imports.
+    pub fn tags_mut(&mut self) -> &mut NameMap {
+        &mut self.tags
+    }
+}
+
+/// The name section is a custom section whose name string is itself ‘𝚗𝚊𝚖𝚎’.
+/// Its contents consist of a sequence of subsections, each identified by an id,
+/// which must appear at most once and in order of increasing id.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#subsections>
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum NameSubsection {
+    /// The module name subsection has the id 0. It consists of a single name.
+    Module = 0,
+    /// The function name subsection has the id 1. It consists of a name map for function indices.
+    Function,
+    /// The local name subsection has the id 2.
+    /// It consists of an indirect name map for local indices grouped by function indices.
+    Local,
+    /// The label name subsection has the id 3.
+    /// It consists of an indirect name map for label indices grouped by function indices.
+    Label,
+    /// The type name subsection has the id 4. It consists of a name map for type indices.
+    Type,
+    /// The table name subsection has the id 5. It consists of a name map for table indices.
+    Table,
+    /// The memory name subsection has the id 6. It consists of a name map for memory indices.
+    Memory,
+    /// The global name subsection has the id 7. It consists of a name map for global indices.
+    Global,
+    /// The element name subsection has the id 8. It consists of a name map for element indices.
+    Element,
+    /// The data name subsection has the id 9. It consists of a name map for data indices.
+    Data,
+    /// The field name subsection has the id 10.
+    /// It consists of an indirect name map for field indices grouped by type indices.
+    Field,
+    /// The tag name subsection has the id 11. It consists of a name map for tag indices.
+    Tag,
+}
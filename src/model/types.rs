@@ -1,5 +1,7 @@
 //! Model for types in the WebAssembly syntax.
 
+use crate::model::indices::TypeIndex;
+
 /// Number types classify numeric values.
 /// Number types are transparent, meaning that their bit patterns can be observed.
 /// Values of number type can be stored in memories.
@@ -78,31 +80,162 @@ pub enum FloatType {
     F64,
 }
 
+/// Heap types classify the objects in the runtime store that references point to.
+/// Abstract heap types denote a common class of objects (e.g., all functions),
+/// while concrete heap types denote the objects of a type defined in the module.
+///
+/// The garbage collection proposal adds abstract heap types for the internal (𝖺𝗇𝗒) hierarchy,
+/// as well as the bottom types (e.g., 𝗇𝗈𝗇𝖾) of each hierarchy.
+///
+/// See <https://webassembly.github.io/function-references/core/syntax/types.html#heap-types>
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#heap-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{HeapType, ReferenceType};
+///
+/// assert_eq!(HeapType::Function, ReferenceType::Function.heap_type());
+/// assert_eq!(HeapType::Index(2), ReferenceType::NonNullable(HeapType::Index(2)).heap_type());
+/// assert_eq!(HeapType::from(2), HeapType::Index(2));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HeapType {
+    /// 𝖿𝗎𝗇𝖼
+    Function,
+    /// 𝖾𝗑𝗍𝖾𝗋𝗇
+    External,
+    /// 𝖾𝗑𝗇
+    Exception,
+    /// 𝖺𝗇𝗒
+    Any,
+    /// 𝖾𝗊
+    Equality,
+    /// 𝗂𝟥𝟣
+    I31,
+    /// 𝗌𝗍𝗋𝗎𝖼𝗍
+    Struct,
+    /// 𝖺𝗋𝗋𝖺𝗒
+    Array,
+    /// 𝗇𝗈𝗇𝖾
+    None,
+    /// 𝗇𝗈𝖿𝗎𝗇𝖼
+    NoFunction,
+    /// 𝗇𝗈𝖾𝗑𝗍𝖾𝗋𝗇
+    NoExternal,
+    /// 𝗇𝗈𝖾𝗑𝗇
+    NoException,
+    /// A concrete heap type referring to a type defined in the module.
+    Index(TypeIndex),
+}
+
+impl From<TypeIndex> for HeapType {
+    fn from(index: TypeIndex) -> Self {
+        HeapType::Index(index)
+    }
+}
+
 /// Reference types classify first-class references to objects in the runtime store.
 /// The type 𝖿𝗎𝗇𝖼𝗋𝖾𝖿 denotes the infinite union of all references to functions,
 /// regardless of their function types.
 /// The type 𝖾𝗑𝗍𝖾𝗋𝗇𝗋𝖾𝖿 denotes the infinite union of all references to objects owned by the
 /// embedder and that can be passed into WebAssembly under this type.
+/// The type 𝖾𝗑𝗇𝗋𝖾𝖿 denotes references to exceptions caught by a 𝗍𝗋𝗒_𝗍𝖺𝖻𝗅𝖾 instruction.
 /// Reference types are opaque, meaning that neither their size nor their bit pattern can be observed.
 /// Values of reference type can be stored in tables.
 ///
+/// With typed function references, a reference type (𝗋𝖾𝖿 𝗇𝗎𝗅𝗅? heaptype) pairs a heap type with
+/// whether the reference may be null.
+/// The types 𝖿𝗎𝗇𝖼𝗋𝖾𝖿, 𝖾𝗑𝗍𝖾𝗋𝗇𝗋𝖾𝖿 and 𝖾𝗑𝗇𝗋𝖾𝖿 are shorthands for (𝗋𝖾𝖿 𝗇𝗎𝗅𝗅 𝖿𝗎𝗇𝖼),
+/// (𝗋𝖾𝖿 𝗇𝗎𝗅𝗅 𝖾𝗑𝗍𝖾𝗋𝗇) and (𝗋𝖾𝖿 𝗇𝗎𝗅𝗅 𝖾𝗑𝗇), respectively.
+/// Nullable references to the function, external and exception heap types are always modeled
+/// by their shorthand variants (see [`ReferenceType::nullable`]).
+/// The garbage collection shorthands (e.g., 𝖺𝗇𝗒𝗋𝖾𝖿) are modeled as nullable references
+/// to the corresponding abstract heap type.
+/// All of the shorthands are emitted in their shorthand form.
+///
 /// See <https://webassembly.github.io/spec/core/syntax/types.html#reference-types>
 ///
+/// See <https://webassembly.github.io/exception-handling/core/syntax/types.html#reference-types>
+///
+/// See <https://webassembly.github.io/function-references/core/syntax/types.html#reference-types>
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#reference-types>
+///
 /// # Examples
+/// ## Shorthand
 /// ```rust
-/// use wasm_ast::{ValueType, ReferenceType};
+/// use wasm_ast::{HeapType, ValueType, ReferenceType};
 ///
 /// assert_eq!(ValueType::FunctionReference, ReferenceType::Function.into());
 /// assert_eq!(ValueType::ExternalReference, ReferenceType::External.into());
+/// assert_eq!(ValueType::ExceptionReference, ReferenceType::Exception.into());
+/// assert_eq!(ReferenceType::External.heap_type(), HeapType::External);
+/// assert!(ReferenceType::External.is_nullable());
+/// assert_eq!(ReferenceType::nullable(HeapType::Function), ReferenceType::Function);
+/// assert_eq!(ReferenceType::nullable(HeapType::Any), ReferenceType::Nullable(HeapType::Any));
+/// ```
+///
+/// ## Typed
+/// ```rust
+/// use wasm_ast::{HeapType, ValueType, ReferenceType};
+///
+/// let nullable = ReferenceType::Nullable(HeapType::Index(0));
+/// let non_nullable = ReferenceType::NonNullable(HeapType::Function);
+///
+/// assert_eq!(ValueType::Reference(nullable), nullable.into());
+/// assert_eq!(nullable.heap_type(), HeapType::Index(0));
+/// assert!(nullable.is_nullable());
+/// assert_eq!(non_nullable.heap_type(), HeapType::Function);
+/// assert!(!non_nullable.is_nullable());
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ReferenceType {
     Function,
     External,
+    Exception,
+    /// (𝗋𝖾𝖿 𝗇𝗎𝗅𝗅 heaptype)
+    Nullable(HeapType),
+    /// (𝗋𝖾𝖿 heaptype)
+    NonNullable(HeapType),
+}
+
+impl ReferenceType {
+    /// Creates a nullable reference type to the given heap type,
+    /// using the shorthand variants for the function, external and exception heap types.
+    pub fn nullable(heap_type: HeapType) -> Self {
+        match heap_type {
+            HeapType::Function => ReferenceType::Function,
+            HeapType::External => ReferenceType::External,
+            HeapType::Exception => ReferenceType::Exception,
+            heap_type => ReferenceType::Nullable(heap_type),
+        }
+    }
+
+    /// The heap type of the objects referenced by this `ReferenceType`.
+    pub fn heap_type(&self) -> HeapType {
+        match self {
+            ReferenceType::Function => HeapType::Function,
+            ReferenceType::External => HeapType::External,
+            ReferenceType::Exception => HeapType::Exception,
+            ReferenceType::Nullable(kind) | ReferenceType::NonNullable(kind) => *kind,
+        }
+    }
+
+    /// Whether references of this `ReferenceType` may be null.
+    pub fn is_nullable(&self) -> bool {
+        !matches!(self, ReferenceType::NonNullable(_))
+    }
 }
 
 /// Value types classify the individual values that WebAssembly code can compute with and the values that a variable accepts.
-/// They are either number types or reference types.
+/// They are either number types, vector types, or reference types.
+///
+/// The vector type 𝗏𝟣𝟤𝟪 classifies vectors of packed integer or floating-point data.
+/// Vectors are transparent, meaning that their bit patterns can be observed.
+///
+/// The shorthand reference types have dedicated value types,
+/// while all other reference types are wrapped as a 𝗋𝖾𝖿 value type.
 ///
 /// See <https://webassembly.github.io/spec/core/syntax/types.html#value-types>
 ///
@@ -120,6 +253,7 @@ pub enum ReferenceType {
 /// assert_eq!(ValueType::F64, NumberType::F64.into());
 /// assert_eq!(ValueType::FunctionReference, ReferenceType::Function.into());
 /// assert_eq!(ValueType::ExternalReference, ReferenceType::External.into());
+/// assert_eq!(ValueType::ExceptionReference, ReferenceType::Exception.into());
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ValueType {
@@ -127,8 +261,11 @@ pub enum ValueType {
     I64,
     F32,
     F64,
+    V128,
     FunctionReference,
     ExternalReference,
+    ExceptionReference,
+    Reference(ReferenceType),
 }
 
 impl<T> From<T> for ValueType
@@ -150,6 +287,8 @@ impl From<ReferenceType> for ValueType {
         match kind {
             ReferenceType::Function => ValueType::FunctionReference,
             ReferenceType::External => ValueType::ExternalReference,
+            ReferenceType::Exception => ValueType::ExceptionReference,
+            kind => ValueType::Reference(kind),
         }
     }
 }
@@ -212,7 +351,7 @@ impl From<ReferenceType> for ValueType {
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ResultType {
-    kinds: Vec<ValueType>,
+    pub(crate) kinds: Vec<ValueType>,
 }
 
 impl ResultType {
@@ -297,8 +436,8 @@ impl From<Vec<ValueType>> for ResultType {
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FunctionType {
-    parameters: ResultType,
-    results: ResultType,
+    pub(crate) parameters: ResultType,
+    pub(crate) results: ResultType,
 }
 
 impl FunctionType {
@@ -345,15 +484,408 @@ impl FunctionType {
     }
 }
 
+/// Packed types are storage types that are narrower than any value type.
+/// Fields and array elements of packed type are sign- or zero-extended to 𝗂𝟥𝟤 when read.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{PackedType, StorageType};
+///
+/// assert_eq!(StorageType::Packed(PackedType::I8), PackedType::I8.into());
+/// assert_eq!(StorageType::Packed(PackedType::I16), PackedType::I16.into());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PackedType {
+    I8,
+    I16,
+}
+
+/// Storage types classify the values stored in the fields of structures and the elements of arrays.
+/// A storage type is either a value type or a packed type.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{PackedType, StorageType, ValueType};
+///
+/// assert_eq!(StorageType::Value(ValueType::F64), ValueType::F64.into());
+/// assert_eq!(StorageType::Packed(PackedType::I8), PackedType::I8.into());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StorageType {
+    Value(ValueType),
+    Packed(PackedType),
+}
+
+impl From<ValueType> for StorageType {
+    fn from(kind: ValueType) -> Self {
+        StorageType::Value(kind)
+    }
+}
+
+impl From<PackedType> for StorageType {
+    fn from(kind: PackedType) -> Self {
+        StorageType::Packed(kind)
+    }
+}
+
+/// Field types classify the fields of structures and the elements of arrays
+/// by their storage type and mutability.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{FieldType, Mutability, PackedType, StorageType, ValueType};
+///
+/// let mutable = FieldType::mutable(PackedType::I8.into());
+/// let immutable = FieldType::immutable(ValueType::I32.into());
+///
+/// assert_eq!(mutable.kind(), StorageType::Packed(PackedType::I8));
+/// assert_eq!(mutable.mutability(), Mutability::Mutable);
+/// assert_eq!(immutable, FieldType::new(ValueType::I32.into(), Mutability::Immutable));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FieldType {
+    pub(crate) kind: StorageType,
+    mutability: Mutability,
+}
+
+impl FieldType {
+    /// Creates a new `FieldType` with the given storage type and mutability.
+    pub fn new(kind: StorageType, mutability: Mutability) -> Self {
+        FieldType { kind, mutability }
+    }
+
+    /// Creates a new mutable `FieldType` with the given storage type.
+    pub fn mutable(kind: StorageType) -> Self {
+        FieldType {
+            kind,
+            mutability: Mutability::Mutable,
+        }
+    }
+
+    /// Creates a new immutable `FieldType` with the given storage type.
+    pub fn immutable(kind: StorageType) -> Self {
+        FieldType {
+            kind,
+            mutability: Mutability::Immutable,
+        }
+    }
+
+    /// The storage type of this `FieldType`.
+    pub fn kind(&self) -> StorageType {
+        self.kind
+    }
+
+    /// The mutability of this `FieldType`.
+    pub fn mutability(&self) -> Mutability {
+        self.mutability
+    }
+}
+
+/// Structure types define heap-allocated aggregates with a fixed sequence of heterogeneous fields.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{FieldType, StructType, ValueType};
+///
+/// let fields = vec![FieldType::immutable(ValueType::I32.into())];
+/// let struct_type = StructType::new(fields.clone());
+///
+/// assert_eq!(struct_type.fields(), fields.as_slice());
+/// assert_eq!(struct_type, fields.into());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructType {
+    pub(crate) fields: Vec<FieldType>,
+}
+
+impl StructType {
+    /// Creates a new `StructType` with the given fields.
+    pub fn new(fields: Vec<FieldType>) -> Self {
+        StructType { fields }
+    }
+
+    /// The fields of this `StructType`.
+    pub fn fields(&self) -> &[FieldType] {
+        &self.fields
+    }
+}
+
+impl From<Vec<FieldType>> for StructType {
+    fn from(fields: Vec<FieldType>) -> Self {
+        StructType { fields }
+    }
+}
+
+/// Array types define heap-allocated aggregates with a dynamic number of homogeneous elements.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{ArrayType, FieldType, PackedType};
+///
+/// let field = FieldType::mutable(PackedType::I16.into());
+/// let array_type = ArrayType::new(field);
+///
+/// assert_eq!(array_type.field(), field);
+/// assert_eq!(array_type, field.into());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ArrayType {
+    pub(crate) field: FieldType,
+}
+
+impl ArrayType {
+    /// Creates a new `ArrayType` with elements of the given field type.
+    pub fn new(field: FieldType) -> Self {
+        ArrayType { field }
+    }
+
+    /// The field type of the elements of this `ArrayType`.
+    pub fn field(&self) -> FieldType {
+        self.field
+    }
+}
+
+impl From<FieldType> for ArrayType {
+    fn from(field: FieldType) -> Self {
+        ArrayType { field }
+    }
+}
+
+/// Composite types are all types composed from simpler types,
+/// including function types, structure types and array types.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#composite-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{ArrayType, CompositeType, FieldType, FunctionType, StructType, ValueType};
+///
+/// let array_type = ArrayType::new(FieldType::mutable(ValueType::I32.into()));
+///
+/// assert_eq!(CompositeType::Function(FunctionType::runnable()), FunctionType::runnable().into());
+/// assert_eq!(CompositeType::Struct(StructType::new(vec![])), StructType::new(vec![]).into());
+/// assert_eq!(CompositeType::Array(array_type), array_type.into());
+/// assert_eq!(CompositeType::from(FunctionType::runnable()).function(), Some(&FunctionType::runnable()));
+/// assert_eq!(CompositeType::from(array_type).function(), None);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CompositeType {
+    Function(FunctionType),
+    Struct(StructType),
+    Array(ArrayType),
+}
+
+impl CompositeType {
+    /// The function type of this `CompositeType`, if it is one.
+    pub fn function(&self) -> Option<&FunctionType> {
+        match self {
+            CompositeType::Function(kind) => Some(kind),
+            _ => None,
+        }
+    }
+}
+
+impl From<FunctionType> for CompositeType {
+    fn from(kind: FunctionType) -> Self {
+        CompositeType::Function(kind)
+    }
+}
+
+impl From<StructType> for CompositeType {
+    fn from(kind: StructType) -> Self {
+        CompositeType::Struct(kind)
+    }
+}
+
+impl From<ArrayType> for CompositeType {
+    fn from(kind: ArrayType) -> Self {
+        CompositeType::Array(kind)
+    }
+}
+
+/// Sub types declare a composite type along with its declared supertypes.
+/// A final sub type cannot be a supertype of any other type.
+/// A composite type on its own is shorthand for a final sub type without supertypes.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#recursive-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{CompositeType, FunctionType, StructType, SubType};
+///
+/// let open = SubType::new(false, vec![0], StructType::new(vec![]).into());
+///
+/// assert!(!open.is_final());
+/// assert_eq!(open.supertypes(), &[0]);
+/// assert_eq!(open.kind(), &CompositeType::Struct(StructType::new(vec![])));
+///
+/// let function = SubType::from(FunctionType::runnable());
+///
+/// assert!(function.is_final());
+/// assert!(function.supertypes().is_empty());
+/// assert_eq!(function, SubType::new(true, vec![], FunctionType::runnable().into()));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubType {
+    is_final: bool,
+    pub(crate) supertypes: Vec<TypeIndex>,
+    pub(crate) kind: CompositeType,
+}
+
+impl SubType {
+    /// Creates a new `SubType` of the given composite type with the given finality and supertypes.
+    pub fn new(is_final: bool, supertypes: Vec<TypeIndex>, kind: CompositeType) -> Self {
+        SubType {
+            is_final,
+            supertypes,
+            kind,
+        }
+    }
+
+    /// Whether this `SubType` may not be the supertype of any other type.
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// The indices of the declared supertypes of this `SubType`.
+    pub fn supertypes(&self) -> &[TypeIndex] {
+        &self.supertypes
+    }
+
+    /// The composite type declared by this `SubType`.
+    pub fn kind(&self) -> &CompositeType {
+        &self.kind
+    }
+}
+
+impl<T> From<T> for SubType
+where
+    T: Into<CompositeType>,
+{
+    fn from(kind: T) -> Self {
+        SubType {
+            is_final: true,
+            supertypes: Vec::new(),
+            kind: kind.into(),
+        }
+    }
+}
+
+/// Recursive types group sub types that may refer to each other.
+/// Each sub type in a group defines its own type index,
+/// in the order the sub types are declared.
+/// A single sub type is shorthand for a group containing only that sub type.
+///
+/// See <https://webassembly.github.io/gc/core/syntax/types.html#recursive-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{FunctionType, RecursiveType, StructType, SubType};
+///
+/// let group = RecursiveType::new(vec![
+///     SubType::from(StructType::new(vec![])),
+///     SubType::from(FunctionType::runnable()),
+/// ]);
+///
+/// assert_eq!(group.len(), 2);
+/// assert!(!group.is_empty());
+/// assert_eq!(group.types()[1], SubType::from(FunctionType::runnable()));
+/// assert_eq!(RecursiveType::from(FunctionType::runnable()).len(), 1);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecursiveType {
+    pub(crate) types: Vec<SubType>,
+}
+
+impl RecursiveType {
+    /// Creates a new `RecursiveType` for the given group of sub types.
+    pub fn new(types: Vec<SubType>) -> Self {
+        RecursiveType { types }
+    }
+
+    /// The sub types in this recursive group.
+    pub fn types(&self) -> &[SubType] {
+        &self.types
+    }
+
+    /// The number of sub types (i.e., type indices) defined by this recursive group.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Returns true if this recursive group defines no sub types, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+impl From<Vec<SubType>> for RecursiveType {
+    fn from(types: Vec<SubType>) -> Self {
+        RecursiveType { types }
+    }
+}
+
+impl<T> From<T> for RecursiveType
+where
+    T: Into<SubType>,
+{
+    fn from(kind: T) -> Self {
+        RecursiveType {
+            types: vec![kind.into()],
+        }
+    }
+}
+
+/// Index types classify the type of the addresses used to index into a memory or table.
+/// Memories and tables default to 32-bit indices, while the memory64 proposal introduces 64-bit indices.
+///
+/// See <https://webassembly.github.io/memory64/core/syntax/types.html#index-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{IndexType, Limit, ValueType};
+///
+/// assert_eq!(Limit::unbounded(1).index_type(), IndexType::I32);
+/// assert_eq!(ValueType::from(IndexType::I32), ValueType::I32);
+/// assert_eq!(ValueType::from(IndexType::I64), ValueType::I64);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IndexType {
+    I32,
+    I64,
+}
+
+impl From<IndexType> for ValueType {
+    fn from(kind: IndexType) -> Self {
+        match kind {
+            IndexType::I32 => ValueType::I32,
+            IndexType::I64 => ValueType::I64,
+        }
+    }
+}
+
 /// Limits classify the size range of resizeable storage associated with memory types and table types.
+/// Limits also determine the index type used to address the storage.
 ///
 /// See <https://webassembly.github.io/spec/core/syntax/types.html#limits>
 ///
+/// See <https://webassembly.github.io/memory64/core/syntax/types.html#limits>
+///
 /// # Examples
 ///
 /// ## New
 /// ```rust
-/// use wasm_ast::Limit;
+/// use wasm_ast::{IndexType, Limit};
 ///
 /// let max = Some(2);
 /// let min = 0;
@@ -361,6 +893,7 @@ impl FunctionType {
 ///
 /// assert_eq!(limit.min(), min);
 /// assert_eq!(limit.max(), max);
+/// assert_eq!(limit.index_type(), IndexType::I32);
 /// ```
 ///
 /// ## Unbounded
@@ -376,49 +909,92 @@ impl FunctionType {
 ///
 /// assert_eq!(Limit::bounded(2, 5), Limit::new(2, Some(5)));
 /// ```
+///
+/// ## With Index Type
+/// ```rust
+/// use wasm_ast::{IndexType, Limit};
+///
+/// let limit = Limit::with_index_type(IndexType::I64, 1, Some(u32::MAX as u64 + 1));
+///
+/// assert_eq!(limit.index_type(), IndexType::I64);
+/// assert_eq!(limit.min(), 1);
+/// assert_eq!(limit.max(), Some(u32::MAX as u64 + 1));
+/// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Limit {
-    min: u32,
-    max: Option<u32>,
+    index_type: IndexType,
+    min: u64,
+    max: Option<u64>,
 }
 
 impl Limit {
-    /// Creates a new limit with a required minimum and optional maximum.
-    pub fn new(min: u32, max: Option<u32>) -> Self {
-        Limit { min, max }
+    /// Creates a new limit with a 32-bit index type, a required minimum and optional maximum.
+    pub fn new(min: u64, max: Option<u64>) -> Self {
+        Limit {
+            index_type: IndexType::I32,
+            min,
+            max,
+        }
     }
 
-    /// Creates a new limit with a required minimum and no maximum.
-    pub fn unbounded(min: u32) -> Self {
-        Limit { min, max: None }
+    /// Creates a new limit with a 32-bit index type, a required minimum and no maximum.
+    pub fn unbounded(min: u64) -> Self {
+        Limit {
+            index_type: IndexType::I32,
+            min,
+            max: None,
+        }
     }
 
-    /// Creates a new limit with a required minimum and maximum.
-    pub fn bounded(min: u32, max: u32) -> Self {
+    /// Creates a new limit with a 32-bit index type, a required minimum and maximum.
+    pub fn bounded(min: u64, max: u64) -> Self {
         Limit {
+            index_type: IndexType::I32,
             min,
             max: Some(max),
         }
     }
 
+    /// Creates a new limit with the given index type, a required minimum and optional maximum.
+    pub fn with_index_type(index_type: IndexType, min: u64, max: Option<u64>) -> Self {
+        Limit {
+            index_type,
+            min,
+            max,
+        }
+    }
+
     /// The minimum value of the limit.
-    pub fn min(&self) -> u32 {
+    pub fn min(&self) -> u64 {
         self.min
     }
 
     /// The optional maximum value of the limit.
-    pub fn max(&self) -> Option<u32> {
+    pub fn max(&self) -> Option<u64> {
         self.max
     }
+
+    /// The type of the indices used to address the storage constrained by this limit.
+    pub fn index_type(&self) -> IndexType {
+        self.index_type
+    }
 }
 
 /// Memory types classify linear memories and their size range.
 /// The limits constrain the minimum and optionally the maximum size of a memory.
 /// The limits are given in units of page size.
 ///
+/// Memories may be shared between threads (i.e., instances running concurrently).
+/// Shared memories must declare a maximum size.
+///
 /// See <https://webassembly.github.io/spec/core/syntax/types.html#memory-types>
 ///
+/// See <https://webassembly.github.io/threads/core/syntax/types.html#memory-types>
+///
+/// See <https://webassembly.github.io/memory64/core/syntax/types.html#memory-types>
+///
 /// # Examples
+/// ## Unshared
 /// ```rust
 /// use wasm_ast::{Limit, MemoryType};
 ///
@@ -426,28 +1002,72 @@ impl Limit {
 /// let memory_type = MemoryType::new(limit.clone());
 ///
 /// assert_eq!(memory_type.limits(), &limit);
+/// assert!(!memory_type.is_shared());
 /// assert_eq!(memory_type, limit.into());
 /// ```
+///
+/// ## Shared
+/// ```rust
+/// use wasm_ast::{Limit, MemoryType};
+///
+/// let limit = Limit::bounded(1, 2);
+/// let memory_type = MemoryType::shared(limit.clone());
+///
+/// assert_eq!(memory_type.limits(), &limit);
+/// assert!(memory_type.is_shared());
+/// ```
+///
+/// ## 64-bit
+/// ```rust
+/// use wasm_ast::{IndexType, Limit, MemoryType};
+///
+/// let limit = Limit::with_index_type(IndexType::I64, 1, None);
+/// let memory_type = MemoryType::new(limit);
+///
+/// assert_eq!(memory_type.index_type(), IndexType::I64);
+/// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct MemoryType {
-    limits: Limit,
+    pub(crate) limits: Limit,
+    shared: bool,
 }
 
 impl MemoryType {
-    /// Creates a new memory type from the given limits.
+    /// Creates a new unshared memory type from the given limits.
     pub fn new(limit: Limit) -> Self {
-        MemoryType { limits: limit }
+        MemoryType {
+            limits: limit,
+            shared: false,
+        }
+    }
+
+    /// Creates a new memory type from the given limits that can be shared between threads.
+    pub fn shared(limit: Limit) -> Self {
+        MemoryType {
+            limits: limit,
+            shared: true,
+        }
     }
 
     /// The limits of the number of pages for this `MemoryType`.
     pub fn limits(&self) -> &Limit {
         &self.limits
     }
+
+    /// Whether memories of this `MemoryType` can be shared between threads.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// The type of the addresses used to access memories of this `MemoryType`.
+    pub fn index_type(&self) -> IndexType {
+        self.limits.index_type()
+    }
 }
 
 impl From<Limit> for MemoryType {
     fn from(limit: Limit) -> Self {
-        MemoryType { limits: limit }
+        MemoryType::new(limit)
     }
 }
 
@@ -469,8 +1089,8 @@ impl From<Limit> for MemoryType {
 /// ```
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct TableType {
-    limits: Limit,
-    kind: ReferenceType,
+    pub(crate) limits: Limit,
+    pub(crate) kind: ReferenceType,
 }
 
 impl TableType {
@@ -519,7 +1139,7 @@ impl TableType {
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct GlobalType {
     mutability: Mutability,
-    kind: ValueType,
+    pub(crate) kind: ValueType,
 }
 
 impl GlobalType {
@@ -561,3 +1181,41 @@ pub enum Mutability {
     Mutable,
     Immutable,
 }
+
+/// Tag types classify the exceptions that can be thrown and caught.
+/// A tag type refers to a function type whose parameters define the values carried by the exception.
+/// The result type of the function type must be empty.
+///
+/// See <https://webassembly.github.io/exception-handling/core/syntax/types.html#tag-types>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::TagType;
+///
+/// let tag_type = TagType::new(3);
+///
+/// assert_eq!(tag_type.kind(), 3);
+/// assert_eq!(tag_type, 3.into());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TagType {
+    pub(crate) kind: TypeIndex,
+}
+
+impl TagType {
+    /// Creates a new `TagType` for exceptions with the parameters of the given function type.
+    pub fn new(kind: TypeIndex) -> Self {
+        TagType { kind }
+    }
+
+    /// The index of the function type that describes the values of exceptions with this `TagType`.
+    pub fn kind(&self) -> TypeIndex {
+        self.kind
+    }
+}
+
+impl From<TypeIndex> for TagType {
+    fn from(kind: TypeIndex) -> Self {
+        TagType { kind }
+    }
+}
@@ -16,6 +16,7 @@
 /// assert_eq!(name, Name::from(text));
 /// assert_eq!(name, Name::from(text.to_string()));
 /// assert_eq!(name.as_bytes(), text.as_bytes());
+/// assert_eq!(name.as_str(), text);
 /// assert_eq!(name.len(), text.len());
 /// assert_eq!(name.is_empty(), false);
 /// ```
@@ -35,6 +36,11 @@ impl Name {
         self.value.as_bytes()
     }
 
+    /// Returns a string slice of this `Name`’s contents.
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
+
     /// Returns the length of this `Name`, in bytes, not chars or graphemes.
     /// In other words, it may not be what a human considers the length of the name.
     pub fn len(&self) -> usize {
@@ -1,24 +1,265 @@
+use crate::{FunctionIndex, ModuleSection};
+use nom::error::{ErrorKind, FromExternalError};
+use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
 /// An error in parser a WebAssembly module.
 #[derive(Error, Debug)]
 pub enum ParseError {
     #[cfg(feature = "text")]
-    #[error(
-        "The WebAssembly module in text format could not be transformed to the binary format."
-    )]
-    InvalidText(#[from] wat::Error),
-    #[error("The WebAssembly module is not a valid binary format.")]
-    InvalidBinary,
+    #[error("The WebAssembly module in text format is invalid at line {0}, column {1}: {2}.")]
+    InvalidText(usize, usize, String),
+    #[error("The WebAssembly module is not a valid binary format: {0}.")]
+    InvalidBinary(BinaryError),
     #[error(
         "The module's type and code sections have different lengths (type: {0:?}, code: {1:?})."
     )]
     MismatchedFunctionParts(Option<usize>, Option<usize>),
+    #[error("Unable to read the WebAssembly module: {0}.")]
+    Read(std::io::Error),
+}
+
+/// The location and cause of an error in the binary format of a WebAssembly module.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_binary, Expected, ModuleSection, ParseError};
+///
+/// let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\xFF\x0B";
+///
+/// match parse_binary(binary) {
+///     Err(ParseError::InvalidBinary(error)) => {
+///         assert_eq!(error.offset(), 0x17);
+///         assert_eq!(error.section(), Some(ModuleSection::Code));
+///         assert_eq!(error.function(), Some(0));
+///         assert_eq!(error.expected(), Expected::Opcode);
+///         assert_eq!(error.found(), Some(0xFF));
+///         assert_eq!(error.to_string(), "unknown opcode 0xFF at offset 0x17 in function 0");
+///     }
+///     result => panic!("unexpected result: {:?}", result),
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BinaryError {
+    offset: usize,
+    section: Option<ModuleSection>,
+    function: Option<FunctionIndex>,
+    expected: Expected,
+    found: Option<u8>,
+}
+
+impl BinaryError {
+    /// Creates a binary error from a parser error on a suffix of the given input.
+    /// Errors for input that ended unexpectedly are located where the input ended.
+    pub(crate) fn new(input: &[u8], error: nom::Err<InputError<'_>>) -> Self {
+        let (remaining, expected) = match error {
+            nom::Err::Error(error) | nom::Err::Failure(error)
+                if error.expected == Expected::More =>
+            {
+                (&error.input[error.input.len()..], Expected::More)
+            }
+            nom::Err::Error(error) | nom::Err::Failure(error) => (error.input, error.expected),
+            nom::Err::Incomplete(_) => (&input[input.len()..], Expected::More),
+        };
+        let offset = (remaining.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+
+        BinaryError {
+            offset,
+            section: None,
+            function: None,
+            expected,
+            found: remaining.first().copied(),
+        }
+    }
+
+    /// Moves the error by the given number of bytes, for input that starts at that offset.
+    pub(crate) fn offset_by(mut self, offset: usize) -> Self {
+        self.offset += offset;
+        self
+    }
+
+    /// Sets the section and function index the error occurred in.
+    pub(crate) fn within(
+        mut self,
+        section: Option<ModuleSection>,
+        function: Option<FunctionIndex>,
+    ) -> Self {
+        self.section = section;
+        self.function = function;
+        self
+    }
+
+    /// The offset in bytes of the error from the start of the input.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The section being parsed when the error occurred, if any.
+    pub fn section(&self) -> Option<ModuleSection> {
+        self.section
+    }
+
+    /// The index of the function whose body was being parsed when the error occurred, if any.
+    /// The index includes imported functions.
+    pub fn function(&self) -> Option<FunctionIndex> {
+        self.function
+    }
+
+    /// What the parser expected to find at the offset.
+    pub fn expected(&self) -> Expected {
+        self.expected
+    }
+
+    /// The byte found at the offset, or `None` at the end of the input.
+    pub fn found(&self) -> Option<u8> {
+        self.found
+    }
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.expected, self.found) {
+            (Expected::Opcode, Some(found)) => write!(f, "unknown opcode {:#04X}", found)?,
+            (expected, Some(found)) => write!(f, "expected {}, found {:#04X}", expected, found)?,
+            (expected, None) => write!(f, "unexpected end of input, expected {}", expected)?,
+        }
+
+        write!(f, " at offset {:#x}", self.offset)?;
+
+        match (self.function, self.section) {
+            (Some(function), _) => write!(f, " in function {}", function),
+            (None, Some(section)) => write!(f, " in the {:?} section", section),
+            (None, None) => Ok(()),
+        }
+    }
 }
 
-/// Create a parse error from a nom error.
-impl<T> From<nom::Err<nom::error::Error<T>>> for ParseError {
-    fn from(_: nom::Err<nom::error::Error<T>>) -> Self {
-        ParseError::InvalidBinary
+/// What the parser expected to find where an error in the binary format occurred.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Expected {
+    /// The magic constant that identifies the binary format.
+    Preamble,
+    /// The supported version of the binary format.
+    Version,
+    /// A known section, in the order required by the binary format.
+    Section,
+    /// The end of a section or a function body, as given by its size.
+    End,
+    /// A specific byte, such as the end of an expression.
+    Byte(u8),
+    /// A known instruction opcode.
+    Opcode,
+    /// An integer in the LEB128 encoding, within the range of its type.
+    Integer,
+    /// A name encoded as UTF-8.
+    Name,
+    /// A known type encoding.
+    Type,
+    /// More bytes than remain in the input.
+    More,
+    /// Well-formed syntax in general.
+    Syntax,
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Preamble => write!(f, "the magic constant \\0asm"),
+            Expected::Version => write!(f, "version 1 of the binary format"),
+            Expected::Section => write!(f, "a known section in order"),
+            Expected::End => write!(f, "the end of the section or function body"),
+            Expected::Byte(byte) => write!(f, "byte {:#04X}", byte),
+            Expected::Opcode => write!(f, "an opcode"),
+            Expected::Integer => write!(f, "an LEB128 integer"),
+            Expected::Name => write!(f, "a UTF-8 name"),
+            Expected::Type => write!(f, "a type"),
+            Expected::More => write!(f, "more bytes"),
+            Expected::Syntax => write!(f, "well-formed syntax"),
+        }
+    }
+}
+
+/// The result of a parser of the binary format.
+pub(crate) type ParseResult<'input, O> = nom::IResult<&'input [u8], O, InputError<'input>>;
+
+/// An error of a parser of the binary format, at the remaining input where the error occurred.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct InputError<'input> {
+    input: &'input [u8],
+    expected: Expected,
+}
+
+impl<'input> InputError<'input> {
+    pub(crate) fn new(input: &'input [u8], expected: Expected) -> Self {
+        InputError { input, expected }
+    }
+}
+
+impl<'input> nom::error::ParseError<&'input [u8]> for InputError<'input> {
+    fn from_error_kind(input: &'input [u8], kind: ErrorKind) -> Self {
+        let expected = match kind {
+            ErrorKind::Eof | ErrorKind::Complete => Expected::More,
+            _ if input.is_empty() => Expected::More,
+            _ => Expected::Syntax,
+        };
+
+        InputError::new(input, expected)
+    }
+
+    fn append(_: &'input [u8], _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    /// Keeps the error of the alternative that progressed the furthest into the input.
+    fn or(self, other: Self) -> Self {
+        if self.input.len() < other.input.len() {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+impl<'input, E> FromExternalError<&'input [u8], E> for InputError<'input> {
+    fn from_external_error(input: &'input [u8], _: ErrorKind, _: E) -> Self {
+        InputError::new(input, Expected::Syntax)
+    }
+}
+
+/// Replaces the expectation of errors that occur before the given parser consumes any input.
+pub(crate) fn expect<'input, O, P>(
+    expected: Expected,
+    mut parser: P,
+) -> impl FnMut(&'input [u8]) -> ParseResult<'input, O>
+where
+    P: nom::Parser<&'input [u8], O, InputError<'input>>,
+{
+    move |input: &'input [u8]| {
+        parser.parse(input).map_err(|error| {
+            error.map(|error| match error.input.as_ptr() == input.as_ptr() {
+                true => InputError::new(input, expected),
+                false => error,
+            })
+        })
+    }
+}
+
+/// Applies the given parser to the whole input, expecting no bytes to remain afterwards.
+pub(crate) fn consume_all<'input, O, P>(
+    mut parser: P,
+) -> impl FnMut(&'input [u8]) -> ParseResult<'input, O>
+where
+    P: nom::Parser<&'input [u8], O, InputError<'input>>,
+{
+    move |input: &'input [u8]| {
+        let (remaining, output) = parser.parse(input)?;
+
+        if remaining.is_empty() {
+            Ok((remaining, output))
+        } else {
+            Err(nom::Err::Error(InputError::new(remaining, Expected::End)))
+        }
     }
 }
@@ -1,17 +1,21 @@
-use crate::parser::types::{parse_reference_type, parse_value_type};
-use crate::parser::values::{match_byte, parse_s32, parse_s33, parse_s64, parse_u32, parse_vector};
+use crate::parser::errors::{expect, Expected, InputError, ParseResult};
+use crate::parser::types::{parse_heap_type, parse_value_type};
+use crate::parser::values::{
+    match_byte, parse_s32, parse_s33, parse_s64, parse_u32, parse_u64, parse_vector,
+};
 use crate::{
-    BlockType, ControlInstruction, Expression, FloatType, Instruction, IntegerType, MemoryArgument,
-    MemoryInstruction, NumberType, NumericInstruction, ParametricInstruction, ReferenceInstruction,
-    SignExtension, TableInstruction, VariableInstruction,
+    AggregateInstruction, AtomicInstruction, AtomicOperation, BlockType, CatchClause,
+    ControlInstruction, Expression, FloatType, FloatVectorShape, HeapType, Instruction,
+    IntegerType, IntegerVectorShape, LabelIndex, LaneIndex, MemoryArgument, MemoryInstruction,
+    NumberType, NumericInstruction, ParametricInstruction, ReferenceInstruction, ReferenceType,
+    SignExtension, TableInstruction, VariableInstruction, VectorHalf, VectorInstruction,
+    VectorShape,
 };
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::combinator::map;
-use nom::multi::fold_many0;
-use nom::number::complete::{le_f32, le_f64};
-use nom::sequence::{delimited, preceded, terminated, tuple};
-use nom::IResult;
+use nom::bytes::complete::{tag, take};
+use nom::combinator::{map, verify};
+use nom::number::complete::{le_f32, le_f64, le_i128, u8 as parse_byte};
+use nom::sequence::{preceded, tuple};
 
 /// Marks the end of an expression.
 const EXPRESSION_END: u8 = 0x0B;
@@ -19,45 +23,70 @@ const EXPRESSION_END: u8 = 0x0B;
 /// Parses a WebAssembly expression from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#expressions>
-pub fn parse_expression(input: &[u8]) -> IResult<&[u8], Expression> {
+pub fn parse_expression(input: &[u8]) -> ParseResult<'_, Expression> {
     parse_expression_with_terminal(EXPRESSION_END)(input)
 }
 
 /// Parses a WebAssembly expression with a given terminating opcode from the input.
+/// Bytes other than the terminating opcode must start an instruction,
+/// so that an unknown opcode is reported as such rather than as a missing terminal.
 fn parse_expression_with_terminal<'input>(
     terminal: u8,
-) -> impl FnMut(&'input [u8]) -> IResult<&'input [u8], Expression> {
-    map(
-        terminated(
-            fold_many0(parse_instruction, Vec::new, |mut accumulator, item| {
-                accumulator.push(item);
-                accumulator
-            }),
-            match_byte(terminal),
-        ),
-        Expression::new,
-    )
+) -> impl FnMut(&'input [u8]) -> ParseResult<'input, Expression> {
+    move |mut input| {
+        let mut instructions = Vec::new();
+
+        loop {
+            if let Ok((remaining, _)) = match_byte(terminal)(input) {
+                return Ok((remaining, Expression::new(instructions)));
+            }
+
+            let (remaining, instruction) = parse_instruction(input)?;
+
+            instructions.push(instruction);
+            input = remaining;
+        }
+    }
 }
 
 /// Parses a WebAssembly instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html>
-pub fn parse_instruction(input: &[u8]) -> IResult<&[u8], Instruction> {
-    alt((
-        map(parse_control_instruction, Instruction::from),
-        map(parse_reference_instruction, Instruction::from),
-        map(parse_parametric_instruction, Instruction::from),
-        map(parse_variable_instruction, Instruction::from),
-        map(parse_table_instruction, Instruction::from),
-        map(parse_memory_instruction, Instruction::from),
-        map(parse_numeric_instruction, Instruction::from),
-    ))(input)
+pub fn parse_instruction(input: &[u8]) -> ParseResult<'_, Instruction> {
+    expect(Expected::Opcode, parse_opcode)(input)
+}
+
+/// Parses a WebAssembly instruction from the input,
+/// using the leading byte of its opcode (and the second byte for the 0xFC prefix)
+/// to select the kind of instruction to parse rather than trying each kind in turn.
+fn parse_opcode(input: &[u8]) -> ParseResult<'_, Instruction> {
+    match input {
+        [0x00..=0x15 | 0x1F | 0xD5 | 0xD6, ..] => {
+            map(parse_control_instruction, Instruction::from)(input)
+        }
+        [0x1A..=0x1C, ..] => map(parse_parametric_instruction, Instruction::from)(input),
+        [0x20..=0x24, ..] => map(parse_variable_instruction, Instruction::from)(input),
+        [0x25 | 0x26, ..] | [0xFC, 12..=17, ..] => {
+            map(parse_table_instruction, Instruction::from)(input)
+        }
+        [0x28..=0x40, ..] | [0xFC, 8..=11, ..] => {
+            map(parse_memory_instruction, Instruction::from)(input)
+        }
+        [0x41..=0xC4, ..] | [0xFC, 0..=7, ..] => {
+            map(parse_numeric_instruction, Instruction::from)(input)
+        }
+        [0xD0..=0xD4, ..] => map(parse_reference_instruction, Instruction::from)(input),
+        [0xFB, ..] => parse_gc_instruction(input),
+        [0xFD, ..] => map(parse_vector_instruction, Instruction::from)(input),
+        [0xFE, ..] => map(parse_atomic_instruction, Instruction::from)(input),
+        _ => Err(nom::Err::Error(InputError::new(input, Expected::Opcode))),
+    }
 }
 
 /// Parses a WebAssembly control instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#control-instructions>
-pub fn parse_control_instruction(input: &[u8]) -> IResult<&[u8], ControlInstruction> {
+pub fn parse_control_instruction(input: &[u8]) -> ParseResult<'_, ControlInstruction> {
     alt((
         map(match_byte(0x00), |_| ControlInstruction::Unreachable),
         map(match_byte(0x01), |_| ControlInstruction::Nop),
@@ -119,27 +148,94 @@ pub fn parse_control_instruction(input: &[u8]) -> IResult<&[u8], ControlInstruct
             preceded(match_byte(0x11), tuple((parse_u32, parse_u32))),
             |(type_index, table_index)| ControlInstruction::CallIndirect(type_index, table_index),
         ),
+        map(
+            preceded(match_byte(0x12), parse_u32),
+            ControlInstruction::ReturnCall,
+        ),
+        map(
+            preceded(match_byte(0x13), tuple((parse_u32, parse_u32))),
+            |(type_index, table_index)| {
+                ControlInstruction::ReturnCallIndirect(type_index, table_index)
+            },
+        ),
+        map(
+            preceded(match_byte(0x14), parse_u32),
+            ControlInstruction::CallRef,
+        ),
+        map(
+            preceded(match_byte(0x15), parse_u32),
+            ControlInstruction::ReturnCallRef,
+        ),
+        map(
+            preceded(match_byte(0xD5), parse_u32),
+            ControlInstruction::BranchOnNull,
+        ),
+        map(
+            preceded(match_byte(0xD6), parse_u32),
+            ControlInstruction::BranchOnNonNull,
+        ),
+        map(
+            preceded(match_byte(0x08), parse_u32),
+            ControlInstruction::Throw,
+        ),
+        map(match_byte(0x0A), |_| ControlInstruction::ThrowRef),
+        map(
+            preceded(
+                match_byte(0x1F),
+                tuple((
+                    parse_block_type,
+                    parse_vector(parse_catch_clause),
+                    parse_expression,
+                )),
+            ),
+            |(kind, catches, expression)| ControlInstruction::TryTable(kind, catches, expression),
+        ),
+    ))(input)
+}
+
+/// Parses a catch clause of a WebAssembly try table instruction from the input.
+///
+/// See <https://webassembly.github.io/exception-handling/core/binary/instructions.html#control-instructions>
+pub fn parse_catch_clause(input: &[u8]) -> ParseResult<'_, CatchClause> {
+    alt((
+        map(
+            preceded(match_byte(0x00), tuple((parse_u32, parse_u32))),
+            |(tag, label)| CatchClause::Catch(tag, label),
+        ),
+        map(
+            preceded(match_byte(0x01), tuple((parse_u32, parse_u32))),
+            |(tag, label)| CatchClause::CatchReference(tag, label),
+        ),
+        map(preceded(match_byte(0x02), parse_u32), CatchClause::CatchAll),
+        map(
+            preceded(match_byte(0x03), parse_u32),
+            CatchClause::CatchAllReference,
+        ),
     ))(input)
 }
 
 /// Parses a WebAssembly control instruction's block type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#control-instructions>
-pub fn parse_block_type(input: &[u8]) -> IResult<&[u8], BlockType> {
+pub fn parse_block_type(input: &[u8]) -> ParseResult<'_, BlockType> {
     alt((
         map(match_byte(0x40), |_| BlockType::None),
         map(parse_value_type, BlockType::ValueType),
-        map(parse_s33, |index| BlockType::Index(index as u32)),
+        map(parse_s33, BlockType::Index),
     ))(input)
 }
 
 /// Parses a WebAssembly reference instruction from the input.
+/// The 𝗋𝖾𝖿.𝗍𝖾𝗌𝗍 and 𝗋𝖾𝖿.𝖼𝖺𝗌𝗍 instructions share the 0xFB prefix with the aggregate instructions,
+/// so they are parsed by `parse_gc_instruction` instead.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#reference-instructions>
-pub fn parse_reference_instruction(input: &[u8]) -> IResult<&[u8], ReferenceInstruction> {
+///
+/// See <https://webassembly.github.io/gc/core/binary/instructions.html#reference-instructions>
+pub fn parse_reference_instruction(input: &[u8]) -> ParseResult<'_, ReferenceInstruction> {
     alt((
         map(
-            preceded(match_byte(0xD0), parse_reference_type),
+            preceded(match_byte(0xD0), parse_heap_type),
             ReferenceInstruction::Null,
         ),
         map(match_byte(0xD1), |_| ReferenceInstruction::IsNull),
@@ -147,13 +243,15 @@ pub fn parse_reference_instruction(input: &[u8]) -> IResult<&[u8], ReferenceInst
             preceded(match_byte(0xD2), parse_u32),
             ReferenceInstruction::Function,
         ),
+        map(match_byte(0xD3), |_| ReferenceInstruction::Equal),
+        map(match_byte(0xD4), |_| ReferenceInstruction::AsNonNull),
     ))(input)
 }
 
 /// Parses a WebAssembly parametric instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#parametric-instructions>
-pub fn parse_parametric_instruction(input: &[u8]) -> IResult<&[u8], ParametricInstruction> {
+pub fn parse_parametric_instruction(input: &[u8]) -> ParseResult<'_, ParametricInstruction> {
     alt((
         map(match_byte(0x1A), |_| ParametricInstruction::Drop),
         map(match_byte(0x1B), |_| ParametricInstruction::Select(None)),
@@ -167,7 +265,7 @@ pub fn parse_parametric_instruction(input: &[u8]) -> IResult<&[u8], ParametricIn
 /// Parses a WebAssembly variable instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#variable-instructions>
-pub fn parse_variable_instruction(input: &[u8]) -> IResult<&[u8], VariableInstruction> {
+pub fn parse_variable_instruction(input: &[u8]) -> ParseResult<'_, VariableInstruction> {
     alt((
         map(
             preceded(match_byte(0x20), parse_u32),
@@ -195,7 +293,7 @@ pub fn parse_variable_instruction(input: &[u8]) -> IResult<&[u8], VariableInstru
 /// Parses a WebAssembly table instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#table-instructions>
-pub fn parse_table_instruction(input: &[u8]) -> IResult<&[u8], TableInstruction> {
+pub fn parse_table_instruction(input: &[u8]) -> ParseResult<'_, TableInstruction> {
     alt((
         map(preceded(match_byte(0x25), parse_u32), TableInstruction::Get),
         map(preceded(match_byte(0x26), parse_u32), TableInstruction::Set),
@@ -229,7 +327,7 @@ pub fn parse_table_instruction(input: &[u8]) -> IResult<&[u8], TableInstruction>
 /// Parses a WebAssembly memory instruction from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions>
-pub fn parse_memory_instruction(input: &[u8]) -> IResult<&[u8], MemoryInstruction> {
+pub fn parse_memory_instruction(input: &[u8]) -> ParseResult<'_, MemoryInstruction> {
     alt((
         alt((
             map(
@@ -335,584 +433,1103 @@ pub fn parse_memory_instruction(input: &[u8]) -> IResult<&[u8], MemoryInstructio
                 MemoryInstruction::Store32,
             ),
         )),
-        map(tag([0x3F, 0x00]), |_| MemoryInstruction::Size),
-        map(tag([0x40, 0x00]), |_| MemoryInstruction::Grow),
         map(
-            delimited(tag([0xFC, 8u8]), parse_u32, match_byte(0x00)),
-            MemoryInstruction::Init,
+            preceded(match_byte(0x3F), parse_u32),
+            MemoryInstruction::Size,
+        ),
+        map(
+            preceded(match_byte(0x40), parse_u32),
+            MemoryInstruction::Grow,
+        ),
+        map(
+            preceded(tag([0xFC, 8u8]), tuple((parse_u32, parse_u32))),
+            |(data, memory)| MemoryInstruction::Init(data, memory),
         ),
         map(
             preceded(tag([0xFC, 9u8]), parse_u32),
             MemoryInstruction::DataDrop,
         ),
-        map(tag([0xFC, 10u8, 0x00, 0x00]), |_| MemoryInstruction::Copy),
-        map(tag([0xFC, 11u8, 0x00]), |_| MemoryInstruction::Fill),
+        map(
+            preceded(tag([0xFC, 10u8]), tuple((parse_u32, parse_u32))),
+            |(destination, source)| MemoryInstruction::Copy(destination, source),
+        ),
+        map(
+            preceded(tag([0xFC, 11u8]), parse_u32),
+            MemoryInstruction::Fill,
+        ),
     ))(input)
 }
 
+/// Bit of the alignment field of a memarg that denotes an explicit memory index follows.
+const MEMORY_INDEX_FLAG: u32 = 1 << 6;
+
 /// Parses a WebAssembly memory instruction memarg from the input.
+/// When bit 6 of the alignment is set, the alignment is followed by a memory index.
+/// Otherwise, the memarg refers to memory 0.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions>
-pub fn parse_memory_argument(input: &[u8]) -> IResult<&[u8], MemoryArgument> {
-    map(tuple((parse_u32, parse_u32)), |(align, offset)| {
-        MemoryArgument::new(align, offset)
-    })(input)
+///
+/// See <https://webassembly.github.io/multi-memory/core/binary/instructions.html#memory-instructions>
+pub fn parse_memory_argument(input: &[u8]) -> ParseResult<'_, MemoryArgument> {
+    let (input, align) = parse_u32(input)?;
+
+    if align & MEMORY_INDEX_FLAG == 0 {
+        map(parse_u64, move |offset| MemoryArgument::new(align, offset))(input)
+    } else {
+        map(tuple((parse_u32, parse_u64)), move |(memory, offset)| {
+            MemoryArgument::with_memory(memory, align & !MEMORY_INDEX_FLAG, offset)
+        })(input)
+    }
 }
 
-/// Parses a WebAssembly numeric instruction from the input.
+/// The operations of the atomic read-modify-write instructions, in opcode order.
+const ATOMIC_OPERATIONS: [AtomicOperation; 7] = [
+    AtomicOperation::Add,
+    AtomicOperation::Subtract,
+    AtomicOperation::And,
+    AtomicOperation::Or,
+    AtomicOperation::Xor,
+    AtomicOperation::Exchange,
+    AtomicOperation::CompareExchange,
+];
+
+/// Parses a WebAssembly atomic memory instruction from the input.
+/// Atomic instructions are prefixed with the byte 0xFE, followed by an opcode encoded as an
+/// unsigned 32-bit integer.
 ///
-/// See <https://webassembly.github.io/spec/core/binary/instructions.html#numeric-instructions>
-pub fn parse_numeric_instruction(input: &[u8]) -> IResult<&[u8], NumericInstruction> {
-    alt((
-        alt((
-            map(
-                preceded(match_byte(0x41), parse_s32),
-                NumericInstruction::I32Constant,
-            ),
-            map(
-                preceded(match_byte(0x42), parse_s64),
-                NumericInstruction::I64Constant,
-            ),
-            map(
-                preceded(match_byte(0x43), le_f32),
-                NumericInstruction::F32Constant,
-            ),
-            map(
-                preceded(match_byte(0x44), le_f64),
-                NumericInstruction::F64Constant,
-            ),
-        )),
-        alt((
-            map(match_byte(0x45), |_| {
-                NumericInstruction::EqualToZero(IntegerType::I32)
-            }),
-            map(match_byte(0x46), |_| {
-                NumericInstruction::Equal(NumberType::I32)
-            }),
-            map(match_byte(0x47), |_| {
-                NumericInstruction::NotEqual(NumberType::I32)
-            }),
-            map(match_byte(0x48), |_| {
-                NumericInstruction::LessThanInteger(IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0x49), |_| {
-                NumericInstruction::LessThanInteger(IntegerType::I32, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x4A), |_| {
-                NumericInstruction::GreaterThanInteger(IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0x4B), |_| {
-                NumericInstruction::GreaterThanInteger(IntegerType::I32, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x4C), |_| {
-                NumericInstruction::LessThanOrEqualToInteger(
-                    IntegerType::I32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0x4D), |_| {
-                NumericInstruction::LessThanOrEqualToInteger(
-                    IntegerType::I32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0x4E), |_| {
-                NumericInstruction::GreaterThanOrEqualToInteger(
-                    IntegerType::I32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0x4F), |_| {
-                NumericInstruction::GreaterThanOrEqualToInteger(
-                    IntegerType::I32,
-                    SignExtension::Unsigned,
-                )
-            }),
-        )),
-        alt((
-            map(match_byte(0x50), |_| {
-                NumericInstruction::EqualToZero(IntegerType::I64)
-            }),
-            map(match_byte(0x51), |_| {
-                NumericInstruction::Equal(NumberType::I64)
-            }),
-            map(match_byte(0x52), |_| {
-                NumericInstruction::NotEqual(NumberType::I64)
-            }),
-            map(match_byte(0x53), |_| {
-                NumericInstruction::LessThanInteger(IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0x54), |_| {
-                NumericInstruction::LessThanInteger(IntegerType::I64, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x55), |_| {
-                NumericInstruction::GreaterThanInteger(IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0x56), |_| {
-                NumericInstruction::GreaterThanInteger(IntegerType::I64, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x57), |_| {
-                NumericInstruction::LessThanOrEqualToInteger(
-                    IntegerType::I64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0x58), |_| {
-                NumericInstruction::LessThanOrEqualToInteger(
-                    IntegerType::I64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0x59), |_| {
-                NumericInstruction::GreaterThanOrEqualToInteger(
-                    IntegerType::I64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0x5A), |_| {
-                NumericInstruction::GreaterThanOrEqualToInteger(
-                    IntegerType::I64,
-                    SignExtension::Unsigned,
-                )
-            }),
-        )),
-        alt((
-            map(match_byte(0x5B), |_| {
-                NumericInstruction::Equal(NumberType::F32)
-            }),
-            map(match_byte(0x5C), |_| {
-                NumericInstruction::NotEqual(NumberType::F32)
-            }),
-            map(match_byte(0x5D), |_| {
-                NumericInstruction::LessThanFloat(FloatType::F32)
-            }),
-            map(match_byte(0x5E), |_| {
-                NumericInstruction::GreaterThanFloat(FloatType::F32)
-            }),
-            map(match_byte(0x5F), |_| {
-                NumericInstruction::LessThanOrEqualToFloat(FloatType::F32)
-            }),
-            map(match_byte(0x60), |_| {
-                NumericInstruction::GreaterThanOrEqualToFloat(FloatType::F32)
-            }),
-        )),
-        alt((
-            map(match_byte(0x61), |_| {
-                NumericInstruction::Equal(NumberType::F64)
-            }),
-            map(match_byte(0x62), |_| {
-                NumericInstruction::NotEqual(NumberType::F64)
-            }),
-            map(match_byte(0x63), |_| {
-                NumericInstruction::LessThanFloat(FloatType::F64)
-            }),
-            map(match_byte(0x64), |_| {
-                NumericInstruction::GreaterThanFloat(FloatType::F64)
-            }),
-            map(match_byte(0x65), |_| {
-                NumericInstruction::LessThanOrEqualToFloat(FloatType::F64)
-            }),
-            map(match_byte(0x66), |_| {
-                NumericInstruction::GreaterThanOrEqualToFloat(FloatType::F64)
-            }),
-        )),
-        alt((
-            map(match_byte(0x67), |_| {
-                NumericInstruction::CountLeadingZeros(IntegerType::I32)
-            }),
-            map(match_byte(0x68), |_| {
-                NumericInstruction::CountTrailingZeros(IntegerType::I32)
-            }),
-            map(match_byte(0x69), |_| {
-                NumericInstruction::CountOnes(IntegerType::I32)
-            }),
-            map(match_byte(0x6A), |_| {
-                NumericInstruction::Add(NumberType::I32)
-            }),
-            map(match_byte(0x6B), |_| {
-                NumericInstruction::Subtract(NumberType::I32)
-            }),
-            map(match_byte(0x6C), |_| {
-                NumericInstruction::Multiply(NumberType::I32)
-            }),
-            map(match_byte(0x6D), |_| {
-                NumericInstruction::DivideInteger(IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0x6E), |_| {
-                NumericInstruction::DivideInteger(IntegerType::I32, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x6F), |_| {
-                NumericInstruction::Remainder(IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0x70), |_| {
-                NumericInstruction::Remainder(IntegerType::I32, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x71), |_| {
-                NumericInstruction::And(IntegerType::I32)
-            }),
-            map(match_byte(0x72), |_| {
-                NumericInstruction::Or(IntegerType::I32)
-            }),
-            map(match_byte(0x73), |_| {
-                NumericInstruction::Xor(IntegerType::I32)
-            }),
-            map(match_byte(0x74), |_| {
-                NumericInstruction::ShiftLeft(IntegerType::I32)
-            }),
-            map(match_byte(0x75), |_| {
-                NumericInstruction::ShiftRight(IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0x76), |_| {
-                NumericInstruction::ShiftRight(IntegerType::I32, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x77), |_| {
-                NumericInstruction::RotateLeft(IntegerType::I32)
-            }),
-            map(match_byte(0x78), |_| {
-                NumericInstruction::RotateRight(IntegerType::I32)
-            }),
-        )),
-        alt((
-            map(match_byte(0x79), |_| {
-                NumericInstruction::CountLeadingZeros(IntegerType::I64)
-            }),
-            map(match_byte(0x7A), |_| {
-                NumericInstruction::CountTrailingZeros(IntegerType::I64)
-            }),
-            map(match_byte(0x7B), |_| {
-                NumericInstruction::CountOnes(IntegerType::I64)
-            }),
-            map(match_byte(0x7C), |_| {
-                NumericInstruction::Add(NumberType::I64)
-            }),
-            map(match_byte(0x7D), |_| {
-                NumericInstruction::Subtract(NumberType::I64)
-            }),
-            map(match_byte(0x7E), |_| {
-                NumericInstruction::Multiply(NumberType::I64)
-            }),
-            map(match_byte(0x7F), |_| {
-                NumericInstruction::DivideInteger(IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0x80), |_| {
-                NumericInstruction::DivideInteger(IntegerType::I64, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x81), |_| {
-                NumericInstruction::Remainder(IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0x82), |_| {
-                NumericInstruction::Remainder(IntegerType::I64, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x83), |_| {
-                NumericInstruction::And(IntegerType::I64)
-            }),
-            map(match_byte(0x84), |_| {
-                NumericInstruction::Or(IntegerType::I64)
-            }),
-            map(match_byte(0x85), |_| {
-                NumericInstruction::Xor(IntegerType::I64)
-            }),
-            map(match_byte(0x86), |_| {
-                NumericInstruction::ShiftLeft(IntegerType::I64)
-            }),
-            map(match_byte(0x87), |_| {
-                NumericInstruction::ShiftRight(IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0x88), |_| {
-                NumericInstruction::ShiftRight(IntegerType::I64, SignExtension::Unsigned)
-            }),
-            map(match_byte(0x89), |_| {
-                NumericInstruction::RotateLeft(IntegerType::I64)
-            }),
-            map(match_byte(0x8A), |_| {
-                NumericInstruction::RotateRight(IntegerType::I64)
-            }),
-        )),
-        alt((
-            map(match_byte(0x8B), |_| {
-                NumericInstruction::AbsoluteValue(FloatType::F32)
-            }),
-            map(match_byte(0x8C), |_| {
-                NumericInstruction::Negate(FloatType::F32)
-            }),
-            map(match_byte(0x8D), |_| {
-                NumericInstruction::Ceiling(FloatType::F32)
-            }),
-            map(match_byte(0x8E), |_| {
-                NumericInstruction::Floor(FloatType::F32)
-            }),
-            map(match_byte(0x8F), |_| {
-                NumericInstruction::Truncate(FloatType::F32)
-            }),
-            map(match_byte(0x90), |_| {
-                NumericInstruction::Nearest(FloatType::F32)
-            }),
-            map(match_byte(0x91), |_| {
-                NumericInstruction::SquareRoot(FloatType::F32)
-            }),
-            map(match_byte(0x92), |_| {
-                NumericInstruction::Add(NumberType::F32)
-            }),
-            map(match_byte(0x93), |_| {
-                NumericInstruction::Subtract(NumberType::F32)
-            }),
-            map(match_byte(0x94), |_| {
-                NumericInstruction::Multiply(NumberType::F32)
-            }),
-            map(match_byte(0x95), |_| {
-                NumericInstruction::DivideFloat(FloatType::F32)
-            }),
-            map(match_byte(0x96), |_| {
-                NumericInstruction::Minimum(FloatType::F32)
-            }),
-            map(match_byte(0x97), |_| {
-                NumericInstruction::Maximum(FloatType::F32)
-            }),
-            map(match_byte(0x98), |_| {
-                NumericInstruction::CopySign(FloatType::F32)
-            }),
-        )),
-        alt((
-            map(match_byte(0x99), |_| {
-                NumericInstruction::AbsoluteValue(FloatType::F64)
-            }),
-            map(match_byte(0x9A), |_| {
-                NumericInstruction::Negate(FloatType::F64)
-            }),
-            map(match_byte(0x9B), |_| {
-                NumericInstruction::Ceiling(FloatType::F64)
-            }),
-            map(match_byte(0x9C), |_| {
-                NumericInstruction::Floor(FloatType::F64)
-            }),
-            map(match_byte(0x9D), |_| {
-                NumericInstruction::Truncate(FloatType::F64)
-            }),
-            map(match_byte(0x9E), |_| {
-                NumericInstruction::Nearest(FloatType::F64)
-            }),
-            map(match_byte(0x9F), |_| {
-                NumericInstruction::SquareRoot(FloatType::F64)
-            }),
-            map(match_byte(0xA0), |_| {
-                NumericInstruction::Add(NumberType::F64)
-            }),
-            map(match_byte(0xA1), |_| {
-                NumericInstruction::Subtract(NumberType::F64)
-            }),
-            map(match_byte(0xA2), |_| {
-                NumericInstruction::Multiply(NumberType::F64)
-            }),
-            map(match_byte(0xA3), |_| {
-                NumericInstruction::DivideFloat(FloatType::F64)
-            }),
-            map(match_byte(0xA4), |_| {
-                NumericInstruction::Minimum(FloatType::F64)
-            }),
-            map(match_byte(0xA5), |_| {
-                NumericInstruction::Maximum(FloatType::F64)
-            }),
-            map(match_byte(0xA6), |_| {
-                NumericInstruction::CopySign(FloatType::F64)
-            }),
-        )),
-        alt((
-            map(match_byte(0xA7), |_| NumericInstruction::Wrap),
-            map(match_byte(0xA8), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I32,
-                    FloatType::F32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0xA9), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I32,
-                    FloatType::F32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xAA), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I32,
-                    FloatType::F64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0xAB), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I32,
-                    FloatType::F64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xAC), |_| {
-                NumericInstruction::ExtendWithSignExtension(SignExtension::Signed)
-            }),
-            map(match_byte(0xAD), |_| {
-                NumericInstruction::ExtendWithSignExtension(SignExtension::Unsigned)
-            }),
-        )),
-        alt((
-            map(match_byte(0xAE), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I64,
-                    FloatType::F32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0xAF), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I64,
-                    FloatType::F32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xB0), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I64,
-                    FloatType::F64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(match_byte(0xB1), |_| {
-                NumericInstruction::ConvertAndTruncate(
-                    IntegerType::I64,
-                    FloatType::F64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xB2), |_| {
-                NumericInstruction::Convert(FloatType::F32, IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0xB3), |_| {
-                NumericInstruction::Convert(
-                    FloatType::F32,
-                    IntegerType::I32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xB4), |_| {
-                NumericInstruction::Convert(FloatType::F32, IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0xB5), |_| {
-                NumericInstruction::Convert(
-                    FloatType::F32,
-                    IntegerType::I64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xB6), |_| NumericInstruction::Demote),
-            map(match_byte(0xB7), |_| {
-                NumericInstruction::Convert(FloatType::F64, IntegerType::I32, SignExtension::Signed)
-            }),
-            map(match_byte(0xB8), |_| {
-                NumericInstruction::Convert(
-                    FloatType::F64,
-                    IntegerType::I32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xB9), |_| {
-                NumericInstruction::Convert(FloatType::F64, IntegerType::I64, SignExtension::Signed)
-            }),
-            map(match_byte(0xBA), |_| {
-                NumericInstruction::Convert(
-                    FloatType::F64,
-                    IntegerType::I64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(match_byte(0xBB), |_| NumericInstruction::Promote),
-        )),
-        alt((
-            map(match_byte(0xBC), |_| {
-                NumericInstruction::ReinterpretFloat(IntegerType::I32)
-            }),
-            map(match_byte(0xBD), |_| {
-                NumericInstruction::ReinterpretFloat(IntegerType::I64)
-            }),
-            map(match_byte(0xBE), |_| {
-                NumericInstruction::ReinterpretInteger(FloatType::F32)
-            }),
-            map(match_byte(0xBF), |_| {
-                NumericInstruction::ReinterpretInteger(FloatType::F64)
-            }),
+/// The read-modify-write instructions are grouped by operation,
+/// with 7 consecutive opcodes per operation.
+///
+/// See <https://webassembly.github.io/threads/core/binary/instructions.html#atomic-memory-instructions>
+pub fn parse_atomic_instruction(input: &[u8]) -> ParseResult<'_, AtomicInstruction> {
+    let start = input;
+    let (input, opcode) = preceded(match_byte(0xFE), parse_u32)(input)?;
+
+    match opcode {
+        0x00 => map(parse_memory_argument, AtomicInstruction::Notify)(input),
+        0x01 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Wait(IntegerType::I32, memarg)
+        })(input),
+        0x02 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Wait(IntegerType::I64, memarg)
+        })(input),
+        0x03 => map(match_byte(0x00), |_| AtomicInstruction::Fence)(input),
+        0x10 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load(IntegerType::I32, memarg)
+        })(input),
+        0x11 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load(IntegerType::I64, memarg)
+        })(input),
+        0x12 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load8(IntegerType::I32, memarg)
+        })(input),
+        0x13 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load16(IntegerType::I32, memarg)
+        })(input),
+        0x14 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load8(IntegerType::I64, memarg)
+        })(input),
+        0x15 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Load16(IntegerType::I64, memarg)
+        })(input),
+        0x16 => map(parse_memory_argument, AtomicInstruction::Load32)(input),
+        0x17 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store(IntegerType::I32, memarg)
+        })(input),
+        0x18 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store(IntegerType::I64, memarg)
+        })(input),
+        0x19 => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store8(IntegerType::I32, memarg)
+        })(input),
+        0x1A => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store16(IntegerType::I32, memarg)
+        })(input),
+        0x1B => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store8(IntegerType::I64, memarg)
+        })(input),
+        0x1C => map(parse_memory_argument, |memarg| {
+            AtomicInstruction::Store16(IntegerType::I64, memarg)
+        })(input),
+        0x1D => map(parse_memory_argument, AtomicInstruction::Store32)(input),
+        0x1E..=0x4E => {
+            let operation = ATOMIC_OPERATIONS[((opcode - 0x1E) / 7) as usize];
+
+            map(parse_memory_argument, move |memarg| {
+                match (opcode - 0x1E) % 7 {
+                    0 => AtomicInstruction::ReadModifyWrite(operation, IntegerType::I32, memarg),
+                    1 => AtomicInstruction::ReadModifyWrite(operation, IntegerType::I64, memarg),
+                    2 => AtomicInstruction::ReadModifyWrite8(operation, IntegerType::I32, memarg),
+                    3 => AtomicInstruction::ReadModifyWrite16(operation, IntegerType::I32, memarg),
+                    4 => AtomicInstruction::ReadModifyWrite8(operation, IntegerType::I64, memarg),
+                    5 => AtomicInstruction::ReadModifyWrite16(operation, IntegerType::I64, memarg),
+                    _ => AtomicInstruction::ReadModifyWrite32(operation, memarg),
+                }
+            })(input)
+        }
+        _ => Err(nom::Err::Error(InputError::new(start, Expected::Opcode))),
+    }
+}
+
+/// Parses a WebAssembly garbage collection instruction from the input.
+/// The 0xFB prefix is shared by the aggregate instructions,
+/// the reference test and cast instructions, and the branch on cast instructions,
+/// each followed by an opcode encoded as an unsigned 32-bit integer.
+///
+/// See <https://webassembly.github.io/gc/core/binary/instructions.html>
+pub fn parse_gc_instruction(input: &[u8]) -> ParseResult<'_, Instruction> {
+    let start = input;
+    let (input, opcode) = preceded(match_byte(0xFB), parse_u32)(input)?;
+
+    match opcode {
+        0 => map(parse_u32, |kind| {
+            AggregateInstruction::StructNew(kind).into()
+        })(input),
+        1 => map(parse_u32, |kind| {
+            AggregateInstruction::StructNewDefault(kind).into()
+        })(input),
+        2..=4 => map(tuple((parse_u32, parse_u32)), |(kind, field)| {
+            let extension = match opcode {
+                2 => None,
+                3 => Some(SignExtension::Signed),
+                _ => Some(SignExtension::Unsigned),
+            };
+
+            AggregateInstruction::StructGet(extension, kind, field).into()
+        })(input),
+        5 => map(tuple((parse_u32, parse_u32)), |(kind, field)| {
+            AggregateInstruction::StructSet(kind, field).into()
+        })(input),
+        6 => map(parse_u32, |kind| {
+            AggregateInstruction::ArrayNew(kind).into()
+        })(input),
+        7 => map(parse_u32, |kind| {
+            AggregateInstruction::ArrayNewDefault(kind).into()
+        })(input),
+        8 => map(tuple((parse_u32, parse_u32)), |(kind, size)| {
+            AggregateInstruction::ArrayNewFixed(kind, size).into()
+        })(input),
+        9 => map(tuple((parse_u32, parse_u32)), |(kind, data)| {
+            AggregateInstruction::ArrayNewData(kind, data).into()
+        })(input),
+        10 => map(tuple((parse_u32, parse_u32)), |(kind, element)| {
+            AggregateInstruction::ArrayNewElement(kind, element).into()
+        })(input),
+        11..=13 => map(parse_u32, |kind| {
+            let extension = match opcode {
+                11 => None,
+                12 => Some(SignExtension::Signed),
+                _ => Some(SignExtension::Unsigned),
+            };
+
+            AggregateInstruction::ArrayGet(extension, kind).into()
+        })(input),
+        14 => map(parse_u32, |kind| {
+            AggregateInstruction::ArraySet(kind).into()
+        })(input),
+        15 => Ok((input, AggregateInstruction::ArrayLength.into())),
+        16 => map(parse_u32, |kind| {
+            AggregateInstruction::ArrayFill(kind).into()
+        })(input),
+        17 => map(tuple((parse_u32, parse_u32)), |(destination, source)| {
+            AggregateInstruction::ArrayCopy(destination, source).into()
+        })(input),
+        18 => map(tuple((parse_u32, parse_u32)), |(kind, data)| {
+            AggregateInstruction::ArrayInitData(kind, data).into()
+        })(input),
+        19 => map(tuple((parse_u32, parse_u32)), |(kind, element)| {
+            AggregateInstruction::ArrayInitElement(kind, element).into()
+        })(input),
+        20 => map(parse_heap_type, |kind| {
+            ReferenceInstruction::Test(ReferenceType::NonNullable(kind)).into()
+        })(input),
+        21 => map(parse_heap_type, |kind| {
+            ReferenceInstruction::Test(ReferenceType::Nullable(kind)).into()
+        })(input),
+        22 => map(parse_heap_type, |kind| {
+            ReferenceInstruction::Cast(ReferenceType::NonNullable(kind)).into()
+        })(input),
+        23 => map(parse_heap_type, |kind| {
+            ReferenceInstruction::Cast(ReferenceType::Nullable(kind)).into()
+        })(input),
+        24 => map(parse_cast, |(index, from, to)| {
+            ControlInstruction::BranchOnCast(index, from, to).into()
+        })(input),
+        25 => map(parse_cast, |(index, from, to)| {
+            ControlInstruction::BranchOnCastFail(index, from, to).into()
+        })(input),
+        26 => Ok((input, AggregateInstruction::AnyConvertExternal.into())),
+        27 => Ok((input, AggregateInstruction::ExternalConvertAny.into())),
+        28 => Ok((input, AggregateInstruction::ReferenceI31.into())),
+        29 => Ok((
+            input,
+            AggregateInstruction::I31Get(SignExtension::Signed).into(),
         )),
-        alt((
-            map(match_byte(0xC0), |_| {
-                NumericInstruction::ExtendSigned8(IntegerType::I32)
-            }),
-            map(match_byte(0xC1), |_| {
-                NumericInstruction::ExtendSigned16(IntegerType::I32)
-            }),
-            map(match_byte(0xC2), |_| {
-                NumericInstruction::ExtendSigned8(IntegerType::I64)
-            }),
-            map(match_byte(0xC3), |_| {
-                NumericInstruction::ExtendSigned16(IntegerType::I64)
-            }),
-            map(match_byte(0xC4), |_| NumericInstruction::ExtendSigned32),
+        30 => Ok((
+            input,
+            AggregateInstruction::I31Get(SignExtension::Unsigned).into(),
         )),
-        alt((
-            map(tag([0xFC, 0u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I32,
-                    FloatType::F32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(tag([0xFC, 1u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I32,
-                    FloatType::F32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(tag([0xFC, 2u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I32,
-                    FloatType::F64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(tag([0xFC, 3u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I32,
-                    FloatType::F64,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(tag([0xFC, 4u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I64,
-                    FloatType::F32,
-                    SignExtension::Signed,
-                )
-            }),
-            map(tag([0xFC, 5u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I64,
-                    FloatType::F32,
-                    SignExtension::Unsigned,
-                )
-            }),
-            map(tag([0xFC, 6u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I64,
-                    FloatType::F64,
-                    SignExtension::Signed,
-                )
-            }),
-            map(tag([0xFC, 7u8]), |_| {
-                NumericInstruction::ConvertAndTruncateWithSaturation(
-                    IntegerType::I64,
-                    FloatType::F64,
-                    SignExtension::Unsigned,
-                )
-            }),
+        _ => Err(nom::Err::Error(InputError::new(start, Expected::Opcode))),
+    }
+}
+
+/// Parses the immediates of a WebAssembly branch on cast instruction from the input.
+/// The nullability of both reference types is encoded in a leading flags byte.
+///
+/// See <https://webassembly.github.io/gc/core/binary/instructions.html#control-instructions>
+fn parse_cast(input: &[u8]) -> ParseResult<'_, (LabelIndex, ReferenceType, ReferenceType)> {
+    let reference_type = |nullable: bool, kind: HeapType| {
+        if nullable {
+            ReferenceType::Nullable(kind)
+        } else {
+            ReferenceType::NonNullable(kind)
+        }
+    };
+
+    map(
+        tuple((
+            verify(parse_byte, |flags| flags & !0x03 == 0),
+            parse_u32,
+            parse_heap_type,
+            parse_heap_type,
         )),
-    ))(input)
+        move |(flags, index, from, to)| {
+            (
+                index,
+                reference_type(flags & 0x01 != 0, from),
+                reference_type(flags & 0x02 != 0, to),
+            )
+        },
+    )(input)
+}
+
+/// Parses a WebAssembly numeric instruction from the input.
+/// Numeric instructions other than constants have no immediate arguments,
+/// so they are looked up by their opcode.
+///
+/// See <https://webassembly.github.io/spec/core/binary/instructions.html#numeric-instructions>
+pub fn parse_numeric_instruction(input: &[u8]) -> ParseResult<'_, NumericInstruction> {
+    let (remaining, instruction) = match input {
+        [0x41, remaining @ ..] => {
+            return map(parse_s32, NumericInstruction::I32Constant)(remaining)
+        }
+        [0x42, remaining @ ..] => {
+            return map(parse_s64, NumericInstruction::I64Constant)(remaining)
+        }
+        [0x43, remaining @ ..] => return map(le_f32, NumericInstruction::F32Constant)(remaining),
+        [0x44, remaining @ ..] => return map(le_f64, NumericInstruction::F64Constant)(remaining),
+        [0xFC, opcode, remaining @ ..] => (remaining, parse_saturating_opcode(*opcode)),
+        [opcode, remaining @ ..] => (remaining, parse_numeric_opcode(*opcode)),
+        [] => (input, None),
+    };
+
+    match instruction {
+        Some(instruction) => Ok((remaining, instruction)),
+        None => Err(nom::Err::Error(InputError::new(input, Expected::Opcode))),
+    }
+}
+
+/// Maps the opcode of a numeric instruction without immediate arguments to the instruction.
+fn parse_numeric_opcode(opcode: u8) -> Option<NumericInstruction> {
+    let instruction = match opcode {
+        0x45 => NumericInstruction::EqualToZero(IntegerType::I32),
+        0x46 => NumericInstruction::Equal(NumberType::I32),
+        0x47 => NumericInstruction::NotEqual(NumberType::I32),
+        0x48 => NumericInstruction::LessThanInteger(IntegerType::I32, SignExtension::Signed),
+        0x49 => NumericInstruction::LessThanInteger(IntegerType::I32, SignExtension::Unsigned),
+        0x4A => NumericInstruction::GreaterThanInteger(IntegerType::I32, SignExtension::Signed),
+        0x4B => NumericInstruction::GreaterThanInteger(IntegerType::I32, SignExtension::Unsigned),
+        0x4C => {
+            NumericInstruction::LessThanOrEqualToInteger(IntegerType::I32, SignExtension::Signed)
+        }
+        0x4D => {
+            NumericInstruction::LessThanOrEqualToInteger(IntegerType::I32, SignExtension::Unsigned)
+        }
+        0x4E => {
+            NumericInstruction::GreaterThanOrEqualToInteger(IntegerType::I32, SignExtension::Signed)
+        }
+        0x4F => NumericInstruction::GreaterThanOrEqualToInteger(
+            IntegerType::I32,
+            SignExtension::Unsigned,
+        ),
+        0x50 => NumericInstruction::EqualToZero(IntegerType::I64),
+        0x51 => NumericInstruction::Equal(NumberType::I64),
+        0x52 => NumericInstruction::NotEqual(NumberType::I64),
+        0x53 => NumericInstruction::LessThanInteger(IntegerType::I64, SignExtension::Signed),
+        0x54 => NumericInstruction::LessThanInteger(IntegerType::I64, SignExtension::Unsigned),
+        0x55 => NumericInstruction::GreaterThanInteger(IntegerType::I64, SignExtension::Signed),
+        0x56 => NumericInstruction::GreaterThanInteger(IntegerType::I64, SignExtension::Unsigned),
+        0x57 => {
+            NumericInstruction::LessThanOrEqualToInteger(IntegerType::I64, SignExtension::Signed)
+        }
+        0x58 => {
+            NumericInstruction::LessThanOrEqualToInteger(IntegerType::I64, SignExtension::Unsigned)
+        }
+        0x59 => {
+            NumericInstruction::GreaterThanOrEqualToInteger(IntegerType::I64, SignExtension::Signed)
+        }
+        0x5A => NumericInstruction::GreaterThanOrEqualToInteger(
+            IntegerType::I64,
+            SignExtension::Unsigned,
+        ),
+        0x5B => NumericInstruction::Equal(NumberType::F32),
+        0x5C => NumericInstruction::NotEqual(NumberType::F32),
+        0x5D => NumericInstruction::LessThanFloat(FloatType::F32),
+        0x5E => NumericInstruction::GreaterThanFloat(FloatType::F32),
+        0x5F => NumericInstruction::LessThanOrEqualToFloat(FloatType::F32),
+        0x60 => NumericInstruction::GreaterThanOrEqualToFloat(FloatType::F32),
+        0x61 => NumericInstruction::Equal(NumberType::F64),
+        0x62 => NumericInstruction::NotEqual(NumberType::F64),
+        0x63 => NumericInstruction::LessThanFloat(FloatType::F64),
+        0x64 => NumericInstruction::GreaterThanFloat(FloatType::F64),
+        0x65 => NumericInstruction::LessThanOrEqualToFloat(FloatType::F64),
+        0x66 => NumericInstruction::GreaterThanOrEqualToFloat(FloatType::F64),
+        0x67 => NumericInstruction::CountLeadingZeros(IntegerType::I32),
+        0x68 => NumericInstruction::CountTrailingZeros(IntegerType::I32),
+        0x69 => NumericInstruction::CountOnes(IntegerType::I32),
+        0x6A => NumericInstruction::Add(NumberType::I32),
+        0x6B => NumericInstruction::Subtract(NumberType::I32),
+        0x6C => NumericInstruction::Multiply(NumberType::I32),
+        0x6D => NumericInstruction::DivideInteger(IntegerType::I32, SignExtension::Signed),
+        0x6E => NumericInstruction::DivideInteger(IntegerType::I32, SignExtension::Unsigned),
+        0x6F => NumericInstruction::Remainder(IntegerType::I32, SignExtension::Signed),
+        0x70 => NumericInstruction::Remainder(IntegerType::I32, SignExtension::Unsigned),
+        0x71 => NumericInstruction::And(IntegerType::I32),
+        0x72 => NumericInstruction::Or(IntegerType::I32),
+        0x73 => NumericInstruction::Xor(IntegerType::I32),
+        0x74 => NumericInstruction::ShiftLeft(IntegerType::I32),
+        0x75 => NumericInstruction::ShiftRight(IntegerType::I32, SignExtension::Signed),
+        0x76 => NumericInstruction::ShiftRight(IntegerType::I32, SignExtension::Unsigned),
+        0x77 => NumericInstruction::RotateLeft(IntegerType::I32),
+        0x78 => NumericInstruction::RotateRight(IntegerType::I32),
+        0x79 => NumericInstruction::CountLeadingZeros(IntegerType::I64),
+        0x7A => NumericInstruction::CountTrailingZeros(IntegerType::I64),
+        0x7B => NumericInstruction::CountOnes(IntegerType::I64),
+        0x7C => NumericInstruction::Add(NumberType::I64),
+        0x7D => NumericInstruction::Subtract(NumberType::I64),
+        0x7E => NumericInstruction::Multiply(NumberType::I64),
+        0x7F => NumericInstruction::DivideInteger(IntegerType::I64, SignExtension::Signed),
+        0x80 => NumericInstruction::DivideInteger(IntegerType::I64, SignExtension::Unsigned),
+        0x81 => NumericInstruction::Remainder(IntegerType::I64, SignExtension::Signed),
+        0x82 => NumericInstruction::Remainder(IntegerType::I64, SignExtension::Unsigned),
+        0x83 => NumericInstruction::And(IntegerType::I64),
+        0x84 => NumericInstruction::Or(IntegerType::I64),
+        0x85 => NumericInstruction::Xor(IntegerType::I64),
+        0x86 => NumericInstruction::ShiftLeft(IntegerType::I64),
+        0x87 => NumericInstruction::ShiftRight(IntegerType::I64, SignExtension::Signed),
+        0x88 => NumericInstruction::ShiftRight(IntegerType::I64, SignExtension::Unsigned),
+        0x89 => NumericInstruction::RotateLeft(IntegerType::I64),
+        0x8A => NumericInstruction::RotateRight(IntegerType::I64),
+        0x8B => NumericInstruction::AbsoluteValue(FloatType::F32),
+        0x8C => NumericInstruction::Negate(FloatType::F32),
+        0x8D => NumericInstruction::Ceiling(FloatType::F32),
+        0x8E => NumericInstruction::Floor(FloatType::F32),
+        0x8F => NumericInstruction::Truncate(FloatType::F32),
+        0x90 => NumericInstruction::Nearest(FloatType::F32),
+        0x91 => NumericInstruction::SquareRoot(FloatType::F32),
+        0x92 => NumericInstruction::Add(NumberType::F32),
+        0x93 => NumericInstruction::Subtract(NumberType::F32),
+        0x94 => NumericInstruction::Multiply(NumberType::F32),
+        0x95 => NumericInstruction::DivideFloat(FloatType::F32),
+        0x96 => NumericInstruction::Minimum(FloatType::F32),
+        0x97 => NumericInstruction::Maximum(FloatType::F32),
+        0x98 => NumericInstruction::CopySign(FloatType::F32),
+        0x99 => NumericInstruction::AbsoluteValue(FloatType::F64),
+        0x9A => NumericInstruction::Negate(FloatType::F64),
+        0x9B => NumericInstruction::Ceiling(FloatType::F64),
+        0x9C => NumericInstruction::Floor(FloatType::F64),
+        0x9D => NumericInstruction::Truncate(FloatType::F64),
+        0x9E => NumericInstruction::Nearest(FloatType::F64),
+        0x9F => NumericInstruction::SquareRoot(FloatType::F64),
+        0xA0 => NumericInstruction::Add(NumberType::F64),
+        0xA1 => NumericInstruction::Subtract(NumberType::F64),
+        0xA2 => NumericInstruction::Multiply(NumberType::F64),
+        0xA3 => NumericInstruction::DivideFloat(FloatType::F64),
+        0xA4 => NumericInstruction::Minimum(FloatType::F64),
+        0xA5 => NumericInstruction::Maximum(FloatType::F64),
+        0xA6 => NumericInstruction::CopySign(FloatType::F64),
+        0xA7 => NumericInstruction::Wrap,
+        0xA8 => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I32,
+            FloatType::F32,
+            SignExtension::Signed,
+        ),
+        0xA9 => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I32,
+            FloatType::F32,
+            SignExtension::Unsigned,
+        ),
+        0xAA => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I32,
+            FloatType::F64,
+            SignExtension::Signed,
+        ),
+        0xAB => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I32,
+            FloatType::F64,
+            SignExtension::Unsigned,
+        ),
+        0xAC => NumericInstruction::ExtendWithSignExtension(SignExtension::Signed),
+        0xAD => NumericInstruction::ExtendWithSignExtension(SignExtension::Unsigned),
+        0xAE => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I64,
+            FloatType::F32,
+            SignExtension::Signed,
+        ),
+        0xAF => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I64,
+            FloatType::F32,
+            SignExtension::Unsigned,
+        ),
+        0xB0 => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I64,
+            FloatType::F64,
+            SignExtension::Signed,
+        ),
+        0xB1 => NumericInstruction::ConvertAndTruncate(
+            IntegerType::I64,
+            FloatType::F64,
+            SignExtension::Unsigned,
+        ),
+        0xB2 => {
+            NumericInstruction::Convert(FloatType::F32, IntegerType::I32, SignExtension::Signed)
+        }
+        0xB3 => {
+            NumericInstruction::Convert(FloatType::F32, IntegerType::I32, SignExtension::Unsigned)
+        }
+        0xB4 => {
+            NumericInstruction::Convert(FloatType::F32, IntegerType::I64, SignExtension::Signed)
+        }
+        0xB5 => {
+            NumericInstruction::Convert(FloatType::F32, IntegerType::I64, SignExtension::Unsigned)
+        }
+        0xB6 => NumericInstruction::Demote,
+        0xB7 => {
+            NumericInstruction::Convert(FloatType::F64, IntegerType::I32, SignExtension::Signed)
+        }
+        0xB8 => {
+            NumericInstruction::Convert(FloatType::F64, IntegerType::I32, SignExtension::Unsigned)
+        }
+        0xB9 => {
+            NumericInstruction::Convert(FloatType::F64, IntegerType::I64, SignExtension::Signed)
+        }
+        0xBA => {
+            NumericInstruction::Convert(FloatType::F64, IntegerType::I64, SignExtension::Unsigned)
+        }
+        0xBB => NumericInstruction::Promote,
+        0xBC => NumericInstruction::ReinterpretFloat(IntegerType::I32),
+        0xBD => NumericInstruction::ReinterpretFloat(IntegerType::I64),
+        0xBE => NumericInstruction::ReinterpretInteger(FloatType::F32),
+        0xBF => NumericInstruction::ReinterpretInteger(FloatType::F64),
+        0xC0 => NumericInstruction::ExtendSigned8(IntegerType::I32),
+        0xC1 => NumericInstruction::ExtendSigned16(IntegerType::I32),
+        0xC2 => NumericInstruction::ExtendSigned8(IntegerType::I64),
+        0xC3 => NumericInstruction::ExtendSigned16(IntegerType::I64),
+        0xC4 => NumericInstruction::ExtendSigned32,
+        _ => return None,
+    };
+
+    Some(instruction)
+}
+
+/// Maps the opcode following the 0xFC prefix of a saturating truncation instruction to the instruction.
+fn parse_saturating_opcode(opcode: u8) -> Option<NumericInstruction> {
+    let instruction = match opcode {
+        0 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I32,
+            FloatType::F32,
+            SignExtension::Signed,
+        ),
+        1 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I32,
+            FloatType::F32,
+            SignExtension::Unsigned,
+        ),
+        2 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I32,
+            FloatType::F64,
+            SignExtension::Signed,
+        ),
+        3 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I32,
+            FloatType::F64,
+            SignExtension::Unsigned,
+        ),
+        4 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I64,
+            FloatType::F32,
+            SignExtension::Signed,
+        ),
+        5 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I64,
+            FloatType::F32,
+            SignExtension::Unsigned,
+        ),
+        6 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I64,
+            FloatType::F64,
+            SignExtension::Signed,
+        ),
+        7 => NumericInstruction::ConvertAndTruncateWithSaturation(
+            IntegerType::I64,
+            FloatType::F64,
+            SignExtension::Unsigned,
+        ),
+        _ => return None,
+    };
+
+    Some(instruction)
+}
+
+/// Parses a WebAssembly vector instruction from the input.
+/// Vector instructions are prefixed with the byte 0xFD, followed by an opcode encoded as an
+/// unsigned 32-bit integer.
+///
+/// See <https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions>
+pub fn parse_vector_instruction(input: &[u8]) -> ParseResult<'_, VectorInstruction> {
+    let start = input;
+    let (input, opcode) = preceded(match_byte(0xFD), parse_u32)(input)?;
+
+    match opcode {
+        0x00 => map(parse_memory_argument, VectorInstruction::Load)(input),
+        0x01 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load8x8(SignExtension::Signed, memarg)
+        })(input),
+        0x02 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load8x8(SignExtension::Unsigned, memarg)
+        })(input),
+        0x03 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load16x4(SignExtension::Signed, memarg)
+        })(input),
+        0x04 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load16x4(SignExtension::Unsigned, memarg)
+        })(input),
+        0x05 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load32x2(SignExtension::Signed, memarg)
+        })(input),
+        0x06 => map(parse_memory_argument, |memarg| {
+            VectorInstruction::Load32x2(SignExtension::Unsigned, memarg)
+        })(input),
+        0x07 => map(parse_memory_argument, VectorInstruction::Load8Splat)(input),
+        0x08 => map(parse_memory_argument, VectorInstruction::Load16Splat)(input),
+        0x09 => map(parse_memory_argument, VectorInstruction::Load32Splat)(input),
+        0x0A => map(parse_memory_argument, VectorInstruction::Load64Splat)(input),
+        0x0B => map(parse_memory_argument, VectorInstruction::Store)(input),
+        0x0C => map(le_i128, VectorInstruction::Constant)(input),
+        0x0D => map(parse_lanes, VectorInstruction::Shuffle)(input),
+        0x15 => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Signed), lane)
+        })(input),
+        0x16 => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Unsigned), lane)
+        })(input),
+        0x17 => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::I8x16, lane)
+        })(input),
+        0x18 => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I16x8, Some(SignExtension::Signed), lane)
+        })(input),
+        0x19 => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I16x8, Some(SignExtension::Unsigned), lane)
+        })(input),
+        0x1A => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::I16x8, lane)
+        })(input),
+        0x1B => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I32x4, None, lane)
+        })(input),
+        0x1C => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::I32x4, lane)
+        })(input),
+        0x1D => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::I64x2, None, lane)
+        })(input),
+        0x1E => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::I64x2, lane)
+        })(input),
+        0x1F => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::F32x4, None, lane)
+        })(input),
+        0x20 => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::F32x4, lane)
+        })(input),
+        0x21 => map(parse_lane_index, |lane| {
+            VectorInstruction::ExtractLane(VectorShape::F64x2, None, lane)
+        })(input),
+        0x22 => map(parse_lane_index, |lane| {
+            VectorInstruction::ReplaceLane(VectorShape::F64x2, lane)
+        })(input),
+        0x54 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Load8Lane(memarg, lane),
+        )(input),
+        0x55 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Load16Lane(memarg, lane),
+        )(input),
+        0x56 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Load32Lane(memarg, lane),
+        )(input),
+        0x57 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Load64Lane(memarg, lane),
+        )(input),
+        0x58 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Store8Lane(memarg, lane),
+        )(input),
+        0x59 => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Store16Lane(memarg, lane),
+        )(input),
+        0x5A => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Store32Lane(memarg, lane),
+        )(input),
+        0x5B => map(
+            tuple((parse_memory_argument, parse_lane_index)),
+            |(memarg, lane)| VectorInstruction::Store64Lane(memarg, lane),
+        )(input),
+        0x5C => map(parse_memory_argument, VectorInstruction::Load32Zero)(input),
+        0x5D => map(parse_memory_argument, VectorInstruction::Load64Zero)(input),
+        _ => match parse_vector_opcode(opcode) {
+            Some(instruction) => Ok((input, instruction)),
+            None => Err(nom::Err::Error(InputError::new(start, Expected::Opcode))),
+        },
+    }
+}
+
+/// Maps the opcode of a vector instruction without immediate arguments to the instruction.
+fn parse_vector_opcode(opcode: u32) -> Option<VectorInstruction> {
+    let instruction = match opcode {
+        0x0E => VectorInstruction::Swizzle,
+        0x0F => VectorInstruction::Splat(VectorShape::I8x16),
+        0x10 => VectorInstruction::Splat(VectorShape::I16x8),
+        0x11 => VectorInstruction::Splat(VectorShape::I32x4),
+        0x12 => VectorInstruction::Splat(VectorShape::I64x2),
+        0x13 => VectorInstruction::Splat(VectorShape::F32x4),
+        0x14 => VectorInstruction::Splat(VectorShape::F64x2),
+        0x23 => VectorInstruction::Equal(VectorShape::I8x16),
+        0x24 => VectorInstruction::NotEqual(VectorShape::I8x16),
+        0x25 => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I8x16, SignExtension::Signed)
+        }
+        0x26 => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned)
+        }
+        0x27 => {
+            VectorInstruction::GreaterThanInteger(IntegerVectorShape::I8x16, SignExtension::Signed)
+        }
+        0x28 => VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ),
+        0x29 => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Signed,
+        ),
+        0x2A => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ),
+        0x2B => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Signed,
+        ),
+        0x2C => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I8x16,
+            SignExtension::Unsigned,
+        ),
+        0x2D => VectorInstruction::Equal(VectorShape::I16x8),
+        0x2E => VectorInstruction::NotEqual(VectorShape::I16x8),
+        0x2F => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I16x8, SignExtension::Signed)
+        }
+        0x30 => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned)
+        }
+        0x31 => {
+            VectorInstruction::GreaterThanInteger(IntegerVectorShape::I16x8, SignExtension::Signed)
+        }
+        0x32 => VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ),
+        0x33 => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Signed,
+        ),
+        0x34 => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ),
+        0x35 => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Signed,
+        ),
+        0x36 => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I16x8,
+            SignExtension::Unsigned,
+        ),
+        0x37 => VectorInstruction::Equal(VectorShape::I32x4),
+        0x38 => VectorInstruction::NotEqual(VectorShape::I32x4),
+        0x39 => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I32x4, SignExtension::Signed)
+        }
+        0x3A => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned)
+        }
+        0x3B => {
+            VectorInstruction::GreaterThanInteger(IntegerVectorShape::I32x4, SignExtension::Signed)
+        }
+        0x3C => VectorInstruction::GreaterThanInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ),
+        0x3D => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Signed,
+        ),
+        0x3E => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ),
+        0x3F => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Signed,
+        ),
+        0x40 => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I32x4,
+            SignExtension::Unsigned,
+        ),
+        0x41 => VectorInstruction::Equal(VectorShape::F32x4),
+        0x42 => VectorInstruction::NotEqual(VectorShape::F32x4),
+        0x43 => VectorInstruction::LessThanFloat(FloatVectorShape::F32x4),
+        0x44 => VectorInstruction::GreaterThanFloat(FloatVectorShape::F32x4),
+        0x45 => VectorInstruction::LessThanOrEqualToFloat(FloatVectorShape::F32x4),
+        0x46 => VectorInstruction::GreaterThanOrEqualToFloat(FloatVectorShape::F32x4),
+        0x47 => VectorInstruction::Equal(VectorShape::F64x2),
+        0x48 => VectorInstruction::NotEqual(VectorShape::F64x2),
+        0x49 => VectorInstruction::LessThanFloat(FloatVectorShape::F64x2),
+        0x4A => VectorInstruction::GreaterThanFloat(FloatVectorShape::F64x2),
+        0x4B => VectorInstruction::LessThanOrEqualToFloat(FloatVectorShape::F64x2),
+        0x4C => VectorInstruction::GreaterThanOrEqualToFloat(FloatVectorShape::F64x2),
+        0x4D => VectorInstruction::Not,
+        0x4E => VectorInstruction::And,
+        0x4F => VectorInstruction::AndNot,
+        0x50 => VectorInstruction::Or,
+        0x51 => VectorInstruction::Xor,
+        0x52 => VectorInstruction::BitSelect,
+        0x53 => VectorInstruction::AnyTrue,
+        0x5E => VectorInstruction::Demote,
+        0x5F => VectorInstruction::Promote,
+        0x60 => VectorInstruction::AbsoluteValue(VectorShape::I8x16),
+        0x61 => VectorInstruction::Negate(VectorShape::I8x16),
+        0x62 => VectorInstruction::CountOnes,
+        0x63 => VectorInstruction::AllTrue(IntegerVectorShape::I8x16),
+        0x64 => VectorInstruction::Bitmask(IntegerVectorShape::I8x16),
+        0x65 => VectorInstruction::Narrow(IntegerVectorShape::I8x16, SignExtension::Signed),
+        0x66 => VectorInstruction::Narrow(IntegerVectorShape::I8x16, SignExtension::Unsigned),
+        0x67 => VectorInstruction::Ceiling(FloatVectorShape::F32x4),
+        0x68 => VectorInstruction::Floor(FloatVectorShape::F32x4),
+        0x69 => VectorInstruction::Truncate(FloatVectorShape::F32x4),
+        0x6A => VectorInstruction::Nearest(FloatVectorShape::F32x4),
+        0x6B => VectorInstruction::ShiftLeft(IntegerVectorShape::I8x16),
+        0x6C => VectorInstruction::ShiftRight(IntegerVectorShape::I8x16, SignExtension::Signed),
+        0x6D => VectorInstruction::ShiftRight(IntegerVectorShape::I8x16, SignExtension::Unsigned),
+        0x6E => VectorInstruction::Add(VectorShape::I8x16),
+        0x6F => VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Signed),
+        0x70 => VectorInstruction::AddSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned),
+        0x71 => VectorInstruction::Subtract(VectorShape::I8x16),
+        0x72 => {
+            VectorInstruction::SubtractSaturate(IntegerVectorShape::I8x16, SignExtension::Signed)
+        }
+        0x73 => {
+            VectorInstruction::SubtractSaturate(IntegerVectorShape::I8x16, SignExtension::Unsigned)
+        }
+        0x74 => VectorInstruction::Ceiling(FloatVectorShape::F64x2),
+        0x75 => VectorInstruction::Floor(FloatVectorShape::F64x2),
+        0x76 => VectorInstruction::MinimumInteger(IntegerVectorShape::I8x16, SignExtension::Signed),
+        0x77 => {
+            VectorInstruction::MinimumInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned)
+        }
+        0x78 => VectorInstruction::MaximumInteger(IntegerVectorShape::I8x16, SignExtension::Signed),
+        0x79 => {
+            VectorInstruction::MaximumInteger(IntegerVectorShape::I8x16, SignExtension::Unsigned)
+        }
+        0x7A => VectorInstruction::Truncate(FloatVectorShape::F64x2),
+        0x7B => VectorInstruction::AverageRounded(IntegerVectorShape::I8x16),
+        0x7C => {
+            VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I16x8, SignExtension::Signed)
+        }
+        0x7D => {
+            VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I16x8, SignExtension::Unsigned)
+        }
+        0x7E => {
+            VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I32x4, SignExtension::Signed)
+        }
+        0x7F => {
+            VectorInstruction::ExtendAddPairwise(IntegerVectorShape::I32x4, SignExtension::Unsigned)
+        }
+        0x80 => VectorInstruction::AbsoluteValue(VectorShape::I16x8),
+        0x81 => VectorInstruction::Negate(VectorShape::I16x8),
+        0x82 => VectorInstruction::Q15MultiplyRoundSaturate,
+        0x83 => VectorInstruction::AllTrue(IntegerVectorShape::I16x8),
+        0x84 => VectorInstruction::Bitmask(IntegerVectorShape::I16x8),
+        0x85 => VectorInstruction::Narrow(IntegerVectorShape::I16x8, SignExtension::Signed),
+        0x86 => VectorInstruction::Narrow(IntegerVectorShape::I16x8, SignExtension::Unsigned),
+        0x87 => VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0x88 => VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0x89 => VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0x8A => VectorInstruction::Extend(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0x8B => VectorInstruction::ShiftLeft(IntegerVectorShape::I16x8),
+        0x8C => VectorInstruction::ShiftRight(IntegerVectorShape::I16x8, SignExtension::Signed),
+        0x8D => VectorInstruction::ShiftRight(IntegerVectorShape::I16x8, SignExtension::Unsigned),
+        0x8E => VectorInstruction::Add(VectorShape::I16x8),
+        0x8F => VectorInstruction::AddSaturate(IntegerVectorShape::I16x8, SignExtension::Signed),
+        0x90 => VectorInstruction::AddSaturate(IntegerVectorShape::I16x8, SignExtension::Unsigned),
+        0x91 => VectorInstruction::Subtract(VectorShape::I16x8),
+        0x92 => {
+            VectorInstruction::SubtractSaturate(IntegerVectorShape::I16x8, SignExtension::Signed)
+        }
+        0x93 => {
+            VectorInstruction::SubtractSaturate(IntegerVectorShape::I16x8, SignExtension::Unsigned)
+        }
+        0x94 => VectorInstruction::Nearest(FloatVectorShape::F64x2),
+        0x95 => VectorInstruction::Multiply(VectorShape::I16x8),
+        0x96 => VectorInstruction::MinimumInteger(IntegerVectorShape::I16x8, SignExtension::Signed),
+        0x97 => {
+            VectorInstruction::MinimumInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned)
+        }
+        0x98 => VectorInstruction::MaximumInteger(IntegerVectorShape::I16x8, SignExtension::Signed),
+        0x99 => {
+            VectorInstruction::MaximumInteger(IntegerVectorShape::I16x8, SignExtension::Unsigned)
+        }
+        0x9B => VectorInstruction::AverageRounded(IntegerVectorShape::I16x8),
+        0x9C => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0x9D => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0x9E => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0x9F => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I16x8,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0xA0 => VectorInstruction::AbsoluteValue(VectorShape::I32x4),
+        0xA1 => VectorInstruction::Negate(VectorShape::I32x4),
+        0xA3 => VectorInstruction::AllTrue(IntegerVectorShape::I32x4),
+        0xA4 => VectorInstruction::Bitmask(IntegerVectorShape::I32x4),
+        0xA7 => VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0xA8 => VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0xA9 => VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0xAA => VectorInstruction::Extend(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0xAB => VectorInstruction::ShiftLeft(IntegerVectorShape::I32x4),
+        0xAC => VectorInstruction::ShiftRight(IntegerVectorShape::I32x4, SignExtension::Signed),
+        0xAD => VectorInstruction::ShiftRight(IntegerVectorShape::I32x4, SignExtension::Unsigned),
+        0xAE => VectorInstruction::Add(VectorShape::I32x4),
+        0xB1 => VectorInstruction::Subtract(VectorShape::I32x4),
+        0xB5 => VectorInstruction::Multiply(VectorShape::I32x4),
+        0xB6 => VectorInstruction::MinimumInteger(IntegerVectorShape::I32x4, SignExtension::Signed),
+        0xB7 => {
+            VectorInstruction::MinimumInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned)
+        }
+        0xB8 => VectorInstruction::MaximumInteger(IntegerVectorShape::I32x4, SignExtension::Signed),
+        0xB9 => {
+            VectorInstruction::MaximumInteger(IntegerVectorShape::I32x4, SignExtension::Unsigned)
+        }
+        0xBA => VectorInstruction::DotProduct,
+        0xBC => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0xBD => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0xBE => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0xBF => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I32x4,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0xC0 => VectorInstruction::AbsoluteValue(VectorShape::I64x2),
+        0xC1 => VectorInstruction::Negate(VectorShape::I64x2),
+        0xC3 => VectorInstruction::AllTrue(IntegerVectorShape::I64x2),
+        0xC4 => VectorInstruction::Bitmask(IntegerVectorShape::I64x2),
+        0xC7 => VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0xC8 => VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0xC9 => VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0xCA => VectorInstruction::Extend(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0xCB => VectorInstruction::ShiftLeft(IntegerVectorShape::I64x2),
+        0xCC => VectorInstruction::ShiftRight(IntegerVectorShape::I64x2, SignExtension::Signed),
+        0xCD => VectorInstruction::ShiftRight(IntegerVectorShape::I64x2, SignExtension::Unsigned),
+        0xCE => VectorInstruction::Add(VectorShape::I64x2),
+        0xD1 => VectorInstruction::Subtract(VectorShape::I64x2),
+        0xD5 => VectorInstruction::Multiply(VectorShape::I64x2),
+        0xD6 => VectorInstruction::Equal(VectorShape::I64x2),
+        0xD7 => VectorInstruction::NotEqual(VectorShape::I64x2),
+        0xD8 => {
+            VectorInstruction::LessThanInteger(IntegerVectorShape::I64x2, SignExtension::Signed)
+        }
+        0xD9 => {
+            VectorInstruction::GreaterThanInteger(IntegerVectorShape::I64x2, SignExtension::Signed)
+        }
+        0xDA => VectorInstruction::LessThanOrEqualToInteger(
+            IntegerVectorShape::I64x2,
+            SignExtension::Signed,
+        ),
+        0xDB => VectorInstruction::GreaterThanOrEqualToInteger(
+            IntegerVectorShape::I64x2,
+            SignExtension::Signed,
+        ),
+        0xDC => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Signed,
+        ),
+        0xDD => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Signed,
+        ),
+        0xDE => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::Low,
+            SignExtension::Unsigned,
+        ),
+        0xDF => VectorInstruction::ExtendMultiply(
+            IntegerVectorShape::I64x2,
+            VectorHalf::High,
+            SignExtension::Unsigned,
+        ),
+        0xE0 => VectorInstruction::AbsoluteValue(VectorShape::F32x4),
+        0xE1 => VectorInstruction::Negate(VectorShape::F32x4),
+        0xE3 => VectorInstruction::SquareRoot(FloatVectorShape::F32x4),
+        0xE4 => VectorInstruction::Add(VectorShape::F32x4),
+        0xE5 => VectorInstruction::Subtract(VectorShape::F32x4),
+        0xE6 => VectorInstruction::Multiply(VectorShape::F32x4),
+        0xE7 => VectorInstruction::DivideFloat(FloatVectorShape::F32x4),
+        0xE8 => VectorInstruction::MinimumFloat(FloatVectorShape::F32x4),
+        0xE9 => VectorInstruction::MaximumFloat(FloatVectorShape::F32x4),
+        0xEA => VectorInstruction::PseudoMinimum(FloatVectorShape::F32x4),
+        0xEB => VectorInstruction::PseudoMaximum(FloatVectorShape::F32x4),
+        0xEC => VectorInstruction::AbsoluteValue(VectorShape::F64x2),
+        0xED => VectorInstruction::Negate(VectorShape::F64x2),
+        0xEF => VectorInstruction::SquareRoot(FloatVectorShape::F64x2),
+        0xF0 => VectorInstruction::Add(VectorShape::F64x2),
+        0xF1 => VectorInstruction::Subtract(VectorShape::F64x2),
+        0xF2 => VectorInstruction::Multiply(VectorShape::F64x2),
+        0xF3 => VectorInstruction::DivideFloat(FloatVectorShape::F64x2),
+        0xF4 => VectorInstruction::MinimumFloat(FloatVectorShape::F64x2),
+        0xF5 => VectorInstruction::MaximumFloat(FloatVectorShape::F64x2),
+        0xF6 => VectorInstruction::PseudoMinimum(FloatVectorShape::F64x2),
+        0xF7 => VectorInstruction::PseudoMaximum(FloatVectorShape::F64x2),
+        0xF8 => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Signed,
+        ),
+        0xF9 => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Unsigned,
+        ),
+        0xFA => VectorInstruction::Convert(FloatVectorShape::F32x4, SignExtension::Signed),
+        0xFB => VectorInstruction::Convert(FloatVectorShape::F32x4, SignExtension::Unsigned),
+        0xFC => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Signed,
+        ),
+        0xFD => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Unsigned,
+        ),
+        0xFE => VectorInstruction::Convert(FloatVectorShape::F64x2, SignExtension::Signed),
+        0xFF => VectorInstruction::Convert(FloatVectorShape::F64x2, SignExtension::Unsigned),
+        _ => return None,
+    };
+
+    Some(instruction)
+}
+
+/// Parses a WebAssembly vector lane index from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions>
+pub fn parse_lane_index(input: &[u8]) -> ParseResult<'_, LaneIndex> {
+    parse_byte(input)
+}
+
+/// Parses the 16 lane indices of a vector shuffle instruction from the input.
+fn parse_lanes(input: &[u8]) -> ParseResult<'_, [LaneIndex; 16]> {
+    map(take(16usize), |bytes: &[u8]| {
+        let mut lanes = [0; 16];
+        lanes.copy_from_slice(bytes);
+        lanes
+    })(input)
 }
 
 #[cfg(test)]
@@ -943,6 +1560,233 @@ mod tests {
         assert_eq!(remaining, &[extra]);
     }
 
+    #[test]
+    fn parse_vector_instructions() {
+        let input = vec![
+            0xFD,
+            0x0C,
+            0x2A,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0x80, // v128.const
+            0xFD,
+            0x15,
+            0x03, // i8x16.extract_lane_s 3
+            0xFD,
+            0xAE,
+            0x01, // i32x4.add
+            0xFD,
+            0x5B,
+            0x03,
+            0x08,
+            0x01, // v128.store64_lane align=3 offset=8 1
+            EXPRESSION_END,
+        ];
+
+        let (remaining, actual) = parse_expression(input.as_slice()).unwrap();
+        let expected = Expression::new(vec![
+            VectorInstruction::Constant(i128::MIN | 42).into(),
+            VectorInstruction::ExtractLane(VectorShape::I8x16, Some(SignExtension::Signed), 3)
+                .into(),
+            VectorInstruction::Add(VectorShape::I32x4).into(),
+            VectorInstruction::Store64Lane(MemoryArgument::new(3, 8), 1).into(),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn parse_invalid_vector_instruction() {
+        let input = vec![0xFD, 0x9A, 0x01, EXPRESSION_END];
+        let result = parse_expression(input.as_slice());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_atomic_instructions() {
+        let input = vec![
+            0xFE,
+            0x03,
+            0x00, // atomic.fence
+            0xFE,
+            0x12,
+            0x00,
+            0x04, // i32.atomic.load8_u offset=4
+            0xFE,
+            0x4E,
+            0x03,
+            0x00, // i64.atomic.rmw32.cmpxchg_u
+            EXPRESSION_END,
+        ];
+
+        let (remaining, actual) = parse_expression(input.as_slice()).unwrap();
+        let expected = Expression::new(vec![
+            AtomicInstruction::Fence.into(),
+            AtomicInstruction::Load8(IntegerType::I32, MemoryArgument::new(0, 4)).into(),
+            AtomicInstruction::ReadModifyWrite32(
+                AtomicOperation::CompareExchange,
+                MemoryArgument::new(3, 0),
+            )
+            .into(),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn parse_multiple_memory_instructions() {
+        let input = vec![
+            0x28,
+            0x42,
+            0x01,
+            0x04, // i32.load 1 offset=4 align=4
+            0x36,
+            0x02,
+            0x08, // i32.store offset=8 align=4
+            0x3F,
+            0x01, // memory.size 1
+            0xFC,
+            0x08,
+            0x00,
+            0x01, // memory.init 1 0
+            0xFC,
+            0x0A,
+            0x00,
+            0x01, // memory.copy 0 1
+            0xFC,
+            0x0B,
+            0x01, // memory.fill 1
+            EXPRESSION_END,
+        ];
+
+        let (remaining, actual) = parse_expression(input.as_slice()).unwrap();
+        let expected = Expression::new(vec![
+            MemoryInstruction::Load(NumberType::I32, MemoryArgument::with_memory(1, 2, 4)).into(),
+            MemoryInstruction::Store(NumberType::I32, MemoryArgument::new(2, 8)).into(),
+            MemoryInstruction::Size(1).into(),
+            MemoryInstruction::Init(0, 1).into(),
+            MemoryInstruction::Copy(0, 1).into(),
+            MemoryInstruction::Fill(1).into(),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn parse_try_table() {
+        let input = vec![
+            0x1F,
+            0x40,
+            0x04, // try_table with 4 catch clauses
+            0x00,
+            0x00,
+            0x00, // catch 0 0
+            0x01,
+            0x01,
+            0x00, // catch_ref 1 0
+            0x02,
+            0x01, // catch_all 1
+            0x03,
+            0x00, // catch_all_ref 0
+            0x08,
+            0x00, // throw 0
+            EXPRESSION_END,
+            0x0A, // throw_ref
+            EXPRESSION_END,
+        ];
+
+        let (remaining, actual) = parse_expression(input.as_slice()).unwrap();
+        let expected = Expression::new(vec![
+            ControlInstruction::TryTable(
+                BlockType::None,
+                vec![
+                    CatchClause::Catch(0, 0),
+                    CatchClause::CatchReference(1, 0),
+                    CatchClause::CatchAll(1),
+                    CatchClause::CatchAllReference(0),
+                ],
+                Expression::new(vec![ControlInstruction::Throw(0).into()]),
+            )
+            .into(),
+            ControlInstruction::ThrowRef.into(),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn parse_gc_instructions() {
+        let input = vec![
+            0xFB,
+            0x03,
+            0x00,
+            0x01, // struct.get_s 0 1
+            0xFB,
+            0x08,
+            0x01,
+            0x02, // array.new_fixed 1 2
+            0xFB,
+            0x14,
+            0x6C, // ref.test (ref i31)
+            0xFB,
+            0x17,
+            0x00, // ref.cast (ref null 0)
+            0xFB,
+            0x18,
+            0x01,
+            0x00,
+            0x6E,
+            0x6C, // br_on_cast 0 anyref (ref i31)
+            0xFB,
+            0x1E, // i31.get_u
+            0xD3, // ref.eq
+            EXPRESSION_END,
+        ];
+
+        let (remaining, actual) = parse_expression(input.as_slice()).unwrap();
+        let expected = Expression::new(vec![
+            AggregateInstruction::StructGet(Some(SignExtension::Signed), 0, 1).into(),
+            AggregateInstruction::ArrayNewFixed(1, 2).into(),
+            ReferenceInstruction::Test(ReferenceType::NonNullable(HeapType::I31)).into(),
+            ReferenceInstruction::Cast(ReferenceType::Nullable(HeapType::Index(0))).into(),
+            ControlInstruction::BranchOnCast(
+                0,
+                ReferenceType::Nullable(HeapType::Any),
+                ReferenceType::NonNullable(HeapType::I31),
+            )
+            .into(),
+            AggregateInstruction::I31Get(SignExtension::Unsigned).into(),
+            ReferenceInstruction::Equal.into(),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn parse_unknown_gc_instruction() {
+        let input = vec![0xFB, 0x1F, EXPRESSION_END];
+
+        assert!(parse_expression(input.as_slice()).is_err());
+    }
+
     #[test]
     fn parse_invalid_expression() {
         let input = vec![3];
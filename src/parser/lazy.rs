@@ -0,0 +1,22 @@
+use crate::parser::errors::{consume_all, BinaryError};
+use crate::parser::instructions::parse_expression;
+use crate::{Expression, FunctionIndex, ModuleSection};
+use std::ops::Range;
+
+/// Decodes the expression of a function body that was parsed lazily from a range of the shared code bytes,
+/// which start at the given offset of the module.
+/// Errors are located within the module and the function with the given index.
+pub(crate) fn decode_body(
+    code: &[u8],
+    offset: usize,
+    range: Range<usize>,
+    function: FunctionIndex,
+) -> Result<Expression, BinaryError> {
+    consume_all(parse_expression)(&code[range])
+        .map(|(_, expression)| expression)
+        .map_err(|error| {
+            BinaryError::new(code, error)
+                .offset_by(offset)
+                .within(Some(ModuleSection::Code), Some(function))
+        })
+}
@@ -2,22 +2,42 @@
 
 mod errors;
 mod instructions;
+mod lazy;
 mod module;
+mod names;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod sections;
+mod stream;
+#[cfg(feature = "text")]
+mod text;
 mod types;
 mod values;
 
+use crate::model::{Body, EncodedBody};
+use crate::parser::errors::{consume_all, expect, InputError, ParseResult};
+use crate::parser::module::parse_import;
+use crate::parser::names::parse_name_section;
 use crate::parser::sections::{
-    parse_code_section, parse_custom_section, parse_data_count_section, parse_data_section,
-    parse_element_section, parse_export_section, parse_function_section, parse_global_section,
-    parse_import_section, parse_memory_section, parse_start_section, parse_table_section,
+    parse_borrowed_custom_section, parse_borrowed_data_section, parse_borrowed_export_section,
+    parse_borrowed_import_section, parse_code_section, parse_custom_section,
+    parse_data_count_section, parse_data_section, parse_element_section, parse_export_section,
+    parse_function_section, parse_global_section, parse_import_section, parse_lazy_code_section,
+    parse_memory_section, parse_start_section, parse_table_section, parse_tag_section,
     parse_type_section,
 };
-use crate::{Expression, Function, Module, ModuleSection, ResultType, TypeIndex};
-pub use errors::ParseError;
+use crate::parser::values::{parse_u32, parse_vector};
+use crate::{
+    BorrowedModule, Custom, Expression, Function, FunctionIndex, ImportDescription, Module,
+    ModuleBuilder, ModuleSection, NameSection, ResultType, TypeIndex,
+};
+pub use errors::{BinaryError, Expected, ParseError};
+pub(crate) use lazy::decode_body;
 use nom::bytes::complete::tag;
-use nom::combinator::all_consuming;
+use nom::number::complete::u8 as parse_byte;
 use nom::sequence::tuple;
+use std::sync::Arc;
+pub use stream::{parse_stream, Event, Events, StreamParser};
 
 /// A magic constant used to quickly identify WebAssembly binary file contents.
 const PREAMBLE: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
@@ -25,6 +45,9 @@ const PREAMBLE: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
 /// The version of the binary WebAssembly format emitted.
 const VERSION: [u8; 4] = [0x01, 0x00, 0x00, 0x00];
 
+/// The name of the custom section that holds the names of a module.
+const NAME_SECTION: &str = "name";
+
 /// Parses the given bytes into a WebAssembly module.
 /// The bytes are parsed using the WebAssembly binary format.
 /// Requires that no trailing information is present after the last group of custom sections
@@ -54,101 +77,563 @@ const VERSION: [u8; 4] = [0x01, 0x00, 0x00, 0x00];
 /// assert_eq!(module.data_count(), None);
 /// ```
 pub fn parse_binary(input: &[u8]) -> Result<Module, ParseError> {
+    let (mut builder, signatures, codes) = parse_owned_module(input, parse_code_section)?;
+
+    validate_function_counts(codes.as_ref(), signatures.as_ref())?;
+
+    builder.set_functions(zip_functions(signatures, codes));
+
+    Ok(builder.build())
+}
+
+/// Parses the given bytes into a WebAssembly module, decoding the bodies of functions on multiple threads.
+/// The code section is split into chunks of similar size by the sizes of its entries,
+/// and each chunk is decoded on its own thread.
+/// Small code sections are decoded on the current thread.
+///
+/// The module, or the error for an invalid module, is identical to the result of `parse_binary`.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_binary, parse_binary_parallel};
+///
+/// let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\x01\x0B";
+///
+/// assert_eq!(parse_binary_parallel(binary).unwrap(), parse_binary(binary).unwrap());
+/// ```
+#[cfg(feature = "parallel")]
+pub fn parse_binary_parallel(input: &[u8]) -> Result<Module, ParseError> {
+    let (mut builder, signatures, codes) =
+        parse_owned_module(input, parallel::parse_parallel_code_section)?;
+
+    validate_function_counts(codes.as_ref(), signatures.as_ref())?;
+
+    builder.set_functions(zip_functions(signatures, codes));
+
+    Ok(builder.build())
+}
+
+/// Parses the given bytes into a WebAssembly module, leaving the bodies of functions encoded.
+/// The body of a function is decoded the first time it is accessed,
+/// which saves time and memory for uses that only inspect the other parts of a module (e.g., imports and exports).
+/// The encoded bodies share a single copy of the code section.
+///
+/// Errors in the bodies of functions are only found once they are decoded.
+/// Accessing an invalid body with `Function::body` panics, while `Function::decode_body` returns the error.
+/// Emitting a module writes encoded bodies as is.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_binary, parse_binary_lazy};
+///
+/// let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\x01\x0B";
+/// let module = parse_binary_lazy(binary).unwrap();
+/// let function = &module.functions().unwrap()[0];
+///
+/// assert!(!function.is_decoded());
+/// assert_eq!(function.body().instructions().len(), 1);
+/// assert!(function.is_decoded());
+/// assert_eq!(module, parse_binary(binary).unwrap());
+/// ```
+pub fn parse_binary_lazy(input: &[u8]) -> Result<Module, ParseError> {
+    let (mut builder, signatures, codes) = parse_owned_module(input, parse_lazy_code_section)?;
+
+    validate_counts(
+        codes.as_ref().map(Vec::len),
+        signatures.as_ref().map(Vec::len),
+    )?;
+
+    let imported_functions = builder
+        .imports()
+        .unwrap_or_default()
+        .iter()
+        .filter(|import| matches!(import.description(), ImportDescription::Function(_)))
+        .count() as u32;
+    let codes = codes.map(|codes| encode_bodies(input, codes, imported_functions));
+
+    builder.set_functions(zip_bodies(signatures, codes));
+
+    Ok(builder.build())
+}
+
+/// Copies the bytes spanned by the encoded bodies of a code section,
+/// sharing the copy among the bodies.
+fn encode_bodies(
+    input: &[u8],
+    codes: Vec<(ResultType, &[u8])>,
+    imported_functions: u32,
+) -> Vec<(ResultType, Body)> {
+    let start = codes
+        .first()
+        .map(|(_, body)| offset(input, body))
+        .unwrap_or_default();
+    let end = codes
+        .last()
+        .map(|(_, body)| offset(input, body) + body.len())
+        .unwrap_or_default();
+    let code: Arc<[u8]> = Arc::from(&input[start..end]);
+
+    codes
+        .into_iter()
+        .zip(imported_functions..)
+        .map(|((locals, body), function)| {
+            let range = offset(input, body) - start..offset(input, body) + body.len() - start;
+            let body = EncodedBody::new(code.clone(), start, range, function);
+
+            (locals, Body::Encoded(Box::new(body)))
+        })
+        .collect()
+}
+
+/// Parses the given bytes into a WebAssembly module, borrowing the names of imports and exports,
+/// the initializers of data segments and the contents of custom sections from the input instead of copying them.
+/// The name section is decoded into the owned components of the module, as with `parse_binary`.
+///
+/// Converting the borrowed module into an owned module yields the result of `parse_binary`.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_binary, parse_binary_borrowed, ModuleSection};
+///
+/// let binary = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x05\x03\x01\x00\x01\x0B\x07\x01\x01\x04\x01\x02\x03\x04\x00\x06\x05dwarf";
+/// let module = parse_binary_borrowed(binary).unwrap();
+/// let data = &module.data().unwrap()[0];
+/// let custom = &module.custom_sections_at(ModuleSection::Data).unwrap()[0];
+///
+/// assert_eq!(data.initializer(), &binary[0x12..0x16]);
+/// assert_eq!(data.initializer().as_ptr(), binary[0x12..].as_ptr());
+/// assert_eq!(custom.name(), "dwarf");
+/// assert_eq!(custom.bytes(), &[]);
+/// assert_eq!(module.module().memories().map(<[_]>::len), Some(1));
+/// assert_eq!(module.into_owned(), parse_binary(binary).unwrap());
+/// ```
+pub fn parse_binary_borrowed(input: &[u8]) -> Result<BorrowedModule<'_>, ParseError> {
+    let (_, parts) = parse_module(
+        input,
+        parse_borrowed_import_section,
+        parse_borrowed_export_section,
+        parse_code_section,
+        parse_borrowed_data_section,
+        parse_borrowed_custom_section,
+    )
+    .map_err(|error| locate_error(input, error))?;
+
+    validate_function_counts(parts.codes.as_ref(), parts.signatures.as_ref())?;
+
+    let mut builder = parts.builder;
+    let mut custom_sections = Vec::new();
+
+    for (insertion_point, mut sections) in parts.custom_sections {
+        decode_names(&mut builder, insertion_point, &mut sections, |custom| {
+            (custom.name(), custom.bytes())
+        });
+
+        custom_sections.extend(sections.into_iter().map(|custom| (insertion_point, custom)));
+    }
+
+    let trailing = custom_sections
+        .iter()
+        .filter(|(insertion_point, _)| *insertion_point == parts.last_section)
+        .count();
+
+    normalize_names_placement(&mut builder, parts.last_section, trailing);
+
+    builder.set_functions(zip_functions(parts.signatures, parts.codes));
+
+    Ok(BorrowedModule::new(
+        builder.build(),
+        parts.imports,
+        parts.exports,
+        parts.data,
+        custom_sections,
+    ))
+}
+
+/// Parses the sections of a module in the binary format with the given parser for the code section,
+/// copying the names of imports and exports and the contents of data segments and custom sections.
+fn parse_owned_module<'input, C>(
+    input: &'input [u8],
+    parse_code_section: SectionParser<'input, C>,
+) -> Result<OwnedModuleParts<C>, ParseError> {
+    let (_, parts) = parse_module(
+        input,
+        parse_import_section,
+        parse_export_section,
+        parse_code_section,
+        parse_data_section,
+        parse_custom_section,
+    )
+    .map_err(|error| locate_error(input, error))?;
+    let mut builder = parts.builder;
+
+    builder.set_imports(parts.imports);
+    builder.set_exports(parts.exports);
+    builder.set_data(parts.data);
+
+    for (insertion_point, custom_sections) in parts.custom_sections {
+        set_custom_sections(&mut builder, insertion_point, Some(custom_sections));
+    }
+
+    let trailing = builder
+        .custom_sections_at(parts.last_section)
+        .map_or(0, <[Custom]>::len);
+
+    normalize_names_placement(&mut builder, parts.last_section, trailing);
+
+    Ok((builder, parts.signatures, parts.codes))
+}
+
+/// The parts of a module parsed from the binary format, with the imports, exports, data segments and custom sections copied.
+/// The function and code sections are kept apart until their lengths are validated.
+type OwnedModuleParts<C> = (ModuleBuilder, Option<Vec<TypeIndex>>, Option<Vec<C>>);
+
+/// A parser of a section whose entries may be decoded, borrowed or copied.
+type SectionParser<'input, O> = fn(&'input [u8]) -> ParseResult<'input, Option<Vec<O>>>;
+
+/// The parts of a module parsed from the binary format.
+/// The function and code sections are kept apart until their lengths are validated.
+/// The imports, exports, data segments and custom sections are kept apart, as they may be borrowed from the input.
+struct ModuleParts<I, E, C, D, S> {
+    builder: ModuleBuilder,
+    imports: Option<Vec<I>>,
+    exports: Option<Vec<E>>,
+    signatures: Option<Vec<TypeIndex>>,
+    codes: Option<Vec<C>>,
+    data: Option<Vec<D>>,
+    custom_sections: Vec<(ModuleSection, Vec<S>)>,
+    last_section: ModuleSection,
+}
+
+/// Parses the sections of a module in the binary format, in order,
+/// using the given parsers for the import, export, code, data and custom sections.
+fn parse_module<'input, I, E, C, D, S>(
+    input: &'input [u8],
+    parse_import_section: SectionParser<'input, I>,
+    parse_export_section: SectionParser<'input, E>,
+    parse_code_section: SectionParser<'input, C>,
+    parse_data_section: SectionParser<'input, D>,
+    parse_custom_section: SectionParser<'input, S>,
+) -> ParseResult<'input, ModuleParts<I, E, C, D, S>> {
+    let mut groups = Vec::new();
+    let mut set_custom_sections =
+        |insertion_point: ModuleSection, custom_sections: Option<Vec<S>>| {
+            if let Some(custom_sections) = custom_sections {
+                groups.push((insertion_point, custom_sections));
+            }
+        };
     let mut builder = Module::builder();
 
-    let (input, _) = tuple((tag(PREAMBLE), tag(VERSION)))(input)?;
+    let (input, _) = expect(Expected::Preamble, tag(PREAMBLE))(input)?;
+    let (input, _) = expect(Expected::Version, tag(VERSION))(input)?;
 
-    let (input, custom_sections) =
-        parse_custom_section(input).map_err(|_| ParseError::InvalidBinary)?;
-    builder.set_custom_sections(ModuleSection::Custom, custom_sections);
+    let (input, custom_sections) = parse_custom_section(input)?;
+    set_custom_sections(ModuleSection::Custom, custom_sections);
 
     let (input, types) = parse_type_section(input)?;
-    builder.set_function_types(types);
+    builder.set_types(types);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Type, custom_sections);
+    set_custom_sections(ModuleSection::Type, custom_sections);
 
     let (input, imports) = parse_import_section(input)?;
-    builder.set_imports(imports);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Import, custom_sections);
+    set_custom_sections(ModuleSection::Import, custom_sections);
 
     let (input, signatures) = parse_function_section(input)?;
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Function, custom_sections);
+    set_custom_sections(ModuleSection::Function, custom_sections);
 
     let (input, tables) = parse_table_section(input)?;
     builder.set_tables(tables);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Table, custom_sections);
+    set_custom_sections(ModuleSection::Table, custom_sections);
 
     let (input, memories) = parse_memory_section(input)?;
     builder.set_memories(memories);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Memory, custom_sections);
+    set_custom_sections(ModuleSection::Memory, custom_sections);
+
+    let (input, tags) = parse_tag_section(input)?;
+    builder.set_tags(tags);
+
+    let (input, custom_sections) = parse_custom_section(input)?;
+    set_custom_sections(ModuleSection::Tag, custom_sections);
 
     let (input, globals) = parse_global_section(input)?;
     builder.set_globals(globals);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Global, custom_sections);
+    set_custom_sections(ModuleSection::Global, custom_sections);
 
     let (input, exports) = parse_export_section(input)?;
-    builder.set_exports(exports);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Export, custom_sections);
+    set_custom_sections(ModuleSection::Export, custom_sections);
 
     let (input, start) = parse_start_section(input)?;
     builder.set_start(start);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Start, custom_sections);
+    set_custom_sections(ModuleSection::Start, custom_sections);
 
     let (input, elements) = parse_element_section(input)?;
     builder.set_elements(elements);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Element, custom_sections);
+    set_custom_sections(ModuleSection::Element, custom_sections);
 
     let (input, data_count) = parse_data_count_section(input)?;
     builder.set_data_count(data_count);
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::DataCount, custom_sections);
+    set_custom_sections(ModuleSection::DataCount, custom_sections);
 
     let (input, codes) = parse_code_section(input)?;
 
-    validate_function_counts(codes.as_ref(), signatures.as_ref())?;
+    let (input, custom_sections) = parse_custom_section(input)?;
+    set_custom_sections(ModuleSection::Code, custom_sections);
 
-    builder.set_functions(zip_functions(signatures, codes));
+    let (input, data) = parse_data_section(input)?;
 
     let (input, custom_sections) = parse_custom_section(input)?;
-    builder.set_custom_sections(ModuleSection::Code, custom_sections);
+    set_custom_sections(ModuleSection::Data, custom_sections);
 
-    let (input, data) = parse_data_section(input)?;
-    builder.set_data(data);
+    if !input.is_empty() {
+        return Err(nom::Err::Error(InputError::new(input, Expected::Section)));
+    }
+
+    let sections = [
+        (ModuleSection::Data, data.is_some()),
+        (ModuleSection::Code, codes.is_some()),
+        (ModuleSection::DataCount, data_count.is_some()),
+        (ModuleSection::Element, builder.elements().is_some()),
+        (ModuleSection::Start, builder.start().is_some()),
+        (ModuleSection::Export, exports.is_some()),
+        (ModuleSection::Global, builder.globals().is_some()),
+        (ModuleSection::Tag, builder.tags().is_some()),
+        (ModuleSection::Memory, builder.memories().is_some()),
+        (ModuleSection::Table, builder.tables().is_some()),
+        (ModuleSection::Function, signatures.is_some()),
+        (ModuleSection::Import, imports.is_some()),
+        (ModuleSection::Type, builder.types().is_some()),
+    ];
+    let last_section = sections
+        .into_iter()
+        .find_map(|(section, present)| present.then_some(section))
+        .unwrap_or(ModuleSection::Custom);
+
+    Ok((
+        input,
+        ModuleParts {
+            builder,
+            imports,
+            exports,
+            signatures,
+            codes,
+            data,
+            custom_sections: groups,
+            last_section,
+        },
+    ))
+}
 
-    let (_, custom_sections) = all_consuming(parse_custom_section)(input)?;
-    builder.set_custom_sections(ModuleSection::Data, custom_sections);
+/// Locates an error in the binary format of a module within the section and function it occurred in.
+/// The function index accounts for the functions imported by the module.
+fn locate_error(input: &[u8], error: nom::Err<InputError<'_>>) -> ParseError {
+    let error = BinaryError::new(input, error);
+    let mut remaining = input
+        .get(PREAMBLE.len() + VERSION.len()..)
+        .unwrap_or_default();
+    let mut imported_functions = 0;
+
+    while let Ok((contents, (id, length))) = tuple((parse_byte, parse_u32))(remaining) {
+        let (contents, next) = contents.split_at(contents.len().min(length as usize));
+        let section = module_section(id);
+        let start = offset(input, remaining);
+
+        if is_within(&error, start, offset(input, next)) {
+            let function = match section {
+                Some(ModuleSection::Code) => {
+                    locate_function(input, contents, &error).map(|index| index + imported_functions)
+                }
+                _ => None,
+            };
+
+            return ParseError::InvalidBinary(error.within(section, function));
+        }
+
+        if section == Some(ModuleSection::Import) {
+            imported_functions = parse_vector(parse_import)(contents)
+                .map(|(_, imports)| {
+                    imports
+                        .iter()
+                        .filter(|import| {
+                            matches!(import.description(), ImportDescription::Function(_))
+                        })
+                        .count() as u32
+                })
+                .unwrap_or_default();
+        }
+
+        remaining = next;
+    }
 
-    Ok(builder.build())
+    ParseError::InvalidBinary(error)
+}
+
+/// Finds the index of the entry of a code section's contents that contains the given error.
+fn locate_function(input: &[u8], contents: &[u8], error: &BinaryError) -> Option<FunctionIndex> {
+    let (mut remaining, count) = parse_u32(contents).ok()?;
+
+    for index in 0..count {
+        let start = offset(input, remaining);
+        let (body, size) = parse_u32(remaining).ok()?;
+
+        remaining = body.get(size as usize..).unwrap_or_default();
+
+        if is_within(error, start, offset(input, body) + size as usize) {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+/// Returns true if the error occurred within the given range of offsets of the input.
+/// An error at the end of the range is within it when the input ended there unexpectedly.
+fn is_within(error: &BinaryError, start: usize, end: usize) -> bool {
+    (start..end).contains(&error.offset())
+        || (error.offset() == end && error.expected() == Expected::More)
+}
+
+/// The offset of a suffix of the input from the start of the input.
+fn offset(input: &[u8], suffix: &[u8]) -> usize {
+    suffix.as_ptr() as usize - input.as_ptr() as usize
+}
+
+/// The section of a module with the given identifier, if any.
+fn module_section(id: u8) -> Option<ModuleSection> {
+    let section = match id {
+        0 => ModuleSection::Custom,
+        1 => ModuleSection::Type,
+        2 => ModuleSection::Import,
+        3 => ModuleSection::Function,
+        4 => ModuleSection::Table,
+        5 => ModuleSection::Memory,
+        6 => ModuleSection::Global,
+        7 => ModuleSection::Export,
+        8 => ModuleSection::Start,
+        9 => ModuleSection::Element,
+        10 => ModuleSection::Code,
+        11 => ModuleSection::Data,
+        12 => ModuleSection::DataCount,
+        13 => ModuleSection::Tag,
+        _ => return None,
+    };
+
+    Some(section)
+}
+
+/// Sets the custom sections at the given insertion point of the module to be built.
+/// The first name section that decodes successfully is extracted into the names of the module,
+/// while a name section that fails to decode is kept as an uninterpreted custom section,
+/// as errors in the name section do not invalidate the module.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+fn set_custom_sections(
+    builder: &mut ModuleBuilder,
+    insertion_point: ModuleSection,
+    custom_sections: Option<Vec<Custom>>,
+) {
+    let mut custom_sections = match custom_sections {
+        Some(custom_sections) if builder.names().is_none() => custom_sections,
+        custom_sections => return builder.set_custom_sections(insertion_point, custom_sections),
+    };
+
+    decode_names(builder, insertion_point, &mut custom_sections, |custom| {
+        (custom.name().as_str(), custom.bytes())
+    });
+
+    builder.set_custom_sections(
+        insertion_point,
+        Some(custom_sections).filter(|sections| !sections.is_empty()),
+    );
+}
+
+/// Decodes the first valid name section among the given custom sections into the names of the module,
+/// removing it from the custom sections and recording its placement.
+/// Custom sections are left as is once the names of the module are decoded.
+fn decode_names<S>(
+    builder: &mut ModuleBuilder,
+    insertion_point: ModuleSection,
+    custom_sections: &mut Vec<S>,
+    contents: fn(&S) -> (&str, &[u8]),
+) {
+    if builder.names().is_some() {
+        return;
+    }
+
+    let decoded = custom_sections
+        .iter()
+        .enumerate()
+        .map(|(index, custom)| (index, contents(custom)))
+        .filter(|(_, (name, _))| *name == NAME_SECTION)
+        .find_map(|(index, (_, bytes))| Some((index, parse_names(bytes).ok()?)));
+
+    if let Some((index, names)) = decoded {
+        custom_sections.remove(index);
+        builder.set_names(Some(names));
+        builder.set_names_placement(Some((insertion_point, index)));
+    }
+}
+
+/// Clears the placement of the name section when the emitter places the names in the same position by default,
+/// so that modules compare equal regardless of whether they were emitted and parsed again.
+/// By default, the name section follows the data section and precedes the other custom sections after it.
+/// Without a data section, custom sections after the last section are parsed at its insertion point,
+/// so a name section after all of them (i.e., the given number of trailing custom sections) is in the default position.
+fn normalize_names_placement(
+    builder: &mut ModuleBuilder,
+    last_section: ModuleSection,
+    trailing: usize,
+) {
+    let is_default = match builder.names_placement() {
+        Some(placement) if last_section == ModuleSection::Data => placement == (last_section, 0),
+        Some(placement) => placement == (last_section, trailing),
+        None => true,
+    };
+
+    if is_default {
+        builder.set_names_placement(None);
+    }
 }
 
 /// Zips code and function sections into a function syntax type.
 fn zip_functions(
     signatures: Option<Vec<TypeIndex>>,
     codes: Option<Vec<(ResultType, Expression)>>,
+) -> Option<Vec<Function>> {
+    zip_bodies(signatures, codes)
+}
+
+/// Zips code and function sections into a function syntax type, for decoded or encoded bodies.
+fn zip_bodies<B: Into<Body>>(
+    signatures: Option<Vec<TypeIndex>>,
+    codes: Option<Vec<(ResultType, B)>>,
 ) -> Option<Vec<Function>> {
     codes.zip(signatures).map(|(codes, signatures)| {
         codes
             .into_iter()
             .zip(signatures)
-            .map(|((locals, body), kind)| Function::new(kind, locals, body))
+            .map(|((locals, body), kind)| Function {
+                kind,
+                locals,
+                body: body.into(),
+            })
             .collect()
     })
 }
@@ -158,12 +643,18 @@ fn validate_function_counts(
     codes: Option<&Vec<(ResultType, Expression)>>,
     signatures: Option<&Vec<TypeIndex>>,
 ) -> Result<(), ParseError> {
-    if codes.is_none() && signatures.is_none() {
+    validate_counts(codes.map(Vec::len), signatures.map(Vec::len))
+}
+
+/// Validates the lengths of the parsed function and code sections match.
+fn validate_counts(
+    code_count: Option<usize>,
+    signature_count: Option<usize>,
+) -> Result<(), ParseError> {
+    if code_count.is_none() && signature_count.is_none() {
         return Ok(());
     }
 
-    let code_count = codes.as_ref().map(|v| v.len());
-    let signature_count = signatures.as_ref().map(|v| v.len());
     let lengths_match = code_count.zip(signature_count).filter(|(a, b)| a == b);
 
     lengths_match
@@ -174,9 +665,33 @@ fn validate_function_counts(
         ))
 }
 
+/// Parses the contents of a name custom section into the names of a module and its definitions.
+/// The binary parser decodes the name section that follows the data section automatically,
+/// making the names available through `Module::names`.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_names, Name};
+///
+/// let names = parse_names(b"\x00\x04\x03app\x01\x07\x01\x00\x04main").unwrap();
+///
+/// assert_eq!(names.module(), Some(&Name::from("app")));
+/// assert_eq!(names.function(0), Some(&Name::from("main")));
+/// ```
+pub fn parse_names(bytes: &[u8]) -> Result<NameSection, ParseError> {
+    let (_, names) = consume_all(parse_name_section)(bytes)
+        .map_err(|error| ParseError::InvalidBinary(BinaryError::new(bytes, error)))?;
+
+    Ok(names)
+}
+
 /// Parses the given string into a WebAssembly module.
-/// The string is first converted to WebAssembly binary, then parse.
-/// Some information may be lost in the conversion from text to binary format.
+/// The string is parsed using the WebAssembly text format, resolving symbolic identifiers to indices.
+/// Use `parse_text_with_names` to also retain the identifiers.
+///
+/// See <https://webassembly.github.io/spec/core/text/index.html>
 ///
 /// # Examples
 /// ## Empty
@@ -199,9 +714,33 @@ fn validate_function_counts(
 /// ```
 #[cfg(feature = "text")]
 pub fn parse_text(text: &str) -> Result<Module, ParseError> {
-    let binary = wat::parse_str(text)?;
+    text::parse_module(text).map(|(module, _)| module)
+}
 
-    parse_binary(binary.as_slice())
+/// Parses the given string into a WebAssembly module, along with the names of the symbolic
+/// identifiers used in the text.
+/// The names are keyed by the indices the identifiers resolve to, following the layout of the
+/// name custom section.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_text_with_names, Name};
+///
+/// let (module, names) = parse_text_with_names(
+///     "(module $math (func $add (param $x i32) (param $y i32) (result i32) local.get $x local.get $y i32.add))",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(module.functions().map(<[_]>::len), Some(1));
+/// assert_eq!(names.module(), Some(&Name::from("math")));
+/// assert_eq!(names.function(0), Some(&Name::from("add")));
+/// assert_eq!(names.local(0, 1), Some(&Name::from("y")));
+/// ```
+#[cfg(feature = "text")]
+pub fn parse_text_with_names(text: &str) -> Result<(Module, NameSection), ParseError> {
+    text::parse_module(text)
 }
 
 #[cfg(test)]
@@ -325,4 +864,253 @@ mod tests {
 
         assert_eq!(actual, module);
     }
+
+    fn binary_error(bytes: &[u8]) -> BinaryError {
+        match parse_binary(bytes) {
+            Err(ParseError::InvalidBinary(error)) => error,
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn invalid_preamble() {
+        let error = binary_error(b"\x00\x61\x73\x6E\x01\x00\x00\x00");
+
+        assert_eq!(error.offset(), 0);
+        assert_eq!(error.section(), None);
+        assert_eq!(error.expected(), Expected::Preamble);
+        assert_eq!(error.found(), Some(0x00));
+    }
+
+    #[test]
+    fn unknown_opcode_after_imports() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x02\x07\x01\x01m\x01f\x00\x00\
+            \x03\x03\x02\x00\x00\
+            \x0A\x09\x02\x02\x00\x0B\x04\x00\xFD\xFF\x0B";
+        let error = binary_error(bytes);
+
+        assert_eq!(error.offset(), 0x24);
+        assert_eq!(error.section(), Some(ModuleSection::Code));
+        assert_eq!(error.function(), Some(2));
+        assert_eq!(error.expected(), Expected::Opcode);
+        assert_eq!(
+            error.to_string(),
+            "unknown opcode 0xFD at offset 0x24 in function 2"
+        );
+    }
+
+    #[test]
+    fn truncated_section() {
+        let error = binary_error(b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x05\x01\x60\x00");
+
+        assert_eq!(error.offset(), 0x0D);
+        assert_eq!(error.section(), Some(ModuleSection::Type));
+        assert_eq!(error.expected(), Expected::More);
+        assert_eq!(error.found(), None);
+    }
+
+    #[test]
+    fn truncated_code_section() {
+        let bytes = wat::parse_str(
+            "(module (func) (func) (func) (func (drop (i32.const 1)) (drop (i32.const 2))))",
+        )
+        .unwrap();
+        let error = binary_error(&bytes[..bytes.len() - 3]);
+
+        assert_eq!(error.section(), Some(ModuleSection::Code));
+        assert_eq!(error.found(), None);
+        assert_eq!(
+            error.to_string(),
+            "unexpected end of input, expected more bytes at offset 0x27 in function 3"
+        );
+    }
+
+    #[test]
+    fn unknown_section() {
+        let error = binary_error(b"\x00\x61\x73\x6D\x01\x00\x00\x00\x0E\x00");
+
+        assert_eq!(error.offset(), 0x08);
+        assert_eq!(error.section(), None);
+        assert_eq!(error.expected(), Expected::Section);
+        assert_eq!(
+            error.to_string(),
+            "expected a known section in order, found 0x0E at offset 0x8"
+        );
+    }
+
+    #[test]
+    fn lazy_bodies_decode_on_demand() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x03\x03\x02\x00\x00\
+            \x0A\x0B\x02\x02\x00\x0B\x06\x01\x01\x7F\x01\x1A\x0B";
+        let module = parse_binary_lazy(bytes).unwrap();
+        let functions = module.functions().unwrap();
+
+        assert!(functions.iter().all(|function| !function.is_decoded()));
+        assert_eq!(functions[1].locals().len(), 1);
+        assert!(functions[1].decode_body().is_ok());
+        assert!(!functions[0].is_decoded());
+        assert!(functions[1].is_decoded());
+        assert_eq!(module, parse_binary(bytes).unwrap());
+    }
+
+    #[test]
+    fn lazy_invalid_body() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x02\x07\x01\x01m\x01f\x00\x00\
+            \x03\x03\x02\x00\x00\
+            \x0A\x09\x02\x02\x00\x0B\x04\x00\xFD\xFF\x0B";
+        let module = parse_binary_lazy(bytes).unwrap();
+        let functions = module.functions().unwrap();
+
+        assert!(functions[0].decode_body().is_ok());
+
+        match functions[1].decode_body() {
+            Err(ParseError::InvalidBinary(error)) => assert_eq!(error, binary_error(bytes)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        assert!(!functions[1].is_decoded());
+    }
+
+    #[test]
+    fn lazy_mismatched_function_parts() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x03\x02\x01\x00";
+
+        assert!(matches!(
+            parse_binary_lazy(bytes),
+            Err(ParseError::MismatchedFunctionParts(None, Some(1)))
+        ));
+    }
+
+    #[test]
+    fn borrowed_module() {
+        let bytes = wat::parse_str(
+            r#"(module
+                (@custom "dwarf" (before first) "debug")
+                (import "env" "log" (func $log))
+                (memory 1)
+                (func $start (export "start"))
+                (data (i32.const 0) "hello")
+                (data "world")
+                (@custom "producers" (after data) "wasm-ast"))"#,
+        )
+        .unwrap();
+        let module = parse_binary_borrowed(&bytes).unwrap();
+        let input = bytes.as_ptr_range();
+
+        assert!(module
+            .data()
+            .unwrap()
+            .iter()
+            .all(|data| input.contains(&data.initializer().as_ptr())));
+        assert_eq!(module.data().unwrap()[1].initializer(), b"world");
+        assert!(module.imports().unwrap().iter().all(|import| {
+            input.contains(&import.module().as_ptr()) && input.contains(&import.name().as_ptr())
+        }));
+        assert_eq!(module.imports().unwrap()[0].name(), "log");
+        assert!(input.contains(&module.exports().unwrap()[0].name().as_ptr()));
+        assert_eq!(module.exports().unwrap()[0].name(), "start");
+        assert_eq!(
+            module.custom_sections_at(ModuleSection::Custom).unwrap()[0].bytes(),
+            b"debug"
+        );
+        assert_eq!(
+            module.custom_sections_at(ModuleSection::Data).unwrap()[0].name(),
+            "producers"
+        );
+        assert!(module.module().names().is_some());
+        assert_eq!(module.module().imports(), None);
+        assert_eq!(module.module().exports(), None);
+        assert_eq!(module.module().data(), None);
+        assert_eq!(module.into_owned(), parse_binary(&bytes).unwrap());
+    }
+
+    #[test]
+    fn borrowed_invalid_module() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x0B\x06\x01\x01\x04\x01\x02\x03";
+
+        match (parse_binary_borrowed(bytes), parse_binary(bytes)) {
+            (Err(ParseError::InvalidBinary(actual)), Err(ParseError::InvalidBinary(expected))) => {
+                assert_eq!(actual, expected)
+            }
+            results => panic!("unexpected results: {:?}", results),
+        }
+    }
+
+    #[cfg(feature = "emitter")]
+    #[test]
+    fn lazy_round_trip() {
+        let bytes = wat::parse_str(
+            r#"(module
+                (func $add (param i32 i32) (result i32) (local i64)
+                    local.get 0
+                    local.get 1
+                    i32.add)
+                (func (export "main") (result i32)
+                    (call $add (i32.const 1) (i32.const 2))))"#,
+        )
+        .unwrap();
+        let module = parse_binary_lazy(&bytes).unwrap();
+        let mut buffer = Vec::new();
+
+        crate::emit_binary(&module, &mut buffer).unwrap();
+
+        assert!(module
+            .functions()
+            .unwrap()
+            .iter()
+            .all(|function| !function.is_decoded()));
+        assert_eq!(
+            parse_binary(&buffer).unwrap(),
+            parse_binary(&bytes).unwrap()
+        );
+    }
+
+    #[cfg(feature = "emitter")]
+    #[test]
+    fn borrowed_round_trip() {
+        let bytes = wat::parse_str(
+            r#"(module
+                (@custom "dwarf" (before first) "debug")
+                (import "env" "log" (func $log))
+                (memory 1)
+                (func $start (export "start") data.drop 1)
+                (data (i32.const 0) "hello")
+                (data "world")
+                (@custom "producers" (after data) "wasm-ast"))"#,
+        )
+        .unwrap();
+        let module = parse_binary_borrowed(&bytes).unwrap();
+        let mut buffer = Vec::new();
+
+        crate::emit_binary_borrowed(&module, &mut buffer).unwrap();
+
+        assert_eq!(module.data_count(), Some(2));
+        assert_eq!(buffer, bytes);
+        assert_eq!(crate::validate(&module.into_owned()), Ok(()));
+    }
+
+    #[test]
+    fn invalid_name_section() {
+        let mut builder = Module::builder();
+
+        builder.add_custom_section(
+            ModuleSection::Custom,
+            Custom::new(NAME_SECTION.into(), vec![0x0C, 0x00]),
+        );
+
+        let module = builder.build();
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x00\x07\x04name\x0C\x00";
+        let actual = parse_binary(bytes).unwrap();
+
+        assert_eq!(actual, module);
+        assert_eq!(actual.names(), None);
+    }
 }
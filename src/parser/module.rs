@@ -1,31 +1,46 @@
+use crate::parser::errors::{consume_all, ParseResult};
 use crate::parser::instructions::parse_expression;
 use crate::parser::types::{
-    parse_global_type, parse_memory_type, parse_reference_type, parse_table_type, parse_value_type,
+    parse_global_type, parse_memory_type, parse_reference_type, parse_table_type, parse_tag_type,
+    parse_value_type,
+};
+use crate::parser::values::{
+    match_byte, parse_borrowed_name, parse_byte_vector, parse_u32, parse_vector,
 };
-use crate::parser::values::{match_byte, parse_byte_vector, parse_name, parse_u32, parse_vector};
 use crate::{
-    Data, Element, ElementInitializer, Export, ExportDescription, Expression, Global, Import,
-    ImportDescription, Memory, ReferenceType, ResultType, Start, Table,
+    BorrowedData, BorrowedExport, BorrowedImport, Data, Element, ElementInitializer, Export,
+    ExportDescription, Expression, Global, Import, ImportDescription, Memory, ReferenceType,
+    ResultType, Start, Table, Tag,
 };
 use nom::branch::alt;
 use nom::bytes::complete::take;
-use nom::combinator::{all_consuming, map};
+use nom::combinator::map;
 use nom::multi::fold_many_m_n;
 use nom::sequence::{preceded, tuple};
-use nom::IResult;
 
 /// Parses a WebAssembly import component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-importsec>
-pub fn parse_import(input: &[u8]) -> IResult<&[u8], Import> {
+pub fn parse_import(input: &[u8]) -> ParseResult<'_, Import> {
+    map(parse_borrowed_import, Import::from)(input)
+}
+
+/// Parses a WebAssembly import component from the input, borrowing its module and name from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-importsec>
+pub fn parse_borrowed_import(input: &[u8]) -> ParseResult<'_, BorrowedImport<'_>> {
     map(
-        tuple((parse_name, parse_name, parse_import_description)),
-        |(module, import, description)| Import::new(module, import, description),
+        tuple((
+            parse_borrowed_name,
+            parse_borrowed_name,
+            parse_import_description,
+        )),
+        |(module, import, description)| BorrowedImport::new(module, import, description),
     )(input)
 }
 
 /// Parses an import description.
-fn parse_import_description(input: &[u8]) -> IResult<&[u8], ImportDescription> {
+fn parse_import_description(input: &[u8]) -> ParseResult<'_, ImportDescription> {
     alt((
         map(
             preceded(match_byte(0x00), parse_u32),
@@ -43,27 +58,49 @@ fn parse_import_description(input: &[u8]) -> IResult<&[u8], ImportDescription> {
             preceded(match_byte(0x03), parse_global_type),
             ImportDescription::Global,
         ),
+        map(
+            preceded(match_byte(0x04), parse_tag_type),
+            ImportDescription::Tag,
+        ),
     ))(input)
 }
 
 /// Parses a WebAssembly table component from the input.
 ///
-/// See <https://webassembly.github.io/spec/core/binary/modules.html#memory-section>
-pub fn parse_table(input: &[u8]) -> IResult<&[u8], Table> {
-    map(parse_table_type, Table::from)(input)
+/// Tables with an initializer are prefixed by the bytes `0x40 0x00`.
+///
+/// See <https://webassembly.github.io/function-references/core/binary/modules.html#table-section>
+pub fn parse_table(input: &[u8]) -> ParseResult<'_, Table> {
+    alt((
+        map(
+            preceded(
+                tuple((match_byte(0x40), match_byte(0x00))),
+                tuple((parse_table_type, parse_expression)),
+            ),
+            |(kind, initializer)| Table::with_initializer(kind, initializer),
+        ),
+        map(parse_table_type, Table::from),
+    ))(input)
 }
 
 /// Parses a WebAssembly memory component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#memory-section>
-pub fn parse_memory(input: &[u8]) -> IResult<&[u8], Memory> {
+pub fn parse_memory(input: &[u8]) -> ParseResult<'_, Memory> {
     map(parse_memory_type, Memory::from)(input)
 }
 
+/// Parses a WebAssembly tag component from the input.
+///
+/// See <https://webassembly.github.io/exception-handling/core/binary/modules.html#tag-section>
+pub fn parse_tag(input: &[u8]) -> ParseResult<'_, Tag> {
+    map(parse_tag_type, Tag::from)(input)
+}
+
 /// Parses a WebAssembly global component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#global-section>
-pub fn parse_global(input: &[u8]) -> IResult<&[u8], Global> {
+pub fn parse_global(input: &[u8]) -> ParseResult<'_, Global> {
     map(
         tuple((parse_global_type, parse_expression)),
         |(kind, initializer)| Global::new(kind, initializer),
@@ -73,24 +110,32 @@ pub fn parse_global(input: &[u8]) -> IResult<&[u8], Global> {
 /// Parses a WebAssembly data component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#data-section>
-pub fn parse_data(input: &[u8]) -> IResult<&[u8], Data> {
+pub fn parse_data(input: &[u8]) -> ParseResult<'_, Data> {
+    map(parse_borrowed_data, Data::from)(input)
+}
+
+/// Parses a WebAssembly data component from the input, borrowing its initializer from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#data-section>
+pub fn parse_borrowed_data(input: &[u8]) -> ParseResult<'_, BorrowedData<'_>> {
     alt((
         map(
             preceded(
                 match_byte(0x00),
                 tuple((parse_expression, parse_byte_vector)),
             ),
-            |(offset, bytes)| Data::active(0, offset, bytes.into()),
+            |(offset, bytes)| BorrowedData::active(0, offset, bytes),
+        ),
+        map(
+            preceded(match_byte(0x01), parse_byte_vector),
+            BorrowedData::passive,
         ),
-        map(preceded(match_byte(0x01), parse_byte_vector), |bytes| {
-            Data::passive(bytes.into())
-        }),
         map(
             preceded(
                 match_byte(0x02),
                 tuple((parse_u32, parse_expression, parse_byte_vector)),
             ),
-            |(memory, offset, bytes)| Data::active(memory, offset, bytes.into()),
+            |(memory, offset, bytes)| BorrowedData::active(memory, offset, bytes),
         ),
     ))(input)
 }
@@ -98,22 +143,29 @@ pub fn parse_data(input: &[u8]) -> IResult<&[u8], Data> {
 /// Parses a WebAssembly start component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#start-section>
-pub fn parse_start(input: &[u8]) -> IResult<&[u8], Start> {
+pub fn parse_start(input: &[u8]) -> ParseResult<'_, Start> {
     map(parse_u32, Start::new)(input)
 }
 
 /// Parses a WebAssembly export component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#export-section>
-pub fn parse_export(input: &[u8]) -> IResult<&[u8], Export> {
+pub fn parse_export(input: &[u8]) -> ParseResult<'_, Export> {
+    map(parse_borrowed_export, Export::from)(input)
+}
+
+/// Parses a WebAssembly export component from the input, borrowing its name from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-exportsec>
+pub fn parse_borrowed_export(input: &[u8]) -> ParseResult<'_, BorrowedExport<'_>> {
     map(
-        tuple((parse_name, parse_export_description)),
-        |(export, description)| Export::new(export, description),
+        tuple((parse_borrowed_name, parse_export_description)),
+        |(export, description)| BorrowedExport::new(export, description),
     )(input)
 }
 
 /// Parses an export description.
-fn parse_export_description(input: &[u8]) -> IResult<&[u8], ExportDescription> {
+fn parse_export_description(input: &[u8]) -> ParseResult<'_, ExportDescription> {
     alt((
         map(
             preceded(match_byte(0x00), parse_u32),
@@ -131,13 +183,17 @@ fn parse_export_description(input: &[u8]) -> IResult<&[u8], ExportDescription> {
             preceded(match_byte(0x03), parse_u32),
             ExportDescription::Global,
         ),
+        map(
+            preceded(match_byte(0x04), parse_u32),
+            ExportDescription::Tag,
+        ),
     ))(input)
 }
 
 /// Parses a WebAssembly element component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#element-section>
-pub fn parse_element(input: &[u8]) -> IResult<&[u8], Element> {
+pub fn parse_element(input: &[u8]) -> ParseResult<'_, Element> {
     alt((
         map(
             preceded(
@@ -228,18 +284,30 @@ pub fn parse_element(input: &[u8]) -> IResult<&[u8], Element> {
 /// Parses a WebAssembly code portion of a function component from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
-pub fn parse_code(input: &[u8]) -> IResult<&[u8], (ResultType, Expression)> {
+pub fn parse_code(input: &[u8]) -> ParseResult<'_, (ResultType, Expression)> {
     let (input, size) = parse_u32(input)?;
     let (remaining, input) = take(size as usize)(input)?;
-    let (_, code) = all_consuming(tuple((parse_locals, parse_expression)))(input)?;
+    let (_, code) = consume_all(tuple((parse_locals, parse_expression)))(input)?;
 
     Ok((remaining, code))
 }
 
+/// Parses a WebAssembly code portion of a function component from the input,
+/// leaving the body of the function encoded.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
+pub fn parse_lazy_code(input: &[u8]) -> ParseResult<'_, (ResultType, &[u8])> {
+    let (input, size) = parse_u32(input)?;
+    let (remaining, input) = take(size as usize)(input)?;
+    let (body, locals) = parse_locals(input)?;
+
+    Ok((remaining, (locals, body)))
+}
+
 /// Parses the value types of locals in a function.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
-pub fn parse_locals(input: &[u8]) -> IResult<&[u8], ResultType> {
+pub fn parse_locals(input: &[u8]) -> ParseResult<'_, ResultType> {
     let (input, length) = parse_u32(input)?;
     let length = length as usize;
     let (remaining, value_types) = fold_many_m_n(
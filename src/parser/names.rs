@@ -0,0 +1,165 @@
+use crate::parser::errors::{consume_all, Expected, InputError, ParseResult};
+use crate::parser::values::{parse_byte_vector, parse_name, parse_u32, parse_vector};
+use crate::{IndirectNameMap, Name, NameMap, NameSection, NameSubsection};
+use nom::combinator::{map_opt, map_res};
+use nom::error::ErrorKind;
+use nom::number::complete::u8 as parse_byte;
+use nom::sequence::tuple;
+use std::collections::BTreeMap;
+
+/// Parses the contents of a WebAssembly name section.
+/// Subsections must appear at most once and in order of increasing id.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-section>
+pub fn parse_name_section(mut input: &[u8]) -> ParseResult<'_, NameSection> {
+    let mut names = NameSection::new();
+    let mut previous = None;
+
+    while !input.is_empty() {
+        let (remaining, subsection) = parse_subsection_id(input)?;
+
+        if previous >= Some(subsection) {
+            return Err(nom::Err::Error(InputError::new(input, Expected::Syntax)));
+        }
+
+        let (remaining, contents) = parse_byte_vector(remaining)?;
+
+        match subsection {
+            NameSubsection::Module => names.set_module(Some(parse_all(contents, parse_name)?)),
+            NameSubsection::Function => {
+                *names.functions_mut() = parse_all(contents, parse_name_map)?
+            }
+            NameSubsection::Local => {
+                *names.locals_mut() = parse_all(contents, parse_indirect_name_map)?
+            }
+            NameSubsection::Label => {
+                *names.labels_mut() = parse_all(contents, parse_indirect_name_map)?
+            }
+            NameSubsection::Type => *names.types_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Table => *names.tables_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Memory => *names.memories_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Global => *names.globals_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Element => *names.elements_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Data => *names.data_mut() = parse_all(contents, parse_name_map)?,
+            NameSubsection::Field => {
+                *names.fields_mut() = parse_all(contents, parse_indirect_name_map)?
+            }
+            NameSubsection::Tag => *names.tags_mut() = parse_all(contents, parse_name_map)?,
+        }
+
+        previous = Some(subsection);
+        input = remaining;
+    }
+
+    Ok((input, names))
+}
+
+/// Parses the id of a name subsection.
+fn parse_subsection_id(input: &[u8]) -> ParseResult<'_, NameSubsection> {
+    map_opt(parse_byte, |id| match id {
+        0 => Some(NameSubsection::Module),
+        1 => Some(NameSubsection::Function),
+        2 => Some(NameSubsection::Local),
+        3 => Some(NameSubsection::Label),
+        4 => Some(NameSubsection::Type),
+        5 => Some(NameSubsection::Table),
+        6 => Some(NameSubsection::Memory),
+        7 => Some(NameSubsection::Global),
+        8 => Some(NameSubsection::Element),
+        9 => Some(NameSubsection::Data),
+        10 => Some(NameSubsection::Field),
+        11 => Some(NameSubsection::Tag),
+        _ => None,
+    })(input)
+}
+
+/// Parses the contents of a name subsection, which must be consumed entirely by the given parser.
+fn parse_all<'input, T>(
+    contents: &'input [u8],
+    parser: fn(&'input [u8]) -> ParseResult<'input, T>,
+) -> Result<T, nom::Err<InputError<'input>>> {
+    consume_all(parser)(contents).map(|(_, value)| value)
+}
+
+/// Parses a WebAssembly name map.
+/// The entries must be ordered by strictly increasing index.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-maps>
+pub fn parse_name_map(input: &[u8]) -> ParseResult<'_, NameMap> {
+    map_res(parse_vector(parse_name_association), ordered::<Name>)(input)
+}
+
+/// Parses a WebAssembly indirect name map.
+/// The entries must be ordered by strictly increasing index.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/custom.html#name-maps>
+pub fn parse_indirect_name_map(input: &[u8]) -> ParseResult<'_, IndirectNameMap> {
+    map_res(
+        parse_vector(parse_indirect_name_association),
+        ordered::<NameMap>,
+    )(input)
+}
+
+/// Parses an index and the name associated with it.
+fn parse_name_association(input: &[u8]) -> ParseResult<'_, (u32, Name)> {
+    tuple((parse_u32, parse_name))(input)
+}
+
+/// Parses an index and the name map associated with it.
+fn parse_indirect_name_association(input: &[u8]) -> ParseResult<'_, (u32, NameMap)> {
+    tuple((parse_u32, parse_name_map))(input)
+}
+
+/// Collects the given associations into a map, requiring strictly increasing indices.
+fn ordered<V>(entries: Vec<(u32, V)>) -> Result<BTreeMap<u32, V>, ErrorKind> {
+    if entries.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+        Ok(entries.into_iter().collect())
+    } else {
+        Err(ErrorKind::Verify)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_empty_name_section() {
+        let (remaining, names) = parse_name_section(&[]).unwrap();
+
+        assert!(remaining.is_empty());
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn parse_names() {
+        let input = [
+            0x00, 0x04, 0x03, b'm', b'o', b'd', // module
+            0x01, 0x06, 0x01, 0x02, 0x03, b'a', b'd', b'd', // functions
+            0x02, 0x07, 0x01, 0x02, 0x01, 0x00, 0x02, b'l', b'h', // locals
+            0x0B, 0x04, 0x01, 0x00, 0x01, b'e', // tags
+        ];
+        let (remaining, names) = parse_name_section(&input).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(names.module(), Some(&Name::from("mod")));
+        assert_eq!(names.function(2), Some(&Name::from("add")));
+        assert_eq!(names.local(2, 0), Some(&Name::from("lh")));
+        assert_eq!(names.tags().get(&0), Some(&Name::from("e")));
+        assert!(names.types().is_empty());
+    }
+
+    #[test]
+    fn parse_names_out_of_order() {
+        let input = [0x01, 0x01, 0x00, 0x00, 0x01, 0x00];
+
+        assert!(parse_name_section(&input).is_err());
+    }
+
+    #[test]
+    fn parse_name_map_duplicate_index() {
+        let input = [0x02, 0x01, 0x01, b'a', 0x01, 0x01, b'b'];
+
+        assert!(parse_name_map(&input).is_err());
+    }
+}
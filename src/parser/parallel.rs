@@ -0,0 +1,274 @@
+use crate::parser::errors::{InputError, ParseResult};
+use crate::parser::module::parse_code;
+use crate::parser::sections::parse_section;
+use crate::parser::values::parse_u32;
+use crate::{Expression, ModuleSection, ResultType};
+use nom::bytes::complete::take;
+use nom::combinator::{opt, recognize};
+use std::num::NonZeroUsize;
+use std::{mem, thread};
+
+/// The number of bytes of function bodies per thread,
+/// below which spawning another thread costs more than it saves.
+/// Decoding this many bytes takes far longer than spawning a thread (a few microseconds),
+/// so the threads spawned for each code section cost little next to the work they do.
+const MINIMUM_CHUNK_SIZE: usize = 16 * 1024;
+
+/// The locals and body of every function in a code section, in order.
+type Code = Vec<(ResultType, Expression)>;
+
+/// Parses a WebAssembly code section, decoding its entries on multiple threads.
+/// The output and errors are identical to those of the sequential parser.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
+pub(crate) fn parse_parallel_code_section(input: &[u8]) -> ParseResult<'_, Option<Code>> {
+    opt(parse_section(ModuleSection::Code, parse_codes))(input)
+}
+
+/// Splits the vector of code entries by their sizes, then decodes the locals and body of each entry on multiple threads.
+/// When more than one entry is invalid, the error of the first one is returned,
+/// whether it occurred while splitting or decoding the entries.
+fn parse_codes(input: &[u8]) -> ParseResult<'_, Code> {
+    let (mut remaining, length) = parse_u32(input)?;
+    let mut entries = Vec::new();
+    let mut error = None;
+
+    for _ in 0..length {
+        match parse_entry(remaining) {
+            Ok((input, entry)) => {
+                remaining = input;
+                entries.push(entry);
+            }
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+
+    let size: usize = entries.iter().map(|entry| entry.len()).sum();
+    let threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(size / MINIMUM_CHUNK_SIZE);
+    let codes = decode_entries(entries, threads)?;
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok((remaining, codes)),
+    }
+}
+
+/// Parses the bytes of a code entry, including its size, without decoding the entry.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
+fn parse_entry(input: &[u8]) -> ParseResult<'_, &[u8]> {
+    recognize(|input| {
+        let (input, size) = parse_u32(input)?;
+
+        take(size as usize)(input)
+    })(input)
+}
+
+/// Decodes code entries on up to the given number of threads.
+/// Each thread decodes a contiguous chunk of the entries, so the results are joined in order.
+/// When more than one entry is invalid, the error of the first one is returned.
+///
+/// The threads are scoped to the call rather than kept in a pool,
+/// so that they can borrow the entries and return errors that borrow the input.
+/// A pool shared between calls would only accept jobs that own their data,
+/// requiring a copy of every code section parsed.
+/// Since each thread decodes at least `MINIMUM_CHUNK_SIZE` bytes, spawning it is cheap by comparison
+/// (see the `code section` group of the `parser` benchmark).
+fn decode_entries(entries: Vec<&[u8]>, threads: usize) -> Result<Code, nom::Err<InputError<'_>>> {
+    let threads = threads.min(entries.len());
+
+    if threads <= 1 {
+        return decode_chunk(entries);
+    }
+
+    let length = entries.len();
+    let chunks = split(entries, threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || decode_chunk(chunk)))
+            .collect();
+        let mut decoded = Vec::with_capacity(length);
+
+        for handle in handles {
+            let chunk = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+
+            decoded.extend(chunk);
+        }
+
+        Ok(decoded)
+    })
+}
+
+/// Decodes a chunk of code entries in order, stopping at the first invalid entry.
+fn decode_chunk(entries: Vec<&[u8]>) -> Result<Code, nom::Err<InputError<'_>>> {
+    entries
+        .into_iter()
+        .map(|entry| parse_code(entry).map(|(_, code)| code))
+        .collect()
+}
+
+/// Splits code entries into at most the given number of contiguous chunks,
+/// with a similar number of bytes in each.
+fn split(entries: Vec<&[u8]>, chunks: usize) -> Vec<Vec<&[u8]>> {
+    let total: usize = entries.iter().map(|entry| entry.len()).sum();
+    let target = total.div_ceil(chunks).max(1);
+    let mut result = Vec::with_capacity(chunks);
+    let mut chunk = Vec::new();
+    let mut size = 0;
+
+    for entry in entries {
+        size += entry.len();
+        chunk.push(entry);
+
+        if size >= target {
+            result.push(mem::take(&mut chunk));
+            size = 0;
+        }
+    }
+
+    if !chunk.is_empty() {
+        result.push(chunk);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::errors::Expected;
+    use crate::{parse_binary, parse_binary_parallel, BinaryError, ParseError, ValueType};
+
+    fn module(functions: usize) -> Vec<u8> {
+        let mut text = String::from("(module");
+
+        for index in 0..functions {
+            text.push_str(&format!(
+                "(func (export \"f{index}\") (param i32) (result i32) (local i64)
+                    (i32.add (local.get 0) (i32.const {index})))"
+            ));
+        }
+
+        text.push(')');
+
+        wat::parse_str(text).unwrap()
+    }
+
+    fn assert_same_error(bytes: &[u8]) -> BinaryError {
+        match (parse_binary_parallel(bytes), parse_binary(bytes)) {
+            (Err(ParseError::InvalidBinary(actual)), Err(ParseError::InvalidBinary(expected))) => {
+                assert_eq!(actual, expected);
+
+                actual
+            }
+            results => panic!("unexpected results: {:?}", results),
+        }
+    }
+
+    #[test]
+    fn decode_entries_in_order() {
+        let entries: Vec<&[u8]> = vec![
+            b"\x02\x00\x0B",
+            b"\x03\x00\x01\x0B",
+            b"\x05\x00\x41\x01\x1A\x0B",
+            b"\x03\x00\x00\x0B",
+            b"\x04\x01\x01\x7F\x0B",
+        ];
+        let sequential = decode_entries(entries.clone(), 1).unwrap();
+
+        assert_eq!(sequential[4].0.kinds(), &[ValueType::I32]);
+
+        for threads in 2..=6 {
+            assert_eq!(
+                decode_entries(entries.clone(), threads).unwrap(),
+                sequential
+            );
+        }
+    }
+
+    #[test]
+    fn decode_entries_first_error() {
+        let first: &[u8] = b"\x04\x00\x01\xFF\x0B";
+        let entries = vec![
+            &b"\x02\x00\x0B"[..],
+            first,
+            b"\x03\x00\x01\x0B",
+            b"\x03\x00\x0B\x0B",
+        ];
+
+        for threads in 1..=4 {
+            match decode_entries(entries.clone(), threads) {
+                Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+                    assert_eq!(error, InputError::new(&first[3..], Expected::Opcode))
+                }
+                result => panic!("unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn split_by_size() {
+        let entries: Vec<&[u8]> = vec![b"\x01\x01\x01\x0B", b"\x0B", b"\x0B", b"\x01\x0B"];
+        let chunks: Vec<usize> = split(entries, 2).iter().map(Vec::len).collect();
+
+        assert_eq!(chunks, vec![1, 3]);
+    }
+
+    #[test]
+    fn invalid_body_before_invalid_locals() {
+        let error = assert_same_error(
+            b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x03\x03\x02\x00\x00\
+            \x0A\x0A\x02\x03\x00\xFF\x0B\x04\x01\x01\x55\x0B",
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "unknown opcode 0xFF at offset 0x18 in function 0"
+        );
+    }
+
+    #[test]
+    fn invalid_body_before_truncated_entry() {
+        let error = assert_same_error(
+            b"\x00\x61\x73\x6D\x01\x00\x00\x00\
+            \x01\x04\x01\x60\x00\x00\
+            \x03\x03\x02\x00\x00\
+            \x0A\x08\x02\x03\x00\xFF\x0B\x05\x00\x0B",
+        );
+
+        assert_eq!(error.function(), Some(0));
+        assert_eq!(error.expected(), Expected::Opcode);
+    }
+
+    #[test]
+    fn parse_large_module() {
+        let bytes = module(10000);
+
+        assert_eq!(
+            parse_binary_parallel(&bytes).unwrap(),
+            parse_binary(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_module() {
+        let mut bytes = module(10000);
+        let end = bytes.len() - 1;
+        let position = bytes[..end].iter().rposition(|byte| *byte == 0x6A).unwrap();
+
+        bytes[position] = 0xFF;
+
+        assert_same_error(&bytes);
+    }
+}
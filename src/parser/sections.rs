@@ -1,64 +1,98 @@
+use crate::parser::errors::{consume_all, InputError, ParseResult};
 use crate::parser::module::{
-    parse_code, parse_data, parse_element, parse_export, parse_global, parse_import, parse_memory,
-    parse_start, parse_table,
+    parse_borrowed_data, parse_borrowed_export, parse_borrowed_import, parse_code, parse_data,
+    parse_element, parse_export, parse_global, parse_import, parse_lazy_code, parse_memory,
+    parse_start, parse_table, parse_tag,
 };
-use crate::parser::types::parse_function_type;
-use crate::parser::values::{match_byte, parse_name, parse_u32, parse_vector};
+use crate::parser::types::parse_recursive_type;
+use crate::parser::values::{match_byte, parse_borrowed_name, parse_u32, parse_vector};
 use crate::{
-    Custom, Data, Element, Export, Expression, FunctionType, Global, Import, Memory, ModuleSection,
-    ResultType, Start, Table, TypeIndex,
+    BorrowedCustom, BorrowedData, BorrowedExport, BorrowedImport, Custom, Data, Element, Export,
+    Expression, Global, Import, Memory, ModuleSection, RecursiveType, ResultType, Start, Table,
+    Tag, TypeIndex,
 };
 use nom::bytes::complete::take;
-use nom::combinator::{all_consuming, map, map_parser, opt, rest};
+use nom::combinator::{cut, map, map_parser, opt, rest};
 use nom::multi::fold_many1;
 use nom::sequence::tuple;
-use nom::{IResult, Parser};
+use nom::Parser;
 
 /// Parses a WebAssembly custom section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-customsec>
-pub fn parse_custom_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Custom>>> {
+pub fn parse_custom_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Custom>>> {
+    parse_custom_sections(map(parse_custom_content, Custom::from))(input)
+}
+
+/// Parses a WebAssembly custom section, borrowing its name and contents from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-customsec>
+pub fn parse_borrowed_custom_section(
+    input: &[u8],
+) -> ParseResult<'_, Option<Vec<BorrowedCustom<'_>>>> {
+    parse_custom_sections(parse_custom_content)(input)
+}
+
+/// Parses a group of consecutive custom sections with the given parser for their contents.
+fn parse_custom_sections<'input, O, P>(
+    parser: P,
+) -> impl FnMut(&'input [u8]) -> ParseResult<'input, Option<Vec<O>>>
+where
+    P: nom::Parser<&'input [u8], O, InputError<'input>>,
+{
     opt(fold_many1(
-        parse_section(ModuleSection::Custom, parse_custom_content),
+        parse_section(ModuleSection::Custom, parser),
         Vec::new,
         |mut accumulator, item| {
             accumulator.push(item);
             accumulator
         },
-    ))(input)
+    ))
 }
 
 /// Parses the custom content (name and bytes) of a custom section.
-fn parse_custom_content(input: &[u8]) -> IResult<&[u8], Custom> {
-    map(tuple((parse_name, rest)), |(name, contents)| {
-        Custom::new(name, Vec::from(contents))
+fn parse_custom_content(input: &[u8]) -> ParseResult<'_, BorrowedCustom<'_>> {
+    map(tuple((parse_borrowed_name, rest)), |(name, contents)| {
+        BorrowedCustom::new(name, contents)
     })(input)
 }
 
 /// Parses a WebAssembly type section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-typesec>
-pub fn parse_type_section(input: &[u8]) -> IResult<&[u8], Option<Vec<FunctionType>>> {
+pub fn parse_type_section(input: &[u8]) -> ParseResult<'_, Option<Vec<RecursiveType>>> {
     opt(parse_section(
         ModuleSection::Type,
-        parse_vector(parse_function_type),
+        parse_vector(parse_recursive_type),
     ))(input)
 }
 
 /// Parses a WebAssembly import section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-importsec>
-pub fn parse_import_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Import>>> {
+pub fn parse_import_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Import>>> {
     opt(parse_section(
         ModuleSection::Import,
         parse_vector(parse_import),
     ))(input)
 }
 
+/// Parses a WebAssembly import section, borrowing the module and name of its imports from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#binary-importsec>
+pub fn parse_borrowed_import_section(
+    input: &[u8],
+) -> ParseResult<'_, Option<Vec<BorrowedImport<'_>>>> {
+    opt(parse_section(
+        ModuleSection::Import,
+        parse_vector(parse_borrowed_import),
+    ))(input)
+}
+
 /// Parses a WebAssembly function section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#function-section>
-pub fn parse_function_section(input: &[u8]) -> IResult<&[u8], Option<Vec<TypeIndex>>> {
+pub fn parse_function_section(input: &[u8]) -> ParseResult<'_, Option<Vec<TypeIndex>>> {
     opt(parse_section(
         ModuleSection::Function,
         parse_vector(parse_u32),
@@ -68,7 +102,7 @@ pub fn parse_function_section(input: &[u8]) -> IResult<&[u8], Option<Vec<TypeInd
 /// Parses a WebAssembly table section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#table-section>
-pub fn parse_table_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Table>>> {
+pub fn parse_table_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Table>>> {
     opt(parse_section(
         ModuleSection::Table,
         parse_vector(parse_table),
@@ -78,17 +112,24 @@ pub fn parse_table_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Table>>> {
 /// Parses a WebAssembly memory section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#memory-section>
-pub fn parse_memory_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Memory>>> {
+pub fn parse_memory_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Memory>>> {
     opt(parse_section(
         ModuleSection::Memory,
         parse_vector(parse_memory),
     ))(input)
 }
 
+/// Parses a WebAssembly tag section.
+///
+/// See <https://webassembly.github.io/exception-handling/core/binary/modules.html#tag-section>
+pub fn parse_tag_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Tag>>> {
+    opt(parse_section(ModuleSection::Tag, parse_vector(parse_tag)))(input)
+}
+
 /// Parses a WebAssembly global section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#global-section>
-pub fn parse_global_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Global>>> {
+pub fn parse_global_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Global>>> {
     opt(parse_section(
         ModuleSection::Global,
         parse_vector(parse_global),
@@ -98,38 +139,60 @@ pub fn parse_global_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Global>>>
 /// Parses a WebAssembly data count section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#data-count-section>
-pub fn parse_data_count_section(input: &[u8]) -> IResult<&[u8], Option<u32>> {
+pub fn parse_data_count_section(input: &[u8]) -> ParseResult<'_, Option<u32>> {
     opt(parse_section(ModuleSection::DataCount, parse_u32))(input)
 }
 
 /// Parses a WebAssembly data section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#data-section>
-pub fn parse_data_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Data>>> {
+pub fn parse_data_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Data>>> {
     opt(parse_section(ModuleSection::Data, parse_vector(parse_data)))(input)
 }
 
+/// Parses a WebAssembly data section, borrowing the initializers of its segments from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#data-section>
+pub fn parse_borrowed_data_section(input: &[u8]) -> ParseResult<'_, Option<Vec<BorrowedData<'_>>>> {
+    opt(parse_section(
+        ModuleSection::Data,
+        parse_vector(parse_borrowed_data),
+    ))(input)
+}
+
 /// Parses a WebAssembly start section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#start-section>
-pub fn parse_start_section(input: &[u8]) -> IResult<&[u8], Option<Start>> {
+pub fn parse_start_section(input: &[u8]) -> ParseResult<'_, Option<Start>> {
     opt(parse_section(ModuleSection::Start, parse_start))(input)
 }
 
 /// Parses a WebAssembly export section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#export-section>
-pub fn parse_export_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Export>>> {
+pub fn parse_export_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Export>>> {
     opt(parse_section(
         ModuleSection::Export,
         parse_vector(parse_export),
     ))(input)
 }
 
+/// Parses a WebAssembly export section, borrowing the names of its exports from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#export-section>
+pub fn parse_borrowed_export_section(
+    input: &[u8],
+) -> ParseResult<'_, Option<Vec<BorrowedExport<'_>>>> {
+    opt(parse_section(
+        ModuleSection::Export,
+        parse_vector(parse_borrowed_export),
+    ))(input)
+}
+
 /// Parses a WebAssembly element section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#element-section>
-pub fn parse_element_section(input: &[u8]) -> IResult<&[u8], Option<Vec<Element>>> {
+pub fn parse_element_section(input: &[u8]) -> ParseResult<'_, Option<Vec<Element>>> {
     opt(parse_section(
         ModuleSection::Element,
         parse_vector(parse_element),
@@ -142,32 +205,48 @@ type Code = Vec<(ResultType, Expression)>;
 /// Parses a WebAssembly code section.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
-pub fn parse_code_section(input: &[u8]) -> IResult<&[u8], Option<Code>> {
+pub fn parse_code_section(input: &[u8]) -> ParseResult<'_, Option<Code>> {
     opt(parse_section(ModuleSection::Code, parse_vector(parse_code)))(input)
 }
 
+/// Type alias for a code section entry with an encoded body.
+type LazyCode<'input> = Vec<(ResultType, &'input [u8])>;
+
+/// Parses a WebAssembly code section, leaving the bodies of the functions encoded.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#code-section>
+pub fn parse_lazy_code_section(input: &[u8]) -> ParseResult<'_, Option<LazyCode<'_>>> {
+    opt(parse_section(
+        ModuleSection::Code,
+        parse_vector(parse_lazy_code),
+    ))(input)
+}
+
 /// Parses a section with the given identifier.
+/// Once the identifier matches, errors in the contents of the section are not recoverable.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#sections>
-fn parse_section<'input, O, P>(
+pub fn parse_section<'input, O, P>(
     section: ModuleSection,
     parser: P,
-) -> impl FnMut(&'input [u8]) -> IResult<&'input [u8], O>
+) -> impl FnMut(&'input [u8]) -> ParseResult<'input, O>
 where
-    P: Parser<&'input [u8], O, nom::error::Error<&'input [u8]>>,
+    P: Parser<&'input [u8], O, InputError<'input>>,
 {
-    map_parser(parse_section_raw(section), all_consuming(parser))
+    map_parser(parse_section_raw(section), cut(consume_all(parser)))
 }
 
 /// Parses the raw bytes of a section with the given identifier.
 /// Validates the section identified and length.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/modules.html#sections>
-fn parse_section_raw(section: ModuleSection) -> impl Fn(&[u8]) -> IResult<&[u8], &[u8]> {
+fn parse_section_raw(section: ModuleSection) -> impl Fn(&[u8]) -> ParseResult<'_, &[u8]> {
     move |input| {
         let (input, _) = match_byte(section as u8)(input)?;
-        let (input, length) = parse_u32(input)?;
+        cut(|input| {
+            let (input, length) = parse_u32(input)?;
 
-        take(length)(input)
+            take(length)(input)
+        })(input)
     }
 }
@@ -0,0 +1,712 @@
+use crate::parser::errors::{expect, BinaryError, Expected, InputError, ParseResult};
+use crate::parser::module::{parse_code, parse_data};
+use crate::parser::sections::{
+    parse_custom_section, parse_data_count_section, parse_element_section, parse_export_section,
+    parse_function_section, parse_global_section, parse_import_section, parse_memory_section,
+    parse_start_section, parse_table_section, parse_tag_section, parse_type_section,
+};
+use crate::parser::values::parse_u32;
+use crate::parser::{module_section, ParseError, PREAMBLE, VERSION};
+use crate::{
+    Custom, Data, DataIndex, Element, Export, Function, FunctionIndex, Global, Import,
+    ImportDescription, Memory, ModuleSection, RecursiveType, Start, Table, Tag, TypeIndex,
+};
+use nom::bytes::complete::tag;
+use nom::number::complete::u8 as parse_byte;
+use nom::sequence::tuple;
+use std::io::{ErrorKind, Read};
+
+/// The order in which the non-custom sections of a module must appear in the binary format.
+const SECTION_ORDER: [ModuleSection; 13] = [
+    ModuleSection::Type,
+    ModuleSection::Import,
+    ModuleSection::Function,
+    ModuleSection::Table,
+    ModuleSection::Memory,
+    ModuleSection::Tag,
+    ModuleSection::Global,
+    ModuleSection::Export,
+    ModuleSection::Start,
+    ModuleSection::Element,
+    ModuleSection::DataCount,
+    ModuleSection::Code,
+    ModuleSection::Data,
+];
+
+/// The number of bytes read from a reader at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// An event produced by the streaming parser as the bytes of a module arrive.
+/// Most sections produce a single event once all of their bytes have arrived,
+/// while the code and data sections produce an event per entry.
+///
+/// See <https://webassembly.github.io/spec/core/binary/modules.html#sections>
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A custom section, along with the last non-custom section that precedes it
+    /// (`ModuleSection::Custom` for custom sections at the start of the module).
+    /// Name sections are not decoded; use `parse_names` on their bytes to do so.
+    Custom(ModuleSection, Custom),
+    Types(Vec<RecursiveType>),
+    Imports(Vec<Import>),
+    /// The type indices of the functions defined by the module.
+    Functions(Vec<TypeIndex>),
+    Tables(Vec<Table>),
+    Memories(Vec<Memory>),
+    Tags(Vec<Tag>),
+    Globals(Vec<Global>),
+    Exports(Vec<Export>),
+    Start(Start),
+    Elements(Vec<Element>),
+    DataCount(u32),
+    /// The start of the code section, along with the number of function bodies in the section.
+    CodeStart(u32),
+    /// A function defined by the module, along with its index in the function index space
+    /// (i.e., after any imported functions).
+    Function(FunctionIndex, Function),
+    /// The start of the data section, along with the number of segments in the section.
+    DataStart(u32),
+    /// A data segment, along with its index.
+    Data(DataIndex, Data),
+    /// The end of the module.
+    End,
+}
+
+/// The part of a module the streaming parser expects next.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum State {
+    Header,
+    Sections,
+    Entries(ModuleSection, Entries),
+    Done,
+}
+
+/// The progress of the streaming parser through the entries of a code or data section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Entries {
+    end: usize,
+    count: Option<u32>,
+    index: u32,
+}
+
+/// An incremental parser of the WebAssembly binary format.
+/// Bytes are pushed into the parser in chunks of any size as they arrive,
+/// and events are pulled from the parser as soon as enough bytes have arrived to produce them.
+/// Only the bytes of the event currently being parsed are buffered.
+/// Sections and function bodies are parsed once all of their bytes have arrived, as given by their size,
+/// so each byte is parsed once no matter how the input is split into chunks.
+///
+/// The function and code sections must have matching lengths, as with `parse_binary`.
+///
+/// See <https://webassembly.github.io/spec/core/binary/index.html>
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{Event, StreamParser};
+///
+/// let mut parser = StreamParser::new();
+///
+/// parser.push(b"\x00\x61\x73\x6D\x01\x00");
+/// assert_eq!(parser.next_event().unwrap(), None);
+///
+/// parser.push(b"\x00\x00\x08\x01\x00");
+/// parser.finish();
+///
+/// assert_eq!(parser.next_event().unwrap(), Some(Event::Start(0.into())));
+/// assert_eq!(parser.next_event().unwrap(), Some(Event::End));
+/// assert_eq!(parser.next_event().unwrap(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamParser {
+    buffer: Vec<u8>,
+    position: usize,
+    offset: usize,
+    finished: bool,
+    state: State,
+    previous: ModuleSection,
+    signatures: Option<Vec<TypeIndex>>,
+    imported_functions: u32,
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamParser {
+    /// Creates a new streaming parser with no input.
+    pub fn new() -> Self {
+        StreamParser {
+            buffer: Vec::new(),
+            position: 0,
+            offset: 0,
+            finished: false,
+            state: State::Header,
+            previous: ModuleSection::Custom,
+            signatures: None,
+            imported_functions: 0,
+        }
+    }
+
+    /// Appends the given bytes to the input of the parser.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.drain(..self.position);
+        self.offset += self.position;
+        self.position = 0;
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Marks the end of the input, after which an incomplete module is an error.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// The number of bytes of the input that have been parsed into events.
+    pub fn offset(&self) -> usize {
+        self.offset + self.position
+    }
+
+    /// Parses the next event from the input.
+    /// Returns `None` when more input is needed to produce the next event,
+    /// or once the end of the module has been produced.
+    pub fn next_event(&mut self) -> Result<Option<Event>, ParseError> {
+        loop {
+            match self.state {
+                State::Header => {
+                    let header = self.parse(
+                        PREAMBLE.len() + VERSION.len(),
+                        |input| {
+                            tuple((
+                                expect(Expected::Preamble, tag(PREAMBLE)),
+                                expect(Expected::Version, tag(VERSION)),
+                            ))(input)
+                            .map(|(input, _)| (input, ()))
+                        },
+                        None,
+                        None,
+                    )?;
+
+                    match header {
+                        Some(_) => self.state = State::Sections,
+                        None => return Ok(None),
+                    }
+                }
+                State::Sections => return self.next_section(),
+                State::Entries(section, entries) => match self.next_entry(section, entries)? {
+                    Some(Some(event)) => return Ok(Some(event)),
+                    Some(None) => continue,
+                    None => return Ok(None),
+                },
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    /// Parses the next section of the module, or the start of a code or data section.
+    fn next_section(&mut self) -> Result<Option<Event>, ParseError> {
+        if self.remaining().is_empty() && self.finished {
+            if let Some(signatures) = &self.signatures {
+                return Err(ParseError::MismatchedFunctionParts(
+                    None,
+                    Some(signatures.len()),
+                ));
+            }
+
+            self.state = State::Done;
+
+            return Ok(Some(Event::End));
+        }
+
+        let (id, length, header) = match tuple((parse_byte, parse_u32))(self.remaining()) {
+            Ok((remaining, (id, length))) => (id, length, self.remaining().len() - remaining.len()),
+            Err(_) if !self.finished && self.remaining().len() < 6 => return Ok(None),
+            Err(error) => return Err(self.error(error, None, None)),
+        };
+        let end = self.offset() + header + length as usize;
+        let section = match module_section(id) {
+            Some(ModuleSection::Custom) => ModuleSection::Custom,
+            Some(section) if rank(section) > rank(self.previous) => section,
+            _ => {
+                let error = nom::Err::Error(InputError::new(self.remaining(), Expected::Section));
+
+                return Err(self.error(error, None, None));
+            }
+        };
+
+        if matches!(section, ModuleSection::Code | ModuleSection::Data) {
+            self.position += header;
+            self.previous = section;
+            self.state = State::Entries(
+                section,
+                Entries {
+                    end,
+                    count: None,
+                    index: 0,
+                },
+            );
+
+            return self.next_event();
+        }
+
+        let previous = self.previous;
+        let event = self.parse_complete(
+            end,
+            move |input| match section {
+                ModuleSection::Custom => parse_custom_section(input).map(|(input, sections)| {
+                    let custom = sections.and_then(|sections| sections.into_iter().next());
+
+                    (input, custom.map(|custom| Event::Custom(previous, custom)))
+                }),
+                ModuleSection::Type => event(parse_type_section(input), Event::Types),
+                ModuleSection::Import => event(parse_import_section(input), Event::Imports),
+                ModuleSection::Function => event(parse_function_section(input), Event::Functions),
+                ModuleSection::Table => event(parse_table_section(input), Event::Tables),
+                ModuleSection::Memory => event(parse_memory_section(input), Event::Memories),
+                ModuleSection::Tag => event(parse_tag_section(input), Event::Tags),
+                ModuleSection::Global => event(parse_global_section(input), Event::Globals),
+                ModuleSection::Export => event(parse_export_section(input), Event::Exports),
+                ModuleSection::Start => event(parse_start_section(input), Event::Start),
+                ModuleSection::Element => event(parse_element_section(input), Event::Elements),
+                ModuleSection::DataCount => {
+                    event(parse_data_count_section(input), Event::DataCount)
+                }
+                ModuleSection::Code | ModuleSection::Data => Ok((input, None)),
+            },
+            Some(section),
+            None,
+        )?;
+
+        match event.flatten() {
+            Some(event) => {
+                if section != ModuleSection::Custom {
+                    self.previous = section;
+                }
+
+                match &event {
+                    Event::Imports(imports) => {
+                        self.imported_functions = imports
+                            .iter()
+                            .filter(|import| {
+                                matches!(import.description(), ImportDescription::Function(_))
+                            })
+                            .count() as u32;
+                    }
+                    Event::Functions(signatures) => self.signatures = Some(signatures.clone()),
+                    _ => {}
+                }
+
+                Ok(Some(event))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the next entry of a code or data section.
+    /// Returns `Some(None)` at the end of the section.
+    fn next_entry(
+        &mut self,
+        section: ModuleSection,
+        mut entries: Entries,
+    ) -> Result<Option<Option<Event>>, ParseError> {
+        let count = match entries.count {
+            Some(count) => count,
+            None => {
+                let count = match self.parse(entries.end, parse_u32, Some(section), None)? {
+                    Some(count) => count,
+                    None => return Ok(None),
+                };
+
+                entries.count = Some(count);
+                self.state = State::Entries(section, entries);
+
+                if section == ModuleSection::Data {
+                    return Ok(Some(Some(Event::DataStart(count))));
+                }
+
+                let signatures = self.signatures.as_ref().map(Vec::len);
+
+                if signatures != Some(count as usize) {
+                    return Err(ParseError::MismatchedFunctionParts(
+                        Some(count as usize),
+                        signatures,
+                    ));
+                }
+
+                return Ok(Some(Some(Event::CodeStart(count))));
+            }
+        };
+
+        if entries.index == count {
+            if self.offset() != entries.end {
+                let error = nom::Err::Error(InputError::new(self.remaining(), Expected::End));
+
+                return Err(self.error(error, Some(section), None));
+            }
+
+            if section == ModuleSection::Code {
+                self.signatures = None;
+            }
+
+            self.state = State::Sections;
+
+            return Ok(Some(None));
+        }
+
+        let index = entries.index;
+        let event = match section {
+            ModuleSection::Code => {
+                let function = self.imported_functions + index;
+                let kind = self
+                    .signatures
+                    .as_ref()
+                    .and_then(|signatures| signatures.get(index as usize))
+                    .copied()
+                    .unwrap_or_default();
+                let end = match parse_u32(self.remaining()) {
+                    Ok((remaining, size)) => {
+                        let header = self.remaining().len() - remaining.len();
+
+                        entries.end.min(self.offset() + header + size as usize)
+                    }
+                    Err(_) if !self.finished && self.remaining().len() < 5 => return Ok(None),
+                    Err(_) => entries.end,
+                };
+                let code = self.parse_complete(end, parse_code, Some(section), Some(function))?;
+
+                code.map(|(locals, body)| {
+                    Event::Function(function, Function::new(kind, locals, body))
+                })
+            }
+            _ => self
+                .parse(entries.end, parse_data, Some(section), None)?
+                .map(|data| Event::Data(index, data)),
+        };
+
+        if event.is_some() {
+            entries.index += 1;
+            self.state = State::Entries(section, entries);
+        }
+
+        Ok(event.map(Some))
+    }
+
+    /// Applies the given parser to the buffered input, up to the given offset of the input.
+    /// Returns `None` if the parser fails before all the bytes up to the offset have arrived.
+    fn parse<O>(
+        &mut self,
+        end: usize,
+        parser: impl for<'input> Fn(&'input [u8]) -> ParseResult<'input, O>,
+        section: Option<ModuleSection>,
+        function: Option<FunctionIndex>,
+    ) -> Result<Option<O>, ParseError> {
+        let available = (end - self.offset).min(self.buffer.len());
+        let input = &self.buffer[self.position..available.max(self.position)];
+
+        match parser(input) {
+            Ok((remaining, output)) => {
+                self.position += input.len() - remaining.len();
+                Ok(Some(output))
+            }
+            Err(_) if !self.finished && available < end - self.offset => Ok(None),
+            Err(error) => Err(self.error(error, section, function)),
+        }
+    }
+
+    /// Applies the given parser to the buffered input once all the bytes up to the given offset of the input have arrived,
+    /// or the input has finished.
+    /// Waiting for the whole input avoids re-parsing a large section or function body from its start on every push.
+    fn parse_complete<O>(
+        &mut self,
+        end: usize,
+        parser: impl for<'input> Fn(&'input [u8]) -> ParseResult<'input, O>,
+        section: Option<ModuleSection>,
+        function: Option<FunctionIndex>,
+    ) -> Result<Option<O>, ParseError> {
+        if !self.finished && self.offset + self.buffer.len() < end {
+            return Ok(None);
+        }
+
+        self.parse(end, parser, section, function)
+    }
+
+    /// The buffered input that has yet to be parsed.
+    fn remaining(&self) -> &[u8] {
+        &self.buffer[self.position..]
+    }
+
+    /// Locates an error in the buffered input within the given section and function.
+    fn error(
+        &self,
+        error: nom::Err<InputError<'_>>,
+        section: Option<ModuleSection>,
+        function: Option<FunctionIndex>,
+    ) -> ParseError {
+        ParseError::InvalidBinary(
+            BinaryError::new(&self.buffer, error)
+                .offset_by(self.offset)
+                .within(section, function),
+        )
+    }
+}
+
+/// Maps the contents of a parsed section to an event.
+fn event<'input, T>(
+    result: ParseResult<'input, Option<T>>,
+    event: fn(T) -> Event,
+) -> ParseResult<'input, Option<Event>> {
+    result.map(|(input, contents)| (input, contents.map(event)))
+}
+
+/// The position of a non-custom section in the order required by the binary format.
+fn rank(section: ModuleSection) -> usize {
+    SECTION_ORDER
+        .iter()
+        .position(|other| *other == section)
+        .map(|position| position + 1)
+        .unwrap_or_default()
+}
+
+/// Parses a WebAssembly module in the binary format from a reader, producing events as the bytes are read.
+/// The iterator ends after the end of the module or the first error.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{parse_stream, Event};
+///
+/// let bytes: &[u8] = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x04\x01\x02\x00\x0B";
+/// let events = parse_stream(bytes).collect::<Result<Vec<Event>, _>>().unwrap();
+///
+/// assert_eq!(events.len(), 5);
+/// assert!(matches!(events[3], Event::Function(0, _)));
+/// assert_eq!(events[4], Event::End);
+/// ```
+pub fn parse_stream<R: Read>(reader: R) -> Events<R> {
+    Events {
+        reader,
+        parser: StreamParser::new(),
+        chunk: vec![0; CHUNK_SIZE],
+        done: false,
+    }
+}
+
+/// An iterator over the events of a WebAssembly module read in the binary format.
+pub struct Events<R> {
+    reader: R,
+    parser: StreamParser,
+    chunk: Vec<u8>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for Events<R> {
+    type Item = Result<Event, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.parser.next_event() {
+                Ok(Some(event)) => {
+                    self.done = event == Event::End;
+
+                    return Some(Ok(event));
+                }
+                Ok(None) => match self.reader.read(&mut self.chunk) {
+                    Ok(0) => self.parser.finish(),
+                    Ok(length) => self.parser.push(&self.chunk[..length]),
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                    Err(error) => {
+                        self.done = true;
+
+                        return Some(Err(ParseError::Read(error)));
+                    }
+                },
+                Err(error) => {
+                    self.done = true;
+
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_binary;
+
+    const MODULE: &str = r#"
+        (module
+            (import "env" "log" (func (param i32)))
+            (memory 1)
+            (func (export "run") (param i32) (result i32)
+                local.get 0
+                call 0
+                local.get 0
+                i32.const 1
+                i32.add)
+            (func (result i32) i32.const 42)
+            (data (i32.const 0) "hello")
+            (data "world"))
+    "#;
+
+    fn events(bytes: &[u8], chunk: usize) -> Result<Vec<Event>, ParseError> {
+        let mut parser = StreamParser::new();
+        let mut events = Vec::new();
+
+        for chunk in bytes.chunks(chunk) {
+            parser.push(chunk);
+
+            while let Some(event) = parser.next_event()? {
+                events.push(event);
+            }
+        }
+
+        parser.finish();
+
+        while let Some(event) = parser.next_event()? {
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    fn binary_error(result: Result<Vec<Event>, ParseError>) -> BinaryError {
+        match result {
+            Err(ParseError::InvalidBinary(error)) => error,
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn stream_matches_binary_parser() {
+        let bytes = wat::parse_str(MODULE).unwrap();
+        let module = parse_binary(&bytes).unwrap();
+        let functions = module.functions().unwrap();
+        let data = module.data().unwrap();
+        let expected = vec![
+            Event::Types(module.types().unwrap().to_vec()),
+            Event::Imports(module.imports().unwrap().to_vec()),
+            Event::Functions(functions.iter().map(Function::kind).collect()),
+            Event::Memories(module.memories().unwrap().to_vec()),
+            Event::Exports(module.exports().unwrap().to_vec()),
+            Event::CodeStart(2),
+            Event::Function(1, functions[0].clone()),
+            Event::Function(2, functions[1].clone()),
+            Event::DataStart(2),
+            Event::Data(0, data[0].clone()),
+            Event::Data(1, data[1].clone()),
+            Event::End,
+        ];
+
+        assert_eq!(events(&bytes, 1).unwrap(), expected);
+        assert_eq!(events(&bytes, bytes.len()).unwrap(), expected);
+        assert_eq!(
+            parse_stream(bytes.as_slice())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn stream_large_sections_byte_by_byte() {
+        let mut text = String::from("(module (func");
+
+        for index in 0..20000 {
+            text.push_str(&format!(" (drop (i32.const {index}))"));
+        }
+
+        text.push(')');
+
+        for index in 0..2000 {
+            text.push_str(&format!(" (export \"f{index}\" (func 0))"));
+        }
+
+        text.push(')');
+
+        let bytes = wat::parse_str(text).unwrap();
+        let module = parse_binary(&bytes).unwrap();
+        let events = events(&bytes, 1).unwrap();
+
+        assert_eq!(
+            events[2],
+            Event::Exports(module.exports().unwrap().to_vec())
+        );
+        assert_eq!(
+            events[4],
+            Event::Function(0, module.functions().unwrap()[0].clone())
+        );
+    }
+
+    #[test]
+    fn stream_errors_match_binary_parser() {
+        let mut bytes = wat::parse_str(MODULE).unwrap();
+        let position = bytes
+            .windows(2)
+            .position(|pair| pair == [0x6A, 0x0B])
+            .unwrap();
+
+        bytes[position] = 0xFF;
+
+        let error = binary_error(events(&bytes, 3));
+
+        assert_eq!(error.function(), Some(1));
+        assert_eq!(error.expected(), Expected::Opcode);
+        assert_eq!(
+            ParseError::InvalidBinary(error).to_string(),
+            parse_binary(&bytes).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn stream_out_of_order_section() {
+        let error = binary_error(events(
+            b"\x00\x61\x73\x6D\x01\x00\x00\x00\x05\x03\x01\x00\x01\x04\x04\x01\x70\x00\x00",
+            4,
+        ));
+
+        assert_eq!(error.offset(), 0x0D);
+        assert_eq!(error.expected(), Expected::Section);
+    }
+
+    #[test]
+    fn stream_truncated() {
+        let error = binary_error(events(
+            b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x05\x01\x60",
+            2,
+        ));
+
+        assert_eq!(error.offset(), 0x0C);
+        assert_eq!(error.section(), Some(ModuleSection::Type));
+        assert_eq!(error.expected(), Expected::More);
+    }
+
+    #[test]
+    fn stream_truncated_code_section() {
+        let bytes = wat::parse_str(
+            "(module (func) (func) (func) (func (drop (i32.const 1)) (drop (i32.const 2))))",
+        )
+        .unwrap();
+        let bytes = &bytes[..bytes.len() - 3];
+        let error = binary_error(events(bytes, 4));
+
+        assert_eq!(error.offset(), 0x27);
+        assert_eq!(error.function(), Some(3));
+        assert_eq!(
+            ParseError::InvalidBinary(error).to_string(),
+            parse_binary(bytes).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn stream_mismatched_function_parts() {
+        let result = events(b"\x00\x61\x73\x6D\x01\x00\x00\x00\x03\x02\x01\x00", 5);
+
+        assert!(matches!(
+            result,
+            Err(ParseError::MismatchedFunctionParts(None, Some(1)))
+        ));
+    }
+}
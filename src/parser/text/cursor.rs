@@ -0,0 +1,301 @@
+//! A cursor over the tokens of the WebAssembly text format.
+
+use crate::parser::text::lexer::{error, Token, TokenKind};
+use crate::parser::text::values::{
+    parse_f32, parse_f64, parse_i32, parse_i64, parse_u32, parse_u64,
+};
+use crate::parser::ParseError;
+use crate::Name;
+
+/// A position in a sequence of tokens, with helpers to consume the S-expression structure
+/// of the text format.
+pub struct Cursor<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a new cursor at the start of the given tokens.
+    pub fn new(tokens: Vec<Token<'a>>) -> Self {
+        Cursor {
+            tokens,
+            position: 0,
+        }
+    }
+
+    /// The index of the next token.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor to the given token index.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Returns true if all tokens have been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// The kind of the token at the given distance from the next token, without consuming it.
+    pub fn peek_at(&self, distance: usize) -> Option<&TokenKind<'a>> {
+        self.tokens
+            .get(self.position + distance)
+            .map(|token| token.kind())
+    }
+
+    /// The kind of the next token, without consuming it.
+    pub fn peek(&self) -> Option<&TokenKind<'a>> {
+        self.peek_at(0)
+    }
+
+    /// Returns true if the next token is a right parenthesis (or the input is exhausted).
+    pub fn at_end_of_list(&self) -> bool {
+        matches!(self.peek(), None | Some(TokenKind::RightParenthesis))
+    }
+
+    /// The next token if it is a keyword, without consuming it.
+    pub fn peek_keyword(&self) -> Option<&'a str> {
+        match self.peek() {
+            Some(TokenKind::Keyword(keyword)) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// The keyword following the next token if the next token is a left parenthesis,
+    /// without consuming either (e.g., `param` for `(param i32)`).
+    pub fn peek_list(&self) -> Option<&'a str> {
+        match (self.peek(), self.peek_at(1)) {
+            (Some(TokenKind::LeftParenthesis), Some(TokenKind::Keyword(keyword))) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the next token opens a list starting with the given keyword.
+    pub fn at_list(&self, keyword: &str) -> bool {
+        self.peek_list() == Some(keyword)
+    }
+
+    /// Returns true if the next token is an identifier or an unsigned integer.
+    pub fn at_index(&self) -> bool {
+        match self.peek() {
+            Some(TokenKind::Identifier(_)) => true,
+            Some(TokenKind::Number(number)) => parse_u32(number).is_some(),
+            _ => false,
+        }
+    }
+
+    /// Consumes the next token.
+    pub fn next(&mut self) -> Result<&Token<'a>, ParseError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    /// Consumes a left parenthesis.
+    pub fn left_parenthesis(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(TokenKind::LeftParenthesis) => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error("expected `(`")),
+        }
+    }
+
+    /// Consumes a right parenthesis.
+    pub fn right_parenthesis(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(TokenKind::RightParenthesis) => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error("expected `)`")),
+        }
+    }
+
+    /// Consumes a left parenthesis followed by the given keyword.
+    pub fn open_list(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if !self.at_list(keyword) {
+            return Err(self.error(format!("expected `({}`", keyword)));
+        }
+
+        self.position += 2;
+
+        Ok(())
+    }
+
+    /// Consumes a keyword.
+    pub fn keyword(&mut self) -> Result<&'a str, ParseError> {
+        match self.peek_keyword() {
+            Some(keyword) => {
+                self.position += 1;
+                Ok(keyword)
+            }
+            None => Err(self.error("expected a keyword")),
+        }
+    }
+
+    /// Consumes the next token if it is the given keyword, returning whether it was consumed.
+    pub fn take_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.peek_keyword() == Some(keyword);
+
+        if matched {
+            self.position += 1;
+        }
+
+        matched
+    }
+
+    /// Consumes the given keyword.
+    pub fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.take_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", keyword)))
+        }
+    }
+
+    /// Consumes the next token if it is an identifier.
+    pub fn identifier(&mut self) -> Option<&'a str> {
+        match self.peek() {
+            Some(TokenKind::Identifier(identifier)) => {
+                let identifier = *identifier;
+
+                self.position += 1;
+
+                Some(identifier)
+            }
+            _ => None,
+        }
+    }
+
+    /// Consumes a number, returning its textual form.
+    /// The float constants 𝗂𝗇𝖿 and 𝗇𝖺𝗇 are lexed as keywords, so they are accepted here as well.
+    pub fn number(&mut self) -> Result<&'a str, ParseError> {
+        match self.peek() {
+            Some(TokenKind::Number(number)) => {
+                let number = *number;
+
+                self.position += 1;
+
+                Ok(number)
+            }
+            Some(TokenKind::Keyword(keyword))
+                if *keyword == "inf" || *keyword == "nan" || keyword.starts_with("nan:") =>
+            {
+                let keyword = *keyword;
+
+                self.position += 1;
+
+                Ok(keyword)
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    /// Consumes a number, interpreting it with the given function.
+    pub fn value<T>(
+        &mut self,
+        kind: &str,
+        interpret: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, ParseError> {
+        let start = self.position;
+        let number = self.number()?;
+
+        interpret(number).ok_or_else(|| {
+            self.position = start;
+            self.error(format!("invalid {} `{}`", kind, number))
+        })
+    }
+
+    /// Consumes an unsigned 32-bit integer.
+    pub fn u32(&mut self) -> Result<u32, ParseError> {
+        self.value("u32", parse_u32)
+    }
+
+    /// Consumes an unsigned 64-bit integer.
+    pub fn u64(&mut self) -> Result<u64, ParseError> {
+        self.value("u64", parse_u64)
+    }
+
+    /// Consumes an uninterpreted 32-bit integer.
+    pub fn i32(&mut self) -> Result<i32, ParseError> {
+        self.value("i32", parse_i32)
+    }
+
+    /// Consumes an uninterpreted 64-bit integer.
+    pub fn i64(&mut self) -> Result<i64, ParseError> {
+        self.value("i64", parse_i64)
+    }
+
+    /// Consumes a 32-bit floating-point number.
+    pub fn f32(&mut self) -> Result<f32, ParseError> {
+        self.value("f32", parse_f32)
+    }
+
+    /// Consumes a 64-bit floating-point number.
+    pub fn f64(&mut self) -> Result<f64, ParseError> {
+        self.value("f64", parse_f64)
+    }
+
+    /// Consumes a string literal.
+    pub fn string(&mut self) -> Result<Vec<u8>, ParseError> {
+        match self.peek() {
+            Some(TokenKind::String(bytes)) => {
+                let bytes = bytes.clone();
+
+                self.position += 1;
+
+                Ok(bytes)
+            }
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    /// Consumes a string literal that must be valid UTF-8.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/values.html#names>
+    pub fn name(&mut self) -> Result<Name, ParseError> {
+        let start = self.position;
+        let bytes = self.string()?;
+
+        String::from_utf8(bytes).map(Name::new).map_err(|_| {
+            self.position = start;
+            self.error("malformed UTF-8 encoding")
+        })
+    }
+
+    /// Skips the remaining tokens of the current list, including its closing parenthesis.
+    pub fn skip_list(&mut self) -> Result<(), ParseError> {
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.next()?.kind() {
+                TokenKind::LeftParenthesis => depth += 1,
+                TokenKind::RightParenthesis => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates a parse error at the position of the next token (or the last token).
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        match self
+            .tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+        {
+            Some(token) => error(token.line(), token.column(), message),
+            None => error(1, 1, message),
+        }
+    }
+}
@@ -0,0 +1,1217 @@
+//! Parse WebAssembly instructions encoded in the text format.
+//!
+//! See <https://webassembly.github.io/spec/core/text/instructions.html>
+
+use crate::parser::text::lexer::TokenKind;
+use crate::parser::text::values::{parse_i16, parse_i8, parse_u32, parse_u64};
+use crate::parser::text::{Space, TextParser};
+use crate::parser::ParseError;
+use crate::{
+    AggregateInstruction, AtomicInstruction, AtomicOperation, CatchClause, ControlInstruction,
+    Expression, FloatType, FloatVectorShape, Instruction, IntegerType, IntegerVectorShape,
+    MemoryArgument, MemoryInstruction, NumberType, NumericInstruction, ParametricInstruction,
+    ReferenceInstruction, SignExtension, TableInstruction, VariableInstruction, VectorHalf,
+    VectorInstruction, VectorShape,
+};
+
+impl<'a> TextParser<'a> {
+    /// Parses a sequence of plain and folded instructions, until the end of the enclosing list
+    /// or a keyword that terminates a block (i.e., 𝖾𝗅𝗌𝖾 or 𝖾𝗇𝖽).
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#instructions>
+    pub(super) fn instructions(&mut self) -> Result<Vec<Instruction>, ParseError> {
+        let mut instructions = Vec::new();
+
+        loop {
+            match self.input.peek() {
+                Some(TokenKind::LeftParenthesis) => self.folded_instruction(&mut instructions)?,
+                Some(TokenKind::Keyword("end" | "else")) => return Ok(instructions),
+                Some(TokenKind::Keyword(_)) => instructions.push(self.plain_instruction()?),
+                _ => return Ok(instructions),
+            }
+        }
+    }
+
+    /// Parses an expression that extends to the end of the enclosing list.
+    pub(super) fn expression(&mut self) -> Result<Expression, ParseError> {
+        let instructions = self.instructions()?;
+
+        if !self.input.at_end_of_list() {
+            return Err(self.input.error("unexpected token in expression"));
+        }
+
+        Ok(Expression::new(instructions))
+    }
+
+    /// Parses a folded instruction, appending its operands (in order) followed by the
+    /// instruction itself to the output.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#folded-instructions>
+    pub(super) fn folded_instruction(
+        &mut self,
+        output: &mut Vec<Instruction>,
+    ) -> Result<(), ParseError> {
+        self.input.left_parenthesis()?;
+
+        match self.input.peek_keyword() {
+            Some(keyword @ ("block" | "loop" | "try_table")) => {
+                self.input.keyword()?;
+
+                let label = self.input.identifier();
+                let kind = self.block_type()?;
+                let catches = self.catch_clauses()?;
+
+                self.push_label(label);
+
+                let body = Expression::new(self.instructions()?);
+
+                self.pop_label();
+                self.input.right_parenthesis()?;
+
+                output.push(match keyword {
+                    "block" => ControlInstruction::Block(kind, body).into(),
+                    "loop" => ControlInstruction::Loop(kind, body).into(),
+                    _ => ControlInstruction::TryTable(kind, catches, body).into(),
+                });
+            }
+            Some("if") => {
+                self.input.keyword()?;
+
+                let label = self.input.identifier();
+                let kind = self.block_type()?;
+
+                while !self.input.at_list("then") {
+                    if self.input.at_end_of_list() {
+                        return Err(self.input.error("expected `(then`"));
+                    }
+
+                    self.folded_instruction(output)?;
+                }
+
+                self.push_label(label);
+                self.input.open_list("then")?;
+
+                let consequent = Expression::new(self.instructions()?);
+
+                self.input.right_parenthesis()?;
+
+                let alternative = if self.input.at_list("else") {
+                    self.input.open_list("else")?;
+
+                    let alternative = Expression::new(self.instructions()?);
+
+                    self.input.right_parenthesis()?;
+
+                    Some(alternative)
+                } else {
+                    None
+                };
+
+                self.pop_label();
+                self.input.right_parenthesis()?;
+
+                output.push(ControlInstruction::If(kind, consequent, alternative).into());
+            }
+            Some(_) => {
+                let instruction = self.plain_instruction()?;
+
+                while !self.input.at_end_of_list() {
+                    self.folded_instruction(output)?;
+                }
+
+                self.input.right_parenthesis()?;
+                output.push(instruction);
+            }
+            None => return Err(self.input.error("expected an instruction")),
+        }
+
+        Ok(())
+    }
+
+    /// Parses a structured instruction in its plain form, which is terminated by 𝖾𝗇𝖽.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#control-instructions>
+    fn block(&mut self, keyword: &str) -> Result<Instruction, ParseError> {
+        let label = self.input.identifier();
+        let kind = self.block_type()?;
+        let catches = self.catch_clauses()?;
+
+        self.push_label(label);
+
+        let body = Expression::new(self.instructions()?);
+        let instruction = match keyword {
+            "block" => ControlInstruction::Block(kind, body),
+            "loop" => ControlInstruction::Loop(kind, body),
+            "if" if self.input.take_keyword("else") => {
+                self.closing_label()?;
+
+                let alternative = Expression::new(self.instructions()?);
+
+                ControlInstruction::If(kind, body, Some(alternative))
+            }
+            "if" => ControlInstruction::If(kind, body, None),
+            _ => ControlInstruction::TryTable(kind, catches, body),
+        };
+
+        self.input.expect_keyword("end")?;
+        self.closing_label()?;
+        self.pop_label();
+
+        Ok(instruction.into())
+    }
+
+    /// Parses the catch clauses of a 𝗍𝗋𝗒_𝗍𝖺𝖻𝗅𝖾 instruction.
+    /// Labels are resolved outside the block of the instruction.
+    ///
+    /// See <https://webassembly.github.io/exception-handling/core/text/instructions.html#control-instructions>
+    fn catch_clauses(&mut self) -> Result<Vec<CatchClause>, ParseError> {
+        let mut clauses = Vec::new();
+
+        while let Some(keyword @ ("catch" | "catch_ref" | "catch_all" | "catch_all_ref")) =
+            self.input.peek_list()
+        {
+            self.input.open_list(keyword)?;
+
+            let clause = match keyword {
+                "catch" => CatchClause::Catch(self.index(Space::Tag)?, self.label_index()?),
+                "catch_ref" => {
+                    CatchClause::CatchReference(self.index(Space::Tag)?, self.label_index()?)
+                }
+                "catch_all" => CatchClause::CatchAll(self.label_index()?),
+                _ => CatchClause::CatchAllReference(self.label_index()?),
+            };
+
+            self.input.right_parenthesis()?;
+            clauses.push(clause);
+        }
+
+        Ok(clauses)
+    }
+
+    /// Parses a plain instruction, including its immediate arguments.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html>
+    fn plain_instruction(&mut self) -> Result<Instruction, ParseError> {
+        let start = self.input.position();
+        let keyword = self.input.keyword()?;
+
+        let instruction: Instruction = match keyword {
+            "block" | "loop" | "if" | "try_table" => return self.block(keyword),
+            "br" => ControlInstruction::Branch(self.label_index()?).into(),
+            "br_if" => ControlInstruction::BranchIf(self.label_index()?).into(),
+            "br_table" => {
+                let mut labels = vec![self.label_index()?];
+
+                while self.input.at_index() {
+                    labels.push(self.label_index()?);
+                }
+
+                let default = labels.pop().unwrap_or_default();
+
+                ControlInstruction::BranchTable(labels, default).into()
+            }
+            "br_on_null" => ControlInstruction::BranchOnNull(self.label_index()?).into(),
+            "br_on_non_null" => ControlInstruction::BranchOnNonNull(self.label_index()?).into(),
+            "br_on_cast" => ControlInstruction::BranchOnCast(
+                self.label_index()?,
+                self.reference_type()?,
+                self.reference_type()?,
+            )
+            .into(),
+            "br_on_cast_fail" => ControlInstruction::BranchOnCastFail(
+                self.label_index()?,
+                self.reference_type()?,
+                self.reference_type()?,
+            )
+            .into(),
+            "call" => ControlInstruction::Call(self.index(Space::Function)?).into(),
+            "return_call" => ControlInstruction::ReturnCall(self.index(Space::Function)?).into(),
+            "call_indirect" | "return_call_indirect" => {
+                let table = self.optional_index(Space::Table)?.unwrap_or_default();
+                let type_use = self.type_use()?;
+                let kind = self.resolve_type_use(&type_use)?;
+
+                if keyword == "call_indirect" {
+                    ControlInstruction::CallIndirect(kind, table).into()
+                } else {
+                    ControlInstruction::ReturnCallIndirect(kind, table).into()
+                }
+            }
+            "call_ref" => ControlInstruction::CallRef(self.index(Space::Type)?).into(),
+            "return_call_ref" => ControlInstruction::ReturnCallRef(self.index(Space::Type)?).into(),
+            "throw" => ControlInstruction::Throw(self.index(Space::Tag)?).into(),
+            "select" => {
+                if self.input.at_list("result") {
+                    let mut kinds = Vec::new();
+
+                    while self.input.at_list("result") {
+                        self.input.open_list("result")?;
+
+                        while !self.input.at_end_of_list() {
+                            kinds.push(self.value_type()?);
+                        }
+
+                        self.input.right_parenthesis()?;
+                    }
+
+                    ParametricInstruction::Select(Some(kinds)).into()
+                } else {
+                    ParametricInstruction::Select(None).into()
+                }
+            }
+            "local.get" => VariableInstruction::LocalGet(self.local_index()?).into(),
+            "local.set" => VariableInstruction::LocalSet(self.local_index()?).into(),
+            "local.tee" => VariableInstruction::LocalTee(self.local_index()?).into(),
+            "global.get" => VariableInstruction::GlobalGet(self.index(Space::Global)?).into(),
+            "global.set" => VariableInstruction::GlobalSet(self.index(Space::Global)?).into(),
+            "table.get" => TableInstruction::Get(self.default_index(Space::Table)?).into(),
+            "table.set" => TableInstruction::Set(self.default_index(Space::Table)?).into(),
+            "table.size" => TableInstruction::Size(self.default_index(Space::Table)?).into(),
+            "table.grow" => TableInstruction::Grow(self.default_index(Space::Table)?).into(),
+            "table.fill" => TableInstruction::Fill(self.default_index(Space::Table)?).into(),
+            "table.copy" => TableInstruction::Copy(
+                self.default_index(Space::Table)?,
+                self.default_index(Space::Table)?,
+            )
+            .into(),
+            "table.init" => {
+                let (table, element) = self.segment_indices(Space::Table, Space::Element)?;
+
+                TableInstruction::Init(element, table).into()
+            }
+            "elem.drop" => TableInstruction::ElementDrop(self.index(Space::Element)?).into(),
+            "memory.size" => MemoryInstruction::Size(self.default_index(Space::Memory)?).into(),
+            "memory.grow" => MemoryInstruction::Grow(self.default_index(Space::Memory)?).into(),
+            "memory.fill" => MemoryInstruction::Fill(self.default_index(Space::Memory)?).into(),
+            "memory.copy" => MemoryInstruction::Copy(
+                self.default_index(Space::Memory)?,
+                self.default_index(Space::Memory)?,
+            )
+            .into(),
+            "memory.init" => {
+                let (memory, data) = self.segment_indices(Space::Memory, Space::Data)?;
+
+                self.uses_data_count = true;
+
+                MemoryInstruction::Init(data, memory).into()
+            }
+            "data.drop" => {
+                self.uses_data_count = true;
+
+                MemoryInstruction::DataDrop(self.index(Space::Data)?).into()
+            }
+            "ref.null" => ReferenceInstruction::Null(self.heap_type()?).into(),
+            "ref.func" => ReferenceInstruction::Function(self.index(Space::Function)?).into(),
+            "ref.test" => ReferenceInstruction::Test(self.reference_type()?).into(),
+            "ref.cast" => ReferenceInstruction::Cast(self.reference_type()?).into(),
+            "i32.const" => NumericInstruction::I32Constant(self.input.i32()?).into(),
+            "i64.const" => NumericInstruction::I64Constant(self.input.i64()?).into(),
+            "f32.const" => NumericInstruction::F32Constant(self.input.f32()?).into(),
+            "f64.const" => NumericInstruction::F64Constant(self.input.f64()?).into(),
+            "v128.const" => VectorInstruction::Constant(self.vector_constant()?).into(),
+            "i8x16.shuffle" => {
+                let mut lanes = [0; 16];
+
+                for lane in lanes.iter_mut() {
+                    *lane = self.lane_index()?;
+                }
+
+                VectorInstruction::Shuffle(lanes).into()
+            }
+            "struct.new" => AggregateInstruction::StructNew(self.index(Space::Type)?).into(),
+            "struct.new_default" => {
+                AggregateInstruction::StructNewDefault(self.index(Space::Type)?).into()
+            }
+            "struct.get" | "struct.get_s" | "struct.get_u" => {
+                let kind = self.index(Space::Type)?;
+                let field = self.field_index(kind)?;
+
+                AggregateInstruction::StructGet(packed_extension(keyword), kind, field).into()
+            }
+            "struct.set" => {
+                let kind = self.index(Space::Type)?;
+                let field = self.field_index(kind)?;
+
+                AggregateInstruction::StructSet(kind, field).into()
+            }
+            "array.new" => AggregateInstruction::ArrayNew(self.index(Space::Type)?).into(),
+            "array.new_default" => {
+                AggregateInstruction::ArrayNewDefault(self.index(Space::Type)?).into()
+            }
+            "array.new_fixed" => {
+                AggregateInstruction::ArrayNewFixed(self.index(Space::Type)?, self.input.u32()?)
+                    .into()
+            }
+            "array.new_data" => {
+                self.uses_data_count = true;
+
+                AggregateInstruction::ArrayNewData(
+                    self.index(Space::Type)?,
+                    self.index(Space::Data)?,
+                )
+                .into()
+            }
+            "array.new_elem" => AggregateInstruction::ArrayNewElement(
+                self.index(Space::Type)?,
+                self.index(Space::Element)?,
+            )
+            .into(),
+            "array.get" | "array.get_s" | "array.get_u" => {
+                AggregateInstruction::ArrayGet(packed_extension(keyword), self.index(Space::Type)?)
+                    .into()
+            }
+            "array.set" => AggregateInstruction::ArraySet(self.index(Space::Type)?).into(),
+            "array.fill" => AggregateInstruction::ArrayFill(self.index(Space::Type)?).into(),
+            "array.copy" => {
+                AggregateInstruction::ArrayCopy(self.index(Space::Type)?, self.index(Space::Type)?)
+                    .into()
+            }
+            "array.init_data" => {
+                self.uses_data_count = true;
+
+                AggregateInstruction::ArrayInitData(
+                    self.index(Space::Type)?,
+                    self.index(Space::Data)?,
+                )
+                .into()
+            }
+            "array.init_elem" => AggregateInstruction::ArrayInitElement(
+                self.index(Space::Type)?,
+                self.index(Space::Element)?,
+            )
+            .into(),
+            _ => {
+                if let Some(instruction) = simple_instruction(keyword) {
+                    instruction
+                } else if let Some(instruction) = self.lane_instruction(keyword)? {
+                    instruction
+                } else if is_memory_access(keyword) {
+                    let argument = self.memory_argument(keyword, false)?;
+
+                    memory_instruction(keyword, argument).ok_or_else(|| {
+                        self.input.seek(start);
+                        self.input
+                            .error(format!("unknown instruction `{}`", keyword))
+                    })?
+                } else {
+                    self.input.seek(start);
+
+                    return Err(self
+                        .input
+                        .error(format!("unknown instruction `{}`", keyword)));
+                }
+            }
+        };
+
+        Ok(instruction)
+    }
+
+    /// Parses an optional index, which defaults to zero.
+    fn default_index(&mut self, space: Space) -> Result<u32, ParseError> {
+        Ok(self.optional_index(space)?.unwrap_or_default())
+    }
+
+    /// Parses the operands of 𝗍𝖺𝖻𝗅𝖾.𝗂𝗇𝗂𝗍 and 𝗆𝖾𝗆𝗈𝗋𝗒.𝗂𝗇𝗂𝗍, where the table or memory index
+    /// is optional and precedes the segment index.
+    fn segment_indices(&mut self, target: Space, segment: Space) -> Result<(u32, u32), ParseError> {
+        let two_indices = self.input.at_index()
+            && matches!(
+                self.input.peek_at(1),
+                Some(TokenKind::Identifier(_) | TokenKind::Number(_))
+            );
+
+        if two_indices {
+            Ok((self.index(target)?, self.index(segment)?))
+        } else {
+            Ok((0, self.index(segment)?))
+        }
+    }
+
+    /// Parses a lane index of a vector instruction.
+    fn lane_index(&mut self) -> Result<u8, ParseError> {
+        self.input.value("lane index", |text| {
+            parse_u32(text).and_then(|lane| u8::try_from(lane).ok())
+        })
+    }
+
+    /// Parses the shape and lanes of a vector constant into its little-endian value.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#vector-instructions>
+    fn vector_constant(&mut self) -> Result<i128, ParseError> {
+        let mut bytes = Vec::with_capacity(16);
+
+        match self.input.keyword()? {
+            "i8x16" => {
+                for _ in 0..16 {
+                    bytes.push(self.input.value("i8", parse_i8)?);
+                }
+            }
+            "i16x8" => {
+                for _ in 0..8 {
+                    bytes.extend(self.input.value("i16", parse_i16)?.to_le_bytes());
+                }
+            }
+            "i32x4" => {
+                for _ in 0..4 {
+                    bytes.extend(self.input.i32()?.to_le_bytes());
+                }
+            }
+            "i64x2" => {
+                for _ in 0..2 {
+                    bytes.extend(self.input.i64()?.to_le_bytes());
+                }
+            }
+            "f32x4" => {
+                for _ in 0..4 {
+                    bytes.extend(self.input.f32()?.to_bits().to_le_bytes());
+                }
+            }
+            "f64x2" => {
+                for _ in 0..2 {
+                    bytes.extend(self.input.f64()?.to_bits().to_le_bytes());
+                }
+            }
+            _ => return Err(self.input.error("expected a vector shape")),
+        }
+
+        let mut value = [0; 16];
+
+        value.copy_from_slice(&bytes);
+
+        Ok(i128::from_le_bytes(value))
+    }
+
+    /// Parses the vector instructions that take a lane index, returning `None` if the keyword
+    /// does not denote such an instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#vector-instructions>
+    fn lane_instruction(&mut self, keyword: &str) -> Result<Option<Instruction>, ParseError> {
+        let (prefix, operation) = match keyword.split_once('.') {
+            Some(parts) => parts,
+            None => return Ok(None),
+        };
+
+        if prefix == "v128" && operation.ends_with("_lane") {
+            let argument = self.memory_argument(keyword, true)?;
+            let lane = self.lane_index()?;
+            let instruction = match operation {
+                "load8_lane" => VectorInstruction::Load8Lane(argument, lane),
+                "load16_lane" => VectorInstruction::Load16Lane(argument, lane),
+                "load32_lane" => VectorInstruction::Load32Lane(argument, lane),
+                "load64_lane" => VectorInstruction::Load64Lane(argument, lane),
+                "store8_lane" => VectorInstruction::Store8Lane(argument, lane),
+                "store16_lane" => VectorInstruction::Store16Lane(argument, lane),
+                "store32_lane" => VectorInstruction::Store32Lane(argument, lane),
+                "store64_lane" => VectorInstruction::Store64Lane(argument, lane),
+                _ => return Ok(None),
+            };
+
+            return Ok(Some(instruction.into()));
+        }
+
+        let shape = match vector_shape(prefix) {
+            Some(shape) => shape,
+            None => return Ok(None),
+        };
+        let integer = matches!(
+            shape,
+            VectorShape::I8x16 | VectorShape::I16x8 | VectorShape::I32x4 | VectorShape::I64x2
+        );
+        let packed = matches!(shape, VectorShape::I8x16 | VectorShape::I16x8);
+        let instruction = match operation {
+            "extract_lane_s" if packed => VectorInstruction::ExtractLane(
+                shape,
+                Some(SignExtension::Signed),
+                self.lane_index()?,
+            ),
+            "extract_lane_u" if packed => VectorInstruction::ExtractLane(
+                shape,
+                Some(SignExtension::Unsigned),
+                self.lane_index()?,
+            ),
+            "extract_lane" if !packed || !integer => {
+                VectorInstruction::ExtractLane(shape, None, self.lane_index()?)
+            }
+            "replace_lane" => VectorInstruction::ReplaceLane(shape, self.lane_index()?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(instruction.into()))
+    }
+
+    /// Parses the memory argument of a load or store instruction,
+    /// defaulting the alignment to the natural alignment of the instruction.
+    /// The memory index is optional and defaults to zero.
+    /// For instructions with a lane index, a lone integer is treated as the lane index.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#memory-instructions>
+    ///
+    /// See <https://webassembly.github.io/multi-memory/core/text/instructions.html#memory-instructions>
+    fn memory_argument(&mut self, keyword: &str, lane: bool) -> Result<MemoryArgument, ParseError> {
+        let explicit_memory = match self.input.peek() {
+            Some(TokenKind::Identifier(_)) => true,
+            Some(TokenKind::Number(_)) if lane => match self.input.peek_at(1) {
+                Some(TokenKind::Number(_)) => true,
+                Some(TokenKind::Keyword(keyword)) => {
+                    keyword.starts_with("offset=") || keyword.starts_with("align=")
+                }
+                _ => false,
+            },
+            Some(TokenKind::Number(_)) => true,
+            _ => false,
+        };
+        let memory = if explicit_memory {
+            self.index(Space::Memory)?
+        } else {
+            0
+        };
+
+        let offset = match self
+            .input
+            .peek_keyword()
+            .and_then(|keyword| keyword.strip_prefix("offset="))
+        {
+            Some(offset) => {
+                let offset = parse_u64(offset)
+                    .ok_or_else(|| self.input.error(format!("invalid offset `{}`", offset)))?;
+
+                self.input.keyword()?;
+
+                offset
+            }
+            None => 0,
+        };
+
+        let align = match self
+            .input
+            .peek_keyword()
+            .and_then(|keyword| keyword.strip_prefix("align="))
+        {
+            Some(align) => {
+                let align = parse_u32(align)
+                    .filter(|align| align.is_power_of_two())
+                    .ok_or_else(|| self.input.error(format!("invalid alignment `{}`", align)))?;
+
+                self.input.keyword()?;
+
+                align.trailing_zeros()
+            }
+            None => natural_alignment(keyword),
+        };
+
+        Ok(MemoryArgument::with_memory(memory, align, offset))
+    }
+}
+
+/// The sign extension of a packed field access (e.g., 𝗌𝗍𝗋𝗎𝖼𝗍.𝗀𝖾𝗍_𝗌).
+fn packed_extension(keyword: &str) -> Option<SignExtension> {
+    if keyword.ends_with("_s") {
+        Some(SignExtension::Signed)
+    } else if keyword.ends_with("_u") {
+        Some(SignExtension::Unsigned)
+    } else {
+        None
+    }
+}
+
+/// Determines whether the given keyword denotes an instruction that takes a memory argument.
+fn is_memory_access(keyword: &str) -> bool {
+    match keyword.split_once('.') {
+        Some((_, operation)) => {
+            operation.starts_with("load")
+                || operation.starts_with("store")
+                || operation.starts_with("atomic.")
+        }
+        None => false,
+    }
+}
+
+/// The natural alignment (as the exponent of a power of two) of the memory access
+/// performed by the instruction with the given keyword.
+fn natural_alignment(keyword: &str) -> u32 {
+    let (prefix, operation) = keyword.split_once('.').unwrap_or(("", keyword));
+    let operation = operation.trim_start_matches("atomic.");
+    let width: String = operation
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == 'x')
+        .collect();
+
+    let bits = match width.split_once('x') {
+        Some((size, count)) => size.parse::<u32>().unwrap_or(8) * count.parse::<u32>().unwrap_or(1),
+        None => match width.parse::<u32>() {
+            Ok(bits) => bits,
+            Err(_) => match prefix {
+                "i64" | "f64" => 64,
+                "v128" => 128,
+                _ => 32,
+            },
+        },
+    };
+
+    (bits / 8).trailing_zeros()
+}
+
+/// The instruction denoted by the given keyword, for instructions without immediate arguments.
+fn simple_instruction(keyword: &str) -> Option<Instruction> {
+    let instruction = match keyword {
+        "unreachable" => ControlInstruction::Unreachable.into(),
+        "nop" => ControlInstruction::Nop.into(),
+        "return" => ControlInstruction::Return.into(),
+        "throw_ref" => ControlInstruction::ThrowRef.into(),
+        "drop" => ParametricInstruction::Drop.into(),
+        "ref.is_null" => ReferenceInstruction::IsNull.into(),
+        "ref.as_non_null" => ReferenceInstruction::AsNonNull.into(),
+        "ref.eq" => ReferenceInstruction::Equal.into(),
+        "array.len" => AggregateInstruction::ArrayLength.into(),
+        "ref.i31" => AggregateInstruction::ReferenceI31.into(),
+        "i31.get_s" => AggregateInstruction::I31Get(SignExtension::Signed).into(),
+        "i31.get_u" => AggregateInstruction::I31Get(SignExtension::Unsigned).into(),
+        "any.convert_extern" => AggregateInstruction::AnyConvertExternal.into(),
+        "extern.convert_any" => AggregateInstruction::ExternalConvertAny.into(),
+        "atomic.fence" => AtomicInstruction::Fence.into(),
+        _ => {
+            return numeric_instruction(keyword)
+                .map(Instruction::from)
+                .or_else(|| vector_instruction(keyword).map(Instruction::from))
+        }
+    };
+
+    Some(instruction)
+}
+
+/// The sign extension denoted by the given suffix (i.e., `s` or `u`).
+fn sign_extension(suffix: &str) -> Option<SignExtension> {
+    match suffix {
+        "s" => Some(SignExtension::Signed),
+        "u" => Some(SignExtension::Unsigned),
+        _ => None,
+    }
+}
+
+/// The numeric instruction denoted by the given keyword (e.g., 𝗂𝟥𝟤.𝖺𝖽𝖽).
+///
+/// See <https://webassembly.github.io/spec/core/text/instructions.html#numeric-instructions>
+fn numeric_instruction(keyword: &str) -> Option<NumericInstruction> {
+    let (prefix, operation) = keyword.split_once('.')?;
+
+    match prefix {
+        "i32" => integer_instruction(IntegerType::I32, NumberType::I32, operation),
+        "i64" => integer_instruction(IntegerType::I64, NumberType::I64, operation),
+        "f32" => float_instruction(FloatType::F32, NumberType::F32, operation),
+        "f64" => float_instruction(FloatType::F64, NumberType::F64, operation),
+        _ => None,
+    }
+}
+
+/// The integer instruction with the given operation.
+fn integer_instruction(
+    integer: IntegerType,
+    number: NumberType,
+    operation: &str,
+) -> Option<NumericInstruction> {
+    let instruction = match (integer, operation) {
+        (_, "clz") => NumericInstruction::CountLeadingZeros(integer),
+        (_, "ctz") => NumericInstruction::CountTrailingZeros(integer),
+        (_, "popcnt") => NumericInstruction::CountOnes(integer),
+        (_, "add") => NumericInstruction::Add(number),
+        (_, "sub") => NumericInstruction::Subtract(number),
+        (_, "mul") => NumericInstruction::Multiply(number),
+        (_, "and") => NumericInstruction::And(integer),
+        (_, "or") => NumericInstruction::Or(integer),
+        (_, "xor") => NumericInstruction::Xor(integer),
+        (_, "shl") => NumericInstruction::ShiftLeft(integer),
+        (_, "rotl") => NumericInstruction::RotateLeft(integer),
+        (_, "rotr") => NumericInstruction::RotateRight(integer),
+        (_, "eqz") => NumericInstruction::EqualToZero(integer),
+        (_, "eq") => NumericInstruction::Equal(number),
+        (_, "ne") => NumericInstruction::NotEqual(number),
+        (_, "extend8_s") => NumericInstruction::ExtendSigned8(integer),
+        (_, "extend16_s") => NumericInstruction::ExtendSigned16(integer),
+        (IntegerType::I64, "extend32_s") => NumericInstruction::ExtendSigned32,
+        (IntegerType::I32, "wrap_i64") => NumericInstruction::Wrap,
+        (IntegerType::I64, "extend_i32_s") => {
+            NumericInstruction::ExtendWithSignExtension(SignExtension::Signed)
+        }
+        (IntegerType::I64, "extend_i32_u") => {
+            NumericInstruction::ExtendWithSignExtension(SignExtension::Unsigned)
+        }
+        (IntegerType::I32, "reinterpret_f32") | (IntegerType::I64, "reinterpret_f64") => {
+            NumericInstruction::ReinterpretFloat(integer)
+        }
+        _ => {
+            let (operation, suffix) = operation.rsplit_once('_')?;
+            let extension = sign_extension(suffix)?;
+
+            match operation {
+                "div" => NumericInstruction::DivideInteger(integer, extension),
+                "rem" => NumericInstruction::Remainder(integer, extension),
+                "shr" => NumericInstruction::ShiftRight(integer, extension),
+                "lt" => NumericInstruction::LessThanInteger(integer, extension),
+                "gt" => NumericInstruction::GreaterThanInteger(integer, extension),
+                "le" => NumericInstruction::LessThanOrEqualToInteger(integer, extension),
+                "ge" => NumericInstruction::GreaterThanOrEqualToInteger(integer, extension),
+                "trunc_f32" => {
+                    NumericInstruction::ConvertAndTruncate(integer, FloatType::F32, extension)
+                }
+                "trunc_f64" => {
+                    NumericInstruction::ConvertAndTruncate(integer, FloatType::F64, extension)
+                }
+                "trunc_sat_f32" => NumericInstruction::ConvertAndTruncateWithSaturation(
+                    integer,
+                    FloatType::F32,
+                    extension,
+                ),
+                "trunc_sat_f64" => NumericInstruction::ConvertAndTruncateWithSaturation(
+                    integer,
+                    FloatType::F64,
+                    extension,
+                ),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(instruction)
+}
+
+/// The floating-point instruction with the given operation.
+fn float_instruction(
+    float: FloatType,
+    number: NumberType,
+    operation: &str,
+) -> Option<NumericInstruction> {
+    let instruction = match (float, operation) {
+        (_, "abs") => NumericInstruction::AbsoluteValue(float),
+        (_, "neg") => NumericInstruction::Negate(float),
+        (_, "sqrt") => NumericInstruction::SquareRoot(float),
+        (_, "ceil") => NumericInstruction::Ceiling(float),
+        (_, "floor") => NumericInstruction::Floor(float),
+        (_, "trunc") => NumericInstruction::Truncate(float),
+        (_, "nearest") => NumericInstruction::Nearest(float),
+        (_, "add") => NumericInstruction::Add(number),
+        (_, "sub") => NumericInstruction::Subtract(number),
+        (_, "mul") => NumericInstruction::Multiply(number),
+        (_, "div") => NumericInstruction::DivideFloat(float),
+        (_, "min") => NumericInstruction::Minimum(float),
+        (_, "max") => NumericInstruction::Maximum(float),
+        (_, "copysign") => NumericInstruction::CopySign(float),
+        (_, "eq") => NumericInstruction::Equal(number),
+        (_, "ne") => NumericInstruction::NotEqual(number),
+        (_, "lt") => NumericInstruction::LessThanFloat(float),
+        (_, "gt") => NumericInstruction::GreaterThanFloat(float),
+        (_, "le") => NumericInstruction::LessThanOrEqualToFloat(float),
+        (_, "ge") => NumericInstruction::GreaterThanOrEqualToFloat(float),
+        (FloatType::F32, "demote_f64") => NumericInstruction::Demote,
+        (FloatType::F64, "promote_f32") => NumericInstruction::Promote,
+        (FloatType::F32, "reinterpret_i32") | (FloatType::F64, "reinterpret_i64") => {
+            NumericInstruction::ReinterpretInteger(float)
+        }
+        _ => {
+            let (operation, suffix) = operation.rsplit_once('_')?;
+            let extension = sign_extension(suffix)?;
+
+            match operation {
+                "convert_i32" => NumericInstruction::Convert(float, IntegerType::I32, extension),
+                "convert_i64" => NumericInstruction::Convert(float, IntegerType::I64, extension),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(instruction)
+}
+
+/// The vector shape denoted by the given keyword prefix (e.g., 𝗂𝟪𝗑𝟣𝟨).
+fn vector_shape(prefix: &str) -> Option<VectorShape> {
+    match prefix {
+        "i8x16" => Some(VectorShape::I8x16),
+        "i16x8" => Some(VectorShape::I16x8),
+        "i32x4" => Some(VectorShape::I32x4),
+        "i64x2" => Some(VectorShape::I64x2),
+        "f32x4" => Some(VectorShape::F32x4),
+        "f64x2" => Some(VectorShape::F64x2),
+        _ => None,
+    }
+}
+
+/// The vector instruction denoted by the given keyword, for instructions without immediate
+/// arguments.
+/// The shape prefix of an instruction is the shape of its result (e.g., 𝗂𝟣𝟨𝗑𝟪.𝖾𝗑𝗍𝖾𝗇𝖽_𝗅𝗈𝗐_𝗂𝟪𝗑𝟣𝟨_𝗌).
+///
+/// See <https://webassembly.github.io/spec/core/text/instructions.html#vector-instructions>
+fn vector_instruction(keyword: &str) -> Option<VectorInstruction> {
+    let (prefix, operation) = keyword.split_once('.')?;
+
+    if prefix == "v128" {
+        let instruction = match operation {
+            "not" => VectorInstruction::Not,
+            "and" => VectorInstruction::And,
+            "andnot" => VectorInstruction::AndNot,
+            "or" => VectorInstruction::Or,
+            "xor" => VectorInstruction::Xor,
+            "bitselect" => VectorInstruction::BitSelect,
+            "any_true" => VectorInstruction::AnyTrue,
+            _ => return None,
+        };
+
+        return Some(instruction);
+    }
+
+    let shape = vector_shape(prefix)?;
+    let instruction = match operation {
+        "splat" => VectorInstruction::Splat(shape),
+        "eq" => VectorInstruction::Equal(shape),
+        "ne" => VectorInstruction::NotEqual(shape),
+        "abs" => VectorInstruction::AbsoluteValue(shape),
+        "neg" => VectorInstruction::Negate(shape),
+        "add" => VectorInstruction::Add(shape),
+        "sub" => VectorInstruction::Subtract(shape),
+        "mul" if shape != VectorShape::I8x16 => VectorInstruction::Multiply(shape),
+        _ => {
+            return match shape {
+                VectorShape::I8x16 => {
+                    integer_vector_instruction(IntegerVectorShape::I8x16, operation)
+                }
+                VectorShape::I16x8 => {
+                    integer_vector_instruction(IntegerVectorShape::I16x8, operation)
+                }
+                VectorShape::I32x4 => {
+                    integer_vector_instruction(IntegerVectorShape::I32x4, operation)
+                }
+                VectorShape::I64x2 => {
+                    integer_vector_instruction(IntegerVectorShape::I64x2, operation)
+                }
+                VectorShape::F32x4 => float_vector_instruction(FloatVectorShape::F32x4, operation),
+                VectorShape::F64x2 => float_vector_instruction(FloatVectorShape::F64x2, operation),
+            }
+        }
+    };
+
+    Some(instruction)
+}
+
+/// The integer vector instruction with the given operation.
+fn integer_vector_instruction(
+    shape: IntegerVectorShape,
+    operation: &str,
+) -> Option<VectorInstruction> {
+    use IntegerVectorShape::*;
+
+    let instruction = match (shape, operation) {
+        (I8x16, "swizzle") => VectorInstruction::Swizzle,
+        (I8x16, "popcnt") => VectorInstruction::CountOnes,
+        (I16x8, "q15mulr_sat_s") => VectorInstruction::Q15MultiplyRoundSaturate,
+        (I32x4, "dot_i16x8_s") => VectorInstruction::DotProduct,
+        (I32x4, "trunc_sat_f32x4_s") => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Signed,
+        ),
+        (I32x4, "trunc_sat_f32x4_u") => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F32x4,
+            SignExtension::Unsigned,
+        ),
+        (I32x4, "trunc_sat_f64x2_s_zero") => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Signed,
+        ),
+        (I32x4, "trunc_sat_f64x2_u_zero") => VectorInstruction::ConvertAndTruncateWithSaturation(
+            FloatVectorShape::F64x2,
+            SignExtension::Unsigned,
+        ),
+        (_, "all_true") => VectorInstruction::AllTrue(shape),
+        (_, "bitmask") => VectorInstruction::Bitmask(shape),
+        (_, "shl") => VectorInstruction::ShiftLeft(shape),
+        (I8x16 | I16x8, "avgr_u") => VectorInstruction::AverageRounded(shape),
+        _ => {
+            let source = match shape {
+                I8x16 => "i16x8",
+                I16x8 => "i8x16",
+                I32x4 => "i16x8",
+                I64x2 => "i32x4",
+            };
+            let (operation, suffix) = operation.rsplit_once('_')?;
+            let extension = sign_extension(suffix)?;
+            let unsigned = extension == SignExtension::Unsigned;
+
+            match (shape, operation) {
+                (_, "shr") => VectorInstruction::ShiftRight(shape, extension),
+                (I64x2, _) if unsigned && matches!(operation, "lt" | "gt" | "le" | "ge") => {
+                    return None
+                }
+                (_, "lt") => VectorInstruction::LessThanInteger(shape, extension),
+                (_, "gt") => VectorInstruction::GreaterThanInteger(shape, extension),
+                (_, "le") => VectorInstruction::LessThanOrEqualToInteger(shape, extension),
+                (_, "ge") => VectorInstruction::GreaterThanOrEqualToInteger(shape, extension),
+                (I8x16 | I16x8, "add_sat") => VectorInstruction::AddSaturate(shape, extension),
+                (I8x16 | I16x8, "sub_sat") => VectorInstruction::SubtractSaturate(shape, extension),
+                (I8x16 | I16x8 | I32x4, "min") => {
+                    VectorInstruction::MinimumInteger(shape, extension)
+                }
+                (I8x16 | I16x8 | I32x4, "max") => {
+                    VectorInstruction::MaximumInteger(shape, extension)
+                }
+                (I8x16 | I16x8, _)
+                    if operation.strip_prefix("narrow_") == Some(source_of_narrow(shape)) =>
+                {
+                    VectorInstruction::Narrow(shape, extension)
+                }
+                (I16x8 | I32x4 | I64x2, _) => {
+                    if let Some(half) = operation
+                        .strip_prefix("extend_")
+                        .and_then(|rest| vector_half(rest, source))
+                    {
+                        VectorInstruction::Extend(shape, half, extension)
+                    } else if let Some(half) = operation
+                        .strip_prefix("extmul_")
+                        .and_then(|rest| vector_half(rest, source))
+                    {
+                        VectorInstruction::ExtendMultiply(shape, half, extension)
+                    } else if shape != I64x2
+                        && operation.strip_prefix("extadd_pairwise_") == Some(source)
+                    {
+                        VectorInstruction::ExtendAddPairwise(shape, extension)
+                    } else {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+        }
+    };
+
+    Some(instruction)
+}
+
+/// The shape of the operands of a narrowing instruction with the given result shape.
+fn source_of_narrow(shape: IntegerVectorShape) -> &'static str {
+    match shape {
+        IntegerVectorShape::I8x16 => "i16x8",
+        _ => "i32x4",
+    }
+}
+
+/// Parses the half of the operand vector (e.g., `low_i8x16`) with the given operand shape.
+fn vector_half(text: &str, source: &str) -> Option<VectorHalf> {
+    let (half, shape) = text.split_once('_')?;
+
+    if shape != source {
+        return None;
+    }
+
+    match half {
+        "low" => Some(VectorHalf::Low),
+        "high" => Some(VectorHalf::High),
+        _ => None,
+    }
+}
+
+/// The floating-point vector instruction with the given operation.
+fn float_vector_instruction(shape: FloatVectorShape, operation: &str) -> Option<VectorInstruction> {
+    let instruction = match (shape, operation) {
+        (_, "lt") => VectorInstruction::LessThanFloat(shape),
+        (_, "gt") => VectorInstruction::GreaterThanFloat(shape),
+        (_, "le") => VectorInstruction::LessThanOrEqualToFloat(shape),
+        (_, "ge") => VectorInstruction::GreaterThanOrEqualToFloat(shape),
+        (_, "sqrt") => VectorInstruction::SquareRoot(shape),
+        (_, "ceil") => VectorInstruction::Ceiling(shape),
+        (_, "floor") => VectorInstruction::Floor(shape),
+        (_, "trunc") => VectorInstruction::Truncate(shape),
+        (_, "nearest") => VectorInstruction::Nearest(shape),
+        (_, "div") => VectorInstruction::DivideFloat(shape),
+        (_, "min") => VectorInstruction::MinimumFloat(shape),
+        (_, "max") => VectorInstruction::MaximumFloat(shape),
+        (_, "pmin") => VectorInstruction::PseudoMinimum(shape),
+        (_, "pmax") => VectorInstruction::PseudoMaximum(shape),
+        (FloatVectorShape::F32x4, "demote_f64x2_zero") => VectorInstruction::Demote,
+        (FloatVectorShape::F64x2, "promote_low_f32x4") => VectorInstruction::Promote,
+        (FloatVectorShape::F32x4, "convert_i32x4_s")
+        | (FloatVectorShape::F64x2, "convert_low_i32x4_s") => {
+            VectorInstruction::Convert(shape, SignExtension::Signed)
+        }
+        (FloatVectorShape::F32x4, "convert_i32x4_u")
+        | (FloatVectorShape::F64x2, "convert_low_i32x4_u") => {
+            VectorInstruction::Convert(shape, SignExtension::Unsigned)
+        }
+        _ => return None,
+    };
+
+    Some(instruction)
+}
+
+/// The memory instruction (including vector and atomic memory instructions) denoted by the
+/// given keyword, with the given memory argument.
+///
+/// See <https://webassembly.github.io/spec/core/text/instructions.html#memory-instructions>
+///
+/// See <https://webassembly.github.io/threads/core/text/instructions.html#atomic-memory-instructions>
+fn memory_instruction(keyword: &str, argument: MemoryArgument) -> Option<Instruction> {
+    use SignExtension::*;
+
+    let instruction: Instruction = match keyword {
+        "i32.load" => MemoryInstruction::Load(NumberType::I32, argument).into(),
+        "i64.load" => MemoryInstruction::Load(NumberType::I64, argument).into(),
+        "f32.load" => MemoryInstruction::Load(NumberType::F32, argument).into(),
+        "f64.load" => MemoryInstruction::Load(NumberType::F64, argument).into(),
+        "i32.load8_s" => MemoryInstruction::Load8(IntegerType::I32, Signed, argument).into(),
+        "i32.load8_u" => MemoryInstruction::Load8(IntegerType::I32, Unsigned, argument).into(),
+        "i32.load16_s" => MemoryInstruction::Load16(IntegerType::I32, Signed, argument).into(),
+        "i32.load16_u" => MemoryInstruction::Load16(IntegerType::I32, Unsigned, argument).into(),
+        "i64.load8_s" => MemoryInstruction::Load8(IntegerType::I64, Signed, argument).into(),
+        "i64.load8_u" => MemoryInstruction::Load8(IntegerType::I64, Unsigned, argument).into(),
+        "i64.load16_s" => MemoryInstruction::Load16(IntegerType::I64, Signed, argument).into(),
+        "i64.load16_u" => MemoryInstruction::Load16(IntegerType::I64, Unsigned, argument).into(),
+        "i64.load32_s" => MemoryInstruction::Load32(Signed, argument).into(),
+        "i64.load32_u" => MemoryInstruction::Load32(Unsigned, argument).into(),
+        "i32.store" => MemoryInstruction::Store(NumberType::I32, argument).into(),
+        "i64.store" => MemoryInstruction::Store(NumberType::I64, argument).into(),
+        "f32.store" => MemoryInstruction::Store(NumberType::F32, argument).into(),
+        "f64.store" => MemoryInstruction::Store(NumberType::F64, argument).into(),
+        "i32.store8" => MemoryInstruction::Store8(IntegerType::I32, argument).into(),
+        "i32.store16" => MemoryInstruction::Store16(IntegerType::I32, argument).into(),
+        "i64.store8" => MemoryInstruction::Store8(IntegerType::I64, argument).into(),
+        "i64.store16" => MemoryInstruction::Store16(IntegerType::I64, argument).into(),
+        "i64.store32" => MemoryInstruction::Store32(argument).into(),
+        "v128.load" => VectorInstruction::Load(argument).into(),
+        "v128.store" => VectorInstruction::Store(argument).into(),
+        "v128.load8x8_s" => VectorInstruction::Load8x8(Signed, argument).into(),
+        "v128.load8x8_u" => VectorInstruction::Load8x8(Unsigned, argument).into(),
+        "v128.load16x4_s" => VectorInstruction::Load16x4(Signed, argument).into(),
+        "v128.load16x4_u" => VectorInstruction::Load16x4(Unsigned, argument).into(),
+        "v128.load32x2_s" => VectorInstruction::Load32x2(Signed, argument).into(),
+        "v128.load32x2_u" => VectorInstruction::Load32x2(Unsigned, argument).into(),
+        "v128.load8_splat" => VectorInstruction::Load8Splat(argument).into(),
+        "v128.load16_splat" => VectorInstruction::Load16Splat(argument).into(),
+        "v128.load32_splat" => VectorInstruction::Load32Splat(argument).into(),
+        "v128.load64_splat" => VectorInstruction::Load64Splat(argument).into(),
+        "v128.load32_zero" => VectorInstruction::Load32Zero(argument).into(),
+        "v128.load64_zero" => VectorInstruction::Load64Zero(argument).into(),
+        _ => return atomic_instruction(keyword, argument).map(Instruction::from),
+    };
+
+    Some(instruction)
+}
+
+/// The atomic memory instruction denoted by the given keyword, with the given memory argument.
+///
+/// See <https://webassembly.github.io/threads/core/text/instructions.html#atomic-memory-instructions>
+fn atomic_instruction(keyword: &str, argument: MemoryArgument) -> Option<AtomicInstruction> {
+    let (prefix, operation) = keyword.split_once('.')?;
+    let operation = operation.strip_prefix("atomic.")?;
+    let integer = match prefix {
+        "memory" => {
+            return match operation {
+                "notify" => Some(AtomicInstruction::Notify(argument)),
+                "wait32" => Some(AtomicInstruction::Wait(IntegerType::I32, argument)),
+                "wait64" => Some(AtomicInstruction::Wait(IntegerType::I64, argument)),
+                _ => None,
+            }
+        }
+        "i32" => IntegerType::I32,
+        "i64" => IntegerType::I64,
+        _ => return None,
+    };
+
+    let instruction = match (integer, operation) {
+        (_, "load") => AtomicInstruction::Load(integer, argument),
+        (_, "load8_u") => AtomicInstruction::Load8(integer, argument),
+        (_, "load16_u") => AtomicInstruction::Load16(integer, argument),
+        (IntegerType::I64, "load32_u") => AtomicInstruction::Load32(argument),
+        (_, "store") => AtomicInstruction::Store(integer, argument),
+        (_, "store8") => AtomicInstruction::Store8(integer, argument),
+        (_, "store16") => AtomicInstruction::Store16(integer, argument),
+        (IntegerType::I64, "store32") => AtomicInstruction::Store32(argument),
+        _ => {
+            let (width, operation) = operation.strip_prefix("rmw")?.split_once('.')?;
+            let operation = if width.is_empty() {
+                operation
+            } else {
+                operation.strip_suffix("_u")?
+            };
+            let operation = match operation {
+                "add" => AtomicOperation::Add,
+                "sub" => AtomicOperation::Subtract,
+                "and" => AtomicOperation::And,
+                "or" => AtomicOperation::Or,
+                "xor" => AtomicOperation::Xor,
+                "xchg" => AtomicOperation::Exchange,
+                "cmpxchg" => AtomicOperation::CompareExchange,
+                _ => return None,
+            };
+
+            match (integer, width) {
+                (_, "") => AtomicInstruction::ReadModifyWrite(operation, integer, argument),
+                (_, "8") => AtomicInstruction::ReadModifyWrite8(operation, integer, argument),
+                (_, "16") => AtomicInstruction::ReadModifyWrite16(operation, integer, argument),
+                (IntegerType::I64, "32") => {
+                    AtomicInstruction::ReadModifyWrite32(operation, argument)
+                }
+                _ => return None,
+            }
+        }
+    };
+
+    Some(instruction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_alignments() {
+        assert_eq!(natural_alignment("i32.load"), 2);
+        assert_eq!(natural_alignment("f64.store"), 3);
+        assert_eq!(natural_alignment("i64.load16_s"), 1);
+        assert_eq!(natural_alignment("i64.store32"), 2);
+        assert_eq!(natural_alignment("v128.load"), 4);
+        assert_eq!(natural_alignment("v128.load8x8_u"), 3);
+        assert_eq!(natural_alignment("v128.load8_splat"), 0);
+        assert_eq!(natural_alignment("v128.store64_lane"), 3);
+        assert_eq!(natural_alignment("memory.atomic.notify"), 2);
+        assert_eq!(natural_alignment("memory.atomic.wait64"), 3);
+        assert_eq!(natural_alignment("i64.atomic.rmw"), 3);
+        assert_eq!(natural_alignment("i64.atomic.rmw8.add_u"), 0);
+        assert_eq!(natural_alignment("i32.atomic.load16_u"), 1);
+    }
+
+    #[test]
+    fn simple_instructions() {
+        assert_eq!(
+            simple_instruction("i64.trunc_sat_f32_u"),
+            Some(
+                NumericInstruction::ConvertAndTruncateWithSaturation(
+                    IntegerType::I64,
+                    FloatType::F32,
+                    SignExtension::Unsigned
+                )
+                .into()
+            )
+        );
+        assert_eq!(
+            simple_instruction("i16x8.extend_high_i8x16_u"),
+            Some(
+                VectorInstruction::Extend(
+                    IntegerVectorShape::I16x8,
+                    VectorHalf::High,
+                    SignExtension::Unsigned
+                )
+                .into()
+            )
+        );
+        assert_eq!(
+            simple_instruction("i8x16.narrow_i16x8_s"),
+            Some(
+                VectorInstruction::Narrow(IntegerVectorShape::I8x16, SignExtension::Signed).into()
+            )
+        );
+        assert_eq!(simple_instruction("i8x16.mul"), None);
+        assert_eq!(simple_instruction("i64x2.lt_u"), None);
+        assert_eq!(simple_instruction("i32.wrap_i32"), None);
+        assert_eq!(
+            simple_instruction("f32.demote_f64"),
+            Some(NumericInstruction::Demote.into())
+        );
+        assert_eq!(simple_instruction("i64x2.extadd_pairwise_i32x4_s"), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! Lexical analysis of the WebAssembly text format.
+//!
+//! See <https://webassembly.github.io/spec/core/text/lexical.html>
+
+use crate::parser::ParseError;
+
+/// A token of the WebAssembly text format, along with the line and column at which it starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token<'a> {
+    kind: TokenKind<'a>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Token<'a> {
+    /// The kind of token.
+    pub fn kind(&self) -> &TokenKind<'a> {
+        &self.kind
+    }
+
+    /// The one-based line on which the token starts.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The one-based column at which the token starts.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// The kinds of tokens in the WebAssembly text format.
+/// Numbers are kept in their textual form, since their interpretation depends on the context.
+///
+/// See <https://webassembly.github.io/spec/core/text/lexical.html#tokens>
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind<'a> {
+    LeftParenthesis,
+    RightParenthesis,
+    /// A keyword, including the float constants 𝗂𝗇𝖿 and 𝗇𝖺𝗇.
+    Keyword(&'a str),
+    /// A symbolic identifier, without the leading `$`.
+    Identifier(&'a str),
+    /// An unsigned, signed or floating-point number.
+    Number(&'a str),
+    /// A string literal with its escape sequences already decoded.
+    String(Vec<u8>),
+}
+
+/// Splits the given text into tokens, skipping white space and comments.
+///
+/// See <https://webassembly.github.io/spec/core/text/lexical.html>
+pub fn tokenize(text: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut lexer = Lexer {
+        text,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Tracks the position of the lexer in the text.
+struct Lexer<'a> {
+    text: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    /// Returns the next token in the text, or `None` if only white space remains.
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        self.skip_white_space()?;
+
+        let (line, column) = (self.line, self.column);
+        let kind = match self.peek() {
+            None => return Ok(None),
+            Some('(') => {
+                self.advance();
+                TokenKind::LeftParenthesis
+            }
+            Some(')') => {
+                self.advance();
+                TokenKind::RightParenthesis
+            }
+            Some('"') => TokenKind::String(self.string()?),
+            Some(c) if is_id_char(c) => {
+                let start = self.offset;
+
+                while self.peek().filter(|&c| is_id_char(c)).is_some() {
+                    self.advance();
+                }
+
+                let text = &self.text[start..self.offset];
+
+                match text.as_bytes()[0] {
+                    b'$' if text.len() > 1 => TokenKind::Identifier(&text[1..]),
+                    b'a'..=b'z' => TokenKind::Keyword(text),
+                    b'0'..=b'9' | b'+' | b'-' => TokenKind::Number(text),
+                    _ => return Err(error(line, column, format!("unknown token `{}`", text))),
+                }
+            }
+            Some(c) => return Err(error(line, column, format!("unexpected character {:?}", c))),
+        };
+
+        Ok(Some(Token { kind, line, column }))
+    }
+
+    /// Skips white space, line comments and (nested) block comments.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/lexical.html#white-space>
+    fn skip_white_space(&mut self) -> Result<(), ParseError> {
+        loop {
+            let remaining = &self.text[self.offset..];
+
+            if remaining.starts_with(";;") {
+                while self.peek().filter(|&c| c != '\n').is_some() {
+                    self.advance();
+                }
+            } else if remaining.starts_with("(;") {
+                self.block_comment()?;
+            } else if self.peek().filter(|c| c.is_ascii_whitespace()).is_some() {
+                self.advance();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips a block comment, which may contain other block comments.
+    fn block_comment(&mut self) -> Result<(), ParseError> {
+        let (line, column) = (self.line, self.column);
+        let mut depth = 0;
+
+        loop {
+            let remaining = &self.text[self.offset..];
+
+            if remaining.starts_with("(;") {
+                depth += 1;
+                self.advance();
+                self.advance();
+            } else if remaining.starts_with(";)") {
+                depth -= 1;
+                self.advance();
+                self.advance();
+
+                if depth == 0 {
+                    return Ok(());
+                }
+            } else if self.advance().is_none() {
+                return Err(error(line, column, "unterminated block comment"));
+            }
+        }
+    }
+
+    /// Decodes a string literal, including the surrounding quotes.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/values.html#strings>
+    fn string(&mut self) -> Result<Vec<u8>, ParseError> {
+        let (line, column) = (self.line, self.column);
+        let mut bytes = Vec::new();
+
+        self.advance();
+
+        loop {
+            match self.advance() {
+                None | Some('\n') => return Err(error(line, column, "unterminated string")),
+                Some('"') => return Ok(bytes),
+                Some('\\') => self.escape(&mut bytes)?,
+                Some(c) => {
+                    let mut buffer = [0; 4];
+
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Decodes an escape sequence in a string literal into the given bytes.
+    fn escape(&mut self, bytes: &mut Vec<u8>) -> Result<(), ParseError> {
+        let (line, column) = (self.line, self.column);
+
+        match self.advance() {
+            Some('t') => bytes.push(b'\t'),
+            Some('n') => bytes.push(b'\n'),
+            Some('r') => bytes.push(b'\r'),
+            Some('"') => bytes.push(b'"'),
+            Some('\'') => bytes.push(b'\''),
+            Some('\\') => bytes.push(b'\\'),
+            Some('u') => {
+                let start = self.offset + 1;
+
+                if self.advance() != Some('{') {
+                    return Err(error(line, column, "invalid unicode escape"));
+                }
+
+                while self.peek().filter(|&c| c != '}').is_some() {
+                    self.advance();
+                }
+
+                let digits = self.text[start..self.offset].replace('_', "");
+                let c = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| error(line, column, "invalid unicode escape"))?;
+                let mut buffer = [0; 4];
+
+                self.advance();
+                bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+            Some(high) => {
+                let low = self.advance();
+                let byte = high
+                    .to_digit(16)
+                    .zip(low.and_then(|low| low.to_digit(16)))
+                    .ok_or_else(|| error(line, column, "invalid escape sequence"))?;
+
+                bytes.push((byte.0 * 16 + byte.1) as u8);
+            }
+            None => return Err(error(line, column, "unterminated string")),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    /// Consumes the next character, updating the line and column.
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+
+        self.offset += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+}
+
+/// Determines whether the given character may appear in a keyword, identifier or number.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#text-idchar>
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '!' | '#'
+                | '$'
+                | '%'
+                | '&'
+                | '\''
+                | '*'
+                | '+'
+                | '-'
+                | '.'
+                | '/'
+                | ':'
+                | '<'
+                | '='
+                | '>'
+                | '?'
+                | '@'
+                | '\\'
+                | '^'
+                | '_'
+                | '`'
+                | '|'
+                | '~'
+        )
+}
+
+/// Creates a text format parse error at the given position.
+pub fn error(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+    ParseError::InvalidText(line, column, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<TokenKind<'_>> {
+        tokenize(text)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_module() {
+        let actual = kinds("(module $m (func $f (param i32) i32.const -0x1_0))");
+
+        assert_eq!(
+            actual,
+            vec![
+                TokenKind::LeftParenthesis,
+                TokenKind::Keyword("module"),
+                TokenKind::Identifier("m"),
+                TokenKind::LeftParenthesis,
+                TokenKind::Keyword("func"),
+                TokenKind::Identifier("f"),
+                TokenKind::LeftParenthesis,
+                TokenKind::Keyword("param"),
+                TokenKind::Keyword("i32"),
+                TokenKind::RightParenthesis,
+                TokenKind::Keyword("i32.const"),
+                TokenKind::Number("-0x1_0"),
+                TokenKind::RightParenthesis,
+                TokenKind::RightParenthesis,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_comments() {
+        let actual = kinds(";; line\n(; block (; nested ;) ;) nop ;; trailing");
+
+        assert_eq!(actual, vec![TokenKind::Keyword("nop")]);
+    }
+
+    #[test]
+    fn tokenize_strings() {
+        let actual = kinds(r#""a\t\"\\\41\u{1F600}é""#);
+
+        assert_eq!(
+            actual,
+            vec![TokenKind::String("a\t\"\\A\u{1F600}é".as_bytes().to_vec())]
+        );
+    }
+
+    #[test]
+    fn tokenize_positions() {
+        let tokens = tokenize("(module\n  (func))").unwrap();
+
+        assert_eq!((tokens[2].line(), tokens[2].column()), (2, 3));
+        assert_eq!((tokens[3].line(), tokens[3].column()), (2, 4));
+    }
+
+    #[test]
+    fn tokenize_unterminated() {
+        assert!(matches!(
+            tokenize("(; open"),
+            Err(ParseError::InvalidText(1, 1, _))
+        ));
+        assert!(matches!(
+            tokenize("\n \"open"),
+            Err(ParseError::InvalidText(2, 2, _))
+        ));
+    }
+}
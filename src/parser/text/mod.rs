@@ -0,0 +1,405 @@
+//! Parser of the WebAssembly text format.
+//!
+//! The text is parsed directly into the model, resolving symbolic identifiers to indices.
+//! Identifiers are also recorded in a name section, so that the names of a module's definitions,
+//! locals and labels survive parsing.
+//!
+//! Parsing happens in two passes over the tokens of the module.
+//! The first pass assigns an index to every definition, so that identifiers may be referenced
+//! before they are defined. The second pass parses types first, followed by the remaining fields
+//! in textual order.
+//!
+//! See <https://webassembly.github.io/spec/core/text/index.html>
+
+mod cursor;
+mod instructions;
+mod lexer;
+mod module;
+mod types;
+mod values;
+
+use crate::parser::text::cursor::Cursor;
+use crate::parser::ParseError;
+use crate::{
+    FunctionType, LabelIndex, LocalIndex, Module, Name, NameMap, NameSection, RecursiveType,
+    SubType, TypeIndex,
+};
+use std::collections::HashMap;
+
+/// Parses the given text into a WebAssembly module and the names of its identifiers.
+pub fn parse_module(text: &str) -> Result<(Module, NameSection), ParseError> {
+    let tokens = lexer::tokenize(text)?;
+    let mut parser = TextParser::new(Cursor::new(tokens));
+
+    parser.module()
+}
+
+/// The index spaces of a module in the text format.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Space {
+    Type,
+    Function,
+    Table,
+    Memory,
+    Global,
+    Element,
+    Data,
+    Tag,
+}
+
+impl Space {
+    /// The name of the index space, used in error messages.
+    fn name(&self) -> &'static str {
+        match self {
+            Space::Type => "type",
+            Space::Function => "function",
+            Space::Table => "table",
+            Space::Memory => "memory",
+            Space::Global => "global",
+            Space::Element => "elem segment",
+            Space::Data => "data segment",
+            Space::Tag => "tag",
+        }
+    }
+}
+
+/// Maps the symbolic identifiers of an index space to their indices.
+///
+/// See <https://webassembly.github.io/spec/core/text/modules.html#indices>
+#[derive(Debug, Default)]
+struct IndexSpace<'a> {
+    identifiers: HashMap<&'a str, u32>,
+    length: u32,
+    defined: bool,
+}
+
+impl<'a> IndexSpace<'a> {
+    /// Assigns the next index to a definition with the given optional identifier.
+    fn push(&mut self, identifier: Option<&'a str>) -> Result<u32, String> {
+        let index = self.length;
+
+        if let Some(identifier) = identifier {
+            if self.identifiers.insert(identifier, index).is_some() {
+                return Err(format!("duplicate identifier `${}`", identifier));
+            }
+        }
+
+        self.length += 1;
+
+        Ok(index)
+    }
+
+    /// Assigns the next index to an import, which must precede all definitions.
+    fn push_import(&mut self, identifier: Option<&'a str>) -> Result<u32, String> {
+        if self.defined {
+            return Err("imports must occur before all non-import definitions".to_string());
+        }
+
+        self.push(identifier)
+    }
+
+    /// Assigns the next index to a definition that is not an import.
+    fn push_definition(&mut self, identifier: Option<&'a str>) -> Result<u32, String> {
+        self.defined = true;
+        self.push(identifier)
+    }
+
+    /// The index of the given identifier.
+    fn resolve(&self, identifier: &str) -> Option<u32> {
+        self.identifiers.get(identifier).copied()
+    }
+
+    /// The names of the identifiers in this space, ordered by index.
+    fn names(&self) -> NameMap {
+        self.identifiers
+            .iter()
+            .map(|(identifier, index)| (*index, Name::from(*identifier)))
+            .collect()
+    }
+}
+
+/// Parses the tokens of a module in the text format,
+/// keeping track of the identifiers in scope and the implicitly defined types.
+struct TextParser<'a> {
+    input: Cursor<'a>,
+    types: IndexSpace<'a>,
+    functions: IndexSpace<'a>,
+    tables: IndexSpace<'a>,
+    memories: IndexSpace<'a>,
+    globals: IndexSpace<'a>,
+    elements: IndexSpace<'a>,
+    data: IndexSpace<'a>,
+    tags: IndexSpace<'a>,
+    fields: HashMap<TypeIndex, IndexSpace<'a>>,
+    definitions: Vec<RecursiveType>,
+    implicit_types: Vec<FunctionType>,
+    function_types: Vec<(FunctionType, TypeIndex)>,
+    locals: IndexSpace<'a>,
+    labels: Vec<Option<&'a str>>,
+    label_names: NameMap,
+    label_count: u32,
+    uses_data_count: bool,
+}
+
+impl<'a> TextParser<'a> {
+    /// Creates a new parser for the given tokens.
+    fn new(input: Cursor<'a>) -> Self {
+        TextParser {
+            input,
+            types: IndexSpace::default(),
+            functions: IndexSpace::default(),
+            tables: IndexSpace::default(),
+            memories: IndexSpace::default(),
+            globals: IndexSpace::default(),
+            elements: IndexSpace::default(),
+            data: IndexSpace::default(),
+            tags: IndexSpace::default(),
+            fields: HashMap::new(),
+            definitions: Vec::new(),
+            implicit_types: Vec::new(),
+            function_types: Vec::new(),
+            locals: IndexSpace::default(),
+            labels: Vec::new(),
+            label_names: NameMap::new(),
+            label_count: 0,
+            uses_data_count: false,
+        }
+    }
+
+    /// The identifiers of the given index space.
+    fn space(&self, space: Space) -> &IndexSpace<'a> {
+        match space {
+            Space::Type => &self.types,
+            Space::Function => &self.functions,
+            Space::Table => &self.tables,
+            Space::Memory => &self.memories,
+            Space::Global => &self.globals,
+            Space::Element => &self.elements,
+            Space::Data => &self.data,
+            Space::Tag => &self.tags,
+        }
+    }
+
+    /// The mutable identifiers of the given index space.
+    fn space_mut(&mut self, space: Space) -> &mut IndexSpace<'a> {
+        match space {
+            Space::Type => &mut self.types,
+            Space::Function => &mut self.functions,
+            Space::Table => &mut self.tables,
+            Space::Memory => &mut self.memories,
+            Space::Global => &mut self.globals,
+            Space::Element => &mut self.elements,
+            Space::Data => &mut self.data,
+            Space::Tag => &mut self.tags,
+        }
+    }
+
+    /// Parses an index in the given space, either as an unsigned integer or a symbolic identifier.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#indices>
+    fn index(&mut self, space: Space) -> Result<u32, ParseError> {
+        let start = self.input.position();
+
+        match self.input.identifier() {
+            Some(identifier) => self.space(space).resolve(identifier).ok_or_else(|| {
+                self.input.seek(start);
+                self.input
+                    .error(format!("unknown {} `${}`", space.name(), identifier))
+            }),
+            None => self.input.u32(),
+        }
+    }
+
+    /// Parses an index in the given space, if one is present.
+    fn optional_index(&mut self, space: Space) -> Result<Option<u32>, ParseError> {
+        if self.input.at_index() {
+            self.index(space).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses the index of a local (including parameters) of the current function.
+    fn local_index(&mut self) -> Result<LocalIndex, ParseError> {
+        let start = self.input.position();
+
+        match self.input.identifier() {
+            Some(identifier) => self.locals.resolve(identifier).ok_or_else(|| {
+                self.input.seek(start);
+                self.input.error(format!("unknown local `${}`", identifier))
+            }),
+            None => self.input.u32(),
+        }
+    }
+
+    /// Parses a label index, resolving identifiers relative to the innermost enclosing block.
+    fn label_index(&mut self) -> Result<LabelIndex, ParseError> {
+        let start = self.input.position();
+
+        match self.input.identifier() {
+            Some(identifier) => self
+                .labels
+                .iter()
+                .rev()
+                .position(|label| *label == Some(identifier))
+                .map(|depth| depth as LabelIndex)
+                .ok_or_else(|| {
+                    self.input.seek(start);
+                    self.input.error(format!("unknown label `${}`", identifier))
+                }),
+            None => self.input.u32(),
+        }
+    }
+
+    /// Parses the index of a field of the given structure type.
+    fn field_index(&mut self, kind: TypeIndex) -> Result<u32, ParseError> {
+        let start = self.input.position();
+
+        match self.input.identifier() {
+            Some(identifier) => self
+                .fields
+                .get(&kind)
+                .and_then(|fields| fields.resolve(identifier))
+                .ok_or_else(|| {
+                    self.input.seek(start);
+                    self.input.error(format!("unknown field `${}`", identifier))
+                }),
+            None => self.input.u32(),
+        }
+    }
+
+    /// Enters a structured instruction with the given optional label.
+    /// Labels are numbered by the order in which their instructions appear in the function.
+    fn push_label(&mut self, label: Option<&'a str>) {
+        if let Some(label) = label {
+            self.label_names.insert(self.label_count, Name::from(label));
+        }
+
+        self.label_count += 1;
+        self.labels.push(label);
+    }
+
+    /// Exits the innermost structured instruction.
+    fn pop_label(&mut self) {
+        self.labels.pop();
+    }
+
+    /// Consumes the optional repeated label after an 𝖾𝗅𝗌𝖾 or 𝖾𝗇𝖽 keyword,
+    /// which must match the label of the block.
+    fn closing_label(&mut self) -> Result<(), ParseError> {
+        if let Some(identifier) = self.input.identifier() {
+            if self.labels.last() != Some(&Some(identifier)) {
+                return Err(self
+                    .input
+                    .error(format!("mismatching label `${}`", identifier)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resets the local and label scopes at the start of a function.
+    fn enter_function(&mut self) {
+        self.locals = IndexSpace::default();
+        self.labels = vec![None];
+        self.label_names = NameMap::new();
+        self.label_count = 0;
+    }
+
+    /// The sub type at the given index, including implicitly defined function types.
+    fn sub_type(&self, index: TypeIndex) -> Option<SubType> {
+        let explicit = self.definitions.iter().flat_map(RecursiveType::types);
+        let implicit = self
+            .implicit_types
+            .iter()
+            .map(|kind| SubType::from(kind.clone()));
+
+        explicit.cloned().chain(implicit).nth(index as usize)
+    }
+
+    /// Parses a module, returning the names of the identifiers it defines.
+    fn module(&mut self) -> Result<(Module, NameSection), ParseError> {
+        let mut names = NameSection::new();
+        let wrapped = self.input.at_list("module");
+
+        if wrapped {
+            self.input.open_list("module")?;
+            names.set_module(self.input.identifier().map(Name::from));
+        }
+
+        let module = self.module_fields(&mut names)?;
+
+        if wrapped {
+            self.input.right_parenthesis()?;
+        }
+
+        if !self.input.is_empty() {
+            return Err(self.input.error("unexpected token after the module"));
+        }
+
+        Ok((module, names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_binary;
+
+    /// Asserts that the native text parser produces the same module as the binary parser
+    /// applied to the output of the reference text-to-binary converter.
+    /// Custom sections are ignored, as the converter emits a name section.
+    pub fn assert_equivalent(text: &str) -> (Module, NameSection) {
+        let (actual, names) = parse_module(text).unwrap();
+        let expected = parse_binary(wat::parse_str(text).unwrap().as_slice()).unwrap();
+
+        assert_eq!(actual.types(), expected.types());
+        assert_eq!(actual.imports(), expected.imports());
+        assert_eq!(actual.functions(), expected.functions());
+        assert_eq!(actual.tables(), expected.tables());
+        assert_eq!(actual.memories(), expected.memories());
+        assert_eq!(actual.tags(), expected.tags());
+        assert_eq!(actual.globals(), expected.globals());
+        assert_eq!(actual.exports(), expected.exports());
+        assert_eq!(actual.start(), expected.start());
+        assert_eq!(actual.elements(), expected.elements());
+        assert_eq!(actual.data(), expected.data());
+        assert_eq!(actual.data_count(), expected.data_count());
+
+        (actual, names)
+    }
+
+    #[test]
+    fn parse_empty_module() {
+        let (module, names) = assert_equivalent("(module $empty)");
+
+        assert_eq!(module, Module::empty());
+        assert_eq!(names.module(), Some(&Name::from("empty")));
+    }
+
+    #[test]
+    fn parse_unwrapped_module() {
+        assert_equivalent("(func $f) (export \"f\" (func $f))");
+    }
+
+    #[test]
+    fn parse_trailing_tokens() {
+        assert!(matches!(
+            parse_module("(module) (func)"),
+            Err(ParseError::InvalidText(1, 10, _))
+        ));
+    }
+
+    #[test]
+    fn parse_mismatched_type_use() {
+        assert_equivalent("(module (type (func (param i32))) (func (type 0) (param i32)))");
+        assert!(matches!(
+            parse_module("(module (type (func (param i32))) (func (type 0) (param i64)))"),
+            Err(ParseError::InvalidText(..))
+        ));
+        assert!(matches!(
+            parse_module("(module (type (struct)) (func (type 0) (result i32) (i32.const 0)))"),
+            Err(ParseError::InvalidText(..))
+        ));
+    }
+}
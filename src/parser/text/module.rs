@@ -0,0 +1,989 @@
+//! Parse WebAssembly modules encoded in the text format.
+//!
+//! See <https://webassembly.github.io/spec/core/text/modules.html>
+
+use crate::parser::text::lexer::TokenKind;
+use crate::parser::text::{Space, TextParser};
+use crate::parser::ParseError;
+use crate::{
+    Data, DataMode, Element, ElementMode, Export, ExportDescription, Expression, Function, Global,
+    Import, ImportDescription, IndexType, Limit, Memory, MemoryType, Module, NameSection,
+    NumericInstruction, RecursiveType, ReferenceInstruction, ReferenceType, ResultType, Start,
+    Table, TableType, Tag, TagType,
+};
+use std::mem::take;
+
+/// The number of bytes in a page of linear memory.
+const PAGE_SIZE: usize = 65536;
+
+/// The definitions of a module, collected in the order of their index spaces.
+#[derive(Default)]
+struct Fields {
+    imports: Vec<Import>,
+    functions: Vec<Function>,
+    tables: Vec<Table>,
+    memories: Vec<Memory>,
+    globals: Vec<Global>,
+    tags: Vec<Tag>,
+    exports: Vec<Export>,
+    start: Option<Start>,
+    elements: Vec<Element>,
+    data: Vec<Data>,
+}
+
+impl<'a> TextParser<'a> {
+    /// Parses the fields of a module until the end of the enclosing list (or the input).
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#modules>
+    pub(super) fn module_fields(&mut self, names: &mut NameSection) -> Result<Module, ParseError> {
+        let positions = self.declare_fields()?;
+        let end = self.input.position();
+
+        for position in positions.iter().copied() {
+            self.input.seek(position);
+
+            match self.input.peek_list() {
+                Some("type") => {
+                    let index = self.types_defined();
+                    let kind = self.type_definition(index)?;
+
+                    if let Some(function) = kind.kind().function() {
+                        if !self
+                            .function_types
+                            .iter()
+                            .any(|(other, _)| other == function)
+                        {
+                            self.function_types.push((function.clone(), index));
+                        }
+                    }
+
+                    self.definitions.push(RecursiveType::from(kind));
+                }
+                Some("rec") => {
+                    let mut index = self.types_defined();
+                    let mut types = Vec::new();
+
+                    self.input.open_list("rec")?;
+
+                    while self.input.at_list("type") {
+                        types.push(self.type_definition(index)?);
+                        index += 1;
+                    }
+
+                    self.input.right_parenthesis()?;
+                    self.definitions.push(RecursiveType::new(types));
+                }
+                _ => {}
+            }
+        }
+
+        let mut fields = Fields::default();
+
+        for position in positions {
+            self.input.seek(position);
+
+            match self.input.peek_list() {
+                Some("type" | "rec") => {}
+                Some("import") => self.import(&mut fields)?,
+                Some("func") => self.function(&mut fields, names)?,
+                Some("table") => self.table(&mut fields)?,
+                Some("memory") => self.memory(&mut fields)?,
+                Some("global") => self.global(&mut fields)?,
+                Some("tag") => self.tag(&mut fields)?,
+                Some("export") => {
+                    self.input.open_list("export")?;
+
+                    let name = self.input.name()?;
+                    let description = self.export_description()?;
+
+                    self.input.right_parenthesis()?;
+                    fields.exports.push(Export::new(name, description));
+                }
+                Some("start") => {
+                    self.input.open_list("start")?;
+                    fields.start = Some(Start::new(self.index(Space::Function)?));
+                    self.input.right_parenthesis()?;
+                }
+                Some("elem") => {
+                    let element = self.element()?;
+
+                    fields.elements.push(element);
+                }
+                Some("data") => {
+                    let data = self.data_segment()?;
+
+                    fields.data.push(data);
+                }
+                _ => return Err(self.input.error("expected a module field")),
+            }
+        }
+
+        self.input.seek(end);
+
+        names.types_mut().extend(self.types.names());
+        names.functions_mut().extend(self.functions.names());
+        names.tables_mut().extend(self.tables.names());
+        names.memories_mut().extend(self.memories.names());
+        names.globals_mut().extend(self.globals.names());
+        names.elements_mut().extend(self.elements.names());
+        names.data_mut().extend(self.data.names());
+        names.tags_mut().extend(self.tags.names());
+
+        for (kind, fields) in self.fields.iter() {
+            let fields = fields.names();
+
+            if !fields.is_empty() {
+                names.fields_mut().insert(*kind, fields);
+            }
+        }
+
+        let mut types = take(&mut self.definitions);
+
+        types.extend(
+            take(&mut self.implicit_types)
+                .into_iter()
+                .map(RecursiveType::from),
+        );
+
+        let mut builder = Module::builder();
+
+        builder.set_types(non_empty(types));
+        builder.set_imports(non_empty(fields.imports));
+        builder.set_functions(non_empty(fields.functions));
+        builder.set_tables(non_empty(fields.tables));
+        builder.set_memories(non_empty(fields.memories));
+        builder.set_tags(non_empty(fields.tags));
+        builder.set_globals(non_empty(fields.globals));
+        builder.set_exports(non_empty(fields.exports));
+        builder.set_start(fields.start);
+        builder.set_elements(non_empty(fields.elements));
+
+        if self.uses_data_count {
+            builder.set_data_count(Some(fields.data.len() as u32));
+        }
+
+        builder.set_data(non_empty(fields.data));
+
+        Ok(builder.build())
+    }
+
+    /// The number of types defined so far by type and recursion group fields.
+    fn types_defined(&self) -> u32 {
+        self.definitions
+            .iter()
+            .map(|kind| kind.types().len() as u32)
+            .sum()
+    }
+
+    /// Assigns indices to the identifiers of all fields, returning the positions of the fields.
+    /// Imports must precede all definitions in the same index space.
+    /// Inline element and data segments of tables and memories are assigned indices in textual
+    /// order.
+    fn declare_fields(&mut self) -> Result<Vec<usize>, ParseError> {
+        let mut positions = Vec::new();
+
+        while !self.input.at_end_of_list() {
+            let position = self.input.position();
+            let keyword = match self.input.peek_list() {
+                Some(keyword) => keyword,
+                None => return Err(self.input.error("expected a module field")),
+            };
+
+            positions.push(position);
+            self.input.open_list(keyword)?;
+
+            match keyword {
+                "type" => self.declare(Space::Type, false)?,
+                "rec" => {
+                    while self.input.at_list("type") {
+                        self.input.open_list("type")?;
+                        self.declare(Space::Type, false)?;
+                        self.input.skip_list()?;
+                    }
+                }
+                "elem" => self.declare(Space::Element, false)?,
+                "data" => self.declare(Space::Data, false)?,
+                "import" => {
+                    self.input.string()?;
+                    self.input.string()?;
+
+                    let space = self
+                        .input
+                        .peek_list()
+                        .and_then(field_space)
+                        .ok_or_else(|| self.input.error("expected an import description"))?;
+
+                    let description = self.input.peek_list().unwrap_or_default();
+
+                    self.input.open_list(description)?;
+                    self.declare(space, true)?;
+                    self.input.skip_list()?;
+                }
+                "func" | "table" | "memory" | "global" | "tag" => {
+                    let space = field_space(keyword).unwrap_or(Space::Function);
+                    let identifier = self.input.identifier();
+
+                    while self.input.at_list("export") {
+                        self.input.open_list("export")?;
+                        self.input.skip_list()?;
+                    }
+
+                    let pushed = if self.input.at_list("import") {
+                        self.space_mut(space).push_import(identifier)
+                    } else {
+                        self.space_mut(space).push_definition(identifier)
+                    };
+
+                    pushed.map_err(|message| self.input.error(message))?;
+
+                    let segment = match keyword {
+                        "table" => Some(("elem", Space::Element)),
+                        "memory" => Some(("data", Space::Data)),
+                        _ => None,
+                    };
+
+                    if let Some((segment, space)) = segment {
+                        while !self.input.at_end_of_list() {
+                            if self.input.at_list(segment) {
+                                self.space_mut(space)
+                                    .push(None)
+                                    .map_err(|message| self.input.error(message))?;
+                            }
+
+                            self.input.next()?;
+                        }
+                    }
+                }
+                "export" | "start" => {}
+                _ => {
+                    self.input.seek(position);
+
+                    return Err(self
+                        .input
+                        .error(format!("unknown module field `{}`", keyword)));
+                }
+            }
+
+            self.input.seek(position);
+            self.input.left_parenthesis()?;
+            self.input.skip_list()?;
+        }
+
+        Ok(positions)
+    }
+
+    /// Assigns the next index of the given space to the optional identifier at the cursor.
+    fn declare(&mut self, space: Space, import: bool) -> Result<(), ParseError> {
+        let identifier = self.input.identifier();
+        let pushed = if import {
+            self.space_mut(space).push_import(identifier)
+        } else {
+            self.space_mut(space).push(identifier)
+        };
+
+        pushed
+            .map(|_| ())
+            .map_err(|message| self.input.error(message))
+    }
+
+    /// Parses the inline exports of a definition with the given description.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#text-func-abbrev>
+    fn inline_exports(
+        &mut self,
+        fields: &mut Fields,
+        description: ExportDescription,
+    ) -> Result<(), ParseError> {
+        while self.input.at_list("export") {
+            self.input.open_list("export")?;
+            fields
+                .exports
+                .push(Export::new(self.input.name()?, description));
+            self.input.right_parenthesis()?;
+        }
+
+        Ok(())
+    }
+
+    /// Parses an inline import (i.e., `(import "module" "name")`), if present,
+    /// returning the module and name.
+    fn inline_import(&mut self) -> Result<Option<(crate::Name, crate::Name)>, ParseError> {
+        if !self.input.at_list("import") {
+            return Ok(None);
+        }
+
+        self.input.open_list("import")?;
+
+        let module = self.input.name()?;
+        let name = self.input.name()?;
+
+        self.input.right_parenthesis()?;
+
+        Ok(Some((module, name)))
+    }
+
+    /// Parses an import field.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#imports>
+    fn import(&mut self, fields: &mut Fields) -> Result<(), ParseError> {
+        self.input.open_list("import")?;
+
+        let module = self.input.name()?;
+        let name = self.input.name()?;
+        let keyword = self.input.peek_list().unwrap_or_default();
+
+        self.input.open_list(keyword)?;
+        self.input.identifier();
+
+        let description = self.import_description(keyword)?;
+
+        self.input.right_parenthesis()?;
+        self.input.right_parenthesis()?;
+        fields.imports.push(Import::new(module, name, description));
+
+        Ok(())
+    }
+
+    /// Parses the description of an imported definition of the given kind.
+    fn import_description(&mut self, keyword: &str) -> Result<ImportDescription, ParseError> {
+        let description = match keyword {
+            "func" => {
+                let type_use = self.type_use()?;
+
+                ImportDescription::Function(self.resolve_type_use(&type_use)?)
+            }
+            "table" => ImportDescription::Table(self.table_type()?),
+            "memory" => ImportDescription::Memory(self.memory_type()?),
+            "global" => ImportDescription::Global(self.global_type()?),
+            "tag" => {
+                let type_use = self.type_use()?;
+
+                ImportDescription::Tag(TagType::new(self.resolve_type_use(&type_use)?))
+            }
+            _ => return Err(self.input.error("expected an import description")),
+        };
+
+        Ok(description)
+    }
+
+    /// Parses the description of an exported definition.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#exports>
+    fn export_description(&mut self) -> Result<ExportDescription, ParseError> {
+        let keyword = self.input.peek_list().unwrap_or_default();
+
+        self.input.open_list(keyword)?;
+
+        let description = match keyword {
+            "func" => ExportDescription::Function(self.index(Space::Function)?),
+            "table" => ExportDescription::Table(self.index(Space::Table)?),
+            "memory" => ExportDescription::Memory(self.index(Space::Memory)?),
+            "global" => ExportDescription::Global(self.index(Space::Global)?),
+            "tag" => ExportDescription::Tag(self.index(Space::Tag)?),
+            _ => return Err(self.input.error("expected an export description")),
+        };
+
+        self.input.right_parenthesis()?;
+
+        Ok(description)
+    }
+
+    /// Parses a function field, recording the names of its locals and labels.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#functions>
+    fn function(&mut self, fields: &mut Fields, names: &mut NameSection) -> Result<(), ParseError> {
+        let index = self.imported_count(fields, |description| {
+            matches!(description, ImportDescription::Function(_))
+        }) + fields.functions.len() as u32;
+
+        self.input.open_list("func")?;
+        self.input.identifier();
+        self.inline_exports(fields, ExportDescription::Function(index))?;
+
+        if let Some((module, name)) = self.inline_import()? {
+            let description = self.import_description("func")?;
+
+            self.input.right_parenthesis()?;
+            fields.imports.push(Import::new(module, name, description));
+
+            return Ok(());
+        }
+
+        let type_use = self.type_use()?;
+        let kind = self.resolve_type_use(&type_use)?;
+
+        self.enter_function();
+
+        if type_use.parameters().is_empty() {
+            for _ in 0..self.parameter_count(&type_use)? {
+                self.locals.push(None).ok();
+            }
+        } else {
+            for parameter in type_use.parameters() {
+                self.locals
+                    .push(*parameter)
+                    .map_err(|message| self.input.error(message))?;
+            }
+        }
+
+        let mut locals = Vec::new();
+
+        while self.input.at_list("local") {
+            self.input.open_list("local")?;
+
+            match self.input.identifier() {
+                Some(identifier) => {
+                    self.locals
+                        .push(Some(identifier))
+                        .map_err(|message| self.input.error(message))?;
+                    locals.push(self.value_type()?);
+                }
+                None => {
+                    while !self.input.at_end_of_list() {
+                        self.locals.push(None).ok();
+                        locals.push(self.value_type()?);
+                    }
+                }
+            }
+
+            self.input.right_parenthesis()?;
+        }
+
+        let body = self.expression()?;
+
+        self.input.right_parenthesis()?;
+
+        let local_names = self.locals.names();
+        let label_names = take(&mut self.label_names);
+
+        if !local_names.is_empty() {
+            names.locals_mut().insert(index, local_names);
+        }
+
+        if !label_names.is_empty() {
+            names.labels_mut().insert(index, label_names);
+        }
+
+        fields
+            .functions
+            .push(Function::new(kind, ResultType::new(locals), body));
+
+        Ok(())
+    }
+
+    /// Parses a table field, including an inline element segment or an initializer expression.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#tables>
+    fn table(&mut self, fields: &mut Fields) -> Result<(), ParseError> {
+        let index = self.imported_count(fields, |description| {
+            matches!(description, ImportDescription::Table(_))
+        }) + fields.tables.len() as u32;
+
+        self.input.open_list("table")?;
+        self.input.identifier();
+        self.inline_exports(fields, ExportDescription::Table(index))?;
+
+        if let Some((module, name)) = self.inline_import()? {
+            let description = self.import_description("table")?;
+
+            self.input.right_parenthesis()?;
+            fields.imports.push(Import::new(module, name, description));
+
+            return Ok(());
+        }
+
+        let start = self.input.position();
+        let index_type = self.index_type();
+
+        if self.at_reference_type() {
+            let kind = self.reference_type()?;
+
+            self.input.open_list("elem")?;
+
+            let initializers = if self.input.at_index() {
+                let mut initializers = Vec::new();
+
+                while self.input.at_index() {
+                    initializers.push(self.function_reference()?);
+                }
+
+                initializers
+            } else {
+                self.element_expressions()?
+            };
+
+            self.input.right_parenthesis()?;
+            self.input.right_parenthesis()?;
+
+            let length = initializers.len() as u64;
+            let limit = Limit::with_index_type(index_type, length, Some(length));
+
+            fields.tables.push(Table::new(TableType::new(kind, limit)));
+            fields.elements.push(Element::new(
+                kind,
+                ElementMode::Active(index, zero_offset(index_type)),
+                initializers,
+            ));
+
+            return Ok(());
+        }
+
+        self.input.seek(start);
+
+        let kind = self.table_type()?;
+
+        if self.input.at_end_of_list() {
+            fields.tables.push(Table::new(kind));
+        } else {
+            let initializer = self.expression()?;
+
+            fields
+                .tables
+                .push(Table::with_initializer(kind, initializer));
+        }
+
+        self.input.right_parenthesis()?;
+
+        Ok(())
+    }
+
+    /// Parses a memory field, including an inline data segment.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#memories>
+    fn memory(&mut self, fields: &mut Fields) -> Result<(), ParseError> {
+        let index = self.imported_count(fields, |description| {
+            matches!(description, ImportDescription::Memory(_))
+        }) + fields.memories.len() as u32;
+
+        self.input.open_list("memory")?;
+        self.input.identifier();
+        self.inline_exports(fields, ExportDescription::Memory(index))?;
+
+        if let Some((module, name)) = self.inline_import()? {
+            let description = self.import_description("memory")?;
+
+            self.input.right_parenthesis()?;
+            fields.imports.push(Import::new(module, name, description));
+
+            return Ok(());
+        }
+
+        let start = self.input.position();
+        let index_type = self.index_type();
+
+        if self.input.at_list("data") {
+            self.input.open_list("data")?;
+
+            let bytes = self.strings()?;
+
+            self.input.right_parenthesis()?;
+            self.input.right_parenthesis()?;
+
+            let pages = bytes.len().div_ceil(PAGE_SIZE) as u64;
+            let limit = Limit::with_index_type(index_type, pages, Some(pages));
+
+            fields.memories.push(Memory::new(MemoryType::new(limit)));
+            fields.data.push(Data::new(
+                DataMode::Active(index, zero_offset(index_type)),
+                bytes,
+            ));
+
+            return Ok(());
+        }
+
+        self.input.seek(start);
+
+        let kind = self.memory_type()?;
+
+        self.input.right_parenthesis()?;
+        fields.memories.push(Memory::new(kind));
+
+        Ok(())
+    }
+
+    /// Parses a global field.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#globals>
+    fn global(&mut self, fields: &mut Fields) -> Result<(), ParseError> {
+        let index = self.imported_count(fields, |description| {
+            matches!(description, ImportDescription::Global(_))
+        }) + fields.globals.len() as u32;
+
+        self.input.open_list("global")?;
+        self.input.identifier();
+        self.inline_exports(fields, ExportDescription::Global(index))?;
+
+        if let Some((module, name)) = self.inline_import()? {
+            let description = self.import_description("global")?;
+
+            self.input.right_parenthesis()?;
+            fields.imports.push(Import::new(module, name, description));
+
+            return Ok(());
+        }
+
+        let kind = self.global_type()?;
+        let initializer = self.expression()?;
+
+        self.input.right_parenthesis()?;
+        fields.globals.push(Global::new(kind, initializer));
+
+        Ok(())
+    }
+
+    /// Parses a tag field.
+    ///
+    /// See <https://webassembly.github.io/exception-handling/core/text/modules.html#tags>
+    fn tag(&mut self, fields: &mut Fields) -> Result<(), ParseError> {
+        let index = self.imported_count(fields, |description| {
+            matches!(description, ImportDescription::Tag(_))
+        }) + fields.tags.len() as u32;
+
+        self.input.open_list("tag")?;
+        self.input.identifier();
+        self.inline_exports(fields, ExportDescription::Tag(index))?;
+
+        if let Some((module, name)) = self.inline_import()? {
+            let description = self.import_description("tag")?;
+
+            self.input.right_parenthesis()?;
+            fields.imports.push(Import::new(module, name, description));
+
+            return Ok(());
+        }
+
+        let type_use = self.type_use()?;
+        let kind = self.resolve_type_use(&type_use)?;
+
+        self.input.right_parenthesis()?;
+        fields.tags.push(Tag::new(TagType::new(kind)));
+
+        Ok(())
+    }
+
+    /// The number of imports matching the given predicate.
+    fn imported_count(
+        &self,
+        fields: &Fields,
+        predicate: impl Fn(&ImportDescription) -> bool,
+    ) -> u32 {
+        fields
+            .imports
+            .iter()
+            .filter(|import| predicate(import.description()))
+            .count() as u32
+    }
+
+    /// Parses an element segment field.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#element-segments>
+    fn element(&mut self) -> Result<Element, ParseError> {
+        self.input.open_list("elem")?;
+        self.input.identifier();
+
+        let mode = if self.input.take_keyword("declare") {
+            Some(ElementMode::Declarative)
+        } else if self.input.at_list("table")
+            || self.input.at_index()
+            || matches!(self.input.peek_list(), Some(keyword) if keyword != "ref" && keyword != "item")
+        {
+            let table = if self.input.at_list("table") {
+                self.input.open_list("table")?;
+
+                let table = self.index(Space::Table)?;
+
+                self.input.right_parenthesis()?;
+
+                table
+            } else {
+                self.optional_index(Space::Table)?.unwrap_or_default()
+            };
+
+            Some(ElementMode::Active(table, self.offset()?))
+        } else {
+            None
+        };
+
+        let (kind, initializers) = if self.at_reference_type() {
+            (self.reference_type()?, self.element_expressions()?)
+        } else {
+            self.input.take_keyword("func");
+
+            let mut initializers = Vec::new();
+
+            while self.input.at_index() {
+                initializers.push(self.function_reference()?);
+            }
+
+            (ReferenceType::Function, initializers)
+        };
+
+        self.input.right_parenthesis()?;
+
+        Ok(Element::new(
+            kind,
+            mode.unwrap_or(ElementMode::Passive),
+            initializers,
+        ))
+    }
+
+    /// Parses the offset of an active segment, either as an `(offset ...)` list or as a single
+    /// folded instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#text-elem-abbrev>
+    fn offset(&mut self) -> Result<Expression, ParseError> {
+        self.single_expression("offset")
+    }
+
+    /// Parses an expression wrapped in a list with the given keyword, or a single folded
+    /// instruction.
+    fn single_expression(&mut self, keyword: &str) -> Result<Expression, ParseError> {
+        if self.input.at_list(keyword) {
+            self.input.open_list(keyword)?;
+
+            let expression = self.expression()?;
+
+            self.input.right_parenthesis()?;
+
+            Ok(expression)
+        } else {
+            let mut instructions = Vec::new();
+
+            self.folded_instruction(&mut instructions)?;
+
+            Ok(Expression::new(instructions))
+        }
+    }
+
+    /// Parses the element expressions of a segment, each either as an `(item ...)` list or as a
+    /// single folded instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#element-segments>
+    fn element_expressions(&mut self) -> Result<Vec<Expression>, ParseError> {
+        let mut initializers = Vec::new();
+
+        while matches!(self.input.peek(), Some(TokenKind::LeftParenthesis)) {
+            initializers.push(self.single_expression("item")?);
+        }
+
+        Ok(initializers)
+    }
+
+    /// Parses a function index as a `ref.func` element expression.
+    fn function_reference(&mut self) -> Result<Expression, ParseError> {
+        let function = self.index(Space::Function)?;
+
+        Ok(Expression::new(vec![ReferenceInstruction::Function(
+            function,
+        )
+        .into()]))
+    }
+
+    /// Parses a data segment field.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#data-segments>
+    fn data_segment(&mut self) -> Result<Data, ParseError> {
+        self.input.open_list("data")?;
+        self.input.identifier();
+
+        let mode = if self.input.at_list("memory") {
+            self.input.open_list("memory")?;
+
+            let memory = self.index(Space::Memory)?;
+
+            self.input.right_parenthesis()?;
+
+            DataMode::Active(memory, self.offset()?)
+        } else if self.input.at_index() {
+            let memory = self.index(Space::Memory)?;
+
+            DataMode::Active(memory, self.offset()?)
+        } else if matches!(self.input.peek(), Some(TokenKind::LeftParenthesis)) {
+            DataMode::Active(0, self.offset()?)
+        } else {
+            DataMode::Passive
+        };
+
+        let bytes = self.strings()?;
+
+        self.input.right_parenthesis()?;
+
+        Ok(Data::new(mode, bytes))
+    }
+
+    /// Parses a sequence of string literals into their concatenated bytes.
+    fn strings(&mut self) -> Result<Vec<u8>, ParseError> {
+        let mut bytes = Vec::new();
+
+        while matches!(self.input.peek(), Some(TokenKind::String(_))) {
+            bytes.extend(self.input.string()?);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// The index space of a module field with the given keyword.
+fn field_space(keyword: &str) -> Option<Space> {
+    match keyword {
+        "func" => Some(Space::Function),
+        "table" => Some(Space::Table),
+        "memory" => Some(Space::Memory),
+        "global" => Some(Space::Global),
+        "tag" => Some(Space::Tag),
+        _ => None,
+    }
+}
+
+/// The offset of an inline segment, which starts at the beginning of its table or memory.
+fn zero_offset(index_type: IndexType) -> Expression {
+    let instruction = match index_type {
+        IndexType::I32 => NumericInstruction::I32Constant(0),
+        IndexType::I64 => NumericInstruction::I64Constant(0),
+    };
+
+    Expression::new(vec![instruction.into()])
+}
+
+/// Wraps the given vector in an option that is `None` when the vector is empty,
+/// matching the representation of absent sections.
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::text::parse_module;
+    use crate::parser::text::tests::assert_equivalent;
+    use crate::parser::ParseError;
+    use crate::Name;
+
+    #[test]
+    fn parse_definitions() {
+        assert_equivalent(
+            r#"(module
+                (type $pair (func (param i32 i64) (result i64 i32)))
+                (rec
+                    (type $node (struct (field $value i32) (field $next (ref null $node))))
+                    (type $list (array (mut (ref null $node)))))
+                (import "env" "log" (func $log (param i32)))
+                (import "env" "table" (table 1 funcref))
+                (global $counter (import "env" "counter") (mut i32))
+                (func $swap (export "swap") (type $pair) (param $a i32) (param $b i64) (result i64 i32)
+                    local.get $b
+                    local.get $a)
+                (func $store (param i32) (local $tmp i64)
+                    (i64.store offset=8 align=4 (local.get 0) (local.get $tmp))
+                    (memory.init $bytes (i32.const 0) (i32.const 0) (i32.const 4))
+                    (data.drop $bytes))
+                (table $functions 2 10 funcref)
+                (table $inline funcref (elem $swap $store))
+                (memory $memory (export "memory") (data "hello" "world"))
+                (global $limit i64 (i64.const 42))
+                (tag $error (param i32))
+                (export "counter" (global $counter))
+                (start $entry)
+                (func $entry)
+                (elem (table $functions) (i32.const 1) func $entry)
+                (elem declare func $swap)
+                (elem $passive funcref (ref.func $log) (item ref.null func))
+                (data $bytes "\00\01\02\03")
+                (data (memory 0) (offset (i32.const 16)) "\ff"))"#,
+        );
+    }
+
+    #[test]
+    fn parse_instructions() {
+        let (_, names) = assert_equivalent(
+            r#"(module
+                (type $point (struct (field $x (mut i32)) (field $y i32)))
+                (tag $oops)
+                (func $control (param $n i32) (result i32)
+                    (block $outer (result i32)
+                        (loop $inner (param i32) (result i32)
+                            (br_if $inner (local.get $n))
+                            (if (result i32 i32) (local.get $n)
+                                (then (i32.const 1) (i32.const 2))
+                                (else (i32.const 3) (i32.const 4)))
+                            drop
+                            (br_table $inner $outer 0))
+                        block $exit (param i32) (result i32)
+                            try_table (catch $oops $exit) (catch_all $exit)
+                                throw $oops
+                            end
+                        end $exit))
+                (func $vectors (param v128) (result v128)
+                    local.get 0
+                    v128.const i32x4 1 2 3 4
+                    i32x4.add
+                    i8x16.shuffle 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+                    i16x8.extend_low_i8x16_s
+                    f64x2.convert_low_i32x4_u
+                    i32x4.trunc_sat_f64x2_s_zero
+                    i16x8.extract_lane_u 3
+                    i16x8.splat
+                    f32x4.demote_f64x2_zero
+                    i32.const 0
+                    v128.load32_lane 1
+                    i64.const 0x10
+                    i64x2.replace_lane 1)
+                (func $references (param $p (ref null $point)) (result i32)
+                    (struct.set $point $x (local.get $p) (i32.const 7))
+                    (struct.get $point $y (local.get $p))
+                    (i31.get_u (ref.i31 (i32.const 9)))
+                    drop
+                    (select (result i32) (i32.const 0) (i32.const 1) (i32.const 2))
+                    i32.add)
+                (memory 1 2 shared)
+                (func $atomics
+                    (i32.atomic.rmw8.cmpxchg_u offset=4 (i32.const 0) (i32.const 1) (i32.const 2))
+                    (memory.atomic.notify (i32.const 0) (i32.const 1))
+                    i64.extend_i32_u
+                    i32.wrap_i64
+                    i32.add
+                    drop
+                    atomic.fence))"#,
+        );
+
+        assert_eq!(names.function(0), Some(&Name::from("control")));
+        assert_eq!(names.local(0, 0), Some(&Name::from("n")));
+        assert_eq!(names.label(0, 0), Some(&Name::from("outer")));
+        assert_eq!(names.label(0, 1), Some(&Name::from("inner")));
+        assert_eq!(names.label(0, 3), Some(&Name::from("exit")));
+        assert_eq!(names.field(0, 1), Some(&Name::from("y")));
+        assert_eq!(names.local(2, 0), Some(&Name::from("p")));
+        assert_eq!(names.tags().get(&0), Some(&Name::from("oops")));
+    }
+
+    #[test]
+    fn parse_implicit_types() {
+        assert_equivalent(
+            r#"(module
+                (func (param i32) (result i32) (local.get 0))
+                (type $unit (func))
+                (func (type $unit))
+                (func (result i32 i64)
+                    (block (param) (result i32 i64) (i32.const 1) (i64.const 2))))"#,
+        );
+    }
+
+    #[test]
+    fn parse_unknown_identifier() {
+        assert!(matches!(
+            parse_module("(module\n  (func (call $missing)))"),
+            Err(ParseError::InvalidText(2, 15, message)) if message.contains("$missing")
+        ));
+    }
+
+    #[test]
+    fn parse_import_after_definition() {
+        assert!(matches!(
+            parse_module("(module (func) (import \"a\" \"b\" (func)))"),
+            Err(ParseError::InvalidText(..))
+        ));
+    }
+}
@@ -0,0 +1,489 @@
+//! Parse WebAssembly types encoded in the text format.
+//!
+//! See <https://webassembly.github.io/spec/core/text/types.html>
+
+use crate::parser::text::lexer::TokenKind;
+use crate::parser::text::{IndexSpace, Space, TextParser};
+use crate::parser::ParseError;
+use crate::{
+    ArrayType, BlockType, CompositeType, FieldType, FunctionType, GlobalType, HeapType, IndexType,
+    Limit, MemoryType, Mutability, NumberType, PackedType, ReferenceType, ResultType, StorageType,
+    StructType, SubType, TableType, TypeIndex, ValueType,
+};
+
+/// A reference to a function type, either by index, by an inline signature, or both.
+///
+/// See <https://webassembly.github.io/spec/core/text/modules.html#type-uses>
+pub struct TypeUse<'a> {
+    index: Option<TypeIndex>,
+    parameters: Vec<Option<&'a str>>,
+    kind: FunctionType,
+}
+
+impl<'a> TypeUse<'a> {
+    /// The identifiers of the inline parameters.
+    pub fn parameters(&self) -> &[Option<&'a str>] {
+        &self.parameters
+    }
+}
+
+impl<'a> TextParser<'a> {
+    /// Parses a heap type, either abstract or a concrete type index.
+    ///
+    /// See <https://webassembly.github.io/function-references/core/text/types.html#heap-types>
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#heap-types>
+    pub(super) fn heap_type(&mut self) -> Result<HeapType, ParseError> {
+        let kind = match self.input.peek_keyword() {
+            Some("func") => HeapType::Function,
+            Some("extern") => HeapType::External,
+            Some("exn") => HeapType::Exception,
+            Some("any") => HeapType::Any,
+            Some("eq") => HeapType::Equality,
+            Some("i31") => HeapType::I31,
+            Some("struct") => HeapType::Struct,
+            Some("array") => HeapType::Array,
+            Some("none") => HeapType::None,
+            Some("nofunc") => HeapType::NoFunction,
+            Some("noextern") => HeapType::NoExternal,
+            Some("noexn") => HeapType::NoException,
+            _ => return self.index(Space::Type).map(HeapType::Index),
+        };
+
+        self.input.keyword()?;
+
+        Ok(kind)
+    }
+
+    /// Returns true if the next tokens form a reference type.
+    pub(super) fn at_reference_type(&self) -> bool {
+        self.input.at_list("ref")
+            || matches!(
+                self.input.peek_keyword(),
+                Some(keyword) if reference_type_shorthand(keyword).is_some()
+            )
+    }
+
+    /// Parses a reference type.
+    /// Nullable references to the abstract function, external and exception heap types
+    /// are equivalent to their shorthands.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#reference-types>
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#reference-types>
+    pub(super) fn reference_type(&mut self) -> Result<ReferenceType, ParseError> {
+        if let Some(kind) = self.input.peek_keyword().and_then(reference_type_shorthand) {
+            self.input.keyword()?;
+
+            return Ok(kind);
+        }
+
+        self.input.open_list("ref")?;
+
+        let nullable = self.input.take_keyword("null");
+        let heap_type = self.heap_type()?;
+
+        self.input.right_parenthesis()?;
+
+        if nullable {
+            Ok(ReferenceType::nullable(heap_type))
+        } else {
+            Ok(ReferenceType::NonNullable(heap_type))
+        }
+    }
+
+    /// Parses a value type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#value-types>
+    pub(super) fn value_type(&mut self) -> Result<ValueType, ParseError> {
+        let kind = match self.input.peek_keyword() {
+            Some("i32") => ValueType::from(NumberType::I32),
+            Some("i64") => ValueType::from(NumberType::I64),
+            Some("f32") => ValueType::from(NumberType::F32),
+            Some("f64") => ValueType::from(NumberType::F64),
+            Some("v128") => ValueType::V128,
+            _ if self.at_reference_type() => return self.reference_type().map(ValueType::from),
+            _ => return Err(self.input.error("expected a value type")),
+        };
+
+        self.input.keyword()?;
+
+        Ok(kind)
+    }
+
+    /// Parses the value types until the end of the current list.
+    fn value_types(&mut self, kinds: &mut Vec<ValueType>) -> Result<(), ParseError> {
+        while !self.input.at_end_of_list() {
+            kinds.push(self.value_type()?);
+        }
+
+        Ok(())
+    }
+
+    /// Parses a sequence of parameter and result declarations, returning the identifiers of the
+    /// parameters along with the function type.
+    /// A declaration with an identifier may only declare a single parameter.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#function-types>
+    pub(super) fn signature(&mut self) -> Result<(Vec<Option<&'a str>>, FunctionType), ParseError> {
+        let mut identifiers = Vec::new();
+        let mut parameters = Vec::new();
+        let mut results = Vec::new();
+
+        while self.input.at_list("param") {
+            self.input.open_list("param")?;
+
+            match self.input.identifier() {
+                Some(identifier) => {
+                    identifiers.push(Some(identifier));
+                    parameters.push(self.value_type()?);
+                }
+                None => {
+                    let start = parameters.len();
+
+                    self.value_types(&mut parameters)?;
+                    identifiers.resize(identifiers.len() + parameters.len() - start, None);
+                }
+            }
+
+            self.input.right_parenthesis()?;
+        }
+
+        while self.input.at_list("result") {
+            self.input.open_list("result")?;
+            self.value_types(&mut results)?;
+            self.input.right_parenthesis()?;
+        }
+
+        let kind = FunctionType::new(ResultType::new(parameters), ResultType::new(results));
+
+        Ok((identifiers, kind))
+    }
+
+    /// Parses a type use, which references a type by index and/or declares an inline signature.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#type-uses>
+    pub(super) fn type_use(&mut self) -> Result<TypeUse<'a>, ParseError> {
+        let index = if self.input.at_list("type") {
+            self.input.open_list("type")?;
+
+            let index = self.index(Space::Type)?;
+
+            self.input.right_parenthesis()?;
+
+            Some(index)
+        } else {
+            None
+        };
+
+        let (parameters, kind) = self.signature()?;
+
+        Ok(TypeUse {
+            index,
+            parameters,
+            kind,
+        })
+    }
+
+    /// Resolves a type use to a type index.
+    /// An inline signature without an index refers to the first type definition (outside of a
+    /// recursion group) or implicit type with the same signature.
+    /// If no such type exists, one is implicitly appended to the type section.
+    ///
+    /// When both an index and an inline signature are given, the signature must match the referenced function type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/modules.html#abbreviations>
+    pub(super) fn resolve_type_use(
+        &mut self,
+        type_use: &TypeUse<'a>,
+    ) -> Result<TypeIndex, ParseError> {
+        if let Some(index) = type_use.index {
+            let inline =
+                !type_use.kind.parameters().is_empty() || !type_use.kind.results().is_empty();
+
+            if inline {
+                let sub_type = self.sub_type(index);
+                let kind = sub_type
+                    .as_ref()
+                    .and_then(|kind| kind.kind().function())
+                    .ok_or_else(|| {
+                        self.input
+                            .error(format!("type {} is not a function type", index))
+                    })?;
+
+                if kind != &type_use.kind {
+                    return Err(self
+                        .input
+                        .error("inline function type doesn't match type reference"));
+                }
+            }
+
+            return Ok(index);
+        }
+
+        let existing = self
+            .function_types
+            .iter()
+            .find(|(kind, _)| kind == &type_use.kind)
+            .map(|(_, index)| *index);
+
+        Ok(existing.unwrap_or_else(|| {
+            let index = self.types.length + self.implicit_types.len() as TypeIndex;
+
+            self.implicit_types.push(type_use.kind.clone());
+            self.function_types.push((type_use.kind.clone(), index));
+
+            index
+        }))
+    }
+
+    /// The number of parameters of the function type referenced by the given type use.
+    pub(super) fn parameter_count(&self, type_use: &TypeUse<'a>) -> Result<usize, ParseError> {
+        match type_use.index {
+            None => Ok(type_use.kind.parameters().len()),
+            Some(index) => self
+                .sub_type(index)
+                .and_then(|kind| kind.kind().function().map(|kind| kind.parameters().len()))
+                .ok_or_else(|| {
+                    self.input
+                        .error(format!("type {} is not a function type", index))
+                }),
+        }
+    }
+
+    /// Parses the type of a structured instruction.
+    /// Block types without parameters and with at most one result are encoded inline,
+    /// all others refer to a function type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/instructions.html#control-instructions>
+    pub(super) fn block_type(&mut self) -> Result<BlockType, ParseError> {
+        let type_use = self.type_use()?;
+
+        if type_use.index.is_some() || !type_use.kind.parameters().is_empty() {
+            return Ok(BlockType::Index(self.resolve_type_use(&type_use)?));
+        }
+
+        match type_use.kind.results().kinds() {
+            [] => Ok(BlockType::None),
+            [kind] => Ok(BlockType::ValueType(*kind)),
+            _ => Ok(BlockType::Index(self.resolve_type_use(&type_use)?)),
+        }
+    }
+
+    /// Parses a storage type, which is either a value type or a packed type.
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#aggregate-types>
+    fn storage_type(&mut self) -> Result<StorageType, ParseError> {
+        if self.input.take_keyword("i8") {
+            Ok(StorageType::Packed(PackedType::I8))
+        } else if self.input.take_keyword("i16") {
+            Ok(StorageType::Packed(PackedType::I16))
+        } else {
+            self.value_type().map(StorageType::Value)
+        }
+    }
+
+    /// Parses a field type, which is a storage type that is optionally mutable.
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#aggregate-types>
+    fn field_type(&mut self) -> Result<FieldType, ParseError> {
+        if self.input.at_list("mut") {
+            self.input.open_list("mut")?;
+
+            let kind = self.storage_type()?;
+
+            self.input.right_parenthesis()?;
+
+            Ok(FieldType::mutable(kind))
+        } else {
+            self.storage_type().map(FieldType::immutable)
+        }
+    }
+
+    /// Parses a composite type, recording the identifiers of structure fields for the type at the
+    /// given index.
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#composite-types>
+    fn composite_type(&mut self, index: TypeIndex) -> Result<CompositeType, ParseError> {
+        match self.input.peek_list() {
+            Some("func") => {
+                self.input.open_list("func")?;
+
+                let (_, kind) = self.signature()?;
+
+                self.input.right_parenthesis()?;
+
+                Ok(CompositeType::Function(kind))
+            }
+            Some("struct") => {
+                let mut fields = Vec::new();
+                let mut identifiers = IndexSpace::default();
+
+                self.input.open_list("struct")?;
+
+                while self.input.at_list("field") {
+                    self.input.open_list("field")?;
+
+                    match self.input.identifier() {
+                        Some(identifier) => {
+                            identifiers
+                                .push(Some(identifier))
+                                .map_err(|message| self.input.error(message))?;
+                            fields.push(self.field_type()?);
+                        }
+                        None => {
+                            while !self.input.at_end_of_list() {
+                                identifiers
+                                    .push(None)
+                                    .map_err(|message| self.input.error(message))?;
+                                fields.push(self.field_type()?);
+                            }
+                        }
+                    }
+
+                    self.input.right_parenthesis()?;
+                }
+
+                self.input.right_parenthesis()?;
+                self.fields.insert(index, identifiers);
+
+                Ok(CompositeType::Struct(StructType::new(fields)))
+            }
+            Some("array") => {
+                self.input.open_list("array")?;
+
+                let field = self.field_type()?;
+
+                self.input.right_parenthesis()?;
+
+                Ok(CompositeType::Array(ArrayType::new(field)))
+            }
+            _ => Err(self.input.error("expected a composite type")),
+        }
+    }
+
+    /// Parses a type definition (i.e., `(type $t ...)`) with the given index.
+    /// Composite types without the `sub` keyword are final and have no supertypes.
+    ///
+    /// See <https://webassembly.github.io/gc/core/text/types.html#recursive-types>
+    pub(super) fn type_definition(&mut self, index: TypeIndex) -> Result<SubType, ParseError> {
+        self.input.open_list("type")?;
+        self.input.identifier();
+
+        let kind = if self.input.at_list("sub") {
+            self.input.open_list("sub")?;
+
+            let is_final = self.input.take_keyword("final");
+            let mut supertypes = Vec::new();
+
+            while self.input.at_index() {
+                supertypes.push(self.index(Space::Type)?);
+            }
+
+            let kind = self.composite_type(index)?;
+
+            self.input.right_parenthesis()?;
+
+            SubType::new(is_final, supertypes, kind)
+        } else {
+            SubType::from(self.composite_type(index)?)
+        };
+
+        self.input.right_parenthesis()?;
+
+        Ok(kind)
+    }
+
+    /// Parses limits, with bounds of the given index type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#limits>
+    fn limit(&mut self, index_type: IndexType) -> Result<Limit, ParseError> {
+        let min = self.input.u64()?;
+        let max = match self.input.peek() {
+            Some(TokenKind::Number(_)) => Some(self.input.u64()?),
+            _ => None,
+        };
+
+        Ok(Limit::with_index_type(index_type, min, max))
+    }
+
+    /// Parses the optional index type that prefixes a table or memory type.
+    ///
+    /// See <https://webassembly.github.io/memory64/core/text/types.html>
+    pub(super) fn index_type(&mut self) -> IndexType {
+        if self.input.take_keyword("i64") {
+            IndexType::I64
+        } else {
+            self.input.take_keyword("i32");
+            IndexType::I32
+        }
+    }
+
+    /// Parses a table type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#table-types>
+    pub(super) fn table_type(&mut self) -> Result<TableType, ParseError> {
+        let index_type = self.index_type();
+        let limit = self.limit(index_type)?;
+        let kind = self.reference_type()?;
+
+        Ok(TableType::new(kind, limit))
+    }
+
+    /// Parses a memory type, which may be shared.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#memory-types>
+    ///
+    /// See <https://webassembly.github.io/threads/core/text/types.html#memory-types>
+    pub(super) fn memory_type(&mut self) -> Result<MemoryType, ParseError> {
+        let index_type = self.index_type();
+        let limit = self.limit(index_type)?;
+
+        if self.input.take_keyword("shared") {
+            Ok(MemoryType::shared(limit))
+        } else {
+            self.input.take_keyword("unshared");
+            Ok(MemoryType::new(limit))
+        }
+    }
+
+    /// Parses a global type, which is a value type that is optionally mutable.
+    ///
+    /// See <https://webassembly.github.io/spec/core/text/types.html#global-types>
+    pub(super) fn global_type(&mut self) -> Result<GlobalType, ParseError> {
+        if self.input.at_list("mut") {
+            self.input.open_list("mut")?;
+
+            let kind = self.value_type()?;
+
+            self.input.right_parenthesis()?;
+
+            Ok(GlobalType::new(kind, Mutability::Mutable))
+        } else {
+            Ok(GlobalType::new(self.value_type()?, Mutability::Immutable))
+        }
+    }
+}
+
+/// The reference type denoted by the given shorthand keyword (e.g., 𝖿𝗎𝗇𝖼𝗋𝖾𝖿).
+///
+/// See <https://webassembly.github.io/gc/core/text/types.html#reference-types>
+fn reference_type_shorthand(keyword: &str) -> Option<ReferenceType> {
+    let kind = match keyword {
+        "funcref" => ReferenceType::Function,
+        "externref" => ReferenceType::External,
+        "exnref" => ReferenceType::Exception,
+        "anyref" => ReferenceType::Nullable(HeapType::Any),
+        "eqref" => ReferenceType::Nullable(HeapType::Equality),
+        "i31ref" => ReferenceType::Nullable(HeapType::I31),
+        "structref" => ReferenceType::Nullable(HeapType::Struct),
+        "arrayref" => ReferenceType::Nullable(HeapType::Array),
+        "nullref" => ReferenceType::Nullable(HeapType::None),
+        "nullfuncref" => ReferenceType::Nullable(HeapType::NoFunction),
+        "nullexternref" => ReferenceType::Nullable(HeapType::NoExternal),
+        "nullexnref" => ReferenceType::Nullable(HeapType::NoException),
+        _ => return None,
+    };
+
+    Some(kind)
+}
@@ -0,0 +1,371 @@
+//! Parse WebAssembly values encoded in the text format.
+//!
+//! See <https://webassembly.github.io/spec/core/text/values.html>
+
+/// Parses an integer into its sign (true if negative) and magnitude.
+/// Integers may be written in decimal or hexadecimal (with a `0x` prefix),
+/// with an optional sign and single underscores between digits.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#integers>
+fn parse_integer(text: &str) -> Option<(bool, u128)> {
+    let (negative, text) = split_sign(text);
+    let (radix, digits) = match text.strip_prefix("0x") {
+        Some(digits) => (16, digits),
+        None => (10, text),
+    };
+
+    if !valid_digits(digits, radix) {
+        return None;
+    }
+
+    let magnitude = u128::from_str_radix(&digits.replace('_', ""), radix).ok()?;
+
+    Some((negative, magnitude))
+}
+
+/// Splits the optional sign from the given number.
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+/// Determines whether the given text is a non-empty sequence of digits in the given radix,
+/// with underscores only allowed between digits.
+fn valid_digits(digits: &str, radix: u32) -> bool {
+    !digits.is_empty()
+        && !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+        && digits.chars().all(|c| c == '_' || c.is_digit(radix))
+}
+
+/// Parses an unsigned 32-bit integer.
+pub fn parse_u32(text: &str) -> Option<u32> {
+    match parse_integer(text)? {
+        (false, magnitude) if !text.starts_with('+') => u32::try_from(magnitude).ok(),
+        _ => None,
+    }
+}
+
+/// Parses an unsigned 64-bit integer.
+pub fn parse_u64(text: &str) -> Option<u64> {
+    match parse_integer(text)? {
+        (false, magnitude) if !text.starts_with('+') => u64::try_from(magnitude).ok(),
+        _ => None,
+    }
+}
+
+/// Parses an uninterpreted 32-bit integer, which may be written as either a signed or unsigned value.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#integers>
+pub fn parse_i32(text: &str) -> Option<i32> {
+    match parse_integer(text)? {
+        (true, magnitude) if magnitude <= 1 << 31 => Some((magnitude as u32).wrapping_neg() as i32),
+        (false, magnitude) => u32::try_from(magnitude).ok().map(|value| value as i32),
+        _ => None,
+    }
+}
+
+/// Parses an uninterpreted 64-bit integer, which may be written as either a signed or unsigned value.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#integers>
+pub fn parse_i64(text: &str) -> Option<i64> {
+    match parse_integer(text)? {
+        (true, magnitude) if magnitude <= 1 << 63 => Some((magnitude as u64).wrapping_neg() as i64),
+        (false, magnitude) => u64::try_from(magnitude).ok().map(|value| value as i64),
+        _ => None,
+    }
+}
+
+/// Parses an uninterpreted 8-bit integer, used for the lanes of vector constants.
+pub fn parse_i8(text: &str) -> Option<u8> {
+    match parse_integer(text)? {
+        (true, magnitude) if magnitude <= 1 << 7 => Some((magnitude as u8).wrapping_neg()),
+        (false, magnitude) => u8::try_from(magnitude).ok(),
+        _ => None,
+    }
+}
+
+/// Parses an uninterpreted 16-bit integer, used for the lanes of vector constants.
+pub fn parse_i16(text: &str) -> Option<u16> {
+    match parse_integer(text)? {
+        (true, magnitude) if magnitude <= 1 << 15 => Some((magnitude as u16).wrapping_neg()),
+        (false, magnitude) => u16::try_from(magnitude).ok(),
+        _ => None,
+    }
+}
+
+/// Parses a 32-bit floating-point number.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#floating-point>
+pub fn parse_f32(text: &str) -> Option<f32> {
+    parse_float(text, 23, 8).map(|bits| f32::from_bits(bits as u32))
+}
+
+/// Parses a 64-bit floating-point number.
+///
+/// See <https://webassembly.github.io/spec/core/text/values.html#floating-point>
+pub fn parse_f64(text: &str) -> Option<f64> {
+    parse_float(text, 52, 11).map(f64::from_bits)
+}
+
+/// Parses a floating-point number into the bits of an IEEE 754 value with the given number of
+/// significand (excluding the implicit bit) and exponent bits.
+fn parse_float(text: &str, significand_bits: u32, exponent_bits: u32) -> Option<u64> {
+    let (negative, unsigned) = split_sign(text);
+    let sign = (negative as u64) << (significand_bits + exponent_bits);
+    let infinity = ((1u64 << exponent_bits) - 1) << significand_bits;
+
+    let magnitude = if unsigned == "inf" {
+        infinity
+    } else if unsigned == "nan" {
+        infinity | 1 << (significand_bits - 1)
+    } else if let Some(payload) = unsigned.strip_prefix("nan:0x") {
+        if !valid_digits(payload, 16) {
+            return None;
+        }
+
+        let payload = u64::from_str_radix(&payload.replace('_', ""), 16).ok()?;
+
+        if payload == 0 || payload >= 1 << significand_bits {
+            return None;
+        }
+
+        infinity | payload
+    } else if let Some(hexadecimal) = unsigned.strip_prefix("0x") {
+        parse_hexadecimal_float(hexadecimal, significand_bits, exponent_bits)?
+    } else {
+        if !valid_decimal_float(unsigned) {
+            return None;
+        }
+
+        let decimal = unsigned.replace('_', "");
+
+        if significand_bits == 23 {
+            decimal.parse::<f32>().ok()?.to_bits() as u64
+        } else {
+            decimal.parse::<f64>().ok()?.to_bits()
+        }
+    };
+
+    Some(sign | magnitude)
+}
+
+/// Determines whether the given text is a valid unsigned decimal floating-point number.
+fn valid_decimal_float(text: &str) -> bool {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(index) => (&text[..index], Some(split_sign(&text[index + 1..]).1)),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    valid_digits(whole, 10)
+        && fraction.is_none_or(|f| f.is_empty() || valid_digits(f, 10))
+        && exponent.is_none_or(|e| valid_digits(e, 10))
+}
+
+/// Parses an unsigned hexadecimal floating-point number (without the `0x` prefix) into the bits
+/// of an IEEE 754 value, rounding to the nearest representable value (ties to even).
+fn parse_hexadecimal_float(text: &str, significand_bits: u32, exponent_bits: u32) -> Option<u64> {
+    let (mantissa, exponent) = match text.find(['p', 'P']) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (mantissa, ""),
+    };
+
+    if !valid_digits(whole, 16) || !(fraction.is_empty() || valid_digits(fraction, 16)) {
+        return None;
+    }
+
+    let mut exponent: i64 = match exponent {
+        Some(exponent) => {
+            let (negative, digits) = split_sign(exponent);
+
+            if !valid_digits(digits, 10) {
+                return None;
+            }
+
+            let magnitude = digits
+                .replace('_', "")
+                .parse::<i64>()
+                .unwrap_or(i64::MAX / 2);
+            let magnitude = magnitude.min(1 << 20);
+
+            if negative {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+        None => 0,
+    };
+
+    let mut significand: u128 = 0;
+    let mut sticky = false;
+    let digits = whole
+        .chars()
+        .map(|c| (c, false))
+        .chain(fraction.chars().map(|c| (c, true)));
+
+    for (c, is_fraction) in digits {
+        let digit = match c.to_digit(16) {
+            Some(digit) => digit as u128,
+            None => continue,
+        };
+
+        if significand >> 120 == 0 {
+            significand = significand << 4 | digit;
+
+            if is_fraction {
+                exponent -= 4;
+            }
+        } else {
+            sticky |= digit != 0;
+
+            if !is_fraction {
+                exponent += 4;
+            }
+        }
+    }
+
+    Some(round_float(
+        significand,
+        exponent,
+        sticky,
+        significand_bits,
+        exponent_bits,
+    ))
+}
+
+/// Rounds the value `significand * 2^exponent` (plus a sticky fraction below the significand)
+/// to the bits of the nearest IEEE 754 value with the given significand and exponent bits.
+fn round_float(
+    significand: u128,
+    exponent: i64,
+    sticky: bool,
+    significand_bits: u32,
+    exponent_bits: u32,
+) -> u64 {
+    if significand == 0 {
+        return 0;
+    }
+
+    let bias = (1i64 << (exponent_bits - 1)) - 1;
+    let top = 127 - significand.leading_zeros() as i64;
+    let minimum = 1 - bias;
+    let subnormal = top + exponent < minimum;
+    let shift = if subnormal {
+        minimum - significand_bits as i64 - exponent
+    } else {
+        top - significand_bits as i64
+    };
+
+    let mut rounded = if shift <= 0 {
+        significand << -shift
+    } else if shift > 128 {
+        0
+    } else {
+        let (quotient, remainder) = if shift == 128 {
+            (0, significand)
+        } else {
+            (significand >> shift, significand & ((1 << shift) - 1))
+        };
+        let half = 1u128 << (shift - 1);
+        let round_up = remainder > half || (remainder == half && (sticky || quotient & 1 == 1));
+
+        quotient + round_up as u128
+    };
+
+    if subnormal {
+        return rounded as u64;
+    }
+
+    let mut biased = top + exponent + bias;
+
+    if rounded >> (significand_bits + 1) != 0 {
+        rounded >>= 1;
+        biased += 1;
+    }
+
+    if biased >= (1 << exponent_bits) - 1 {
+        return ((1u64 << exponent_bits) - 1) << significand_bits;
+    }
+
+    (biased as u64) << significand_bits | (rounded as u64 & ((1 << significand_bits) - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_integers() {
+        assert_eq!(parse_u32("4_294_967_295"), Some(u32::MAX));
+        assert_eq!(parse_u32("0xFF"), Some(255));
+        assert_eq!(parse_u32("4294967296"), None);
+        assert_eq!(parse_u32("-1"), None);
+        assert_eq!(parse_u32("1__0"), None);
+        assert_eq!(parse_i32("-2147483648"), Some(i32::MIN));
+        assert_eq!(parse_i32("0xFFFFFFFF"), Some(-1));
+        assert_eq!(parse_i32("-2147483649"), None);
+        assert_eq!(parse_i64("-0x8000000000000000"), Some(i64::MIN));
+        assert_eq!(parse_i64("18446744073709551615"), Some(-1));
+        assert_eq!(parse_i8("-128"), Some(0x80));
+        assert_eq!(parse_i16("65535"), Some(0xFFFF));
+    }
+
+    #[test]
+    fn parse_decimal_floats() {
+        assert_eq!(parse_f32("1.5"), Some(1.5));
+        assert_eq!(parse_f32("-0.0").map(f32::to_bits), Some(0x8000_0000));
+        assert_eq!(parse_f64("1e10"), Some(1e10));
+        assert_eq!(parse_f64("1_000.000_1"), Some(1000.0001));
+        assert_eq!(parse_f64("1."), Some(1.0));
+        assert_eq!(parse_f64("infinity"), None);
+    }
+
+    #[test]
+    fn parse_special_floats() {
+        assert_eq!(parse_f32("inf"), Some(f32::INFINITY));
+        assert_eq!(parse_f64("-inf"), Some(f64::NEG_INFINITY));
+        assert_eq!(parse_f32("nan").map(f32::to_bits), Some(0x7FC0_0000));
+        assert_eq!(parse_f32("-nan:0x1").map(f32::to_bits), Some(0xFF80_0001));
+        assert_eq!(parse_f32("nan:0x0"), None);
+        assert_eq!(
+            parse_f64("nan:0x8_0000_0000_0001").map(f64::to_bits),
+            Some(0x7FF8_0000_0000_0001)
+        );
+    }
+
+    #[test]
+    fn parse_hexadecimal_floats() {
+        assert_eq!(parse_f32("0x1p-1"), Some(0.5));
+        assert_eq!(parse_f64("0x1.8p1"), Some(3.0));
+        assert_eq!(parse_f64("0x.8"), None);
+        assert_eq!(parse_f64("0x1P+2"), Some(4.0));
+        assert_eq!(parse_f32("0x1.fffffep127"), Some(f32::MAX));
+        assert_eq!(parse_f32("0x1.ffffffp127"), Some(f32::INFINITY));
+        assert_eq!(parse_f32("0x1p-149").map(f32::to_bits), Some(1));
+        assert_eq!(parse_f32("0x1p-150").map(f32::to_bits), Some(0));
+        assert_eq!(parse_f32("0x1.8p-149").map(f32::to_bits), Some(2));
+        assert_eq!(
+            parse_f64("0x1.000000000000080p0").map(f64::to_bits),
+            Some(0x3FF0_0000_0000_0000)
+        );
+        assert_eq!(
+            parse_f64("0x1.000000000000080001p0").map(f64::to_bits),
+            Some(0x3FF0_0000_0000_0001)
+        );
+        assert_eq!(parse_f64("0x1p-1074").map(f64::to_bits), Some(1));
+        assert_eq!(parse_f64("0x1p1024"), Some(f64::INFINITY));
+    }
+}
@@ -2,20 +2,22 @@
 //!
 //! See <https://webassembly.github.io/spec/core/binary/types.html>
 
-use crate::parser::values::{match_byte, parse_u32, parse_vector};
+use crate::parser::errors::{expect, Expected, InputError, ParseResult};
+use crate::parser::values::{match_byte, parse_s33, parse_u32, parse_u64, parse_vector};
 use crate::{
-    FloatType, FunctionType, GlobalType, IntegerType, Limit, MemoryType, Mutability, NumberType,
-    ReferenceType, ResultType, TableType, ValueType,
+    ArrayType, CompositeType, FieldType, FloatType, FunctionType, GlobalType, HeapType, IndexType,
+    IntegerType, Limit, MemoryType, Mutability, NumberType, PackedType, RecursiveType,
+    ReferenceType, ResultType, StorageType, StructType, SubType, TableType, TagType, ValueType,
 };
 use nom::branch::alt;
-use nom::combinator::map;
+use nom::combinator::{cond, map, verify};
+use nom::number::complete::u8 as parse_byte;
 use nom::sequence::{preceded, tuple};
-use nom::IResult;
 
 /// Parses a WebAssembly integer type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#number-types>
-pub fn parse_integer_type(input: &[u8]) -> IResult<&[u8], IntegerType> {
+pub fn parse_integer_type(input: &[u8]) -> ParseResult<'_, IntegerType> {
     alt((
         map(match_byte(0x7F), |_| IntegerType::I32),
         map(match_byte(0x7E), |_| IntegerType::I64),
@@ -25,7 +27,7 @@ pub fn parse_integer_type(input: &[u8]) -> IResult<&[u8], IntegerType> {
 /// Parses a WebAssembly float type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#number-types>
-pub fn parse_float_type(input: &[u8]) -> IResult<&[u8], FloatType> {
+pub fn parse_float_type(input: &[u8]) -> ParseResult<'_, FloatType> {
     alt((
         map(match_byte(0x7D), |_| FloatType::F32),
         map(match_byte(0x7C), |_| FloatType::F64),
@@ -35,44 +37,93 @@ pub fn parse_float_type(input: &[u8]) -> IResult<&[u8], FloatType> {
 /// Parses a WebAssembly number type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#number-types>
-pub fn parse_number_type(input: &[u8]) -> IResult<&[u8], NumberType> {
+pub fn parse_number_type(input: &[u8]) -> ParseResult<'_, NumberType> {
     alt((
         map(parse_integer_type, NumberType::from),
         map(parse_float_type, NumberType::from),
     ))(input)
 }
 
+/// Parses a WebAssembly heap type from the input.
+/// Concrete heap types are encoded as a positive signed 33-bit type index.
+///
+/// See <https://webassembly.github.io/function-references/core/binary/types.html#heap-types>
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#heap-types>
+pub fn parse_heap_type(input: &[u8]) -> ParseResult<'_, HeapType> {
+    expect(
+        Expected::Type,
+        alt((parse_abstract_heap_type, map(parse_s33, HeapType::Index))),
+    )(input)
+}
+
+/// Parses a WebAssembly abstract heap type from the input.
+fn parse_abstract_heap_type(input: &[u8]) -> ParseResult<'_, HeapType> {
+    alt((
+        map(match_byte(0x70), |_| HeapType::Function),
+        map(match_byte(0x6F), |_| HeapType::External),
+        map(match_byte(0x69), |_| HeapType::Exception),
+        map(match_byte(0x6E), |_| HeapType::Any),
+        map(match_byte(0x6D), |_| HeapType::Equality),
+        map(match_byte(0x6C), |_| HeapType::I31),
+        map(match_byte(0x6B), |_| HeapType::Struct),
+        map(match_byte(0x6A), |_| HeapType::Array),
+        map(match_byte(0x71), |_| HeapType::None),
+        map(match_byte(0x73), |_| HeapType::NoFunction),
+        map(match_byte(0x72), |_| HeapType::NoExternal),
+        map(match_byte(0x74), |_| HeapType::NoException),
+    ))(input)
+}
+
 /// Parses a WebAssembly reference type from the input.
+/// Nullable references are decoded in the same form whether or not they are encoded as a shorthand.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#reference-types>
-pub fn parse_reference_type(input: &[u8]) -> IResult<&[u8], ReferenceType> {
+///
+/// See <https://webassembly.github.io/exception-handling/core/binary/types.html#reference-types>
+///
+/// See <https://webassembly.github.io/function-references/core/binary/types.html#reference-types>
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#reference-types>
+pub fn parse_reference_type(input: &[u8]) -> ParseResult<'_, ReferenceType> {
     alt((
-        map(match_byte(0x70), |_| ReferenceType::Function),
-        map(match_byte(0x6F), |_| ReferenceType::External),
+        map(parse_abstract_heap_type, ReferenceType::nullable),
+        map(
+            preceded(match_byte(0x63), parse_heap_type),
+            ReferenceType::nullable,
+        ),
+        map(
+            preceded(match_byte(0x64), parse_heap_type),
+            ReferenceType::NonNullable,
+        ),
     ))(input)
 }
 
 /// Parses a WebAssembly value type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#value-types>
-pub fn parse_value_type(input: &[u8]) -> IResult<&[u8], ValueType> {
-    alt((
-        map(parse_number_type, ValueType::from),
-        map(parse_reference_type, ValueType::from),
-    ))(input)
+pub fn parse_value_type(input: &[u8]) -> ParseResult<'_, ValueType> {
+    expect(
+        Expected::Type,
+        alt((
+            map(parse_number_type, ValueType::from),
+            map(match_byte(0x7B), |_| ValueType::V128),
+            map(parse_reference_type, ValueType::from),
+        )),
+    )(input)
 }
 
 /// Parses a WebAssembly result type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#result-types>
-pub fn parse_result_type(input: &[u8]) -> IResult<&[u8], ResultType> {
+pub fn parse_result_type(input: &[u8]) -> ParseResult<'_, ResultType> {
     map(parse_vector(parse_value_type), ResultType::from)(input)
 }
 
 /// Parses a WebAssembly function type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#function-types>
-pub fn parse_function_type(input: &[u8]) -> IResult<&[u8], FunctionType> {
+pub fn parse_function_type(input: &[u8]) -> ParseResult<'_, FunctionType> {
     map(
         preceded(
             match_byte(0x60),
@@ -82,23 +133,137 @@ pub fn parse_function_type(input: &[u8]) -> IResult<&[u8], FunctionType> {
     )(input)
 }
 
-/// Parses a WebAssembly limit from the input.
+/// Parses a WebAssembly storage type from the input.
 ///
-/// See <https://webassembly.github.io/spec/core/binary/types.html#limits>
-pub fn parse_limit(input: &[u8]) -> IResult<&[u8], Limit> {
+/// See <https://webassembly.github.io/gc/core/binary/types.html#aggregate-types>
+pub fn parse_storage_type(input: &[u8]) -> ParseResult<'_, StorageType> {
+    alt((
+        map(match_byte(0x78), |_| StorageType::Packed(PackedType::I8)),
+        map(match_byte(0x77), |_| StorageType::Packed(PackedType::I16)),
+        map(parse_value_type, StorageType::Value),
+    ))(input)
+}
+
+/// Parses a WebAssembly field type from the input.
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#aggregate-types>
+pub fn parse_field_type(input: &[u8]) -> ParseResult<'_, FieldType> {
+    map(
+        tuple((parse_storage_type, parse_mutability)),
+        |(kind, mutability)| FieldType::new(kind, mutability),
+    )(input)
+}
+
+/// Parses a WebAssembly composite type from the input.
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#composite-types>
+pub fn parse_composite_type(input: &[u8]) -> ParseResult<'_, CompositeType> {
     alt((
-        map(preceded(match_byte(0x00), parse_u32), Limit::unbounded),
+        map(parse_function_type, CompositeType::Function),
         map(
-            preceded(match_byte(0x01), tuple((parse_u32, parse_u32))),
-            |(min, max)| Limit::bounded(min, max),
+            preceded(match_byte(0x5F), parse_vector(parse_field_type)),
+            |fields| CompositeType::Struct(StructType::new(fields)),
         ),
+        map(preceded(match_byte(0x5E), parse_field_type), |field| {
+            CompositeType::Array(ArrayType::new(field))
+        }),
     ))(input)
 }
 
+/// Parses a WebAssembly sub type from the input.
+/// A composite type without a prefix is shorthand for a final sub type without supertypes.
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#recursive-types>
+pub fn parse_sub_type(input: &[u8]) -> ParseResult<'_, SubType> {
+    alt((
+        map(
+            preceded(
+                match_byte(0x50),
+                tuple((parse_vector(parse_u32), parse_composite_type)),
+            ),
+            |(supertypes, kind)| SubType::new(false, supertypes, kind),
+        ),
+        map(
+            preceded(
+                match_byte(0x4F),
+                tuple((parse_vector(parse_u32), parse_composite_type)),
+            ),
+            |(supertypes, kind)| SubType::new(true, supertypes, kind),
+        ),
+        map(parse_composite_type, SubType::from),
+    ))(input)
+}
+
+/// Parses a WebAssembly recursive type from the input.
+/// A sub type without a prefix is shorthand for a recursive group containing only that sub type.
+///
+/// See <https://webassembly.github.io/gc/core/binary/types.html#recursive-types>
+pub fn parse_recursive_type(input: &[u8]) -> ParseResult<'_, RecursiveType> {
+    alt((
+        map(
+            preceded(match_byte(0x4E), parse_vector(parse_sub_type)),
+            RecursiveType::new,
+        ),
+        map(parse_sub_type, RecursiveType::from),
+    ))(input)
+}
+
+/// Bit of the limit flags that denotes the limit has a maximum.
+const LIMIT_MAX_FLAG: u8 = 0x01;
+
+/// Bit of the limit flags that denotes a shared memory.
+const LIMIT_SHARED_FLAG: u8 = 0x02;
+
+/// Bit of the limit flags that denotes a 64-bit index type.
+const LIMIT_64_FLAG: u8 = 0x04;
+
+/// Parses a WebAssembly limit from the input.
+/// Limits with a 64-bit index type are encoded with the flags 0x04 (no maximum) and 0x05.
+///
+/// See <https://webassembly.github.io/spec/core/binary/types.html#limits>
+///
+/// See <https://webassembly.github.io/memory64/core/binary/types.html#limits>
+pub fn parse_limit(input: &[u8]) -> ParseResult<'_, Limit> {
+    let (remaining, flags) = parse_limit_flags(input)?;
+
+    if flags & LIMIT_SHARED_FLAG != 0 {
+        return Err(nom::Err::Error(InputError::new(input, Expected::Syntax)));
+    }
+
+    parse_limit_bounds(flags)(remaining)
+}
+
+/// Parses the flags byte that prefixes a limit.
+fn parse_limit_flags(input: &[u8]) -> ParseResult<'_, u8> {
+    verify(parse_byte, |flags| {
+        flags & !(LIMIT_MAX_FLAG | LIMIT_SHARED_FLAG | LIMIT_64_FLAG) == 0
+    })(input)
+}
+
+/// Parses the minimum and optional maximum of a limit described by the given flags.
+fn parse_limit_bounds<'input>(flags: u8) -> impl FnMut(&'input [u8]) -> ParseResult<'input, Limit> {
+    let index_type = if flags & LIMIT_64_FLAG == 0 {
+        IndexType::I32
+    } else {
+        IndexType::I64
+    };
+    let bound = move |input| match index_type {
+        IndexType::I32 => map(parse_u32, u64::from)(input),
+        IndexType::I64 => parse_u64(input),
+    };
+
+    move |input| {
+        let (input, min) = bound(input)?;
+        let (input, max) = cond(flags & LIMIT_MAX_FLAG != 0, bound)(input)?;
+
+        Ok((input, Limit::with_index_type(index_type, min, max)))
+    }
+}
+
 /// Parses a WebAssembly table type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#table-types>
-pub fn parse_table_type(input: &[u8]) -> IResult<&[u8], TableType> {
+pub fn parse_table_type(input: &[u8]) -> ParseResult<'_, TableType> {
     map(
         tuple((parse_reference_type, parse_limit)),
         |(kind, limit)| TableType::new(kind, limit),
@@ -106,31 +271,55 @@ pub fn parse_table_type(input: &[u8]) -> IResult<&[u8], TableType> {
 }
 
 /// Parses a WebAssembly memory type from the input.
+/// Shared memories are encoded with the limit flags 0x02 (no maximum) and 0x03,
+/// or 0x06 and 0x07 for memories with a 64-bit index type.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#memory-types>
-pub fn parse_memory_type(input: &[u8]) -> IResult<&[u8], MemoryType> {
-    map(parse_limit, MemoryType::new)(input)
+///
+/// See <https://webassembly.github.io/threads/core/binary/types.html#memory-types>
+///
+/// See <https://webassembly.github.io/memory64/core/binary/types.html#memory-types>
+pub fn parse_memory_type(input: &[u8]) -> ParseResult<'_, MemoryType> {
+    let (input, flags) = parse_limit_flags(input)?;
+    let (input, limit) = parse_limit_bounds(flags)(input)?;
+
+    if flags & LIMIT_SHARED_FLAG == 0 {
+        Ok((input, MemoryType::new(limit)))
+    } else {
+        Ok((input, MemoryType::shared(limit)))
+    }
+}
+
+/// Parses a WebAssembly tag type from the input.
+/// Tag types are prefixed by an attribute byte that must be 0x00 (i.e., exception).
+///
+/// See <https://webassembly.github.io/exception-handling/core/binary/types.html#tag-types>
+pub fn parse_tag_type(input: &[u8]) -> ParseResult<'_, TagType> {
+    map(preceded(match_byte(0x00), parse_u32), TagType::new)(input)
 }
 
 /// Parses a WebAssembly global type from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/types.html#global-types>
-pub fn parse_global_type(input: &[u8]) -> IResult<&[u8], GlobalType> {
+pub fn parse_global_type(input: &[u8]) -> ParseResult<'_, GlobalType> {
     map(
-        tuple((
-            parse_value_type,
-            alt((
-                map(match_byte(0x00), |_| Mutability::Immutable),
-                map(match_byte(0x01), |_| Mutability::Mutable),
-            )),
-        )),
+        tuple((parse_value_type, parse_mutability)),
         |(kind, mutability)| GlobalType::new(kind, mutability),
     )(input)
 }
 
+/// Parses the mutability of a global or field type from the input.
+fn parse_mutability(input: &[u8]) -> ParseResult<'_, Mutability> {
+    alt((
+        map(match_byte(0x00), |_| Mutability::Immutable),
+        map(match_byte(0x01), |_| Mutability::Mutable),
+    ))(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nom::multi::count;
 
     #[test]
     fn parse_result_type_with_all_types() {
@@ -171,7 +360,7 @@ mod tests {
         let min = 42;
         let input = vec![0x00, min, extra];
         let (remaining, actual) = parse_limit(input.as_slice()).unwrap();
-        let expected = Limit::unbounded(min as u32);
+        let expected = Limit::unbounded(min as u64);
 
         assert_eq!(actual, expected);
         assert_eq!(remaining, &[extra]);
@@ -184,7 +373,7 @@ mod tests {
         let max = 42;
         let input = vec![0x01, min, max, extra];
         let (remaining, actual) = parse_limit(input.as_slice()).unwrap();
-        let expected = Limit::bounded(min as u32, max as u32);
+        let expected = Limit::bounded(min as u64, max as u64);
 
         assert_eq!(actual, expected);
         assert_eq!(remaining, &[extra]);
@@ -196,19 +385,129 @@ mod tests {
         let min = 42;
         let input = vec![0x00, min, extra];
         let (remaining, actual) = parse_memory_type(input.as_slice()).unwrap();
-        let expected = Limit::unbounded(min as u32).into();
+        let expected = Limit::unbounded(min as u64).into();
 
         assert_eq!(actual, expected);
         assert_eq!(remaining, &[extra]);
     }
 
+    #[test]
+    fn parse_memory_type_shared() {
+        let extra = 0x01;
+        let min = 1;
+        let max = 42;
+        let input = vec![0x03, min, max, extra];
+        let (remaining, actual) = parse_memory_type(input.as_slice()).unwrap();
+        let expected = MemoryType::shared(Limit::bounded(min as u64, max as u64));
+
+        assert_eq!(actual, expected);
+        assert_eq!(remaining, &[extra]);
+    }
+
+    #[test]
+    fn parse_memory_type_64() {
+        let extra = 0x01;
+        let input = vec![0x07, 0x01, 0x80, 0x80, 0x80, 0x80, 0x10, extra];
+        let (remaining, actual) = parse_memory_type(input.as_slice()).unwrap();
+        let expected = MemoryType::shared(Limit::with_index_type(
+            IndexType::I64,
+            1,
+            Some(u32::MAX as u64 + 1),
+        ));
+
+        assert_eq!(actual, expected);
+        assert_eq!(remaining, &[extra]);
+    }
+
+    #[test]
+    fn parse_limit_shared() {
+        let input = vec![0x03, 0x01, 0x02];
+        let result = parse_limit(input.as_slice());
+
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(InputError::new(
+                input.as_slice(),
+                Expected::Syntax
+            )))
+        );
+    }
+
+    #[test]
+    fn parse_typed_reference_types() {
+        let extra = 0x01;
+        let input = vec![0x63, 0x70, 0x64, 0x02, 0x6F, extra];
+        let (remaining, actual) = count(parse_reference_type, 3)(input.as_slice()).unwrap();
+        let expected = vec![
+            ReferenceType::Function,
+            ReferenceType::NonNullable(HeapType::Index(2)),
+            ReferenceType::External,
+        ];
+
+        assert_eq!(actual, expected);
+        assert_eq!(remaining, &[extra]);
+    }
+
+    #[test]
+    fn parse_gc_reference_types() {
+        let extra = 0x01;
+        let input = vec![0x6E, 0x64, 0x6C, 0x63, 0x71, extra];
+        let (remaining, actual) = count(parse_reference_type, 3)(input.as_slice()).unwrap();
+        let expected = vec![
+            ReferenceType::Nullable(HeapType::Any),
+            ReferenceType::NonNullable(HeapType::I31),
+            ReferenceType::Nullable(HeapType::None),
+        ];
+
+        assert_eq!(actual, expected);
+        assert_eq!(remaining, &[extra]);
+    }
+
+    #[test]
+    fn parse_recursive_group() {
+        let extra = 0x01;
+        let input = vec![
+            0x4E, 0x02, 0x50, 0x00, 0x5F, 0x01, 0x78, 0x01, 0x4F, 0x01, 0x00, 0x5E, 0x63, 0x00,
+            0x00, extra,
+        ];
+        let (remaining, actual) = parse_recursive_type(input.as_slice()).unwrap();
+        let expected = RecursiveType::new(vec![
+            SubType::new(
+                false,
+                vec![],
+                StructType::new(vec![FieldType::mutable(PackedType::I8.into())]).into(),
+            ),
+            SubType::new(
+                true,
+                vec![0],
+                ArrayType::new(FieldType::immutable(
+                    ValueType::Reference(ReferenceType::Nullable(HeapType::Index(0))).into(),
+                ))
+                .into(),
+            ),
+        ]);
+
+        assert_eq!(actual, expected);
+        assert_eq!(remaining, &[extra]);
+    }
+
+    #[test]
+    fn parse_recursive_type_shorthand() {
+        let extra = 0x01;
+        let input = vec![0x60, 0x00, 0x00, extra];
+        let (remaining, actual) = parse_recursive_type(input.as_slice()).unwrap();
+
+        assert_eq!(actual, FunctionType::runnable().into());
+        assert_eq!(remaining, &[extra]);
+    }
+
     #[test]
     fn parse_table_type_unbounded() {
         let extra = 0x01;
         let min = 42;
         let input = vec![0x6F, 0x00, min, extra];
         let (remaining, actual) = parse_table_type(input.as_slice()).unwrap();
-        let expected = TableType::new(ReferenceType::External, Limit::unbounded(min as u32));
+        let expected = TableType::new(ReferenceType::External, Limit::unbounded(min as u64));
 
         assert_eq!(actual, expected);
         assert_eq!(remaining, &[extra]);
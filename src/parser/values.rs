@@ -1,41 +1,59 @@
 use crate::leb128::{parse_signed, parse_unsigned, LEB128Error};
+use crate::parser::errors::{expect, Expected, InputError, ParseResult};
 use crate::Name;
-use nom::bytes::complete::{tag, take};
+use nom::bytes::complete::take;
 use nom::combinator::{map, map_res};
 use nom::multi::fold_many_m_n;
-use nom::{IResult, Parser};
+use nom::Parser;
 use std::convert::TryFrom;
 
 /// Parses a single byte and verified the parsed byte matches the given byte.
-pub fn match_byte<'input>(byte: u8) -> impl FnMut(&'input [u8]) -> IResult<&'input [u8], u8> {
-    map(tag([byte]), |bytes: &'input [u8]| bytes[0])
+/// The byte is compared directly, as alternatives that start with different bytes are tried in turn throughout the parser.
+pub fn match_byte<'input>(byte: u8) -> impl Fn(&'input [u8]) -> ParseResult<'input, u8> {
+    move |input: &'input [u8]| match input.split_first() {
+        Some((first, remaining)) if *first == byte => Ok((remaining, byte)),
+        _ => Err(nom::Err::Error(InputError::new(
+            input,
+            Expected::Byte(byte),
+        ))),
+    }
 }
 
 /// Parses an unsigned 32-bit integer using LEB128 (Little-Endian Base 128) encoding.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#integers>
-pub fn parse_u32(input: &[u8]) -> IResult<&[u8], u32> {
+pub fn parse_u32(input: &[u8]) -> ParseResult<'_, u32> {
     nomify(input, parse_unsigned::<u32>(input))
 }
 
+/// Parses an unsigned 64-bit integer using LEB128 (Little-Endian Base 128) encoding.
+///
+/// See <https://webassembly.github.io/spec/core/binary/values.html#integers>
+pub fn parse_u64(input: &[u8]) -> ParseResult<'_, u64> {
+    nomify(input, parse_unsigned::<u64>(input))
+}
+
 /// Parses a signed 33-bit integer using LEB128 (Little-Endian Base 128) encoding.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#integers>
-pub fn parse_s33(input: &[u8]) -> IResult<&[u8], u32> {
-    map_res(|i| nomify(i, parse_signed::<i64>(i)), u32::try_from)(input)
+pub fn parse_s33(input: &[u8]) -> ParseResult<'_, u32> {
+    expect(
+        Expected::Integer,
+        map_res(|i| nomify(i, parse_signed::<i64>(i)), u32::try_from),
+    )(input)
 }
 
 /// Parses a signed 32-bit integer using LEB128 (Little-Endian Base 128) encoding.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#integers>
-pub fn parse_s32(input: &[u8]) -> IResult<&[u8], i32> {
+pub fn parse_s32(input: &[u8]) -> ParseResult<'_, i32> {
     nomify(input, parse_signed::<i32>(input))
 }
 
 /// Parses a signed 32-bit integer using LEB128 (Little-Endian Base 128) encoding.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#integers>
-pub fn parse_s64(input: &[u8]) -> IResult<&[u8], i64> {
+pub fn parse_s64(input: &[u8]) -> ParseResult<'_, i64> {
     nomify(input, parse_signed::<i64>(input))
 }
 
@@ -43,27 +61,34 @@ pub fn parse_s64(input: &[u8]) -> IResult<&[u8], i64> {
 fn nomify<'input, T>(
     input: &'input [u8],
     result: Result<(&'input [u8], T), LEB128Error>,
-) -> IResult<&'input [u8], T> {
+) -> ParseResult<'input, T> {
     match result {
         Ok(value) => Ok(value),
-        Err(_) => Err(nom::Err::Failure(nom::error::Error::new(
-            input,
-            nom::error::ErrorKind::MapRes,
-        ))),
+        Err(_) => Err(nom::Err::Failure(InputError::new(input, Expected::Integer))),
     }
 }
 
 /// Parses a WebAssembly name value.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#names>
-pub fn parse_name(input: &[u8]) -> IResult<&[u8], Name> {
-    map(map_res(parse_byte_vector, std::str::from_utf8), Name::from)(input)
+pub fn parse_name(input: &[u8]) -> ParseResult<'_, Name> {
+    map(parse_borrowed_name, Name::from)(input)
+}
+
+/// Parses a WebAssembly name value, borrowing its text from the input.
+///
+/// See <https://webassembly.github.io/spec/core/binary/values.html#names>
+pub fn parse_borrowed_name(input: &[u8]) -> ParseResult<'_, &str> {
+    expect(
+        Expected::Name,
+        map_res(parse_byte_vector, std::str::from_utf8),
+    )(input)
 }
 
 /// Parses a WebAssembly byte vector.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/values.html#bytes>
-pub fn parse_byte_vector(input: &[u8]) -> IResult<&[u8], &[u8]> {
+pub fn parse_byte_vector(input: &[u8]) -> ParseResult<'_, &[u8]> {
     let (input, length) = parse_u32(input)?;
     let (input, bytes) = take(length as usize)(input)?;
 
@@ -73,11 +98,9 @@ pub fn parse_byte_vector(input: &[u8]) -> IResult<&[u8], &[u8]> {
 /// Parses a WebAssembly encoded vector of items from the input.
 ///
 /// See <https://webassembly.github.io/spec/core/binary/conventions.html#vectors>
-pub fn parse_vector<'input, O, P>(
-    parser: P,
-) -> impl Fn(&'input [u8]) -> IResult<&'input [u8], Vec<O>>
+pub fn parse_vector<'input, O, P>(parser: P) -> impl Fn(&'input [u8]) -> ParseResult<'input, Vec<O>>
 where
-    P: Copy + Parser<&'input [u8], O, nom::error::Error<&'input [u8]>>,
+    P: Copy + Parser<&'input [u8], O, InputError<'input>>,
 {
     move |input| {
         let (input, length) = parse_u32(input)?;
@@ -181,7 +204,7 @@ mod tests {
         assert!(result.is_err());
     }
 
-    fn take_byte(input: &[u8]) -> IResult<&[u8], u8> {
+    fn take_byte(input: &[u8]) -> ParseResult<'_, u8> {
         map(take(1usize), |x: &[u8]| x[0])(input)
     }
 }
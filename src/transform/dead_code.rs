@@ -0,0 +1,592 @@
+use crate::model::{
+    DataIndex, DataMode, ElementIndex, ElementMode, Expression, FunctionIndex, GlobalIndex, Import,
+    ImportDescription, Module, RecursiveType, TableIndex, TypeIndex,
+};
+use crate::transform::{remap_indices, IndexMap, IndexRemapping, TransformError};
+use crate::validation::Space;
+use crate::visit::Visit;
+use std::collections::BTreeSet;
+
+/// Removes the definitions of a module that are unreachable from its roots,
+/// and renumbers the remaining definitions using [`remap_indices`].
+///
+/// The roots of a module are its exports, the start function, active element and data segments,
+/// memories and tags. Definitions are reachable when a reachable definition refers to them
+/// (e.g., through `call`, `ref.func`, `global.get`, `call_indirect` or `table.init` instructions).
+/// Unreachable functions, globals, types, tables and element and data segments are removed,
+/// including unused imports. Memories and tags are always kept.
+///
+/// The entries of declarative element segments only declare functions for use in `ref.func` instructions,
+/// so entries that refer to removed functions are dropped, along with segments that become empty.
+///
+/// Fails if the module refers to a definition that does not exist,
+/// or if the body of a function parsed lazily is invalid.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{eliminate_dead_code, ControlInstruction, Export, Expression, Function, FunctionType, Module, ResultType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder.add_function_type(FunctionType::runnable()).unwrap();
+///
+/// builder.add_function(Function::new(kind, ResultType::empty(), Expression::empty())).unwrap();
+/// builder
+///     .add_function(Function::new(
+///         kind,
+///         ResultType::empty(),
+///         Expression::from(vec![ControlInstruction::Nop.into()]),
+///     ))
+///     .unwrap();
+/// builder.add_export(Export::function("main".into(), 1));
+///
+/// let module = eliminate_dead_code(builder.build()).unwrap();
+///
+/// assert_eq!(
+///     module.functions(),
+///     Some(&[Function::new(kind, ResultType::empty(), Expression::from(vec![ControlInstruction::Nop.into()]))][..])
+/// );
+/// assert_eq!(module.exports(), Some(&[Export::function("main".into(), 0)][..]));
+/// ```
+pub fn eliminate_dead_code(mut module: Module) -> Result<Module, TransformError> {
+    #[cfg(feature = "parser")]
+    super::remap::decode_bodies(&module)?;
+
+    let (live, declarations) = {
+        let mut reachability = Reachability::new(&module);
+
+        reachability.visit_roots();
+        reachability.propagate();
+
+        let declarations = reachability.visit_declarations();
+
+        reachability.propagate();
+
+        (reachability.live, declarations)
+    };
+
+    for (element, initializers) in module.elements.iter_mut().flatten().zip(declarations) {
+        if let Some(initializers) = initializers {
+            element.initializers = initializers;
+        }
+    }
+
+    let mut remapping = IndexRemapping::new();
+
+    remapping.set_types(Some(index_map(&live.types)));
+    remapping.set_functions(Some(index_map(&live.functions)));
+    remapping.set_tables(Some(index_map(&live.tables)));
+    remapping.set_globals(Some(index_map(&live.globals)));
+    remapping.set_elements(Some(index_map(&live.elements)));
+    remapping.set_data(Some(index_map(&live.data)));
+
+    remap_indices(module, &remapping)
+}
+
+/// Assigns consecutive new indices to the live definitions of an index space, in their original order.
+fn index_map(live: &BTreeSet<u32>) -> IndexMap {
+    live.iter()
+        .enumerate()
+        .map(|(index, old)| (*old, index as u32))
+        .collect()
+}
+
+/// The live definitions of each index space that can be removed.
+#[derive(Default)]
+struct Live {
+    types: BTreeSet<TypeIndex>,
+    functions: BTreeSet<FunctionIndex>,
+    tables: BTreeSet<TableIndex>,
+    globals: BTreeSet<GlobalIndex>,
+    elements: BTreeSet<ElementIndex>,
+    data: BTreeSet<DataIndex>,
+}
+
+/// A worklist-based traversal of the definitions that are reachable from the roots of a module.
+struct Reachability<'ast> {
+    module: &'ast Module,
+    types: Vec<(TypeIndex, &'ast RecursiveType)>,
+    function_imports: Vec<&'ast Import>,
+    table_imports: Vec<&'ast Import>,
+    global_imports: Vec<&'ast Import>,
+    live: Live,
+    pending: Vec<(Space, u32)>,
+}
+
+impl<'ast> Reachability<'ast> {
+    fn new(module: &'ast Module) -> Self {
+        let mut start = 0;
+        let types = module
+            .types()
+            .unwrap_or_default()
+            .iter()
+            .map(|group| {
+                let index = start;
+
+                start += group.types.len() as u32;
+
+                (index, group)
+            })
+            .collect();
+        let imports = module.imports().unwrap_or_default();
+        let imports_of = |space: fn(&ImportDescription) -> bool| {
+            imports
+                .iter()
+                .filter(|import| space(import.description()))
+                .collect()
+        };
+
+        Reachability {
+            module,
+            types,
+            function_imports: imports_of(|description| {
+                matches!(description, ImportDescription::Function(_))
+            }),
+            table_imports: imports_of(|description| {
+                matches!(description, ImportDescription::Table(_))
+            }),
+            global_imports: imports_of(|description| {
+                matches!(description, ImportDescription::Global(_))
+            }),
+            live: Live::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Visits the exports, start function, active segments, memories and tags of the module.
+    fn visit_roots(&mut self) {
+        let module = self.module;
+
+        for export in module.exports().unwrap_or_default() {
+            self.visit_export(export);
+        }
+
+        if let Some(start) = module.start() {
+            self.visit_start(start);
+        }
+
+        for (index, element) in module.elements().unwrap_or_default().iter().enumerate() {
+            if let ElementMode::Active(_, _) = element.mode() {
+                self.visit_element_index(index as ElementIndex);
+            }
+        }
+
+        for (index, data) in module.data().unwrap_or_default().iter().enumerate() {
+            if let DataMode::Active(_, _) = data.mode() {
+                self.visit_data_index(index as DataIndex);
+            }
+        }
+
+        for import in module.imports().unwrap_or_default() {
+            if let ImportDescription::Memory(_) | ImportDescription::Tag(_) = import.description() {
+                self.visit_import(import);
+            }
+        }
+
+        for memory in module.memories().unwrap_or_default() {
+            self.visit_memory(memory);
+        }
+
+        for tag in module.tags().unwrap_or_default() {
+            self.visit_tag(tag);
+        }
+    }
+
+    /// Visits the definitions that became live until no new definitions are found.
+    fn propagate(&mut self) {
+        let module = self.module;
+
+        while let Some((space, index)) = self.pending.pop() {
+            match space {
+                Space::Type => {
+                    let group = self
+                        .types
+                        .iter()
+                        .rev()
+                        .find(|(start, _)| *start <= index)
+                        .copied();
+
+                    if let Some((start, group)) = group {
+                        for offset in 0..group.types.len() as u32 {
+                            self.visit_type_index(start + offset);
+                        }
+
+                        if start == index {
+                            self.visit_recursive_type(group);
+                        }
+                    }
+                }
+                Space::Function => {
+                    let imports = self.function_imports.len();
+
+                    match self.function_imports.get(index as usize) {
+                        Some(import) => self.visit_import(import),
+                        None => {
+                            let functions = module.functions().unwrap_or_default();
+
+                            if let Some(function) = functions.get(index as usize - imports) {
+                                self.visit_function(function);
+                            }
+                        }
+                    }
+                }
+                Space::Table => {
+                    let imports = self.table_imports.len();
+
+                    match self.table_imports.get(index as usize) {
+                        Some(import) => self.visit_import(import),
+                        None => {
+                            let tables = module.tables().unwrap_or_default();
+
+                            if let Some(table) = tables.get(index as usize - imports) {
+                                self.visit_table(table);
+                            }
+                        }
+                    }
+                }
+                Space::Global => {
+                    let imports = self.global_imports.len();
+
+                    match self.global_imports.get(index as usize) {
+                        Some(import) => self.visit_import(import),
+                        None => {
+                            let globals = module.globals().unwrap_or_default();
+
+                            if let Some(global) = globals.get(index as usize - imports) {
+                                self.visit_global(global);
+                            }
+                        }
+                    }
+                }
+                Space::Element => {
+                    if let Some(element) = module.elements().unwrap_or_default().get(index as usize)
+                    {
+                        self.visit_element(element);
+                    }
+                }
+                Space::Data => {
+                    if let Some(data) = module.data().unwrap_or_default().get(index as usize) {
+                        self.visit_data(data);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Keeps the entries of declarative element segments that only refer to live functions,
+    /// visiting the segments that are not empty.
+    /// Returns the remaining entries of each declarative element segment.
+    fn visit_declarations(&mut self) -> Vec<Option<Vec<Expression>>> {
+        let module = self.module;
+        let mut declarations = Vec::new();
+
+        for (index, element) in module.elements().unwrap_or_default().iter().enumerate() {
+            if *element.mode() != ElementMode::Declarative {
+                declarations.push(None);
+                continue;
+            }
+
+            let initializers: Vec<&'ast Expression> = element
+                .initializers()
+                .iter()
+                .filter(|initializer| {
+                    let mut functions = Functions::default();
+
+                    functions.visit_expression(initializer);
+                    functions
+                        .0
+                        .iter()
+                        .all(|function| self.live.functions.contains(function))
+                })
+                .collect();
+
+            if !initializers.is_empty() {
+                self.live.elements.insert(index as ElementIndex);
+                self.visit_reference_type(&element.kind);
+
+                for initializer in &initializers {
+                    self.visit_expression(initializer);
+                }
+            }
+
+            declarations.push(Some(initializers.into_iter().cloned().collect()));
+        }
+
+        declarations
+    }
+
+    /// Marks the definition with the given index as live, scheduling it to be visited if it was not live already.
+    fn mark(&mut self, space: Space, index: u32) {
+        let live = match space {
+            Space::Type => &mut self.live.types,
+            Space::Function => &mut self.live.functions,
+            Space::Table => &mut self.live.tables,
+            Space::Global => &mut self.live.globals,
+            Space::Element => &mut self.live.elements,
+            Space::Data => &mut self.live.data,
+            _ => return,
+        };
+
+        if live.insert(index) {
+            self.pending.push((space, index));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Reachability<'ast> {
+    fn visit_type_index(&mut self, index: TypeIndex) {
+        self.mark(Space::Type, index);
+    }
+
+    fn visit_function_index(&mut self, index: FunctionIndex) {
+        self.mark(Space::Function, index);
+    }
+
+    fn visit_table_index(&mut self, index: TableIndex) {
+        self.mark(Space::Table, index);
+    }
+
+    fn visit_global_index(&mut self, index: GlobalIndex) {
+        self.mark(Space::Global, index);
+    }
+
+    fn visit_element_index(&mut self, index: ElementIndex) {
+        self.mark(Space::Element, index);
+    }
+
+    fn visit_data_index(&mut self, index: DataIndex) {
+        self.mark(Space::Data, index);
+    }
+}
+
+/// Collects the functions referenced by an expression.
+#[derive(Default)]
+struct Functions(Vec<FunctionIndex>);
+
+impl<'ast> Visit<'ast> for Functions {
+    fn visit_function_index(&mut self, index: FunctionIndex) {
+        self.0.push(index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+    use crate::validation::validate;
+
+    #[test]
+    fn eliminate_unreachable_definitions() {
+        let mut builder = Module::builder();
+        let mut names = NameSection::new();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_function_type(FunctionType::side_effect(ResultType::from(vec![
+                ValueType::I32,
+            ])))
+            .unwrap();
+        builder
+            .add_import(Import::function("env".into(), "unused".into(), 1))
+            .unwrap();
+        builder
+            .add_import(Import::function("env".into(), "log".into(), 0))
+            .unwrap();
+        builder
+            .add_global(Global::immutable(
+                ValueType::I32,
+                Expression::from(vec![42i32.into()]),
+            ))
+            .unwrap();
+        builder
+            .add_global(Global::immutable(
+                ValueType::FunctionReference,
+                Expression::from(vec![ReferenceInstruction::Function(3).into()]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    VariableInstruction::GlobalGet(1).into(),
+                    ParametricInstruction::Drop.into(),
+                    ControlInstruction::Call(1).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(0, ResultType::empty(), Expression::empty()))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                1,
+                ResultType::empty(),
+                Expression::from(vec![
+                    VariableInstruction::GlobalGet(0).into(),
+                    ControlInstruction::Call(0).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::declarative(
+                ReferenceType::Function,
+                vec![3u32, 4].to_initializers(),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::passive(
+                ReferenceType::Function,
+                vec![4u32].to_initializers(),
+            ))
+            .unwrap();
+        builder.add_data(Data::passive(vec![1])).unwrap();
+        builder
+            .add_data(Data::active(
+                0,
+                Expression::from(vec![0i32.into()]),
+                vec![2],
+            ))
+            .unwrap();
+        builder
+            .add_memory(MemoryType::from(Limit::unbounded(1)).into())
+            .unwrap();
+        builder.include_data_count();
+        builder.add_export(Export::function("main".into(), 2));
+        names.functions_mut().insert(2, "main".into());
+        names.functions_mut().insert(4, "dead".into());
+        builder.set_names(Some(names));
+
+        let module = eliminate_dead_code(builder.build()).unwrap();
+
+        assert_eq!(validate(&module), Ok(()));
+        assert_eq!(module.types().map(<[_]>::len), Some(1));
+        assert_eq!(
+            module.imports(),
+            Some(&[Import::function("env".into(), "log".into(), 0)][..])
+        );
+        assert_eq!(
+            module.globals(),
+            Some(
+                &[Global::immutable(
+                    ValueType::FunctionReference,
+                    Expression::from(vec![ReferenceInstruction::Function(2).into()]),
+                )][..]
+            )
+        );
+        assert_eq!(
+            module.functions(),
+            Some(
+                &[
+                    Function::new(
+                        0,
+                        ResultType::empty(),
+                        Expression::from(vec![
+                            VariableInstruction::GlobalGet(0).into(),
+                            ParametricInstruction::Drop.into(),
+                            ControlInstruction::Call(0).into(),
+                        ]),
+                    ),
+                    Function::new(0, ResultType::empty(), Expression::empty())
+                ][..]
+            )
+        );
+        assert_eq!(
+            module.elements(),
+            Some(
+                &[Element::declarative(
+                    ReferenceType::Function,
+                    vec![2u32].to_initializers(),
+                )][..]
+            )
+        );
+        assert_eq!(
+            module.data(),
+            Some(
+                &[Data::active(
+                    0,
+                    Expression::from(vec![0i32.into()]),
+                    vec![2]
+                )][..]
+            )
+        );
+        assert_eq!(module.data_count(), Some(1));
+        assert_eq!(
+            module.exports(),
+            Some(&[Export::function("main".into(), 1)][..])
+        );
+        assert_eq!(
+            module.names().unwrap().function(1),
+            Some(&Name::from("main"))
+        );
+        assert_eq!(module.names().unwrap().function(2), None);
+    }
+
+    #[test]
+    fn keep_reachable_definitions() {
+        let mut builder = Module::builder();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_table(TableType::new(ReferenceType::Function, Limit::unbounded(2)).into())
+            .unwrap();
+        builder
+            .add_function(Function::new(0, ResultType::empty(), Expression::empty()))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    0i32.into(),
+                    ControlInstruction::CallIndirect(0, 0).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::active(
+                0,
+                Expression::from(vec![0i32.into()]),
+                ReferenceType::Function,
+                vec![0u32].to_initializers(),
+            ))
+            .unwrap();
+        builder.set_start(Some(Start::new(1)));
+
+        let module = builder.build();
+
+        assert_eq!(eliminate_dead_code(module.clone()), Ok(module));
+    }
+
+    #[test]
+    fn undefined_reference() {
+        let mut builder = Module::builder();
+
+        builder.set_start(Some(Start::new(0)));
+
+        assert_eq!(
+            eliminate_dead_code(builder.build()),
+            Err(TransformError::InvalidMapping(Space::Function))
+        );
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn invalid_lazy_body() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\xFF\x0B";
+        let module = crate::parse_binary_lazy(bytes).unwrap();
+        let error = match crate::parse_binary(bytes) {
+            Err(crate::ParseError::InvalidBinary(error)) => error,
+            result => panic!("unexpected result: {:?}", result),
+        };
+
+        assert_eq!(
+            eliminate_dead_code(module.clone()),
+            Err(TransformError::InvalidBinary(error))
+        );
+        assert_eq!(
+            remap_indices(module, &IndexRemapping::new()),
+            Err(TransformError::InvalidBinary(error))
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::validation::Space;
+use thiserror::Error;
+
+/// An error in transforming a WebAssembly module.
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum TransformError {
+    #[error("The mapping for the {0} index space must assign consecutive indices starting at 0 to existing definitions, with imports preceding definitions and recursive types kept whole.")]
+    InvalidMapping(Space),
+    #[error("The module refers to the {0} with index {1}, which is removed by the mapping.")]
+    UnmappedIndex(Space, u32),
+    #[cfg(feature = "parser")]
+    #[error("The code of a function parsed lazily is not a valid binary format: {0}.")]
+    InvalidBinary(crate::parser::BinaryError),
+}
@@ -0,0 +1,12 @@
+//! Transformations of WebAssembly modules.
+//!
+//! Transformations rewrite a module as a whole, while keeping the references between its
+//! definitions consistent (e.g., the function index of a call instruction after functions are reordered).
+
+mod dead_code;
+mod errors;
+mod remap;
+
+pub use dead_code::eliminate_dead_code;
+pub use errors::TransformError;
+pub use remap::{remap_indices, IndexMap, IndexRemapping};
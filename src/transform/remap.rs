@@ -0,0 +1,735 @@
+use crate::model::{
+    DataIndex, ElementIndex, FunctionIndex, GlobalIndex, Import, ImportDescription, MemoryIndex,
+    Module, NameSection, RecursiveType, TableIndex, TagIndex, TypeIndex,
+};
+use crate::transform::TransformError;
+use crate::validation::Space;
+use crate::visit::VisitMut;
+use std::collections::BTreeMap;
+
+/// Decodes the bodies of the functions parsed lazily by `parse_binary_lazy`,
+/// so that invalid bodies are reported as errors instead of panicking while transforming the module.
+#[cfg(feature = "parser")]
+pub(super) fn decode_bodies(module: &Module) -> Result<(), TransformError> {
+    for function in module.functions.as_deref().unwrap_or_default() {
+        function
+            .body
+            .decode()
+            .map_err(TransformError::InvalidBinary)?;
+    }
+
+    Ok(())
+}
+
+/// A map from the old index of a definition to its new index in the same index space.
+pub type IndexMap = BTreeMap<u32, u32>;
+
+/// The index spaces that include imports, in the order of the kinds of import descriptions.
+const IMPORTED_SPACES: [Space; 5] = [
+    Space::Function,
+    Space::Table,
+    Space::Memory,
+    Space::Global,
+    Space::Tag,
+];
+
+/// Old-to-new index maps for the index spaces of a module.
+/// An index space without a map is left unchanged.
+/// Otherwise, the map describes the entire index space after remapping:
+/// definitions (including imports) whose old index is absent from the map are removed,
+/// and the remaining definitions are reordered according to their new indices.
+///
+/// As in the module itself, indices include the imports of the same index space.
+/// Therefore, imports must be mapped to the lowest new indices, preceding all other definitions.
+/// The type index space counts the sub types of each recursive type,
+/// which can only be moved or removed as a whole.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{IndexMap, IndexRemapping};
+///
+/// let mut remapping = IndexRemapping::new();
+///
+/// assert_eq!(remapping.functions(), None);
+///
+/// remapping.set_functions(Some(IndexMap::from([(0, 1), (1, 0)])));
+///
+/// assert_eq!(remapping.functions(), Some(&IndexMap::from([(0, 1), (1, 0)])));
+/// assert_eq!(remapping.types(), None);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IndexRemapping {
+    types: Option<IndexMap>,
+    functions: Option<IndexMap>,
+    tables: Option<IndexMap>,
+    memories: Option<IndexMap>,
+    globals: Option<IndexMap>,
+    tags: Option<IndexMap>,
+    elements: Option<IndexMap>,
+    data: Option<IndexMap>,
+}
+
+impl IndexRemapping {
+    /// Creates a new remapping that leaves every index space unchanged.
+    pub fn new() -> Self {
+        IndexRemapping::default()
+    }
+
+    /// The map for the type index space, if any.
+    pub fn types(&self) -> Option<&IndexMap> {
+        self.types.as_ref()
+    }
+
+    /// Sets the map for the type index space.
+    pub fn set_types(&mut self, types: Option<IndexMap>) {
+        self.types = types;
+    }
+
+    /// The map for the function index space, if any.
+    pub fn functions(&self) -> Option<&IndexMap> {
+        self.functions.as_ref()
+    }
+
+    /// Sets the map for the function index space.
+    pub fn set_functions(&mut self, functions: Option<IndexMap>) {
+        self.functions = functions;
+    }
+
+    /// The map for the table index space, if any.
+    pub fn tables(&self) -> Option<&IndexMap> {
+        self.tables.as_ref()
+    }
+
+    /// Sets the map for the table index space.
+    pub fn set_tables(&mut self, tables: Option<IndexMap>) {
+        self.tables = tables;
+    }
+
+    /// The map for the memory index space, if any.
+    pub fn memories(&self) -> Option<&IndexMap> {
+        self.memories.as_ref()
+    }
+
+    /// Sets the map for the memory index space.
+    pub fn set_memories(&mut self, memories: Option<IndexMap>) {
+        self.memories = memories;
+    }
+
+    /// The map for the global index space, if any.
+    pub fn globals(&self) -> Option<&IndexMap> {
+        self.globals.as_ref()
+    }
+
+    /// Sets the map for the global index space.
+    pub fn set_globals(&mut self, globals: Option<IndexMap>) {
+        self.globals = globals;
+    }
+
+    /// The map for the tag index space, if any.
+    pub fn tags(&self) -> Option<&IndexMap> {
+        self.tags.as_ref()
+    }
+
+    /// Sets the map for the tag index space.
+    pub fn set_tags(&mut self, tags: Option<IndexMap>) {
+        self.tags = tags;
+    }
+
+    /// The map for the element segment index space, if any.
+    pub fn elements(&self) -> Option<&IndexMap> {
+        self.elements.as_ref()
+    }
+
+    /// Sets the map for the element segment index space.
+    pub fn set_elements(&mut self, elements: Option<IndexMap>) {
+        self.elements = elements;
+    }
+
+    /// The map for the data segment index space, if any.
+    pub fn data(&self) -> Option<&IndexMap> {
+        self.data.as_ref()
+    }
+
+    /// Sets the map for the data segment index space.
+    pub fn set_data(&mut self, data: Option<IndexMap>) {
+        self.data = data;
+    }
+
+    /// The map for the given index space, if any.
+    fn map(&self, space: Space) -> Option<&IndexMap> {
+        match space {
+            Space::Type => self.types(),
+            Space::Function => self.functions(),
+            Space::Table => self.tables(),
+            Space::Memory => self.memories(),
+            Space::Global => self.globals(),
+            Space::Tag => self.tags(),
+            Space::Element => self.elements(),
+            Space::Data => self.data(),
+            Space::Local | Space::Label | Space::Field => None,
+        }
+    }
+}
+
+/// Remaps the index spaces of a module.
+/// Definitions are removed and reordered according to the remapping,
+/// and every reference to them is rewritten to the new index,
+/// including the references in instructions, imports, exports, the start function,
+/// element segments, data segments and the names of the module.
+/// The data count, if any, is updated to the number of remaining data segments.
+///
+/// Fails if a map does not describe a valid index space,
+/// if a remaining part of the module refers to a removed definition,
+/// or if the body of a function parsed lazily is invalid.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::{remap_indices, ControlInstruction, Export, Expression, Function, FunctionType, Import, IndexMap, IndexRemapping, Module, ResultType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder.add_function_type(FunctionType::runnable()).unwrap();
+///
+/// builder.add_import(Import::function("env".into(), "log".into(), kind)).unwrap();
+/// builder.add_function(Function::new(kind, ResultType::empty(), Expression::empty())).unwrap();
+/// builder
+///     .add_function(Function::new(
+///         kind,
+///         ResultType::empty(),
+///         Expression::from(vec![ControlInstruction::Call(0).into()]),
+///     ))
+///     .unwrap();
+/// builder.add_export(Export::function("main".into(), 2));
+///
+/// let mut remapping = IndexRemapping::new();
+///
+/// remapping.set_functions(Some(IndexMap::from([(0, 0), (2, 1)])));
+///
+/// let module = remap_indices(builder.build(), &remapping).unwrap();
+///
+/// assert_eq!(
+///     module.functions(),
+///     Some(&[Function::new(kind, ResultType::empty(), Expression::from(vec![ControlInstruction::Call(0).into()]))][..])
+/// );
+/// assert_eq!(module.exports(), Some(&[Export::function("main".into(), 1)][..]));
+/// ```
+pub fn remap_indices(
+    mut module: Module,
+    remapping: &IndexRemapping,
+) -> Result<Module, TransformError> {
+    #[cfg(feature = "parser")]
+    decode_bodies(&module)?;
+
+    let types = module.types.as_deref().unwrap_or_default();
+    let imports = module.imports.as_deref().unwrap_or_default();
+
+    check_recursive_types(types, remapping.types())?;
+    check_map(
+        Space::Type,
+        remapping.types(),
+        0,
+        types.iter().map(|group| group.types.len()).sum(),
+    )?;
+
+    for (space, count) in [
+        (Space::Function, module.functions.as_ref().map(Vec::len)),
+        (Space::Table, module.tables.as_ref().map(Vec::len)),
+        (Space::Memory, module.memories.as_ref().map(Vec::len)),
+        (Space::Global, module.globals.as_ref().map(Vec::len)),
+        (Space::Tag, module.tags.as_ref().map(Vec::len)),
+    ] {
+        let imported = imports
+            .iter()
+            .filter(|import| import_space(import) == space)
+            .count();
+
+        check_map(
+            space,
+            remapping.map(space),
+            imported,
+            imported + count.unwrap_or_default(),
+        )?;
+    }
+
+    check_map(
+        Space::Element,
+        remapping.elements(),
+        0,
+        module.elements.as_ref().map(Vec::len).unwrap_or_default(),
+    )?;
+    check_map(
+        Space::Data,
+        remapping.data(),
+        0,
+        module.data.as_ref().map(Vec::len).unwrap_or_default(),
+    )?;
+
+    let offsets = IMPORTED_SPACES.map(|space| {
+        imports
+            .iter()
+            .filter(|import| import_space(import) == space)
+            .count()
+    });
+
+    module.types = module
+        .types
+        .map(|types| reorder_recursive_types(types, remapping.types()));
+    module.imports = module
+        .imports
+        .map(|imports| reorder_imports(imports, remapping));
+    module.functions = module
+        .functions
+        .map(|functions| reorder(functions, offsets[0], remapping.functions()));
+    module.tables = module
+        .tables
+        .map(|tables| reorder(tables, offsets[1], remapping.tables()));
+    module.memories = module
+        .memories
+        .map(|memories| reorder(memories, offsets[2], remapping.memories()));
+    module.globals = module
+        .globals
+        .map(|globals| reorder(globals, offsets[3], remapping.globals()));
+    module.tags = module
+        .tags
+        .map(|tags| reorder(tags, offsets[4], remapping.tags()));
+    module.elements = module
+        .elements
+        .map(|elements| reorder(elements, 0, remapping.elements()));
+    module.data = module.data.map(|data| reorder(data, 0, remapping.data()));
+
+    if module.data_count.is_some() {
+        module.data_count = Some(module.data.as_ref().map(Vec::len).unwrap_or_default() as u32);
+    }
+
+    if let Some(names) = module.names.as_mut() {
+        remap_names(names, remapping);
+    }
+
+    let mut remapper = Remapper {
+        remapping,
+        error: None,
+    };
+
+    remapper.visit_module_mut(&mut module);
+
+    match remapper.error {
+        Some(error) => Err(error),
+        None => Ok(module),
+    }
+}
+
+/// The index space of the definition required by an import.
+fn import_space(import: &Import) -> Space {
+    match import.description {
+        ImportDescription::Function(_) => Space::Function,
+        ImportDescription::Table(_) => Space::Table,
+        ImportDescription::Memory(_) => Space::Memory,
+        ImportDescription::Global(_) => Space::Global,
+        ImportDescription::Tag(_) => Space::Tag,
+    }
+}
+
+/// Checks that the map assigns consecutive new indices starting at 0 to existing definitions,
+/// and that the imports of the index space precede the other definitions.
+fn check_map(
+    space: Space,
+    map: Option<&IndexMap>,
+    imports: usize,
+    count: usize,
+) -> Result<(), TransformError> {
+    let map = match map {
+        Some(map) => map,
+        None => return Ok(()),
+    };
+
+    let mut indices: Vec<u32> = map.values().copied().collect();
+
+    indices.sort_unstable();
+
+    let existing = map.keys().all(|index| (*index as usize) < count);
+    let consecutive = indices
+        .iter()
+        .enumerate()
+        .all(|(expected, index)| *index as usize == expected);
+    let kept_imports = map.range(..imports as u32).count();
+    let imports_first = map
+        .range(..imports as u32)
+        .all(|(_, index)| (*index as usize) < kept_imports);
+
+    if existing && consecutive && imports_first {
+        Ok(())
+    } else {
+        Err(TransformError::InvalidMapping(space))
+    }
+}
+
+/// Checks that the sub types of each recursive type are either all removed,
+/// or all kept in their original order with consecutive new indices.
+fn check_recursive_types(
+    types: &[RecursiveType],
+    map: Option<&IndexMap>,
+) -> Result<(), TransformError> {
+    let map = match map {
+        Some(map) => map,
+        None => return Ok(()),
+    };
+
+    let mut start = 0;
+
+    for group in types {
+        let first = map.get(&start).copied();
+        let whole = (0..group.types.len() as u32)
+            .all(|offset| map.get(&(start + offset)).copied() == first.map(|first| first + offset));
+
+        if !whole {
+            return Err(TransformError::InvalidMapping(Space::Type));
+        }
+
+        start += group.types.len() as u32;
+    }
+
+    Ok(())
+}
+
+/// Removes and reorders the definitions of an index space according to the map.
+/// The offset is the number of imports that precede the definitions in the index space.
+fn reorder<T>(definitions: Vec<T>, offset: usize, map: Option<&IndexMap>) -> Vec<T> {
+    let map = match map {
+        Some(map) => map,
+        None => return definitions,
+    };
+
+    let mut kept: Vec<(u32, T)> = definitions
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, definition)| Some((*map.get(&((offset + index) as u32))?, definition)))
+        .collect();
+
+    kept.sort_by_key(|(index, _)| *index);
+    kept.into_iter().map(|(_, definition)| definition).collect()
+}
+
+/// Removes and reorders recursive types according to the new index of their first sub type.
+fn reorder_recursive_types(
+    types: Vec<RecursiveType>,
+    map: Option<&IndexMap>,
+) -> Vec<RecursiveType> {
+    let map = match map {
+        Some(map) => map,
+        None => return types,
+    };
+
+    let mut start = 0;
+    let mut kept = Vec::new();
+
+    for group in types {
+        let length = group.types.len() as u32;
+
+        if let Some(index) = map.get(&start) {
+            kept.push((*index, group));
+        }
+
+        start += length;
+    }
+
+    kept.sort_by_key(|(index, _)| *index);
+    kept.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Removes and reorders imports according to the maps of their index spaces.
+/// Imports of different index spaces are interleaved in the import section,
+/// so the remaining imports of each index space are reordered among the positions they occupy.
+fn reorder_imports(imports: Vec<Import>, remapping: &IndexRemapping) -> Vec<Import> {
+    let mut counts = [0u32; IMPORTED_SPACES.len()];
+    let mut spaces = Vec::with_capacity(imports.len());
+    let mut kept: [Vec<(u32, Import)>; IMPORTED_SPACES.len()] = Default::default();
+
+    for import in imports {
+        let space = IMPORTED_SPACES
+            .iter()
+            .position(|space| *space == import_space(&import))
+            .unwrap_or_default();
+        let index = counts[space];
+
+        counts[space] += 1;
+
+        let index = match remapping.map(IMPORTED_SPACES[space]) {
+            Some(map) => map.get(&index).copied(),
+            None => Some(index),
+        };
+
+        if let Some(index) = index {
+            spaces.push(space);
+            kept[space].push((index, import));
+        }
+    }
+
+    for imports in kept.iter_mut() {
+        imports.sort_by_key(|(index, _)| std::cmp::Reverse(*index));
+    }
+
+    spaces
+        .into_iter()
+        .filter_map(|space| kept[space].pop())
+        .map(|(_, import)| import)
+        .collect()
+}
+
+/// Remaps the indices of the names of a module, dropping the names of removed definitions.
+fn remap_names(names: &mut NameSection, remapping: &IndexRemapping) {
+    remap_keys(names.functions_mut(), remapping.functions());
+    remap_keys(names.locals_mut(), remapping.functions());
+    remap_keys(names.labels_mut(), remapping.functions());
+    remap_keys(names.types_mut(), remapping.types());
+    remap_keys(names.fields_mut(), remapping.types());
+    remap_keys(names.tables_mut(), remapping.tables());
+    remap_keys(names.memories_mut(), remapping.memories());
+    remap_keys(names.globals_mut(), remapping.globals());
+    remap_keys(names.tags_mut(), remapping.tags());
+    remap_keys(names.elements_mut(), remapping.elements());
+    remap_keys(names.data_mut(), remapping.data());
+}
+
+/// Remaps the keys of an index-ordered map, dropping the entries of removed definitions.
+fn remap_keys<V>(entries: &mut BTreeMap<u32, V>, map: Option<&IndexMap>) {
+    if let Some(map) = map {
+        *entries = std::mem::take(entries)
+            .into_iter()
+            .filter_map(|(index, value)| Some((*map.get(&index)?, value)))
+            .collect();
+    }
+}
+
+/// Rewrites the references of a module, recording the first reference to a removed definition.
+struct Remapper<'remapping> {
+    remapping: &'remapping IndexRemapping,
+    error: Option<TransformError>,
+}
+
+impl Remapper<'_> {
+    fn remap(&mut self, space: Space, index: &mut u32) {
+        if let Some(map) = self.remapping.map(space) {
+            match map.get(index) {
+                Some(new) => *index = *new,
+                None => {
+                    self.error
+                        .get_or_insert(TransformError::UnmappedIndex(space, *index));
+                }
+            }
+        }
+    }
+}
+
+impl VisitMut for Remapper<'_> {
+    fn visit_type_index_mut(&mut self, index: &mut TypeIndex) {
+        self.remap(Space::Type, index);
+    }
+
+    fn visit_function_index_mut(&mut self, index: &mut FunctionIndex) {
+        self.remap(Space::Function, index);
+    }
+
+    fn visit_table_index_mut(&mut self, index: &mut TableIndex) {
+        self.remap(Space::Table, index);
+    }
+
+    fn visit_memory_index_mut(&mut self, index: &mut MemoryIndex) {
+        self.remap(Space::Memory, index);
+    }
+
+    fn visit_global_index_mut(&mut self, index: &mut GlobalIndex) {
+        self.remap(Space::Global, index);
+    }
+
+    fn visit_element_index_mut(&mut self, index: &mut ElementIndex) {
+        self.remap(Space::Element, index);
+    }
+
+    fn visit_data_index_mut(&mut self, index: &mut DataIndex) {
+        self.remap(Space::Data, index);
+    }
+
+    fn visit_tag_index_mut(&mut self, index: &mut TagIndex) {
+        self.remap(Space::Tag, index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+
+    fn module() -> Module {
+        let mut builder = Module::builder();
+        let mut names = NameSection::new();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_function_type(FunctionType::side_effect(ResultType::from(vec![
+                ValueType::I32,
+            ])))
+            .unwrap();
+        builder
+            .add_import(Import::function("env".into(), "log".into(), 1))
+            .unwrap();
+        builder
+            .add_import(Import::global(
+                "env".into(),
+                "base".into(),
+                GlobalType::immutable(ValueType::I32),
+            ))
+            .unwrap();
+        builder
+            .add_import(Import::function("env".into(), "abort".into(), 0))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    VariableInstruction::GlobalGet(0).into(),
+                    ControlInstruction::Call(0).into(),
+                    ControlInstruction::Call(3).into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![ControlInstruction::Call(1).into()]),
+            ))
+            .unwrap();
+        builder
+            .add_element(Element::declarative(
+                ReferenceType::Function,
+                vec![2u32, 3].to_initializers(),
+            ))
+            .unwrap();
+        builder.add_export(Export::function("main".into(), 2));
+        builder.set_start(Some(Start::new(3)));
+
+        names.functions_mut().insert(0, "log".into());
+        names.functions_mut().insert(2, "main".into());
+        names
+            .locals_mut()
+            .entry(3)
+            .or_default()
+            .insert(0, "x".into());
+        builder.set_names(Some(names));
+
+        builder.build()
+    }
+
+    #[test]
+    fn remap_functions() {
+        let mut remapping = IndexRemapping::new();
+
+        remapping.set_functions(Some(IndexMap::from([(0, 1), (1, 0), (2, 3), (3, 2)])));
+
+        let module = remap_indices(module(), &remapping).unwrap();
+        let imports = module.imports().unwrap();
+        let functions = module.functions().unwrap();
+        let names = module.names().unwrap();
+
+        assert_eq!(imports[0].name(), &Name::from("abort"));
+        assert_eq!(imports[1].name(), &Name::from("base"));
+        assert_eq!(imports[2].name(), &Name::from("log"));
+        assert_eq!(
+            functions[0].body(),
+            &Expression::from(vec![ControlInstruction::Call(0).into()])
+        );
+        assert_eq!(
+            functions[1].body(),
+            &Expression::from(vec![
+                VariableInstruction::GlobalGet(0).into(),
+                ControlInstruction::Call(1).into(),
+                ControlInstruction::Call(2).into(),
+            ])
+        );
+        assert_eq!(
+            module.elements().unwrap()[0],
+            Element::declarative(ReferenceType::Function, vec![3u32, 2].to_initializers())
+        );
+        assert_eq!(
+            module.exports(),
+            Some(&[Export::function("main".into(), 3)][..])
+        );
+        assert_eq!(module.start(), Some(&Start::new(2)));
+        assert_eq!(names.function(1), Some(&Name::from("log")));
+        assert_eq!(names.function(3), Some(&Name::from("main")));
+        assert_eq!(names.local(2, 0), Some(&Name::from("x")));
+    }
+
+    #[test]
+    fn remove_definitions() {
+        let mut remapping = IndexRemapping::new();
+
+        remapping.set_types(Some(IndexMap::from([(0, 0)])));
+        remapping.set_functions(Some(IndexMap::from([(1, 0), (3, 1)])));
+        remapping.set_globals(Some(IndexMap::new()));
+
+        let mut builder = module().into_builder();
+
+        builder.remove_export("main");
+        builder.set_elements(None);
+
+        let module = remap_indices(builder.build(), &remapping).unwrap();
+
+        assert_eq!(module.types().map(<[_]>::len), Some(1));
+        assert_eq!(
+            module.imports(),
+            Some(&[Import::function("env".into(), "abort".into(), 0)][..])
+        );
+        assert_eq!(
+            module.functions(),
+            Some(
+                &[Function::new(
+                    0,
+                    ResultType::empty(),
+                    Expression::from(vec![ControlInstruction::Call(0).into()])
+                )][..]
+            )
+        );
+        assert_eq!(module.start(), Some(&Start::new(1)));
+        assert_eq!(module.names().unwrap().function(0), None);
+    }
+
+    #[test]
+    fn remove_referenced_definition() {
+        let mut remapping = IndexRemapping::new();
+
+        remapping.set_functions(Some(IndexMap::from([(1, 0), (2, 1), (3, 2)])));
+
+        assert_eq!(
+            remap_indices(module(), &remapping),
+            Err(TransformError::UnmappedIndex(Space::Function, 0))
+        );
+    }
+
+    #[test]
+    fn invalid_mappings() {
+        let mut remapping = IndexRemapping::new();
+
+        remapping.set_functions(Some(IndexMap::from([(0, 2), (1, 1), (2, 0), (3, 3)])));
+
+        assert_eq!(
+            remap_indices(module(), &remapping),
+            Err(TransformError::InvalidMapping(Space::Function))
+        );
+
+        remapping.set_functions(Some(IndexMap::from([(0, 0), (1, 2)])));
+
+        assert_eq!(
+            remap_indices(module(), &remapping),
+            Err(TransformError::InvalidMapping(Space::Function))
+        );
+
+        remapping.set_functions(None);
+        remapping.set_tables(Some(IndexMap::from([(0, 0)])));
+
+        assert_eq!(
+            remap_indices(module(), &remapping),
+            Err(TransformError::InvalidMapping(Space::Table))
+        );
+    }
+}
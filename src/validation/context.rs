@@ -0,0 +1,137 @@
+//! The validation context of a module.
+//!
+//! See <https://webassembly.github.io/spec/core/valid/conventions.html#contexts>
+
+use crate::model::{
+    ElementMode, ExportDescription, Expression, FunctionIndex, GlobalType, ImportDescription,
+    Instruction, MemoryType, Module, ReferenceInstruction, ReferenceType, TableType, TypeIndex,
+};
+use crate::validation::types::Types;
+use std::collections::HashSet;
+
+/// The definitions of a module that are in scope when validating its components.
+/// Imported definitions precede the definitions of the module in each index space.
+pub struct Context<'m> {
+    pub types: Types<'m>,
+    pub functions: Vec<TypeIndex>,
+    pub tables: Vec<TableType>,
+    pub memories: Vec<MemoryType>,
+    pub globals: Vec<GlobalType>,
+    pub tags: Vec<TypeIndex>,
+    pub elements: Vec<ReferenceType>,
+    pub data: usize,
+    pub data_count: Option<u32>,
+    pub references: HashSet<FunctionIndex>,
+}
+
+impl<'m> Context<'m> {
+    /// Creates the context of the given module.
+    pub fn new(module: &'m Module) -> Self {
+        let mut context = Context {
+            types: Types::new(module.types().unwrap_or_default()),
+            functions: Vec::new(),
+            tables: Vec::new(),
+            memories: Vec::new(),
+            globals: Vec::new(),
+            tags: Vec::new(),
+            elements: Vec::new(),
+            data: module.data().unwrap_or_default().len(),
+            data_count: module.data_count(),
+            references: HashSet::new(),
+        };
+
+        for import in module.imports().unwrap_or_default() {
+            match import.description() {
+                ImportDescription::Function(kind) => context.functions.push(*kind),
+                ImportDescription::Table(kind) => context.tables.push(*kind),
+                ImportDescription::Memory(kind) => context.memories.push(*kind),
+                ImportDescription::Global(kind) => context.globals.push(*kind),
+                ImportDescription::Tag(kind) => context.tags.push(kind.kind()),
+            }
+        }
+
+        context.functions.extend(
+            module
+                .functions()
+                .unwrap_or_default()
+                .iter()
+                .map(|function| function.kind()),
+        );
+        context.tables.extend(
+            module
+                .tables()
+                .unwrap_or_default()
+                .iter()
+                .map(|table| *table.kind()),
+        );
+        context.memories.extend(
+            module
+                .memories()
+                .unwrap_or_default()
+                .iter()
+                .map(|memory| *memory.kind()),
+        );
+        context.globals.extend(
+            module
+                .globals()
+                .unwrap_or_default()
+                .iter()
+                .map(|global| *global.kind()),
+        );
+        context.tags.extend(
+            module
+                .tags()
+                .unwrap_or_default()
+                .iter()
+                .map(|tag| tag.kind().kind()),
+        );
+        context.elements.extend(
+            module
+                .elements()
+                .unwrap_or_default()
+                .iter()
+                .map(|element| element.kind()),
+        );
+        context.references = references(module);
+        context
+    }
+}
+
+/// The functions that are referenced outside of function bodies,
+/// which are the only functions that 𝗋𝖾𝖿.𝖿𝗎𝗇𝖼 may refer to within function bodies.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#valid-module>
+fn references(module: &Module) -> HashSet<FunctionIndex> {
+    let mut references = HashSet::new();
+    let mut collect = |expression: &Expression| {
+        for instruction in expression.instructions() {
+            if let Instruction::Reference(ReferenceInstruction::Function(function)) = instruction {
+                references.insert(*function);
+            }
+        }
+    };
+
+    for table in module.tables().unwrap_or_default() {
+        table.initializer().into_iter().for_each(&mut collect);
+    }
+
+    for global in module.globals().unwrap_or_default() {
+        collect(global.initializer());
+    }
+
+    for element in module.elements().unwrap_or_default() {
+        if let ElementMode::Active(_, offset) = element.mode() {
+            collect(offset);
+        }
+
+        element.initializers().iter().for_each(&mut collect);
+    }
+
+    for export in module.exports().unwrap_or_default() {
+        if let ExportDescription::Function(function) = export.description() {
+            references.insert(*function);
+        }
+    }
+
+    references
+}
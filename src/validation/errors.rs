@@ -0,0 +1,208 @@
+use crate::model::{
+    DataIndex, ElementIndex, FieldIndex, FunctionIndex, GlobalIndex, Instruction, LabelIndex,
+    LaneIndex, LocalIndex, ReferenceType, TableIndex, TypeIndex, ValueType,
+};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// An error in validating a WebAssembly module.
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    #[error("The instruction at offset {1} in {0} is invalid: {2}")]
+    InvalidInstruction(Location, usize, InstructionError),
+    #[error("The module does not define a {0} with index {1}.")]
+    UnknownIndex(Space, u32),
+    #[error("The type with index {0} is not a function type.")]
+    ExpectedFunctionType(TypeIndex),
+    #[error("The type with index {0} does not match its declared supertype.")]
+    InvalidSubtype(TypeIndex),
+    #[error(
+        "The limits are out of range or the minimum exceeds the maximum (min: {0}, max: {1:?})."
+    )]
+    InvalidLimits(u64, Option<u64>),
+    #[error("A shared memory must declare a maximum size.")]
+    UnboundedSharedMemory,
+    #[error("The table with index {0} has a non-nullable element type, but no initializer.")]
+    NonDefaultableTable(TableIndex),
+    #[error("The function type with index {0} of a tag must not have results.")]
+    InvalidTagType(TypeIndex),
+    #[error("The start function with index {0} must not have parameters or results.")]
+    InvalidStart(FunctionIndex),
+    #[error("The module exports the name {0:?} more than once.")]
+    DuplicateExport(String),
+    #[error("The element segment with index {0} does not match the element type of its table.")]
+    ElementTypeMismatch(ElementIndex),
+    #[error("The data count ({0}) does not match the number of data segments ({1}).")]
+    DataCountMismatch(u32, usize),
+    #[cfg(feature = "parser")]
+    #[error("The code of a function parsed lazily is not a valid binary format: {0}.")]
+    InvalidBinary(crate::parser::BinaryError),
+}
+
+impl ValidationError {
+    /// The index of the function containing the invalid instruction, if any.
+    pub fn function(&self) -> Option<FunctionIndex> {
+        match self {
+            ValidationError::InvalidInstruction(Location::Function(function), _, _) => {
+                Some(*function)
+            }
+            #[cfg(feature = "parser")]
+            ValidationError::InvalidBinary(error) => error.function(),
+            _ => None,
+        }
+    }
+
+    /// The offset of the invalid instruction within its expression, if any.
+    /// Instructions are numbered in the order they appear in the text format,
+    /// such that the instructions in the body of a block follow the block instruction itself.
+    /// Errors detected at the end of a block refer to the offset of the block instruction,
+    /// while errors detected at the end of an expression refer to the number of instructions
+    /// in the expression.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ValidationError::InvalidInstruction(_, offset, _) => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+/// An error in type-checking an instruction.
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum InstructionError {
+    #[error("Expected an operand of type {0:?}, but found {1:?}.")]
+    TypeMismatch(ValueType, ValueType),
+    #[error("Expected an operand, but the operand stack is empty.")]
+    MissingOperand,
+    #[error("Expected an operand of a reference type, but found {0:?}.")]
+    ExpectedReference(ValueType),
+    #[error("Expected an operand of type {0:?}, but found a reference.")]
+    UnexpectedReference(ValueType),
+    #[error("The block leaves {0} unexpected operands on the stack.")]
+    UnexpectedOperands(usize),
+    #[error("The module does not define a {0} with index {1}.")]
+    UnknownIndex(Space, u32),
+    #[error("The type with index {0} is not a function type.")]
+    ExpectedFunctionType(TypeIndex),
+    #[error("The type with index {0} is not a structure type.")]
+    ExpectedStructType(TypeIndex),
+    #[error("The type with index {0} is not an array type.")]
+    ExpectedArrayType(TypeIndex),
+    #[error("The global with index {0} is immutable.")]
+    ImmutableGlobal(GlobalIndex),
+    #[error("The field with index {1} of the structure type with index {0} is immutable.")]
+    ImmutableField(TypeIndex, FieldIndex),
+    #[error("The array type with index {0} is immutable.")]
+    ImmutableArray(TypeIndex),
+    #[error("The local with index {0} is read before it is initialized.")]
+    UninitializedLocal(LocalIndex),
+    #[error("The alignment exponent {0} is invalid for the width of the memory access.")]
+    InvalidAlignment(u32),
+    #[error("The offset {0} is out of range for a 32-bit memory.")]
+    InvalidOffset(u64),
+    #[error("The lane index {0} is out of range for the vector shape.")]
+    InvalidLane(LaneIndex),
+    #[error("A sign extension must be given exactly when accessing a packed value.")]
+    InvalidSignExtension,
+    #[error(
+        "The function with index {0} is referenced, but not declared outside of function bodies."
+    )]
+    UndeclaredFunction(FunctionIndex),
+    #[error("The instruction is not allowed in a constant expression: {0:?}")]
+    NonConstant(Instruction),
+    #[error("The type with index {0} has fields that are not defaultable.")]
+    NonDefaultable(TypeIndex),
+    #[error("An untyped select requires numeric or vector operands of the same type.")]
+    InvalidSelect,
+    #[error("The operands do not match the types of the label with index {0}.")]
+    MismatchedLabel(LabelIndex),
+    #[error("The results of the callee do not match the results of the caller.")]
+    MismatchedReturn,
+    #[error("The target type {1:?} of the cast does not match the source type {0:?}.")]
+    InvalidCast(ReferenceType, ReferenceType),
+    #[error("The array type with index {0} cannot be initialized from a data segment.")]
+    InvalidArrayData(TypeIndex),
+    #[error("The module must include a data count in order to refer to data segments in code.")]
+    MissingDataCount,
+}
+
+/// The expressions of a module that contain instructions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Location {
+    /// The body of the function with the given index.
+    Function(FunctionIndex),
+    /// The initializer of the global with the given index.
+    Global(GlobalIndex),
+    /// The initializer of the table with the given index.
+    Table(TableIndex),
+    /// The offset of the active element segment with the given index.
+    ElementOffset(ElementIndex),
+    /// An initializer of the element segment with the given index.
+    ElementInitializer(ElementIndex, usize),
+    /// The offset of the active data segment with the given index.
+    DataOffset(DataIndex),
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Function(function) => write!(f, "the function with index {}", function),
+            Location::Global(global) => {
+                write!(f, "the initializer of the global with index {}", global)
+            }
+            Location::Table(table) => {
+                write!(f, "the initializer of the table with index {}", table)
+            }
+            Location::ElementOffset(element) => {
+                write!(
+                    f,
+                    "the offset of the element segment with index {}",
+                    element
+                )
+            }
+            Location::ElementInitializer(element, item) => write!(
+                f,
+                "initializer {} of the element segment with index {}",
+                item, element
+            ),
+            Location::DataOffset(data) => {
+                write!(f, "the offset of the data segment with index {}", data)
+            }
+        }
+    }
+}
+
+/// The index spaces of a module, along with the local spaces of a function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Space {
+    Type,
+    Function,
+    Table,
+    Memory,
+    Global,
+    Element,
+    Data,
+    Tag,
+    Local,
+    Label,
+    Field,
+}
+
+impl Display for Space {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Space::Type => "type",
+            Space::Function => "function",
+            Space::Table => "table",
+            Space::Memory => "memory",
+            Space::Global => "global",
+            Space::Element => "element segment",
+            Space::Data => "data segment",
+            Space::Tag => "tag",
+            Space::Local => "local",
+            Space::Label => "label",
+            Space::Field => "field",
+        };
+
+        write!(f, "{}", name)
+    }
+}
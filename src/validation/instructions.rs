@@ -0,0 +1,1749 @@
+//! Type-checking of instruction sequences.
+//!
+//! Instructions are checked by the algorithm in the appendix of the specification,
+//! which tracks the types of the operand stack and of the enclosing structured instructions.
+//! After an unconditional branch, the remainder of a block is unreachable and its operand stack
+//! is polymorphic, which is modeled by operands of an unknown type.
+//!
+//! See <https://webassembly.github.io/spec/core/appendix/algorithm.html>
+
+use crate::constant::is_constant_instruction;
+use crate::model::{
+    AggregateInstruction, AtomicInstruction, AtomicOperation, BlockType, CatchClause,
+    CompositeType, ControlInstruction, FloatType, FunctionType, HeapType, IndexType, Instruction,
+    IntegerType, LabelIndex, LocalIndex, MemoryArgument, MemoryInstruction, MemoryType, Mutability,
+    NumberType, NumericInstruction, ParametricInstruction, ReferenceInstruction, ReferenceType,
+    StorageType, StructType, TableInstruction, TableType, TypeIndex, ValueType,
+    VariableInstruction, VectorInstruction, VectorShape,
+};
+use crate::model::{ArrayType, FieldType};
+use crate::validation::context::Context;
+use crate::validation::types::{is_defaultable, reference, unpacked};
+use crate::validation::{InstructionError, Space};
+
+/// The type of an operand on the stack, which is unknown in unreachable code.
+/// A non-null reference of an unknown type (i.e., `(ref bot)`) matches any reference type.
+///
+/// See <https://webassembly.github.io/gc/core/appendix/algorithm.html#data-structures>
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operand {
+    Unknown,
+    Bottom,
+    Value(ValueType),
+}
+
+/// A structured instruction (or the body of a function) that encloses the current instruction.
+///
+/// See <https://webassembly.github.io/spec/core/appendix/algorithm.html#data-structures>
+#[derive(Clone, Debug)]
+struct Frame {
+    is_loop: bool,
+    parameters: Vec<ValueType>,
+    results: Vec<ValueType>,
+    height: usize,
+    initialized: usize,
+    unreachable: bool,
+}
+
+impl Frame {
+    /// The types of the operands of a branch to this frame's label.
+    fn label(&self) -> &[ValueType] {
+        if self.is_loop {
+            &self.parameters
+        } else {
+            &self.results
+        }
+    }
+}
+
+/// An error at the instruction with the given offset.
+pub type Failure = (usize, InstructionError);
+
+/// Type-checks the expressions of a module.
+pub struct ExpressionValidator<'c, 'm> {
+    context: &'c Context<'m>,
+    constant: Option<usize>,
+    locals: Vec<ValueType>,
+    initialized: Vec<bool>,
+    initializations: Vec<LocalIndex>,
+    results: Vec<ValueType>,
+    operands: Vec<Operand>,
+    frames: Vec<Frame>,
+    offset: usize,
+}
+
+impl<'c, 'm> ExpressionValidator<'c, 'm> {
+    /// Creates a validator for the body of a function with the given type and locals.
+    /// Parameters and locals of defaultable types are initialized on entry to the function.
+    pub fn function(context: &'c Context<'m>, kind: &FunctionType, locals: &[ValueType]) -> Self {
+        let parameters = kind.parameters().kinds();
+        let locals: Vec<ValueType> = parameters.iter().chain(locals).copied().collect();
+        let initialized = locals
+            .iter()
+            .enumerate()
+            .map(|(index, kind)| index < parameters.len() || is_defaultable(*kind))
+            .collect();
+
+        ExpressionValidator {
+            context,
+            constant: None,
+            locals,
+            initialized,
+            initializations: Vec::new(),
+            results: kind.results().kinds().to_vec(),
+            operands: Vec::new(),
+            frames: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Creates a validator for a constant expression,
+    /// in which only the given number of leading globals may be referenced.
+    pub fn constant(context: &'c Context<'m>, globals: usize) -> Self {
+        ExpressionValidator {
+            context,
+            constant: Some(globals),
+            locals: Vec::new(),
+            initialized: Vec::new(),
+            initializations: Vec::new(),
+            results: Vec::new(),
+            operands: Vec::new(),
+            frames: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Validates that the given instructions produce values of the given result types.
+    pub fn validate(
+        mut self,
+        instructions: &[Instruction],
+        results: &[ValueType],
+    ) -> Result<(), Failure> {
+        self.push_frame(false, Vec::new(), results.to_vec());
+        self.sequence(instructions)?;
+        self.pop_frame().map_err(|error| (self.offset, error))?;
+
+        Ok(())
+    }
+
+    /// Validates a sequence of instructions.
+    fn sequence(&mut self, instructions: &[Instruction]) -> Result<(), Failure> {
+        for instruction in instructions {
+            let offset = self.offset;
+
+            self.offset += 1;
+
+            if let Some(globals) = self.constant {
+                self.check_constant(instruction, globals)
+                    .map_err(|error| (offset, error))?;
+            }
+
+            match instruction {
+                Instruction::Control(control) => self.control(control, offset)?,
+                instruction => self
+                    .instruction(instruction)
+                    .map_err(|error| (offset, error))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates that an instruction is allowed in a constant expression.
+    /// Constant expressions may only read immutable globals defined before the expression.
+    fn check_constant(
+        &self,
+        instruction: &Instruction,
+        globals: usize,
+    ) -> Result<(), InstructionError> {
+        let constant = match instruction {
+            Instruction::Variable(VariableInstruction::GlobalGet(global)) => {
+                (*global as usize) < globals
+                    && self
+                        .context
+                        .globals
+                        .get(*global as usize)
+                        .is_some_and(|kind| kind.mutability() == Mutability::Immutable)
+            }
+            instruction => is_constant_instruction(instruction),
+        };
+
+        if constant {
+            Ok(())
+        } else {
+            Err(InstructionError::NonConstant(instruction.clone()))
+        }
+    }
+
+    /// Pushes an operand of the given type.
+    fn push(&mut self, kind: ValueType) {
+        self.operands.push(Operand::Value(kind));
+    }
+
+    /// Pushes operands of the given types.
+    fn push_values(&mut self, kinds: &[ValueType]) {
+        self.operands
+            .extend(kinds.iter().copied().map(Operand::Value));
+    }
+
+    /// Pops an operand of any type.
+    fn pop(&mut self) -> Result<Operand, InstructionError> {
+        let frame = self.frames.last().ok_or(InstructionError::MissingOperand)?;
+
+        if self.operands.len() == frame.height {
+            if frame.unreachable {
+                Ok(Operand::Unknown)
+            } else {
+                Err(InstructionError::MissingOperand)
+            }
+        } else {
+            self.operands.pop().ok_or(InstructionError::MissingOperand)
+        }
+    }
+
+    /// Pops an operand that matches the expected type.
+    fn pop_expected(&mut self, expected: ValueType) -> Result<Operand, InstructionError> {
+        match self.pop()? {
+            Operand::Value(actual) if !self.context.types.subtype(actual, expected) => {
+                Err(InstructionError::TypeMismatch(expected, actual))
+            }
+            Operand::Bottom if reference(expected).is_none() => {
+                Err(InstructionError::UnexpectedReference(expected))
+            }
+            operand => Ok(operand),
+        }
+    }
+
+    /// Pops operands that match the given types, returning them in stack order.
+    fn pop_values(&mut self, kinds: &[ValueType]) -> Result<Vec<Operand>, InstructionError> {
+        let mut operands = Vec::with_capacity(kinds.len());
+
+        for kind in kinds.iter().rev() {
+            operands.push(self.pop_expected(*kind)?);
+        }
+
+        operands.reverse();
+
+        Ok(operands)
+    }
+
+    /// Pops an operand of a reference type, returning `None` if its type is unknown.
+    fn pop_reference(&mut self) -> Result<Option<ReferenceType>, InstructionError> {
+        match self.pop()? {
+            Operand::Unknown | Operand::Bottom => Ok(None),
+            Operand::Value(kind) => reference(kind)
+                .map(Some)
+                .ok_or(InstructionError::ExpectedReference(kind)),
+        }
+    }
+
+    /// Pushes the non-null form of a reference popped by `pop_reference`,
+    /// which is a non-null reference of an unknown type when the popped type is unknown.
+    fn push_non_null(&mut self, operand: Option<ReferenceType>) {
+        self.operands.push(match operand {
+            Some(kind) => Operand::Value(ValueType::Reference(ReferenceType::NonNullable(
+                kind.heap_type(),
+            ))),
+            None => Operand::Bottom,
+        });
+    }
+
+    /// Enters a structured instruction with the given parameters and results.
+    fn push_frame(&mut self, is_loop: bool, parameters: Vec<ValueType>, results: Vec<ValueType>) {
+        self.frames.push(Frame {
+            is_loop,
+            height: self.operands.len(),
+            initialized: self.initializations.len(),
+            unreachable: false,
+            parameters: Vec::new(),
+            results,
+        });
+        self.push_values(&parameters);
+
+        if let Some(frame) = self.frames.last_mut() {
+            frame.parameters = parameters;
+        }
+    }
+
+    /// Exits the innermost structured instruction,
+    /// checking that exactly its results remain on the operand stack.
+    /// Locals initialized within the instruction are no longer considered initialized.
+    fn pop_frame(&mut self) -> Result<Frame, InstructionError> {
+        let results = match self.frames.last() {
+            Some(frame) => frame.results.clone(),
+            None => return Err(InstructionError::MissingOperand),
+        };
+
+        self.pop_values(&results)?;
+
+        let frame = self.frames.pop().ok_or(InstructionError::MissingOperand)?;
+
+        if self.operands.len() != frame.height {
+            return Err(InstructionError::UnexpectedOperands(
+                self.operands.len() - frame.height,
+            ));
+        }
+
+        for local in self.initializations.drain(frame.initialized..) {
+            self.initialized[local as usize] = false;
+        }
+
+        Ok(frame)
+    }
+
+    /// Marks the remainder of the innermost structured instruction as unreachable.
+    fn unreachable(&mut self) {
+        if let Some(frame) = self.frames.last_mut() {
+            self.operands.truncate(frame.height);
+            frame.unreachable = true;
+        }
+    }
+
+    /// The types of the operands of a branch to the given label.
+    fn label(&self, label: LabelIndex) -> Result<Vec<ValueType>, InstructionError> {
+        self.frames
+            .len()
+            .checked_sub(label as usize + 1)
+            .and_then(|depth| self.frames.get(depth))
+            .map(|frame| frame.label().to_vec())
+            .ok_or(InstructionError::UnknownIndex(Space::Label, label))
+    }
+
+    /// The type of the function with the given type index.
+    fn function_type(&self, index: TypeIndex) -> Result<&'m FunctionType, InstructionError> {
+        match self.context.types.get(index) {
+            Some(kind) => kind
+                .kind()
+                .function()
+                .ok_or(InstructionError::ExpectedFunctionType(index)),
+            None => Err(InstructionError::UnknownIndex(Space::Type, index)),
+        }
+    }
+
+    /// The structure type with the given type index.
+    fn struct_type(&self, index: TypeIndex) -> Result<&'m StructType, InstructionError> {
+        match self.context.types.get(index).map(|kind| kind.kind()) {
+            Some(CompositeType::Struct(kind)) => Ok(kind),
+            Some(_) => Err(InstructionError::ExpectedStructType(index)),
+            None => Err(InstructionError::UnknownIndex(Space::Type, index)),
+        }
+    }
+
+    /// The array type with the given type index.
+    fn array_type(&self, index: TypeIndex) -> Result<&'m ArrayType, InstructionError> {
+        match self.context.types.get(index).map(|kind| kind.kind()) {
+            Some(CompositeType::Array(kind)) => Ok(kind),
+            Some(_) => Err(InstructionError::ExpectedArrayType(index)),
+            None => Err(InstructionError::UnknownIndex(Space::Type, index)),
+        }
+    }
+
+    /// The type of the function with the given index.
+    fn callee(&self, function: u32) -> Result<&'m FunctionType, InstructionError> {
+        let kind = self
+            .context
+            .functions
+            .get(function as usize)
+            .ok_or(InstructionError::UnknownIndex(Space::Function, function))?;
+
+        self.function_type(*kind)
+    }
+
+    /// The type of the table with the given index.
+    fn table(&self, table: u32) -> Result<TableType, InstructionError> {
+        self.context
+            .tables
+            .get(table as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Table, table))
+    }
+
+    /// The type of the memory with the given index.
+    fn memory(&self, memory: u32) -> Result<MemoryType, InstructionError> {
+        self.context
+            .memories
+            .get(memory as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Memory, memory))
+    }
+
+    /// The element type of the element segment with the given index.
+    fn element(&self, element: u32) -> Result<ReferenceType, InstructionError> {
+        self.context
+            .elements
+            .get(element as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Element, element))
+    }
+
+    /// Checks that the data segment with the given index exists,
+    /// and that the module includes a data count, which is required to refer to data segments.
+    fn data(&self, data: u32) -> Result<(), InstructionError> {
+        if self.context.data_count.is_none() {
+            Err(InstructionError::MissingDataCount)
+        } else if (data as usize) < self.context.data {
+            Ok(())
+        } else {
+            Err(InstructionError::UnknownIndex(Space::Data, data))
+        }
+    }
+
+    /// The function type of the tag with the given index.
+    fn tag(&self, tag: u32) -> Result<&'m FunctionType, InstructionError> {
+        let kind = self
+            .context
+            .tags
+            .get(tag as usize)
+            .ok_or(InstructionError::UnknownIndex(Space::Tag, tag))?;
+
+        self.function_type(*kind)
+    }
+
+    /// Checks that the heap type refers to a defined type.
+    fn heap_type(&self, kind: HeapType) -> Result<(), InstructionError> {
+        match kind {
+            HeapType::Index(index) if index as usize >= self.context.types.len() => {
+                Err(InstructionError::UnknownIndex(Space::Type, index))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the value type refers to defined types.
+    fn value_type(&self, kind: ValueType) -> Result<(), InstructionError> {
+        match reference(kind) {
+            Some(kind) => self.heap_type(kind.heap_type()),
+            None => Ok(()),
+        }
+    }
+
+    /// The parameters and results of a block type.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/types.html#block-types>
+    fn block_type(
+        &self,
+        kind: &BlockType,
+    ) -> Result<(Vec<ValueType>, Vec<ValueType>), InstructionError> {
+        match kind {
+            BlockType::None => Ok((Vec::new(), Vec::new())),
+            BlockType::ValueType(kind) => {
+                self.value_type(*kind)?;
+
+                Ok((Vec::new(), vec![*kind]))
+            }
+            BlockType::Index(index) => {
+                let kind = self.function_type(*index)?;
+
+                Ok((
+                    kind.parameters().kinds().to_vec(),
+                    kind.results().kinds().to_vec(),
+                ))
+            }
+        }
+    }
+
+    /// Validates a control instruction, including the bodies of structured instructions.
+    fn control(&mut self, instruction: &ControlInstruction, offset: usize) -> Result<(), Failure> {
+        let at = |error| (offset, error);
+
+        match instruction {
+            ControlInstruction::Block(kind, body) | ControlInstruction::Loop(kind, body) => {
+                let (parameters, results) = self.block_type(kind).map_err(at)?;
+
+                self.pop_values(&parameters).map_err(at)?;
+                self.push_frame(
+                    matches!(instruction, ControlInstruction::Loop(..)),
+                    parameters,
+                    results,
+                );
+                self.sequence(body.instructions())?;
+
+                let frame = self.pop_frame().map_err(at)?;
+
+                self.push_values(&frame.results);
+            }
+            ControlInstruction::If(kind, consequent, alternative) => {
+                let (parameters, results) = self.block_type(kind).map_err(at)?;
+
+                self.pop_expected(ValueType::I32).map_err(at)?;
+                self.pop_values(&parameters).map_err(at)?;
+                self.push_frame(false, parameters.clone(), results.clone());
+                self.sequence(consequent.instructions())?;
+                self.pop_frame().map_err(at)?;
+                self.push_frame(false, parameters, results);
+
+                if let Some(alternative) = alternative {
+                    self.sequence(alternative.instructions())?;
+                }
+
+                let frame = self.pop_frame().map_err(at)?;
+
+                self.push_values(&frame.results);
+            }
+            ControlInstruction::TryTable(kind, catches, body) => {
+                let (parameters, results) = self.block_type(kind).map_err(at)?;
+
+                for catch in catches {
+                    self.catch(catch).map_err(at)?;
+                }
+
+                self.pop_values(&parameters).map_err(at)?;
+                self.push_frame(false, parameters, results);
+                self.sequence(body.instructions())?;
+
+                let frame = self.pop_frame().map_err(at)?;
+
+                self.push_values(&frame.results);
+            }
+            instruction => self.branch(instruction).map_err(at)?,
+        }
+
+        Ok(())
+    }
+
+    /// Validates that a catch clause provides the operands expected by its label.
+    ///
+    /// See <https://webassembly.github.io/exception-handling/core/valid/instructions.html#xref-syntax-instructions-syntax-catch-mathsf-catch-x-l>
+    fn catch(&self, catch: &CatchClause) -> Result<(), InstructionError> {
+        let mut operands = match catch {
+            CatchClause::Catch(tag, _) | CatchClause::CatchReference(tag, _) => {
+                self.tag(*tag)?.parameters().kinds().to_vec()
+            }
+            CatchClause::CatchAll(_) | CatchClause::CatchAllReference(_) => Vec::new(),
+        };
+
+        if matches!(
+            catch,
+            CatchClause::CatchReference(..) | CatchClause::CatchAllReference(_)
+        ) {
+            operands.push(ValueType::Reference(ReferenceType::NonNullable(
+                HeapType::Exception,
+            )));
+        }
+
+        if self
+            .context
+            .types
+            .subtypes(&operands, &self.label(catch.label())?)
+        {
+            Ok(())
+        } else {
+            Err(InstructionError::MismatchedLabel(catch.label()))
+        }
+    }
+
+    /// Validates a control instruction that is not structured.
+    fn branch(&mut self, instruction: &ControlInstruction) -> Result<(), InstructionError> {
+        match instruction {
+            ControlInstruction::Nop => {}
+            ControlInstruction::Unreachable => self.unreachable(),
+            ControlInstruction::Branch(label) => {
+                let kinds = self.label(*label)?;
+
+                self.pop_values(&kinds)?;
+                self.unreachable();
+            }
+            ControlInstruction::BranchIf(label) => {
+                let kinds = self.label(*label)?;
+
+                self.pop_expected(ValueType::I32)?;
+
+                let operands = self.pop_values(&kinds)?;
+
+                self.operands.extend(operands);
+            }
+            ControlInstruction::BranchTable(labels, default) => {
+                let arity = self.label(*default)?.len();
+
+                self.pop_expected(ValueType::I32)?;
+
+                for label in labels {
+                    let kinds = self.label(*label)?;
+
+                    if kinds.len() != arity {
+                        return Err(InstructionError::MismatchedLabel(*label));
+                    }
+
+                    let operands = self.pop_values(&kinds)?;
+
+                    self.operands.extend(operands);
+                }
+
+                let kinds = self.label(*default)?;
+
+                self.pop_values(&kinds)?;
+                self.unreachable();
+            }
+            ControlInstruction::Return => {
+                let results = self.results.clone();
+
+                self.pop_values(&results)?;
+                self.unreachable();
+            }
+            ControlInstruction::Call(function) => {
+                let kind = self.callee(*function)?;
+
+                self.call(kind)?;
+            }
+            ControlInstruction::CallIndirect(kind, table) => {
+                let kind = self.function_type(*kind)?;
+
+                self.call_indirect(*table)?;
+                self.call(kind)?;
+            }
+            ControlInstruction::CallRef(kind) => {
+                let function = self.function_type(*kind)?;
+
+                self.pop_expected(ValueType::Reference(ReferenceType::Nullable(
+                    HeapType::Index(*kind),
+                )))?;
+                self.call(function)?;
+            }
+            ControlInstruction::ReturnCall(function) => {
+                let kind = self.callee(*function)?;
+
+                self.return_call(kind)?;
+            }
+            ControlInstruction::ReturnCallIndirect(kind, table) => {
+                let kind = self.function_type(*kind)?;
+
+                self.call_indirect(*table)?;
+                self.return_call(kind)?;
+            }
+            ControlInstruction::ReturnCallRef(kind) => {
+                let function = self.function_type(*kind)?;
+
+                self.pop_expected(ValueType::Reference(ReferenceType::Nullable(
+                    HeapType::Index(*kind),
+                )))?;
+                self.return_call(function)?;
+            }
+            ControlInstruction::BranchOnNull(label) => {
+                let operand = self.pop_reference()?;
+                let kinds = self.label(*label)?;
+                let operands = self.pop_values(&kinds)?;
+
+                self.operands.extend(operands);
+                self.push_non_null(operand);
+            }
+            ControlInstruction::BranchOnNonNull(label) => {
+                let operand = self.pop_reference()?;
+                let kinds = self.label(*label)?;
+
+                if kinds.is_empty() {
+                    return Err(InstructionError::MismatchedLabel(*label));
+                }
+
+                self.push_non_null(operand);
+
+                let operands = self.pop_values(&kinds)?;
+
+                self.operands
+                    .extend(operands.into_iter().take(kinds.len() - 1));
+            }
+            ControlInstruction::BranchOnCast(label, source, target)
+            | ControlInstruction::BranchOnCastFail(label, source, target) => {
+                let difference = if target.is_nullable() {
+                    ReferenceType::NonNullable(source.heap_type())
+                } else {
+                    *source
+                };
+                let (branch, fallthrough) = match instruction {
+                    ControlInstruction::BranchOnCast(..) => (*target, difference),
+                    _ => (difference, *target),
+                };
+
+                self.cast(*source, *target)?;
+                self.pop_expected(ValueType::Reference(*source))?;
+
+                let kinds = self.label(*label)?;
+
+                if kinds.is_empty() {
+                    return Err(InstructionError::MismatchedLabel(*label));
+                }
+
+                self.push(ValueType::Reference(branch));
+
+                let operands = self.pop_values(&kinds)?;
+
+                self.operands
+                    .extend(operands.into_iter().take(kinds.len() - 1));
+                self.push(ValueType::Reference(fallthrough));
+            }
+            ControlInstruction::Throw(tag) => {
+                let kind = self.tag(*tag)?;
+
+                self.pop_values(kind.parameters().kinds())?;
+                self.unreachable();
+            }
+            ControlInstruction::ThrowRef => {
+                self.pop_expected(ValueType::ExceptionReference)?;
+                self.unreachable();
+            }
+            ControlInstruction::Block(..)
+            | ControlInstruction::Loop(..)
+            | ControlInstruction::If(..)
+            | ControlInstruction::TryTable(..) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the target type of a cast is valid and matches the source type.
+    fn cast(&self, source: ReferenceType, target: ReferenceType) -> Result<(), InstructionError> {
+        self.heap_type(source.heap_type())?;
+        self.heap_type(target.heap_type())?;
+
+        if self.context.types.reference_subtype(target, source) {
+            Ok(())
+        } else {
+            Err(InstructionError::InvalidCast(source, target))
+        }
+    }
+
+    /// Pops the arguments and pushes the results of a call to a function of the given type.
+    fn call(&mut self, kind: &FunctionType) -> Result<(), InstructionError> {
+        self.pop_values(kind.parameters().kinds())?;
+        self.push_values(kind.results().kinds());
+
+        Ok(())
+    }
+
+    /// Pops the operand of an indirect call through the given table.
+    fn call_indirect(&mut self, table: u32) -> Result<(), InstructionError> {
+        let table = self.table(table)?;
+        let element = ValueType::Reference(table.kind());
+
+        if !self
+            .context
+            .types
+            .subtype(element, ValueType::FunctionReference)
+        {
+            return Err(InstructionError::TypeMismatch(
+                ValueType::FunctionReference,
+                element,
+            ));
+        }
+
+        self.pop_expected(table.limits().index_type().into())?;
+
+        Ok(())
+    }
+
+    /// Pops the arguments of a tail call to a function of the given type,
+    /// whose results must match the results of the calling function.
+    fn return_call(&mut self, kind: &FunctionType) -> Result<(), InstructionError> {
+        if !self
+            .context
+            .types
+            .subtypes(kind.results().kinds(), &self.results)
+        {
+            return Err(InstructionError::MismatchedReturn);
+        }
+
+        self.pop_values(kind.parameters().kinds())?;
+        self.unreachable();
+
+        Ok(())
+    }
+
+    /// Validates an instruction that is not a control instruction.
+    fn instruction(&mut self, instruction: &Instruction) -> Result<(), InstructionError> {
+        match instruction {
+            Instruction::Numeric(instruction) => {
+                let (operands, result) = numeric(instruction);
+
+                self.pop_values(&operands)?;
+                self.push(result);
+            }
+            Instruction::Vector(instruction) => self.vector(instruction)?,
+            Instruction::Reference(instruction) => self.reference(instruction)?,
+            Instruction::Parametric(instruction) => self.parametric(instruction)?,
+            Instruction::Variable(instruction) => self.variable(instruction)?,
+            Instruction::Table(instruction) => self.table_instruction(instruction)?,
+            Instruction::Memory(instruction) => self.memory_instruction(instruction)?,
+            Instruction::Atomic(instruction) => self.atomic(instruction)?,
+            Instruction::Aggregate(instruction) => self.aggregate(instruction)?,
+            Instruction::Control(instruction) => self.branch(instruction)?,
+        }
+
+        Ok(())
+    }
+
+    /// Validates a reference instruction.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/instructions.html#reference-instructions>
+    fn reference(&mut self, instruction: &ReferenceInstruction) -> Result<(), InstructionError> {
+        match instruction {
+            ReferenceInstruction::Null(kind) => {
+                self.heap_type(*kind)?;
+                self.push(ValueType::Reference(ReferenceType::Nullable(*kind)));
+            }
+            ReferenceInstruction::IsNull => {
+                self.pop_reference()?;
+                self.push(ValueType::I32);
+            }
+            ReferenceInstruction::Function(function) => {
+                let kind = self
+                    .context
+                    .functions
+                    .get(*function as usize)
+                    .copied()
+                    .ok_or(InstructionError::UnknownIndex(Space::Function, *function))?;
+
+                if self.constant.is_none() && !self.context.references.contains(function) {
+                    return Err(InstructionError::UndeclaredFunction(*function));
+                }
+
+                self.push(ValueType::Reference(ReferenceType::NonNullable(
+                    HeapType::Index(kind),
+                )));
+            }
+            ReferenceInstruction::AsNonNull => {
+                let operand = self.pop_reference()?;
+
+                self.push_non_null(operand);
+            }
+            ReferenceInstruction::Equal => {
+                let equality = ValueType::Reference(ReferenceType::Nullable(HeapType::Equality));
+
+                self.pop_expected(equality)?;
+                self.pop_expected(equality)?;
+                self.push(ValueType::I32);
+            }
+            ReferenceInstruction::Test(kind) | ReferenceInstruction::Cast(kind) => {
+                let top = ReferenceType::Nullable(self.context.types.top(kind.heap_type()));
+
+                self.cast(top, *kind)?;
+                self.pop_expected(ValueType::Reference(top))?;
+
+                match instruction {
+                    ReferenceInstruction::Test(_) => self.push(ValueType::I32),
+                    _ => self.push(ValueType::Reference(*kind)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a parametric instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/instructions.html#parametric-instructions>
+    fn parametric(&mut self, instruction: &ParametricInstruction) -> Result<(), InstructionError> {
+        match instruction {
+            ParametricInstruction::Drop => {
+                self.pop()?;
+            }
+            ParametricInstruction::Select(None) => {
+                self.pop_expected(ValueType::I32)?;
+
+                let first = self.pop()?;
+                let second = self.pop()?;
+                let numeric = |operand: Operand| match operand {
+                    Operand::Value(kind) => reference(kind).is_none(),
+                    Operand::Unknown => true,
+                    Operand::Bottom => false,
+                };
+
+                if !numeric(first) || !numeric(second) {
+                    return Err(InstructionError::InvalidSelect);
+                }
+
+                match (first, second) {
+                    (Operand::Unknown, operand) | (operand, Operand::Unknown) => {
+                        self.operands.push(operand)
+                    }
+                    (first, second) if first == second => self.operands.push(first),
+                    _ => return Err(InstructionError::InvalidSelect),
+                }
+            }
+            ParametricInstruction::Select(Some(kinds)) => {
+                let kind = match kinds.as_slice() {
+                    [kind] => *kind,
+                    _ => return Err(InstructionError::InvalidSelect),
+                };
+
+                self.value_type(kind)?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(kind)?;
+                self.pop_expected(kind)?;
+                self.push(kind);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a variable instruction.
+    ///
+    /// See <https://webassembly.github.io/function-references/core/valid/instructions.html#variable-instructions>
+    fn variable(&mut self, instruction: &VariableInstruction) -> Result<(), InstructionError> {
+        match instruction {
+            VariableInstruction::LocalGet(local) => {
+                let kind = self.local(*local)?;
+
+                if !self.initialized[*local as usize] {
+                    return Err(InstructionError::UninitializedLocal(*local));
+                }
+
+                self.push(kind);
+            }
+            VariableInstruction::LocalSet(local) | VariableInstruction::LocalTee(local) => {
+                let kind = self.local(*local)?;
+
+                self.pop_expected(kind)?;
+
+                if !self.initialized[*local as usize] {
+                    self.initialized[*local as usize] = true;
+                    self.initializations.push(*local);
+                }
+
+                if let VariableInstruction::LocalTee(_) = instruction {
+                    self.push(kind);
+                }
+            }
+            VariableInstruction::GlobalGet(global) => {
+                let kind = self.global(*global)?;
+
+                self.push(kind.kind());
+            }
+            VariableInstruction::GlobalSet(global) => {
+                let kind = self.global(*global)?;
+
+                if kind.mutability() != Mutability::Mutable {
+                    return Err(InstructionError::ImmutableGlobal(*global));
+                }
+
+                self.pop_expected(kind.kind())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The type of the local with the given index.
+    fn local(&self, local: LocalIndex) -> Result<ValueType, InstructionError> {
+        self.locals
+            .get(local as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Local, local))
+    }
+
+    /// The type of the global with the given index.
+    fn global(&self, global: u32) -> Result<crate::model::GlobalType, InstructionError> {
+        self.context
+            .globals
+            .get(global as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Global, global))
+    }
+
+    /// Validates a table instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/instructions.html#table-instructions>
+    fn table_instruction(
+        &mut self,
+        instruction: &TableInstruction,
+    ) -> Result<(), InstructionError> {
+        match instruction {
+            TableInstruction::Get(table) => {
+                let table = self.table(*table)?;
+
+                self.pop_expected(table.limits().index_type().into())?;
+                self.push(ValueType::Reference(table.kind()));
+            }
+            TableInstruction::Set(table) => {
+                let table = self.table(*table)?;
+
+                self.pop_expected(ValueType::Reference(table.kind()))?;
+                self.pop_expected(table.limits().index_type().into())?;
+            }
+            TableInstruction::Size(table) => {
+                let table = self.table(*table)?;
+
+                self.push(table.limits().index_type().into());
+            }
+            TableInstruction::Grow(table) => {
+                let table = self.table(*table)?;
+                let index = table.limits().index_type().into();
+
+                self.pop_expected(index)?;
+                self.pop_expected(ValueType::Reference(table.kind()))?;
+                self.push(index);
+            }
+            TableInstruction::Fill(table) => {
+                let table = self.table(*table)?;
+                let index = table.limits().index_type().into();
+
+                self.pop_expected(index)?;
+                self.pop_expected(ValueType::Reference(table.kind()))?;
+                self.pop_expected(index)?;
+            }
+            TableInstruction::Copy(target, source) => {
+                let target = self.table(*target)?;
+                let source = self.table(*source)?;
+
+                self.check_reference(source.kind(), target.kind())?;
+                self.pop_expected(
+                    minimum(source.limits().index_type(), target.limits().index_type()).into(),
+                )?;
+                self.pop_expected(source.limits().index_type().into())?;
+                self.pop_expected(target.limits().index_type().into())?;
+            }
+            TableInstruction::Init(element, table) => {
+                let element = self.element(*element)?;
+                let table = self.table(*table)?;
+
+                self.check_reference(element, table.kind())?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(table.limits().index_type().into())?;
+            }
+            TableInstruction::ElementDrop(element) => {
+                self.element(*element)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a reference type matches another.
+    fn check_reference(
+        &self,
+        kind: ReferenceType,
+        supertype: ReferenceType,
+    ) -> Result<(), InstructionError> {
+        if self.context.types.reference_subtype(kind, supertype) {
+            Ok(())
+        } else {
+            Err(InstructionError::TypeMismatch(
+                ValueType::Reference(supertype),
+                ValueType::Reference(kind),
+            ))
+        }
+    }
+
+    /// Validates a memory argument for an access with the given width in bits,
+    /// returning the type of the addresses of the memory.
+    /// The alignment must not exceed the natural alignment of the access,
+    /// unless the access is atomic, in which case the alignment must equal the natural alignment.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/instructions.html#memory-instructions>
+    fn memory_argument(
+        &self,
+        argument: &MemoryArgument,
+        width: u32,
+        atomic: bool,
+    ) -> Result<ValueType, InstructionError> {
+        let memory = self.memory(argument.memory())?;
+        let natural = (width / 8).trailing_zeros();
+
+        if argument.align() > natural || (atomic && argument.align() != natural) {
+            return Err(InstructionError::InvalidAlignment(argument.align()));
+        }
+
+        if memory.index_type() == IndexType::I32 && argument.offset() > u32::MAX as u64 {
+            return Err(InstructionError::InvalidOffset(argument.offset()));
+        }
+
+        Ok(memory.index_type().into())
+    }
+
+    /// Validates a load of the given width that produces a value of the given type.
+    fn load(
+        &mut self,
+        argument: &MemoryArgument,
+        width: u32,
+        kind: ValueType,
+        atomic: bool,
+    ) -> Result<(), InstructionError> {
+        let address = self.memory_argument(argument, width, atomic)?;
+
+        self.pop_expected(address)?;
+        self.push(kind);
+
+        Ok(())
+    }
+
+    /// Validates a store of the given width that consumes a value of the given type.
+    fn store(
+        &mut self,
+        argument: &MemoryArgument,
+        width: u32,
+        kind: ValueType,
+        atomic: bool,
+    ) -> Result<(), InstructionError> {
+        let address = self.memory_argument(argument, width, atomic)?;
+
+        self.pop_expected(kind)?;
+        self.pop_expected(address)?;
+
+        Ok(())
+    }
+
+    /// Validates a memory instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/instructions.html#memory-instructions>
+    fn memory_instruction(
+        &mut self,
+        instruction: &MemoryInstruction,
+    ) -> Result<(), InstructionError> {
+        match instruction {
+            MemoryInstruction::Load(kind, argument) => {
+                self.load(argument, number_width(*kind), (*kind).into(), false)?
+            }
+            MemoryInstruction::Store(kind, argument) => {
+                self.store(argument, number_width(*kind), (*kind).into(), false)?
+            }
+            MemoryInstruction::Load8(kind, _, argument) => {
+                self.load(argument, 8, (*kind).into(), false)?
+            }
+            MemoryInstruction::Load16(kind, _, argument) => {
+                self.load(argument, 16, (*kind).into(), false)?
+            }
+            MemoryInstruction::Load32(_, argument) => {
+                self.load(argument, 32, ValueType::I64, false)?
+            }
+            MemoryInstruction::Store8(kind, argument) => {
+                self.store(argument, 8, (*kind).into(), false)?
+            }
+            MemoryInstruction::Store16(kind, argument) => {
+                self.store(argument, 16, (*kind).into(), false)?
+            }
+            MemoryInstruction::Store32(argument) => {
+                self.store(argument, 32, ValueType::I64, false)?
+            }
+            MemoryInstruction::Size(memory) => {
+                let memory = self.memory(*memory)?;
+
+                self.push(memory.index_type().into());
+            }
+            MemoryInstruction::Grow(memory) => {
+                let address = self.memory(*memory)?.index_type().into();
+
+                self.pop_expected(address)?;
+                self.push(address);
+            }
+            MemoryInstruction::Fill(memory) => {
+                let address = self.memory(*memory)?.index_type().into();
+
+                self.pop_expected(address)?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(address)?;
+            }
+            MemoryInstruction::Copy(target, source) => {
+                let target = self.memory(*target)?.index_type();
+                let source = self.memory(*source)?.index_type();
+
+                self.pop_expected(minimum(source, target).into())?;
+                self.pop_expected(source.into())?;
+                self.pop_expected(target.into())?;
+            }
+            MemoryInstruction::Init(data, memory) => {
+                let address = self.memory(*memory)?.index_type().into();
+
+                self.data(*data)?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(address)?;
+            }
+            MemoryInstruction::DataDrop(data) => self.data(*data)?,
+        }
+
+        Ok(())
+    }
+
+    /// Validates an atomic memory instruction.
+    ///
+    /// See <https://webassembly.github.io/threads/core/valid/instructions.html#atomic-memory-instructions>
+    fn atomic(&mut self, instruction: &AtomicInstruction) -> Result<(), InstructionError> {
+        let width = |kind: IntegerType| match kind {
+            IntegerType::I32 => 32,
+            IntegerType::I64 => 64,
+        };
+
+        match instruction {
+            AtomicInstruction::Notify(argument) => {
+                let address = self.memory_argument(argument, 32, true)?;
+
+                self.pop_expected(ValueType::I32)?;
+                self.pop_expected(address)?;
+                self.push(ValueType::I32);
+            }
+            AtomicInstruction::Wait(kind, argument) => {
+                let address = self.memory_argument(argument, width(*kind), true)?;
+
+                self.pop_expected(ValueType::I64)?;
+                self.pop_expected((*kind).into())?;
+                self.pop_expected(address)?;
+                self.push(ValueType::I32);
+            }
+            AtomicInstruction::Fence => {}
+            AtomicInstruction::Load(kind, argument) => {
+                self.load(argument, width(*kind), (*kind).into(), true)?
+            }
+            AtomicInstruction::Load8(kind, argument) => {
+                self.load(argument, 8, (*kind).into(), true)?
+            }
+            AtomicInstruction::Load16(kind, argument) => {
+                self.load(argument, 16, (*kind).into(), true)?
+            }
+            AtomicInstruction::Load32(argument) => self.load(argument, 32, ValueType::I64, true)?,
+            AtomicInstruction::Store(kind, argument) => {
+                self.store(argument, width(*kind), (*kind).into(), true)?
+            }
+            AtomicInstruction::Store8(kind, argument) => {
+                self.store(argument, 8, (*kind).into(), true)?
+            }
+            AtomicInstruction::Store16(kind, argument) => {
+                self.store(argument, 16, (*kind).into(), true)?
+            }
+            AtomicInstruction::Store32(argument) => {
+                self.store(argument, 32, ValueType::I64, true)?
+            }
+            AtomicInstruction::ReadModifyWrite(operation, kind, argument) => {
+                self.read_modify_write(*operation, (*kind).into(), argument, width(*kind))?
+            }
+            AtomicInstruction::ReadModifyWrite8(operation, kind, argument) => {
+                self.read_modify_write(*operation, (*kind).into(), argument, 8)?
+            }
+            AtomicInstruction::ReadModifyWrite16(operation, kind, argument) => {
+                self.read_modify_write(*operation, (*kind).into(), argument, 16)?
+            }
+            AtomicInstruction::ReadModifyWrite32(operation, argument) => {
+                self.read_modify_write(*operation, ValueType::I64, argument, 32)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates an atomic read-modify-write instruction.
+    fn read_modify_write(
+        &mut self,
+        operation: AtomicOperation,
+        kind: ValueType,
+        argument: &MemoryArgument,
+        width: u32,
+    ) -> Result<(), InstructionError> {
+        let address = self.memory_argument(argument, width, true)?;
+
+        if operation == AtomicOperation::CompareExchange {
+            self.pop_expected(kind)?;
+        }
+
+        self.pop_expected(kind)?;
+        self.pop_expected(address)?;
+        self.push(kind);
+
+        Ok(())
+    }
+
+    /// Validates a vector instruction.
+    ///
+    /// See <https://webassembly.github.io/spec/core/valid/instructions.html#vector-instructions>
+    fn vector(&mut self, instruction: &VectorInstruction) -> Result<(), InstructionError> {
+        let v128 = ValueType::V128;
+
+        match instruction {
+            VectorInstruction::Constant(_) => self.push(v128),
+            VectorInstruction::Shuffle(lanes) => {
+                if let Some(lane) = lanes.iter().find(|lane| **lane >= 32) {
+                    return Err(InstructionError::InvalidLane(*lane));
+                }
+
+                self.pop_values(&[v128, v128])?;
+                self.push(v128);
+            }
+            VectorInstruction::Splat(shape) => {
+                self.pop_expected(lane_type(*shape))?;
+                self.push(v128);
+            }
+            VectorInstruction::ExtractLane(shape, extension, lane) => {
+                let packed = matches!(shape, VectorShape::I8x16 | VectorShape::I16x8);
+
+                if packed != extension.is_some() {
+                    return Err(InstructionError::InvalidSignExtension);
+                }
+
+                if *lane >= shape.lanes() {
+                    return Err(InstructionError::InvalidLane(*lane));
+                }
+
+                self.pop_expected(v128)?;
+                self.push(lane_type(*shape));
+            }
+            VectorInstruction::ReplaceLane(shape, lane) => {
+                if *lane >= shape.lanes() {
+                    return Err(InstructionError::InvalidLane(*lane));
+                }
+
+                self.pop_expected(lane_type(*shape))?;
+                self.pop_expected(v128)?;
+                self.push(v128);
+            }
+            VectorInstruction::AnyTrue
+            | VectorInstruction::AllTrue(_)
+            | VectorInstruction::Bitmask(_) => {
+                self.pop_expected(v128)?;
+                self.push(ValueType::I32);
+            }
+            VectorInstruction::ShiftLeft(_) | VectorInstruction::ShiftRight(..) => {
+                self.pop_values(&[v128, ValueType::I32])?;
+                self.push(v128);
+            }
+            VectorInstruction::BitSelect => {
+                self.pop_values(&[v128, v128, v128])?;
+                self.push(v128);
+            }
+            VectorInstruction::Not
+            | VectorInstruction::AbsoluteValue(_)
+            | VectorInstruction::Negate(_)
+            | VectorInstruction::CountOnes
+            | VectorInstruction::SquareRoot(_)
+            | VectorInstruction::Ceiling(_)
+            | VectorInstruction::Floor(_)
+            | VectorInstruction::Truncate(_)
+            | VectorInstruction::Nearest(_)
+            | VectorInstruction::Extend(..)
+            | VectorInstruction::ExtendAddPairwise(..)
+            | VectorInstruction::Demote
+            | VectorInstruction::Promote
+            | VectorInstruction::ConvertAndTruncateWithSaturation(..)
+            | VectorInstruction::Convert(..) => {
+                self.pop_expected(v128)?;
+                self.push(v128);
+            }
+            VectorInstruction::Load(argument) => self.load(argument, 128, v128, false)?,
+            VectorInstruction::Store(argument) => self.store(argument, 128, v128, false)?,
+            VectorInstruction::Load8x8(_, argument)
+            | VectorInstruction::Load16x4(_, argument)
+            | VectorInstruction::Load32x2(_, argument)
+            | VectorInstruction::Load64Splat(argument)
+            | VectorInstruction::Load64Zero(argument) => self.load(argument, 64, v128, false)?,
+            VectorInstruction::Load8Splat(argument) => self.load(argument, 8, v128, false)?,
+            VectorInstruction::Load16Splat(argument) => self.load(argument, 16, v128, false)?,
+            VectorInstruction::Load32Splat(argument) | VectorInstruction::Load32Zero(argument) => {
+                self.load(argument, 32, v128, false)?
+            }
+            VectorInstruction::Load8Lane(argument, lane) => self.lane(argument, 8, *lane, true)?,
+            VectorInstruction::Load16Lane(argument, lane) => {
+                self.lane(argument, 16, *lane, true)?
+            }
+            VectorInstruction::Load32Lane(argument, lane) => {
+                self.lane(argument, 32, *lane, true)?
+            }
+            VectorInstruction::Load64Lane(argument, lane) => {
+                self.lane(argument, 64, *lane, true)?
+            }
+            VectorInstruction::Store8Lane(argument, lane) => {
+                self.lane(argument, 8, *lane, false)?
+            }
+            VectorInstruction::Store16Lane(argument, lane) => {
+                self.lane(argument, 16, *lane, false)?
+            }
+            VectorInstruction::Store32Lane(argument, lane) => {
+                self.lane(argument, 32, *lane, false)?
+            }
+            VectorInstruction::Store64Lane(argument, lane) => {
+                self.lane(argument, 64, *lane, false)?
+            }
+            _ => {
+                self.pop_values(&[v128, v128])?;
+                self.push(v128);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a load or store of a single lane with the given width in bits.
+    fn lane(
+        &mut self,
+        argument: &MemoryArgument,
+        width: u32,
+        lane: u8,
+        load: bool,
+    ) -> Result<(), InstructionError> {
+        if lane as u32 >= 128 / width {
+            return Err(InstructionError::InvalidLane(lane));
+        }
+
+        let address = self.memory_argument(argument, width, false)?;
+
+        self.pop_values(&[address, ValueType::V128])?;
+
+        if load {
+            self.push(ValueType::V128);
+        }
+
+        Ok(())
+    }
+
+    /// Validates an aggregate instruction.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/instructions.html#aggregate-reference-instructions>
+    fn aggregate(&mut self, instruction: &AggregateInstruction) -> Result<(), InstructionError> {
+        let array_reference =
+            |kind: TypeIndex| ValueType::Reference(ReferenceType::Nullable(HeapType::Index(kind)));
+        let new = |kind: TypeIndex| {
+            ValueType::Reference(ReferenceType::NonNullable(HeapType::Index(kind)))
+        };
+
+        match instruction {
+            AggregateInstruction::StructNew(kind) => {
+                let fields: Vec<ValueType> = self
+                    .struct_type(*kind)?
+                    .fields()
+                    .iter()
+                    .map(|field| unpacked(field.kind()))
+                    .collect();
+
+                self.pop_values(&fields)?;
+                self.push(new(*kind));
+            }
+            AggregateInstruction::StructNewDefault(kind) => {
+                let fields = self.struct_type(*kind)?.fields();
+
+                if !fields.iter().all(|field| defaultable_field(*field)) {
+                    return Err(InstructionError::NonDefaultable(*kind));
+                }
+
+                self.push(new(*kind));
+            }
+            AggregateInstruction::StructGet(extension, kind, field) => {
+                let field = self.field(*kind, *field)?;
+
+                check_extension(field, extension.is_some())?;
+                self.pop_expected(array_reference(*kind))?;
+                self.push(unpacked(field.kind()));
+            }
+            AggregateInstruction::StructSet(kind, index) => {
+                let field = self.field(*kind, *index)?;
+
+                if field.mutability() != Mutability::Mutable {
+                    return Err(InstructionError::ImmutableField(*kind, *index));
+                }
+
+                self.pop_expected(unpacked(field.kind()))?;
+                self.pop_expected(array_reference(*kind))?;
+            }
+            AggregateInstruction::ArrayNew(kind) => {
+                let field = self.array_type(*kind)?.field();
+
+                self.pop_values(&[unpacked(field.kind()), ValueType::I32])?;
+                self.push(new(*kind));
+            }
+            AggregateInstruction::ArrayNewDefault(kind) => {
+                if !defaultable_field(self.array_type(*kind)?.field()) {
+                    return Err(InstructionError::NonDefaultable(*kind));
+                }
+
+                self.pop_expected(ValueType::I32)?;
+                self.push(new(*kind));
+            }
+            AggregateInstruction::ArrayNewFixed(kind, length) => {
+                let element = unpacked(self.array_type(*kind)?.field().kind());
+
+                for _ in 0..*length {
+                    self.pop_expected(element)?;
+                }
+
+                self.push(new(*kind));
+            }
+            AggregateInstruction::ArrayNewData(kind, data) => {
+                self.array_data(*kind, *data)?;
+                self.pop_values(&[ValueType::I32, ValueType::I32])?;
+                self.push(new(*kind));
+            }
+            AggregateInstruction::ArrayNewElement(kind, element) => {
+                self.array_element(*kind, *element)?;
+                self.pop_values(&[ValueType::I32, ValueType::I32])?;
+                self.push(new(*kind));
+            }
+            AggregateInstruction::ArrayGet(extension, kind) => {
+                let field = self.array_type(*kind)?.field();
+
+                check_extension(field, extension.is_some())?;
+                self.pop_values(&[array_reference(*kind), ValueType::I32])?;
+                self.push(unpacked(field.kind()));
+            }
+            AggregateInstruction::ArraySet(kind) => {
+                let field = self.mutable_array(*kind)?;
+
+                self.pop_values(&[
+                    array_reference(*kind),
+                    ValueType::I32,
+                    unpacked(field.kind()),
+                ])?;
+            }
+            AggregateInstruction::ArrayLength => {
+                self.pop_expected(ValueType::Reference(ReferenceType::Nullable(
+                    HeapType::Array,
+                )))?;
+                self.push(ValueType::I32);
+            }
+            AggregateInstruction::ArrayFill(kind) => {
+                let field = self.mutable_array(*kind)?;
+
+                self.pop_values(&[
+                    array_reference(*kind),
+                    ValueType::I32,
+                    unpacked(field.kind()),
+                    ValueType::I32,
+                ])?;
+            }
+            AggregateInstruction::ArrayCopy(target, source) => {
+                let target_field = self.mutable_array(*target)?;
+                let source_field = self.array_type(*source)?.field();
+                let matches = match (source_field.kind(), target_field.kind()) {
+                    (StorageType::Value(source), StorageType::Value(target)) => {
+                        self.context.types.subtype(source, target)
+                    }
+                    (source, target) => source == target,
+                };
+
+                if !matches {
+                    return Err(InstructionError::TypeMismatch(
+                        unpacked(target_field.kind()),
+                        unpacked(source_field.kind()),
+                    ));
+                }
+
+                self.pop_values(&[
+                    array_reference(*target),
+                    ValueType::I32,
+                    array_reference(*source),
+                    ValueType::I32,
+                    ValueType::I32,
+                ])?;
+            }
+            AggregateInstruction::ArrayInitData(kind, data) => {
+                self.mutable_array(*kind)?;
+                self.array_data(*kind, *data)?;
+                self.pop_values(&[
+                    array_reference(*kind),
+                    ValueType::I32,
+                    ValueType::I32,
+                    ValueType::I32,
+                ])?;
+            }
+            AggregateInstruction::ArrayInitElement(kind, element) => {
+                self.mutable_array(*kind)?;
+                self.array_element(*kind, *element)?;
+                self.pop_values(&[
+                    array_reference(*kind),
+                    ValueType::I32,
+                    ValueType::I32,
+                    ValueType::I32,
+                ])?;
+            }
+            AggregateInstruction::ReferenceI31 => {
+                self.pop_expected(ValueType::I32)?;
+                self.push(ValueType::Reference(ReferenceType::NonNullable(
+                    HeapType::I31,
+                )));
+            }
+            AggregateInstruction::I31Get(_) => {
+                self.pop_expected(ValueType::Reference(ReferenceType::Nullable(HeapType::I31)))?;
+                self.push(ValueType::I32);
+            }
+            AggregateInstruction::AnyConvertExternal | AggregateInstruction::ExternalConvertAny => {
+                let (source, target) = match instruction {
+                    AggregateInstruction::AnyConvertExternal => (HeapType::External, HeapType::Any),
+                    _ => (HeapType::Any, HeapType::External),
+                };
+                let nullable = match self
+                    .pop_expected(ValueType::Reference(ReferenceType::Nullable(source)))?
+                {
+                    Operand::Value(kind) => reference(kind).is_some_and(|kind| kind.is_nullable()),
+                    Operand::Unknown | Operand::Bottom => false,
+                };
+
+                self.push(ValueType::Reference(if nullable {
+                    ReferenceType::Nullable(target)
+                } else {
+                    ReferenceType::NonNullable(target)
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The field with the given index of a structure type.
+    fn field(&self, kind: TypeIndex, field: u32) -> Result<FieldType, InstructionError> {
+        self.struct_type(kind)?
+            .fields()
+            .get(field as usize)
+            .copied()
+            .ok_or(InstructionError::UnknownIndex(Space::Field, field))
+    }
+
+    /// The element field of a mutable array type.
+    fn mutable_array(&self, kind: TypeIndex) -> Result<FieldType, InstructionError> {
+        let field = self.array_type(kind)?.field();
+
+        if field.mutability() == Mutability::Mutable {
+            Ok(field)
+        } else {
+            Err(InstructionError::ImmutableArray(kind))
+        }
+    }
+
+    /// Checks that an array type can be initialized from the given data segment.
+    fn array_data(&self, kind: TypeIndex, data: u32) -> Result<(), InstructionError> {
+        let field = self.array_type(kind)?.field();
+
+        self.data(data)?;
+
+        match field.kind() {
+            StorageType::Value(field) if reference(field).is_some() => {
+                Err(InstructionError::InvalidArrayData(kind))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that an array type can be initialized from the given element segment.
+    fn array_element(&self, kind: TypeIndex, element: u32) -> Result<(), InstructionError> {
+        let field = self.array_type(kind)?.field();
+        let element = self.element(element)?;
+
+        match field.kind() {
+            StorageType::Value(field)
+                if self
+                    .context
+                    .types
+                    .subtype(ValueType::Reference(element), field) =>
+            {
+                Ok(())
+            }
+            field => Err(InstructionError::TypeMismatch(
+                unpacked(field),
+                ValueType::Reference(element),
+            )),
+        }
+    }
+}
+
+/// Checks that a sign extension is given exactly when accessing a packed field.
+fn check_extension(field: FieldType, extension: bool) -> Result<(), InstructionError> {
+    let packed = matches!(field.kind(), StorageType::Packed(_));
+
+    if packed == extension {
+        Ok(())
+    } else {
+        Err(InstructionError::InvalidSignExtension)
+    }
+}
+
+/// Determines whether a field has a default value.
+fn defaultable_field(field: FieldType) -> bool {
+    match field.kind() {
+        StorageType::Value(kind) => is_defaultable(kind),
+        StorageType::Packed(_) => true,
+    }
+}
+
+/// The smaller of two index types, used for the length operand of copies between tables or memories.
+fn minimum(left: IndexType, right: IndexType) -> IndexType {
+    match (left, right) {
+        (IndexType::I64, IndexType::I64) => IndexType::I64,
+        _ => IndexType::I32,
+    }
+}
+
+/// The width in bits of a number type.
+fn number_width(kind: NumberType) -> u32 {
+    match kind {
+        NumberType::I32 | NumberType::F32 => 32,
+        NumberType::I64 | NumberType::F64 => 64,
+    }
+}
+
+/// The type of the values in the lanes of a vector shape.
+fn lane_type(shape: VectorShape) -> ValueType {
+    match shape {
+        VectorShape::I8x16 | VectorShape::I16x8 | VectorShape::I32x4 => ValueType::I32,
+        VectorShape::I64x2 => ValueType::I64,
+        VectorShape::F32x4 => ValueType::F32,
+        VectorShape::F64x2 => ValueType::F64,
+    }
+}
+
+/// The operand types and result type of a numeric instruction.
+///
+/// See <https://webassembly.github.io/spec/core/valid/instructions.html#numeric-instructions>
+fn numeric(instruction: &NumericInstruction) -> (Vec<ValueType>, ValueType) {
+    let unary = |kind: ValueType| (vec![kind], kind);
+    let binary = |kind: ValueType| (vec![kind, kind], kind);
+    let test = |kind: ValueType| (vec![kind], ValueType::I32);
+    let compare = |kind: ValueType| (vec![kind, kind], ValueType::I32);
+
+    match instruction {
+        NumericInstruction::I32Constant(_) => (Vec::new(), ValueType::I32),
+        NumericInstruction::I64Constant(_) => (Vec::new(), ValueType::I64),
+        NumericInstruction::F32Constant(_) => (Vec::new(), ValueType::F32),
+        NumericInstruction::F64Constant(_) => (Vec::new(), ValueType::F64),
+        NumericInstruction::CountLeadingZeros(kind)
+        | NumericInstruction::CountTrailingZeros(kind)
+        | NumericInstruction::CountOnes(kind)
+        | NumericInstruction::ExtendSigned8(kind)
+        | NumericInstruction::ExtendSigned16(kind) => unary((*kind).into()),
+        NumericInstruction::ExtendSigned32 => unary(ValueType::I64),
+        NumericInstruction::AbsoluteValue(kind)
+        | NumericInstruction::Negate(kind)
+        | NumericInstruction::SquareRoot(kind)
+        | NumericInstruction::Ceiling(kind)
+        | NumericInstruction::Floor(kind)
+        | NumericInstruction::Truncate(kind)
+        | NumericInstruction::Nearest(kind) => unary((*kind).into()),
+        NumericInstruction::Add(kind)
+        | NumericInstruction::Subtract(kind)
+        | NumericInstruction::Multiply(kind) => binary((*kind).into()),
+        NumericInstruction::DivideInteger(kind, _)
+        | NumericInstruction::Remainder(kind, _)
+        | NumericInstruction::ShiftRight(kind, _)
+        | NumericInstruction::And(kind)
+        | NumericInstruction::Or(kind)
+        | NumericInstruction::Xor(kind)
+        | NumericInstruction::ShiftLeft(kind)
+        | NumericInstruction::RotateLeft(kind)
+        | NumericInstruction::RotateRight(kind) => binary((*kind).into()),
+        NumericInstruction::DivideFloat(kind)
+        | NumericInstruction::Minimum(kind)
+        | NumericInstruction::Maximum(kind)
+        | NumericInstruction::CopySign(kind) => binary((*kind).into()),
+        NumericInstruction::EqualToZero(kind) => test((*kind).into()),
+        NumericInstruction::Equal(kind) | NumericInstruction::NotEqual(kind) => {
+            compare((*kind).into())
+        }
+        NumericInstruction::LessThanInteger(kind, _)
+        | NumericInstruction::GreaterThanInteger(kind, _)
+        | NumericInstruction::LessThanOrEqualToInteger(kind, _)
+        | NumericInstruction::GreaterThanOrEqualToInteger(kind, _) => compare((*kind).into()),
+        NumericInstruction::LessThanFloat(kind)
+        | NumericInstruction::GreaterThanFloat(kind)
+        | NumericInstruction::LessThanOrEqualToFloat(kind)
+        | NumericInstruction::GreaterThanOrEqualToFloat(kind) => compare((*kind).into()),
+        NumericInstruction::Wrap => (vec![ValueType::I64], ValueType::I32),
+        NumericInstruction::ExtendWithSignExtension(_) => (vec![ValueType::I32], ValueType::I64),
+        NumericInstruction::ConvertAndTruncate(kind, source, _)
+        | NumericInstruction::ConvertAndTruncateWithSaturation(kind, source, _) => {
+            (vec![(*source).into()], (*kind).into())
+        }
+        NumericInstruction::Demote => (vec![ValueType::F64], ValueType::F32),
+        NumericInstruction::Promote => (vec![ValueType::F32], ValueType::F64),
+        NumericInstruction::Convert(kind, source, _) => (vec![(*source).into()], (*kind).into()),
+        NumericInstruction::ReinterpretFloat(kind) => match kind {
+            IntegerType::I32 => (vec![ValueType::F32], ValueType::I32),
+            IntegerType::I64 => (vec![ValueType::F64], ValueType::I64),
+        },
+        NumericInstruction::ReinterpretInteger(kind) => match kind {
+            FloatType::F32 => (vec![ValueType::I32], ValueType::F32),
+            FloatType::F64 => (vec![ValueType::I64], ValueType::F64),
+        },
+    }
+}
@@ -0,0 +1,369 @@
+//! Validate WebAssembly modules.
+//!
+//! Validation checks that a module is well-formed according to the type system of WebAssembly,
+//! which is what engines (e.g., wasmtime) check before instantiating a module.
+//! Instructions are type-checked against the operand stack, block types and the index spaces of
+//! the module, including the typed function references, garbage collection, exception handling,
+//! threads, memory64 and extended constant expression proposals.
+//!
+//! See <https://webassembly.github.io/spec/core/valid/index.html>
+
+mod context;
+mod errors;
+mod instructions;
+mod module;
+mod types;
+
+pub use errors::{InstructionError, Location, Space, ValidationError};
+pub(crate) use types::Types;
+
+use crate::model::Module;
+
+/// Validates a WebAssembly module.
+/// Errors in instructions identify the expression (e.g., the index of the function)
+/// and the offset of the instruction within it.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#valid-module>
+///
+/// # Examples
+/// ## Valid
+/// ```rust
+/// use wasm_ast::{validate, ControlInstruction, Expression, Function, FunctionType, Module, ResultType, ValueType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder
+///     .add_function_type(FunctionType::nullary(ResultType::from(vec![ValueType::I32])))
+///     .unwrap();
+///
+/// builder
+///     .add_function(Function::new(kind, ResultType::empty(), Expression::from(vec![42i32.into()])))
+///     .unwrap();
+///
+/// assert!(validate(&builder.build()).is_ok());
+/// ```
+///
+/// ## Invalid
+/// ```rust
+/// use wasm_ast::{validate, Expression, Function, FunctionType, InstructionError, Location, Module, ResultType, ValidationError, ValueType};
+///
+/// let mut builder = Module::builder();
+/// let kind = builder
+///     .add_function_type(FunctionType::nullary(ResultType::from(vec![ValueType::I64])))
+///     .unwrap();
+///
+/// builder
+///     .add_function(Function::new(kind, ResultType::empty(), Expression::from(vec![42i32.into()])))
+///     .unwrap();
+///
+/// let error = validate(&builder.build()).unwrap_err();
+///
+/// assert_eq!(
+///     error,
+///     ValidationError::InvalidInstruction(
+///         Location::Function(0),
+///         1,
+///         InstructionError::TypeMismatch(ValueType::I64, ValueType::I32)
+///     )
+/// );
+/// assert_eq!(error.function(), Some(0));
+/// assert_eq!(error.offset(), Some(1));
+/// ```
+pub fn validate(module: &Module) -> Result<(), ValidationError> {
+    module::validate_module(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        ControlInstruction, Expression, Function, FunctionType, Global, ResultType, ValueType,
+        VariableInstruction,
+    };
+
+    #[test]
+    fn validate_empty_module() {
+        assert_eq!(validate(&Module::empty()), Ok(()));
+    }
+
+    #[test]
+    fn validate_unreachable_code() {
+        let mut builder = Module::builder();
+        let kind = builder
+            .add_function_type(FunctionType::nullary(ResultType::from(vec![
+                ValueType::F64,
+            ])))
+            .unwrap();
+
+        builder
+            .add_function(Function::new(
+                kind,
+                ResultType::empty(),
+                Expression::from(vec![
+                    ControlInstruction::Unreachable.into(),
+                    crate::model::NumericInstruction::Add(crate::model::NumberType::F64).into(),
+                ]),
+            ))
+            .unwrap();
+
+        assert_eq!(validate(&builder.build()), Ok(()));
+    }
+
+    #[test]
+    fn validate_constant_global() {
+        let mut builder = Module::builder();
+
+        builder
+            .add_global(Global::immutable(
+                ValueType::I32,
+                Expression::from(vec![1i32.into()]),
+            ))
+            .unwrap();
+        builder
+            .add_global(Global::mutable(
+                ValueType::I32,
+                Expression::from(vec![VariableInstruction::GlobalGet(1).into()]),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            validate(&builder.build()),
+            Err(ValidationError::InvalidInstruction(
+                Location::Global(1),
+                0,
+                InstructionError::NonConstant(VariableInstruction::GlobalGet(1).into())
+            ))
+        );
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn validate_invalid_lazy_body() {
+        let bytes = b"\x00\x61\x73\x6D\x01\x00\x00\x00\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0A\x05\x01\x03\x00\xFF\x0B";
+        let module = crate::parse_binary_lazy(bytes).unwrap();
+        let error = validate(&module).unwrap_err();
+
+        assert!(matches!(error, ValidationError::InvalidBinary(_)));
+        assert_eq!(error.function(), Some(0));
+        assert_eq!(
+            error.to_string(),
+            "The code of a function parsed lazily is not a valid binary format: unknown opcode 0xFF at offset 0x17 in function 0."
+        );
+    }
+
+    #[cfg(feature = "text")]
+    mod text {
+        use super::*;
+        use crate::model::{HeapType, ReferenceType};
+        use crate::parser::parse_text;
+
+        /// Validates the module in the given text format.
+        fn validate_text(text: &str) -> Result<(), ValidationError> {
+            validate(&parse_text(text).unwrap())
+        }
+
+        /// Asserts that the module is invalid at the given offset of the given function.
+        fn assert_invalid(text: &str, function: u32, offset: usize, expected: InstructionError) {
+            assert_eq!(
+                validate_text(text),
+                Err(ValidationError::InvalidInstruction(
+                    Location::Function(function),
+                    offset,
+                    expected
+                ))
+            );
+        }
+
+        #[test]
+        fn validate_valid_module() {
+            let text = r#"(module
+                (type $point (struct (field $x (mut i32)) (field $y i64)))
+                (type $bytes (array (mut i8)))
+                (type $binary (func (param i32 i32) (result i32)))
+                (import "env" "log" (func $log (param i32)))
+                (import "env" "error" (tag $error (param i32)))
+                (memory $memory 1 2 shared)
+                (table $table 2 funcref)
+                (global $base i32 (i32.const 8))
+                (global $offset i32 (i32.add (global.get $base) (i32.const 8)))
+                (global $counter (mut i32) (i32.const 0))
+                (func $add (type $binary) (i32.add (local.get 0) (local.get 1)))
+                (func $main (export "main") (param $n i32) (result i32) (local $point (ref null $point)) (local $f (ref $binary))
+                    (local.set $f (ref.func $add))
+                    (local.set $point (struct.new $point (i32.const 1) (i64.const 2)))
+                    (struct.set $point $x (local.get $point) (local.get $n))
+                    (block $done (result i32)
+                        (loop $loop
+                            (br_if $done (i32.const 0) (i32.eqz (local.get $n)))
+                            (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+                            (br $loop))
+                        (unreachable))
+                    (drop)
+                    (try_table (catch $error 0) (call $log (i32.atomic.load (i32.const 0))))
+                    (call_indirect $table (type $binary) (i32.const 1) (i32.const 2) (i32.const 0))
+                    (call_ref $binary (i32.const 3) (i32.const 4) (local.get $f))
+                    (i32.add)
+                    (array.len (array.new_default $bytes (i32.const 4)))
+                    (i32.add)
+                    (if (result i32) (local.get $n) (then (i32.const 1)) (else (i32.const 2)))
+                    (select (i32.const 5))
+                    (return))
+                (elem (table $table) (offset (global.get $offset)) func $add)
+                (data (memory $memory) (global.get $base) "data"))"#;
+
+            assert_eq!(validate_text(text), Ok(()));
+        }
+
+        #[test]
+        fn validate_type_mismatch() {
+            assert_invalid(
+                "(func (result i32) (block (result i32) (i64.const 1) (i32.const 2) (i32.add)) (nop))",
+                0,
+                3,
+                InstructionError::TypeMismatch(ValueType::I32, ValueType::I64),
+            );
+        }
+
+        #[test]
+        fn validate_block_results() {
+            assert_invalid(
+                "(func (block (result i32) (nop)) (drop))",
+                0,
+                0,
+                InstructionError::MissingOperand,
+            );
+            assert_invalid(
+                "(func (i32.const 1) (i32.const 2) (drop))",
+                0,
+                3,
+                InstructionError::UnexpectedOperands(1),
+            );
+        }
+
+        #[test]
+        fn validate_imported_function_index() {
+            assert_invalid(
+                r#"(import "env" "f" (func)) (func (call 2))"#,
+                1,
+                0,
+                InstructionError::UnknownIndex(Space::Function, 2),
+            );
+        }
+
+        #[test]
+        fn validate_uninitialized_local() {
+            assert_invalid(
+                "(type $f (func)) (func (local (ref $f)) (block (local.set 0 (ref.func 0))) (drop (local.get 0))) (elem declare func 0)",
+                0,
+                3,
+                InstructionError::UninitializedLocal(0),
+            );
+        }
+
+        #[test]
+        fn validate_undeclared_function_reference() {
+            assert_invalid(
+                "(func (drop (ref.func 0)))",
+                0,
+                0,
+                InstructionError::UndeclaredFunction(0),
+            );
+        }
+
+        #[test]
+        fn validate_alignment() {
+            assert_invalid(
+                "(memory 1) (func (drop (i32.load align=8 (i32.const 0))))",
+                0,
+                1,
+                InstructionError::InvalidAlignment(3),
+            );
+        }
+
+        #[test]
+        fn validate_immutable_field() {
+            assert_invalid(
+                "(type $t (struct (field i32))) (func (param (ref $t)) (struct.set $t 0 (local.get 0) (i32.const 1)))",
+                0,
+                2,
+                InstructionError::ImmutableField(0, 0),
+            );
+        }
+
+        #[test]
+        fn validate_unreachable_references() {
+            assert_invalid(
+                "(func (result i32) unreachable ref.as_non_null)",
+                0,
+                2,
+                InstructionError::UnexpectedReference(ValueType::I32),
+            );
+            assert_invalid(
+                "(func (result i32) unreachable (br_on_null 0))",
+                0,
+                2,
+                InstructionError::UnexpectedReference(ValueType::I32),
+            );
+            assert_invalid(
+                "(func (result i32) unreachable (br_on_non_null 0))",
+                0,
+                1,
+                InstructionError::UnexpectedReference(ValueType::I32),
+            );
+            assert_eq!(
+                validate_text(
+                    "(type $t (func)) (func (result (ref $t)) unreachable ref.as_non_null)"
+                ),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn validate_module_errors() {
+            assert_eq!(
+                validate_text(r#"(func (export "f")) (func (export "f"))"#),
+                Err(ValidationError::DuplicateExport("f".to_string()))
+            );
+            assert_eq!(
+                validate_text("(memory 2 1)"),
+                Err(ValidationError::InvalidLimits(2, Some(1)))
+            );
+            assert_eq!(
+                validate_text("(memory 1 shared)"),
+                Err(ValidationError::UnboundedSharedMemory)
+            );
+            assert_eq!(
+                validate_text("(func $f (param i32)) (start $f)"),
+                Err(ValidationError::InvalidStart(0))
+            );
+            assert_eq!(
+                validate_text("(type $a (sub final (struct))) (type (sub $a (struct)))"),
+                Err(ValidationError::InvalidSubtype(1))
+            );
+        }
+
+        #[test]
+        fn validate_table_initializer() {
+            assert_eq!(
+                validate_text(
+                    "(type $t (func)) (func $f (type $t)) (table 1 (ref $t) (ref.func $f))"
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                validate_text("(type $t (func)) (table 1 (ref $t))"),
+                Err(ValidationError::NonDefaultableTable(0))
+            );
+            assert_eq!(
+                validate_text("(type $t (func)) (table 1 (ref $t) (ref.null $t))"),
+                Err(ValidationError::InvalidInstruction(
+                    Location::Table(0),
+                    1,
+                    InstructionError::TypeMismatch(
+                        ValueType::Reference(ReferenceType::NonNullable(HeapType::Index(0))),
+                        ValueType::Reference(ReferenceType::Nullable(HeapType::Index(0)))
+                    )
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! Validation of the components of a module.
+//!
+//! See <https://webassembly.github.io/spec/core/valid/modules.html>
+
+use crate::model::{
+    CompositeType, DataMode, ElementMode, ExportDescription, Expression, FunctionType, GlobalType,
+    HeapType, ImportDescription, IndexType, Limit, MemoryType, Module, RecursiveType, StorageType,
+    TableType, TypeIndex, ValueType,
+};
+use crate::validation::context::Context;
+use crate::validation::instructions::ExpressionValidator;
+use crate::validation::types::reference;
+use crate::validation::{Location, Space, ValidationError};
+use std::collections::HashSet;
+
+/// The maximum number of pages in a 32-bit memory.
+const MAXIMUM_PAGES_32: u64 = 1 << 16;
+
+/// The maximum number of pages in a 64-bit memory.
+const MAXIMUM_PAGES_64: u64 = 1 << 48;
+
+/// Validates a module against the validation rules of the specification.
+pub fn validate_module(module: &Module) -> Result<(), ValidationError> {
+    let definitions = module.types().unwrap_or_default();
+
+    validate_type_indices(definitions)?;
+
+    let context = Context::new(module);
+
+    validate_subtypes(&context)?;
+    validate_imports(module, &context)?;
+
+    for kind in &context.functions[context.functions.len() - function_count(module)..] {
+        function_type(&context, *kind)?;
+    }
+
+    validate_tables(module, &context)?;
+
+    for memory in context.memories.iter() {
+        validate_memory(memory)?;
+    }
+
+    for kind in context.tags.iter() {
+        validate_tag(&context, *kind)?;
+    }
+
+    validate_globals(module, &context)?;
+    validate_exports(module, &context)?;
+
+    if let Some(start) = module.start() {
+        let kind = context.functions.get(start.function() as usize).ok_or(
+            ValidationError::UnknownIndex(Space::Function, start.function()),
+        )?;
+        let kind = function_type(&context, *kind)?;
+
+        if !kind.parameters().is_empty() || !kind.results().is_empty() {
+            return Err(ValidationError::InvalidStart(start.function()));
+        }
+    }
+
+    validate_elements(module, &context)?;
+    validate_data(module, &context)?;
+    validate_functions(module, &context)
+}
+
+/// The number of functions defined (i.e., not imported) by the module.
+fn function_count(module: &Module) -> usize {
+    module.functions().unwrap_or_default().len()
+}
+
+/// Validates that every type index referenced by a type definition refers to a type defined
+/// in the same or an earlier recursive type.
+fn validate_type_indices(definitions: &[RecursiveType]) -> Result<(), ValidationError> {
+    let mut end = 0;
+
+    for definition in definitions {
+        end += definition.len() as TypeIndex;
+
+        for kind in definition.types() {
+            let mut kinds: Vec<ValueType> = Vec::new();
+
+            match kind.kind() {
+                CompositeType::Function(kind) => {
+                    kinds.extend(kind.parameters().kinds());
+                    kinds.extend(kind.results().kinds());
+                }
+                CompositeType::Struct(kind) => {
+                    kinds.extend(kind.fields().iter().filter_map(|field| match field.kind() {
+                        StorageType::Value(kind) => Some(kind),
+                        StorageType::Packed(_) => None,
+                    }))
+                }
+                CompositeType::Array(kind) => {
+                    if let StorageType::Value(kind) = kind.field().kind() {
+                        kinds.push(kind);
+                    }
+                }
+            }
+
+            for kind in kinds {
+                check_value_type(kind, end)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates the declared supertypes of each type.
+/// A type may have at most one supertype, which must be defined before the type,
+/// must not be final and must have a matching composite type.
+///
+/// See <https://webassembly.github.io/gc/core/valid/types.html#sub-types>
+fn validate_subtypes(context: &Context) -> Result<(), ValidationError> {
+    for index in 0..context.types.len() as TypeIndex {
+        let kind = context.types.get(index).expect("type index is in range");
+        let supertype = match kind.supertypes() {
+            [] => continue,
+            [supertype] => *supertype,
+            _ => return Err(ValidationError::InvalidSubtype(index)),
+        };
+        let valid = supertype < index
+            && context.types.get(supertype).is_some_and(|supertype| {
+                !supertype.is_final()
+                    && context
+                        .types
+                        .composite_subtype(kind.kind(), supertype.kind())
+            });
+
+        if !valid {
+            return Err(ValidationError::InvalidSubtype(index));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates that a value type only refers to types with an index less than the given bound.
+fn check_value_type(kind: ValueType, types: TypeIndex) -> Result<(), ValidationError> {
+    match reference(kind).map(|kind| kind.heap_type()) {
+        Some(HeapType::Index(index)) if index >= types => {
+            Err(ValidationError::UnknownIndex(Space::Type, index))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates that a value type refers to defined types.
+fn value_type(context: &Context, kind: ValueType) -> Result<(), ValidationError> {
+    check_value_type(kind, context.types.len() as TypeIndex)
+}
+
+/// The function type with the given index.
+fn function_type<'m>(
+    context: &Context<'m>,
+    index: TypeIndex,
+) -> Result<&'m FunctionType, ValidationError> {
+    match context.types.get(index) {
+        Some(_) => context
+            .types
+            .function(index)
+            .ok_or(ValidationError::ExpectedFunctionType(index)),
+        None => Err(ValidationError::UnknownIndex(Space::Type, index)),
+    }
+}
+
+/// Validates limits with the given maximum for both the minimum and maximum.
+///
+/// See <https://webassembly.github.io/spec/core/valid/types.html#limits>
+fn validate_limits(limits: &Limit, range: u64) -> Result<(), ValidationError> {
+    let valid = limits.min() <= range
+        && limits
+            .max()
+            .is_none_or(|max| max <= range && limits.min() <= max);
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidLimits(limits.min(), limits.max()))
+    }
+}
+
+/// Validates a table type.
+///
+/// See <https://webassembly.github.io/spec/core/valid/types.html#table-types>
+fn validate_table(context: &Context, table: &TableType) -> Result<(), ValidationError> {
+    let range = match table.limits().index_type() {
+        IndexType::I32 => u32::MAX as u64,
+        IndexType::I64 => u64::MAX,
+    };
+
+    validate_limits(table.limits(), range)?;
+    value_type(context, ValueType::Reference(table.kind()))
+}
+
+/// Validates the imported and defined tables of the module.
+/// Tables of non-nullable references must have an initializer,
+/// which may only refer to imported globals.
+///
+/// See <https://webassembly.github.io/function-references/core/valid/modules.html#tables>
+fn validate_tables(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    let tables = module.tables().unwrap_or_default();
+    let imported = context.tables.len() - tables.len();
+    let globals = context.globals.len() - module.globals().unwrap_or_default().len();
+
+    for (index, table) in context.tables.iter().enumerate() {
+        validate_table(context, table)?;
+
+        let initializer = index
+            .checked_sub(imported)
+            .and_then(|offset| tables[offset].initializer());
+
+        match initializer {
+            Some(initializer) => validate_constant(
+                context,
+                initializer,
+                ValueType::Reference(table.kind()),
+                globals,
+                Location::Table(index as u32),
+            )?,
+            None if !table.kind().is_nullable() => {
+                return Err(ValidationError::NonDefaultableTable(index as u32))
+            }
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a memory type.
+///
+/// See <https://webassembly.github.io/threads/core/valid/types.html#memory-types>
+fn validate_memory(memory: &MemoryType) -> Result<(), ValidationError> {
+    let range = match memory.index_type() {
+        IndexType::I32 => MAXIMUM_PAGES_32,
+        IndexType::I64 => MAXIMUM_PAGES_64,
+    };
+
+    validate_limits(memory.limits(), range)?;
+
+    if memory.is_shared() && memory.limits().max().is_none() {
+        return Err(ValidationError::UnboundedSharedMemory);
+    }
+
+    Ok(())
+}
+
+/// Validates the type of a tag, which must be a function type without results.
+///
+/// See <https://webassembly.github.io/exception-handling/core/valid/types.html#tag-types>
+fn validate_tag(context: &Context, kind: TypeIndex) -> Result<(), ValidationError> {
+    if function_type(context, kind)?.results().is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidTagType(kind))
+    }
+}
+
+/// Validates the descriptions of the imports.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#imports>
+fn validate_imports(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    for import in module.imports().unwrap_or_default() {
+        match import.description() {
+            ImportDescription::Function(kind) => {
+                function_type(context, *kind)?;
+            }
+            ImportDescription::Table(kind) => validate_table(context, kind)?,
+            ImportDescription::Memory(kind) => validate_memory(kind)?,
+            ImportDescription::Global(kind) => value_type(context, kind.kind())?,
+            ImportDescription::Tag(kind) => validate_tag(context, kind.kind())?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a constant expression that produces a value of the given type,
+/// in which the given number of leading globals may be referenced.
+fn validate_constant(
+    context: &Context,
+    expression: &Expression,
+    kind: ValueType,
+    globals: usize,
+    location: Location,
+) -> Result<(), ValidationError> {
+    ExpressionValidator::constant(context, globals)
+        .validate(expression.instructions(), &[kind])
+        .map_err(|(offset, error)| ValidationError::InvalidInstruction(location, offset, error))
+}
+
+/// Validates the globals defined by the module.
+/// The initializer of a global may refer to the globals that precede it.
+///
+/// See <https://webassembly.github.io/gc/core/valid/modules.html#globals>
+fn validate_globals(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    let imported = context.globals.len() - module.globals().unwrap_or_default().len();
+
+    for (offset, global) in module.globals().unwrap_or_default().iter().enumerate() {
+        let index = imported + offset;
+        let kind: &GlobalType = global.kind();
+
+        value_type(context, kind.kind())?;
+        validate_constant(
+            context,
+            global.initializer(),
+            kind.kind(),
+            index,
+            Location::Global(index as u32),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Validates that the exports refer to defined components and have distinct names.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#exports>
+fn validate_exports(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    let mut names = HashSet::new();
+
+    for export in module.exports().unwrap_or_default() {
+        let (space, index, length) = match export.description() {
+            ExportDescription::Function(index) => {
+                (Space::Function, *index, context.functions.len())
+            }
+            ExportDescription::Table(index) => (Space::Table, *index, context.tables.len()),
+            ExportDescription::Memory(index) => (Space::Memory, *index, context.memories.len()),
+            ExportDescription::Global(index) => (Space::Global, *index, context.globals.len()),
+            ExportDescription::Tag(index) => (Space::Tag, *index, context.tags.len()),
+        };
+
+        if index as usize >= length {
+            return Err(ValidationError::UnknownIndex(space, index));
+        }
+
+        if !names.insert(export.name().as_str()) {
+            return Err(ValidationError::DuplicateExport(
+                export.name().as_str().to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates the element segments.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#element-segments>
+fn validate_elements(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    let globals = context.globals.len();
+
+    for (index, element) in module.elements().unwrap_or_default().iter().enumerate() {
+        let index = index as u32;
+        let kind = ValueType::Reference(element.kind());
+
+        value_type(context, kind)?;
+
+        for (item, initializer) in element.initializers().iter().enumerate() {
+            validate_constant(
+                context,
+                initializer,
+                kind,
+                globals,
+                Location::ElementInitializer(index, item),
+            )?;
+        }
+
+        if let ElementMode::Active(table, offset) = element.mode() {
+            let table = context
+                .tables
+                .get(*table as usize)
+                .ok_or(ValidationError::UnknownIndex(Space::Table, *table))?;
+
+            validate_constant(
+                context,
+                offset,
+                table.limits().index_type().into(),
+                globals,
+                Location::ElementOffset(index),
+            )?;
+
+            if !context
+                .types
+                .reference_subtype(element.kind(), table.kind())
+            {
+                return Err(ValidationError::ElementTypeMismatch(index));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates the data segments and the data count.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#data-segments>
+fn validate_data(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    for (index, data) in module.data().unwrap_or_default().iter().enumerate() {
+        if let DataMode::Active(memory, offset) = data.mode() {
+            let memory = context
+                .memories
+                .get(*memory as usize)
+                .ok_or(ValidationError::UnknownIndex(Space::Memory, *memory))?;
+
+            validate_constant(
+                context,
+                offset,
+                memory.index_type().into(),
+                context.globals.len(),
+                Location::DataOffset(index as u32),
+            )?;
+        }
+    }
+
+    match context.data_count {
+        Some(count) if count as usize != context.data => {
+            Err(ValidationError::DataCountMismatch(count, context.data))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates the locals and bodies of the functions defined by the module.
+///
+/// See <https://webassembly.github.io/spec/core/valid/modules.html#functions>
+fn validate_functions(module: &Module, context: &Context) -> Result<(), ValidationError> {
+    let imported = context.functions.len() - function_count(module);
+
+    for (offset, function) in module.functions().unwrap_or_default().iter().enumerate() {
+        let index = (imported + offset) as u32;
+        let kind = function_type(context, function.kind())?;
+
+        for local in function.locals().kinds() {
+            value_type(context, *local)?;
+        }
+
+        #[cfg(feature = "parser")]
+        let body = function
+            .body
+            .decode()
+            .map_err(ValidationError::InvalidBinary)?;
+        #[cfg(not(feature = "parser"))]
+        let body = function.body();
+
+        ExpressionValidator::function(context, kind, function.locals().kinds())
+            .validate(body.instructions(), kind.results().kinds())
+            .map_err(|(offset, error)| {
+                ValidationError::InvalidInstruction(Location::Function(index), offset, error)
+            })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,321 @@
+//! Equivalence and subtyping of the types defined by a module.
+//!
+//! See <https://webassembly.github.io/gc/core/valid/matching.html>
+
+use crate::model::{
+    ArrayType, CompositeType, FieldType, FunctionType, HeapType, Mutability, RecursiveType,
+    ReferenceType, ResultType, StorageType, StructType, SubType, TypeIndex, ValueType,
+};
+use std::collections::HashMap;
+
+/// The types defined by a module, along with the canonical representative of each type.
+/// Types are equivalent when their recursive types are structurally identical,
+/// after replacing references to types within the same recursive type with relative indices.
+///
+/// See <https://webassembly.github.io/gc/core/valid/conventions.html#rolling-and-unrolling>
+pub struct Types<'m> {
+    types: Vec<&'m SubType>,
+    canonical: Vec<TypeIndex>,
+}
+
+impl<'m> Types<'m> {
+    /// Collects the sub types of the given recursive types.
+    /// Assumes that all type indices refer to the same or an earlier recursive type.
+    pub fn new(definitions: &'m [RecursiveType]) -> Self {
+        let mut types: Vec<&SubType> = Vec::new();
+        let mut canonical = Vec::new();
+        let mut groups: HashMap<String, TypeIndex> = HashMap::new();
+
+        for definition in definitions {
+            let start = types.len() as TypeIndex;
+            let end = start + definition.len() as TypeIndex;
+            let relative = |index: TypeIndex| {
+                if index >= start {
+                    u32::MAX - (index - start)
+                } else {
+                    canonical.get(index as usize).copied().unwrap_or(index)
+                }
+            };
+            let key: Vec<SubType> = definition
+                .types()
+                .iter()
+                .map(|kind| map_sub_type(kind, &relative))
+                .collect();
+            let representative = *groups.entry(format!("{:?}", key)).or_insert(start);
+
+            types.extend(definition.types());
+            canonical.extend((0..end - start).map(|offset| representative + offset));
+        }
+
+        Types { types, canonical }
+    }
+
+    /// The number of types.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// The sub type with the given index.
+    pub fn get(&self, index: TypeIndex) -> Option<&'m SubType> {
+        self.types.get(index as usize).copied()
+    }
+
+    /// The function type with the given index, if the index refers to a function type.
+    pub fn function(&self, index: TypeIndex) -> Option<&'m FunctionType> {
+        self.get(index).and_then(|kind| kind.kind().function())
+    }
+
+    /// Determines whether the types with the given indices are equivalent.
+    pub fn equivalent(&self, left: TypeIndex, right: TypeIndex) -> bool {
+        left == right || self.canonical.get(left as usize) == self.canonical.get(right as usize)
+    }
+
+    /// Determines whether the type with the given index is a declared subtype of another,
+    /// by following the chain of supertypes.
+    fn declared_subtype(&self, mut index: TypeIndex, supertype: TypeIndex) -> bool {
+        for _ in 0..=self.types.len() {
+            if self.equivalent(index, supertype) {
+                return true;
+            }
+
+            match self.get(index).and_then(|kind| kind.supertypes().first()) {
+                Some(next) => index = *next,
+                None => return false,
+            }
+        }
+
+        false
+    }
+
+    /// The top type of the hierarchy the given heap type belongs to.
+    pub fn top(&self, kind: HeapType) -> HeapType {
+        match kind {
+            HeapType::Function | HeapType::NoFunction => HeapType::Function,
+            HeapType::External | HeapType::NoExternal => HeapType::External,
+            HeapType::Exception | HeapType::NoException => HeapType::Exception,
+            HeapType::Index(index) => match self.get(index).map(SubType::kind) {
+                Some(CompositeType::Function(_)) => HeapType::Function,
+                _ => HeapType::Any,
+            },
+            _ => HeapType::Any,
+        }
+    }
+
+    /// Determines whether one heap type matches (i.e., is a subtype of) another.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/matching.html#heap-types>
+    pub fn heap_subtype(&self, kind: HeapType, supertype: HeapType) -> bool {
+        match (kind, supertype) {
+            (HeapType::Index(index), HeapType::Index(supertype)) => {
+                self.declared_subtype(index, supertype)
+            }
+            (HeapType::Index(index), supertype) => match self.get(index).map(SubType::kind) {
+                Some(CompositeType::Function(_)) => supertype == HeapType::Function,
+                Some(CompositeType::Struct(_)) => matches!(
+                    supertype,
+                    HeapType::Struct | HeapType::Equality | HeapType::Any
+                ),
+                Some(CompositeType::Array(_)) => matches!(
+                    supertype,
+                    HeapType::Array | HeapType::Equality | HeapType::Any
+                ),
+                None => false,
+            },
+            (kind, supertype) if kind == supertype => true,
+            (
+                HeapType::None
+                | HeapType::NoFunction
+                | HeapType::NoExternal
+                | HeapType::NoException,
+                supertype,
+            ) => self.top(kind) == self.top(supertype),
+            (
+                HeapType::I31 | HeapType::Struct | HeapType::Array,
+                HeapType::Equality | HeapType::Any,
+            ) => true,
+            (HeapType::Equality, HeapType::Any) => true,
+            _ => false,
+        }
+    }
+
+    /// Determines whether one reference type matches another.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/matching.html#reference-types>
+    pub fn reference_subtype(&self, kind: ReferenceType, supertype: ReferenceType) -> bool {
+        (!kind.is_nullable() || supertype.is_nullable())
+            && self.heap_subtype(kind.heap_type(), supertype.heap_type())
+    }
+
+    /// Determines whether one value type matches another.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/matching.html#value-types>
+    pub fn subtype(&self, kind: ValueType, supertype: ValueType) -> bool {
+        match (reference(kind), reference(supertype)) {
+            (Some(kind), Some(supertype)) => self.reference_subtype(kind, supertype),
+            (None, None) => kind == supertype,
+            _ => false,
+        }
+    }
+
+    /// Determines whether each type in a sequence matches the corresponding type in another.
+    pub fn subtypes(&self, kinds: &[ValueType], supertypes: &[ValueType]) -> bool {
+        kinds.len() == supertypes.len()
+            && kinds
+                .iter()
+                .zip(supertypes)
+                .all(|(kind, supertype)| self.subtype(*kind, *supertype))
+    }
+
+    /// Determines whether one field type matches another.
+    /// Mutable fields are invariant, while immutable fields are covariant.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/matching.html#field-types>
+    fn field_subtype(&self, field: FieldType, supertype: FieldType) -> bool {
+        let storage = match (field.kind(), supertype.kind()) {
+            (StorageType::Packed(kind), StorageType::Packed(supertype)) => kind == supertype,
+            (StorageType::Value(kind), StorageType::Value(supertype)) => {
+                self.subtype(kind, supertype)
+                    && (field.mutability() == Mutability::Immutable
+                        || self.subtype(supertype, kind))
+            }
+            _ => false,
+        };
+
+        storage && field.mutability() == supertype.mutability()
+    }
+
+    /// Determines whether one composite type matches another.
+    ///
+    /// See <https://webassembly.github.io/gc/core/valid/matching.html#composite-types>
+    pub fn composite_subtype(&self, kind: &CompositeType, supertype: &CompositeType) -> bool {
+        match (kind, supertype) {
+            (CompositeType::Function(kind), CompositeType::Function(supertype)) => {
+                self.subtypes(supertype.parameters().kinds(), kind.parameters().kinds())
+                    && self.subtypes(kind.results().kinds(), supertype.results().kinds())
+            }
+            (CompositeType::Struct(kind), CompositeType::Struct(supertype)) => {
+                kind.fields().len() >= supertype.fields().len()
+                    && kind
+                        .fields()
+                        .iter()
+                        .zip(supertype.fields())
+                        .all(|(field, supertype)| self.field_subtype(*field, *supertype))
+            }
+            (CompositeType::Array(kind), CompositeType::Array(supertype)) => {
+                self.field_subtype(kind.field(), supertype.field())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The reference type of a value type, with shorthands expanded, if it is a reference type.
+pub fn reference(kind: ValueType) -> Option<ReferenceType> {
+    match kind {
+        ValueType::FunctionReference => Some(ReferenceType::Nullable(HeapType::Function)),
+        ValueType::ExternalReference => Some(ReferenceType::Nullable(HeapType::External)),
+        ValueType::ExceptionReference => Some(ReferenceType::Nullable(HeapType::Exception)),
+        ValueType::Reference(kind) if kind.is_nullable() => {
+            Some(ReferenceType::Nullable(kind.heap_type()))
+        }
+        ValueType::Reference(kind) => Some(kind),
+        _ => None,
+    }
+}
+
+/// Determines whether values of the given type have a default value (i.e., zero or null).
+///
+/// See <https://webassembly.github.io/function-references/core/valid/types.html#defaultable-types>
+pub fn is_defaultable(kind: ValueType) -> bool {
+    reference(kind).is_none_or(|kind| kind.is_nullable())
+}
+
+/// The value type used to represent values of the given storage type on the operand stack.
+pub fn unpacked(kind: StorageType) -> ValueType {
+    match kind {
+        StorageType::Value(kind) => kind,
+        StorageType::Packed(_) => ValueType::I32,
+    }
+}
+
+/// Replaces the type indices referenced by a sub type.
+fn map_sub_type(kind: &SubType, map: &impl Fn(TypeIndex) -> TypeIndex) -> SubType {
+    let value = |kind: &ValueType| match reference(*kind) {
+        Some(ReferenceType::Nullable(HeapType::Index(index))) => {
+            ValueType::Reference(ReferenceType::Nullable(HeapType::Index(map(index))))
+        }
+        Some(ReferenceType::NonNullable(HeapType::Index(index))) => {
+            ValueType::Reference(ReferenceType::NonNullable(HeapType::Index(map(index))))
+        }
+        Some(kind) => ValueType::Reference(kind),
+        None => *kind,
+    };
+    let field = |field: FieldType| {
+        let kind = match field.kind() {
+            StorageType::Value(kind) => StorageType::Value(value(&kind)),
+            packed => packed,
+        };
+
+        FieldType::new(kind, field.mutability())
+    };
+    let composite = match kind.kind() {
+        CompositeType::Function(kind) => CompositeType::Function(FunctionType::new(
+            ResultType::new(kind.parameters().kinds().iter().map(value).collect()),
+            ResultType::new(kind.results().kinds().iter().map(value).collect()),
+        )),
+        CompositeType::Struct(kind) => CompositeType::Struct(StructType::new(
+            kind.fields().iter().copied().map(field).collect(),
+        )),
+        CompositeType::Array(kind) => CompositeType::Array(ArrayType::new(field(kind.field()))),
+    };
+
+    SubType::new(
+        kind.is_final(),
+        kind.supertypes().iter().copied().map(map).collect(),
+        composite,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equivalent_recursive_types() {
+        let node = |index| {
+            RecursiveType::from(SubType::from(StructType::new(vec![FieldType::immutable(
+                StorageType::Value(ValueType::Reference(ReferenceType::Nullable(
+                    HeapType::Index(index),
+                ))),
+            )])))
+        };
+        let definitions = vec![node(0), node(1), node(0)];
+        let types = Types::new(&definitions);
+
+        assert!(types.equivalent(0, 1));
+        assert!(!types.equivalent(0, 2));
+        assert!(types.heap_subtype(HeapType::Index(1), HeapType::Index(0)));
+        assert!(types.heap_subtype(HeapType::Index(2), HeapType::Equality));
+        assert!(types.heap_subtype(HeapType::None, HeapType::Index(2)));
+        assert!(!types.heap_subtype(HeapType::NoFunction, HeapType::Index(2)));
+    }
+
+    #[test]
+    fn reference_subtypes() {
+        let types = Types::new(&[]);
+
+        assert!(types.subtype(
+            ValueType::Reference(ReferenceType::NonNullable(HeapType::Function)),
+            ValueType::FunctionReference
+        ));
+        assert!(!types.subtype(
+            ValueType::FunctionReference,
+            ValueType::Reference(ReferenceType::NonNullable(HeapType::Function))
+        ));
+        assert!(types.subtype(
+            ValueType::Reference(ReferenceType::Nullable(HeapType::I31)),
+            ValueType::Reference(ReferenceType::Nullable(HeapType::Any))
+        ));
+        assert!(!types.subtype(ValueType::I32, ValueType::I64));
+    }
+}
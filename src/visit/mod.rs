@@ -0,0 +1,117 @@
+//! Traversals of a WebAssembly syntax tree.
+//! The [`Visit`] trait walks a tree by shared reference, while the [`VisitMut`] trait walks it by mutable reference.
+//! Both traits provide a method for every kind of node in the model whose default implementation
+//! calls the `walk_*` (or `walk_*_mut`) function of the same name to visit the children of the node.
+
+mod mutable;
+mod visitor;
+
+pub use mutable::*;
+pub use visitor::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::*;
+
+    fn module() -> Module {
+        let mut builder = Module::builder();
+
+        builder.add_function_type(FunctionType::runnable()).unwrap();
+        builder
+            .add_import(Import::function("env".into(), "log".into(), 0))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::from(vec![ValueType::I32]),
+                Expression::from(vec![
+                    VariableInstruction::LocalGet(0).into(),
+                    ControlInstruction::If(
+                        BlockType::None,
+                        Expression::from(vec![ControlInstruction::Call(0).into()]),
+                        Some(Expression::from(vec![ControlInstruction::Call(2).into()])),
+                    )
+                    .into(),
+                ]),
+            ))
+            .unwrap();
+        builder
+            .add_function(Function::new(
+                0,
+                ResultType::empty(),
+                Expression::from(vec![
+                    ReferenceInstruction::Function(1).into(),
+                    ParametricInstruction::Drop.into(),
+                ]),
+            ))
+            .unwrap();
+        builder.add_export(Export::function("run".into(), 1));
+        builder.set_start(Some(Start::new(2)));
+
+        builder.build()
+    }
+
+    #[derive(Default)]
+    struct Functions(Vec<FunctionIndex>);
+
+    impl<'ast> Visit<'ast> for Functions {
+        fn visit_function_index(&mut self, index: FunctionIndex) {
+            self.0.push(index);
+        }
+    }
+
+    struct Swap;
+
+    impl VisitMut for Swap {
+        fn visit_function_index_mut(&mut self, index: &mut FunctionIndex) {
+            *index = match *index {
+                1 => 2,
+                2 => 1,
+                index => index,
+            };
+        }
+    }
+
+    #[test]
+    fn visit_function_indices() {
+        let mut functions = Functions::default();
+
+        functions.visit_module(&module());
+
+        assert_eq!(functions.0, vec![0, 2, 1, 1, 2]);
+    }
+
+    #[test]
+    fn visit_function_indices_mut() {
+        let mut module = module();
+        let mut functions = Functions::default();
+
+        Swap.visit_module_mut(&mut module);
+        functions.visit_module(&module);
+
+        assert_eq!(functions.0, vec![0, 1, 2, 2, 1]);
+        assert_eq!(module.start(), Some(&Start::new(1)));
+    }
+
+    #[test]
+    fn visit_nested_expressions() {
+        struct Depth(usize, usize);
+
+        impl<'ast> Visit<'ast> for Depth {
+            fn visit_expression(&mut self, node: &'ast Expression) {
+                self.0 += 1;
+                self.1 = self.1.max(self.0);
+                walk_expression(self, node);
+                self.0 -= 1;
+            }
+        }
+
+        let mut depth = Depth(0, 0);
+
+        depth.visit_module(&module());
+
+        assert_eq!(depth.0, 0);
+        assert_eq!(depth.1, 2);
+    }
+}
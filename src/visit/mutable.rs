@@ -0,0 +1,839 @@
+//! Traversal of a syntax tree by mutable reference.
+
+use crate::model::{
+    AggregateInstruction, ArrayType, AtomicInstruction, BlockType, CatchClause, CompositeType,
+    ControlInstruction, Data, DataIndex, DataMode, Element, ElementIndex, ElementMode, Export,
+    ExportDescription, Expression, FieldIndex, FieldType, Function, FunctionIndex, FunctionType,
+    Global, GlobalIndex, GlobalType, HeapType, Import, ImportDescription, Instruction, LabelIndex,
+    Limit, LocalIndex, Memory, MemoryArgument, MemoryIndex, MemoryInstruction, MemoryType, Module,
+    NumericInstruction, ParametricInstruction, RecursiveType, ReferenceInstruction, ReferenceType,
+    ResultType, Start, StorageType, StructType, SubType, Table, TableIndex, TableInstruction,
+    TableType, Tag, TagIndex, TagType, TypeIndex, ValueType, VariableInstruction,
+    VectorInstruction,
+};
+
+/// A traversal of a syntax tree by mutable reference.
+/// Each method visits a single kind of node and, by default,
+/// calls the corresponding `walk_*_mut` function to visit the children of the node.
+/// Implementations override the methods for the nodes they are interested in,
+/// and call the `walk_*_mut` function to continue the traversal into the children of the node.
+///
+/// Indices are visited by mutable reference, with a separate method for each index space,
+/// which allows a traversal to rewrite the references of a module in place.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::visit::VisitMut;
+/// use wasm_ast::{BlockType, ControlInstruction, Expression, FunctionIndex};
+///
+/// struct Shift(FunctionIndex);
+///
+/// impl VisitMut for Shift {
+///     fn visit_function_index_mut(&mut self, index: &mut FunctionIndex) {
+///         *index += self.0;
+///     }
+/// }
+///
+/// let mut expression = Expression::from(vec![
+///     ControlInstruction::Call(0).into(),
+///     ControlInstruction::Block(
+///         BlockType::None,
+///         Expression::from(vec![ControlInstruction::Call(1).into()]),
+///     )
+///     .into(),
+/// ]);
+///
+/// Shift(2).visit_expression_mut(&mut expression);
+///
+/// assert_eq!(
+///     expression,
+///     Expression::from(vec![
+///         ControlInstruction::Call(2).into(),
+///         ControlInstruction::Block(
+///             BlockType::None,
+///             Expression::from(vec![ControlInstruction::Call(3).into()]),
+///         )
+///         .into(),
+///     ])
+/// );
+/// ```
+pub trait VisitMut {
+    /// Visits a module.
+    fn visit_module_mut(&mut self, node: &mut Module) {
+        walk_module_mut(self, node)
+    }
+
+    /// Visits a recursive type.
+    fn visit_recursive_type_mut(&mut self, node: &mut RecursiveType) {
+        walk_recursive_type_mut(self, node)
+    }
+
+    /// Visits a sub type.
+    fn visit_sub_type_mut(&mut self, node: &mut SubType) {
+        walk_sub_type_mut(self, node)
+    }
+
+    /// Visits a composite type.
+    fn visit_composite_type_mut(&mut self, node: &mut CompositeType) {
+        walk_composite_type_mut(self, node)
+    }
+
+    /// Visits a function type.
+    fn visit_function_type_mut(&mut self, node: &mut FunctionType) {
+        walk_function_type_mut(self, node)
+    }
+
+    /// Visits a structure type.
+    fn visit_struct_type_mut(&mut self, node: &mut StructType) {
+        walk_struct_type_mut(self, node)
+    }
+
+    /// Visits an array type.
+    fn visit_array_type_mut(&mut self, node: &mut ArrayType) {
+        walk_array_type_mut(self, node)
+    }
+
+    /// Visits a field type.
+    fn visit_field_type_mut(&mut self, node: &mut FieldType) {
+        walk_field_type_mut(self, node)
+    }
+
+    /// Visits a result type (i.e., the parameters, results or locals of a function).
+    fn visit_result_type_mut(&mut self, node: &mut ResultType) {
+        walk_result_type_mut(self, node)
+    }
+
+    /// Visits a value type.
+    fn visit_value_type_mut(&mut self, node: &mut ValueType) {
+        walk_value_type_mut(self, node)
+    }
+
+    /// Visits a reference type.
+    fn visit_reference_type_mut(&mut self, node: &mut ReferenceType) {
+        walk_reference_type_mut(self, node)
+    }
+
+    /// Visits a heap type.
+    fn visit_heap_type_mut(&mut self, node: &mut HeapType) {
+        walk_heap_type_mut(self, node)
+    }
+
+    /// Visits the limits of a table or memory type.
+    fn visit_limit_mut(&mut self, _node: &mut Limit) {}
+
+    /// Visits a table type.
+    fn visit_table_type_mut(&mut self, node: &mut TableType) {
+        walk_table_type_mut(self, node)
+    }
+
+    /// Visits a memory type.
+    fn visit_memory_type_mut(&mut self, node: &mut MemoryType) {
+        walk_memory_type_mut(self, node)
+    }
+
+    /// Visits a global type.
+    fn visit_global_type_mut(&mut self, node: &mut GlobalType) {
+        walk_global_type_mut(self, node)
+    }
+
+    /// Visits a tag type.
+    fn visit_tag_type_mut(&mut self, node: &mut TagType) {
+        walk_tag_type_mut(self, node)
+    }
+
+    /// Visits an import.
+    fn visit_import_mut(&mut self, node: &mut Import) {
+        walk_import_mut(self, node)
+    }
+
+    /// Visits a function.
+    fn visit_function_mut(&mut self, node: &mut Function) {
+        walk_function_mut(self, node)
+    }
+
+    /// Visits a table.
+    fn visit_table_mut(&mut self, node: &mut Table) {
+        walk_table_mut(self, node)
+    }
+
+    /// Visits a memory.
+    fn visit_memory_mut(&mut self, node: &mut Memory) {
+        walk_memory_mut(self, node)
+    }
+
+    /// Visits a tag.
+    fn visit_tag_mut(&mut self, node: &mut Tag) {
+        walk_tag_mut(self, node)
+    }
+
+    /// Visits a global.
+    fn visit_global_mut(&mut self, node: &mut Global) {
+        walk_global_mut(self, node)
+    }
+
+    /// Visits an export.
+    fn visit_export_mut(&mut self, node: &mut Export) {
+        walk_export_mut(self, node)
+    }
+
+    /// Visits the start function.
+    fn visit_start_mut(&mut self, node: &mut Start) {
+        walk_start_mut(self, node)
+    }
+
+    /// Visits an element segment.
+    fn visit_element_mut(&mut self, node: &mut Element) {
+        walk_element_mut(self, node)
+    }
+
+    /// Visits a data segment.
+    fn visit_data_mut(&mut self, node: &mut Data) {
+        walk_data_mut(self, node)
+    }
+
+    /// Visits an expression (e.g., a function body, an initializer or the body of a block).
+    fn visit_expression_mut(&mut self, node: &mut Expression) {
+        walk_expression_mut(self, node)
+    }
+
+    /// Visits an instruction.
+    fn visit_instruction_mut(&mut self, node: &mut Instruction) {
+        walk_instruction_mut(self, node)
+    }
+
+    /// Visits a numeric instruction.
+    fn visit_numeric_instruction_mut(&mut self, _node: &mut NumericInstruction) {}
+
+    /// Visits a vector instruction.
+    fn visit_vector_instruction_mut(&mut self, node: &mut VectorInstruction) {
+        walk_vector_instruction_mut(self, node)
+    }
+
+    /// Visits a reference instruction.
+    fn visit_reference_instruction_mut(&mut self, node: &mut ReferenceInstruction) {
+        walk_reference_instruction_mut(self, node)
+    }
+
+    /// Visits a parametric instruction.
+    fn visit_parametric_instruction_mut(&mut self, node: &mut ParametricInstruction) {
+        walk_parametric_instruction_mut(self, node)
+    }
+
+    /// Visits a variable instruction.
+    fn visit_variable_instruction_mut(&mut self, node: &mut VariableInstruction) {
+        walk_variable_instruction_mut(self, node)
+    }
+
+    /// Visits a table instruction.
+    fn visit_table_instruction_mut(&mut self, node: &mut TableInstruction) {
+        walk_table_instruction_mut(self, node)
+    }
+
+    /// Visits a memory instruction.
+    fn visit_memory_instruction_mut(&mut self, node: &mut MemoryInstruction) {
+        walk_memory_instruction_mut(self, node)
+    }
+
+    /// Visits an atomic memory instruction.
+    fn visit_atomic_instruction_mut(&mut self, node: &mut AtomicInstruction) {
+        walk_atomic_instruction_mut(self, node)
+    }
+
+    /// Visits an aggregate (i.e., structure, array or i31) instruction.
+    fn visit_aggregate_instruction_mut(&mut self, node: &mut AggregateInstruction) {
+        walk_aggregate_instruction_mut(self, node)
+    }
+
+    /// Visits a control instruction.
+    fn visit_control_instruction_mut(&mut self, node: &mut ControlInstruction) {
+        walk_control_instruction_mut(self, node)
+    }
+
+    /// Visits the block type of a structured control instruction.
+    fn visit_block_type_mut(&mut self, node: &mut BlockType) {
+        walk_block_type_mut(self, node)
+    }
+
+    /// Visits a catch clause of a try table instruction.
+    fn visit_catch_clause_mut(&mut self, node: &mut CatchClause) {
+        walk_catch_clause_mut(self, node)
+    }
+
+    /// Visits the memory argument of a load or store instruction.
+    fn visit_memory_argument_mut(&mut self, node: &mut MemoryArgument) {
+        walk_memory_argument_mut(self, node)
+    }
+
+    /// Visits a reference to a type.
+    fn visit_type_index_mut(&mut self, _index: &mut TypeIndex) {}
+
+    /// Visits a reference to a function.
+    fn visit_function_index_mut(&mut self, _index: &mut FunctionIndex) {}
+
+    /// Visits a reference to a table.
+    fn visit_table_index_mut(&mut self, _index: &mut TableIndex) {}
+
+    /// Visits a reference to a memory.
+    fn visit_memory_index_mut(&mut self, _index: &mut MemoryIndex) {}
+
+    /// Visits a reference to a global.
+    fn visit_global_index_mut(&mut self, _index: &mut GlobalIndex) {}
+
+    /// Visits a reference to an element segment.
+    fn visit_element_index_mut(&mut self, _index: &mut ElementIndex) {}
+
+    /// Visits a reference to a data segment.
+    fn visit_data_index_mut(&mut self, _index: &mut DataIndex) {}
+
+    /// Visits a reference to a local.
+    fn visit_local_index_mut(&mut self, _index: &mut LocalIndex) {}
+
+    /// Visits a reference to a label.
+    fn visit_label_index_mut(&mut self, _index: &mut LabelIndex) {}
+
+    /// Visits a reference to a tag.
+    fn visit_tag_index_mut(&mut self, _index: &mut TagIndex) {}
+
+    /// Visits a reference to a field of a structure type.
+    fn visit_field_index_mut(&mut self, _index: &mut FieldIndex) {}
+}
+
+/// Visits the components of a module in the order of their index spaces:
+/// types, imports, functions, tables, memories, tags, globals, exports,
+/// the start function, element segments and data segments.
+pub fn walk_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Module) {
+    for kind in node.types.iter_mut().flatten() {
+        visitor.visit_recursive_type_mut(kind);
+    }
+
+    for import in node.imports.iter_mut().flatten() {
+        visitor.visit_import_mut(import);
+    }
+
+    for function in node.functions.iter_mut().flatten() {
+        visitor.visit_function_mut(function);
+    }
+
+    for table in node.tables.iter_mut().flatten() {
+        visitor.visit_table_mut(table);
+    }
+
+    for memory in node.memories.iter_mut().flatten() {
+        visitor.visit_memory_mut(memory);
+    }
+
+    for tag in node.tags.iter_mut().flatten() {
+        visitor.visit_tag_mut(tag);
+    }
+
+    for global in node.globals.iter_mut().flatten() {
+        visitor.visit_global_mut(global);
+    }
+
+    for export in node.exports.iter_mut().flatten() {
+        visitor.visit_export_mut(export);
+    }
+
+    if let Some(start) = &mut node.start {
+        visitor.visit_start_mut(start);
+    }
+
+    for element in node.elements.iter_mut().flatten() {
+        visitor.visit_element_mut(element);
+    }
+
+    for data in node.data.iter_mut().flatten() {
+        visitor.visit_data_mut(data);
+    }
+}
+
+/// Visits the sub types of a recursive type.
+pub fn walk_recursive_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut RecursiveType) {
+    for kind in &mut node.types {
+        visitor.visit_sub_type_mut(kind);
+    }
+}
+
+/// Visits the supertypes and composite type of a sub type.
+pub fn walk_sub_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut SubType) {
+    for supertype in &mut node.supertypes {
+        visitor.visit_type_index_mut(supertype);
+    }
+
+    visitor.visit_composite_type_mut(&mut node.kind);
+}
+
+/// Visits the function, structure or array type of a composite type.
+pub fn walk_composite_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut CompositeType) {
+    match node {
+        CompositeType::Function(kind) => visitor.visit_function_type_mut(kind),
+        CompositeType::Struct(kind) => visitor.visit_struct_type_mut(kind),
+        CompositeType::Array(kind) => visitor.visit_array_type_mut(kind),
+    }
+}
+
+/// Visits the parameters and results of a function type.
+pub fn walk_function_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut FunctionType) {
+    visitor.visit_result_type_mut(&mut node.parameters);
+    visitor.visit_result_type_mut(&mut node.results);
+}
+
+/// Visits the fields of a structure type.
+pub fn walk_struct_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut StructType) {
+    for field in &mut node.fields {
+        visitor.visit_field_type_mut(field);
+    }
+}
+
+/// Visits the field of an array type.
+pub fn walk_array_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut ArrayType) {
+    visitor.visit_field_type_mut(&mut node.field);
+}
+
+/// Visits the value type of a field, unless the field is packed.
+pub fn walk_field_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut FieldType) {
+    if let StorageType::Value(kind) = &mut node.kind {
+        visitor.visit_value_type_mut(kind);
+    }
+}
+
+/// Visits the value types of a result type.
+pub fn walk_result_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut ResultType) {
+    for kind in &mut node.kinds {
+        visitor.visit_value_type_mut(kind);
+    }
+}
+
+/// Visits the reference type of a value type, if any.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn walk_value_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut ValueType) {
+    if let ValueType::Reference(kind) = node {
+        visitor.visit_reference_type_mut(kind);
+    }
+}
+
+/// Visits the heap type of a reference type.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn walk_reference_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut ReferenceType) {
+    if let ReferenceType::Nullable(kind) | ReferenceType::NonNullable(kind) = node {
+        visitor.visit_heap_type_mut(kind);
+    }
+}
+
+/// Visits the type index of a concrete heap type.
+pub fn walk_heap_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut HeapType) {
+    if let HeapType::Index(index) = node {
+        visitor.visit_type_index_mut(index);
+    }
+}
+
+/// Visits the limits and element type of a table type.
+pub fn walk_table_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TableType) {
+    visitor.visit_limit_mut(&mut node.limits);
+    visitor.visit_reference_type_mut(&mut node.kind);
+}
+
+/// Visits the limits of a memory type.
+pub fn walk_memory_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut MemoryType) {
+    visitor.visit_limit_mut(&mut node.limits);
+}
+
+/// Visits the value type of a global type.
+pub fn walk_global_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut GlobalType) {
+    visitor.visit_value_type_mut(&mut node.kind);
+}
+
+/// Visits the function type index of a tag type.
+pub fn walk_tag_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TagType) {
+    visitor.visit_type_index_mut(&mut node.kind);
+}
+
+/// Visits the type of the definition required by an import.
+pub fn walk_import_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Import) {
+    match &mut node.description {
+        ImportDescription::Function(kind) => visitor.visit_type_index_mut(kind),
+        ImportDescription::Table(kind) => visitor.visit_table_type_mut(kind),
+        ImportDescription::Memory(kind) => visitor.visit_memory_type_mut(kind),
+        ImportDescription::Global(kind) => visitor.visit_global_type_mut(kind),
+        ImportDescription::Tag(kind) => visitor.visit_tag_type_mut(kind),
+    }
+}
+
+/// Visits the type, locals and body of a function.
+pub fn walk_function_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Function) {
+    visitor.visit_type_index_mut(&mut node.kind);
+    visitor.visit_result_type_mut(&mut node.locals);
+    visitor.visit_expression_mut(node.body.expression_mut());
+}
+
+/// Visits the table type and initializer of a table.
+pub fn walk_table_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Table) {
+    visitor.visit_table_type_mut(&mut node.kind);
+
+    if let Some(initializer) = &mut node.initializer {
+        visitor.visit_expression_mut(initializer);
+    }
+}
+
+/// Visits the memory type of a memory.
+pub fn walk_memory_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Memory) {
+    visitor.visit_memory_type_mut(&mut node.kind);
+}
+
+/// Visits the tag type of a tag.
+pub fn walk_tag_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Tag) {
+    visitor.visit_tag_type_mut(&mut node.kind);
+}
+
+/// Visits the type and initializer of a global.
+pub fn walk_global_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Global) {
+    visitor.visit_global_type_mut(&mut node.kind);
+    visitor.visit_expression_mut(&mut node.initializer);
+}
+
+/// Visits the index of the exported definition.
+pub fn walk_export_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Export) {
+    match &mut node.description {
+        ExportDescription::Function(index) => visitor.visit_function_index_mut(index),
+        ExportDescription::Table(index) => visitor.visit_table_index_mut(index),
+        ExportDescription::Memory(index) => visitor.visit_memory_index_mut(index),
+        ExportDescription::Global(index) => visitor.visit_global_index_mut(index),
+        ExportDescription::Tag(index) => visitor.visit_tag_index_mut(index),
+    }
+}
+
+/// Visits the index of the start function.
+pub fn walk_start_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Start) {
+    visitor.visit_function_index_mut(&mut node.function);
+}
+
+/// Visits the element type, mode and initializers of an element segment.
+pub fn walk_element_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Element) {
+    visitor.visit_reference_type_mut(&mut node.kind);
+
+    if let ElementMode::Active(table, offset) = &mut node.mode {
+        visitor.visit_table_index_mut(table);
+        visitor.visit_expression_mut(offset);
+    }
+
+    for initializer in &mut node.initializers {
+        visitor.visit_expression_mut(initializer);
+    }
+}
+
+/// Visits the mode of a data segment.
+pub fn walk_data_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Data) {
+    if let DataMode::Active(memory, offset) = &mut node.mode {
+        visitor.visit_memory_index_mut(memory);
+        visitor.visit_expression_mut(offset);
+    }
+}
+
+/// Visits the instructions of an expression.
+pub fn walk_expression_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Expression) {
+    for instruction in &mut node.instructions {
+        visitor.visit_instruction_mut(instruction);
+    }
+}
+
+/// Visits the instruction according to its family.
+pub fn walk_instruction_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Instruction) {
+    match node {
+        Instruction::Numeric(instruction) => visitor.visit_numeric_instruction_mut(instruction),
+        Instruction::Vector(instruction) => visitor.visit_vector_instruction_mut(instruction),
+        Instruction::Reference(instruction) => visitor.visit_reference_instruction_mut(instruction),
+        Instruction::Parametric(instruction) => {
+            visitor.visit_parametric_instruction_mut(instruction)
+        }
+        Instruction::Variable(instruction) => visitor.visit_variable_instruction_mut(instruction),
+        Instruction::Table(instruction) => visitor.visit_table_instruction_mut(instruction),
+        Instruction::Memory(instruction) => visitor.visit_memory_instruction_mut(instruction),
+        Instruction::Atomic(instruction) => visitor.visit_atomic_instruction_mut(instruction),
+        Instruction::Aggregate(instruction) => visitor.visit_aggregate_instruction_mut(instruction),
+        Instruction::Control(instruction) => visitor.visit_control_instruction_mut(instruction),
+    }
+}
+
+/// Visits the memory argument of a vector load or store instruction.
+pub fn walk_vector_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut VectorInstruction,
+) {
+    match node {
+        VectorInstruction::Load(argument)
+        | VectorInstruction::Store(argument)
+        | VectorInstruction::Load8x8(_, argument)
+        | VectorInstruction::Load16x4(_, argument)
+        | VectorInstruction::Load32x2(_, argument)
+        | VectorInstruction::Load8Splat(argument)
+        | VectorInstruction::Load16Splat(argument)
+        | VectorInstruction::Load32Splat(argument)
+        | VectorInstruction::Load64Splat(argument)
+        | VectorInstruction::Load32Zero(argument)
+        | VectorInstruction::Load64Zero(argument)
+        | VectorInstruction::Load8Lane(argument, _)
+        | VectorInstruction::Load16Lane(argument, _)
+        | VectorInstruction::Load32Lane(argument, _)
+        | VectorInstruction::Load64Lane(argument, _)
+        | VectorInstruction::Store8Lane(argument, _)
+        | VectorInstruction::Store16Lane(argument, _)
+        | VectorInstruction::Store32Lane(argument, _)
+        | VectorInstruction::Store64Lane(argument, _) => {
+            visitor.visit_memory_argument_mut(argument)
+        }
+        _ => {}
+    }
+}
+
+/// Visits the types and function index of a reference instruction.
+pub fn walk_reference_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ReferenceInstruction,
+) {
+    match node {
+        ReferenceInstruction::Null(kind) => visitor.visit_heap_type_mut(kind),
+        ReferenceInstruction::Function(index) => visitor.visit_function_index_mut(index),
+        ReferenceInstruction::Test(kind) | ReferenceInstruction::Cast(kind) => {
+            visitor.visit_reference_type_mut(kind)
+        }
+        ReferenceInstruction::IsNull
+        | ReferenceInstruction::AsNonNull
+        | ReferenceInstruction::Equal => {}
+    }
+}
+
+/// Visits the value types of a typed select instruction.
+pub fn walk_parametric_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ParametricInstruction,
+) {
+    if let ParametricInstruction::Select(Some(kinds)) = node {
+        for kind in kinds {
+            visitor.visit_value_type_mut(kind);
+        }
+    }
+}
+
+/// Visits the local or global index of a variable instruction.
+pub fn walk_variable_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut VariableInstruction,
+) {
+    match node {
+        VariableInstruction::LocalGet(index)
+        | VariableInstruction::LocalSet(index)
+        | VariableInstruction::LocalTee(index) => visitor.visit_local_index_mut(index),
+        VariableInstruction::GlobalGet(index) | VariableInstruction::GlobalSet(index) => {
+            visitor.visit_global_index_mut(index)
+        }
+    }
+}
+
+/// Visits the table and element indices of a table instruction.
+pub fn walk_table_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut TableInstruction,
+) {
+    match node {
+        TableInstruction::Get(table)
+        | TableInstruction::Set(table)
+        | TableInstruction::Size(table)
+        | TableInstruction::Grow(table)
+        | TableInstruction::Fill(table) => visitor.visit_table_index_mut(table),
+        TableInstruction::Copy(destination, source) => {
+            visitor.visit_table_index_mut(destination);
+            visitor.visit_table_index_mut(source);
+        }
+        TableInstruction::Init(element, table) => {
+            visitor.visit_element_index_mut(element);
+            visitor.visit_table_index_mut(table);
+        }
+        TableInstruction::ElementDrop(element) => visitor.visit_element_index_mut(element),
+    }
+}
+
+/// Visits the memory arguments, memory and data indices of a memory instruction.
+pub fn walk_memory_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut MemoryInstruction,
+) {
+    match node {
+        MemoryInstruction::Load(_, argument)
+        | MemoryInstruction::Store(_, argument)
+        | MemoryInstruction::Load8(_, _, argument)
+        | MemoryInstruction::Load16(_, _, argument)
+        | MemoryInstruction::Load32(_, argument)
+        | MemoryInstruction::Store8(_, argument)
+        | MemoryInstruction::Store16(_, argument)
+        | MemoryInstruction::Store32(argument) => visitor.visit_memory_argument_mut(argument),
+        MemoryInstruction::Size(memory)
+        | MemoryInstruction::Grow(memory)
+        | MemoryInstruction::Fill(memory) => visitor.visit_memory_index_mut(memory),
+        MemoryInstruction::Copy(destination, source) => {
+            visitor.visit_memory_index_mut(destination);
+            visitor.visit_memory_index_mut(source);
+        }
+        MemoryInstruction::Init(data, memory) => {
+            visitor.visit_data_index_mut(data);
+            visitor.visit_memory_index_mut(memory);
+        }
+        MemoryInstruction::DataDrop(data) => visitor.visit_data_index_mut(data),
+    }
+}
+
+/// Visits the memory argument of an atomic memory instruction.
+pub fn walk_atomic_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut AtomicInstruction,
+) {
+    match node {
+        AtomicInstruction::Notify(argument)
+        | AtomicInstruction::Wait(_, argument)
+        | AtomicInstruction::Load(_, argument)
+        | AtomicInstruction::Load8(_, argument)
+        | AtomicInstruction::Load16(_, argument)
+        | AtomicInstruction::Load32(argument)
+        | AtomicInstruction::Store(_, argument)
+        | AtomicInstruction::Store8(_, argument)
+        | AtomicInstruction::Store16(_, argument)
+        | AtomicInstruction::Store32(argument)
+        | AtomicInstruction::ReadModifyWrite(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite8(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite16(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite32(_, argument) => {
+            visitor.visit_memory_argument_mut(argument)
+        }
+        AtomicInstruction::Fence => {}
+    }
+}
+
+/// Visits the type, field, data and element indices of an aggregate instruction.
+pub fn walk_aggregate_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut AggregateInstruction,
+) {
+    match node {
+        AggregateInstruction::StructNew(kind)
+        | AggregateInstruction::StructNewDefault(kind)
+        | AggregateInstruction::ArrayNew(kind)
+        | AggregateInstruction::ArrayNewDefault(kind)
+        | AggregateInstruction::ArrayNewFixed(kind, _)
+        | AggregateInstruction::ArrayGet(_, kind)
+        | AggregateInstruction::ArraySet(kind)
+        | AggregateInstruction::ArrayFill(kind) => visitor.visit_type_index_mut(kind),
+        AggregateInstruction::StructGet(_, kind, field)
+        | AggregateInstruction::StructSet(kind, field) => {
+            visitor.visit_type_index_mut(kind);
+            visitor.visit_field_index_mut(field);
+        }
+        AggregateInstruction::ArrayNewData(kind, data)
+        | AggregateInstruction::ArrayInitData(kind, data) => {
+            visitor.visit_type_index_mut(kind);
+            visitor.visit_data_index_mut(data);
+        }
+        AggregateInstruction::ArrayNewElement(kind, element)
+        | AggregateInstruction::ArrayInitElement(kind, element) => {
+            visitor.visit_type_index_mut(kind);
+            visitor.visit_element_index_mut(element);
+        }
+        AggregateInstruction::ArrayCopy(destination, source) => {
+            visitor.visit_type_index_mut(destination);
+            visitor.visit_type_index_mut(source);
+        }
+        AggregateInstruction::ArrayLength
+        | AggregateInstruction::ReferenceI31
+        | AggregateInstruction::I31Get(_)
+        | AggregateInstruction::AnyConvertExternal
+        | AggregateInstruction::ExternalConvertAny => {}
+    }
+}
+
+/// Visits the block types, nested expressions, labels and indices of a control instruction.
+pub fn walk_control_instruction_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ControlInstruction,
+) {
+    match node {
+        ControlInstruction::Block(kind, body) | ControlInstruction::Loop(kind, body) => {
+            visitor.visit_block_type_mut(kind);
+            visitor.visit_expression_mut(body);
+        }
+        ControlInstruction::If(kind, consequent, alternative) => {
+            visitor.visit_block_type_mut(kind);
+            visitor.visit_expression_mut(consequent);
+
+            if let Some(alternative) = alternative {
+                visitor.visit_expression_mut(alternative);
+            }
+        }
+        ControlInstruction::Branch(label)
+        | ControlInstruction::BranchIf(label)
+        | ControlInstruction::BranchOnNull(label)
+        | ControlInstruction::BranchOnNonNull(label) => visitor.visit_label_index_mut(label),
+        ControlInstruction::BranchTable(labels, default) => {
+            for label in labels {
+                visitor.visit_label_index_mut(label);
+            }
+
+            visitor.visit_label_index_mut(default);
+        }
+        ControlInstruction::Call(function) | ControlInstruction::ReturnCall(function) => {
+            visitor.visit_function_index_mut(function)
+        }
+        ControlInstruction::CallIndirect(kind, table)
+        | ControlInstruction::ReturnCallIndirect(kind, table) => {
+            visitor.visit_type_index_mut(kind);
+            visitor.visit_table_index_mut(table);
+        }
+        ControlInstruction::CallRef(kind) | ControlInstruction::ReturnCallRef(kind) => {
+            visitor.visit_type_index_mut(kind)
+        }
+        ControlInstruction::BranchOnCast(label, source, target)
+        | ControlInstruction::BranchOnCastFail(label, source, target) => {
+            visitor.visit_label_index_mut(label);
+            visitor.visit_reference_type_mut(source);
+            visitor.visit_reference_type_mut(target);
+        }
+        ControlInstruction::Throw(tag) => visitor.visit_tag_index_mut(tag),
+        ControlInstruction::TryTable(kind, clauses, body) => {
+            visitor.visit_block_type_mut(kind);
+
+            for clause in clauses {
+                visitor.visit_catch_clause_mut(clause);
+            }
+
+            visitor.visit_expression_mut(body);
+        }
+        ControlInstruction::Nop
+        | ControlInstruction::Unreachable
+        | ControlInstruction::Return
+        | ControlInstruction::ThrowRef => {}
+    }
+}
+
+/// Visits the type index or value type of a block type.
+pub fn walk_block_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut BlockType) {
+    match node {
+        BlockType::None => {}
+        BlockType::Index(index) => visitor.visit_type_index_mut(index),
+        BlockType::ValueType(kind) => visitor.visit_value_type_mut(kind),
+    }
+}
+
+/// Visits the tag and label indices of a catch clause.
+pub fn walk_catch_clause_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut CatchClause) {
+    match node {
+        CatchClause::Catch(tag, label) | CatchClause::CatchReference(tag, label) => {
+            visitor.visit_tag_index_mut(tag);
+            visitor.visit_label_index_mut(label);
+        }
+        CatchClause::CatchAll(label) | CatchClause::CatchAllReference(label) => {
+            visitor.visit_label_index_mut(label)
+        }
+    }
+}
+
+/// Visits the memory index of a memory argument.
+pub fn walk_memory_argument_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut MemoryArgument) {
+    visitor.visit_memory_index_mut(&mut node.memory);
+}
@@ -0,0 +1,850 @@
+//! Traversal of a syntax tree by shared reference.
+
+use crate::model::{
+    AggregateInstruction, ArrayType, AtomicInstruction, BlockType, CatchClause, CompositeType,
+    ControlInstruction, Data, DataIndex, DataMode, Element, ElementIndex, ElementMode, Export,
+    ExportDescription, Expression, FieldIndex, FieldType, Function, FunctionIndex, FunctionType,
+    Global, GlobalIndex, GlobalType, HeapType, Import, ImportDescription, Instruction, LabelIndex,
+    Limit, LocalIndex, Memory, MemoryArgument, MemoryIndex, MemoryInstruction, MemoryType, Module,
+    NumericInstruction, ParametricInstruction, RecursiveType, ReferenceInstruction, ReferenceType,
+    ResultType, Start, StorageType, StructType, SubType, Table, TableIndex, TableInstruction,
+    TableType, Tag, TagIndex, TagType, TypeIndex, ValueType, VariableInstruction,
+    VectorInstruction,
+};
+
+/// A traversal of a syntax tree by shared reference.
+/// Each method visits a single kind of node and, by default,
+/// calls the corresponding `walk_*` function to visit the children of the node.
+/// Implementations override the methods for the nodes they are interested in,
+/// and call the `walk_*` function to continue the traversal into the children of the node.
+///
+/// Indices are visited by value, with a separate method for each index space.
+///
+/// # Examples
+/// ```rust
+/// use wasm_ast::visit::{walk_expression, Visit};
+/// use wasm_ast::{BlockType, ControlInstruction, Expression, FunctionIndex};
+///
+/// #[derive(Default)]
+/// struct Calls {
+///     depth: usize,
+///     calls: Vec<(usize, FunctionIndex)>,
+/// }
+///
+/// impl<'ast> Visit<'ast> for Calls {
+///     fn visit_expression(&mut self, node: &'ast Expression) {
+///         self.depth += 1;
+///         walk_expression(self, node);
+///         self.depth -= 1;
+///     }
+///
+///     fn visit_function_index(&mut self, index: FunctionIndex) {
+///         self.calls.push((self.depth, index));
+///     }
+/// }
+///
+/// let expression = Expression::from(vec![
+///     ControlInstruction::Call(0).into(),
+///     ControlInstruction::Block(
+///         BlockType::None,
+///         Expression::from(vec![ControlInstruction::Call(1).into()]),
+///     )
+///     .into(),
+/// ]);
+/// let mut calls = Calls::default();
+///
+/// calls.visit_expression(&expression);
+///
+/// assert_eq!(calls.calls, vec![(1, 0), (2, 1)]);
+/// ```
+pub trait Visit<'ast> {
+    /// Visits a module.
+    fn visit_module(&mut self, node: &'ast Module) {
+        walk_module(self, node)
+    }
+
+    /// Visits a recursive type.
+    fn visit_recursive_type(&mut self, node: &'ast RecursiveType) {
+        walk_recursive_type(self, node)
+    }
+
+    /// Visits a sub type.
+    fn visit_sub_type(&mut self, node: &'ast SubType) {
+        walk_sub_type(self, node)
+    }
+
+    /// Visits a composite type.
+    fn visit_composite_type(&mut self, node: &'ast CompositeType) {
+        walk_composite_type(self, node)
+    }
+
+    /// Visits a function type.
+    fn visit_function_type(&mut self, node: &'ast FunctionType) {
+        walk_function_type(self, node)
+    }
+
+    /// Visits a structure type.
+    fn visit_struct_type(&mut self, node: &'ast StructType) {
+        walk_struct_type(self, node)
+    }
+
+    /// Visits an array type.
+    fn visit_array_type(&mut self, node: &'ast ArrayType) {
+        walk_array_type(self, node)
+    }
+
+    /// Visits a field type.
+    fn visit_field_type(&mut self, node: &'ast FieldType) {
+        walk_field_type(self, node)
+    }
+
+    /// Visits a result type (i.e., the parameters, results or locals of a function).
+    fn visit_result_type(&mut self, node: &'ast ResultType) {
+        walk_result_type(self, node)
+    }
+
+    /// Visits a value type.
+    fn visit_value_type(&mut self, node: &'ast ValueType) {
+        walk_value_type(self, node)
+    }
+
+    /// Visits a reference type.
+    fn visit_reference_type(&mut self, node: &'ast ReferenceType) {
+        walk_reference_type(self, node)
+    }
+
+    /// Visits a heap type.
+    fn visit_heap_type(&mut self, node: &'ast HeapType) {
+        walk_heap_type(self, node)
+    }
+
+    /// Visits the limits of a table or memory type.
+    fn visit_limit(&mut self, _node: &'ast Limit) {}
+
+    /// Visits a table type.
+    fn visit_table_type(&mut self, node: &'ast TableType) {
+        walk_table_type(self, node)
+    }
+
+    /// Visits a memory type.
+    fn visit_memory_type(&mut self, node: &'ast MemoryType) {
+        walk_memory_type(self, node)
+    }
+
+    /// Visits a global type.
+    fn visit_global_type(&mut self, node: &'ast GlobalType) {
+        walk_global_type(self, node)
+    }
+
+    /// Visits a tag type.
+    fn visit_tag_type(&mut self, node: &'ast TagType) {
+        walk_tag_type(self, node)
+    }
+
+    /// Visits an import.
+    fn visit_import(&mut self, node: &'ast Import) {
+        walk_import(self, node)
+    }
+
+    /// Visits a function.
+    fn visit_function(&mut self, node: &'ast Function) {
+        walk_function(self, node)
+    }
+
+    /// Visits a table.
+    fn visit_table(&mut self, node: &'ast Table) {
+        walk_table(self, node)
+    }
+
+    /// Visits a memory.
+    fn visit_memory(&mut self, node: &'ast Memory) {
+        walk_memory(self, node)
+    }
+
+    /// Visits a tag.
+    fn visit_tag(&mut self, node: &'ast Tag) {
+        walk_tag(self, node)
+    }
+
+    /// Visits a global.
+    fn visit_global(&mut self, node: &'ast Global) {
+        walk_global(self, node)
+    }
+
+    /// Visits an export.
+    fn visit_export(&mut self, node: &'ast Export) {
+        walk_export(self, node)
+    }
+
+    /// Visits the start function.
+    fn visit_start(&mut self, node: &'ast Start) {
+        walk_start(self, node)
+    }
+
+    /// Visits an element segment.
+    fn visit_element(&mut self, node: &'ast Element) {
+        walk_element(self, node)
+    }
+
+    /// Visits a data segment.
+    fn visit_data(&mut self, node: &'ast Data) {
+        walk_data(self, node)
+    }
+
+    /// Visits an expression (e.g., a function body, an initializer or the body of a block).
+    fn visit_expression(&mut self, node: &'ast Expression) {
+        walk_expression(self, node)
+    }
+
+    /// Visits an instruction.
+    fn visit_instruction(&mut self, node: &'ast Instruction) {
+        walk_instruction(self, node)
+    }
+
+    /// Visits a numeric instruction.
+    fn visit_numeric_instruction(&mut self, _node: &'ast NumericInstruction) {}
+
+    /// Visits a vector instruction.
+    fn visit_vector_instruction(&mut self, node: &'ast VectorInstruction) {
+        walk_vector_instruction(self, node)
+    }
+
+    /// Visits a reference instruction.
+    fn visit_reference_instruction(&mut self, node: &'ast ReferenceInstruction) {
+        walk_reference_instruction(self, node)
+    }
+
+    /// Visits a parametric instruction.
+    fn visit_parametric_instruction(&mut self, node: &'ast ParametricInstruction) {
+        walk_parametric_instruction(self, node)
+    }
+
+    /// Visits a variable instruction.
+    fn visit_variable_instruction(&mut self, node: &'ast VariableInstruction) {
+        walk_variable_instruction(self, node)
+    }
+
+    /// Visits a table instruction.
+    fn visit_table_instruction(&mut self, node: &'ast TableInstruction) {
+        walk_table_instruction(self, node)
+    }
+
+    /// Visits a memory instruction.
+    fn visit_memory_instruction(&mut self, node: &'ast MemoryInstruction) {
+        walk_memory_instruction(self, node)
+    }
+
+    /// Visits an atomic memory instruction.
+    fn visit_atomic_instruction(&mut self, node: &'ast AtomicInstruction) {
+        walk_atomic_instruction(self, node)
+    }
+
+    /// Visits an aggregate (i.e., structure, array or i31) instruction.
+    fn visit_aggregate_instruction(&mut self, node: &'ast AggregateInstruction) {
+        walk_aggregate_instruction(self, node)
+    }
+
+    /// Visits a control instruction.
+    fn visit_control_instruction(&mut self, node: &'ast ControlInstruction) {
+        walk_control_instruction(self, node)
+    }
+
+    /// Visits the block type of a structured control instruction.
+    fn visit_block_type(&mut self, node: &'ast BlockType) {
+        walk_block_type(self, node)
+    }
+
+    /// Visits a catch clause of a try table instruction.
+    fn visit_catch_clause(&mut self, node: &'ast CatchClause) {
+        walk_catch_clause(self, node)
+    }
+
+    /// Visits the memory argument of a load or store instruction.
+    fn visit_memory_argument(&mut self, node: &'ast MemoryArgument) {
+        walk_memory_argument(self, node)
+    }
+
+    /// Visits a reference to a type.
+    fn visit_type_index(&mut self, _index: TypeIndex) {}
+
+    /// Visits a reference to a function.
+    fn visit_function_index(&mut self, _index: FunctionIndex) {}
+
+    /// Visits a reference to a table.
+    fn visit_table_index(&mut self, _index: TableIndex) {}
+
+    /// Visits a reference to a memory.
+    fn visit_memory_index(&mut self, _index: MemoryIndex) {}
+
+    /// Visits a reference to a global.
+    fn visit_global_index(&mut self, _index: GlobalIndex) {}
+
+    /// Visits a reference to an element segment.
+    fn visit_element_index(&mut self, _index: ElementIndex) {}
+
+    /// Visits a reference to a data segment.
+    fn visit_data_index(&mut self, _index: DataIndex) {}
+
+    /// Visits a reference to a local.
+    fn visit_local_index(&mut self, _index: LocalIndex) {}
+
+    /// Visits a reference to a label.
+    fn visit_label_index(&mut self, _index: LabelIndex) {}
+
+    /// Visits a reference to a tag.
+    fn visit_tag_index(&mut self, _index: TagIndex) {}
+
+    /// Visits a reference to a field of a structure type.
+    fn visit_field_index(&mut self, _index: FieldIndex) {}
+}
+
+/// Visits the components of a module in the order of their index spaces:
+/// types, imports, functions, tables, memories, tags, globals, exports,
+/// the start function, element segments and data segments.
+pub fn walk_module<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Module) {
+    for kind in node.types.iter().flatten() {
+        visitor.visit_recursive_type(kind);
+    }
+
+    for import in node.imports.iter().flatten() {
+        visitor.visit_import(import);
+    }
+
+    for function in node.functions.iter().flatten() {
+        visitor.visit_function(function);
+    }
+
+    for table in node.tables.iter().flatten() {
+        visitor.visit_table(table);
+    }
+
+    for memory in node.memories.iter().flatten() {
+        visitor.visit_memory(memory);
+    }
+
+    for tag in node.tags.iter().flatten() {
+        visitor.visit_tag(tag);
+    }
+
+    for global in node.globals.iter().flatten() {
+        visitor.visit_global(global);
+    }
+
+    for export in node.exports.iter().flatten() {
+        visitor.visit_export(export);
+    }
+
+    if let Some(start) = &node.start {
+        visitor.visit_start(start);
+    }
+
+    for element in node.elements.iter().flatten() {
+        visitor.visit_element(element);
+    }
+
+    for data in node.data.iter().flatten() {
+        visitor.visit_data(data);
+    }
+}
+
+/// Visits the sub types of a recursive type.
+pub fn walk_recursive_type<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast RecursiveType,
+) {
+    for kind in &node.types {
+        visitor.visit_sub_type(kind);
+    }
+}
+
+/// Visits the supertypes and composite type of a sub type.
+pub fn walk_sub_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast SubType) {
+    for supertype in &node.supertypes {
+        visitor.visit_type_index(*supertype);
+    }
+
+    visitor.visit_composite_type(&node.kind);
+}
+
+/// Visits the function, structure or array type of a composite type.
+pub fn walk_composite_type<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast CompositeType,
+) {
+    match node {
+        CompositeType::Function(kind) => visitor.visit_function_type(kind),
+        CompositeType::Struct(kind) => visitor.visit_struct_type(kind),
+        CompositeType::Array(kind) => visitor.visit_array_type(kind),
+    }
+}
+
+/// Visits the parameters and results of a function type.
+pub fn walk_function_type<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast FunctionType,
+) {
+    visitor.visit_result_type(&node.parameters);
+    visitor.visit_result_type(&node.results);
+}
+
+/// Visits the fields of a structure type.
+pub fn walk_struct_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast StructType) {
+    for field in &node.fields {
+        visitor.visit_field_type(field);
+    }
+}
+
+/// Visits the field of an array type.
+pub fn walk_array_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast ArrayType) {
+    visitor.visit_field_type(&node.field);
+}
+
+/// Visits the value type of a field, unless the field is packed.
+pub fn walk_field_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast FieldType) {
+    if let StorageType::Value(kind) = &node.kind {
+        visitor.visit_value_type(kind);
+    }
+}
+
+/// Visits the value types of a result type.
+pub fn walk_result_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast ResultType) {
+    for kind in &node.kinds {
+        visitor.visit_value_type(kind);
+    }
+}
+
+/// Visits the reference type of a value type, if any.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn walk_value_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast ValueType) {
+    if let ValueType::Reference(kind) = node {
+        visitor.visit_reference_type(kind);
+    }
+}
+
+/// Visits the heap type of a reference type.
+/// Shorthands for reference types (e.g., funcref) are not expanded.
+pub fn walk_reference_type<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ReferenceType,
+) {
+    if let ReferenceType::Nullable(kind) | ReferenceType::NonNullable(kind) = node {
+        visitor.visit_heap_type(kind);
+    }
+}
+
+/// Visits the type index of a concrete heap type.
+pub fn walk_heap_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast HeapType) {
+    if let HeapType::Index(index) = node {
+        visitor.visit_type_index(*index);
+    }
+}
+
+/// Visits the limits and element type of a table type.
+pub fn walk_table_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TableType) {
+    visitor.visit_limit(&node.limits);
+    visitor.visit_reference_type(&node.kind);
+}
+
+/// Visits the limits of a memory type.
+pub fn walk_memory_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast MemoryType) {
+    visitor.visit_limit(&node.limits);
+}
+
+/// Visits the value type of a global type.
+pub fn walk_global_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast GlobalType) {
+    visitor.visit_value_type(&node.kind);
+}
+
+/// Visits the function type index of a tag type.
+pub fn walk_tag_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TagType) {
+    visitor.visit_type_index(node.kind);
+}
+
+/// Visits the type of the definition required by an import.
+pub fn walk_import<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Import) {
+    match &node.description {
+        ImportDescription::Function(kind) => visitor.visit_type_index(*kind),
+        ImportDescription::Table(kind) => visitor.visit_table_type(kind),
+        ImportDescription::Memory(kind) => visitor.visit_memory_type(kind),
+        ImportDescription::Global(kind) => visitor.visit_global_type(kind),
+        ImportDescription::Tag(kind) => visitor.visit_tag_type(kind),
+    }
+}
+
+/// Visits the type, locals and body of a function.
+pub fn walk_function<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Function) {
+    visitor.visit_type_index(node.kind);
+    visitor.visit_result_type(&node.locals);
+    visitor.visit_expression(node.body());
+}
+
+/// Visits the table type and initializer of a table.
+pub fn walk_table<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Table) {
+    visitor.visit_table_type(&node.kind);
+
+    if let Some(initializer) = &node.initializer {
+        visitor.visit_expression(initializer);
+    }
+}
+
+/// Visits the memory type of a memory.
+pub fn walk_memory<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Memory) {
+    visitor.visit_memory_type(&node.kind);
+}
+
+/// Visits the tag type of a tag.
+pub fn walk_tag<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Tag) {
+    visitor.visit_tag_type(&node.kind);
+}
+
+/// Visits the type and initializer of a global.
+pub fn walk_global<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Global) {
+    visitor.visit_global_type(&node.kind);
+    visitor.visit_expression(&node.initializer);
+}
+
+/// Visits the index of the exported definition.
+pub fn walk_export<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Export) {
+    match &node.description {
+        ExportDescription::Function(index) => visitor.visit_function_index(*index),
+        ExportDescription::Table(index) => visitor.visit_table_index(*index),
+        ExportDescription::Memory(index) => visitor.visit_memory_index(*index),
+        ExportDescription::Global(index) => visitor.visit_global_index(*index),
+        ExportDescription::Tag(index) => visitor.visit_tag_index(*index),
+    }
+}
+
+/// Visits the index of the start function.
+pub fn walk_start<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Start) {
+    visitor.visit_function_index(node.function);
+}
+
+/// Visits the element type, mode and initializers of an element segment.
+pub fn walk_element<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Element) {
+    visitor.visit_reference_type(&node.kind);
+
+    if let ElementMode::Active(table, offset) = &node.mode {
+        visitor.visit_table_index(*table);
+        visitor.visit_expression(offset);
+    }
+
+    for initializer in &node.initializers {
+        visitor.visit_expression(initializer);
+    }
+}
+
+/// Visits the mode of a data segment.
+pub fn walk_data<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Data) {
+    if let DataMode::Active(memory, offset) = &node.mode {
+        visitor.visit_memory_index(*memory);
+        visitor.visit_expression(offset);
+    }
+}
+
+/// Visits the instructions of an expression.
+pub fn walk_expression<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Expression) {
+    for instruction in &node.instructions {
+        visitor.visit_instruction(instruction);
+    }
+}
+
+/// Visits the instruction according to its family.
+pub fn walk_instruction<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Instruction) {
+    match node {
+        Instruction::Numeric(instruction) => visitor.visit_numeric_instruction(instruction),
+        Instruction::Vector(instruction) => visitor.visit_vector_instruction(instruction),
+        Instruction::Reference(instruction) => visitor.visit_reference_instruction(instruction),
+        Instruction::Parametric(instruction) => visitor.visit_parametric_instruction(instruction),
+        Instruction::Variable(instruction) => visitor.visit_variable_instruction(instruction),
+        Instruction::Table(instruction) => visitor.visit_table_instruction(instruction),
+        Instruction::Memory(instruction) => visitor.visit_memory_instruction(instruction),
+        Instruction::Atomic(instruction) => visitor.visit_atomic_instruction(instruction),
+        Instruction::Aggregate(instruction) => visitor.visit_aggregate_instruction(instruction),
+        Instruction::Control(instruction) => visitor.visit_control_instruction(instruction),
+    }
+}
+
+/// Visits the memory argument of a vector load or store instruction.
+pub fn walk_vector_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast VectorInstruction,
+) {
+    match node {
+        VectorInstruction::Load(argument)
+        | VectorInstruction::Store(argument)
+        | VectorInstruction::Load8x8(_, argument)
+        | VectorInstruction::Load16x4(_, argument)
+        | VectorInstruction::Load32x2(_, argument)
+        | VectorInstruction::Load8Splat(argument)
+        | VectorInstruction::Load16Splat(argument)
+        | VectorInstruction::Load32Splat(argument)
+        | VectorInstruction::Load64Splat(argument)
+        | VectorInstruction::Load32Zero(argument)
+        | VectorInstruction::Load64Zero(argument)
+        | VectorInstruction::Load8Lane(argument, _)
+        | VectorInstruction::Load16Lane(argument, _)
+        | VectorInstruction::Load32Lane(argument, _)
+        | VectorInstruction::Load64Lane(argument, _)
+        | VectorInstruction::Store8Lane(argument, _)
+        | VectorInstruction::Store16Lane(argument, _)
+        | VectorInstruction::Store32Lane(argument, _)
+        | VectorInstruction::Store64Lane(argument, _) => visitor.visit_memory_argument(argument),
+        _ => {}
+    }
+}
+
+/// Visits the types and function index of a reference instruction.
+pub fn walk_reference_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ReferenceInstruction,
+) {
+    match node {
+        ReferenceInstruction::Null(kind) => visitor.visit_heap_type(kind),
+        ReferenceInstruction::Function(index) => visitor.visit_function_index(*index),
+        ReferenceInstruction::Test(kind) | ReferenceInstruction::Cast(kind) => {
+            visitor.visit_reference_type(kind)
+        }
+        ReferenceInstruction::IsNull
+        | ReferenceInstruction::AsNonNull
+        | ReferenceInstruction::Equal => {}
+    }
+}
+
+/// Visits the value types of a typed select instruction.
+pub fn walk_parametric_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ParametricInstruction,
+) {
+    if let ParametricInstruction::Select(Some(kinds)) = node {
+        for kind in kinds {
+            visitor.visit_value_type(kind);
+        }
+    }
+}
+
+/// Visits the local or global index of a variable instruction.
+pub fn walk_variable_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast VariableInstruction,
+) {
+    match node {
+        VariableInstruction::LocalGet(index)
+        | VariableInstruction::LocalSet(index)
+        | VariableInstruction::LocalTee(index) => visitor.visit_local_index(*index),
+        VariableInstruction::GlobalGet(index) | VariableInstruction::GlobalSet(index) => {
+            visitor.visit_global_index(*index)
+        }
+    }
+}
+
+/// Visits the table and element indices of a table instruction.
+pub fn walk_table_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast TableInstruction,
+) {
+    match node {
+        TableInstruction::Get(table)
+        | TableInstruction::Set(table)
+        | TableInstruction::Size(table)
+        | TableInstruction::Grow(table)
+        | TableInstruction::Fill(table) => visitor.visit_table_index(*table),
+        TableInstruction::Copy(destination, source) => {
+            visitor.visit_table_index(*destination);
+            visitor.visit_table_index(*source);
+        }
+        TableInstruction::Init(element, table) => {
+            visitor.visit_element_index(*element);
+            visitor.visit_table_index(*table);
+        }
+        TableInstruction::ElementDrop(element) => visitor.visit_element_index(*element),
+    }
+}
+
+/// Visits the memory arguments, memory and data indices of a memory instruction.
+pub fn walk_memory_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast MemoryInstruction,
+) {
+    match node {
+        MemoryInstruction::Load(_, argument)
+        | MemoryInstruction::Store(_, argument)
+        | MemoryInstruction::Load8(_, _, argument)
+        | MemoryInstruction::Load16(_, _, argument)
+        | MemoryInstruction::Load32(_, argument)
+        | MemoryInstruction::Store8(_, argument)
+        | MemoryInstruction::Store16(_, argument)
+        | MemoryInstruction::Store32(argument) => visitor.visit_memory_argument(argument),
+        MemoryInstruction::Size(memory)
+        | MemoryInstruction::Grow(memory)
+        | MemoryInstruction::Fill(memory) => visitor.visit_memory_index(*memory),
+        MemoryInstruction::Copy(destination, source) => {
+            visitor.visit_memory_index(*destination);
+            visitor.visit_memory_index(*source);
+        }
+        MemoryInstruction::Init(data, memory) => {
+            visitor.visit_data_index(*data);
+            visitor.visit_memory_index(*memory);
+        }
+        MemoryInstruction::DataDrop(data) => visitor.visit_data_index(*data),
+    }
+}
+
+/// Visits the memory argument of an atomic memory instruction.
+pub fn walk_atomic_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AtomicInstruction,
+) {
+    match node {
+        AtomicInstruction::Notify(argument)
+        | AtomicInstruction::Wait(_, argument)
+        | AtomicInstruction::Load(_, argument)
+        | AtomicInstruction::Load8(_, argument)
+        | AtomicInstruction::Load16(_, argument)
+        | AtomicInstruction::Load32(argument)
+        | AtomicInstruction::Store(_, argument)
+        | AtomicInstruction::Store8(_, argument)
+        | AtomicInstruction::Store16(_, argument)
+        | AtomicInstruction::Store32(argument)
+        | AtomicInstruction::ReadModifyWrite(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite8(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite16(_, _, argument)
+        | AtomicInstruction::ReadModifyWrite32(_, argument) => {
+            visitor.visit_memory_argument(argument)
+        }
+        AtomicInstruction::Fence => {}
+    }
+}
+
+/// Visits the type, field, data and element indices of an aggregate instruction.
+pub fn walk_aggregate_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AggregateInstruction,
+) {
+    match node {
+        AggregateInstruction::StructNew(kind)
+        | AggregateInstruction::StructNewDefault(kind)
+        | AggregateInstruction::ArrayNew(kind)
+        | AggregateInstruction::ArrayNewDefault(kind)
+        | AggregateInstruction::ArrayNewFixed(kind, _)
+        | AggregateInstruction::ArrayGet(_, kind)
+        | AggregateInstruction::ArraySet(kind)
+        | AggregateInstruction::ArrayFill(kind) => visitor.visit_type_index(*kind),
+        AggregateInstruction::StructGet(_, kind, field)
+        | AggregateInstruction::StructSet(kind, field) => {
+            visitor.visit_type_index(*kind);
+            visitor.visit_field_index(*field);
+        }
+        AggregateInstruction::ArrayNewData(kind, data)
+        | AggregateInstruction::ArrayInitData(kind, data) => {
+            visitor.visit_type_index(*kind);
+            visitor.visit_data_index(*data);
+        }
+        AggregateInstruction::ArrayNewElement(kind, element)
+        | AggregateInstruction::ArrayInitElement(kind, element) => {
+            visitor.visit_type_index(*kind);
+            visitor.visit_element_index(*element);
+        }
+        AggregateInstruction::ArrayCopy(destination, source) => {
+            visitor.visit_type_index(*destination);
+            visitor.visit_type_index(*source);
+        }
+        AggregateInstruction::ArrayLength
+        | AggregateInstruction::ReferenceI31
+        | AggregateInstruction::I31Get(_)
+        | AggregateInstruction::AnyConvertExternal
+        | AggregateInstruction::ExternalConvertAny => {}
+    }
+}
+
+/// Visits the block types, nested expressions, labels and indices of a control instruction.
+pub fn walk_control_instruction<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ControlInstruction,
+) {
+    match node {
+        ControlInstruction::Block(kind, body) | ControlInstruction::Loop(kind, body) => {
+            visitor.visit_block_type(kind);
+            visitor.visit_expression(body);
+        }
+        ControlInstruction::If(kind, consequent, alternative) => {
+            visitor.visit_block_type(kind);
+            visitor.visit_expression(consequent);
+
+            if let Some(alternative) = alternative {
+                visitor.visit_expression(alternative);
+            }
+        }
+        ControlInstruction::Branch(label)
+        | ControlInstruction::BranchIf(label)
+        | ControlInstruction::BranchOnNull(label)
+        | ControlInstruction::BranchOnNonNull(label) => visitor.visit_label_index(*label),
+        ControlInstruction::BranchTable(labels, default) => {
+            for label in labels {
+                visitor.visit_label_index(*label);
+            }
+
+            visitor.visit_label_index(*default);
+        }
+        ControlInstruction::Call(function) | ControlInstruction::ReturnCall(function) => {
+            visitor.visit_function_index(*function)
+        }
+        ControlInstruction::CallIndirect(kind, table)
+        | ControlInstruction::ReturnCallIndirect(kind, table) => {
+            visitor.visit_type_index(*kind);
+            visitor.visit_table_index(*table);
+        }
+        ControlInstruction::CallRef(kind) | ControlInstruction::ReturnCallRef(kind) => {
+            visitor.visit_type_index(*kind)
+        }
+        ControlInstruction::BranchOnCast(label, source, target)
+        | ControlInstruction::BranchOnCastFail(label, source, target) => {
+            visitor.visit_label_index(*label);
+            visitor.visit_reference_type(source);
+            visitor.visit_reference_type(target);
+        }
+        ControlInstruction::Throw(tag) => visitor.visit_tag_index(*tag),
+        ControlInstruction::TryTable(kind, clauses, body) => {
+            visitor.visit_block_type(kind);
+
+            for clause in clauses {
+                visitor.visit_catch_clause(clause);
+            }
+
+            visitor.visit_expression(body);
+        }
+        ControlInstruction::Nop
+        | ControlInstruction::Unreachable
+        | ControlInstruction::Return
+        | ControlInstruction::ThrowRef => {}
+    }
+}
+
+/// Visits the type index or value type of a block type.
+pub fn walk_block_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast BlockType) {
+    match node {
+        BlockType::None => {}
+        BlockType::Index(index) => visitor.visit_type_index(*index),
+        BlockType::ValueType(kind) => visitor.visit_value_type(kind),
+    }
+}
+
+/// Visits the tag and label indices of a catch clause.
+pub fn walk_catch_clause<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast CatchClause) {
+    match node {
+        CatchClause::Catch(tag, label) | CatchClause::CatchReference(tag, label) => {
+            visitor.visit_tag_index(*tag);
+            visitor.visit_label_index(*label);
+        }
+        CatchClause::CatchAll(label) | CatchClause::CatchAllReference(label) => {
+            visitor.visit_label_index(*label)
+        }
+    }
+}
+
+/// Visits the memory index of a memory argument.
+pub fn walk_memory_argument<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast MemoryArgument,
+) {
+    visitor.visit_memory_index(node.memory);
+}